@@ -6,7 +6,6 @@ use std::{
     path::PathBuf,
 };
 
-use asn1_rs::{oid, Oid};
 use c509_certificate::{
     attributes::attribute::Attribute,
     big_uint::UnwrappedBigUint,
@@ -39,12 +38,9 @@ enum Cli {
         #[clap(short, long)]
         output: Option<PathBuf>,
         /// Optional private key file, if provided, self-signed certificate will be
-        /// generated. Currently support only PEM format.
+        /// generated. Currently support only PEM format, with Ed25519 or P-256 keys.
         #[clap(long)]
         private_key: Option<PathBuf>,
-        #[clap(long)]
-        /// Optional key type.
-        key_type: Option<String>,
     },
 
     /// C509 certificate signature verification.
@@ -52,7 +48,8 @@ enum Cli {
         /// C509 certificate file
         #[clap(short, long)]
         file: PathBuf,
-        /// Public key file. Currently support only PEM format.
+        /// Public key file. Currently support only PEM format, with Ed25519 or P-256
+        /// keys.
         #[clap(long)]
         public_key: PathBuf,
     },
@@ -78,14 +75,13 @@ impl Cli {
                 json_file,
                 output,
                 private_key,
-                key_type,
             } => {
                 let sk = match private_key {
                     Some(key) => Some(PrivateKey::from_file(key)?),
                     None => None,
                 };
 
-                generate(&json_file, output, sk.as_ref(), key_type)
+                generate(&json_file, output, sk.as_ref())
             },
             Cli::Verify { file, public_key } => verify(&file, public_key),
             Cli::Decode { file, output } => decode(&file, output),
@@ -104,7 +100,8 @@ struct C509Json {
     /// if not provided, a random number will be generated.
     serial_number: Option<UnwrappedBigUint>,
     /// Optional issuer signature algorithm of the certificate,
-    /// if not provided, set to Ed25519.
+    /// if not provided, derived from the private key type, or from the subject public
+    /// key type if the certificate is unsigned.
     issuer_signature_algorithm: Option<IssuerSignatureAlgorithm>,
     /// Optional issuer of the certificate,
     /// if not provided, issuer is the same as subject.
@@ -118,10 +115,10 @@ struct C509Json {
     /// Attributes of the subject.
     subject: Vec<Attribute>,
     /// Optional subject public key algorithm of the certificate,
-    /// if not provided, set to Ed25519.
+    /// if not provided, derived from the subject public key type.
     subject_public_key_algorithm: Option<SubjectPubKeyAlgorithm>,
     /// A path to the public key file.
-    /// Currently support only PEM format.
+    /// Currently support only PEM format, with Ed25519 or P-256 keys.
     subject_public_key: String,
     /// Extensions of the certificate.
     extensions: Extensions,
@@ -130,9 +127,6 @@ struct C509Json {
     issuer_signature_value: Option<Vec<u8>>,
 }
 
-/// Ed25519 oid and parameter - default algorithm.
-const ED25519: (Oid, Option<String>) = (oid!(1.3.101 .112), None);
-
 /// Integer indicate that certificate is self-signed.
 /// 2 for Natively Signed C509 Certificate following X.509 v3
 /// 3 for CBOR re-encoding of X.509 v3 Certificate        
@@ -143,7 +137,6 @@ const SELF_SIGNED_INT: u8 = 2;
 /// A function to generate C509 certificate.
 fn generate(
     file: &PathBuf, output: Option<PathBuf>, private_key: Option<&PrivateKey>,
-    key_type: Option<String>,
 ) -> anyhow::Result<()> {
     let data = fs::read_to_string(file)?;
     let c509_json: C509Json = serde_json::from_str(&data)?;
@@ -175,22 +168,23 @@ fn generate(
 
     let public_key = parse_public_key(&c509_json.subject_public_key)?;
 
-    let key_type = get_key_type(key_type)?;
-
     // Create TbsCert instance
     let tbs = TbsCert::new(
         c509_json.certificate_type.unwrap_or(SELF_SIGNED_INT),
         serial_number,
-        c509_json
-            .issuer_signature_algorithm
-            .unwrap_or(IssuerSignatureAlgorithm::new(key_type.0.clone(), ED25519.1)),
+        c509_json.issuer_signature_algorithm.unwrap_or_else(|| {
+            private_key.map_or_else(
+                || public_key.issuer_signature_algorithm(),
+                PrivateKey::issuer_signature_algorithm,
+            )
+        }),
         Some(Name::new(NameValue::Attribute(issuer))),
         Time::new(not_before),
         Time::new(not_after),
         Name::new(NameValue::Attribute(c509_json.subject)),
         c509_json
             .subject_public_key_algorithm
-            .unwrap_or(SubjectPubKeyAlgorithm::new(key_type.0, key_type.1)),
+            .unwrap_or_else(|| public_key.subject_public_key_algorithm()),
         public_key.to_bytes(),
         c509_json.extensions.clone(),
     );
@@ -246,19 +240,6 @@ fn parse_public_key(public_key: &str) -> anyhow::Result<PublicKey> {
     PublicKey::from_file(pk_path)
 }
 
-/// Get the key type. Currently support only Ed25519.
-fn get_key_type(key_type: Option<String>) -> anyhow::Result<(Oid<'static>, Option<String>)> {
-    match key_type {
-        None => Err(anyhow::anyhow!("Currently only support Ed25519")),
-        Some(key_type) => {
-            match key_type.as_str() {
-                "ed25519" => Ok(ED25519),
-                _ => Err(anyhow::anyhow!("Currently only support Ed25519")),
-            }
-        },
-    }
-}
-
 /// Parse date string to u64.
 fn parse_or_default_date(date_option: Option<String>, default: u64) -> Result<u64, anyhow::Error> {
     match date_option {
@@ -0,0 +1,433 @@
+//! Mapping between X.509 extensions and C509 extensions.
+//!
+//! Registered extensions listed in [`EXTENSION_MAPPINGS`] are converted to their C509
+//! value type, other registered extensions are not supported.
+//! Unregistered extensions keep the DER encoded extension value as bytes.
+
+use x509_cert::{
+    der::{
+        asn1::{BitString, Ia5String, ObjectIdentifier, OctetString},
+        oid::AssociatedOid,
+        Decode, Encode,
+    },
+    ext::{
+        pkix::{
+            name::GeneralName as X509GeneralName, BasicConstraints,
+            ExtendedKeyUsage as X509ExtendedKeyUsage, InhibitAnyPolicy, IssuerAltName, KeyUsage,
+            SubjectAltName, SubjectKeyIdentifier,
+        },
+        Extension as X509Extension,
+    },
+};
+
+use super::{is_registered, name_to_c509, name_to_der, oid_to_c509, oid_to_der, ConversionError};
+use crate::{
+    extensions::{
+        alt_name::{AlternativeName, GeneralNamesOrText},
+        ext_key_usage::ExtendedKeyUsage,
+        extension::{Extension, ExtensionValue},
+        Extensions,
+    },
+    general_names::{
+        general_name::{GeneralName, GeneralNameTypeRegistry, GeneralNameValue},
+        GeneralNames,
+    },
+    oid::C509oid,
+};
+
+/// C509 value type of a registered extension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mapping {
+    /// Key identifier as bytes.
+    SubjectKeyIdentifier,
+    /// Key usage bits as an unsigned integer.
+    KeyUsage,
+    /// General names as an alternative name.
+    AlternativeName,
+    /// CA flag and path length constraint as an integer.
+    BasicConstraints,
+    /// Number of skipped certificates as an integer.
+    InhibitAnyPolicy,
+    /// Key purposes as an extended key usage.
+    ExtendedKeyUsage,
+}
+
+/// Registered extensions supported by the conversion.
+const EXTENSION_MAPPINGS: [(ObjectIdentifier, Mapping); 7] = [
+    (SubjectKeyIdentifier::OID, Mapping::SubjectKeyIdentifier),
+    (KeyUsage::OID, Mapping::KeyUsage),
+    (SubjectAltName::OID, Mapping::AlternativeName),
+    (IssuerAltName::OID, Mapping::AlternativeName),
+    (BasicConstraints::OID, Mapping::BasicConstraints),
+    (InhibitAnyPolicy::OID, Mapping::InhibitAnyPolicy),
+    (X509ExtendedKeyUsage::OID, Mapping::ExtendedKeyUsage),
+];
+
+/// C509 basic constraints value of a non-CA certificate.
+const BASIC_CONSTRAINTS_NOT_CA: i64 = -2;
+/// C509 basic constraints value of a CA certificate without path length constraint.
+const BASIC_CONSTRAINTS_CA: i64 = -1;
+
+/// Get the mapping of the extension.
+fn mapping(oid: &ObjectIdentifier) -> Option<Mapping> {
+    EXTENSION_MAPPINGS
+        .iter()
+        .find(|(mapped_oid, _)| mapped_oid == oid)
+        .map(|(_, mapping)| *mapping)
+}
+
+/// Convert X.509 extensions to C509 extensions.
+pub(super) fn extensions_to_c509(
+    extensions: &[X509Extension],
+) -> Result<Extensions, ConversionError> {
+    let mut c509_extensions = Extensions::new();
+    for extension in extensions {
+        c509_extensions.add_extension(extension_to_c509(extension)?);
+    }
+    Ok(c509_extensions)
+}
+
+/// Convert C509 extensions to X.509 extensions.
+pub(super) fn extensions_to_der(
+    extensions: &Extensions,
+) -> Result<Vec<X509Extension>, ConversionError> {
+    extensions
+        .extensions()
+        .iter()
+        .map(extension_to_der)
+        .collect()
+}
+
+/// Convert an X.509 extension to a C509 extension.
+fn extension_to_c509(extension: &X509Extension) -> Result<Extension, ConversionError> {
+    let oid = oid_to_c509(&extension.extn_id);
+    let der = extension.extn_value.as_bytes();
+    let value = match mapping(&extension.extn_id) {
+        Some(Mapping::SubjectKeyIdentifier) => {
+            ExtensionValue::Bytes(SubjectKeyIdentifier::from_der(der)?.0.into_bytes())
+        },
+        Some(Mapping::KeyUsage) => {
+            ExtensionValue::Int(key_usage_to_c509(&BitString::from_der(der)?)?)
+        },
+        Some(Mapping::AlternativeName) => {
+            let general_names = Vec::<X509GeneralName>::from_der(der)?;
+            ExtensionValue::AlternativeName(AlternativeName::new(GeneralNamesOrText::GeneralNames(
+                general_names_to_c509(&general_names)?,
+            )))
+        },
+        Some(Mapping::BasicConstraints) => {
+            ExtensionValue::Int(basic_constraints_to_c509(&BasicConstraints::from_der(
+                der,
+            )?)?)
+        },
+        Some(Mapping::InhibitAnyPolicy) => {
+            ExtensionValue::Int(InhibitAnyPolicy::from_der(der)?.0.into())
+        },
+        Some(Mapping::ExtendedKeyUsage) => {
+            ExtensionValue::ExtendedKeyUsage(
+                X509ExtendedKeyUsage::from_der(der)?
+                    .0
+                    .iter()
+                    .fold(ExtendedKeyUsage::new(), |eku, purpose| {
+                        eku.purpose(oid_to_c509(purpose))
+                    }),
+            )
+        },
+        None => {
+            let c509_extension =
+                Extension::new(oid, ExtensionValue::Bytes(der.to_vec()), extension.critical);
+            if is_registered(c509_extension.registered_oid()) {
+                return Err(ConversionError::Unsupported(format!(
+                    "extension {}",
+                    extension.extn_id
+                )));
+            }
+            return Ok(c509_extension);
+        },
+    };
+    Ok(Extension::new(oid, value, extension.critical))
+}
+
+/// Convert a C509 extension to an X.509 extension.
+fn extension_to_der(extension: &Extension) -> Result<X509Extension, ConversionError> {
+    let extn_id = oid_to_der(extension.registered_oid().c509_oid().oid())?;
+    let extn_value = match (mapping(&extn_id), extension.value()) {
+        (Some(Mapping::SubjectKeyIdentifier), ExtensionValue::Bytes(bytes)) => {
+            SubjectKeyIdentifier(OctetString::new(bytes.clone())?).to_der()?
+        },
+        (Some(Mapping::KeyUsage), ExtensionValue::Int(value)) => {
+            key_usage_to_der(*value)?.to_der()?
+        },
+        (Some(Mapping::AlternativeName), ExtensionValue::AlternativeName(name)) => {
+            general_names_to_der(name.general_name())?.to_der()?
+        },
+        (Some(Mapping::BasicConstraints), ExtensionValue::Int(value)) => {
+            basic_constraints_to_der(*value)?.to_der()?
+        },
+        (Some(Mapping::InhibitAnyPolicy), ExtensionValue::Int(value)) => {
+            let skip_certs = u32::try_from(*value).map_err(|_| {
+                ConversionError::Unsupported(format!("inhibit any policy value {value}"))
+            })?;
+            InhibitAnyPolicy(skip_certs).to_der()?
+        },
+        (Some(Mapping::ExtendedKeyUsage), ExtensionValue::ExtendedKeyUsage(eku)) => {
+            X509ExtendedKeyUsage(
+                eku.purposes()
+                    .iter()
+                    .map(|purpose| oid_to_der(purpose.oid()))
+                    .collect::<Result<_, _>>()?,
+            )
+            .to_der()?
+        },
+        (None, ExtensionValue::Bytes(bytes)) if !is_registered(extension.registered_oid()) => {
+            bytes.clone()
+        },
+        _ => {
+            return Err(ConversionError::Unsupported(format!(
+                "extension {extn_id} value"
+            )));
+        },
+    };
+    Ok(X509Extension {
+        extn_id,
+        critical: extension.critical(),
+        extn_value: OctetString::new(extn_value)?,
+    })
+}
+
+// -------------------Key usage-----------------------
+
+/// Convert an X.509 key usage bit string to a C509 key usage integer, where the bit
+/// `i` of the integer is the named bit `i` of the bit string.
+fn key_usage_to_c509(bits: &BitString) -> Result<i64, ConversionError> {
+    if bits.bit_len() >= 63 {
+        return Err(ConversionError::Unsupported(format!(
+            "key usage of {} bits",
+            bits.bit_len()
+        )));
+    }
+    Ok(bits
+        .bits()
+        .enumerate()
+        .filter(|(_, bit)| *bit)
+        .fold(0, |acc, (i, _)| acc | (1 << i)))
+}
+
+/// Convert a C509 key usage integer to an X.509 key usage bit string, without trailing
+/// zero bits as required by DER.
+fn key_usage_to_der(value: i64) -> Result<BitString, ConversionError> {
+    let value = u64::try_from(value)
+        .map_err(|_| ConversionError::Unsupported(format!("key usage value {value}")))?;
+    let bit_len = u64::BITS - value.leading_zeros();
+    let byte_len = bit_len.div_ceil(8);
+    let bytes = (0..byte_len)
+        .map(|byte| {
+            (0..8)
+                .filter(|bit| ((value >> (byte * 8 + bit)) & 1) == 1)
+                .fold(0u8, |acc, bit| acc | (0x80 >> bit))
+        })
+        .collect::<Vec<u8>>();
+    let unused_bits = u8::try_from(byte_len * 8 - bit_len)
+        .map_err(|_| ConversionError::Unsupported(format!("key usage value {value}")))?;
+    Ok(BitString::new(unused_bits, bytes)?)
+}
+
+// -------------------Basic constraints-----------------------
+
+/// Convert X.509 basic constraints to a C509 basic constraints integer.
+fn basic_constraints_to_c509(constraints: &BasicConstraints) -> Result<i64, ConversionError> {
+    match (constraints.ca, constraints.path_len_constraint) {
+        (false, None) => Ok(BASIC_CONSTRAINTS_NOT_CA),
+        (true, None) => Ok(BASIC_CONSTRAINTS_CA),
+        (true, Some(path_len)) => Ok(path_len.into()),
+        (false, Some(_)) => {
+            Err(ConversionError::Unsupported(
+                "basic constraints path length of a non-CA certificate".to_string(),
+            ))
+        },
+    }
+}
+
+/// Convert a C509 basic constraints integer to X.509 basic constraints.
+fn basic_constraints_to_der(value: i64) -> Result<BasicConstraints, ConversionError> {
+    match value {
+        BASIC_CONSTRAINTS_NOT_CA => {
+            Ok(BasicConstraints {
+                ca: false,
+                path_len_constraint: None,
+            })
+        },
+        BASIC_CONSTRAINTS_CA => {
+            Ok(BasicConstraints {
+                ca: true,
+                path_len_constraint: None,
+            })
+        },
+        _ => {
+            let path_len = u8::try_from(value).map_err(|_| {
+                ConversionError::Unsupported(format!("basic constraints value {value}"))
+            })?;
+            Ok(BasicConstraints {
+                ca: true,
+                path_len_constraint: Some(path_len),
+            })
+        },
+    }
+}
+
+// -------------------General names-----------------------
+
+/// Convert X.509 general names to C509 general names.
+fn general_names_to_c509(names: &[X509GeneralName]) -> Result<GeneralNames, ConversionError> {
+    let mut general_names = GeneralNames::new();
+    for name in names {
+        let (gn_type, gn_value) = match name {
+            X509GeneralName::Rfc822Name(text) => {
+                (
+                    GeneralNameTypeRegistry::Rfc822Name,
+                    GeneralNameValue::Text(text.to_string()),
+                )
+            },
+            X509GeneralName::DnsName(text) => {
+                (
+                    GeneralNameTypeRegistry::DNSName,
+                    GeneralNameValue::Text(text.to_string()),
+                )
+            },
+            X509GeneralName::UniformResourceIdentifier(text) => {
+                (
+                    GeneralNameTypeRegistry::UniformResourceIdentifier,
+                    GeneralNameValue::Text(text.to_string()),
+                )
+            },
+            X509GeneralName::IpAddress(bytes) => {
+                (
+                    GeneralNameTypeRegistry::IPAddress,
+                    GeneralNameValue::Bytes(bytes.as_bytes().to_vec()),
+                )
+            },
+            X509GeneralName::RegisteredId(oid) => {
+                (
+                    GeneralNameTypeRegistry::RegisteredID,
+                    GeneralNameValue::Oid(C509oid::new(oid_to_c509(oid))),
+                )
+            },
+            X509GeneralName::DirectoryName(name) => {
+                (
+                    GeneralNameTypeRegistry::DirectoryName,
+                    GeneralNameValue::Name(name_to_c509(name)?),
+                )
+            },
+            X509GeneralName::OtherName(_) | X509GeneralName::EdiPartyName(_) => {
+                return Err(ConversionError::Unsupported(
+                    "other name or EDI party name general name".to_string(),
+                ));
+            },
+        };
+        general_names.add_general_name(GeneralName::new(gn_type, gn_value));
+    }
+    Ok(general_names)
+}
+
+/// Convert C509 general names to X.509 general names.
+/// A text is a general names with a single `DNSName`.
+fn general_names_to_der(
+    names: &GeneralNamesOrText,
+) -> Result<Vec<X509GeneralName>, ConversionError> {
+    let general_names = match names {
+        GeneralNamesOrText::GeneralNames(general_names) => general_names,
+        GeneralNamesOrText::Text(text) => {
+            return Ok(vec![X509GeneralName::DnsName(Ia5String::new(text)?)]);
+        },
+    };
+    general_names
+        .general_names()
+        .iter()
+        .map(|name| {
+            match (name.gn_type(), name.gn_value()) {
+                (GeneralNameTypeRegistry::Rfc822Name, GeneralNameValue::Text(text)) => {
+                    Ok(X509GeneralName::Rfc822Name(Ia5String::new(text)?))
+                },
+                (GeneralNameTypeRegistry::DNSName, GeneralNameValue::Text(text)) => {
+                    Ok(X509GeneralName::DnsName(Ia5String::new(text)?))
+                },
+                (
+                    GeneralNameTypeRegistry::UniformResourceIdentifier,
+                    GeneralNameValue::Text(text),
+                ) => {
+                    Ok(X509GeneralName::UniformResourceIdentifier(Ia5String::new(
+                        text,
+                    )?))
+                },
+                (GeneralNameTypeRegistry::IPAddress, GeneralNameValue::Bytes(bytes)) => {
+                    Ok(X509GeneralName::IpAddress(OctetString::new(bytes.clone())?))
+                },
+                (GeneralNameTypeRegistry::RegisteredID, GeneralNameValue::Oid(oid)) => {
+                    Ok(X509GeneralName::RegisteredId(oid_to_der(oid.oid())?))
+                },
+                (GeneralNameTypeRegistry::DirectoryName, GeneralNameValue::Name(name)) => {
+                    Ok(X509GeneralName::DirectoryName(name_to_der(name)?))
+                },
+                (gn_type, _) => {
+                    Err(ConversionError::Unsupported(format!(
+                        "general name {gn_type:?}"
+                    )))
+                },
+            }
+        })
+        .collect()
+}
+
+// -------------------Test----------------------
+
+#[cfg(test)]
+mod test_convert_extensions {
+    use super::*;
+
+    #[test]
+    fn key_usage() {
+        // digitalSignature
+        let bits = BitString::new(7, vec![0x80]).expect("Invalid bit string");
+        assert_eq!(key_usage_to_c509(&bits).expect("Invalid key usage"), 1);
+        assert_eq!(key_usage_to_der(1).expect("Invalid key usage"), bits);
+
+        // keyCertSign, cRLSign
+        let bits = BitString::new(1, vec![0x06]).expect("Invalid bit string");
+        assert_eq!(key_usage_to_c509(&bits).expect("Invalid key usage"), 96);
+        assert_eq!(key_usage_to_der(96).expect("Invalid key usage"), bits);
+
+        // decipherOnly
+        let bits = BitString::new(7, vec![0x00, 0x80]).expect("Invalid bit string");
+        assert_eq!(key_usage_to_c509(&bits).expect("Invalid key usage"), 256);
+        assert_eq!(key_usage_to_der(256).expect("Invalid key usage"), bits);
+
+        assert!(key_usage_to_der(-1).is_err());
+    }
+
+    #[test]
+    fn basic_constraints() {
+        for value in [BASIC_CONSTRAINTS_NOT_CA, BASIC_CONSTRAINTS_CA, 0, 5] {
+            let constraints = basic_constraints_to_der(value).expect("Invalid basic constraints");
+            assert_eq!(
+                basic_constraints_to_c509(&constraints).expect("Invalid basic constraints"),
+                value
+            );
+        }
+        assert!(basic_constraints_to_der(-3).is_err());
+    }
+
+    #[test]
+    fn extended_key_usage() {
+        let extension = ExtendedKeyUsage::new()
+            .server_auth()
+            .purpose(asn1_rs::oid!(1.3.6 .1 .4 .1 .311 .10 .3 .4))
+            .into_extension(true);
+        let der = extension_to_der(&extension).expect("Invalid extended key usage");
+        assert_eq!(der.extn_id, X509ExtendedKeyUsage::OID);
+        assert!(der.critical);
+        assert_eq!(
+            extension_to_c509(&der).expect("Invalid extended key usage"),
+            extension
+        );
+    }
+}
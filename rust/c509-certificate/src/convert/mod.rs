@@ -0,0 +1,645 @@
+//! Conversion between DER encoded X.509 v3 certificates and C509 certificates.
+//!
+//! An X.509 certificate is converted to a C509 certificate of type 3, a CBOR
+//! re-encoding of the X.509 certificate, and back.
+//! The signature is not changed, so it remains valid for the DER encoded TBS
+//! certificate.
+//!
+//! Only certificates which can be represented by this crate are supported, e.g. name
+//! attributes must be UTF-8 strings and the serial number must fit into 64 bits.
+//! [`from_der`] converts the result back to DER and compares it with the input, so a
+//! successful conversion is always lossless.
+//!
+//! For more information about CBOR re-encoding of X.509 certificates,
+//! visit [C509 Certificate](https://datatracker.ietf.org/doc/draft-ietf-cose-cbor-encoded-cert/11/)
+
+// cspell: words secp
+
+mod extensions;
+
+use std::{borrow::Cow, time::Duration};
+
+use asn1_rs::Oid;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use x509_cert::{
+    attr::AttributeTypeAndValue,
+    der::{
+        asn1::{BitString, GeneralizedTime, ObjectIdentifier, SetOfVec, UtcTime},
+        Any, Decode, Encode, Tag, Tagged,
+    },
+    name::{RdnSequence, RelativeDistinguishedName},
+    serial_number::SerialNumber,
+    spki::{AlgorithmIdentifierOwned, SubjectPublicKeyInfoOwned},
+    time::{Time as X509Time, Validity},
+    Certificate, TbsCertificate, Version,
+};
+
+use crate::{
+    attributes::attribute::{Attribute, AttributeValue},
+    big_uint::UnwrappedBigUint,
+    c509::C509,
+    cert_tbs::TbsCert,
+    issuer_sig_algo::IssuerSignatureAlgorithm,
+    name::{Name, NameValue},
+    oid::C509oidRegistered,
+    subject_pub_key_algo::SubjectPubKeyAlgorithm,
+    time::Time,
+};
+
+/// C509 certificate type of the CBOR re-encoded X.509 v3 certificate.
+const X509_CERTIFICATE_TYPE: u8 = 3;
+
+/// Ed25519 algorithm OID, used for both the signature and the public key algorithm.
+const ED25519: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.112");
+/// ECDSA with SHA-256 signature algorithm OID.
+const ECDSA_WITH_SHA256: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.2");
+/// Elliptic curve public key algorithm OID.
+const EC_PUBLIC_KEY: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+/// Named curve secp256r1 (P-256) OID.
+const SECP256R1: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.3.1.7");
+
+/// SEC1 prefix of the uncompressed EC point.
+const SEC1_UNCOMPRESSED: u8 = 0x04;
+/// SEC1 prefix of the compressed EC point with even y coordinate.
+const SEC1_COMPRESSED_EVEN: u8 = 0x02;
+/// SEC1 prefix of the compressed EC point with odd y coordinate.
+const SEC1_COMPRESSED_ODD: u8 = 0x03;
+/// C509 prefix of the uncompressed EC point with even y coordinate.
+const C509_UNCOMPRESSED_EVEN: u8 = 0xFE;
+/// C509 prefix of the uncompressed EC point with odd y coordinate.
+const C509_UNCOMPRESSED_ODD: u8 = 0xFD;
+
+/// 2050-01-01T00:00:00Z in seconds since the Unix epoch, dates from this one on are
+/// encoded as `GeneralizedTime` instead of `UTCTime`.
+const GENERALIZED_TIME_START: u64 = 2_524_608_000;
+
+/// X.509 and C509 certificate conversion error.
+#[derive(thiserror::Error, Debug)]
+pub enum ConversionError {
+    /// Invalid DER encoding.
+    #[error("Invalid DER encoding: {0}")]
+    Der(#[from] x509_cert::der::Error),
+    /// Certificate content which cannot be represented in the target format.
+    #[error("Unsupported {0}")]
+    Unsupported(String),
+    /// Converted certificate differs from the original one.
+    #[error("Certificate cannot be converted losslessly")]
+    NotLossless,
+}
+
+/// Convert a DER encoded X.509 v3 certificate to a C509 certificate of type 3.
+///
+/// # Arguments
+/// - `der` - The DER encoded X.509 certificate.
+///
+/// # Errors
+/// Returns an error if the certificate is not a valid DER encoded X.509 certificate,
+/// or if it contains content which cannot be converted losslessly.
+pub fn from_der(der: &[u8]) -> Result<C509, ConversionError> {
+    let certificate = Certificate::from_der(der)?;
+    let c509 = certificate_to_c509(&certificate)?;
+    if to_der(&c509)? != der {
+        return Err(ConversionError::NotLossless);
+    }
+    Ok(c509)
+}
+
+/// Convert a C509 certificate of type 3 to a DER encoded X.509 v3 certificate.
+///
+/// # Arguments
+/// - `c509` - The C509 certificate.
+///
+/// # Errors
+/// Returns an error if the certificate is not of type 3, is not signed, or contains
+/// content which cannot be represented in X.509.
+pub fn to_der(c509: &C509) -> Result<Vec<u8>, ConversionError> {
+    let tbs_cert = c509.tbs_cert();
+    if tbs_cert.c509_certificate_type() != X509_CERTIFICATE_TYPE {
+        return Err(ConversionError::Unsupported(format!(
+            "certificate type {}, only type {X509_CERTIFICATE_TYPE} can be converted to X.509",
+            tbs_cert.c509_certificate_type()
+        )));
+    }
+    let signature_value = c509
+        .issuer_signature_value()
+        .as_ref()
+        .ok_or_else(|| ConversionError::Unsupported("unsigned certificate".to_string()))?;
+
+    let signature_algorithm =
+        issuer_signature_algorithm_to_der(tbs_cert.issuer_signature_algorithm())?;
+    let extensions = extensions::extensions_to_der(tbs_cert.extensions())?;
+    let tbs_certificate = TbsCertificate {
+        version: Version::V3,
+        serial_number: serial_number_to_der(tbs_cert.certificate_serial_number())?,
+        signature: signature_algorithm.clone(),
+        issuer: name_to_der(tbs_cert.issuer())?,
+        validity: Validity {
+            not_before: time_to_der(tbs_cert.validity_not_before())?,
+            not_after: time_to_der(tbs_cert.validity_not_after())?,
+        },
+        subject: name_to_der(tbs_cert.subject())?,
+        subject_public_key_info: subject_public_key_info_to_der(
+            tbs_cert.subject_public_key_algorithm(),
+            tbs_cert.subject_public_key(),
+        )?,
+        issuer_unique_id: None,
+        subject_unique_id: None,
+        extensions: (!extensions.is_empty()).then_some(extensions),
+    };
+    let signature = signature_to_der(&signature_algorithm.oid, signature_value)?;
+
+    Ok(Certificate {
+        tbs_certificate,
+        signature_algorithm,
+        signature,
+    }
+    .to_der()?)
+}
+
+/// Convert a decoded X.509 certificate to a C509 certificate.
+fn certificate_to_c509(certificate: &Certificate) -> Result<C509, ConversionError> {
+    let tbs = &certificate.tbs_certificate;
+    if tbs.version != Version::V3 {
+        return Err(ConversionError::Unsupported(format!(
+            "certificate version {:?}, only X.509 v3 is supported",
+            tbs.version
+        )));
+    }
+    if tbs.issuer_unique_id.is_some() || tbs.subject_unique_id.is_some() {
+        return Err(ConversionError::Unsupported(
+            "issuer or subject unique identifier".to_string(),
+        ));
+    }
+    if certificate.signature_algorithm != tbs.signature {
+        return Err(ConversionError::Unsupported(
+            "signature algorithm different from the TBS certificate one".to_string(),
+        ));
+    }
+
+    let tbs_cert = TbsCert::new(
+        X509_CERTIFICATE_TYPE,
+        serial_number_to_c509(&tbs.serial_number)?,
+        issuer_signature_algorithm_to_c509(&tbs.signature)?,
+        Some(name_to_c509(&tbs.issuer)?),
+        Time::new(tbs.validity.not_before.to_unix_duration().as_secs()),
+        Time::new(tbs.validity.not_after.to_unix_duration().as_secs()),
+        name_to_c509(&tbs.subject)?,
+        subject_public_key_algorithm_to_c509(&tbs.subject_public_key_info.algorithm)?,
+        subject_public_key_to_c509(&tbs.subject_public_key_info)?,
+        extensions::extensions_to_c509(tbs.extensions.as_deref().unwrap_or_default())?,
+    );
+    let signature = signature_to_c509(&tbs.signature.oid, &certificate.signature)?;
+
+    Ok(C509::new(tbs_cert, Some(signature)))
+}
+
+// -------------------OID-----------------------
+
+/// Convert a DER OID to a C509 OID.
+fn oid_to_c509(oid: &ObjectIdentifier) -> Oid<'static> {
+    Oid::new(Cow::Owned(oid.as_bytes().to_vec()))
+}
+
+/// Convert a C509 OID to a DER OID.
+fn oid_to_der(oid: &Oid<'_>) -> Result<ObjectIdentifier, ConversionError> {
+    Ok(ObjectIdentifier::from_bytes(oid.as_bytes()).map_err(x509_cert::der::Error::from)?)
+}
+
+/// Whether the OID is registered in the C509 lookup table.
+fn is_registered(oid: &C509oidRegistered) -> bool {
+    oid.table().get_map().contains_right(oid.c509_oid().oid())
+}
+
+// -------------------Serial number-----------------------
+
+/// Convert an X.509 serial number to a C509 serial number.
+fn serial_number_to_c509(
+    serial_number: &SerialNumber,
+) -> Result<UnwrappedBigUint, ConversionError> {
+    let bytes = serial_number.as_bytes();
+    if bytes.first().is_some_and(|b| b & 0x80 != 0) {
+        return Err(ConversionError::Unsupported(
+            "negative serial number".to_string(),
+        ));
+    }
+    let significant_bytes = bytes
+        .iter()
+        .skip_while(|&&b| b == 0)
+        .copied()
+        .collect::<Vec<u8>>();
+    if significant_bytes.len() > std::mem::size_of::<u64>() {
+        return Err(ConversionError::Unsupported(format!(
+            "serial number of {} bytes, at most {} bytes are supported",
+            significant_bytes.len(),
+            std::mem::size_of::<u64>()
+        )));
+    }
+    Ok(UnwrappedBigUint::new(
+        significant_bytes
+            .iter()
+            .fold(0, |acc, &b| (acc << 8) | u64::from(b)),
+    ))
+}
+
+/// Convert a C509 serial number to an X.509 serial number.
+fn serial_number_to_der(serial_number: &UnwrappedBigUint) -> Result<SerialNumber, ConversionError> {
+    let bytes = u64::from(serial_number.clone()).to_be_bytes();
+    let significant_bytes = bytes
+        .iter()
+        .skip_while(|&&b| b == 0)
+        .copied()
+        .collect::<Vec<u8>>();
+    if significant_bytes.is_empty() {
+        return Ok(SerialNumber::new(&[0])?);
+    }
+    Ok(SerialNumber::new(&significant_bytes)?)
+}
+
+// -------------------Algorithms-----------------------
+
+/// Convert an X.509 signature algorithm to a C509 issuer signature algorithm.
+fn issuer_signature_algorithm_to_c509(
+    algorithm: &AlgorithmIdentifierOwned,
+) -> Result<IssuerSignatureAlgorithm, ConversionError> {
+    if algorithm.oid != ED25519 && algorithm.oid != ECDSA_WITH_SHA256 {
+        return Err(ConversionError::Unsupported(format!(
+            "signature algorithm {}",
+            algorithm.oid
+        )));
+    }
+    if algorithm.parameters.is_some() {
+        return Err(ConversionError::Unsupported(format!(
+            "signature algorithm {} parameters",
+            algorithm.oid
+        )));
+    }
+    Ok(IssuerSignatureAlgorithm::new(
+        oid_to_c509(&algorithm.oid),
+        None,
+    ))
+}
+
+/// Convert a C509 issuer signature algorithm to an X.509 signature algorithm.
+fn issuer_signature_algorithm_to_der(
+    algorithm: &IssuerSignatureAlgorithm,
+) -> Result<AlgorithmIdentifierOwned, ConversionError> {
+    let oid = oid_to_der(algorithm.algo_identifier().oid())?;
+    if algorithm.algo_identifier().param().is_some() {
+        return Err(ConversionError::Unsupported(format!(
+            "signature algorithm {oid} parameters"
+        )));
+    }
+    Ok(AlgorithmIdentifierOwned {
+        oid,
+        parameters: None,
+    })
+}
+
+/// Convert an X.509 subject public key algorithm to a C509 subject public key
+/// algorithm.
+/// The registered C509 EC public key algorithm implies the secp256r1 curve.
+fn subject_public_key_algorithm_to_c509(
+    algorithm: &AlgorithmIdentifierOwned,
+) -> Result<SubjectPubKeyAlgorithm, ConversionError> {
+    let supported = match &algorithm.parameters {
+        None => algorithm.oid == ED25519,
+        Some(parameters) => {
+            algorithm.oid == EC_PUBLIC_KEY
+                && parameters.decode_as::<ObjectIdentifier>().ok() == Some(SECP256R1)
+        },
+    };
+    if !supported {
+        return Err(ConversionError::Unsupported(format!(
+            "subject public key algorithm {}",
+            algorithm.oid
+        )));
+    }
+    Ok(SubjectPubKeyAlgorithm::new(
+        oid_to_c509(&algorithm.oid),
+        None,
+    ))
+}
+
+/// Convert a C509 subject public key algorithm and key to an X.509 subject public key
+/// info.
+fn subject_public_key_info_to_der(
+    algorithm: &SubjectPubKeyAlgorithm, public_key: &[u8],
+) -> Result<SubjectPublicKeyInfoOwned, ConversionError> {
+    let oid = oid_to_der(algorithm.algo_identifier().oid())?;
+    if algorithm.algo_identifier().param().is_some() {
+        return Err(ConversionError::Unsupported(format!(
+            "subject public key algorithm {oid} parameters"
+        )));
+    }
+    let (parameters, public_key) = if oid == EC_PUBLIC_KEY {
+        (
+            Some(Any::encode_from(&SECP256R1)?),
+            ec_point_to_der(public_key)?,
+        )
+    } else if oid == ED25519 {
+        (None, public_key.to_vec())
+    } else {
+        return Err(ConversionError::Unsupported(format!(
+            "subject public key algorithm {oid}"
+        )));
+    };
+    Ok(SubjectPublicKeyInfoOwned {
+        algorithm: AlgorithmIdentifierOwned { oid, parameters },
+        subject_public_key: BitString::from_bytes(&public_key)?,
+    })
+}
+
+// -------------------Public key-----------------------
+
+/// Convert an X.509 subject public key to a C509 subject public key.
+fn subject_public_key_to_c509(
+    public_key_info: &SubjectPublicKeyInfoOwned,
+) -> Result<Vec<u8>, ConversionError> {
+    let public_key = public_key_info
+        .subject_public_key
+        .as_bytes()
+        .ok_or_else(|| {
+            ConversionError::Unsupported("subject public key with unused bits".to_string())
+        })?;
+    if public_key_info.algorithm.oid == EC_PUBLIC_KEY {
+        ec_point_to_c509(public_key)
+    } else {
+        Ok(public_key.to_vec())
+    }
+}
+
+/// Convert a SEC1 encoded EC point to the C509 encoding.
+/// An uncompressed point is encoded as its x coordinate prefixed with a byte which
+/// indicates the sign of the y coordinate, a compressed point is kept as is.
+fn ec_point_to_c509(point: &[u8]) -> Result<Vec<u8>, ConversionError> {
+    p256::PublicKey::from_sec1_bytes(point)
+        .map_err(|_| ConversionError::Unsupported("P-256 public key".to_string()))?;
+    match point.split_first() {
+        Some((&SEC1_UNCOMPRESSED, coordinates)) => {
+            let (x, y) = coordinates.split_at(coordinates.len() / 2);
+            let prefix = if y.last().is_some_and(|b| b % 2 == 0) {
+                C509_UNCOMPRESSED_EVEN
+            } else {
+                C509_UNCOMPRESSED_ODD
+            };
+            Ok([&[prefix], x].concat())
+        },
+        _ => Ok(point.to_vec()),
+    }
+}
+
+/// Convert a C509 encoded EC point to the SEC1 encoding.
+fn ec_point_to_der(point: &[u8]) -> Result<Vec<u8>, ConversionError> {
+    let invalid_point = || ConversionError::Unsupported("P-256 public key".to_string());
+    match point.split_first() {
+        Some((&prefix @ (C509_UNCOMPRESSED_EVEN | C509_UNCOMPRESSED_ODD), x)) => {
+            let sec1_prefix = if prefix == C509_UNCOMPRESSED_EVEN {
+                SEC1_COMPRESSED_EVEN
+            } else {
+                SEC1_COMPRESSED_ODD
+            };
+            let public_key = p256::PublicKey::from_sec1_bytes(&[&[sec1_prefix], x].concat())
+                .map_err(|_| invalid_point())?;
+            Ok(public_key.to_encoded_point(false).as_bytes().to_vec())
+        },
+        Some((SEC1_COMPRESSED_EVEN | SEC1_COMPRESSED_ODD, _)) => {
+            p256::PublicKey::from_sec1_bytes(point).map_err(|_| invalid_point())?;
+            Ok(point.to_vec())
+        },
+        _ => Err(invalid_point()),
+    }
+}
+
+// -------------------Signature-----------------------
+
+/// Convert an X.509 signature value to a C509 signature value.
+/// ECDSA signatures are converted from the DER encoding to the `r || s` concatenation.
+fn signature_to_c509(
+    algorithm: &ObjectIdentifier, signature: &BitString,
+) -> Result<Vec<u8>, ConversionError> {
+    let signature = signature.as_bytes().ok_or_else(|| {
+        ConversionError::Unsupported("signature value with unused bits".to_string())
+    })?;
+    if *algorithm == ECDSA_WITH_SHA256 {
+        let signature = p256::ecdsa::Signature::from_der(signature)
+            .map_err(|_| ConversionError::Unsupported("ECDSA signature value".to_string()))?;
+        Ok(signature.to_bytes().to_vec())
+    } else {
+        Ok(signature.to_vec())
+    }
+}
+
+/// Convert a C509 signature value to an X.509 signature value.
+fn signature_to_der(
+    algorithm: &ObjectIdentifier, signature: &[u8],
+) -> Result<BitString, ConversionError> {
+    if *algorithm == ECDSA_WITH_SHA256 {
+        let signature = p256::ecdsa::Signature::from_slice(signature)
+            .map_err(|_| ConversionError::Unsupported("ECDSA signature value".to_string()))?;
+        Ok(BitString::from_bytes(signature.to_der().as_bytes())?)
+    } else {
+        Ok(BitString::from_bytes(signature)?)
+    }
+}
+
+// -------------------Name-----------------------
+
+/// Convert an X.509 name to a C509 name.
+/// Each relative distinguished name must contain a single attribute, registered
+/// attributes must have an UTF-8 string value.
+fn name_to_c509(name: &RdnSequence) -> Result<Name, ConversionError> {
+    let attributes = name
+        .0
+        .iter()
+        .map(|rdn| {
+            match rdn.0.as_slice() {
+                [attribute] => attribute_to_c509(attribute),
+                _ => {
+                    Err(ConversionError::Unsupported(
+                        "multi-valued relative distinguished name".to_string(),
+                    ))
+                },
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Name::new(NameValue::Attribute(attributes)))
+}
+
+/// Convert an X.509 attribute to a C509 attribute.
+/// Unregistered attributes keep the DER encoded value.
+fn attribute_to_c509(attribute: &AttributeTypeAndValue) -> Result<Attribute, ConversionError> {
+    let mut c509_attribute = Attribute::new(oid_to_c509(&attribute.oid));
+    let value = if is_registered(c509_attribute.registered_oid()) {
+        if attribute.value.tag() != Tag::Utf8String {
+            return Err(ConversionError::Unsupported(format!(
+                "attribute {} value type {}",
+                attribute.oid,
+                attribute.value.tag()
+            )));
+        }
+        AttributeValue::Text(attribute.value.decode_as::<String>()?)
+    } else {
+        AttributeValue::Bytes(attribute.value.to_der()?)
+    };
+    c509_attribute.add_value(value);
+    Ok(c509_attribute)
+}
+
+/// Convert a C509 name to an X.509 name.
+/// Each attribute value is placed into its own relative distinguished name.
+fn name_to_der(name: &Name) -> Result<RdnSequence, ConversionError> {
+    let NameValue::Attribute(attributes) = name.value() else {
+        return Err(ConversionError::Unsupported(
+            "name which is not a list of attributes".to_string(),
+        ));
+    };
+    let mut rdns = Vec::new();
+    for attribute in attributes {
+        let oid = oid_to_der(attribute.registered_oid().c509_oid().oid())?;
+        for value in attribute.value() {
+            let value = match value {
+                AttributeValue::Text(text) => Any::new(Tag::Utf8String, text.as_bytes())?,
+                AttributeValue::Bytes(bytes) => Any::from_der(bytes)?,
+            };
+            rdns.push(RelativeDistinguishedName(SetOfVec::try_from(vec![
+                AttributeTypeAndValue { oid, value },
+            ])?));
+        }
+    }
+    Ok(RdnSequence(rdns))
+}
+
+// -------------------Time-----------------------
+
+/// Convert a C509 time to an X.509 time.
+/// `UTCTime` is used for dates before 2050, `GeneralizedTime` otherwise.
+fn time_to_der(time: &Time) -> Result<X509Time, ConversionError> {
+    let duration = Duration::from_secs(time.time());
+    if time.time() < GENERALIZED_TIME_START {
+        Ok(X509Time::UtcTime(UtcTime::from_unix_duration(duration)?))
+    } else {
+        Ok(X509Time::GeneralTime(GeneralizedTime::from_unix_duration(
+            duration,
+        )?))
+    }
+}
+
+// -------------------Test----------------------
+
+#[cfg(test)]
+mod test_convert {
+    use minicbor::{Decode, Encode};
+
+    use super::*;
+    use crate::cert_tbs::test_tbs_cert::tbs_1;
+
+    /// Self-signed Ed25519 CA certificate, with critical key usage and basic constraints
+    /// extensions.
+    /// Generated with `openssl` tool:
+    /// ```shell
+    /// openssl req -x509 -new -key ed25519.pem -config ca.cnf -set_serial 0x1234ABCD \
+    ///     -days 3650 -outform DER -out ed25519.der
+    /// ```
+    const ED25519_CERT: &str = concat!(
+        "3082015330820105a00302010202041234abcd300506032b6570302e311930170603550403",
+        "0c10436174616c79737420746573742043413111300f060355040a0c08436174616c797374",
+        "301e170d3236313031363131323531395a170d3336313031333131323531395a302e311930",
+        "1706035504030c10436174616c79737420746573742043413111300f060355040a0c084361",
+        "74616c797374302a300506032b65700321008a8c12bcc28ea1b8ed39511c4c92e982ea0270",
+        "b5f0f352743232849c93dc8010a3453043301d0603551d0e0416041442f230e44efd07b853",
+        "55704352298f311d79d537300e0603551d0f0101ff04040302010630120603551d130101ff",
+        "040830060101ff020101300506032b657003410007a48dafce8ae2717498718b9adfc6cfc6",
+        "a8542aa32f66c25c9c3ab6180cb2d7938f4d49beb4d18907d29d5734eb52d4486e800ca9c1",
+        "77e08070e2db73c97501",
+    );
+
+    /// Self-signed P-256 certificate with an uncompressed public key, subject
+    /// alternative names and an unregistered extension.
+    /// Generated with `openssl` tool:
+    /// ```shell
+    /// openssl req -x509 -new -key p256.pem -sha256 -config server.cnf -set_serial 42 \
+    ///     -days 365 -outform DER -out p256.der
+    /// ```
+    const P256_CERT: &str = concat!(
+        "308201ad30820154a00302010202012a300a06082a8648ce3d040302301b31193017060355",
+        "04030c10636174616c7973742e6578616d706c65301e170d3236313031363131323531395a",
+        "170d3237313031363131323531395a301b3119301706035504030c10636174616c7973742e",
+        "6578616d706c653059301306072a8648ce3d020106082a8648ce3d03010703420004675858",
+        "87e808a1358d074fa0f36763820a8d7510c4903b7a80640c8532d42adee7f97d6b50c15847",
+        "b90b48204e08e5ea9de9402559b087d683d72ae0656800bda38188308185300b0603551d0f",
+        "04040302078030090603551d130402300030370603551d110430302e8210636174616c7973",
+        "742e6578616d706c6582147777772e636174616c7973742e6578616d706c658704c0000201",
+        "301306092b06010401868d1f0104060c0474657374301d0603551d0e0416041441e0ba2ca8",
+        "39b9044ec8037bbe20c6effedbfb67300a06082a8648ce3d0403020347003044022054bab5",
+        "955d1255938c17ee2db85eb52c1618266ea742e3ddeea5b0f06bd4a01a02200ad7050ee537",
+        "ea242ff601526dc41c7f75ca59730180caa256ff2e17759645ac",
+    );
+
+    /// Convert the DER certificate to C509, encode and decode it as CBOR and convert it
+    /// back to DER.
+    fn round_trip(der: &[u8]) -> C509 {
+        let c509 = from_der(der).expect("Failed to convert DER to C509");
+
+        let mut buffer = Vec::new();
+        let mut encoder = minicbor::Encoder::new(&mut buffer);
+        c509.encode(&mut encoder, &mut ())
+            .expect("Failed to encode C509");
+        let mut decoder = minicbor::Decoder::new(&buffer);
+        let decoded = C509::decode(&mut decoder, &mut ()).expect("Failed to decode C509");
+
+        assert_eq!(
+            to_der(&decoded).expect("Failed to convert C509 to DER"),
+            der
+        );
+        c509
+    }
+
+    #[test]
+    fn ed25519_certificate_round_trip() {
+        let der = hex::decode(ED25519_CERT).expect("Invalid hex");
+        let c509 = round_trip(&der);
+
+        let tbs_cert = c509.tbs_cert();
+        assert_eq!(tbs_cert.c509_certificate_type(), X509_CERTIFICATE_TYPE);
+        assert_eq!(
+            tbs_cert.certificate_serial_number(),
+            &UnwrappedBigUint::new(0x1234_ABCD)
+        );
+        assert_eq!(tbs_cert.subject_public_key().len(), 32);
+        assert_eq!(tbs_cert.extensions().extensions().len(), 3);
+    }
+
+    #[test]
+    fn p256_certificate_round_trip() {
+        let der = hex::decode(P256_CERT).expect("Invalid hex");
+        let c509 = round_trip(&der);
+
+        let tbs_cert = c509.tbs_cert();
+        assert_eq!(tbs_cert.subject_public_key().len(), 33);
+        assert!(matches!(
+            tbs_cert.subject_public_key().first(),
+            Some(&(C509_UNCOMPRESSED_EVEN | C509_UNCOMPRESSED_ODD))
+        ));
+        assert_eq!(
+            c509.issuer_signature_value().as_ref().map(Vec::len),
+            Some(64)
+        );
+        assert_eq!(tbs_cert.extensions().extensions().len(), 5);
+    }
+
+    #[test]
+    fn unsupported_certificate() {
+        // Unsigned certificate
+        let (tbs_cert, _) = tbs_1();
+        assert!(matches!(
+            to_der(&C509::new(tbs_cert, None)),
+            Err(ConversionError::Unsupported(_))
+        ));
+
+        // Invalid DER
+        assert!(matches!(
+            from_der(&[0x30, 0x00]),
+            Err(ConversionError::Der(_))
+        ));
+    }
+}
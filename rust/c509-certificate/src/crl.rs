@@ -0,0 +1,603 @@
+//! C509 Certificate Revocation List (CRL)
+//!
+//! A CRL is encoded the same way as the C509 certificate, the TBS CRL followed by the
+//! issuer signature value.
+//!
+//! ```cddl
+//! C509CertificateRevocationList = [
+//!     TBSCertificateRevocationList,
+//!     issuerSignatureValue: any,
+//! ]
+//! TBSCertificateRevocationList = (
+//!     C509CertificateRevocationListType: int,
+//!     issuer: Name,
+//!     thisUpdate: Time,
+//!     nextUpdate: Time,
+//!     revokedCertificates: RevokedCertificates,
+//!     crlExtensions: Extensions,
+//!     issuerSignatureAlgorithm: AlgorithmIdentifier,
+//! )
+//! RevokedCertificates = [
+//!     * (
+//!         userCertificate: CertificateSerialNumber,
+//!         revocationDate: Time,
+//!         crlEntryExtensions: Extensions,
+//!     )
+//! ]
+//! ```
+//!
+//! The revocation reason of an entry is the `reasonCode` CRL entry extension, as
+//! defined in RFC 5280.
+//!
+//! For more information about CRL,
+//! visit [C509 Certificate](https://datatracker.ietf.org/doc/draft-ietf-cose-cbor-encoded-cert/11/)
+
+use anyhow::anyhow;
+use asn1_rs::{oid, Oid};
+use minicbor::{encode::Write, Decode, Decoder, Encode, Encoder};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    big_uint::UnwrappedBigUint,
+    cert_tbs::TbsCert,
+    extensions::{
+        extension::{Extension, ExtensionValue},
+        Extensions,
+    },
+    helper::{
+        decode::{decode_array_len, decode_bytes, decode_datatype, decode_helper},
+        encode::{encode_array_len, encode_bytes, encode_helper, encode_null},
+    },
+    issuer_sig_algo::IssuerSignatureAlgorithm,
+    name::Name,
+    signing::{PrivateKey, PublicKey},
+    time::Time,
+};
+
+/// OID of the `reasonCode` CRL entry extension.
+static REASON_CODE_OID: Oid<'static> = oid!(2.5.29 .21);
+
+/// DER tag of the ENUMERATED type, the `reasonCode` extension value.
+const DER_ENUMERATED_TAG: u8 = 0x0A;
+
+/// Number of the fields of a revoked certificate entry.
+const REVOKED_CERTIFICATE_FIELDS: u64 = 3;
+
+/// Reason of the certificate revocation, `CRLReason` of RFC 5280.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RevocationReason {
+    /// `unspecified`
+    Unspecified = 0,
+    /// `keyCompromise`
+    KeyCompromise = 1,
+    /// `cACompromise`
+    CaCompromise = 2,
+    /// `affiliationChanged`
+    AffiliationChanged = 3,
+    /// `superseded`
+    Superseded = 4,
+    /// `cessationOfOperation`
+    CessationOfOperation = 5,
+    /// `certificateHold`
+    CertificateHold = 6,
+    /// `removeFromCRL`
+    RemoveFromCrl = 8,
+    /// `privilegeWithdrawn`
+    PrivilegeWithdrawn = 9,
+    /// `aACompromise`
+    AaCompromise = 10,
+}
+
+impl TryFrom<u8> for RevocationReason {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Unspecified),
+            1 => Ok(Self::KeyCompromise),
+            2 => Ok(Self::CaCompromise),
+            3 => Ok(Self::AffiliationChanged),
+            4 => Ok(Self::Superseded),
+            5 => Ok(Self::CessationOfOperation),
+            6 => Ok(Self::CertificateHold),
+            8 => Ok(Self::RemoveFromCrl),
+            9 => Ok(Self::PrivilegeWithdrawn),
+            10 => Ok(Self::AaCompromise),
+            _ => Err(anyhow!("Unknown revocation reason {value}")),
+        }
+    }
+}
+
+impl RevocationReason {
+    /// Create the `reasonCode` CRL entry `Extension`.
+    #[must_use]
+    pub fn into_extension(self) -> Extension {
+        // The extension is not registered in C509, so its value is the DER encoding.
+        Extension::new(
+            REASON_CODE_OID.clone(),
+            ExtensionValue::Bytes(vec![DER_ENUMERATED_TAG, 1, self as u8]),
+            false,
+        )
+    }
+}
+
+impl TryFrom<&Extension> for RevocationReason {
+    type Error = anyhow::Error;
+
+    fn try_from(extension: &Extension) -> Result<Self, Self::Error> {
+        match extension.value() {
+            ExtensionValue::Bytes(value)
+                if extension.registered_oid().c509_oid().oid() == &REASON_CODE_OID =>
+            {
+                match value.as_slice() {
+                    [DER_ENUMERATED_TAG, 1, reason] => Self::try_from(*reason),
+                    _ => Err(anyhow!("Invalid reason code extension value")),
+                }
+            },
+            _ => Err(anyhow!("Extension is not a reason code")),
+        }
+    }
+}
+
+/// A revoked certificate entry of the CRL.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct RevokedCertificate {
+    /// Serial number of the revoked certificate.
+    user_certificate: UnwrappedBigUint,
+    /// Time of the revocation.
+    revocation_date: Time,
+    /// CRL entry extensions.
+    crl_entry_extensions: Extensions,
+}
+
+impl RevokedCertificate {
+    /// Create a new instance of the revoked certificate entry.
+    /// The reason, if provided, is added as the `reasonCode` CRL entry extension.
+    #[must_use]
+    pub fn new(
+        user_certificate: UnwrappedBigUint, revocation_date: Time, reason: Option<RevocationReason>,
+    ) -> Self {
+        let mut crl_entry_extensions = Extensions::new();
+        if let Some(reason) = reason {
+            crl_entry_extensions.add_extension(reason.into_extension());
+        }
+        Self {
+            user_certificate,
+            revocation_date,
+            crl_entry_extensions,
+        }
+    }
+
+    /// Get the serial number of the revoked certificate.
+    #[must_use]
+    pub fn user_certificate(&self) -> &UnwrappedBigUint {
+        &self.user_certificate
+    }
+
+    /// Get the time of the revocation.
+    #[must_use]
+    pub fn revocation_date(&self) -> &Time {
+        &self.revocation_date
+    }
+
+    /// Get the CRL entry extensions.
+    #[must_use]
+    pub fn crl_entry_extensions(&self) -> &Extensions {
+        &self.crl_entry_extensions
+    }
+
+    /// Get the revocation reason, from the `reasonCode` CRL entry extension.
+    #[must_use]
+    pub fn reason(&self) -> Option<RevocationReason> {
+        self.crl_entry_extensions
+            .extensions()
+            .iter()
+            .find_map(|ext| RevocationReason::try_from(ext).ok())
+    }
+}
+
+/// A struct represents a To Be Signed Certificate Revocation List (TBS CRL).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct TbsCrl {
+    /// CRL type.
+    c509_crl_type: u8,
+    /// Issuer
+    issuer: Name,
+    /// Time the CRL was issued.
+    this_update: Time,
+    /// Time the next CRL will be issued by.
+    next_update: Time,
+    /// Revoked certificates.
+    revoked_certificates: Vec<RevokedCertificate>,
+    /// CRL Extensions
+    crl_extensions: Extensions,
+    /// Issuer Signature Algorithm
+    issuer_signature_algorithm: IssuerSignatureAlgorithm,
+}
+
+impl TbsCrl {
+    /// Create a new instance of TBS CRL.
+    #[must_use]
+    pub fn new(
+        c509_crl_type: u8, issuer: Name, this_update: Time, next_update: Time,
+        revoked_certificates: Vec<RevokedCertificate>, crl_extensions: Extensions,
+        issuer_signature_algorithm: IssuerSignatureAlgorithm,
+    ) -> Self {
+        Self {
+            c509_crl_type,
+            issuer,
+            this_update,
+            next_update,
+            revoked_certificates,
+            crl_extensions,
+            issuer_signature_algorithm,
+        }
+    }
+
+    /// Get the CRL type.
+    #[must_use]
+    pub fn c509_crl_type(&self) -> u8 {
+        self.c509_crl_type
+    }
+
+    /// Get the issuer.
+    #[must_use]
+    pub fn issuer(&self) -> &Name {
+        &self.issuer
+    }
+
+    /// Get the time the CRL was issued.
+    #[must_use]
+    pub fn this_update(&self) -> &Time {
+        &self.this_update
+    }
+
+    /// Get the time the next CRL will be issued by.
+    #[must_use]
+    pub fn next_update(&self) -> &Time {
+        &self.next_update
+    }
+
+    /// Get the revoked certificates.
+    #[must_use]
+    pub fn revoked_certificates(&self) -> &[RevokedCertificate] {
+        &self.revoked_certificates
+    }
+
+    /// Get the CRL extensions.
+    #[must_use]
+    pub fn crl_extensions(&self) -> &Extensions {
+        &self.crl_extensions
+    }
+
+    /// Get the issuer signature algorithm.
+    #[must_use]
+    pub fn issuer_signature_algorithm(&self) -> &IssuerSignatureAlgorithm {
+        &self.issuer_signature_algorithm
+    }
+
+    /// Get the revoked certificate entry of the certificate serial number.
+    #[must_use]
+    pub fn revoked_certificate(&self, serial: &UnwrappedBigUint) -> Option<&RevokedCertificate> {
+        self.revoked_certificates
+            .iter()
+            .find(|revoked| &revoked.user_certificate == serial)
+    }
+}
+
+impl Encode<()> for TbsCrl {
+    fn encode<W: Write>(
+        &self, e: &mut Encoder<W>, ctx: &mut (),
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        encode_helper(e, "CRL type", ctx, &self.c509_crl_type)?;
+        self.issuer.encode(e, ctx)?;
+        self.this_update.encode(e, ctx)?;
+        self.next_update.encode(e, ctx)?;
+        encode_array_len(
+            e,
+            "Revoked certificates",
+            (self.revoked_certificates.len() as u64) * REVOKED_CERTIFICATE_FIELDS,
+        )?;
+        for revoked in &self.revoked_certificates {
+            revoked.user_certificate.encode(e, ctx)?;
+            revoked.revocation_date.encode(e, ctx)?;
+            revoked.crl_entry_extensions.encode(e, ctx)?;
+        }
+        self.crl_extensions.encode(e, ctx)?;
+        self.issuer_signature_algorithm.encode(e, ctx)?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for TbsCrl {
+    fn decode(d: &mut Decoder<'_>, ctx: &mut ()) -> Result<Self, minicbor::decode::Error> {
+        let crl_type = decode_helper(d, "CRL type", ctx)?;
+        let issuer = Name::decode(d, ctx)?;
+        let this_update = Time::decode(d, ctx)?;
+        let next_update = Time::decode(d, ctx)?;
+
+        let len = decode_array_len(d, "Revoked certificates")?;
+        if len % REVOKED_CERTIFICATE_FIELDS != 0 {
+            return Err(minicbor::decode::Error::message(format!(
+                "Invalid revoked certificates length {len}, must be a multiple of {REVOKED_CERTIFICATE_FIELDS}"
+            )));
+        }
+        let mut revoked_certificates = Vec::new();
+        for _ in 0..len / REVOKED_CERTIFICATE_FIELDS {
+            revoked_certificates.push(RevokedCertificate {
+                user_certificate: UnwrappedBigUint::decode(d, ctx)?,
+                revocation_date: Time::decode(d, ctx)?,
+                crl_entry_extensions: Extensions::decode(d, ctx)?,
+            });
+        }
+
+        let crl_extensions = Extensions::decode(d, ctx)?;
+        let issuer_signature_algorithm = IssuerSignatureAlgorithm::decode(d, ctx)?;
+
+        Ok(TbsCrl::new(
+            crl_type,
+            issuer,
+            this_update,
+            next_update,
+            revoked_certificates,
+            crl_extensions,
+            issuer_signature_algorithm,
+        ))
+    }
+}
+
+/// A struct represents the C509 Certificate Revocation List.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct C509Crl {
+    /// A TBS CRL.
+    tbs_crl: TbsCrl,
+    /// An optional `IssuerSignatureValue` of the CRL.
+    issuer_signature_value: Option<Vec<u8>>,
+}
+
+impl C509Crl {
+    /// Create a new instance of C509 CRL.
+    #[must_use]
+    pub fn new(tbs_crl: TbsCrl, issuer_signature_value: Option<Vec<u8>>) -> Self {
+        Self {
+            tbs_crl,
+            issuer_signature_value,
+        }
+    }
+
+    /// Get the TBS CRL of the C509 CRL.
+    #[must_use]
+    pub fn tbs_crl(&self) -> &TbsCrl {
+        &self.tbs_crl
+    }
+
+    /// Get the `IssuerSignatureValue` of the C509 CRL.
+    #[must_use]
+    pub fn issuer_signature_value(&self) -> &Option<Vec<u8>> {
+        &self.issuer_signature_value
+    }
+}
+
+impl Encode<()> for C509Crl {
+    fn encode<W: Write>(
+        &self, e: &mut Encoder<W>, ctx: &mut (),
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        self.tbs_crl.encode(e, ctx)?;
+        match self.issuer_signature_value {
+            Some(ref value) => encode_bytes(e, "C509 CRL Issuer Signature value", value)?,
+            None => encode_null(e, "C509 CRL Issuer Signature value")?,
+        };
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for C509Crl {
+    fn decode(d: &mut Decoder<'_>, ctx: &mut ()) -> Result<Self, minicbor::decode::Error> {
+        let tbs_crl = TbsCrl::decode(d, ctx)?;
+        let issuer_signature_value = match decode_datatype(d, "C509 CRL Issuer Signature value")? {
+            minicbor::data::Type::Bytes => {
+                Some(decode_bytes(d, "C509 CRL Issuer Signature value")?)
+            },
+            _ => None,
+        };
+        Ok(Self::new(tbs_crl, issuer_signature_value))
+    }
+}
+
+/// Generate a signed or unsigned C509 CRL.
+///
+/// # Arguments
+/// - `tbs_crl` - A TBS CRL.
+/// - `private_key` - An optional private key of the issuer, if provided CRL is signed.
+///
+/// # Returns
+/// Returns a signed or unsigned C509 CRL.
+///
+/// # Errors
+///
+/// Returns an error if the generated data is invalid.
+pub fn generate(tbs_crl: &TbsCrl, private_key: Option<&PrivateKey>) -> anyhow::Result<Vec<u8>> {
+    let mut encoded_tbs = Vec::new();
+    tbs_crl.encode(&mut Encoder::new(&mut encoded_tbs), &mut ())?;
+    let sign_data = private_key.map(|pk| pk.sign(&encoded_tbs));
+
+    let mut encoded_crl = Vec::new();
+    C509Crl::new(tbs_crl.clone(), sign_data)
+        .encode(&mut Encoder::new(&mut encoded_crl), &mut ())?;
+    Ok(encoded_crl)
+}
+
+/// Verify the signature of a C509 CRL.
+///
+/// # Arguments
+/// - `crl` - The cbor encoded C509 CRL to verify.
+/// - `public_key` - The public key of the issuer.
+///
+/// # Errors
+/// Returns an error if the `issuer_signature_value` is invalid or the signature cannot be
+/// verified.
+pub fn verify(crl: &[u8], public_key: &PublicKey) -> anyhow::Result<()> {
+    let crl = C509Crl::decode(&mut Decoder::new(crl), &mut ())?;
+    let mut encoded_tbs = Vec::new();
+    crl.tbs_crl()
+        .encode(&mut Encoder::new(&mut encoded_tbs), &mut ())?;
+    let issuer_sig = crl.issuer_signature_value().clone().ok_or(anyhow!(
+        "Signature verification failed, No issuer signature"
+    ))?;
+    public_key.verify(&encoded_tbs, &issuer_sig)
+}
+
+/// A reason a certificate can not be trusted, found checking it against a CRL.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum RevocationViolation {
+    /// CRL is issued by another issuer than the certificate.
+    #[error("CRL issuer does not match the certificate issuer")]
+    IssuerMismatch,
+    /// CRL is not yet issued at the time of the check.
+    #[error("CRL is not valid before {0}")]
+    NotYetValid(u64),
+    /// A newer CRL must have been issued at the time of the check.
+    #[error("CRL is outdated since {0}")]
+    Outdated(u64),
+    /// Certificate is revoked.
+    #[error("Certificate is revoked since {date}, reason {reason:?}")]
+    Revoked {
+        /// Time of the revocation.
+        date: u64,
+        /// Reason of the revocation.
+        reason: Option<RevocationReason>,
+    },
+}
+
+/// Check the TBS certificate against the CRL of its issuer.
+/// The CRL signature must be verified separately, with [`verify`].
+///
+/// # Arguments
+/// - `tbs_cert` - A TBS certificate, for a C509 certificate use [`C509::tbs_cert`].
+/// - `tbs_crl` - The TBS CRL, for a C509 CRL use [`C509Crl::tbs_crl`].
+/// - `time` - Time of the check, in seconds since the Unix epoch.
+///
+/// # Returns
+/// Returns all violations found, the certificate is not revoked by a valid CRL if none
+/// are found.
+///
+/// [`C509::tbs_cert`]: crate::c509::C509::tbs_cert
+#[must_use]
+pub fn check_revocation(
+    tbs_cert: &TbsCert, tbs_crl: &TbsCrl, time: u64,
+) -> Vec<RevocationViolation> {
+    let mut violations = Vec::new();
+
+    if tbs_cert.issuer() != tbs_crl.issuer() {
+        violations.push(RevocationViolation::IssuerMismatch);
+    }
+
+    let this_update = tbs_crl.this_update().time();
+    if time < this_update {
+        violations.push(RevocationViolation::NotYetValid(this_update));
+    }
+    let next_update = tbs_crl.next_update().time();
+    if time > next_update {
+        violations.push(RevocationViolation::Outdated(next_update));
+    }
+
+    if let Some(revoked) = tbs_crl.revoked_certificate(tbs_cert.certificate_serial_number()) {
+        let date = revoked.revocation_date().time();
+        if date <= time {
+            violations.push(RevocationViolation::Revoked {
+                date,
+                reason: revoked.reason(),
+            });
+        }
+    }
+
+    violations
+}
+
+// ------------------Test----------------------
+
+#[cfg(test)]
+mod test_crl {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::{
+        cert_tbs::test_tbs_cert::tbs_1,
+        signing::tests::{private_key_str, public_key_str},
+    };
+
+    fn tbs_crl(tbs_cert: &TbsCert, private_key: &PrivateKey) -> TbsCrl {
+        TbsCrl::new(
+            2,
+            tbs_cert.issuer().clone(),
+            Time::new(1_700_000_000),
+            Time::new(1_800_000_000),
+            vec![
+                RevokedCertificate::new(UnwrappedBigUint::new(1), Time::new(1_690_000_000), None),
+                RevokedCertificate::new(
+                    tbs_cert.certificate_serial_number().clone(),
+                    Time::new(1_710_000_000),
+                    Some(RevocationReason::KeyCompromise),
+                ),
+            ],
+            Extensions::new(),
+            private_key.issuer_signature_algorithm(),
+        )
+    }
+
+    #[test]
+    fn crl_encode_decode_and_verify() {
+        let (tbs_cert, _) = tbs_1();
+        let private_key: PrivateKey =
+            FromStr::from_str(&private_key_str()).expect("Cannot create private key");
+        let tbs_crl = tbs_crl(&tbs_cert, &private_key);
+
+        let crl = generate(&tbs_crl, Some(&private_key)).expect("Failed to generate CRL");
+        let decoded =
+            C509Crl::decode(&mut Decoder::new(&crl), &mut ()).expect("Failed to decode CRL");
+        assert_eq!(decoded.tbs_crl(), &tbs_crl);
+        let revoked = decoded
+            .tbs_crl()
+            .revoked_certificates()
+            .last()
+            .expect("Missing revoked certificate");
+        assert_eq!(revoked.reason(), Some(RevocationReason::KeyCompromise));
+
+        let public_key: PublicKey =
+            FromStr::from_str(&public_key_str()).expect("Cannot create public key");
+        assert!(verify(&crl, &public_key).is_ok());
+
+        let unsigned = generate(&tbs_crl, None).expect("Failed to generate CRL");
+        assert!(verify(&unsigned, &public_key).is_err());
+    }
+
+    #[test]
+    fn certificate_revocation() {
+        let (tbs_cert, _) = tbs_1();
+        let private_key: PrivateKey =
+            FromStr::from_str(&private_key_str()).expect("Cannot create private key");
+        let tbs_crl = tbs_crl(&tbs_cert, &private_key);
+
+        // Before the revocation
+        assert!(check_revocation(&tbs_cert, &tbs_crl, 1_705_000_000).is_empty());
+        assert_eq!(check_revocation(&tbs_cert, &tbs_crl, 1_750_000_000), vec![
+            RevocationViolation::Revoked {
+                date: 1_710_000_000,
+                reason: Some(RevocationReason::KeyCompromise),
+            }
+        ]);
+        assert_eq!(check_revocation(&tbs_cert, &tbs_crl, 1_600_000_000), vec![
+            RevocationViolation::NotYetValid(1_700_000_000)
+        ]);
+        assert_eq!(check_revocation(&tbs_cert, &tbs_crl, 1_900_000_000), vec![
+            RevocationViolation::Outdated(1_800_000_000),
+            RevocationViolation::Revoked {
+                date: 1_710_000_000,
+                reason: Some(RevocationReason::KeyCompromise),
+            },
+        ]);
+    }
+}
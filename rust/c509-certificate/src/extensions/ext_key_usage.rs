@@ -0,0 +1,322 @@
+//! C509 Extended Key Usage extension.
+//!
+//! Key purposes registered in the C509 Extended Key Usages Registry are encoded as
+//! an int, other key purposes are encoded as an unwrapped OID (~oid).
+//!
+//! ```cddl
+//! ExtKeyUsageSyntax = [ 2* KeyPurposeId ] / KeyPurposeId
+//! KeyPurposeId = int / ~oid
+//! ```
+//!
+//! For more information about `ExtKeyUsageSyntax`,
+//! visit [C509 Certificate](https://datatracker.ietf.org/doc/draft-ietf-cose-cbor-encoded-cert/11/)
+
+use asn1_rs::{oid, Oid};
+use minicbor::{encode::Write, Decode, Decoder, Encode, Encoder};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use super::extension::{data::C509ExtensionType, Extension, ExtensionValue};
+use crate::{
+    helper::{
+        decode::{decode_array_len, decode_datatype, decode_helper},
+        encode::{encode_array_len, encode_helper},
+    },
+    oid::C509oid,
+    tables::IntegerToOidTable,
+};
+
+/// OID of the `serverAuth` key purpose.
+pub const SERVER_AUTH_OID: Oid<'static> = oid!(1.3.6 .1 .5 .5 .7 .3 .1);
+/// OID of the `clientAuth` key purpose.
+pub const CLIENT_AUTH_OID: Oid<'static> = oid!(1.3.6 .1 .5 .5 .7 .3 .2);
+/// OID of the `codeSigning` key purpose.
+pub const CODE_SIGNING_OID: Oid<'static> = oid!(1.3.6 .1 .5 .5 .7 .3 .3);
+/// OID of the `emailProtection` key purpose.
+pub const EMAIL_PROTECTION_OID: Oid<'static> = oid!(1.3.6 .1 .5 .5 .7 .3 .4);
+/// OID of the `timeStamping` key purpose.
+pub const TIME_STAMPING_OID: Oid<'static> = oid!(1.3.6 .1 .5 .5 .7 .3 .8);
+/// OID of the `OCSPSigning` key purpose.
+pub const OCSP_SIGNING_OID: Oid<'static> = oid!(1.3.6 .1 .5 .5 .7 .3 .9);
+
+/// C509 Extended Key Usages Registry.
+const KEY_PURPOSE_DATA: [(i16, Oid<'static>); 6] = [
+    (1, SERVER_AUTH_OID),
+    (2, CLIENT_AUTH_OID),
+    (3, CODE_SIGNING_OID),
+    (4, EMAIL_PROTECTION_OID),
+    (8, TIME_STAMPING_OID),
+    (9, OCSP_SIGNING_OID),
+];
+
+/// Define static lookup for key purposes table
+static KEY_PURPOSES_TABLE: Lazy<IntegerToOidTable> = Lazy::new(|| {
+    let mut table = IntegerToOidTable::new();
+    for (i, oid) in KEY_PURPOSE_DATA {
+        table.add(i, oid);
+    }
+    table
+});
+
+/// A struct of C509 `ExtendedKeyUsage`, the set of key purposes.
+///
+/// # Example
+///
+/// ```
+/// use c509_certificate::extensions::ext_key_usage::ExtendedKeyUsage;
+///
+/// let extension = ExtendedKeyUsage::new()
+///     .server_auth()
+///     .client_auth()
+///     .into_extension(false);
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ExtendedKeyUsage(Vec<C509oid>);
+
+impl ExtendedKeyUsage {
+    /// Create a new instance of `ExtendedKeyUsage` without key purposes.
+    #[must_use]
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Get the key purposes.
+    #[must_use]
+    pub fn purposes(&self) -> &[C509oid] {
+        &self.0
+    }
+
+    /// Add the key purpose, duplicates are ignored.
+    #[must_use]
+    pub fn purpose(mut self, oid: Oid<'static>) -> Self {
+        let purpose = C509oid::new(oid);
+        if !self.0.contains(&purpose) {
+            self.0.push(purpose);
+        }
+        self
+    }
+
+    /// Add the `serverAuth` key purpose.
+    #[must_use]
+    pub fn server_auth(self) -> Self {
+        self.purpose(SERVER_AUTH_OID)
+    }
+
+    /// Add the `clientAuth` key purpose.
+    #[must_use]
+    pub fn client_auth(self) -> Self {
+        self.purpose(CLIENT_AUTH_OID)
+    }
+
+    /// Add the `codeSigning` key purpose.
+    #[must_use]
+    pub fn code_signing(self) -> Self {
+        self.purpose(CODE_SIGNING_OID)
+    }
+
+    /// Add the `emailProtection` key purpose.
+    #[must_use]
+    pub fn email_protection(self) -> Self {
+        self.purpose(EMAIL_PROTECTION_OID)
+    }
+
+    /// Add the `timeStamping` key purpose.
+    #[must_use]
+    pub fn time_stamping(self) -> Self {
+        self.purpose(TIME_STAMPING_OID)
+    }
+
+    /// Add the `OCSPSigning` key purpose.
+    #[must_use]
+    pub fn ocsp_signing(self) -> Self {
+        self.purpose(OCSP_SIGNING_OID)
+    }
+
+    /// Whether the key purpose is present.
+    #[must_use]
+    pub fn contains(&self, oid: &Oid<'static>) -> bool {
+        self.0.iter().any(|purpose| purpose.oid() == oid)
+    }
+
+    /// Create the Extended Key Usage `Extension`.
+    #[must_use]
+    pub fn into_extension(self, critical: bool) -> Extension {
+        Extension::new(
+            C509ExtensionType::ExtendedKeyUsage.oid(),
+            ExtensionValue::ExtendedKeyUsage(self),
+            critical,
+        )
+    }
+}
+
+impl Default for ExtendedKeyUsage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Create a non-critical Extended Key Usage `Extension`.
+impl From<ExtendedKeyUsage> for Extension {
+    fn from(eku: ExtendedKeyUsage) -> Self {
+        eku.into_extension(false)
+    }
+}
+
+impl TryFrom<&Extension> for ExtendedKeyUsage {
+    type Error = anyhow::Error;
+
+    fn try_from(extension: &Extension) -> Result<Self, Self::Error> {
+        match extension.value() {
+            ExtensionValue::ExtendedKeyUsage(eku)
+                if extension.registered_oid().c509_oid().oid()
+                    == &C509ExtensionType::ExtendedKeyUsage.oid() =>
+            {
+                Ok(eku.clone())
+            },
+            _ => Err(anyhow::anyhow!("Extension is not an Extended Key Usage")),
+        }
+    }
+}
+
+impl Encode<()> for ExtendedKeyUsage {
+    fn encode<W: Write>(
+        &self, e: &mut Encoder<W>, ctx: &mut (),
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        match self.0.as_slice() {
+            [] => {
+                return Err(minicbor::encode::Error::message(
+                    "Extended Key Usage should not be empty",
+                ));
+            },
+            [purpose] => encode_purpose(e, ctx, purpose)?,
+            purposes => {
+                encode_array_len(e, "Extended Key Usage", purposes.len() as u64)?;
+                for purpose in purposes {
+                    encode_purpose(e, ctx, purpose)?;
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for ExtendedKeyUsage {
+    fn decode(d: &mut Decoder<'_>, ctx: &mut ()) -> Result<Self, minicbor::decode::Error> {
+        let mut eku = ExtendedKeyUsage::new();
+        if decode_datatype(d, "Extended Key Usage")? == minicbor::data::Type::Array {
+            let len = decode_array_len(d, "Extended Key Usage")?;
+            for _ in 0..len {
+                eku.0.push(decode_purpose(d, ctx)?);
+            }
+        } else {
+            eku.0.push(decode_purpose(d, ctx)?);
+        }
+        Ok(eku)
+    }
+}
+
+/// Encode the key purpose as an int if registered, otherwise as an unwrapped OID.
+fn encode_purpose<W: Write>(
+    e: &mut Encoder<W>, ctx: &mut (), purpose: &C509oid,
+) -> Result<(), minicbor::encode::Error<W::Error>> {
+    if let Some(i) = KEY_PURPOSES_TABLE.get_map().get_by_right(purpose.oid()) {
+        encode_helper(e, "Key purpose as OID int", ctx, i)
+    } else {
+        purpose.encode(e, ctx)
+    }
+}
+
+/// Decode the key purpose encoded as an int or as an unwrapped OID.
+fn decode_purpose(d: &mut Decoder<'_>, ctx: &mut ()) -> Result<C509oid, minicbor::decode::Error> {
+    match decode_datatype(d, "Key purpose")? {
+        minicbor::data::Type::U8 | minicbor::data::Type::I8 => {
+            let i: i16 = decode_helper(d, "Key purpose as OID int", ctx)?;
+            let oid = KEY_PURPOSES_TABLE.get_map().get_by_left(&i).ok_or(
+                minicbor::decode::Error::message(format!(
+                    "Key purpose not found in the registry table given int {i}"
+                )),
+            )?;
+            Ok(C509oid::new(oid.clone()))
+        },
+        _ => C509oid::decode(d, ctx),
+    }
+}
+
+// ------------------Test----------------------
+
+#[cfg(test)]
+mod test_ext_key_usage {
+    use super::*;
+    use crate::extensions::Extensions;
+
+    #[test]
+    fn encode_decode_registered() {
+        let mut buffer = Vec::new();
+        let mut encoder = Encoder::new(&mut buffer);
+
+        let eku = ExtendedKeyUsage::new().server_auth().client_auth();
+        eku.encode(&mut encoder, &mut ())
+            .expect("Failed to encode ExtendedKeyUsage");
+        // Array of 2 : 0x82
+        // serverAuth : 0x01, clientAuth : 0x02
+        assert_eq!(hex::encode(buffer.clone()), "820102");
+
+        let mut decoder = Decoder::new(&buffer);
+        let decoded_eku = ExtendedKeyUsage::decode(&mut decoder, &mut ())
+            .expect("Failed to decode ExtendedKeyUsage");
+        assert_eq!(decoded_eku, eku);
+    }
+
+    #[test]
+    fn encode_decode_single_unregistered() {
+        let mut buffer = Vec::new();
+        let mut encoder = Encoder::new(&mut buffer);
+
+        let eku = ExtendedKeyUsage::new().purpose(oid!(1.3.6 .1 .4 .1 .311 .10 .3 .4));
+        eku.encode(&mut encoder, &mut ())
+            .expect("Failed to encode ExtendedKeyUsage");
+        // Unwrapped OID : 0x4a2b0601040182370a0304
+        assert_eq!(hex::encode(buffer.clone()), "4a2b0601040182370a0304");
+
+        let mut decoder = Decoder::new(&buffer);
+        let decoded_eku = ExtendedKeyUsage::decode(&mut decoder, &mut ())
+            .expect("Failed to decode ExtendedKeyUsage");
+        assert_eq!(decoded_eku, eku);
+    }
+
+    #[test]
+    fn extension_round_trip() {
+        let eku = ExtendedKeyUsage::new()
+            .code_signing()
+            .code_signing()
+            .purpose(oid!(1.3.6 .1 .4 .1 .311 .10 .3 .4));
+        assert_eq!(eku.purposes().len(), 2);
+        assert!(eku.contains(&CODE_SIGNING_OID));
+
+        let mut exts = Extensions::new();
+        exts.add_extension(eku.clone().into_extension(true));
+
+        let mut buffer = Vec::new();
+        let mut encoder = Encoder::new(&mut buffer);
+        exts.encode(&mut encoder, &mut ())
+            .expect("Failed to encode Extensions");
+        // 1 extension (array of 1): 0x81
+        // Extended Key Usage with critical true : 0x27
+        // Array of 2 : 0x82, codeSigning : 0x03, unwrapped OID : 0x4a2b0601040182370a0304
+        assert_eq!(
+            hex::encode(buffer.clone()),
+            "812782034a2b0601040182370a0304"
+        );
+
+        let mut decoder = Decoder::new(&buffer);
+        let decoded_exts =
+            Extensions::decode(&mut decoder, &mut ()).expect("Failed to decode Extensions");
+        assert_eq!(decoded_exts, exts);
+        let ext = decoded_exts
+            .extensions()
+            .first()
+            .expect("Missing Extension");
+        assert!(ext.critical());
+        assert_eq!(ExtendedKeyUsage::try_from(ext).expect("Invalid EKU"), eku);
+    }
+}
@@ -28,6 +28,8 @@ pub enum C509ExtensionType {
     KeyUsage = 2,
     /// Subject Alternative Name
     SubjectAlternativeName = 3,
+    /// Extended Key Usage
+    ExtendedKeyUsage = 8,
 }
 
 /// `Extension` data table
@@ -43,7 +45,7 @@ const EXTENSION_DATA: [ExtensionDataTuple; 25] = [
     ( 5, oid!(2.5.29 .31),                     Evt::Unsupported,     "CRL Distribution Points"),
     ( 6, oid!(2.5.29 .32),                     Evt::Unsupported,     "Certificate Policies"),
     ( 7, oid!(2.5.29 .35),                     Evt::Unsupported,     "Authority Key Identifier"),
-    ( 8, oid!(2.5.29 .37),                     Evt::Unsupported,     "Extended Key Usage"),
+    ( 8, oid!(2.5.29 .37),                     Evt::ExtendedKeyUsage, "Extended Key Usage"),
     ( 9, oid!(1.3.6 .1 .5 .5 .7 .1 .1),        Evt::Unsupported,     "Authority Information Access"),
     (10, oid!(1.3.6 .1 .4 .1 .11129 .2 .4 .2), Evt::Unsupported,     "Signed Certificate Timestamp List"),
     (24, oid!(2.5.29 .9),                      Evt::Unsupported,     "Subject Directory Attributes"),
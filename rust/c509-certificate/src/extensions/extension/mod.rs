@@ -9,7 +9,7 @@ use minicbor::{encode::Write, Decode, Decoder, Encode, Encoder};
 use serde::{Deserialize, Deserializer, Serialize};
 use strum_macros::EnumDiscriminants;
 
-use super::alt_name::AlternativeName;
+use super::{alt_name::AlternativeName, ext_key_usage::ExtendedKeyUsage};
 use crate::{
     helper::{
         decode::{decode_bytes, decode_datatype, decode_helper},
@@ -198,6 +198,8 @@ pub enum ExtensionValue {
     Bytes(Vec<u8>),
     /// An Alternative Name.
     AlternativeName(AlternativeName),
+    /// An Extended Key Usage.
+    ExtendedKeyUsage(ExtendedKeyUsage),
     /// An unsupported value.
     Unsupported,
 }
@@ -222,6 +224,9 @@ impl Encode<()> for ExtensionValue {
             ExtensionValue::AlternativeName(value) => {
                 value.encode(e, ctx)?;
             },
+            ExtensionValue::ExtendedKeyUsage(value) => {
+                value.encode(e, ctx)?;
+            },
             ExtensionValue::Unsupported => {
                 return Err(minicbor::encode::Error::message(
                     "Cannot encode unsupported Extension value",
@@ -249,6 +254,10 @@ where C: ExtensionValueTypeTrait + Debug
                 let value = AlternativeName::decode(d, &mut ())?;
                 Ok(ExtensionValue::AlternativeName(value))
             },
+            ExtensionValueType::ExtendedKeyUsage => {
+                let value = ExtendedKeyUsage::decode(d, &mut ())?;
+                Ok(ExtensionValue::ExtendedKeyUsage(value))
+            },
             ExtensionValueType::Unsupported => {
                 Err(minicbor::decode::Error::message(
                     "Cannot decode Unsupported extension value",
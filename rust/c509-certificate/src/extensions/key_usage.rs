@@ -0,0 +1,225 @@
+//! C509 Key Usage extension.
+//!
+//! The Key Usage BIT STRING is encoded as an unsigned integer, where the bit `i` of the
+//! integer is the named bit `i` of the BIT STRING.
+//!
+//! ```cddl
+//! KeyUsage = int
+//! ```
+//!
+//! For more information about `KeyUsage`,
+//! visit [C509 Certificate](https://datatracker.ietf.org/doc/draft-ietf-cose-cbor-encoded-cert/11/)
+
+use super::extension::{data::C509ExtensionType, Extension, ExtensionValue};
+
+/// Named bits of the Key Usage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyUsageBit {
+    /// `digitalSignature`
+    DigitalSignature = 0,
+    /// `nonRepudiation`, also known as `contentCommitment`
+    NonRepudiation = 1,
+    /// `keyEncipherment`
+    KeyEncipherment = 2,
+    /// `dataEncipherment`
+    DataEncipherment = 3,
+    /// `keyAgreement`
+    KeyAgreement = 4,
+    /// `keyCertSign`
+    KeyCertSign = 5,
+    /// `cRLSign`
+    CrlSign = 6,
+    /// `encipherOnly`
+    EncipherOnly = 7,
+    /// `decipherOnly`
+    DecipherOnly = 8,
+}
+
+impl KeyUsageBit {
+    /// Get the integer value of the bit.
+    fn mask(self) -> i64 {
+        1 << (self as u8)
+    }
+}
+
+/// A struct of C509 `KeyUsage`, the set of the named bits.
+///
+/// # Example
+///
+/// ```
+/// use c509_certificate::extensions::key_usage::KeyUsage;
+///
+/// let extension = KeyUsage::new()
+///     .digital_signature()
+///     .key_cert_sign()
+///     .into_extension(true);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KeyUsage(i64);
+
+impl KeyUsage {
+    /// Create a new instance of `KeyUsage` without any bits set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self(0)
+    }
+
+    /// Get the integer value of the `KeyUsage`.
+    #[must_use]
+    pub fn value(self) -> i64 {
+        self.0
+    }
+
+    /// Set the named bit.
+    #[must_use]
+    pub fn with(self, bit: KeyUsageBit) -> Self {
+        Self(self.0 | bit.mask())
+    }
+
+    /// Whether the named bit is set.
+    #[must_use]
+    pub fn contains(self, bit: KeyUsageBit) -> bool {
+        self.0 & bit.mask() != 0
+    }
+
+    /// Set the `digitalSignature` bit.
+    #[must_use]
+    pub fn digital_signature(self) -> Self {
+        self.with(KeyUsageBit::DigitalSignature)
+    }
+
+    /// Set the `nonRepudiation` bit.
+    #[must_use]
+    pub fn non_repudiation(self) -> Self {
+        self.with(KeyUsageBit::NonRepudiation)
+    }
+
+    /// Set the `keyEncipherment` bit.
+    #[must_use]
+    pub fn key_encipherment(self) -> Self {
+        self.with(KeyUsageBit::KeyEncipherment)
+    }
+
+    /// Set the `dataEncipherment` bit.
+    #[must_use]
+    pub fn data_encipherment(self) -> Self {
+        self.with(KeyUsageBit::DataEncipherment)
+    }
+
+    /// Set the `keyAgreement` bit.
+    #[must_use]
+    pub fn key_agreement(self) -> Self {
+        self.with(KeyUsageBit::KeyAgreement)
+    }
+
+    /// Set the `keyCertSign` bit.
+    #[must_use]
+    pub fn key_cert_sign(self) -> Self {
+        self.with(KeyUsageBit::KeyCertSign)
+    }
+
+    /// Set the `cRLSign` bit.
+    #[must_use]
+    pub fn crl_sign(self) -> Self {
+        self.with(KeyUsageBit::CrlSign)
+    }
+
+    /// Set the `encipherOnly` bit.
+    #[must_use]
+    pub fn encipher_only(self) -> Self {
+        self.with(KeyUsageBit::EncipherOnly)
+    }
+
+    /// Set the `decipherOnly` bit.
+    #[must_use]
+    pub fn decipher_only(self) -> Self {
+        self.with(KeyUsageBit::DecipherOnly)
+    }
+
+    /// Create the Key Usage `Extension`.
+    /// At least one bit should be set, the critical flag of the empty Key Usage is lost
+    /// when it is the only extension.
+    #[must_use]
+    pub fn into_extension(self, critical: bool) -> Extension {
+        Extension::new(
+            C509ExtensionType::KeyUsage.oid(),
+            ExtensionValue::Int(self.0),
+            critical,
+        )
+    }
+}
+
+/// Create a critical Key Usage `Extension`, as recommended by RFC 5280.
+impl From<KeyUsage> for Extension {
+    fn from(key_usage: KeyUsage) -> Self {
+        key_usage.into_extension(true)
+    }
+}
+
+impl TryFrom<&Extension> for KeyUsage {
+    type Error = anyhow::Error;
+
+    fn try_from(extension: &Extension) -> Result<Self, Self::Error> {
+        match extension.value() {
+            ExtensionValue::Int(value)
+                if *value >= 0
+                    && extension.registered_oid().c509_oid().oid()
+                        == &C509ExtensionType::KeyUsage.oid() =>
+            {
+                Ok(Self(*value))
+            },
+            _ => Err(anyhow::anyhow!("Extension is not a Key Usage")),
+        }
+    }
+}
+
+// ------------------Test----------------------
+
+#[cfg(test)]
+mod test_key_usage {
+    use minicbor::{Decode, Decoder, Encode, Encoder};
+
+    use super::*;
+    use crate::extensions::Extensions;
+
+    #[test]
+    fn key_usage_bits() {
+        let key_usage = KeyUsage::new()
+            .digital_signature()
+            .key_cert_sign()
+            .crl_sign();
+        // 0b110_0001
+        assert_eq!(key_usage.value(), 97);
+        assert!(key_usage.contains(KeyUsageBit::KeyCertSign));
+        assert!(!key_usage.contains(KeyUsageBit::DecipherOnly));
+        assert_eq!(KeyUsage::new().decipher_only().value(), 256);
+    }
+
+    #[test]
+    fn extension_round_trip() {
+        let key_usage = KeyUsage::new().digital_signature().key_cert_sign();
+        let mut exts = Extensions::new();
+        exts.add_extension(key_usage.into());
+
+        let mut buffer = Vec::new();
+        let mut encoder = Encoder::new(&mut buffer);
+        exts.encode(&mut encoder, &mut ())
+            .expect("Failed to encode Extensions");
+        // Only KeyUsage with critical true, value -33 : 0x3820
+        assert_eq!(hex::encode(buffer.clone()), "3820");
+
+        let mut decoder = Decoder::new(&buffer);
+        let decoded_exts =
+            Extensions::decode(&mut decoder, &mut ()).expect("Failed to decode Extensions");
+        assert_eq!(decoded_exts, exts);
+        let ext = decoded_exts
+            .extensions()
+            .first()
+            .expect("Missing Extension");
+        assert!(ext.critical());
+        assert_eq!(
+            KeyUsage::try_from(ext).expect("Invalid KeyUsage"),
+            key_usage
+        );
+    }
+}
@@ -15,7 +15,10 @@
 //! visit [C509 Certificate](https://datatracker.ietf.org/doc/draft-ietf-cose-cbor-encoded-cert/11/)
 
 pub mod alt_name;
+pub mod ext_key_usage;
 pub mod extension;
+pub mod key_usage;
+pub mod subject_alt_name;
 
 use std::fmt::Debug;
 
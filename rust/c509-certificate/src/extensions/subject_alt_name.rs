@@ -0,0 +1,208 @@
+//! C509 Subject Alternative Name extension builder.
+//!
+//! The extension value is an [`AlternativeName`], see [`super::alt_name`] for its
+//! encoding.
+
+use std::net::IpAddr;
+
+use asn1_rs::Oid;
+
+use super::{
+    alt_name::{AlternativeName, GeneralNamesOrText},
+    extension::{data::C509ExtensionType, Extension, ExtensionValue},
+};
+use crate::{
+    general_names::{
+        general_name::{GeneralName, GeneralNameTypeRegistry, GeneralNameValue},
+        GeneralNames,
+    },
+    oid::C509oid,
+};
+
+/// A builder of the C509 Subject Alternative Name `Extension`.
+///
+/// # Example
+///
+/// ```
+/// use c509_certificate::extensions::subject_alt_name::SubjectAltName;
+///
+/// let extension = SubjectAltName::new()
+///     .uri("https://example.com")
+///     .dns_name("example.com")
+///     .into_extension(false);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SubjectAltName(GeneralNames);
+
+impl SubjectAltName {
+    /// Create a new instance of `SubjectAltName` without names.
+    #[must_use]
+    pub fn new() -> Self {
+        Self(GeneralNames::new())
+    }
+
+    /// Get the general names.
+    #[must_use]
+    pub fn general_names(&self) -> &GeneralNames {
+        &self.0
+    }
+
+    /// Add the general name.
+    #[must_use]
+    pub fn general_name(
+        mut self, gn_type: GeneralNameTypeRegistry, value: GeneralNameValue,
+    ) -> Self {
+        self.0.add_general_name(GeneralName::new(gn_type, value));
+        self
+    }
+
+    /// Add the `uniformResourceIdentifier` name.
+    #[must_use]
+    pub fn uri(self, uri: impl Into<String>) -> Self {
+        self.general_name(
+            GeneralNameTypeRegistry::UniformResourceIdentifier,
+            GeneralNameValue::Text(uri.into()),
+        )
+    }
+
+    /// Add the `dNSName` name.
+    #[must_use]
+    pub fn dns_name(self, dns_name: impl Into<String>) -> Self {
+        self.general_name(
+            GeneralNameTypeRegistry::DNSName,
+            GeneralNameValue::Text(dns_name.into()),
+        )
+    }
+
+    /// Add the `rfc822Name` (email address) name.
+    #[must_use]
+    pub fn email(self, email: impl Into<String>) -> Self {
+        self.general_name(
+            GeneralNameTypeRegistry::Rfc822Name,
+            GeneralNameValue::Text(email.into()),
+        )
+    }
+
+    /// Add the `iPAddress` name.
+    #[must_use]
+    pub fn ip_address(self, ip_address: IpAddr) -> Self {
+        let bytes = match ip_address {
+            IpAddr::V4(ip) => ip.octets().to_vec(),
+            IpAddr::V6(ip) => ip.octets().to_vec(),
+        };
+        self.general_name(
+            GeneralNameTypeRegistry::IPAddress,
+            GeneralNameValue::Bytes(bytes),
+        )
+    }
+
+    /// Add the `registeredID` name.
+    #[must_use]
+    pub fn registered_id(self, oid: Oid<'static>) -> Self {
+        self.general_name(
+            GeneralNameTypeRegistry::RegisteredID,
+            GeneralNameValue::Oid(C509oid::new(oid)),
+        )
+    }
+
+    /// Create the Subject Alternative Name `Extension`.
+    /// RFC 5280 requires the extension to be critical if the subject is empty.
+    #[must_use]
+    pub fn into_extension(self, critical: bool) -> Extension {
+        Extension::new(
+            C509ExtensionType::SubjectAlternativeName.oid(),
+            ExtensionValue::AlternativeName(AlternativeName::new(
+                GeneralNamesOrText::GeneralNames(self.0),
+            )),
+            critical,
+        )
+    }
+}
+
+/// Create a non-critical Subject Alternative Name `Extension`.
+impl From<SubjectAltName> for Extension {
+    fn from(san: SubjectAltName) -> Self {
+        san.into_extension(false)
+    }
+}
+
+impl TryFrom<&Extension> for SubjectAltName {
+    type Error = anyhow::Error;
+
+    fn try_from(extension: &Extension) -> Result<Self, Self::Error> {
+        if extension.registered_oid().c509_oid().oid()
+            != &C509ExtensionType::SubjectAlternativeName.oid()
+        {
+            return Err(anyhow::anyhow!(
+                "Extension is not a Subject Alternative Name"
+            ));
+        }
+        match extension.value() {
+            ExtensionValue::AlternativeName(name) => {
+                match name.general_name() {
+                    GeneralNamesOrText::GeneralNames(gns) => Ok(Self(gns.clone())),
+                    GeneralNamesOrText::Text(text) => Ok(Self::new().dns_name(text.clone())),
+                }
+            },
+            _ => Err(anyhow::anyhow!("Invalid Subject Alternative Name value")),
+        }
+    }
+}
+
+// ------------------Test----------------------
+
+#[cfg(test)]
+mod test_subject_alt_name {
+    use std::net::Ipv4Addr;
+
+    use asn1_rs::oid;
+    use minicbor::{Decode, Decoder, Encode, Encoder};
+
+    use super::*;
+    use crate::extensions::{key_usage::KeyUsage, Extensions};
+
+    #[test]
+    fn extension_round_trip() {
+        let san = SubjectAltName::new()
+            .uri("https://example.com")
+            .ip_address(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
+            .registered_id(oid!(1.2.3));
+        let mut exts = Extensions::new();
+        exts.add_extension(KeyUsage::new().digital_signature().into());
+        exts.add_extension(san.clone().into_extension(true));
+
+        let mut buffer = Vec::new();
+        let mut encoder = Encoder::new(&mut buffer);
+        exts.encode(&mut encoder, &mut ())
+            .expect("Failed to encode Extensions");
+
+        let mut decoder = Decoder::new(&buffer);
+        let decoded_exts =
+            Extensions::decode(&mut decoder, &mut ()).expect("Failed to decode Extensions");
+        assert_eq!(decoded_exts, exts);
+        let ext = decoded_exts.extensions().get(1).expect("Missing Extension");
+        assert!(ext.critical());
+        assert_eq!(SubjectAltName::try_from(ext).expect("Invalid SAN"), san);
+    }
+
+    #[test]
+    fn only_dns_name() {
+        let san = SubjectAltName::new().dns_name("example.com");
+        let mut exts = Extensions::new();
+        exts.add_extension(san.clone().into());
+
+        let mut buffer = Vec::new();
+        let mut encoder = Encoder::new(&mut buffer);
+        exts.encode(&mut encoder, &mut ())
+            .expect("Failed to encode Extensions");
+        // 1 extension (array of 1): 0x81
+        // Subject Alternative Name : 0x03
+        // "example.com": 0x6b6578616d706c652e636f6d
+        assert_eq!(hex::encode(buffer.clone()), "81036b6578616d706c652e636f6d");
+
+        let mut decoder = Decoder::new(&buffer);
+        let decoded_exts =
+            Extensions::decode(&mut decoder, &mut ()).expect("Failed to decode Extensions");
+        assert_eq!(decoded_exts, exts);
+    }
+}
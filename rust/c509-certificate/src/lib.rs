@@ -54,12 +54,15 @@ pub mod attributes;
 pub mod big_uint;
 pub mod c509;
 pub mod cert_tbs;
+pub mod convert;
+pub mod crl;
 pub mod extensions;
 pub mod general_names;
 mod helper;
 pub mod issuer_sig_algo;
 pub mod name;
 pub mod oid;
+pub mod profile;
 pub mod signing;
 pub mod subject_pub_key_algo;
 mod tables;
@@ -78,8 +81,27 @@ pub mod time;
 ///
 /// # Errors
 ///
-/// Returns an error if the generated data is invalid.
+/// Returns an error if the generated data is invalid, or if the issuer signature
+/// algorithm of the `tbs_cert` is not the algorithm of the `private_key`.
 pub fn generate(tbs_cert: &TbsCert, private_key: Option<&PrivateKey>) -> anyhow::Result<Vec<u8>> {
+    if let Some(private_key) = private_key {
+        let algorithm = tbs_cert
+            .issuer_signature_algorithm()
+            .algo_identifier()
+            .oid();
+        if algorithm
+            != private_key
+                .issuer_signature_algorithm()
+                .algo_identifier()
+                .oid()
+        {
+            return Err(anyhow!(
+                "Issuer signature algorithm {algorithm} does not match the {:?} private key",
+                private_key.key_type()
+            ));
+        }
+    }
+
     // Encode the TbsCert
     let encoded_tbs = {
         let mut buffer = Vec::new();
@@ -126,10 +148,26 @@ mod test {
     use std::str::FromStr;
 
     use cert_tbs::test_tbs_cert::tbs_1;
-    use signing::tests::private_key_str;
+    use signing::tests::{p256_private_key_str, private_key_str};
 
     use super::*;
 
+    /// `tbs_cert` with the issuer signature algorithm of the `private_key`.
+    fn tbs_for_key(tbs_cert: &TbsCert, private_key: &PrivateKey) -> TbsCert {
+        TbsCert::new(
+            tbs_cert.c509_certificate_type(),
+            tbs_cert.certificate_serial_number().clone(),
+            private_key.issuer_signature_algorithm(),
+            Some(tbs_cert.issuer().clone()),
+            tbs_cert.validity_not_before().clone(),
+            tbs_cert.validity_not_after().clone(),
+            tbs_cert.subject().clone(),
+            tbs_cert.subject_public_key_algorithm().clone(),
+            tbs_cert.subject_public_key().to_vec(),
+            tbs_cert.extensions().clone(),
+        )
+    }
+
     #[test]
     fn test_generate_and_verify_signed_c509_cert() {
         let (tbs_cert, _) = tbs_1();
@@ -138,10 +176,51 @@ mod test {
             "Cannot create
 private key",
         );
+        let tbs_cert = tbs_for_key(&tbs_cert, &private_key);
 
         let signed_c509 = generate(&tbs_cert, Some(&private_key))
             .expect("Failed to generate signed C509 certificate");
 
         assert!(verify(&signed_c509, &private_key.public_key()).is_ok());
     }
+
+    #[test]
+    fn test_generate_and_verify_p256_signed_c509_cert() {
+        // `tbs_1` is the RFC example certificate, using ES256 and P-256 algorithms
+        let (tbs_cert, _) = tbs_1();
+
+        let private_key: PrivateKey =
+            FromStr::from_str(&p256_private_key_str()).expect("Cannot create private key");
+        assert_eq!(
+            &private_key.issuer_signature_algorithm(),
+            tbs_cert.issuer_signature_algorithm()
+        );
+
+        let signed_c509 = generate(&tbs_cert, Some(&private_key))
+            .expect("Failed to generate signed C509 certificate");
+
+        assert!(verify(&signed_c509, &private_key.public_key()).is_ok());
+
+        let ed25519_key: PrivateKey =
+            FromStr::from_str(&private_key_str()).expect("Cannot create private key");
+        assert!(verify(&signed_c509, &ed25519_key.public_key()).is_err());
+    }
+
+    #[test]
+    fn test_generate_with_mismatched_issuer_signature_algorithm() {
+        // `tbs_1` uses the ES256 issuer signature algorithm.
+        let (tbs_cert, _) = tbs_1();
+
+        let ed25519_key: PrivateKey =
+            FromStr::from_str(&private_key_str()).expect("Cannot create private key");
+        assert!(generate(&tbs_cert, Some(&ed25519_key)).is_err());
+
+        let p256_key: PrivateKey =
+            FromStr::from_str(&p256_private_key_str()).expect("Cannot create private key");
+        let tbs_cert = tbs_for_key(&tbs_cert, &ed25519_key);
+        assert!(generate(&tbs_cert, Some(&p256_key)).is_err());
+
+        // Unsigned certificates have no signature to match.
+        assert!(generate(&tbs_cert, None).is_ok());
+    }
 }
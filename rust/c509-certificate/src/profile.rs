@@ -0,0 +1,268 @@
+//! C509 Certificate Profile
+//!
+//! A profile describes the organizational constraints a certificate must satisfy,
+//! e.g. which algorithms are allowed and which extensions are required or forbidden.
+//! The same profile can be used to check a TBS certificate before issuing it and to
+//! validate a received certificate.
+
+use asn1_rs::Oid;
+
+use crate::cert_tbs::TbsCert;
+
+/// A set of constraints a C509 certificate must satisfy.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Profile {
+    /// Name of the profile.
+    name: String,
+    /// Required certificate type.
+    certificate_type: Option<u8>,
+    /// Allowed issuer signature algorithms, any algorithm is allowed if empty.
+    issuer_signature_algorithms: Vec<Oid<'static>>,
+    /// Allowed subject public key algorithms, any algorithm is allowed if empty.
+    subject_public_key_algorithms: Vec<Oid<'static>>,
+    /// Extensions which must be present, with the flag whether they must be critical.
+    required_extensions: Vec<(Oid<'static>, bool)>,
+    /// Extensions which must not be present.
+    forbidden_extensions: Vec<Oid<'static>>,
+    /// Maximum validity period in seconds.
+    max_validity_period: Option<u64>,
+}
+
+impl Profile {
+    /// Create a new `Profile` without any constraints.
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Get the name of the profile.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Require the certificate type.
+    #[must_use]
+    pub fn with_certificate_type(mut self, certificate_type: u8) -> Self {
+        self.certificate_type = Some(certificate_type);
+        self
+    }
+
+    /// Allow the issuer signature algorithm.
+    /// Any algorithm is allowed if none are provided.
+    #[must_use]
+    pub fn with_issuer_signature_algorithm(mut self, oid: Oid<'static>) -> Self {
+        self.issuer_signature_algorithms.push(oid);
+        self
+    }
+
+    /// Allow the subject public key algorithm.
+    /// Any algorithm is allowed if none are provided.
+    #[must_use]
+    pub fn with_subject_public_key_algorithm(mut self, oid: Oid<'static>) -> Self {
+        self.subject_public_key_algorithms.push(oid);
+        self
+    }
+
+    /// Require the extension to be present, and to be marked as critical if `critical`
+    /// is set.
+    #[must_use]
+    pub fn with_required_extension(mut self, oid: Oid<'static>, critical: bool) -> Self {
+        self.required_extensions.push((oid, critical));
+        self
+    }
+
+    /// Forbid the extension to be present.
+    #[must_use]
+    pub fn with_forbidden_extension(mut self, oid: Oid<'static>) -> Self {
+        self.forbidden_extensions.push(oid);
+        self
+    }
+
+    /// Limit the validity period of the certificate, in seconds.
+    /// Certificates without an expiration date exceed any limit.
+    #[must_use]
+    pub fn with_max_validity_period(mut self, seconds: u64) -> Self {
+        self.max_validity_period = Some(seconds);
+        self
+    }
+}
+
+/// A violation of a [`Profile`] constraint.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum ProfileViolation {
+    /// Certificate type differs from the required one.
+    #[error("Invalid certificate type, expected {expected}, found {found}")]
+    CertificateType {
+        /// Required certificate type.
+        expected: u8,
+        /// Certificate type of the certificate.
+        found: u8,
+    },
+    /// Issuer signature algorithm is not allowed.
+    #[error("Issuer signature algorithm {0} is not allowed")]
+    IssuerSignatureAlgorithm(Oid<'static>),
+    /// Subject public key algorithm is not allowed.
+    #[error("Subject public key algorithm {0} is not allowed")]
+    SubjectPublicKeyAlgorithm(Oid<'static>),
+    /// Required extension is missing.
+    #[error("Required extension {0} is missing")]
+    MissingExtension(Oid<'static>),
+    /// Required extension is not marked as critical.
+    #[error("Extension {0} must be critical")]
+    NonCriticalExtension(Oid<'static>),
+    /// Forbidden extension is present.
+    #[error("Extension {0} is forbidden")]
+    ForbiddenExtension(Oid<'static>),
+    /// Validity period ends before it starts.
+    #[error("Validity not after {not_after} is before validity not before {not_before}")]
+    InvalidValidityPeriod {
+        /// Validity not before.
+        not_before: u64,
+        /// Validity not after.
+        not_after: u64,
+    },
+    /// Validity period exceeds the allowed maximum.
+    #[error("Validity period of {found} seconds exceeds the maximum of {max} seconds")]
+    ValidityPeriodTooLong {
+        /// Maximum allowed validity period.
+        max: u64,
+        /// Validity period of the certificate.
+        found: u64,
+    },
+}
+
+/// Check the TBS certificate against the profile.
+///
+/// # Arguments
+/// - `tbs_cert` - A TBS certificate, for a C509 certificate use [`C509::tbs_cert`].
+/// - `profile` - The profile to check against.
+///
+/// # Returns
+/// Returns all violations of the profile, the certificate conforms to the profile if
+/// none are found.
+///
+/// [`C509::tbs_cert`]: crate::c509::C509::tbs_cert
+#[must_use]
+pub fn check_profile(tbs_cert: &TbsCert, profile: &Profile) -> Vec<ProfileViolation> {
+    let mut violations = Vec::new();
+
+    if let Some(expected) = profile.certificate_type {
+        let found = tbs_cert.c509_certificate_type();
+        if found != expected {
+            violations.push(ProfileViolation::CertificateType { expected, found });
+        }
+    }
+
+    let issuer_algo = tbs_cert
+        .issuer_signature_algorithm()
+        .algo_identifier()
+        .oid();
+    if !profile.issuer_signature_algorithms.is_empty()
+        && !profile.issuer_signature_algorithms.contains(issuer_algo)
+    {
+        violations.push(ProfileViolation::IssuerSignatureAlgorithm(
+            issuer_algo.clone(),
+        ));
+    }
+
+    let subject_algo = tbs_cert
+        .subject_public_key_algorithm()
+        .algo_identifier()
+        .oid();
+    if !profile.subject_public_key_algorithms.is_empty()
+        && !profile.subject_public_key_algorithms.contains(subject_algo)
+    {
+        violations.push(ProfileViolation::SubjectPublicKeyAlgorithm(
+            subject_algo.clone(),
+        ));
+    }
+
+    let extensions = tbs_cert.extensions().extensions();
+    let find_extension = |oid: &Oid<'static>| {
+        extensions
+            .iter()
+            .find(|ext| ext.registered_oid().c509_oid().oid() == oid)
+    };
+    for (oid, critical) in &profile.required_extensions {
+        match find_extension(oid) {
+            None => violations.push(ProfileViolation::MissingExtension(oid.clone())),
+            Some(ext) if *critical && !ext.critical() => {
+                violations.push(ProfileViolation::NonCriticalExtension(oid.clone()));
+            },
+            Some(_) => {},
+        }
+    }
+    for oid in &profile.forbidden_extensions {
+        if find_extension(oid).is_some() {
+            violations.push(ProfileViolation::ForbiddenExtension(oid.clone()));
+        }
+    }
+
+    let not_before = tbs_cert.validity_not_before().time();
+    let not_after = tbs_cert.validity_not_after().time();
+    if let Some(period) = not_after.checked_sub(not_before) {
+        if let Some(max) = profile.max_validity_period {
+            if period > max {
+                violations.push(ProfileViolation::ValidityPeriodTooLong { max, found: period });
+            }
+        }
+    } else {
+        violations.push(ProfileViolation::InvalidValidityPeriod {
+            not_before,
+            not_after,
+        });
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod test_profile {
+    use asn1_rs::oid;
+
+    use super::*;
+    use crate::cert_tbs::test_tbs_cert::tbs_1;
+
+    #[test]
+    fn conforming_certificate() {
+        let (tbs_cert, _) = tbs_1();
+        let profile = Profile::new("RFC 7925")
+            .with_certificate_type(3)
+            .with_issuer_signature_algorithm(oid!(1.2.840 .10045 .4 .3 .2))
+            .with_subject_public_key_algorithm(oid!(1.2.840 .10045 .2 .1))
+            .with_required_extension(oid!(2.5.29 .15), false)
+            .with_forbidden_extension(oid!(2.5.29 .17))
+            .with_max_validity_period(5 * 365 * 24 * 60 * 60);
+
+        assert!(check_profile(&tbs_cert, &profile).is_empty());
+    }
+
+    #[test]
+    fn violating_certificate() {
+        let (tbs_cert, _) = tbs_1();
+        let profile = Profile::new("Device")
+            .with_certificate_type(2)
+            .with_issuer_signature_algorithm(oid!(1.3.101 .112))
+            .with_required_extension(oid!(2.5.29 .15), true)
+            .with_required_extension(oid!(2.5.29 .17), false)
+            .with_max_validity_period(365 * 24 * 60 * 60);
+
+        assert_eq!(check_profile(&tbs_cert, &profile), vec![
+            ProfileViolation::CertificateType {
+                expected: 2,
+                found: 3
+            },
+            ProfileViolation::IssuerSignatureAlgorithm(oid!(1.2.840 .10045 .4 .3 .2)),
+            ProfileViolation::NonCriticalExtension(oid!(2.5.29 .15)),
+            ProfileViolation::MissingExtension(oid!(2.5.29 .17)),
+            ProfileViolation::ValidityPeriodTooLong {
+                max: 365 * 24 * 60 * 60,
+                found: 1_767_225_600 - 1_672_531_200
+            },
+        ]);
+    }
+}
@@ -0,0 +1,315 @@
+//! CIP-19 Cardano addresses.
+//!
+//! See: <https://cips.cardano.org/cip/CIP-19> for details on address decoding.
+
+use anyhow::{anyhow, bail, ensure};
+use pallas::ledger::addresses::{Address, ByronAddress};
+
+use crate::hashes::{Blake2b224Hash, BLAKE_2B224_SIZE};
+
+/// Network tag of the mainnet addresses.
+pub const MAINNET_NETWORK_TAG: u8 = 1;
+/// Network tag of the testnet addresses.
+pub const TESTNET_NETWORK_TAG: u8 = 0;
+
+/// Size of the base address, header and two hashes.
+const BASE_ADDRESS_SIZE: usize = 1 + 2 * BLAKE_2B224_SIZE;
+/// Size of the address with a single hash, header and the hash.
+const SINGLE_HASH_ADDRESS_SIZE: usize = 1 + BLAKE_2B224_SIZE;
+
+/// Get the network tag from the header byte of a Shelley address.
+#[must_use]
+pub fn network_tag_from_header(header: u8) -> u8 {
+    header & 0x0F
+}
+
+/// The address type, from the header of the address.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Cip19AddressType {
+    /// Base address, payment key hash and stake key hash.
+    BaseKeyKey,
+    /// Base address, payment script hash and stake key hash.
+    BaseScriptKey,
+    /// Base address, payment key hash and stake script hash.
+    BaseKeyScript,
+    /// Base address, payment script hash and stake script hash.
+    BaseScriptScript,
+    /// Pointer address, payment key hash and a stake pointer.
+    PointerKey,
+    /// Pointer address, payment script hash and a stake pointer.
+    PointerScript,
+    /// Enterprise address, payment key hash only.
+    EnterpriseKey,
+    /// Enterprise address, payment script hash only.
+    EnterpriseScript,
+    /// Byron bootstrap address.
+    Byron,
+    /// Reward (stake) address, stake key hash.
+    RewardKey,
+    /// Reward (stake) address, stake script hash.
+    RewardScript,
+}
+
+impl Cip19AddressType {
+    /// Get the address type from the header byte of the address.
+    /// Returns `None` if the type is invalid.
+    #[must_use]
+    pub fn from_header(header: u8) -> Option<Self> {
+        match header >> 4 {
+            0 => Some(Self::BaseKeyKey),
+            1 => Some(Self::BaseScriptKey),
+            2 => Some(Self::BaseKeyScript),
+            3 => Some(Self::BaseScriptScript),
+            4 => Some(Self::PointerKey),
+            5 => Some(Self::PointerScript),
+            6 => Some(Self::EnterpriseKey),
+            7 => Some(Self::EnterpriseScript),
+            8 => Some(Self::Byron),
+            14 => Some(Self::RewardKey),
+            15 => Some(Self::RewardScript),
+            _ => None,
+        }
+    }
+
+    /// Whether the address has a payment part locked by a key, so it can receive
+    /// payments spendable by a key.
+    #[must_use]
+    pub fn is_payable(self) -> bool {
+        matches!(
+            self,
+            Self::BaseKeyKey | Self::BaseKeyScript | Self::PointerKey | Self::EnterpriseKey
+        )
+    }
+
+    /// Whether the payment part of the address is a script hash.
+    #[must_use]
+    pub fn is_script_payment(self) -> bool {
+        matches!(
+            self,
+            Self::BaseScriptKey
+                | Self::BaseScriptScript
+                | Self::PointerScript
+                | Self::EnterpriseScript
+        )
+    }
+
+    /// Check the length of an address of this type.
+    ///
+    /// # Errors
+    ///
+    /// If the length is invalid for the address type.
+    pub fn validate_length(self, len: usize) -> anyhow::Result<()> {
+        match self {
+            Self::BaseKeyKey
+            | Self::BaseScriptKey
+            | Self::BaseKeyScript
+            | Self::BaseScriptScript => {
+                ensure!(
+                    len == BASE_ADDRESS_SIZE,
+                    "Address Length {len} != {BASE_ADDRESS_SIZE}"
+                );
+            },
+            Self::PointerKey | Self::PointerScript => {
+                ensure!(
+                    len >= SINGLE_HASH_ADDRESS_SIZE,
+                    "Pointer Address Length {len} < {SINGLE_HASH_ADDRESS_SIZE}"
+                );
+            },
+            Self::EnterpriseKey | Self::EnterpriseScript | Self::RewardKey | Self::RewardScript => {
+                ensure!(
+                    len == SINGLE_HASH_ADDRESS_SIZE,
+                    "Address Length {len} != {SINGLE_HASH_ADDRESS_SIZE}"
+                );
+            },
+            // Byron addresses are CBOR encoded, and have no fixed length.
+            Self::Byron => {},
+        }
+        Ok(())
+    }
+}
+
+/// A CIP-19 Cardano address.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Cip19Address {
+    /// The address type.
+    address_type: Cip19AddressType,
+    /// The raw address bytes, including the header.
+    raw: Vec<u8>,
+}
+
+impl Cip19Address {
+    /// Parse a raw address.
+    ///
+    /// # Errors
+    ///
+    /// If the address is empty, has an invalid type or an invalid length for its type.
+    pub fn from_bytes(raw: &[u8]) -> anyhow::Result<Self> {
+        let header = raw.first().ok_or_else(|| anyhow!("Empty address"))?;
+        let address_type = Cip19AddressType::from_header(*header)
+            .ok_or_else(|| anyhow!("Address Type {} is invalid and unsupported", header >> 4))?;
+        address_type.validate_length(raw.len())?;
+
+        Ok(Self {
+            address_type,
+            raw: raw.to_vec(),
+        })
+    }
+
+    /// Parse a bech32 encoded Shelley address, or a base58 encoded Byron address.
+    ///
+    /// # Errors
+    ///
+    /// If the string is not a valid address.
+    pub fn from_bech32(address: &str) -> anyhow::Result<Self> {
+        let address = Address::from_bech32(address)
+            .or_else(|_| ByronAddress::from_base58(address).map(Address::Byron))
+            .map_err(|e| anyhow!("Invalid address {address}: {e}"))?;
+        Self::from_bytes(&address.to_vec())
+    }
+
+    /// Render the address, bech32 encoded for Shelley addresses, or base58 encoded for
+    /// Byron addresses.
+    ///
+    /// # Errors
+    ///
+    /// If the address cannot be encoded.
+    pub fn to_bech32(&self) -> anyhow::Result<String> {
+        let address =
+            Address::from_bytes(&self.raw).map_err(|e| anyhow!("Invalid address: {e}"))?;
+        if let Address::Byron(address) = address {
+            return Ok(address.to_base58());
+        }
+        address
+            .to_bech32()
+            .map_err(|e| anyhow!("Failed to encode the address: {e}"))
+    }
+
+    /// The address type.
+    #[must_use]
+    pub fn address_type(&self) -> Cip19AddressType {
+        self.address_type
+    }
+
+    /// The raw address bytes, including the header.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.raw
+    }
+
+    /// The network tag of the address, `None` for Byron addresses which don't have one.
+    #[must_use]
+    pub fn network_tag(&self) -> Option<u8> {
+        if self.address_type == Cip19AddressType::Byron {
+            return None;
+        }
+        self.raw.first().copied().map(network_tag_from_header)
+    }
+
+    /// Check the address has the expected network tag.
+    ///
+    /// # Errors
+    ///
+    /// If the address is a Byron address, or has a different network tag.
+    pub fn validate_network_tag(&self, expected: u8) -> anyhow::Result<()> {
+        let Some(network_tag) = self.network_tag() else {
+            bail!("Byron Addresses are unsupported");
+        };
+        ensure!(
+            network_tag == expected,
+            "Network Tag {network_tag} does not match the expected Network Tag {expected}"
+        );
+        Ok(())
+    }
+
+    /// The payment key or script hash, `None` for Byron and reward addresses.
+    #[must_use]
+    pub fn payment_hash(&self) -> Option<Blake2b224Hash> {
+        match self.address_type {
+            Cip19AddressType::Byron
+            | Cip19AddressType::RewardKey
+            | Cip19AddressType::RewardScript => None,
+            _ => self.hash_at(1),
+        }
+    }
+
+    /// The stake key or script hash, `None` for addresses without a stake hash.
+    #[must_use]
+    pub fn stake_hash(&self) -> Option<Blake2b224Hash> {
+        match self.address_type {
+            Cip19AddressType::BaseKeyKey
+            | Cip19AddressType::BaseScriptKey
+            | Cip19AddressType::BaseKeyScript
+            | Cip19AddressType::BaseScriptScript => self.hash_at(1 + BLAKE_2B224_SIZE),
+            Cip19AddressType::RewardKey | Cip19AddressType::RewardScript => self.hash_at(1),
+            _ => None,
+        }
+    }
+
+    /// Get the hash starting at `offset` in the raw address.
+    fn hash_at(&self, offset: usize) -> Option<Blake2b224Hash> {
+        let bytes = self.raw.get(offset..offset + BLAKE_2B224_SIZE)?;
+        Blake2b224Hash::try_from(bytes).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cip19_address() {
+        // Test vectors from CIP-19.
+        let base = Cip19Address::from_bech32(
+            "addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgse35a3x",
+        )
+        .unwrap();
+        assert_eq!(base.address_type(), Cip19AddressType::BaseKeyKey);
+        assert_eq!(base.network_tag(), Some(MAINNET_NETWORK_TAG));
+        assert!(base.address_type().is_payable());
+        assert!(base.validate_network_tag(MAINNET_NETWORK_TAG).is_ok());
+        assert!(base.validate_network_tag(TESTNET_NETWORK_TAG).is_err());
+        assert_eq!(
+            base.payment_hash().unwrap().to_string(),
+            "9493315cd92eb5d8c4304e67b7e16ae36d61d34502694657811a2c8e"
+        );
+        assert_eq!(
+            base.stake_hash().unwrap().to_string(),
+            "337b62cfff6403a06a3acbc34f8c46003c69fe79a3628cefa9c47251"
+        );
+
+        let stake = Cip19Address::from_bech32(
+            "stake1uyehkck0lajq8gr28t9uxnuvgcqrc6070x3k9r8048z8y5gh6ffgw",
+        )
+        .unwrap();
+        assert_eq!(stake.address_type(), Cip19AddressType::RewardKey);
+        assert!(!stake.address_type().is_payable());
+        assert_eq!(stake.payment_hash(), None);
+        assert_eq!(stake.stake_hash(), base.stake_hash());
+        assert_eq!(
+            stake.to_bech32().unwrap(),
+            "stake1uyehkck0lajq8gr28t9uxnuvgcqrc6070x3k9r8048z8y5gh6ffgw"
+        );
+
+        let script = Cip19Address::from_bech32(
+            "addr_test1wrphkx6acpnf78fuvxn0mkew3l0fd058hzquvz7w36x4gtcl6szpr",
+        )
+        .unwrap();
+        assert_eq!(script.address_type(), Cip19AddressType::EnterpriseScript);
+        assert_eq!(script.network_tag(), Some(TESTNET_NETWORK_TAG));
+        assert!(script.address_type().is_script_payment());
+        assert!(!script.address_type().is_payable());
+        assert_eq!(script.stake_hash(), None);
+    }
+
+    #[test]
+    fn cip19_address_invalid() {
+        assert!(Cip19Address::from_bytes(&[]).is_err());
+        // Invalid address type.
+        assert!(Cip19Address::from_bytes(&[0x90; 29]).is_err());
+        // Invalid base address length.
+        assert!(Cip19Address::from_bytes(&[0x01; 29]).is_err());
+        // Valid enterprise address.
+        assert!(Cip19Address::from_bytes(&[0x61; 29]).is_ok());
+        assert!(Cip19Address::from_bech32("not an address").is_err());
+    }
+}
@@ -0,0 +1,153 @@
+//! Block Epoch and the epoch schedule of a network.
+
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, Utc};
+use pallas::ledger::traverse::wellknown::GenesisValues;
+
+use crate::{conversion::from_saturating, Slot};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// Epoch on the blockchain, a fixed number of slots, which depends on the era.
+pub struct Epoch(u64);
+
+impl From<u64> for Epoch {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Epoch> for u64 {
+    fn from(val: Epoch) -> Self {
+        val.0
+    }
+}
+
+impl Display for Epoch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Schedule of the slots and epochs of a network, across the Byron to Shelley
+/// transition.
+///
+/// The Byron era starts at slot `0` of epoch `0`. Every era after Shelley uses the
+/// Shelley slot and epoch lengths.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct EpochSchedule {
+    /// Unix time of the first Byron slot, in seconds.
+    pub byron_start_time: i64,
+    /// Length of a Byron slot, in seconds.
+    pub byron_slot_length: u64,
+    /// Number of slots of a Byron epoch.
+    pub byron_epoch_slots: u64,
+    /// First Shelley slot.
+    pub shelley_start_slot: u64,
+    /// Unix time of the first Shelley slot, in seconds.
+    pub shelley_start_time: i64,
+    /// Length of a Shelley slot, in seconds.
+    pub shelley_slot_length: u64,
+    /// Number of slots of a Shelley epoch.
+    pub shelley_epoch_slots: u64,
+}
+
+impl From<&GenesisValues> for EpochSchedule {
+    fn from(genesis: &GenesisValues) -> Self {
+        // Genesis epoch lengths are in seconds, not slots.
+        let byron_slot_length = u64::from(genesis.byron_slot_length).max(1);
+        let shelley_slot_length = u64::from(genesis.shelley_slot_length).max(1);
+        Self {
+            byron_start_time: from_saturating(genesis.byron_known_time),
+            byron_slot_length,
+            byron_epoch_slots: (u64::from(genesis.byron_epoch_length) / byron_slot_length).max(1),
+            shelley_start_slot: genesis.shelley_known_slot,
+            shelley_start_time: from_saturating(genesis.shelley_known_time),
+            shelley_slot_length,
+            shelley_epoch_slots: (u64::from(genesis.shelley_epoch_length) / shelley_slot_length)
+                .max(1),
+        }
+    }
+}
+
+impl EpochSchedule {
+    /// First Shelley epoch.
+    #[must_use]
+    pub fn shelley_start_epoch(&self) -> Epoch {
+        Epoch(self.shelley_start_slot / self.byron_epoch_slots)
+    }
+
+    /// Convert a slot to its Wall Time, saturating if out of range.
+    #[must_use]
+    pub fn slot_to_time(&self, slot: Slot) -> DateTime<Utc> {
+        let slot = u64::from(slot);
+        let (start_time, elapsed_slots, slot_length) = if slot < self.shelley_start_slot {
+            (self.byron_start_time, slot, self.byron_slot_length)
+        } else {
+            (
+                self.shelley_start_time,
+                slot - self.shelley_start_slot,
+                self.shelley_slot_length,
+            )
+        };
+        let elapsed: i64 = from_saturating(elapsed_slots.saturating_mul(slot_length));
+        DateTime::from_timestamp(start_time.saturating_add(elapsed), 0)
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+
+    /// Convert an arbitrary time to a slot, `None` if the time predates the blockchain.
+    #[must_use]
+    pub fn time_to_slot(&self, time: DateTime<Utc>) -> Option<Slot> {
+        let time = time.timestamp();
+        if time < self.byron_start_time {
+            return None;
+        }
+
+        let slot = if time < self.shelley_start_time {
+            // Byron era
+            from_saturating::<u64, i64>(time - self.byron_start_time) / self.byron_slot_length
+        } else {
+            // Shelley era
+            let elapsed: u64 = from_saturating(time - self.shelley_start_time);
+            self.shelley_start_slot
+                .saturating_add(elapsed / self.shelley_slot_length)
+        };
+        Some(slot.into())
+    }
+
+    /// Convert a slot to its epoch and the slot number within that epoch.
+    #[must_use]
+    pub fn slot_to_epoch(&self, slot: Slot) -> (Epoch, u64) {
+        let slot = u64::from(slot);
+        if slot < self.shelley_start_slot {
+            (
+                Epoch(slot / self.byron_epoch_slots),
+                slot % self.byron_epoch_slots,
+            )
+        } else {
+            let era_slot = slot - self.shelley_start_slot;
+            (
+                Epoch(
+                    self.shelley_start_epoch()
+                        .0
+                        .saturating_add(era_slot / self.shelley_epoch_slots),
+                ),
+                era_slot % self.shelley_epoch_slots,
+            )
+        }
+    }
+
+    /// First slot of the epoch, saturating if out of range.
+    #[must_use]
+    pub fn epoch_first_slot(&self, epoch: Epoch) -> Slot {
+        let shelley_start_epoch = self.shelley_start_epoch();
+        let slot = if epoch < shelley_start_epoch {
+            epoch.0 * self.byron_epoch_slots
+        } else {
+            (epoch.0 - shelley_start_epoch.0)
+                .saturating_mul(self.shelley_epoch_slots)
+                .saturating_add(self.shelley_start_slot)
+        };
+        slot.into()
+    }
+}
@@ -2,7 +2,8 @@
 
 use std::{fmt, str::FromStr};
 
-use anyhow::bail;
+use anyhow::{bail, Context};
+use bech32::{FromBase32, ToBase32};
 use blake2b_simd::Params;
 use pallas_crypto::hash::Hash;
 
@@ -48,6 +49,39 @@ impl<const BYTES: usize> Blake2bHash<BYTES> {
 
         bytes.into()
     }
+
+    /// Encode the hash as a bech32 string with the given human readable part, e.g.
+    /// `addr_vkh` for the verification key hash.
+    ///
+    /// # Errors
+    ///
+    /// If the human readable part is invalid.
+    pub fn to_bech32(&self, hrp: &str) -> anyhow::Result<String> {
+        bech32::encode(hrp, self.0.to_base32(), bech32::Variant::Bech32)
+            .context("Failed to encode hash as bech32")
+    }
+
+    /// Decode the hash from a bech32 string, which must have the given human readable
+    /// part.
+    ///
+    /// # Errors
+    ///
+    /// If the string is not a valid bech32 string, has another human readable part or
+    /// has an invalid hash length.
+    pub fn from_bech32(s: &str, hrp: &str) -> anyhow::Result<Self> {
+        let (actual_hrp, data, _) = bech32::decode(s).context("Invalid bech32 string")?;
+        if actual_hrp != hrp {
+            bail!("Invalid bech32 human readable part, expected: {hrp}, provided: {actual_hrp}");
+        }
+        let bytes = Vec::<u8>::from_base32(&data).context("Invalid bech32 data")?;
+        if bytes.len() != BYTES {
+            bail!(
+                "Invalid hash length, expected: {BYTES}, provided: {}",
+                bytes.len()
+            );
+        }
+        bytes.try_into()
+    }
 }
 
 impl<const BYTES: usize> From<[u8; BYTES]> for Blake2bHash<BYTES> {
@@ -115,15 +149,31 @@ impl<const BYTES: usize> fmt::Display for Blake2bHash<BYTES> {
     }
 }
 
+/// Parse the hex encoded hash, with an optional `0x` prefix.
 impl<const BYTES: usize> FromStr for Blake2bHash<BYTES> {
     type Err = hex::FromHexError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let hash: Hash<BYTES> = s.parse()?;
+        let hash: Hash<BYTES> = s.strip_prefix("0x").unwrap_or(s).parse()?;
         Ok(hash.into())
     }
 }
 
+impl<const BYTES: usize> serde::Serialize for Blake2bHash<BYTES> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: serde::Serializer {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, const BYTES: usize> serde::Deserialize<'de> for Blake2bHash<BYTES> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: serde::Deserializer<'de> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 impl<C, const BYTES: usize> minicbor::Encode<C> for Blake2bHash<BYTES> {
     fn encode<W: minicbor::encode::Write>(
         &self, e: &mut minicbor::Encoder<W>, _ctx: &mut C,
@@ -142,3 +192,36 @@ impl<'a, C, const BYTES: usize> minicbor::Decode<'a, C> for Blake2bHash<BYTES> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hex encoded test hash.
+    const HASH_HEX: &str = "276fd18711931e2c0e21430192dbeac0e458093cd9d1fcd7210f64b3";
+
+    #[test]
+    fn string_representation() {
+        let hash: Blake2b224Hash = HASH_HEX.parse().unwrap();
+        assert_eq!(hash.to_string(), HASH_HEX);
+        assert_eq!(
+            format!("0x{HASH_HEX}").parse::<Blake2b224Hash>().unwrap(),
+            hash
+        );
+        assert!(HASH_HEX.parse::<Blake2b256Hash>().is_err());
+        assert!("0xzz".parse::<Blake2b224Hash>().is_err());
+
+        let bech32 = hash.to_bech32("addr_vkh").unwrap();
+        assert!(bech32.starts_with("addr_vkh1"));
+        assert_eq!(
+            Blake2b224Hash::from_bech32(&bech32, "addr_vkh").unwrap(),
+            hash
+        );
+        assert!(Blake2b224Hash::from_bech32(&bech32, "stake_vkh").is_err());
+        assert!(Blake2b256Hash::from_bech32(&bech32, "addr_vkh").is_err());
+
+        let json = serde_json::to_string(&hash).unwrap();
+        assert_eq!(json, format!("\"{HASH_HEX}\""));
+        assert_eq!(serde_json::from_str::<Blake2b224Hash>(&json).unwrap(), hash);
+    }
+}
@@ -1,14 +1,19 @@
 //! Catalyst Enhanced `MultiEraBlock` Structures
 
 mod auxdata;
+pub mod cip19;
 pub mod conversion;
+mod epoch;
 mod fork;
 pub mod hashes;
 mod multi_era_block_data;
 mod network;
 mod point;
+pub mod protocol_params;
 mod slot;
+mod txn_balance;
 mod txn_index;
+mod txn_output;
 mod txn_witness;
 
 pub use auxdata::{
@@ -19,10 +24,16 @@ pub use auxdata::{
     metadatum_value::MetadatumValue,
     scripts::{Script, ScriptArray, ScriptType, TransactionScripts},
 };
+pub use epoch::{Epoch, EpochSchedule};
 pub use fork::Fork;
 pub use multi_era_block_data::MultiEraBlock;
 pub use network::Network;
 pub use point::Point;
+pub use protocol_params::{ProtocolParamProposal, ProtocolParamUpdate};
 pub use slot::Slot;
+pub use txn_balance::{
+    AssetId, BalanceDiscrepancy, DepositParams, TxnBalance, UtxoResolver, Value,
+};
 pub use txn_index::TxnIndex;
-pub use txn_witness::{TxnWitness, VKeyHash};
+pub use txn_output::{AddressKind, NativeAsset, TxnOutput, TxnOutputOffset};
+pub use txn_witness::{TxnWitness, VKeyHash, VKEY_HASH_BECH32_HRP};
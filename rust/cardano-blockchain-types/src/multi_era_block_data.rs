@@ -9,7 +9,7 @@
 
 use std::{cmp::Ordering, fmt::Display, sync::Arc};
 
-use anyhow::bail;
+use anyhow::{anyhow, bail};
 use ed25519_dalek::VerifyingKey;
 use ouroboros::self_referencing;
 use tracing::debug;
@@ -21,7 +21,10 @@ use crate::{
     fork::Fork,
     network::Network,
     point::Point,
+    protocol_params::ProtocolParamProposal,
+    txn_balance::{DepositParams, TxnBalance, UtxoResolver},
     txn_index::TxnIndex,
+    txn_output::{TxnOutput, TxnOutputOffset},
     txn_witness::{TxnWitness, VKeyHash},
 };
 
@@ -265,6 +268,39 @@ impl MultiEraBlock {
         txn.metadata(label)
     }
 
+    /// Get the protocol parameter update proposals made in the block.
+    ///
+    /// # Returns
+    ///
+    /// - Block-level proposal for Byron blocks, if any.
+    /// - Proposals carried by the transactions of the block, for Shelley to Babbage
+    ///   blocks.
+    /// - Nothing for Conway blocks, where parameters are changed through governance.
+    ///
+    /// # Errors
+    ///
+    /// If a proposal can not be decoded.
+    pub fn protocol_param_updates(&self) -> anyhow::Result<Vec<ProtocolParamProposal>> {
+        let block = self.decode();
+        let mut proposals = Vec::new();
+
+        if let Some(update) = block.update() {
+            proposals.push(ProtocolParamProposal::new(block.era(), None, &update)?);
+        }
+
+        for (txn_idx, txn) in block.txs().iter().enumerate() {
+            if let Some(update) = txn.update() {
+                proposals.push(ProtocolParamProposal::new(
+                    txn.era(),
+                    Some(TxnIndex::from_saturating(txn_idx)),
+                    &update,
+                )?);
+            }
+        }
+
+        Ok(proposals)
+    }
+
     /// Returns the witness map for the block.
     pub(crate) fn witness_map(&self) -> Option<&TxnWitness> {
         self.inner.witness_map.as_ref()
@@ -281,6 +317,58 @@ impl MultiEraBlock {
 
         None
     }
+
+    /// Get the typed views of all outputs of the transaction at `txn_idx`.
+    ///
+    /// # Errors
+    ///
+    /// If the transaction does not exist in the block, or any of its outputs cannot be
+    /// decoded.
+    pub fn txn_outputs(&self, txn_idx: TxnIndex) -> anyhow::Result<Vec<TxnOutput>> {
+        let txs = self.decode().txs();
+        let tx = txs
+            .get(usize::from(txn_idx))
+            .ok_or_else(|| anyhow!("Transaction {txn_idx:?} not found in the block"))?;
+        tx.outputs()
+            .iter()
+            .enumerate()
+            .map(|(i, output)| TxnOutput::new(TxnOutputOffset::from_saturating(i), output))
+            .collect()
+    }
+
+    /// Get the typed view of the output at `offset` of the transaction at `txn_idx`.
+    ///
+    /// # Errors
+    ///
+    /// If the transaction or the output does not exist, or the output cannot be decoded.
+    pub fn txn_output(
+        &self, txn_idx: TxnIndex, offset: TxnOutputOffset,
+    ) -> anyhow::Result<TxnOutput> {
+        let txs = self.decode().txs();
+        let output = txs
+            .get(usize::from(txn_idx))
+            .ok_or_else(|| anyhow!("Transaction {txn_idx:?} not found in the block"))?
+            .output_at(usize::from(offset))
+            .ok_or_else(|| anyhow!("Output {offset:?} not found in transaction {txn_idx:?}"))?;
+        TxnOutput::new(offset, &output)
+    }
+
+    /// Calculate the fee, deposits and value balance of the transaction at `txn_idx`,
+    /// resolving its spent outputs with the `resolver`.
+    ///
+    /// # Errors
+    ///
+    /// If the transaction does not exist in the block, or any of its produced or
+    /// resolved outputs cannot be decoded.
+    pub fn txn_balance(
+        &self, txn_idx: TxnIndex, resolver: &impl UtxoResolver, params: &DepositParams,
+    ) -> anyhow::Result<TxnBalance> {
+        let txs = self.decode().txs();
+        let tx = txs
+            .get(usize::from(txn_idx))
+            .ok_or_else(|| anyhow!("Transaction {txn_idx:?} not found in the block"))?;
+        TxnBalance::new(tx, resolver, params)
+    }
 }
 
 impl Display for MultiEraBlock {
@@ -11,7 +11,7 @@ use pallas::{
 // use strum_macros;
 use tracing::debug;
 
-use crate::{conversion::from_saturating, Slot};
+use crate::{Epoch, EpochSchedule, Slot};
 
 /// Default name of the executable if we can't derive it.
 pub(crate) const DEFAULT_EXE_NAME: &str = "cardano_chain_follower";
@@ -156,14 +156,16 @@ impl Network {
         }
     }
 
+    /// Return the slot and epoch schedule for given network
+    #[must_use]
+    pub fn epoch_schedule(self) -> EpochSchedule {
+        EpochSchedule::from(&self.genesis_values())
+    }
+
     /// Convert a given slot# to its Wall Time for a Blockchain network.
     #[must_use]
     pub fn slot_to_time(self, slot: Slot) -> DateTime<Utc> {
-        let genesis = self.genesis_values();
-        let wall_clock = genesis.slot_to_wallclock(slot.into());
-
-        let raw_time: i64 = wall_clock.try_into().unwrap_or(i64::MAX);
-        DateTime::from_timestamp(raw_time, 0).unwrap_or(DateTime::<Utc>::MAX_UTC)
+        self.epoch_schedule().slot_to_time(slot)
     }
 
     /// Convert an arbitrary time to a slot.
@@ -173,37 +175,21 @@ impl Network {
     /// The Slot does not have to be a valid slot present in the blockchain.
     #[must_use]
     pub fn time_to_slot(self, time: DateTime<Utc>) -> Option<Slot> {
-        let genesis = self.genesis_values();
-
-        let byron_start_time =
-            DateTime::<Utc>::from_timestamp(from_saturating(genesis.byron_known_time), 0)?;
-        let shelley_start_time =
-            DateTime::<Utc>::from_timestamp(from_saturating(genesis.shelley_known_time), 0)?;
-
-        // determine if the given time is in Byron or Shelley era.
-        if time < byron_start_time {
-            return None;
-        }
-
-        let (elapsed_slots, era_known_slot) = if time < shelley_start_time {
-            // Byron era
-            let time_diff = time - byron_start_time;
-            (
-                time_diff.num_seconds() / i64::from(genesis.byron_slot_length),
-                genesis.byron_known_slot,
-            )
-        } else {
-            // Shelley era
-            let time_diff = time - shelley_start_time;
-            (
-                time_diff.num_seconds() / i64::from(genesis.shelley_slot_length),
-                genesis.shelley_known_slot,
-            )
-        };
+        self.epoch_schedule().time_to_slot(time)
+    }
 
-        let era_known_slot = i64::try_from(era_known_slot).ok()?;
+    /// The slot at the given time, same as `time_to_slot`.
+    ///
+    /// If the given time predates the blockchain, will return None.
+    #[must_use]
+    pub fn slot_at(self, time: DateTime<Utc>) -> Option<Slot> {
+        self.time_to_slot(time)
+    }
 
-        Some(Slot::from_saturating(elapsed_slots + era_known_slot))
+    /// The first slot of the given epoch.
+    #[must_use]
+    pub fn epoch_first_slot(self, epoch: Epoch) -> Slot {
+        self.epoch_schedule().epoch_first_slot(epoch)
     }
 }
 
@@ -225,6 +211,7 @@ mod tests {
     use chrono::{TimeZone, Utc};
 
     use super::*;
+    use crate::{conversion::from_saturating, Point};
 
     #[test]
     fn test_from_str() -> anyhow::Result<()> {
@@ -401,4 +388,84 @@ mod tests {
             "Inconsistency for Conway era time"
         );
     }
+
+    #[test]
+    fn test_mainnet_epoch_boundaries() {
+        let network = Network::Mainnet;
+
+        // Byron start.
+        let slot = Slot::from(0);
+        assert_eq!(slot.to_time(network).timestamp(), 1_506_203_091);
+        assert_eq!(slot.epoch(network), (Epoch::from(0), 0));
+
+        // Byron middle.
+        let slot = Slot::from(2_160_007);
+        assert_eq!(slot.to_time(network).timestamp(), 1_549_403_231);
+        assert_eq!(slot.epoch(network), (Epoch::from(100), 7));
+
+        // Shelley start.
+        let time = DateTime::from_timestamp(1_596_059_091, 0).unwrap();
+        assert_eq!(network.slot_at(time), Some(Slot::from(4_492_800)));
+        let point = Point::fuzzy(4_492_800.into());
+        assert_eq!(point.epoch(network), Some((Epoch::from(208), 0)));
+        assert_eq!(
+            network.epoch_first_slot(Epoch::from(208)),
+            Slot::from(4_492_800)
+        );
+
+        // Last Byron slot.
+        assert_eq!(
+            Slot::from(4_492_799).epoch(network),
+            (Epoch::from(207), 21_599)
+        );
+
+        // Shelley middle.
+        let slot = Slot::from(51_580_240);
+        assert_eq!(slot.to_time(network).timestamp(), 1_643_146_531);
+        assert_eq!(slot.epoch(network), (Epoch::from(316), 431_440));
+        assert_eq!(
+            network.epoch_first_slot(Epoch::from(317)),
+            Slot::from(51_580_800)
+        );
+
+        assert_eq!(Point::TIP.epoch(network), None);
+        assert_eq!(Point::ORIGIN.epoch(network), Some((Epoch::from(0), 0)));
+    }
+
+    #[test]
+    fn test_preprod_epoch_boundaries() {
+        let network = Network::Preprod;
+
+        // Second Byron epoch.
+        let slot = Slot::from(21_600);
+        assert_eq!(slot.to_time(network).timestamp(), 1_654_473_600);
+        assert_eq!(slot.epoch(network), (Epoch::from(1), 0));
+        assert_eq!(network.epoch_first_slot(Epoch::from(1)), slot);
+
+        // Shelley start.
+        let slot = Slot::from(86_400);
+        assert_eq!(slot.to_time(network).timestamp(), 1_655_769_600);
+        assert_eq!(slot.epoch(network), (Epoch::from(4), 0));
+        let time = DateTime::from_timestamp(1_655_769_600, 0).unwrap();
+        assert_eq!(network.slot_at(time), Some(slot));
+
+        // Shelley middle.
+        let slot = Slot::from(38_580_791);
+        assert_eq!(slot.to_time(network).timestamp(), 1_694_263_991);
+        assert_eq!(slot.epoch(network), (Epoch::from(93), 46_391));
+    }
+
+    #[test]
+    fn test_preview_epoch_boundaries() {
+        let network = Network::Preview;
+
+        // No Byron era.
+        let slot = Slot::from(27_556_036);
+        assert_eq!(slot.to_time(network).timestamp(), 1_694_212_036);
+        assert_eq!(slot.epoch(network), (Epoch::from(318), 80_836));
+        assert_eq!(
+            network.epoch_first_slot(Epoch::from(318)),
+            Slot::from(27_475_200)
+        );
+    }
 }
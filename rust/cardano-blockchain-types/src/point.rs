@@ -10,7 +10,7 @@ use std::{
 
 use pallas::crypto::hash::Hash;
 
-use crate::{hashes::Blake2b256Hash, Slot};
+use crate::{hashes::Blake2b256Hash, Epoch, Network, Slot};
 
 /// A specific point in the blockchain. It can be used to
 /// identify a particular location within the blockchain, such as the tip (the
@@ -301,6 +301,26 @@ impl Point {
         }
     }
 
+    /// Retrieves the epoch of the `Point` on the given network, and the slot number
+    /// within that epoch. If the `Point` is the tip, it returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cardano_blockchain_types::{Network, Point};
+    ///
+    /// let point = Point::fuzzy(4_492_800.into());
+    /// assert_eq!(point.epoch(Network::Mainnet), Some((208.into(), 0)));
+    /// assert_eq!(Point::TIP.epoch(Network::Mainnet), None);
+    /// ```
+    #[must_use]
+    pub fn epoch(&self, network: Network) -> Option<(Epoch, u64)> {
+        if self.is_tip() {
+            return None;
+        }
+        Some(self.slot_or_default().epoch(network))
+    }
+
     /// Checks if two `Point` instances are strictly equal.
     /// Strict equality means both the slot number and hash must be identical.
     ///
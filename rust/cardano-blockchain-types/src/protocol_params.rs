@@ -0,0 +1,428 @@
+//! Protocol Parameter Update Proposals
+//!
+//! Decoded protocol parameter updates proposed within a block, tagged by the era they
+//! were proposed in.
+//!
+//! See: <https://github.com/IntersectMBO/cardano-ledger/blob/78b32d585fd4a0340fb2b184959fb0d46f32c8d2/eras/babbage/impl/cddl-files/babbage.cddl#L292>
+//!
+//! Note: Starting from the Conway era protocol parameters are changed through governance
+//! actions instead of update proposals, so Conway blocks never contain any.
+
+use anyhow::bail;
+use pallas::ledger::{
+    primitives::{alonzo, babbage},
+    traverse::{Era, MultiEraUpdate},
+};
+
+use crate::{
+    hashes::{Blake2b224Hash, BLAKE_2B224_SIZE},
+    TxnIndex,
+};
+
+/// A rational number, as used by the protocol parameters.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    /// Numerator of the rational number.
+    pub numerator: u64,
+    /// Denominator of the rational number.
+    pub denominator: u64,
+}
+
+impl From<&alonzo::RationalNumber> for Rational {
+    fn from(value: &alonzo::RationalNumber) -> Self {
+        Self {
+            numerator: value.numerator,
+            denominator: value.denominator,
+        }
+    }
+}
+
+/// Plutus script execution units.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ExUnits {
+    /// Memory units.
+    pub mem: u64,
+    /// CPU step units.
+    pub steps: u64,
+}
+
+impl From<&alonzo::ExUnits> for ExUnits {
+    fn from(value: &alonzo::ExUnits) -> Self {
+        Self {
+            mem: value.mem,
+            steps: value.steps,
+        }
+    }
+}
+
+/// Prices of the Plutus script execution units.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ExUnitPrices {
+    /// Price of a single memory unit.
+    pub mem_price: Rational,
+    /// Price of a single CPU step unit.
+    pub step_price: Rational,
+}
+
+impl From<&alonzo::ExUnitPrices> for ExUnitPrices {
+    fn from(value: &alonzo::ExUnitPrices) -> Self {
+        Self {
+            mem_price: (&value.mem_price).into(),
+            step_price: (&value.step_price).into(),
+        }
+    }
+}
+
+/// Plutus script cost models, per Plutus language version.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CostModels {
+    /// Plutus V1 cost model.
+    pub plutus_v1: Option<Vec<i64>>,
+    /// Plutus V2 cost model.
+    pub plutus_v2: Option<Vec<i64>>,
+}
+
+impl From<&alonzo::CostMdls> for CostModels {
+    fn from(value: &alonzo::CostMdls) -> Self {
+        // The only language defined for Alonzo is Plutus V1.
+        let plutus_v1 = value.iter().find_map(|(language, model)| {
+            matches!(language, alonzo::Language::PlutusV1).then(|| model.clone())
+        });
+        Self {
+            plutus_v1,
+            plutus_v2: None,
+        }
+    }
+}
+
+impl From<&babbage::CostMdls> for CostModels {
+    fn from(value: &babbage::CostMdls) -> Self {
+        Self {
+            plutus_v1: value.plutus_v1.clone(),
+            plutus_v2: value.plutus_v2.clone(),
+        }
+    }
+}
+
+/// Protocol parameter values proposed by a single update.
+///
+/// Only the parameters being changed are set, every `None` parameter stays unchanged.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProtocolParamUpdate {
+    /// Linear fee factor (fee per byte), `a` in `fee = a * size + b`.
+    pub min_fee_a: Option<u64>,
+    /// Constant fee, `b` in `fee = a * size + b`.
+    pub min_fee_b: Option<u64>,
+    /// Maximum block body size.
+    pub max_block_body_size: Option<u64>,
+    /// Maximum transaction size.
+    pub max_transaction_size: Option<u64>,
+    /// Maximum block header size.
+    pub max_block_header_size: Option<u64>,
+    /// Stake address registration deposit.
+    pub key_deposit: Option<u64>,
+    /// Stake pool registration deposit.
+    pub pool_deposit: Option<u64>,
+    /// Maximum number of epochs a pool retirement can be scheduled in advance.
+    pub maximum_epoch: Option<u64>,
+    /// Desired number of stake pools.
+    pub desired_number_of_stake_pools: Option<u64>,
+    /// Pool pledge influence.
+    pub pool_pledge_influence: Option<Rational>,
+    /// Monetary expansion rate.
+    pub expansion_rate: Option<Rational>,
+    /// Treasury growth rate.
+    pub treasury_growth_rate: Option<Rational>,
+    /// Decentralization parameter, only exists before the Babbage era.
+    pub decentralization_constant: Option<Rational>,
+    /// Protocol version as a `(major, minor)` pair.
+    pub protocol_version: Option<(u64, u64)>,
+    /// Minimum stake pool cost.
+    pub min_pool_cost: Option<u64>,
+    /// Cost per UTXO word (Alonzo) or per UTXO byte (Babbage).
+    pub ada_per_utxo_byte: Option<u64>,
+    /// Plutus script cost models.
+    pub cost_models: Option<CostModels>,
+    /// Prices of the Plutus script execution units.
+    pub execution_costs: Option<ExUnitPrices>,
+    /// Maximum execution units of a single transaction.
+    pub max_tx_ex_units: Option<ExUnits>,
+    /// Maximum execution units of a single block.
+    pub max_block_ex_units: Option<ExUnits>,
+    /// Maximum size of a serialized value.
+    pub max_value_size: Option<u64>,
+    /// Percentage of the transaction fee which must be provided as collateral.
+    pub collateral_percentage: Option<u64>,
+    /// Maximum number of collateral inputs.
+    pub max_collateral_inputs: Option<u64>,
+}
+
+impl From<&alonzo::ProtocolParamUpdate> for ProtocolParamUpdate {
+    fn from(value: &alonzo::ProtocolParamUpdate) -> Self {
+        Self {
+            min_fee_a: value.minfee_a.map(u64::from),
+            min_fee_b: value.minfee_b.map(u64::from),
+            max_block_body_size: value.max_block_body_size.map(u64::from),
+            max_transaction_size: value.max_transaction_size.map(u64::from),
+            max_block_header_size: value.max_block_header_size.map(u64::from),
+            key_deposit: value.key_deposit,
+            pool_deposit: value.pool_deposit,
+            maximum_epoch: value.maximum_epoch,
+            desired_number_of_stake_pools: value.desired_number_of_stake_pools.map(u64::from),
+            pool_pledge_influence: value.pool_pledge_influence.as_ref().map(Into::into),
+            expansion_rate: value.expansion_rate.as_ref().map(Into::into),
+            treasury_growth_rate: value.treasury_growth_rate.as_ref().map(Into::into),
+            decentralization_constant: value.decentralization_constant.as_ref().map(Into::into),
+            protocol_version: value.protocol_version,
+            min_pool_cost: value.min_pool_cost,
+            ada_per_utxo_byte: value.ada_per_utxo_byte,
+            cost_models: value
+                .cost_models_for_script_languages
+                .as_ref()
+                .map(Into::into),
+            execution_costs: value.execution_costs.as_ref().map(Into::into),
+            max_tx_ex_units: value.max_tx_ex_units.as_ref().map(Into::into),
+            max_block_ex_units: value.max_block_ex_units.as_ref().map(Into::into),
+            max_value_size: value.max_value_size.map(u64::from),
+            collateral_percentage: value.collateral_percentage.map(u64::from),
+            max_collateral_inputs: value.max_collateral_inputs.map(u64::from),
+        }
+    }
+}
+
+impl From<&babbage::ProtocolParamUpdate> for ProtocolParamUpdate {
+    fn from(value: &babbage::ProtocolParamUpdate) -> Self {
+        Self {
+            min_fee_a: value.minfee_a.map(u64::from),
+            min_fee_b: value.minfee_b.map(u64::from),
+            max_block_body_size: value.max_block_body_size.map(u64::from),
+            max_transaction_size: value.max_transaction_size.map(u64::from),
+            max_block_header_size: value.max_block_header_size.map(u64::from),
+            key_deposit: value.key_deposit,
+            pool_deposit: value.pool_deposit,
+            maximum_epoch: value.maximum_epoch,
+            desired_number_of_stake_pools: value.desired_number_of_stake_pools.map(u64::from),
+            pool_pledge_influence: value.pool_pledge_influence.as_ref().map(Into::into),
+            expansion_rate: value.expansion_rate.as_ref().map(Into::into),
+            treasury_growth_rate: value.treasury_growth_rate.as_ref().map(Into::into),
+            decentralization_constant: None,
+            protocol_version: value.protocol_version,
+            min_pool_cost: value.min_pool_cost,
+            ada_per_utxo_byte: value.ada_per_utxo_byte,
+            cost_models: value
+                .cost_models_for_script_languages
+                .as_ref()
+                .map(Into::into),
+            execution_costs: value.execution_costs.as_ref().map(Into::into),
+            max_tx_ex_units: value.max_tx_ex_units.as_ref().map(Into::into),
+            max_block_ex_units: value.max_block_ex_units.as_ref().map(Into::into),
+            max_value_size: value.max_value_size.map(u64::from),
+            collateral_percentage: value.collateral_percentage.map(u64::from),
+            max_collateral_inputs: value.max_collateral_inputs.map(u64::from),
+        }
+    }
+}
+
+/// A protocol parameter update proposal found in a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtocolParamProposal {
+    /// Era the proposal was made in.
+    era: Era,
+    /// Index of the transaction carrying the proposal.
+    /// Byron proposals are block-level, so they don't have one.
+    txn_idx: Option<TxnIndex>,
+    /// Epoch the proposal is targeting.
+    epoch: u64,
+    /// Proposed updates, keyed by the hash of the proposing genesis delegate key.
+    /// Byron proposals are not keyed by a genesis key hash.
+    updates: Vec<(Option<Blake2b224Hash>, ProtocolParamUpdate)>,
+}
+
+impl ProtocolParamProposal {
+    /// Create a new `ProtocolParamProposal` from a decoded pallas update.
+    ///
+    /// # Errors
+    ///
+    /// If any proposing genesis key hash has an invalid size.
+    pub(crate) fn new(
+        era: Era, txn_idx: Option<TxnIndex>, update: &MultiEraUpdate,
+    ) -> anyhow::Result<Self> {
+        let updates = if let Some(update) = update.as_alonzo() {
+            update
+                .proposed_protocol_parameter_updates
+                .iter()
+                .map(|(hash, params)| Ok((Some(genesis_key_hash(hash)?), params.into())))
+                .collect::<anyhow::Result<_>>()?
+        } else if let Some(update) = update.as_babbage() {
+            update
+                .proposed_protocol_parameter_updates
+                .iter()
+                .map(|(hash, params)| Ok((Some(genesis_key_hash(hash)?), params.into())))
+                .collect::<anyhow::Result<_>>()?
+        } else {
+            let params = ProtocolParamUpdate {
+                max_transaction_size: update.byron_proposed_max_tx_size(),
+                protocol_version: update
+                    .byron_proposed_block_version()
+                    .map(|(major, minor, _)| (major.into(), minor.into())),
+                ..Default::default()
+            };
+            vec![(None, params)]
+        };
+
+        Ok(Self {
+            era,
+            txn_idx,
+            epoch: update.epoch(),
+            updates,
+        })
+    }
+
+    /// Era the proposal was made in.
+    #[must_use]
+    pub fn era(&self) -> Era {
+        self.era
+    }
+
+    /// Index of the transaction carrying the proposal.
+    /// `None` for the block-level Byron proposals.
+    #[must_use]
+    pub fn txn_idx(&self) -> Option<TxnIndex> {
+        self.txn_idx
+    }
+
+    /// Epoch the proposal is targeting.
+    #[must_use]
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Proposed updates, keyed by the hash of the proposing genesis delegate key.
+    /// The key is `None` for the Byron proposals.
+    #[must_use]
+    pub fn updates(&self) -> &[(Option<Blake2b224Hash>, ProtocolParamUpdate)] {
+        &self.updates
+    }
+}
+
+/// Convert the raw genesis key hash bytes into a `Blake2b224Hash`.
+fn genesis_key_hash(bytes: &[u8]) -> anyhow::Result<Blake2b224Hash> {
+    if bytes.len() != BLAKE_2B224_SIZE {
+        bail!(
+            "Invalid genesis key hash size. Expected {BLAKE_2B224_SIZE} bytes, got {}",
+            bytes.len()
+        );
+    }
+    bytes.try_into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use pallas::codec::utils::KeyValuePairs;
+
+    use super::*;
+
+    #[test]
+    fn babbage_proposal() {
+        let params = babbage::ProtocolParamUpdate {
+            minfee_a: Some(44),
+            minfee_b: Some(155_381),
+            max_block_body_size: None,
+            max_transaction_size: Some(16384),
+            max_block_header_size: None,
+            key_deposit: None,
+            pool_deposit: None,
+            maximum_epoch: None,
+            desired_number_of_stake_pools: None,
+            pool_pledge_influence: None,
+            expansion_rate: None,
+            treasury_growth_rate: None,
+            protocol_version: Some((8, 0)),
+            min_pool_cost: None,
+            ada_per_utxo_byte: None,
+            cost_models_for_script_languages: None,
+            execution_costs: None,
+            max_tx_ex_units: Some(alonzo::ExUnits {
+                mem: 14_000_000,
+                steps: 10_000_000_000,
+            }),
+            max_block_ex_units: None,
+            max_value_size: None,
+            collateral_percentage: None,
+            max_collateral_inputs: None,
+        };
+        let update = babbage::Update {
+            proposed_protocol_parameter_updates: KeyValuePairs::from(vec![(
+                vec![1; BLAKE_2B224_SIZE].into(),
+                params,
+            )]),
+            epoch: 365,
+        };
+        let update = MultiEraUpdate::Babbage(Box::new(Cow::Owned(update)));
+
+        let proposal =
+            ProtocolParamProposal::new(Era::Babbage, Some(TxnIndex::from_saturating(2)), &update)
+                .unwrap();
+        assert_eq!(proposal.era(), Era::Babbage);
+        assert_eq!(proposal.epoch(), 365);
+        assert_eq!(proposal.txn_idx(), Some(TxnIndex::from_saturating(2)));
+
+        let [(Some(hash), params)] = proposal.updates() else {
+            panic!("Expected a single keyed update");
+        };
+        assert_eq!(*hash, Blake2b224Hash::from([1; BLAKE_2B224_SIZE]));
+        assert_eq!(params.min_fee_a, Some(44));
+        assert_eq!(params.min_fee_b, Some(155_381));
+        assert_eq!(params.max_transaction_size, Some(16384));
+        assert_eq!(params.protocol_version, Some((8, 0)));
+        assert_eq!(
+            params.max_tx_ex_units,
+            Some(ExUnits {
+                mem: 14_000_000,
+                steps: 10_000_000_000
+            })
+        );
+        assert_eq!(params.decentralization_constant, None);
+    }
+
+    #[test]
+    fn invalid_genesis_key_hash() {
+        let update = alonzo::Update {
+            proposed_protocol_parameter_updates: KeyValuePairs::from(vec![(
+                vec![1; 32].into(),
+                alonzo::ProtocolParamUpdate {
+                    minfee_a: None,
+                    minfee_b: None,
+                    max_block_body_size: None,
+                    max_transaction_size: None,
+                    max_block_header_size: None,
+                    key_deposit: None,
+                    pool_deposit: None,
+                    maximum_epoch: None,
+                    desired_number_of_stake_pools: None,
+                    pool_pledge_influence: None,
+                    expansion_rate: None,
+                    treasury_growth_rate: None,
+                    decentralization_constant: None,
+                    extra_entropy: None,
+                    protocol_version: None,
+                    min_pool_cost: None,
+                    ada_per_utxo_byte: None,
+                    cost_models_for_script_languages: None,
+                    execution_costs: None,
+                    max_tx_ex_units: None,
+                    max_block_ex_units: None,
+                    max_value_size: None,
+                    collateral_percentage: None,
+                    max_collateral_inputs: None,
+                },
+            )]),
+            epoch: 300,
+        };
+        let update = MultiEraUpdate::AlonzoCompatible(Box::new(Cow::Owned(update)));
+
+        assert!(ProtocolParamProposal::new(Era::Alonzo, None, &update).is_err());
+    }
+}
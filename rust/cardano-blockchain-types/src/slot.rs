@@ -1,7 +1,11 @@
 //! Block Slot
-use crate::conversion::from_saturating;
+use std::ops::{Add, Sub};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+use chrono::{DateTime, Utc};
+
+use crate::{conversion::from_saturating, Epoch, Network};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 /// Slot on the blockchain, typically one slot equals one second.  However chain
 /// parameters can alter how long a slot is.
 pub struct Slot(u64);
@@ -20,6 +24,48 @@ impl Slot {
         let value: u64 = from_saturating(value);
         Self(value)
     }
+
+    /// Add a number of slots, `None` on overflow.
+    #[must_use]
+    pub fn checked_add(self, slots: u64) -> Option<Self> {
+        self.0.checked_add(slots).map(Self)
+    }
+
+    /// Number of slots since the earlier slot, `None` if it is not earlier.
+    #[must_use]
+    pub fn checked_sub(self, earlier: Slot) -> Option<u64> {
+        self.0.checked_sub(earlier.0)
+    }
+
+    /// Convert the slot to its Wall Time on the given network.
+    #[must_use]
+    pub fn to_time(self, network: Network) -> DateTime<Utc> {
+        network.slot_to_time(self)
+    }
+
+    /// Epoch of the slot on the given network, and the slot number within that epoch.
+    #[must_use]
+    pub fn epoch(self, network: Network) -> (Epoch, u64) {
+        network.epoch_schedule().slot_to_epoch(self)
+    }
+}
+
+impl Add<u64> for Slot {
+    type Output = Slot;
+
+    /// Add a number of slots, saturating if out of range.
+    fn add(self, slots: u64) -> Self::Output {
+        Self(self.0.saturating_add(slots))
+    }
+}
+
+impl Sub<u64> for Slot {
+    type Output = Slot;
+
+    /// Subtract a number of slots, saturating at slot `0`.
+    fn sub(self, slots: u64) -> Self::Output {
+        Self(self.0.saturating_sub(slots))
+    }
 }
 
 impl From<u64> for Slot {
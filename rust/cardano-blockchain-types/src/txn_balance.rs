@@ -0,0 +1,382 @@
+//! Transaction Balance
+//!
+//! Calculates the value consumed and produced by a transaction and checks the value is
+//! conserved, i.e.
+//!
+//! `inputs + withdrawals + refunds + mint == outputs + fee + deposits + donation`
+//!
+//! The spent outputs are not a part of the transaction, so they are resolved with a
+//! [`UtxoResolver`].
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::BuildHasher,
+};
+
+use pallas::ledger::{
+    primitives::{alonzo, conway},
+    traverse::{MultiEraCert, MultiEraInput, MultiEraTx},
+};
+
+use crate::{
+    hashes::{Blake2b224Hash, Blake2b256Hash},
+    txn_output::{TxnOutput, TxnOutputOffset},
+};
+
+/// Native asset identifier, the policy id and the asset name.
+pub type AssetId = (Blake2b224Hash, Vec<u8>);
+
+/// Resolves the outputs spent by the transaction inputs.
+pub trait UtxoResolver {
+    /// Get the output at `offset` of the transaction `txn_hash`, `None` if it is
+    /// unknown.
+    fn resolve(&self, txn_hash: &Blake2b256Hash, offset: TxnOutputOffset) -> Option<TxnOutput>;
+}
+
+impl<S: BuildHasher> UtxoResolver for HashMap<(Blake2b256Hash, TxnOutputOffset), TxnOutput, S> {
+    fn resolve(&self, txn_hash: &Blake2b256Hash, offset: TxnOutputOffset) -> Option<TxnOutput> {
+        self.get(&(*txn_hash, offset)).cloned()
+    }
+}
+
+/// Deposit amounts of the protocol parameters active for the transaction.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DepositParams {
+    /// Stake address registration deposit.
+    pub key_deposit: u64,
+    /// Stake pool registration deposit.
+    pub pool_deposit: u64,
+}
+
+/// Lovelace and native assets value.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Value {
+    /// Amount of lovelace.
+    pub lovelace: u128,
+    /// Amounts of the native assets.
+    pub assets: BTreeMap<AssetId, u128>,
+}
+
+impl Value {
+    /// Add the value held by the output.
+    fn add_output(&mut self, output: &TxnOutput) {
+        self.lovelace += u128::from(output.lovelace());
+        for asset in output.assets() {
+            *self
+                .assets
+                .entry((asset.policy_id, asset.name.clone()))
+                .or_default() += u128::from(asset.amount);
+        }
+    }
+}
+
+/// A value conservation discrepancy of the transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BalanceDiscrepancy {
+    /// The spent output could not be resolved.
+    UnresolvedInput {
+        /// Hash of the transaction holding the spent output.
+        txn_hash: Blake2b256Hash,
+        /// Offset of the spent output.
+        offset: TxnOutputOffset,
+    },
+    /// Consumed and produced lovelace differ.
+    Lovelace {
+        /// Consumed lovelace.
+        consumed: u128,
+        /// Produced lovelace.
+        produced: u128,
+    },
+    /// Consumed and produced amounts of the native asset differ.
+    Asset {
+        /// The native asset.
+        asset: AssetId,
+        /// Consumed amount, including the minted or burned amount.
+        consumed: i128,
+        /// Produced amount.
+        produced: i128,
+    },
+}
+
+/// Balance of a transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TxnBalance {
+    /// Whether the transaction passed the phase-2 validation. Invalid transactions
+    /// consume their collateral instead of the inputs.
+    pub is_valid: bool,
+    /// Fee of the transaction. Declared for Shelley and later eras, implicit for Byron.
+    /// The collateral fee for the invalid transactions.
+    pub fee: u64,
+    /// Value of the resolved spent outputs.
+    pub inputs: Value,
+    /// Value of the produced outputs.
+    pub outputs: Value,
+    /// Reward withdrawals.
+    pub withdrawals: u64,
+    /// Deposits paid by the certificates and governance proposals.
+    pub deposits: u64,
+    /// Deposits refunded by the certificates.
+    pub refunds: u64,
+    /// Treasury donation.
+    pub donation: u64,
+    /// Minted (positive) and burned (negative) native assets.
+    pub mint: BTreeMap<AssetId, i128>,
+    /// Spent outputs which could not be resolved.
+    pub unresolved: Vec<(Blake2b256Hash, TxnOutputOffset)>,
+}
+
+impl TxnBalance {
+    /// Calculate the balance of the transaction.
+    ///
+    /// **NOTE** Stake pool registration certificates are always counted as a deposit,
+    /// re-registration of an existing pool does not pay it, so it must be accounted for
+    /// by the caller.
+    ///
+    /// # Errors
+    ///
+    /// If any of the produced or resolved outputs cannot be decoded.
+    pub(crate) fn new(
+        tx: &MultiEraTx, resolver: &impl UtxoResolver, params: &DepositParams,
+    ) -> anyhow::Result<Self> {
+        let is_valid = tx.is_valid();
+        let mut balance = Self {
+            is_valid,
+            ..Self::default()
+        };
+
+        for input in tx.consumes() {
+            balance.add_input(&input, resolver);
+        }
+
+        if !is_valid {
+            if let Some(output) = tx.collateral_return() {
+                let offset = TxnOutputOffset::from_saturating(tx.outputs().len());
+                balance
+                    .outputs
+                    .add_output(&TxnOutput::new(offset, &output)?);
+            }
+            let implicit_fee = balance
+                .inputs
+                .lovelace
+                .saturating_sub(balance.outputs.lovelace);
+            balance.fee = match tx.total_collateral() {
+                Some(fee) => fee,
+                None => u64::try_from(implicit_fee)?,
+            };
+            return Ok(balance);
+        }
+
+        for (i, output) in tx.outputs().iter().enumerate() {
+            let output = TxnOutput::new(TxnOutputOffset::from_saturating(i), output)?;
+            balance.outputs.add_output(&output);
+        }
+        balance.withdrawals = tx.withdrawals_sorted_set().iter().map(|(_, v)| v).sum();
+        for cert in tx.certs() {
+            balance.add_cert(&cert, params);
+        }
+        if let Some(conway) = tx.as_conway() {
+            let body = &conway.transaction_body;
+            balance.deposits += body
+                .proposal_procedures
+                .iter()
+                .flat_map(|p| p.iter())
+                .map(|p| p.deposit)
+                .sum::<u64>();
+            balance.donation = body.donation.as_ref().map(u64::from).unwrap_or_default();
+        }
+        for policy in tx.mints() {
+            let policy_id = Blake2b224Hash::from(*policy.policy());
+            for asset in policy.assets() {
+                if let Some(amount) = asset.mint_coin() {
+                    *balance
+                        .mint
+                        .entry((policy_id, asset.name().to_vec()))
+                        .or_default() += i128::from(amount);
+                }
+            }
+        }
+        balance.fee = match tx.fee() {
+            Some(fee) => fee,
+            None => {
+                let implicit_fee = balance
+                    .inputs
+                    .lovelace
+                    .saturating_sub(balance.outputs.lovelace);
+                u64::try_from(implicit_fee)?
+            },
+        };
+
+        Ok(balance)
+    }
+
+    /// Add the value of the spent output.
+    fn add_input(&mut self, input: &MultiEraInput, resolver: &impl UtxoResolver) {
+        let txn_hash = Blake2b256Hash::from(*input.hash());
+        let offset = TxnOutputOffset::from_saturating(input.index());
+        match resolver.resolve(&txn_hash, offset) {
+            Some(output) => self.inputs.add_output(&output),
+            None => self.unresolved.push((txn_hash, offset)),
+        }
+    }
+
+    /// Add the deposit paid or refunded by the certificate.
+    fn add_cert(&mut self, cert: &MultiEraCert, params: &DepositParams) {
+        if let Some(cert) = cert.as_alonzo() {
+            match cert {
+                alonzo::Certificate::StakeRegistration(_) => self.deposits += params.key_deposit,
+                alonzo::Certificate::StakeDeregistration(_) => self.refunds += params.key_deposit,
+                alonzo::Certificate::PoolRegistration { .. } => {
+                    self.deposits += params.pool_deposit;
+                },
+                _ => {},
+            }
+        } else if let Some(cert) = cert.as_conway() {
+            match cert {
+                conway::Certificate::StakeRegistration(_) => self.deposits += params.key_deposit,
+                conway::Certificate::StakeDeregistration(_) => self.refunds += params.key_deposit,
+                conway::Certificate::PoolRegistration { .. } => {
+                    self.deposits += params.pool_deposit;
+                },
+                conway::Certificate::Reg(_, deposit)
+                | conway::Certificate::StakeRegDeleg(_, _, deposit)
+                | conway::Certificate::VoteRegDeleg(_, _, deposit)
+                | conway::Certificate::StakeVoteRegDeleg(_, _, _, deposit)
+                | conway::Certificate::RegDRepCert(_, deposit, _) => self.deposits += deposit,
+                conway::Certificate::UnReg(_, refund)
+                | conway::Certificate::UnRegDRepCert(_, refund) => self.refunds += refund,
+                _ => {},
+            }
+        }
+    }
+
+    /// Total consumed lovelace.
+    #[must_use]
+    pub fn consumed_lovelace(&self) -> u128 {
+        self.inputs.lovelace + u128::from(self.withdrawals) + u128::from(self.refunds)
+    }
+
+    /// Total produced lovelace.
+    #[must_use]
+    pub fn produced_lovelace(&self) -> u128 {
+        self.outputs.lovelace
+            + u128::from(self.fee)
+            + u128::from(self.deposits)
+            + u128::from(self.donation)
+    }
+
+    /// Get all value conservation discrepancies of the transaction.
+    #[must_use]
+    pub fn discrepancies(&self) -> Vec<BalanceDiscrepancy> {
+        let mut discrepancies: Vec<_> = self
+            .unresolved
+            .iter()
+            .map(|(txn_hash, offset)| {
+                BalanceDiscrepancy::UnresolvedInput {
+                    txn_hash: *txn_hash,
+                    offset: *offset,
+                }
+            })
+            .collect();
+
+        let consumed = self.consumed_lovelace();
+        let produced = self.produced_lovelace();
+        if consumed != produced {
+            discrepancies.push(BalanceDiscrepancy::Lovelace { consumed, produced });
+        }
+
+        let mut assets: BTreeMap<&AssetId, (i128, i128)> = BTreeMap::new();
+        for (asset, amount) in &self.inputs.assets {
+            assets.entry(asset).or_default().0 += i128::try_from(*amount).unwrap_or(i128::MAX);
+        }
+        for (asset, amount) in &self.mint {
+            assets.entry(asset).or_default().0 += amount;
+        }
+        for (asset, amount) in &self.outputs.assets {
+            assets.entry(asset).or_default().1 += i128::try_from(*amount).unwrap_or(i128::MAX);
+        }
+        discrepancies.extend(
+            assets
+                .into_iter()
+                .filter(|(_, (consumed, produced))| consumed != produced)
+                .map(|(asset, (consumed, produced))| {
+                    BalanceDiscrepancy::Asset {
+                        asset: asset.clone(),
+                        consumed,
+                        produced,
+                    }
+                }),
+        );
+
+        discrepancies
+    }
+
+    /// Whether the value is conserved by the transaction.
+    #[must_use]
+    pub fn is_balanced(&self) -> bool {
+        self.discrepancies().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_era_block_data::tests::babbage_block;
+
+    #[test]
+    fn txn_balance() {
+        let babbage = babbage_block();
+        let block = pallas::ledger::traverse::MultiEraBlock::decode(&babbage)
+            .expect("Failed to decode MultiEraBlock");
+        let resolver = HashMap::new();
+
+        for tx in block.txs() {
+            let balance = TxnBalance::new(&tx, &resolver, &DepositParams::default())
+                .expect("Failed to calculate balance");
+            assert_eq!(balance.unresolved.len(), tx.consumes().len());
+            assert_eq!(balance.inputs, Value::default());
+
+            let outputs: u64 = tx.outputs().iter().map(|o| o.lovelace_amount()).sum();
+            assert_eq!(balance.outputs.lovelace, u128::from(outputs));
+            assert_eq!(Some(balance.fee), tx.fee());
+            assert!(!balance.is_balanced());
+        }
+    }
+
+    #[test]
+    fn txn_balance_discrepancies() {
+        let asset: AssetId = (Blake2b224Hash::from([1; 28]), b"token".to_vec());
+        let mut balance = TxnBalance {
+            is_valid: true,
+            fee: 200_000,
+            inputs: Value {
+                lovelace: 10_000_000,
+                assets: BTreeMap::from([(asset.clone(), 10)]),
+            },
+            outputs: Value {
+                lovelace: 7_800_000,
+                assets: BTreeMap::from([(asset.clone(), 15)]),
+            },
+            withdrawals: 1_000_000,
+            deposits: 2_000_000,
+            refunds: 0,
+            donation: 1_000_000,
+            mint: BTreeMap::from([(asset.clone(), 5)]),
+            unresolved: Vec::new(),
+        };
+        assert!(balance.is_balanced());
+
+        balance.mint.clear();
+        balance.fee = 100_000;
+        assert_eq!(balance.discrepancies(), vec![
+            BalanceDiscrepancy::Lovelace {
+                consumed: 11_000_000,
+                produced: 10_900_000,
+            },
+            BalanceDiscrepancy::Asset {
+                asset,
+                consumed: 10,
+                produced: 15,
+            },
+        ]);
+    }
+}
@@ -21,3 +21,15 @@ impl TxnIndex {
         Self(value)
     }
 }
+
+impl From<u16> for TxnIndex {
+    fn from(value: u16) -> Self {
+        Self(value)
+    }
+}
+
+impl From<TxnIndex> for usize {
+    fn from(value: TxnIndex) -> Self {
+        value.0.into()
+    }
+}
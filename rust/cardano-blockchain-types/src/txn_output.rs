@@ -0,0 +1,207 @@
+//! Transaction Output
+use pallas::ledger::{addresses::Address, traverse::MultiEraOutput};
+
+use crate::{conversion::from_saturating, hashes::Blake2b224Hash};
+
+/// Transaction output offset within a transaction.
+/// See: <https://github.com/IntersectMBO/cardano-ledger/blob/78b32d585fd4a0340fb2b184959fb0d46f32c8d2/eras/conway/impl/cddl-files/conway.cddl#L121>
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TxnOutputOffset(u16);
+
+impl TxnOutputOffset {
+    /// Convert an `<T>` to transaction output offset (saturate if out of range).
+    pub(crate) fn from_saturating<
+        T: Copy
+            + TryInto<u16>
+            + std::ops::Sub<Output = T>
+            + std::cmp::PartialOrd<T>
+            + num_traits::identities::Zero,
+    >(
+        value: T,
+    ) -> Self {
+        let value: u16 = from_saturating(value);
+        Self(value)
+    }
+}
+
+impl From<u16> for TxnOutputOffset {
+    fn from(value: u16) -> Self {
+        Self(value)
+    }
+}
+
+impl From<TxnOutputOffset> for usize {
+    fn from(value: TxnOutputOffset) -> Self {
+        value.0.into()
+    }
+}
+
+/// Kind of the address a transaction output is locked by.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AddressKind {
+    /// Byron era bootstrap address.
+    Byron,
+    /// Shelley address with a payment key credential.
+    Payment,
+    /// Shelley address with a payment script credential.
+    Script,
+    /// Stake (reward) address.
+    Stake,
+}
+
+impl From<&Address> for AddressKind {
+    fn from(address: &Address) -> Self {
+        match address {
+            Address::Byron(_) => Self::Byron,
+            Address::Shelley(addr) if addr.payment().is_script() => Self::Script,
+            Address::Shelley(_) => Self::Payment,
+            Address::Stake(_) => Self::Stake,
+        }
+    }
+}
+
+/// A single native asset held by a transaction output.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NativeAsset {
+    /// Minting policy id.
+    pub policy_id: Blake2b224Hash,
+    /// Asset name.
+    pub name: Vec<u8>,
+    /// Amount of the asset.
+    pub amount: u64,
+}
+
+/// Typed view of a transaction output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxnOutput {
+    /// Offset of the output within its transaction.
+    offset: TxnOutputOffset,
+    /// Raw bytes of the output address.
+    address: Vec<u8>,
+    /// Kind of the output address.
+    address_kind: AddressKind,
+    /// Amount of lovelace.
+    lovelace: u64,
+    /// Native assets bundle.
+    assets: Vec<NativeAsset>,
+    /// Whether the output has a datum hash or an inline datum.
+    has_datum: bool,
+    /// Whether the output has a reference script.
+    has_script_ref: bool,
+}
+
+impl TxnOutput {
+    /// Create a new `TxnOutput` from the decoded output at the given offset.
+    ///
+    /// # Errors
+    ///
+    /// If the output address cannot be decoded.
+    pub(crate) fn new(offset: TxnOutputOffset, output: &MultiEraOutput) -> anyhow::Result<Self> {
+        let address = output
+            .address()
+            .map_err(|e| anyhow::anyhow!("Invalid output address: {e}"))?;
+        let assets = output
+            .non_ada_assets()
+            .iter()
+            .flat_map(|policy| {
+                let policy_id = Blake2b224Hash::from(*policy.policy());
+                policy.assets().into_iter().filter_map(move |asset| {
+                    asset.output_coin().map(|amount| {
+                        NativeAsset {
+                            policy_id,
+                            name: asset.name().to_vec(),
+                            amount,
+                        }
+                    })
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            offset,
+            address_kind: AddressKind::from(&address),
+            address: address.to_vec(),
+            lovelace: output.lovelace_amount(),
+            assets,
+            has_datum: output.datum().is_some(),
+            has_script_ref: output.script_ref().is_some(),
+        })
+    }
+
+    /// Offset of the output within its transaction.
+    #[must_use]
+    pub fn offset(&self) -> TxnOutputOffset {
+        self.offset
+    }
+
+    /// Raw bytes of the output address.
+    #[must_use]
+    pub fn address(&self) -> &[u8] {
+        &self.address
+    }
+
+    /// Kind of the output address.
+    #[must_use]
+    pub fn address_kind(&self) -> AddressKind {
+        self.address_kind
+    }
+
+    /// Amount of lovelace held by the output.
+    #[must_use]
+    pub fn lovelace(&self) -> u64 {
+        self.lovelace
+    }
+
+    /// Native assets held by the output.
+    #[must_use]
+    pub fn assets(&self) -> &[NativeAsset] {
+        &self.assets
+    }
+
+    /// Whether the output has a datum hash or an inline datum.
+    #[must_use]
+    pub fn has_datum(&self) -> bool {
+        self.has_datum
+    }
+
+    /// Whether the output has a reference script.
+    #[must_use]
+    pub fn has_script_ref(&self) -> bool {
+        self.has_script_ref
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_era_block_data::tests::babbage_block;
+
+    #[test]
+    fn txn_output() {
+        let babbage = babbage_block();
+        let block = pallas::ledger::traverse::MultiEraBlock::decode(&babbage)
+            .expect("Failed to decode MultiEraBlock");
+
+        for tx in block.txs() {
+            for (i, output) in tx.outputs().iter().enumerate() {
+                let offset = TxnOutputOffset::from_saturating(i);
+                let txn_output = TxnOutput::new(offset, output).expect("Failed to decode output");
+                assert_eq!(usize::from(txn_output.offset()), i);
+                assert_eq!(txn_output.lovelace(), output.lovelace_amount());
+                assert_eq!(txn_output.has_datum(), output.datum().is_some());
+                assert_eq!(txn_output.has_script_ref(), output.script_ref().is_some());
+
+                let assets: usize = output
+                    .non_ada_assets()
+                    .iter()
+                    .map(|p| p.assets().len())
+                    .sum();
+                assert_eq!(txn_output.assets().len(), assets);
+
+                let address = output.address().expect("Failed to decode address");
+                assert_eq!(txn_output.address(), address.to_vec());
+                assert_eq!(txn_output.address_kind(), AddressKind::from(&address));
+            }
+        }
+    }
+}
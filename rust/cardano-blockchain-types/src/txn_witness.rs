@@ -10,6 +10,9 @@ use crate::{conversion::vkey_from_bytes, hashes::Blake2b224Hash, TxnIndex};
 /// Hash of a witness verifying public key
 pub type VKeyHash = Blake2b224Hash;
 
+/// Bech32 human readable part of the [`VKeyHash`], as defined in CIP-5.
+pub const VKEY_HASH_BECH32_HRP: &str = "addr_vkh";
+
 /// `WitnessMap` type of `DashMap` with
 /// key as [u8; 28] = (`blake2b_244` hash of the public key)
 /// value as `(Bytes, Vec<u8>) = (public key, tx index within the block)`
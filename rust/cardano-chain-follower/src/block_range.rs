@@ -0,0 +1,180 @@
+//! Bounded memory stream of a historical range of immutable blocks.
+//!
+//! `ChainFollower` is built to follow the tip. Batch indexers which only need to
+//! reprocess history can use `ChainFollower::read_range` instead, which reads the
+//! blocks straight from the Mithril snapshot. The blocks are read ahead on a background
+//! task, into a bounded queue, so a slow consumer applies backpressure to the reader
+//! rather than accumulating blocks in memory.
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{pin_mut, stream, Stream, StreamExt};
+use tokio::sync::mpsc;
+use tracing::debug;
+
+use crate::{
+    chain_sync_ready::block_until_sync_ready, error::Error, mithril_snapshot::MithrilSnapshot,
+    MultiEraBlock, Network, Point,
+};
+
+/// Default number of blocks read ahead of the consumer.
+pub const DEFAULT_READ_AHEAD: usize = 64;
+
+/// A stream of the immutable blocks in a range.
+pub struct BlockRangeStream {
+    /// Blocks read ahead of the consumer.
+    blocks: mpsc::Receiver<MultiEraBlock>,
+}
+
+impl BlockRangeStream {
+    /// Start reading the immutable blocks in the range.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain` - The blockchain network to read.
+    /// * `start` - The point to start reading from (inclusive).
+    /// * `end` - The point to stop reading at (inclusive).
+    /// * `read_ahead` - The maximum number of blocks read ahead of the consumer.
+    ///
+    /// # Errors
+    ///
+    /// If the start point is not within the Mithril snapshot.
+    pub(crate) async fn new(
+        chain: Network, start: Point, end: Point, read_ahead: usize,
+    ) -> crate::Result<Self> {
+        // The Mithril snapshot must be fully downloaded and validated before reading it.
+        block_until_sync_ready(chain).await;
+
+        let snapshot = MithrilSnapshot::new(chain);
+        let Some(iterator) = snapshot.try_read_blocks_from_point(&start).await else {
+            return Err(Error::MithrilSnapshot(None));
+        };
+
+        let blocks = stream::unfold(iterator, |iterator| {
+            async move { iterator.next().await.map(|block| (block, iterator)) }
+        });
+        Ok(Self::from_blocks(chain, blocks, start, end, read_ahead))
+    }
+
+    /// Start reading the blocks in the range from the ordered `blocks`.
+    fn from_blocks(
+        chain: Network, blocks: impl Stream<Item = MultiEraBlock> + Send + 'static, start: Point,
+        end: Point, read_ahead: usize,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel(read_ahead.max(1));
+        tokio::spawn(async move {
+            pin_mut!(blocks);
+            while let Some(block) = blocks.next().await {
+                // The blocks are not required to start at the range.
+                if block < start {
+                    continue;
+                }
+                if block > end {
+                    break;
+                }
+                // The consumer dropped the stream, so there is no need to read any further.
+                if tx.send(block).await.is_err() {
+                    break;
+                }
+            }
+            debug!(chain = %chain, start = %start, end = %end, "Finished reading block range");
+        });
+
+        Self { blocks: rx }
+    }
+
+    /// Get the next block in the range.
+    /// Returns NONE if there are no blocks left in the range.
+    pub async fn next(&mut self) -> Option<MultiEraBlock> {
+        self.blocks.recv().await
+    }
+}
+
+impl Stream for BlockRangeStream {
+    type Item = MultiEraBlock;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.blocks.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::{TIP_POINT, UNKNOWN_POINT};
+
+    /// Blocks of the range source, ordered by their point.
+    fn blocks() -> Vec<MultiEraBlock> {
+        let mut blocks: Vec<_> = [
+            include_str!("./../test_data/shelley.block"),
+            include_str!("./../test_data/allegra.block"),
+            include_str!("./../test_data/mary.block"),
+            include_str!("./../test_data/alonzo.block"),
+            include_str!("./../test_data/babbage.block"),
+        ]
+        .into_iter()
+        .map(|block| {
+            let raw_block = hex::decode(block).expect("Failed to decode hex block.");
+            MultiEraBlock::new(Network::Preprod, raw_block, &UNKNOWN_POINT, 0)
+                .expect("cannot create block")
+        })
+        .collect();
+        blocks.sort();
+        blocks
+    }
+
+    /// Read the range of the `blocks`.
+    async fn read_range(blocks: Vec<MultiEraBlock>, start: Point, end: Point) -> Vec<Point> {
+        BlockRangeStream::from_blocks(
+            Network::Preprod,
+            stream::iter(blocks),
+            start,
+            end,
+            DEFAULT_READ_AHEAD,
+        )
+        .map(|block| block.point())
+        .collect()
+        .await
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn test_read_middle_range() {
+        let blocks = blocks();
+        let points: Vec<_> = blocks.iter().map(MultiEraBlock::point).collect();
+
+        let range = read_range(blocks, points[1].clone(), points[3].clone()).await;
+        assert_eq!(range, points[1..=3]);
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn test_read_range_past_end() {
+        let blocks = blocks();
+        let points: Vec<_> = blocks.iter().map(MultiEraBlock::point).collect();
+
+        // The range ends with the last block.
+        let range = read_range(blocks, points[2].clone(), TIP_POINT).await;
+        assert_eq!(range, points[2..]);
+    }
+
+    #[tokio::test]
+    #[allow(clippy::indexing_slicing)]
+    async fn test_read_empty_range() {
+        let blocks = blocks();
+        let points: Vec<_> = blocks.iter().map(MultiEraBlock::point).collect();
+
+        // The end is before the start.
+        let range = read_range(blocks.clone(), points[3].clone(), points[1].clone()).await;
+        assert!(range.is_empty());
+
+        // No block between the start and the end.
+        let start = Point::fuzzy(points[1].slot_or_default() + 1);
+        let end = Point::fuzzy(points[2].slot_or_default() - 1);
+        let range = read_range(blocks, start, end).await;
+        assert!(range.is_empty());
+    }
+}
@@ -0,0 +1,237 @@
+//! Rollback aware stream of chain events.
+//!
+//! `ChainFollower` reports a rollback as a single `Kind::Rollback` update, leaving it to
+//! the consumer to work out which of the blocks it already processed are now orphaned.
+//! `ChainEventStream` keeps the backlog of delivered blocks which are not yet immutable,
+//! and turns every rollback into explicit `Reverted` events for those blocks.
+
+use std::collections::VecDeque;
+
+use crate::{
+    chain_update::{ChainUpdate, Kind},
+    ChainFollower, MultiEraBlock, Network, Point,
+};
+
+/// The maximum number of delivered blocks which can be reverted.
+///
+/// Blocks deeper than the security parameter `k` can never be rolled back.
+const MAX_ROLLBACK_DEPTH: usize = 2160;
+
+/// An event of the chain being followed.
+#[derive(Clone, Debug)]
+pub enum ChainEvent {
+    /// The chain moved forward, either with a new block or a new immutable tip.
+    Forward(ChainUpdate),
+    /// A previously delivered block was orphaned by a rollback, and must be undone.
+    ///
+    /// Reverted blocks are delivered newest first.
+    Reverted(MultiEraBlock),
+}
+
+impl ChainEvent {
+    /// Gets the block of the event.
+    #[must_use]
+    pub fn block_data(&self) -> &MultiEraBlock {
+        match self {
+            ChainEvent::Forward(update) => update.block_data(),
+            ChainEvent::Reverted(block) => block,
+        }
+    }
+}
+
+/// A stream of chain events, with rollbacks replaced by compensating events.
+pub struct ChainEventStream {
+    /// The follower providing the chain updates.
+    follower: ChainFollower,
+    /// Delivered blocks which can still be rolled back, oldest first.
+    delivered: VecDeque<MultiEraBlock>,
+    /// Events waiting to be returned, oldest first.
+    pending: VecDeque<ChainEvent>,
+}
+
+impl ChainEventStream {
+    /// Follow a blockchain as a stream of chain events.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain` - The blockchain network to follow.
+    /// * `start` - The point or tip to start following from (inclusive).
+    /// * `end` - The point or tip to stop following from (inclusive).
+    ///
+    /// # Returns
+    ///
+    /// The stream of chain events of the blocks in the requested range.
+    #[must_use]
+    pub async fn new(chain: Network, start: Point, end: Point) -> Self {
+        Self::from_follower(ChainFollower::new(chain, start, end).await)
+    }
+
+    /// Create a stream of chain events from the updates of an existing follower.
+    ///
+    /// Only blocks delivered by the stream itself can be reverted.
+    #[must_use]
+    pub fn from_follower(follower: ChainFollower) -> Self {
+        Self {
+            follower,
+            delivered: VecDeque::new(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Get the next event from the stream.
+    /// Returns NONE is there is no event left to return.
+    pub async fn next(&mut self) -> Option<ChainEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+
+            let update = self.follower.next().await?;
+            self.process(update);
+        }
+    }
+
+    /// Turn the chain update into its events.
+    fn process(&mut self, update: ChainUpdate) {
+        match update.kind {
+            Kind::Block => {
+                self.deliver(update.data.clone());
+                self.pending.push_back(ChainEvent::Forward(update));
+            },
+            Kind::ImmutableBlockRollForward => {
+                // Nothing up to the immutable tip can be rolled back any more.
+                let immutable_tip = update.data.point();
+                while self
+                    .delivered
+                    .front()
+                    .is_some_and(|block| block.point() <= immutable_tip)
+                {
+                    self.delivered.pop_front();
+                }
+                self.pending.push_back(ChainEvent::Forward(update));
+            },
+            Kind::Rollback => self.rollback(update),
+        }
+    }
+
+    /// Revert every delivered block orphaned by rolling back to the update block.
+    ///
+    /// The rollback block is delivered as a new block, unless it was already delivered.
+    fn rollback(&mut self, update: ChainUpdate) {
+        let rollback_point = update.data.point();
+
+        while let Some(block) = self.delivered.back() {
+            let point = block.point();
+            if point < rollback_point || point.strict_eq(&rollback_point) {
+                break;
+            }
+            if let Some(block) = self.delivered.pop_back() {
+                self.pending.push_back(ChainEvent::Reverted(block));
+            }
+        }
+
+        let already_delivered = self
+            .delivered
+            .back()
+            .is_some_and(|block| block.point().strict_eq(&rollback_point));
+        if !already_delivered {
+            self.deliver(update.data.clone());
+            self.pending.push_back(ChainEvent::Forward(ChainUpdate::new(
+                Kind::Block,
+                update.tip,
+                update.data,
+            )));
+        }
+    }
+
+    /// Remember a delivered block, so it can be reverted later.
+    fn deliver(&mut self, block: MultiEraBlock) {
+        // Immutable blocks can never be rolled back.
+        if block.immutable() {
+            return;
+        }
+        self.delivered.push_back(block);
+        if self.delivered.len() > MAX_ROLLBACK_DEPTH {
+            self.delivered.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        multi_era_block_data::tests::{allegra_block, byron_block, mary_block, shelley_block},
+        ORIGIN_POINT,
+    };
+
+    /// Decode a live test block.
+    fn live_block(raw: Vec<u8>) -> MultiEraBlock {
+        MultiEraBlock::new(Network::Preprod, raw, &ORIGIN_POINT, 2).unwrap()
+    }
+
+    /// Points of the events, with `true` for reverted blocks.
+    fn event_points(stream: &mut ChainEventStream) -> Vec<(bool, Point)> {
+        stream
+            .pending
+            .drain(..)
+            .map(|event| {
+                (
+                    matches!(event, ChainEvent::Reverted(_)),
+                    event.block_data().point(),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_rollback_reverts_orphaned_blocks() {
+        let mut stream = ChainEventStream::new(Network::Preprod, ORIGIN_POINT, ORIGIN_POINT).await;
+
+        // Sorted by slot number from lowest to highest.
+        let blocks: Vec<_> = [
+            byron_block(),
+            shelley_block(),
+            allegra_block(),
+            mary_block(),
+        ]
+        .into_iter()
+        .map(live_block)
+        .collect();
+        for block in &blocks {
+            stream.process(ChainUpdate::new(Kind::Block, false, block.clone()));
+        }
+        assert_eq!(event_points(&mut stream).len(), blocks.len());
+
+        // Roll back to an already delivered block.
+        let rollback_to = blocks.get(1).unwrap().clone();
+        stream.process(ChainUpdate::new(Kind::Rollback, false, rollback_to));
+        assert_eq!(event_points(&mut stream), vec![
+            (true, blocks.get(3).unwrap().point()),
+            (true, blocks.get(2).unwrap().point()),
+        ]);
+
+        // Roll back to a block which was not delivered before.
+        let rollback_to = blocks.get(3).unwrap().clone();
+        stream.process(ChainUpdate::new(Kind::Rollback, true, rollback_to));
+        assert_eq!(event_points(&mut stream), vec![(
+            false,
+            blocks.get(3).unwrap().point()
+        )]);
+
+        // Immutable blocks can no longer be reverted.
+        let immutable_tip = blocks.get(1).unwrap().clone();
+        stream.process(ChainUpdate::new(
+            Kind::ImmutableBlockRollForward,
+            false,
+            immutable_tip,
+        ));
+        event_points(&mut stream);
+        let rollback_to = blocks.first().unwrap().clone();
+        stream.process(ChainUpdate::new(Kind::Rollback, false, rollback_to));
+        assert_eq!(event_points(&mut stream), vec![
+            (true, blocks.get(3).unwrap().point()),
+            (false, blocks.first().unwrap().point()),
+        ]);
+    }
+}
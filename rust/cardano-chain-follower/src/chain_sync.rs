@@ -3,9 +3,10 @@
 //! All iteration of the chain is done through this buffer or a mithril snapshot.
 //! Consumers of this library do not talk to the node directly.
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::Context;
+use futures::future::select_ok;
 use pallas::{
     ledger::traverse::MultiEraHeader,
     network::{
@@ -25,6 +26,7 @@ use crate::{
         get_fill_to_point, get_intersect_points, get_live_block, get_live_head_point, get_peer_tip,
         live_chain_add_block_to_tip, live_chain_backfill, live_chain_length, purge_live_chain,
     },
+    chain_sync_peers::PeerSet,
     chain_sync_ready::{
         get_chain_update_tx_queue, notify_follower, wait_for_sync_ready, SyncReadyWaiter,
     },
@@ -308,51 +310,97 @@ async fn follow_chain(
 /// attempts.
 const PEER_FAILURE_RECONNECT_DELAY: Duration = Duration::from_secs(10);
 
-/// Do not return until we have a connection to the peer.
-async fn persistent_reconnect(addr: &str, chain: Network) -> PeerClient {
-    // Not yet connected to the peer.
-    stats::peer_connected(chain, false, addr);
-
-    loop {
-        // We never have a connection if we end up around the loop, so make a new one.
-        match retry_connect(addr, chain.into()).await {
+/// Connect to the healthiest relay, failing over to the next healthiest one until a
+/// connection is made.
+async fn failover_connect(peers: &PeerSet, chain: Network) -> Option<(String, PeerClient)> {
+    for addr in peers.by_health() {
+        let started = Instant::now();
+        match retry_connect(&addr, chain.into()).await {
             Ok(peer) => {
-                // Successfully connected to the peer.
-                stats::peer_connected(chain, true, addr);
-
-                return peer;
+                peers.success(&addr, Some(started.elapsed()));
+                return Some((addr, peer));
             },
             Err(error) => {
+                peers.failure(&addr);
                 error!(
                     "Chain Sync for: {} from   {}  : Failed to connect to relay: {}",
                     chain, addr, error,
                 );
-
-                // Wait a bit before trying again.
-                tokio::time::sleep(PEER_FAILURE_RECONNECT_DELAY).await;
             },
-        };
+        }
     }
+    None
 }
 
-/// Backfill the live chain, based on the Mithril Sync updates.
-/// This does NOT return until the live chain has been backfilled from the end of mithril
-/// to the current synced tip blocks.
+/// Connect to all relays in parallel, and keep the connection to the fastest one.
+/// Only the connection is raced, the chain is then followed from that relay alone.
+async fn fastest_connect(peers: &PeerSet, chain: Network) -> Option<(String, PeerClient)> {
+    let addresses = peers.by_health();
+    // `select_ok` can not select from nothing.
+    if addresses.is_empty() {
+        return None;
+    }
+
+    let attempts = addresses.iter().map(|addr| {
+        Box::pin(async move {
+            let started = Instant::now();
+            retry_connect(addr, chain.into())
+                .await
+                .map(|peer| (addr, peer, started.elapsed()))
+        })
+    });
+
+    // The slower connection attempts are dropped, which cancels them.
+    match select_ok(attempts).await {
+        Ok(((addr, peer, latency), _)) => {
+            peers.success(addr, Some(latency));
+            Some((addr.clone(), peer))
+        },
+        Err(error) => {
+            for addr in &addresses {
+                peers.failure(addr);
+            }
+            error!("Chain Sync for: {chain} : Failed to connect to any relay: {error}");
+            None
+        },
+    }
+}
+
+/// Do not return until we have a connection to one of the peers.
 ///
-/// This only needs to be done once per chain connection.
-async fn live_sync_backfill(
-    cfg: &ChainSyncConfig, update: &MithrilUpdateMessage,
-) -> anyhow::Result<()> {
-    stats::backfill_started(cfg.chain);
+/// Returns the address of the connected peer, and its connection.
+async fn persistent_reconnect(
+    peers: &PeerSet, chain: Network, parallel: bool,
+) -> (String, PeerClient) {
+    loop {
+        // We never have a connection if we end up around the loop, so make a new one.
+        let connection = if parallel {
+            fastest_connect(peers, chain).await
+        } else {
+            failover_connect(peers, chain).await
+        };
 
-    let (fill_to, _oldest_fork) = get_fill_to_point(cfg.chain).await;
+        if let Some((addr, peer)) = connection {
+            // Successfully connected to the peer.
+            stats::peer_connected(chain, true, &addr);
+
+            return (addr, peer);
+        }
+
+        // Every relay failed, wait a bit before trying again.
+        tokio::time::sleep(PEER_FAILURE_RECONNECT_DELAY).await;
+    }
+}
+
+/// Fetch the backfill blocks of the range from the peer.
+async fn fetch_backfill_blocks(
+    peer: &mut PeerClient, chain: Network, update: &MithrilUpdateMessage, fill_to: &Point,
+) -> anyhow::Result<Vec<MultiEraBlock>> {
     let range = (update.tip.clone().into(), fill_to.clone().into());
     let mut previous_point = update.previous.clone();
 
     let range_msg = format!("{range:?}");
 
-    let mut peer = persistent_reconnect(&cfg.relay_address, cfg.chain).await;
-
     // Request the range of blocks from the Peer.
     peer.blockfetch()
         .request_range(range)
@@ -364,7 +412,7 @@ async fn live_sync_backfill(
     while let Some(block_data) = peer.blockfetch().recv_while_streaming().await? {
         // Backfilled blocks get placed in the oldest fork currently on the live-chain.
         let block =
-            MultiEraBlock::new(cfg.chain, block_data, &previous_point, 1).with_context(|| {
+            MultiEraBlock::new(chain, block_data, &previous_point, 1).with_context(|| {
                 format!(
                     "Failed to decode block data. previous: {previous_point:?}, range: {range_msg}"
                 )
@@ -386,13 +434,42 @@ async fn live_sync_backfill(
     }
 
     // Check we get the last block in the range properly.
-    if backfill_blocks.is_empty() || !previous_point.strict_eq(&fill_to) {
+    if backfill_blocks.is_empty() || !previous_point.strict_eq(fill_to) {
         return Err(Error::BackfillSync(format!(
             "Last Block is invalid. Block {previous_point:?} != Range End {fill_to:?}"
         ))
         .into());
     }
 
+    debug!("Backfill Range Received OK: {}", range_msg);
+
+    Ok(backfill_blocks)
+}
+
+/// Backfill the live chain, based on the Mithril Sync updates.
+/// This does NOT return until the live chain has been backfilled from the end of mithril
+/// to the current synced tip blocks.
+///
+/// This only needs to be done once per chain connection.
+async fn live_sync_backfill(
+    cfg: &ChainSyncConfig, peers: &PeerSet, update: &MithrilUpdateMessage,
+) -> anyhow::Result<()> {
+    stats::backfill_started(cfg.chain);
+
+    let (fill_to, _oldest_fork) = get_fill_to_point(cfg.chain).await;
+
+    let (addr, mut peer) = persistent_reconnect(peers, cfg.chain, false).await;
+
+    let backfill_blocks = match fetch_backfill_blocks(&mut peer, cfg.chain, update, &fill_to).await
+    {
+        Ok(backfill_blocks) => backfill_blocks,
+        Err(error) => {
+            // The peer misbehaved, so prefer another one for the next attempt.
+            peers.failure(&addr);
+            return Err(error);
+        },
+    };
+
     // Report how many backfill blocks we received.
     let backfill_size = backfill_blocks.len() as u64;
 
@@ -401,14 +478,14 @@ async fn live_sync_backfill(
 
     stats::backfill_ended(cfg.chain, backfill_size);
 
-    debug!("Backfilled Range OK: {}", range_msg);
+    debug!("Backfilled Range OK from {addr}");
 
     Ok(())
 }
 
 /// Backfill and Purge the live chain, based on the Mithril Sync updates.
 async fn live_sync_backfill_and_purge(
-    cfg: ChainSyncConfig, mut rx: mpsc::Receiver<MithrilUpdateMessage>,
+    cfg: ChainSyncConfig, peers: PeerSet, mut rx: mpsc::Receiver<MithrilUpdateMessage>,
     mut sync_ready: SyncReadyWaiter,
 ) {
     // Wait for first Mithril Update advice, which triggers a BACKFILL of the Live Data.
@@ -428,7 +505,7 @@ async fn live_sync_backfill_and_purge(
         // We will re-attempt backfill, until its successful.
         // Backfill is atomic, it either fully works, or none of the live-chain is changed.
         debug!("Mithril Tip has advanced to: {update:?} : BACKFILL");
-        while let Err(error) = live_sync_backfill(&cfg, &update).await {
+        while let Err(error) = live_sync_backfill(&cfg, &peers, &update).await {
             error!("Mithril Backfill Sync Failed: {}", error);
             sleep(Duration::from_secs(10)).await;
         }
@@ -512,18 +589,22 @@ async fn live_sync_backfill_and_purge(
 /// This does not return, it is a background task.
 pub(crate) async fn chain_sync(cfg: ChainSyncConfig, rx: mpsc::Receiver<MithrilUpdateMessage>) {
     debug!(
-        "Chain Sync for: {} from {} : Starting",
-        cfg.chain, cfg.relay_address,
+        "Chain Sync for: {} from {:?} : Starting",
+        cfg.chain, cfg.relay_addresses,
     );
 
+    // Health of the relays is shared by the live sync and the backfill.
+    let peers = PeerSet::new(cfg.chain, &cfg.relay_addresses);
+
     // Start the SYNC_READY unlock task.
     let sync_waiter = wait_for_sync_ready(cfg.chain);
 
     let backfill_cfg = cfg.clone();
+    let backfill_peers = peers.clone();
 
     // Start the Live chain backfill task.
     let _backfill_join_handle = spawn(async move {
-        live_sync_backfill_and_purge(backfill_cfg.clone(), rx, sync_waiter).await;
+        live_sync_backfill_and_purge(backfill_cfg.clone(), backfill_peers, rx, sync_waiter).await;
     });
 
     // Live Fill data starts at fork 1.
@@ -533,15 +614,15 @@ pub(crate) async fn chain_sync(cfg: ChainSyncConfig, rx: mpsc::Receiver<MithrilU
 
     loop {
         // We never have a connection if we end up around the loop, so make a new one.
-        let mut peer = persistent_reconnect(&cfg.relay_address, cfg.chain).await;
+        let (addr, mut peer) =
+            persistent_reconnect(&peers, cfg.chain, cfg.parallel_header_fetch).await;
 
         match resync_live_tip(&mut peer, cfg.chain).await {
-            Ok(tip) => debug!("Tip Resynchronized to {tip}"),
+            Ok(tip) => debug!("Tip Resynchronized to {tip} from {addr}"),
             Err(error) => {
-                error!(
-                    "Cardano Client {} failed to resync Tip: {}",
-                    cfg.relay_address, error
-                );
+                error!("Cardano Client {} failed to resync Tip: {}", addr, error);
+                peers.failure(&addr);
+                stats::peer_connected(cfg.chain, false, &addr);
                 continue;
             },
         }
@@ -549,9 +630,11 @@ pub(crate) async fn chain_sync(cfg: ChainSyncConfig, rx: mpsc::Receiver<MithrilU
         // Note: This can ONLY return with an error, otherwise it will sync indefinitely.
         if let Err(error) = follow_chain(&mut peer, cfg.chain, &mut fork_count).await {
             error!(
-                "Cardano Client {} failed to follow chain: {}: Reconnecting.",
-                cfg.relay_address, error
+                "Cardano Client {} failed to follow chain: {}: Failing over.",
+                addr, error
             );
+            peers.failure(&addr);
+            stats::peer_connected(cfg.chain, false, &addr);
             continue;
         }
 
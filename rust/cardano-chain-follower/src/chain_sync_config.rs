@@ -4,7 +4,7 @@
 //! each network. Chain Followers use the data supplied by the Chain-Sync.
 //! This module configures the chain sync processes.
 
-use std::sync::LazyLock;
+use std::{path::PathBuf, sync::LazyLock};
 
 use dashmap::DashMap;
 use strum::IntoEnumIterator;
@@ -13,6 +13,7 @@ use tracing::{debug, error};
 
 use crate::{
     chain_sync::chain_sync,
+    chain_sync_live_chains::{set_live_chain_bounds, LiveChainBounds},
     error::{Error, Result},
     mithril_snapshot_config::MithrilSnapshotConfig,
     network::Network,
@@ -42,13 +43,17 @@ static SYNC_JOIN_HANDLE_MAP: LazyLock<SyncMap> = LazyLock::new(|| {
 pub struct ChainSyncConfig {
     /// Chain Network
     pub chain: Network,
-    /// Relay Node Address
-    pub(crate) relay_address: String,
+    /// Relay Node Addresses, in order of preference.
+    pub(crate) relay_addresses: Vec<String>,
+    /// Race the connections to all relays, and sync from the fastest to connect.
+    pub(crate) parallel_header_fetch: bool,
     /// Block buffer size option.
     chain_update_buffer_size: usize,
     /// If we don't have immutable data, how far back from TIP is the data considered
     /// Immutable (in slots).
     immutable_slot_window: u64,
+    /// Bounds of the live chain kept in memory.
+    live_chain: LiveChainBounds,
     /// Configuration of Mithril Snapshots.
     pub mithril_cfg: MithrilSnapshotConfig,
 }
@@ -61,21 +66,64 @@ impl ChainSyncConfig {
     pub fn default_for(chain: Network) -> Self {
         Self {
             chain,
-            relay_address: chain.default_relay(),
+            relay_addresses: vec![chain.default_relay()],
+            parallel_header_fetch: false,
             chain_update_buffer_size: DEFAULT_CHAIN_UPDATE_BUFFER_SIZE,
             immutable_slot_window: DEFAULT_IMMUTABLE_SLOT_WINDOW,
+            live_chain: LiveChainBounds::default(),
             mithril_cfg: MithrilSnapshotConfig::default_for(chain),
         }
     }
 
-    /// Sets the relay to use for Chain Sync.
+    /// Sets the relay to use for Chain Sync, replacing any other configured relays.
     ///
     /// # Arguments
     ///
     /// * `relay`: Address to use for the blockchain relay node.
     #[must_use]
     pub fn relay(mut self, address: String) -> Self {
-        self.relay_address = address;
+        self.relay_addresses = vec![address];
+        self
+    }
+
+    /// Sets the relays to use for Chain Sync, in order of preference.
+    ///
+    /// Chain Sync uses the healthiest relay, and fails over to the next one when it
+    /// fails to connect or misbehaves.
+    ///
+    /// # Arguments
+    ///
+    /// * `addresses`: Addresses to use for the blockchain relay nodes.
+    #[must_use]
+    pub fn relays(mut self, addresses: Vec<String>) -> Self {
+        self.relay_addresses = addresses;
+        self
+    }
+
+    /// Adds a fallback relay to use for Chain Sync.
+    ///
+    /// # Arguments
+    ///
+    /// * `address`: Address of the additional blockchain relay node.
+    #[must_use]
+    pub fn add_relay(mut self, address: String) -> Self {
+        self.relay_addresses.push(address);
+        self
+    }
+
+    /// Sets whether the connections to all relays are raced when (re)connecting.
+    ///
+    /// When enabled, Chain Sync connects to every relay in parallel, keeps the first
+    /// connection to be established and drops the others. The chain headers are then
+    /// fetched from that single relay only, they are not fetched from several relays in
+    /// parallel. When disabled, the relays are tried one at a time, healthiest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `parallel`: Race the connections to all relays.
+    #[must_use]
+    pub fn parallel_header_fetch(mut self, parallel: bool) -> Self {
+        self.parallel_header_fetch = parallel;
         self
     }
 
@@ -101,6 +149,49 @@ impl ChainSyncConfig {
         self
     }
 
+    /// Sets the maximum number of live blocks kept in memory.
+    ///
+    /// The oldest live blocks are evicted when the live chain grows past the limit, and
+    /// are spilled over to disk if a spillover path is set. Otherwise they are dropped,
+    /// and can not be followed until they become Immutable.
+    /// The live chain is unbounded by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_blocks`: Maximum number of live blocks kept in memory.
+    #[must_use]
+    pub fn live_chain_max_blocks(mut self, max_blocks: usize) -> Self {
+        self.live_chain.max_blocks = Some(max_blocks);
+        self
+    }
+
+    /// Sets the maximum size of the live blocks kept in memory.
+    ///
+    /// The oldest live blocks are evicted when the live chain grows past the limit, the
+    /// same as with [`Self::live_chain_max_blocks`]. The tip is always kept in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_bytes`: Maximum size of the live blocks kept in memory, in bytes.
+    #[must_use]
+    pub fn live_chain_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.live_chain.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Sets the path the live blocks evicted from memory are spilled over to.
+    ///
+    /// Each network uses its own sub-directory, which is cleared when Chain Sync starts.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: Live chain spillover path.
+    #[must_use]
+    pub fn live_chain_spillover(mut self, path: PathBuf) -> Self {
+        self.live_chain.spillover_path = Some(path);
+        self
+    }
+
     /// Sets the the Mithril snapshot Config the `ChainSync` will use.
     ///
     /// # Arguments
@@ -134,6 +225,10 @@ impl ChainSyncConfig {
             "Chain Synchronization Starting"
         );
 
+        if self.relay_addresses.is_empty() {
+            return Err(Error::NoRelays(self.chain));
+        }
+
         stats::sync_started(self.chain);
 
         // Start the Chain Sync - IFF its not already running.
@@ -151,6 +246,9 @@ impl ChainSyncConfig {
             return Err(Error::ChainSyncAlreadyRunning(self.chain));
         }
 
+        // Bound the live chain before it is synced.
+        set_live_chain_bounds(self.chain, &self.live_chain)?;
+
         // Start the Mithril Snapshot Follower
         let rx = self.mithril_cfg.run().await?;
 
@@ -2,16 +2,21 @@
 
 use std::{
     ops::Bound,
-    sync::{Arc, LazyLock, RwLock},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, LazyLock, RwLock,
+    },
     time::Duration,
 };
 
-use crossbeam_skiplist::SkipMap;
+use crossbeam_skiplist::{map::Entry, SkipMap};
 use rayon::prelude::*;
 use strum::IntoEnumIterator;
 use tracing::{debug, error};
 
 use crate::{
+    chain_sync_live_spillover::{LiveChainSpillover, SpilledBlock, SpilledEntry},
     error::{Error, Result},
     mithril_snapshot_data::latest_mithril_snapshot_id,
     point::UNKNOWN_POINT,
@@ -21,11 +26,208 @@ use crate::{
 /// Type we use to manage the Sync Task handle map.
 type LiveChainBlockList = SkipMap<Point, MultiEraBlock>;
 
+/// Entry of a live block kept in memory.
+type MemoryEntry<'a> = Entry<'a, Point, MultiEraBlock>;
+
+/// Bounds of the live blocks kept in memory.
+/// The live chain is unbounded by default.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LiveChainBounds {
+    /// Maximum number of live blocks kept in memory.
+    pub(crate) max_blocks: Option<usize>,
+    /// Maximum size of the live blocks kept in memory, in bytes.
+    pub(crate) max_bytes: Option<u64>,
+    /// Path the evicted live blocks are spilled over to. If not set, the evicted live
+    /// blocks are dropped, and can not be followed anymore.
+    pub(crate) spillover_path: Option<PathBuf>,
+}
+
+/// Bounds of the live chain in effect, with the store of the spilled over blocks.
+#[derive(Clone, Default)]
+struct LiveChainLimits {
+    /// Maximum number of live blocks kept in memory.
+    max_blocks: Option<usize>,
+    /// Maximum size of the live blocks kept in memory, in bytes.
+    max_bytes: Option<u64>,
+    /// Store of the live blocks spilled over to disk.
+    spillover: Option<Arc<LiveChainSpillover>>,
+}
+
+impl LiveChainLimits {
+    /// Is the live chain bounded, so its oldest blocks can be evicted from memory.
+    fn is_bounded(&self) -> bool {
+        self.max_blocks.is_some() || self.max_bytes.is_some()
+    }
+
+    /// Does the live chain in memory exceed its bounds.
+    fn exceeded(&self, blocks: usize, bytes: u64) -> bool {
+        self.max_blocks.is_some_and(|max| blocks > max)
+            || self.max_bytes.is_some_and(|max| bytes > max)
+    }
+}
+
 /// Because we have multi-entry relationships in the live-chain protect it with a
 /// `read/write lock`. The underlying `SkipMap` is still capable of multiple simultaneous
 /// reads from multiple threads which is the most common access.
 #[derive(Clone)]
-struct ProtectedLiveChainBlockList(Arc<RwLock<LiveChainBlockList>>);
+struct ProtectedLiveChainBlockList {
+    /// Live blocks kept in memory.
+    blocks: Arc<RwLock<LiveChainBlockList>>,
+    /// Size of the live blocks kept in memory, in bytes.
+    memory_bytes: Arc<AtomicU64>,
+    /// Bounds of the live blocks kept in memory.
+    limits: Arc<RwLock<LiveChainLimits>>,
+}
+
+/// Entry of a live block, kept in memory or spilled over to disk.
+enum LiveEntry<'a> {
+    /// Block kept in memory.
+    Memory(MemoryEntry<'a>),
+    /// Block spilled over to disk.
+    Spilled(SpilledEntry<'a>),
+}
+
+/// The whole live chain, the spilled over blocks followed by the blocks kept in memory.
+struct LiveChainView<'a> {
+    /// Live blocks kept in memory.
+    memory: &'a LiveChainBlockList,
+    /// Live blocks spilled over to disk, if any.
+    spillover: Option<&'a LiveChainSpillover>,
+}
+
+impl<'a> LiveChainView<'a> {
+    /// Get the spilled over blocks.
+    fn spilled(&self) -> Option<&'a SkipMap<Point, SpilledBlock>> {
+        self.spillover.map(LiveChainSpillover::blocks)
+    }
+
+    /// Get the entry of the point.
+    fn get(&self, point: &Point) -> Option<LiveEntry<'a>> {
+        self.memory
+            .get(point)
+            .map(LiveEntry::Memory)
+            .or_else(|| self.spilled()?.get(point).map(LiveEntry::Spilled))
+    }
+
+    /// Get the entry of the latest block before the point.
+    fn before(&self, point: &Point) -> Option<LiveEntry<'a>> {
+        self.memory
+            .upper_bound(Bound::Excluded(point))
+            .map(LiveEntry::Memory)
+            .or_else(|| {
+                self.spilled()?
+                    .upper_bound(Bound::Excluded(point))
+                    .map(LiveEntry::Spilled)
+            })
+    }
+
+    /// Get the entry of the earliest block after the point.
+    fn after(&self, point: &Point) -> Option<LiveEntry<'a>> {
+        self.spilled()
+            .and_then(|spilled| spilled.lower_bound(Bound::Excluded(point)))
+            .map(LiveEntry::Spilled)
+            .or_else(|| {
+                self.memory
+                    .lower_bound(Bound::Excluded(point))
+                    .map(LiveEntry::Memory)
+            })
+    }
+
+    /// Get the entry of the latest block at or before the point.
+    fn at_or_before(&self, point: &Point) -> Option<LiveEntry<'a>> {
+        self.memory
+            .upper_bound(Bound::Included(point))
+            .map(LiveEntry::Memory)
+            .or_else(|| {
+                self.spilled()?
+                    .upper_bound(Bound::Included(point))
+                    .map(LiveEntry::Spilled)
+            })
+    }
+
+    /// Get the entry of the earliest block at or after the point.
+    fn at_or_after(&self, point: &Point) -> Option<LiveEntry<'a>> {
+        self.spilled()
+            .and_then(|spilled| spilled.lower_bound(Bound::Included(point)))
+            .map(LiveEntry::Spilled)
+            .or_else(|| {
+                self.memory
+                    .lower_bound(Bound::Included(point))
+                    .map(LiveEntry::Memory)
+            })
+    }
+
+    /// Get the entry of the earliest block.
+    fn front(&self) -> Option<LiveEntry<'a>> {
+        self.spilled()
+            .and_then(SkipMap::front)
+            .map(LiveEntry::Spilled)
+            .or_else(|| self.memory.front().map(LiveEntry::Memory))
+    }
+
+    /// Get the entry of the latest block.
+    fn back(&self) -> Option<LiveEntry<'a>> {
+        self.memory
+            .back()
+            .map(LiveEntry::Memory)
+            .or_else(|| self.spilled()?.back().map(LiveEntry::Spilled))
+    }
+
+    /// Get the entry of the block before the entry.
+    fn prev(&self, entry: &LiveEntry<'a>) -> Option<LiveEntry<'a>> {
+        match entry {
+            LiveEntry::Memory(entry) => {
+                entry
+                    .prev()
+                    .map(LiveEntry::Memory)
+                    .or_else(|| self.spilled()?.back().map(LiveEntry::Spilled))
+            },
+            LiveEntry::Spilled(entry) => entry.prev().map(LiveEntry::Spilled),
+        }
+    }
+
+    /// Get the entry of the block after the entry.
+    fn next(&self, entry: &LiveEntry<'a>) -> Option<LiveEntry<'a>> {
+        match entry {
+            LiveEntry::Memory(entry) => entry.next().map(LiveEntry::Memory),
+            LiveEntry::Spilled(entry) => {
+                entry
+                    .next()
+                    .map(LiveEntry::Spilled)
+                    .or_else(|| self.memory.front().map(LiveEntry::Memory))
+            },
+        }
+    }
+
+    /// Get the block of the entry, reading it from disk if it was spilled over.
+    fn block(&self, entry: &LiveEntry<'a>) -> Option<MultiEraBlock> {
+        match entry {
+            LiveEntry::Memory(entry) => Some(entry.value().clone()),
+            LiveEntry::Spilled(entry) => self.spillover?.load(entry),
+        }
+    }
+
+    /// Get the point of the entry.
+    fn point(entry: &LiveEntry<'a>) -> Point {
+        match entry {
+            LiveEntry::Memory(entry) => entry.value().point(),
+            LiveEntry::Spilled(entry) => entry.key().clone(),
+        }
+    }
+
+    /// Get the fork count of the entry.
+    fn fork(entry: &LiveEntry<'a>) -> u64 {
+        match entry {
+            LiveEntry::Memory(entry) => entry.value().fork(),
+            LiveEntry::Spilled(entry) => entry.value().fork(),
+        }
+    }
+
+    /// Get the number of live blocks.
+    fn len(&self) -> usize {
+        self.memory.len() + self.spillover.map_or(0, LiveChainSpillover::len)
+    }
+}
 
 /// Handle to the mithril sync thread. One for each Network ONLY.
 static LIVE_CHAINS: LazyLock<SkipMap<Network, ProtectedLiveChainBlockList>> = LazyLock::new(|| {
@@ -61,7 +263,109 @@ const DATA_RACE_BACKOFF_SECS: u64 = 2;
 impl ProtectedLiveChainBlockList {
     /// Create a new instance of the protected Live Chain skip map.
     fn new() -> Self {
-        ProtectedLiveChainBlockList(Arc::new(RwLock::new(LiveChainBlockList::new())))
+        ProtectedLiveChainBlockList {
+            blocks: Arc::new(RwLock::new(LiveChainBlockList::new())),
+            memory_bytes: Arc::new(AtomicU64::new(0)),
+            limits: Arc::new(RwLock::new(LiveChainLimits::default())),
+        }
+    }
+
+    /// Get the bounds of the live chain in effect.
+    fn limits(&self) -> LiveChainLimits {
+        self.limits
+            .read()
+            .map(|limits| limits.clone())
+            .unwrap_or_default()
+    }
+
+    /// Set the bounds of the live chain.
+    /// Blocks are only evicted as new blocks are added.
+    fn set_bounds(&self, chain: Network, bounds: &LiveChainBounds) -> Result<()> {
+        let spillover = match &bounds.spillover_path {
+            Some(path) => Some(Arc::new(LiveChainSpillover::new(chain, path)?)),
+            None => None,
+        };
+        let mut limits = self.limits.write().map_err(|_| Error::Internal)?;
+        if let Some(previous) = &limits.spillover {
+            previous.clear();
+        }
+        *limits = LiveChainLimits {
+            max_blocks: bounds.max_blocks,
+            max_bytes: bounds.max_bytes,
+            spillover,
+        };
+        Ok(())
+    }
+
+    /// Get the size of the live blocks kept in memory, in bytes.
+    fn memory_bytes(&self) -> u64 {
+        self.memory_bytes.load(Ordering::SeqCst)
+    }
+
+    /// Insert a block in memory, keeping track of the size of the live blocks in memory.
+    fn insert_block(&self, live_chain: &LiveChainBlockList, block: MultiEraBlock) {
+        let point = block.point();
+        if let Some(replaced) = live_chain.get(&point) {
+            self.removed_block(replaced.value());
+        }
+        self.memory_bytes
+            .fetch_add(block.raw().len() as u64, Ordering::SeqCst);
+        let _unused = live_chain.insert(point, block);
+    }
+
+    /// Account for a block removed from memory.
+    fn removed_block(&self, block: &MultiEraBlock) {
+        let size = block.raw().len() as u64;
+        let _unused = self
+            .memory_bytes
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |bytes| {
+                Some(bytes.saturating_sub(size))
+            });
+    }
+
+    /// Remove every live block kept in memory.
+    fn clear_memory(&self, live_chain: &LiveChainBlockList) {
+        live_chain.clear();
+        self.memory_bytes.store(0, Ordering::SeqCst);
+    }
+
+    /// Evict the oldest blocks from memory, until the live chain is within its bounds.
+    /// Evicted blocks are spilled over to disk, if configured. The tip is never evicted.
+    fn enforce_bounds(
+        &self, chain: Network, live_chain: &LiveChainBlockList, limits: &LiveChainLimits,
+    ) {
+        let mut evicted: u64 = 0;
+        let mut dropped: u64 = 0;
+
+        while live_chain.len() > 1 && limits.exceeded(live_chain.len(), self.memory_bytes()) {
+            let Some(entry) = live_chain.pop_front() else {
+                break;
+            };
+            let block = entry.value();
+            self.removed_block(block);
+            evicted += 1;
+
+            let Some(spillover) = &limits.spillover else {
+                dropped += 1;
+                continue;
+            };
+            if let Err(error) = spillover.spill(block) {
+                error!(chain = chain.to_string(), "{error}");
+                dropped += 1;
+            }
+        }
+
+        if evicted > 0 {
+            stats::live_blocks_evicted(chain, evicted, dropped);
+        }
+        stats::live_chain_size(
+            chain,
+            self.memory_bytes(),
+            limits
+                .spillover
+                .as_deref()
+                .map_or(0, LiveChainSpillover::len) as u64,
+        );
     }
 
     /// Get the `nth` Live block immediately following the specified block.
@@ -70,51 +374,59 @@ impl ProtectedLiveChainBlockList {
     /// 1+ = The block that follows the block after the requested point
     /// negative = The block before the requested point.
     fn get_block(&self, point: &Point, mut advance: i64, strict: bool) -> Option<MultiEraBlock> {
-        let chain = self.0.read().ok()?;
+        let chain = self.blocks.read().ok()?;
+        let limits = self.limits();
+        let view = LiveChainView {
+            memory: &chain,
+            spillover: limits.spillover.as_deref(),
+        };
 
         let mut this = if strict {
-            chain.get(point)?
+            view.get(point)?
         } else if advance < 0 {
             // This is a fuzzy lookup backwards.
             advance += 1;
-            chain.upper_bound(Bound::Excluded(point))?
+            view.before(point)?
         } else {
             // This is a fuzzy lookup forwards.
-            chain.lower_bound(Bound::Excluded(point))?
+            view.after(point)?
         };
 
         // If we are stepping backwards, look backwards.
         while advance < 0 {
             advance += 1;
-            this = this.prev()?;
+            this = view.prev(&this)?;
         }
 
         // If we are stepping forwards, look forwards.
         while advance > 0 {
             advance -= 1;
-            this = this.next()?;
+            this = view.next(&this)?;
         }
 
         // Return the block we found.
-        Some(this.value().clone())
+        view.block(&this)
     }
 
     /// Get the earliest block in the Live Chain
     fn get_earliest_block(&self) -> Option<MultiEraBlock> {
-        let chain = self.0.read().ok()?;
-        let entry = chain.front()?;
-        Some(entry.value().clone())
+        let chain = self.blocks.read().ok()?;
+        let limits = self.limits();
+        let view = LiveChainView {
+            memory: &chain,
+            spillover: limits.spillover.as_deref(),
+        };
+        view.block(&view.front()?)
     }
 
     /// Get the point of the first known block in the Live Chain.
-    fn get_first_live_point(live_chain: &LiveChainBlockList) -> Result<Point> {
-        let Some(check_first_live_entry) = live_chain.front() else {
+    fn get_first_live_point(view: &LiveChainView) -> Result<Point> {
+        let Some(check_first_live_entry) = view.front() else {
             return Err(Error::LiveSync(
                 "First Block not found in the Live Chain during Backfill".to_string(),
             ));
         };
-        let check_first_live_block = check_first_live_entry.value();
-        Ok(check_first_live_block.point())
+        Ok(LiveChainView::point(&check_first_live_entry))
     }
 
     /// Get the point of the first known block in the Live Chain.
@@ -136,7 +448,12 @@ impl ProtectedLiveChainBlockList {
     /// Note: This last condition is NOT enforced, but must be met or block chain
     /// iteration will fail.
     fn backfill(&self, chain: Network, blocks: &[MultiEraBlock]) -> Result<()> {
-        let live_chain = self.0.write().map_err(|_| Error::Internal)?;
+        let live_chain = self.blocks.write().map_err(|_| Error::Internal)?;
+        let limits = self.limits();
+        let view = LiveChainView {
+            memory: &live_chain,
+            spillover: limits.spillover.as_deref(),
+        };
 
         // Make sure our first live block == the last mithril tip.
         // Ensures we are properly connected to the Mithril Chain.
@@ -152,7 +469,7 @@ impl ProtectedLiveChainBlockList {
         }
 
         // Get the current Oldest block in the live chain.
-        let check_first_live_point = Self::get_first_live_point(&live_chain)?;
+        let check_first_live_point = Self::get_first_live_point(&view)?;
 
         let last_backfill_block = blocks
             .last()
@@ -168,10 +485,25 @@ impl ProtectedLiveChainBlockList {
             )));
         }
 
-        // SkipMap is thread-safe, so we can parallel iterate inserting the blocks.
-        blocks.par_iter().for_each(|block| {
-            let _unused = live_chain.insert(block.point(), block.clone());
-        });
+        match &limits.spillover {
+            // Blocks already spilled over are newer than the backfill, so the backfill is
+            // spilled over too, to keep them in order. The last block is already known.
+            Some(spillover) if spillover.len() > 0 => {
+                for block in blocks
+                    .iter()
+                    .filter(|block| **block < check_first_live_point)
+                {
+                    spillover.spill(block)?;
+                }
+            },
+            _ => {
+                // SkipMap is thread-safe, so we can parallel iterate inserting the blocks.
+                blocks.par_iter().for_each(|block| {
+                    self.insert_block(&live_chain, block.clone());
+                });
+                self.enforce_bounds(chain, &live_chain, &limits);
+            },
+        }
 
         // End of Successful backfill == Reaching TIP, because live sync is always at tip.
         stats::tip_reached(chain);
@@ -181,9 +513,9 @@ impl ProtectedLiveChainBlockList {
 
     /// Check if the given point is strictly in the live-chain.  This means the slot and
     /// Hash MUST be present.
-    fn strict_block_lookup(live_chain: &LiveChainBlockList, point: &Point) -> bool {
-        if let Some(found_block) = live_chain.get(point) {
-            return found_block.value().point().strict_eq(point);
+    fn strict_block_lookup(view: &LiveChainView, point: &Point) -> bool {
+        if let Some(found_block) = view.get(point) {
+            return LiveChainView::point(&found_block).strict_eq(point);
         }
         false
     }
@@ -194,7 +526,12 @@ impl ProtectedLiveChainBlockList {
     fn add_block_to_tip(
         &self, chain: Network, block: MultiEraBlock, fork_count: &mut u64, tip: Point,
     ) -> Result<()> {
-        let live_chain = self.0.write().map_err(|_| Error::Internal)?;
+        let live_chain = self.blocks.write().map_err(|_| Error::Internal)?;
+        let limits = self.limits();
+        let view = LiveChainView {
+            memory: &live_chain,
+            spillover: limits.spillover.as_deref(),
+        };
 
         // Check if the insert is the next logical block in the live chain.
         // Most likely case, so check it first.
@@ -214,15 +551,37 @@ impl ProtectedLiveChainBlockList {
             // Also check the point we want to link to actually exists.  If either are not true,
             // Then we could be trying to roll back to an earlier block than our earliest known
             // block.
-            let check_first_live_point = Self::get_first_live_point(&live_chain)?;
+            let check_first_live_point = Self::get_first_live_point(&view)?;
             if (block.point() < check_first_live_point)
-                || !Self::strict_block_lookup(&live_chain, &previous_point)
+                || !Self::strict_block_lookup(&view, &previous_point)
             {
                 debug!("Rollback before live chain, clear it.");
                 // We rolled back earlier than the current live chain.
                 // Purge the entire chain, and just add this one block as the new tip.
+                rollback_size = view.len() as u64;
+                self.clear_memory(&live_chain);
+                if let Some(spillover) = &limits.spillover {
+                    spillover.clear();
+                }
+            } else if let (None, Some(spillover)) =
+                (live_chain.get(&previous_point), &limits.spillover)
+            {
+                debug!("Rollback into the spilled over live chain.");
+                // Every block in memory is after the previous block, so purge them all,
+                // and the spilled blocks after it.
+                // Then bring the previous block back to memory, as the head of the chain.
                 rollback_size = live_chain.len() as u64;
-                live_chain.clear();
+                self.clear_memory(&live_chain);
+                while let Some(entry) = spillover.blocks().back() {
+                    if entry.key().strict_eq(&previous_point) {
+                        if let Some(previous_block) = spillover.unspill(&entry) {
+                            self.insert_block(&live_chain, previous_block);
+                        }
+                        break;
+                    }
+                    LiveChainSpillover::remove(&entry);
+                    rollback_size += 1;
+                }
             } else {
                 // If we get here we know for a fact that the previous block exists.
                 // Remove the latest live block, and keep removing it until we re-establish
@@ -230,6 +589,7 @@ impl ProtectedLiveChainBlockList {
                 // We search backwards because a rollback is more likely in the newest blocks than
                 // the oldest.
                 while let Some(popped) = live_chain.pop_back() {
+                    self.removed_block(popped.value());
                     rollback_size += 1;
                     if previous_point.strict_eq(&popped.value().previous()) {
                         // We are now contiguous, so stop purging.
@@ -246,13 +606,21 @@ impl ProtectedLiveChainBlockList {
         let head_slot = block.point().slot_or_default();
 
         // Add the block to the tip of the Live Chain.
-        let _unused = live_chain.insert(block.point(), block);
+        self.insert_block(&live_chain, block);
 
         let tip_slot = tip.slot_or_default();
         update_peer_tip(chain, tip);
 
+        // Keep the live chain within its bounds.
+        self.enforce_bounds(chain, &live_chain, &limits);
+
         // Record the new live chain stats after we add a new block.
-        stats::new_live_block(chain, live_chain.len() as u64, head_slot, tip_slot);
+        let total_live_blocks = live_chain.len()
+            + limits
+                .spillover
+                .as_deref()
+                .map_or(0, LiveChainSpillover::len);
+        stats::new_live_block(chain, total_live_blocks as u64, head_slot, tip_slot);
 
         Ok(())
     }
@@ -274,12 +642,39 @@ impl ProtectedLiveChainBlockList {
             }
         }
 
-        let live_chain = self.0.write().map_err(|_| Error::Internal)?;
+        let live_chain = self.blocks.write().map_err(|_| Error::Internal)?;
+        let limits = self.limits();
 
         // Special Case.
         // If the Purge Point == TIP_POINT, then we purge the entire chain.
         if *point == TIP_POINT {
-            live_chain.clear();
+            self.clear_memory(&live_chain);
+            if let Some(spillover) = &limits.spillover {
+                spillover.clear();
+            }
+        } else if live_chain.get(point).is_none() {
+            // The block we want to purge upto was evicted from memory.
+            // It is spilled over, or was dropped because there is no spillover.
+            let evicted = limits.is_bounded()
+                && live_chain
+                    .front()
+                    .is_some_and(|first_block| first_block.value().point() > *point);
+            if let Some(spillover) = &limits.spillover {
+                let spilled = spillover
+                    .blocks()
+                    .get(point)
+                    .is_some_and(|entry| entry.key().strict_eq(point));
+                if !spilled && !evicted {
+                    return Err(Error::LiveSync(format!(
+                        "The block to purge to {point} is not in the Live chain."
+                    )));
+                }
+                spillover.purge_before(point);
+            } else if !evicted {
+                return Err(Error::LiveSync(format!(
+                    "The block to purge to {point} is not in the Live chain."
+                )));
+            }
         } else {
             // If the block we want to purge upto must be in the chain.
             let Some(purge_start_block_entry) = live_chain.get(point) else {
@@ -297,9 +692,15 @@ impl ProtectedLiveChainBlockList {
 
             // Purge every block prior to the purge point.
             while let Some(previous_block) = purge_start_block_entry.prev() {
+                self.removed_block(previous_block.value());
                 let _unused = previous_block.remove();
             }
 
+            // Spilled over blocks are all older than the blocks in memory.
+            if let Some(spillover) = &limits.spillover {
+                spillover.clear();
+            }
+
             // Try and FORCE the skip map to reclaim its memory
             crossbeam_epoch::pin().flush();
             crossbeam_epoch::pin().flush();
@@ -308,10 +709,16 @@ impl ProtectedLiveChainBlockList {
         Ok(())
     }
 
-    /// Get the current number of blocks in the live chain
+    /// Get the current number of blocks in the live chain, including the spilled over
+    /// blocks.
     fn len(&self) -> usize {
-        if let Ok(chain) = self.0.read() {
-            chain.len()
+        if let Ok(chain) = self.blocks.read() {
+            let limits = self.limits();
+            LiveChainView {
+                memory: &chain,
+                spillover: limits.spillover.as_deref(),
+            }
+            .len()
         } else {
             0
         }
@@ -321,18 +728,24 @@ impl ProtectedLiveChainBlockList {
     fn get_intersect_points(&self) -> Vec<pallas::network::miniprotocols::Point> {
         let mut intersect_points = Vec::new();
 
-        let Ok(chain) = self.0.read() else {
+        let Ok(chain) = self.blocks.read() else {
             return intersect_points;
         };
+        let limits = self.limits();
+        let view = LiveChainView {
+            memory: &chain,
+            spillover: limits.spillover.as_deref(),
+        };
 
-        // Add the top 4 blocks as the first points to intersect.
-        let Some(entry) = chain.back() else {
+        // Add the top 3 blocks as the first points to intersect.
+        let Some(mut entry) = view.back() else {
             return intersect_points;
         };
-        intersect_points.push(entry.value().point().into());
+        intersect_points.push(LiveChainView::point(&entry).into());
         for _ in 0..2 {
-            if let Some(entry) = entry.prev() {
-                intersect_points.push(entry.value().point().into());
+            if let Some(previous) = view.prev(&entry) {
+                intersect_points.push(LiveChainView::point(&previous).into());
+                entry = previous;
             } else {
                 return intersect_points;
             };
@@ -340,19 +753,20 @@ impl ProtectedLiveChainBlockList {
 
         // Now find points based on an every increasing Slot age.
         let mut slot_age: u64 = 40;
-        let reference_slot = entry.value().point().slot_or_default();
-        let mut previous_point = entry.value().point();
+        let mut previous_point = LiveChainView::point(&entry);
+        let reference_slot = previous_point.slot_or_default();
 
         // Loop until we exhaust probe slots, OR we would step past genesis.
         while slot_age < reference_slot {
             let ref_point = Point::fuzzy(reference_slot - slot_age);
-            let Some(entry) = chain.lower_bound(Bound::Included(&ref_point)) else {
+            let Some(entry) = view.at_or_after(&ref_point) else {
                 break;
             };
-            if entry.value().point() == previous_point {
+            let point = LiveChainView::point(&entry);
+            if point == previous_point {
                 break;
             };
-            previous_point = entry.value().point();
+            previous_point = point;
             intersect_points.push(previous_point.clone().into());
             slot_age *= 2;
         }
@@ -366,38 +780,38 @@ impl ProtectedLiveChainBlockList {
         &self, point: &Point, previous_point: &Point, fork: u64,
     ) -> Option<(MultiEraBlock, u64)> {
         let mut rollback_depth: u64 = 0;
-        let Ok(chain) = self.0.read() else {
+        let Ok(chain) = self.blocks.read() else {
             return None;
         };
+        let limits = self.limits();
+        let view = LiveChainView {
+            memory: &chain,
+            spillover: limits.spillover.as_deref(),
+        };
 
         // Get the block <= the current slot.
         let ref_point = Point::fuzzy(point.slot_or_default());
-        let mut entry = chain.upper_bound(Bound::Included(&ref_point))?;
+        let mut entry = view.at_or_before(&ref_point)?;
 
-        let mut this_block = entry.value().clone();
         // Check if the previous block is the one we previously knew, and if so, thats the best
         // block.
-        if this_block.point().strict_eq(previous_point) {
-            return Some((this_block, rollback_depth));
+        if LiveChainView::point(&entry).strict_eq(previous_point) {
+            return Some((view.block(&entry)?, rollback_depth));
         }
 
         // Search backwards for a fork smaller than or equal to the one we know.
-        while this_block.fork() > fork {
+        // Only the block found is read, if it was spilled over.
+        while LiveChainView::fork(&entry) > fork {
             rollback_depth += 1;
-            entry = match entry.prev() {
-                Some(entry) => entry,
-                None => return None,
-            };
-
-            this_block = entry.value().clone();
+            entry = view.prev(&entry)?;
         }
 
-        Some((this_block, rollback_depth))
+        Some((view.block(&entry)?, rollback_depth))
     }
 
     /// Get the point of the block at the head of the live chain.
     fn get_live_head_point(&self) -> Option<Point> {
-        let live_chain = self.0.read().map_err(|_| Error::Internal).ok()?;
+        let live_chain = self.blocks.read().map_err(|_| Error::Internal).ok()?;
 
         let head_point = Self::get_last_live_point(&live_chain);
         if head_point == UNKNOWN_POINT {
@@ -436,6 +850,13 @@ fn get_live_chain(chain: Network) -> ProtectedLiveChainBlockList {
     value.clone()
 }
 
+/// Set the bounds of the live chain of the network.
+/// Blocks spilled over by a previous configuration are removed.
+pub(crate) fn set_live_chain_bounds(chain: Network, bounds: &LiveChainBounds) -> Result<()> {
+    let live_chain = get_live_chain(chain);
+    live_chain.set_bounds(chain, bounds)
+}
+
 /// Get the head `Point` currently in the live chain.
 pub(crate) fn get_live_head_point(chain: Network) -> Option<Point> {
     let live_chain = get_live_chain(chain);
@@ -514,3 +935,228 @@ pub(crate) fn find_best_fork_block(
     let live_chain = get_live_chain(chain);
     live_chain.find_best_fork_block(point, previous_point, fork)
 }
+
+/// Insert a block into the live chain, without checking that it links to the chain.
+#[cfg(test)]
+pub(crate) fn live_chain_insert_block(chain: Network, block: MultiEraBlock) {
+    let live_chain = get_live_chain(chain);
+    if let Ok(blocks) = live_chain.blocks.write() {
+        live_chain.insert_block(&blocks, block);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{
+        mithril_snapshot_data::update_latest_mithril_snapshot,
+        multi_era_block_data::tests::{allegra_block, babbage_block, mary_block, shelley_block},
+        point::ORIGIN_POINT,
+        snapshot_id::SnapshotId,
+    };
+
+    /// Test blocks, in chain order.
+    fn test_blocks() -> Vec<MultiEraBlock> {
+        [
+            shelley_block(),
+            allegra_block(),
+            mary_block(),
+            babbage_block(),
+        ]
+        .into_iter()
+        .map(|raw| MultiEraBlock::new(Network::Mainnet, raw, &ORIGIN_POINT, 1).unwrap())
+        .collect()
+    }
+
+    /// Size of the blocks, in bytes.
+    fn size(blocks: &[MultiEraBlock]) -> u64 {
+        blocks.iter().map(|block| block.raw().len() as u64).sum()
+    }
+
+    /// Live chain of the blocks, keeping `max_blocks` in memory and spilling over the
+    /// others to `path`.
+    fn bounded_live_chain(
+        chain: Network, path: &Path, max_blocks: usize, blocks: &[MultiEraBlock],
+    ) -> ProtectedLiveChainBlockList {
+        let live_chain = ProtectedLiveChainBlockList::new();
+        live_chain
+            .set_bounds(chain, &LiveChainBounds {
+                max_blocks: Some(max_blocks),
+                max_bytes: None,
+                spillover_path: Some(path.to_path_buf()),
+            })
+            .unwrap();
+        let limits = live_chain.limits();
+        let memory = live_chain.blocks.write().unwrap();
+        for block in blocks {
+            live_chain.insert_block(&memory, block.clone());
+            live_chain.enforce_bounds(chain, &memory, &limits);
+        }
+        drop(memory);
+        live_chain
+    }
+
+    /// Number of blocks spilled over.
+    fn spilled(live_chain: &ProtectedLiveChainBlockList) -> usize {
+        live_chain
+            .limits()
+            .spillover
+            .as_deref()
+            .map_or(0, LiveChainSpillover::len)
+    }
+
+    /// Set the tip of the latest mithril snapshot.
+    fn set_mithril_tip(chain: Network, tip: Point) {
+        let snapshot_id =
+            SnapshotId::new(Path::new("test_data/test_snapshot_id/12345"), tip).unwrap();
+        update_latest_mithril_snapshot(chain, snapshot_id);
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    fn test_enforce_bounds() {
+        let path = std::env::temp_dir().join("live_chain_enforce_bounds_test");
+        let mut blocks = test_blocks();
+        blocks[0].set_fork(0);
+        let live_chain = bounded_live_chain(Network::Mainnet, &path, 2, &blocks);
+
+        // The oldest blocks are spilled over, and can still be read.
+        assert_eq!(live_chain.len(), 4);
+        assert_eq!(spilled(&live_chain), 2);
+        assert_eq!(live_chain.memory_bytes(), size(&blocks[2..]));
+        assert_eq!(
+            live_chain.get_block(&blocks[0].point(), 0, true).unwrap(),
+            blocks[0]
+        );
+        assert_eq!(
+            live_chain.get_block(&blocks[3].point(), -2, true).unwrap(),
+            blocks[1]
+        );
+
+        // Intersect points and forks are found in the spilled over blocks.
+        let intersect_points: Vec<pallas::network::miniprotocols::Point> = blocks[1..]
+            .iter()
+            .rev()
+            .map(|block| block.point().into())
+            .collect();
+        assert_eq!(live_chain.get_intersect_points(), intersect_points);
+        let (block, rollback_depth) = live_chain
+            .find_best_fork_block(&blocks[1].point(), &blocks[1].point(), 1)
+            .unwrap();
+        assert_eq!((block, rollback_depth), (blocks[1].clone(), 0));
+        let (block, rollback_depth) = live_chain
+            .find_best_fork_block(&blocks[2].point(), &UNKNOWN_POINT, 0)
+            .unwrap();
+        assert_eq!((block, rollback_depth), (blocks[0].clone(), 2));
+
+        // Without a spillover, evicted blocks are dropped.
+        let limits = LiveChainBounds {
+            max_bytes: Some(size(&blocks[3..])),
+            ..LiveChainBounds::default()
+        };
+        live_chain.set_bounds(Network::Mainnet, &limits).unwrap();
+        let memory = live_chain.blocks.write().unwrap();
+        live_chain.enforce_bounds(Network::Mainnet, &memory, &live_chain.limits());
+        drop(memory);
+        assert_eq!(live_chain.len(), 1);
+        assert_eq!(live_chain.memory_bytes(), size(&blocks[3..]));
+        assert_eq!(live_chain.get_live_head_point().unwrap(), blocks[3].point());
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    fn test_rollback_into_spillover() {
+        let path = std::env::temp_dir().join("live_chain_rollback_test");
+        let blocks = test_blocks();
+        let live_chain = bounded_live_chain(Network::Mainnet, &path, 2, &blocks);
+
+        // A new block following the oldest spilled block rolls the chain back to it.
+        let mut block =
+            MultiEraBlock::new(Network::Mainnet, mary_block(), &ORIGIN_POINT, 1).unwrap();
+        block.set_previous(&blocks[0].point());
+        let mut fork_count = 1;
+        live_chain
+            .add_block_to_tip(
+                Network::Mainnet,
+                block.clone(),
+                &mut fork_count,
+                block.point(),
+            )
+            .unwrap();
+
+        assert_eq!(fork_count, 2);
+        assert_eq!(live_chain.len(), 2);
+        assert_eq!(spilled(&live_chain), 0);
+        assert_eq!(
+            live_chain.memory_bytes(),
+            size(&[blocks[0].clone(), block.clone()])
+        );
+        assert_eq!(
+            live_chain.get_block(&block.point(), -1, true).unwrap(),
+            blocks[0]
+        );
+        assert_eq!(live_chain.get_live_head_point().unwrap(), block.point());
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    fn test_purge_through_spillover() {
+        let path = std::env::temp_dir().join("live_chain_purge_test");
+        let blocks = test_blocks();
+        let live_chain = bounded_live_chain(Network::Mainnet, &path, 1, &blocks);
+        assert_eq!(spilled(&live_chain), 3);
+
+        // Purge up to a spilled block.
+        set_mithril_tip(Network::Mainnet, blocks[1].point());
+        live_chain
+            .purge(Network::Mainnet, &blocks[1].point())
+            .unwrap();
+        assert_eq!(live_chain.len(), 3);
+        assert_eq!(spilled(&live_chain), 2);
+        assert_eq!(
+            live_chain.get_earliest_block().unwrap().point(),
+            blocks[1].point()
+        );
+
+        // Purge up to a block in memory, removes every spilled block.
+        set_mithril_tip(Network::Mainnet, blocks[3].point());
+        live_chain
+            .purge(Network::Mainnet, &blocks[3].point())
+            .unwrap();
+        assert_eq!(live_chain.len(), 1);
+        assert_eq!(spilled(&live_chain), 0);
+        assert_eq!(live_chain.memory_bytes(), size(&blocks[3..]));
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    fn test_backfill_into_spillover() {
+        let path = std::env::temp_dir().join("live_chain_backfill_test");
+        let blocks = test_blocks();
+        let live_chain = bounded_live_chain(Network::Preprod, &path, 1, &blocks[2..]);
+        assert_eq!(spilled(&live_chain), 1);
+
+        // The backfill is older than the spilled blocks, so it is spilled over too.
+        set_mithril_tip(Network::Preprod, blocks[0].point());
+        live_chain.backfill(Network::Preprod, &blocks[..3]).unwrap();
+        assert_eq!(live_chain.len(), 4);
+        assert_eq!(spilled(&live_chain), 3);
+        assert_eq!(live_chain.memory_bytes(), size(&blocks[3..]));
+        for (index, block) in blocks.iter().enumerate().skip(1) {
+            assert_eq!(
+                live_chain.get_block(&block.point(), -1, true).unwrap(),
+                blocks[index - 1]
+            );
+        }
+
+        std::fs::remove_dir_all(path).unwrap();
+    }
+}
@@ -0,0 +1,195 @@
+//! Disk spillover of the Live Chain.
+//!
+//! When the live chain exceeds its configured bounds, its oldest blocks are evicted from
+//! memory. If a spillover path is configured, the evicted blocks are written to disk, so
+//! they can still be followed until the next Immutable update purges them.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crossbeam_skiplist::{map::Entry, SkipMap};
+use tracing::error;
+
+use crate::{
+    error::{Error, Result},
+    MultiEraBlock, Network, Point,
+};
+
+/// A live block spilled over to disk. Only the data needed to rebuild the block is kept
+/// in memory.
+pub(crate) struct SpilledBlock {
+    /// Point of the previous block.
+    previous: Point,
+    /// Fork the block was received on.
+    fork: u64,
+    /// File the raw block is written to.
+    path: PathBuf,
+}
+
+impl SpilledBlock {
+    /// Fork the block was received on.
+    pub(crate) fn fork(&self) -> u64 {
+        self.fork
+    }
+}
+
+/// Entry of a spilled block, ordered by its point.
+pub(crate) type SpilledEntry<'a> = Entry<'a, Point, SpilledBlock>;
+
+/// The live blocks of a network spilled over to disk.
+/// Spilled blocks are always older than the blocks kept in memory.
+pub(crate) struct LiveChainSpillover {
+    /// Network of the live chain.
+    chain: Network,
+    /// Directory the blocks are written to.
+    path: PathBuf,
+    /// The spilled blocks.
+    blocks: SkipMap<Point, SpilledBlock>,
+}
+
+impl LiveChainSpillover {
+    /// Create the spillover store of the network, in a sub-directory of `path`.
+    /// Blocks spilled by a previous run are removed, as the live chain is rebuilt on
+    /// start.
+    pub(crate) fn new(chain: Network, path: &Path) -> Result<Self> {
+        let path = path.join(chain.to_string());
+        if path.exists() {
+            fs::remove_dir_all(&path).map_err(|error| {
+                Error::LiveSync(format!(
+                    "Failed to clear the Live Chain spillover path {}: {error}",
+                    path.display()
+                ))
+            })?;
+        }
+        fs::create_dir_all(&path).map_err(|error| {
+            Error::LiveSync(format!(
+                "Failed to create the Live Chain spillover path {}: {error}",
+                path.display()
+            ))
+        })?;
+
+        Ok(Self {
+            chain,
+            path,
+            blocks: SkipMap::new(),
+        })
+    }
+
+    /// Get the spilled blocks.
+    pub(crate) fn blocks(&self) -> &SkipMap<Point, SpilledBlock> {
+        &self.blocks
+    }
+
+    /// Get the number of spilled blocks.
+    pub(crate) fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Write the block to disk.
+    pub(crate) fn spill(&self, block: &MultiEraBlock) -> Result<()> {
+        let point = block.point();
+        let path = self.path.join(format!(
+            "{}-{}.block",
+            point.slot_or_default(),
+            hex::encode(point.hash_or_default())
+        ));
+        fs::write(&path, block.raw()).map_err(|error| {
+            Error::LiveSync(format!(
+                "Failed to spill over Live Block {point} to {}: {error}",
+                path.display()
+            ))
+        })?;
+
+        let _unused = self.blocks.insert(point, SpilledBlock {
+            previous: block.previous(),
+            fork: block.fork(),
+            path,
+        });
+        Ok(())
+    }
+
+    /// Read the spilled block from disk.
+    pub(crate) fn load(&self, entry: &SpilledEntry) -> Option<MultiEraBlock> {
+        let spilled = entry.value();
+        let raw = match fs::read(&spilled.path) {
+            Ok(raw) => raw,
+            Err(error) => {
+                error!(
+                    chain = self.chain.to_string(),
+                    "Failed to read spilled Live Block {}: {error}",
+                    entry.key()
+                );
+                return None;
+            },
+        };
+        MultiEraBlock::new(self.chain, raw, &spilled.previous, spilled.fork).ok()
+    }
+
+    /// Remove the spilled block, and its file.
+    pub(crate) fn remove(entry: &SpilledEntry) {
+        // The block can not be followed anymore, so a left over file is harmless.
+        let _unused = fs::remove_file(&entry.value().path);
+        let _unused = entry.remove();
+    }
+
+    /// Remove the spilled block, and read it back from disk.
+    pub(crate) fn unspill(&self, entry: &SpilledEntry) -> Option<MultiEraBlock> {
+        let block = self.load(entry);
+        Self::remove(entry);
+        block
+    }
+
+    /// Remove every spilled block older than the point.
+    pub(crate) fn purge_before(&self, point: &Point) {
+        while let Some(entry) = self.blocks.front() {
+            if entry.key() >= point {
+                break;
+            }
+            Self::remove(&entry);
+        }
+    }
+
+    /// Remove every spilled block.
+    pub(crate) fn clear(&self) {
+        while let Some(entry) = self.blocks.front() {
+            Self::remove(&entry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{multi_era_block_data::tests::babbage_block, point::ORIGIN_POINT};
+
+    #[test]
+    fn spill_and_load() {
+        let path = std::env::temp_dir().join("live_chain_spillover_test");
+        let spillover = LiveChainSpillover::new(Network::Preprod, &path).unwrap();
+        let block =
+            MultiEraBlock::new(Network::Preprod, babbage_block(), &ORIGIN_POINT, 1).unwrap();
+
+        spillover.spill(&block).unwrap();
+        assert_eq!(spillover.len(), 1);
+        let entry = spillover.blocks().front().unwrap();
+        assert_eq!(spillover.load(&entry).unwrap(), block);
+
+        assert_eq!(spillover.unspill(&entry).unwrap(), block);
+        assert_eq!(spillover.len(), 0);
+        assert_eq!(
+            fs::read_dir(path.join(Network::Preprod.to_string()))
+                .unwrap()
+                .count(),
+            0
+        );
+
+        spillover.spill(&block).unwrap();
+        spillover.purge_before(&block.point());
+        assert_eq!(spillover.len(), 1);
+        spillover.clear();
+        assert_eq!(spillover.len(), 0);
+        fs::remove_dir_all(path).unwrap();
+    }
+}
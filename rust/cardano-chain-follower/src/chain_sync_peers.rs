@@ -0,0 +1,173 @@
+//! Health tracking of the relay peers used by the chain sync.
+//!
+//! Chain sync can be configured with multiple relays. Each relay has a health score which
+//! drops every time it fails and recovers every time it works, so that chain sync always
+//! fails over to the healthiest relay available.
+
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use tracing::error;
+
+use crate::{stats, Network};
+
+/// The health score of a relay that has never failed.
+const MAX_PEER_SCORE: u32 = 100;
+
+/// How much the health score drops when a relay fails.
+const PEER_FAILURE_PENALTY: u32 = 25;
+
+/// How much the health score recovers when a relay works.
+const PEER_SUCCESS_REWARD: u32 = 5;
+
+/// Health of a single relay peer.
+#[derive(Debug, Clone)]
+struct PeerHealth {
+    /// Address of the relay.
+    address: String,
+    /// Current health score, from 0 up to `MAX_PEER_SCORE`.
+    score: u32,
+    /// How long the last successful connection took.
+    latency: Option<Duration>,
+}
+
+/// The set of relay peers of a chain, shared between the chain sync tasks.
+#[derive(Debug, Clone)]
+pub(crate) struct PeerSet {
+    /// Chain the relays belong to.
+    chain: Network,
+    /// Health of all the relays, in configuration order.
+    peers: Arc<RwLock<Vec<PeerHealth>>>,
+}
+
+impl PeerSet {
+    /// Create a new set of relay peers, all starting as fully healthy.
+    pub(crate) fn new(chain: Network, addresses: &[String]) -> Self {
+        let peers = addresses
+            .iter()
+            .map(|address| {
+                PeerHealth {
+                    address: address.clone(),
+                    score: MAX_PEER_SCORE,
+                    latency: None,
+                }
+            })
+            .collect();
+
+        stats::peers_configured(chain, addresses, MAX_PEER_SCORE);
+
+        Self {
+            chain,
+            peers: Arc::new(RwLock::new(peers)),
+        }
+    }
+
+    /// The relay addresses, healthiest first.
+    ///
+    /// Relays of equal health are ordered by the latency of their last connection, and
+    /// then by their configuration order.
+    pub(crate) fn by_health(&self) -> Vec<String> {
+        let Ok(peers) = self.peers.read() else {
+            error!("Peer health RwLock should never be able to error.");
+            return Vec::new();
+        };
+
+        let mut peers = peers.clone();
+        // Stable sort, so configuration order is kept for otherwise equal relays.
+        peers.sort_by(|a, b| {
+            b.score.cmp(&a.score).then_with(|| {
+                a.latency
+                    .unwrap_or(Duration::MAX)
+                    .cmp(&b.latency.unwrap_or(Duration::MAX))
+            })
+        });
+        peers.into_iter().map(|peer| peer.address).collect()
+    }
+
+    /// Record that a relay worked, and how long it took to connect to it.
+    pub(crate) fn success(&self, address: &str, latency: Option<Duration>) {
+        self.update(address, |peer| {
+            peer.score = peer
+                .score
+                .saturating_add(PEER_SUCCESS_REWARD)
+                .min(MAX_PEER_SCORE);
+            if latency.is_some() {
+                peer.latency = latency;
+            }
+        });
+    }
+
+    /// Record that a relay failed to connect or sync.
+    pub(crate) fn failure(&self, address: &str) {
+        self.update(address, |peer| {
+            peer.score = peer.score.saturating_sub(PEER_FAILURE_PENALTY);
+        });
+    }
+
+    /// Update the health of a relay, and publish it to the statistics.
+    fn update(&self, address: &str, update: impl FnOnce(&mut PeerHealth)) {
+        let Ok(mut peers) = self.peers.write() else {
+            error!("Peer health RwLock should never be able to error.");
+            return;
+        };
+
+        let Some(peer) = peers.iter_mut().find(|peer| peer.address == address) else {
+            error!("Unknown relay peer {address} for {}", self.chain);
+            return;
+        };
+
+        let previous_score = peer.score;
+        update(peer);
+        stats::peer_health(
+            self.chain,
+            address,
+            peer.score,
+            peer.score < previous_score,
+            peer.latency,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_failover_order() {
+        let addresses = vec![
+            "relay-a:3001".to_string(),
+            "relay-b:3001".to_string(),
+            "relay-c:3001".to_string(),
+        ];
+        let peers = PeerSet::new(Network::Preview, &addresses);
+        assert_eq!(peers.by_health(), addresses);
+
+        // A failing relay is moved behind the healthy ones.
+        peers.failure("relay-a:3001");
+        assert_eq!(peers.by_health(), vec![
+            "relay-b:3001".to_string(),
+            "relay-c:3001".to_string(),
+            "relay-a:3001".to_string(),
+        ]);
+
+        // Equally healthy relays are ordered by latency.
+        peers.success("relay-c:3001", Some(Duration::from_millis(10)));
+        peers.success("relay-b:3001", Some(Duration::from_millis(50)));
+        assert_eq!(peers.by_health(), vec![
+            "relay-c:3001".to_string(),
+            "relay-b:3001".to_string(),
+            "relay-a:3001".to_string(),
+        ]);
+
+        // Health recovers when the relay works again.
+        for _ in 0..(PEER_FAILURE_PENALTY / PEER_SUCCESS_REWARD) {
+            peers.success("relay-a:3001", None);
+        }
+        assert_eq!(peers.by_health().last(), Some(&"relay-a:3001".to_string()));
+        peers.failure("relay-b:3001");
+        peers.failure("relay-c:3001");
+        assert_eq!(peers.by_health().first(), Some(&"relay-a:3001".to_string()));
+    }
+}
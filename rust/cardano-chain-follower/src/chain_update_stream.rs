@@ -0,0 +1,119 @@
+//! `futures::Stream` of the chain updates of a follower.
+//!
+//! `ChainFollower::next` borrows the follower for the whole await, which does not fit
+//! `Stream::poll_next`. `ChainUpdateStream` moves the follower into the pending `next`
+//! future and takes it back once the update is ready, so the next update is only
+//! requested when the consumer polls for it, and the consumer is woken by the follower
+//! itself rather than by polling.
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{future::BoxFuture, FutureExt, Stream};
+
+use crate::{ChainFollower, ChainUpdate};
+
+/// The state of the stream.
+enum State {
+    /// Waiting to be polled for the next update.
+    Idle(ChainFollower),
+    /// Waiting for the next update of the follower.
+    Pending(BoxFuture<'static, (ChainFollower, Option<ChainUpdate>)>),
+    /// The follower has no updates left.
+    Done,
+}
+
+/// A stream of the chain updates of a follower.
+pub struct ChainUpdateStream {
+    /// The current state of the stream.
+    state: State,
+}
+
+impl ChainUpdateStream {
+    /// Create a stream of the chain updates of the follower.
+    #[must_use]
+    pub(crate) fn new(follower: ChainFollower) -> Self {
+        Self {
+            state: State::Idle(follower),
+        }
+    }
+}
+
+impl Stream for ChainUpdateStream {
+    type Item = ChainUpdate;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match std::mem::replace(&mut self.state, State::Done) {
+                State::Idle(mut follower) => {
+                    self.state = State::Pending(
+                        async move {
+                            let update = follower.next().await;
+                            (follower, update)
+                        }
+                        .boxed(),
+                    );
+                },
+                State::Pending(mut next) => {
+                    match next.poll_unpin(cx) {
+                        Poll::Ready((follower, Some(update))) => {
+                            self.state = State::Idle(follower);
+                            return Poll::Ready(Some(update));
+                        },
+                        // The follower is dropped, it has nothing left to return.
+                        Poll::Ready((_, None)) => return Poll::Ready(None),
+                        Poll::Pending => {
+                            self.state = State::Pending(next);
+                            return Poll::Pending;
+                        },
+                    }
+                },
+                State::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::{
+        chain_sync_live_chains::live_chain_insert_block, chain_sync_ready::wait_for_sync_ready,
+        chain_update, point::UNKNOWN_POINT, MultiEraBlock, Network,
+    };
+
+    fn live_block(block: &str, fork: u64) -> MultiEraBlock {
+        let raw_block = hex::decode(block).expect("Failed to decode hex block.");
+        MultiEraBlock::new(Network::Preview, raw_block, &UNKNOWN_POINT, fork)
+            .expect("cannot create block")
+    }
+
+    #[tokio::test]
+    async fn test_chain_update_stream() {
+        let chain = Network::Preview;
+        let first = live_block(include_str!("./../test_data/shelley.block"), 2);
+        let last = live_block(include_str!("./../test_data/mary.block"), 2);
+        live_chain_insert_block(chain, first.clone());
+        live_chain_insert_block(chain, last.clone());
+        wait_for_sync_ready(chain).signal();
+
+        let follower = ChainFollower::new(chain, first.point(), last.point()).await;
+        let mut updates = follower.into_stream();
+
+        // Updates are returned in the chain order.
+        let update = updates.next().await.unwrap();
+        assert_eq!(update.kind, chain_update::Kind::Block);
+        assert!(update.block_data().point().strict_eq(&first.point()));
+        let update = updates.next().await.unwrap();
+        assert_eq!(update.kind, chain_update::Kind::Block);
+        assert!(update.block_data().point().strict_eq(&last.point()));
+
+        // The stream ends once the follower reaches the end point, and stays ended.
+        assert!(updates.next().await.is_none());
+        assert!(updates.next().await.is_none());
+    }
+}
@@ -0,0 +1,175 @@
+//! Chain follower checkpoint persistence.
+//!
+//! A checkpoint records the last block processed by a follower, so it can resume from
+//! there after a restart instead of re-syncing from origin.
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+
+use crate::{
+    error::Error,
+    network::Network,
+    point::{Point, ORIGIN_POINT},
+};
+
+/// The last processed point and fork of a chain follower.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// The point of the last processed block.
+    pub point: Point,
+    /// The fork the last processed block was on.
+    pub fork: u64,
+}
+
+/// Pluggable storage of the chain follower checkpoints, one per network.
+#[async_trait]
+pub trait CheckpointStore: Send + Sync {
+    /// Load the checkpoint of the network, `None` if it was never saved.
+    async fn load(&self, chain: Network) -> crate::Result<Option<Checkpoint>>;
+
+    /// Save the checkpoint of the network, replacing the previous one.
+    async fn save(&self, chain: Network, checkpoint: &Checkpoint) -> crate::Result<()>;
+}
+
+/// File based checkpoint store, keeping a JSON file per network in a directory.
+#[derive(Debug, Clone)]
+pub struct FileCheckpointStore {
+    /// Directory the checkpoint files are kept in.
+    dir: PathBuf,
+}
+
+impl FileCheckpointStore {
+    /// Create a file based checkpoint store in the directory.
+    ///
+    /// The directory is created when the first checkpoint is saved.
+    #[must_use]
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// The checkpoint file of the network.
+    fn path(&self, chain: Network) -> PathBuf {
+        self.dir.join(format!("{chain}.checkpoint.json"))
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for FileCheckpointStore {
+    async fn load(&self, chain: Network) -> crate::Result<Option<Checkpoint>> {
+        let data = match tokio::fs::read(self.path(chain)).await {
+            Ok(data) => data,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(Error::Checkpoint(chain, error.into())),
+        };
+        decode_checkpoint(&data)
+            .map(Some)
+            .map_err(|error| Error::Checkpoint(chain, error))
+    }
+
+    async fn save(&self, chain: Network, checkpoint: &Checkpoint) -> crate::Result<()> {
+        let path = self.path(chain);
+        let tmp_path = path.with_extension("json.tmp");
+        let data =
+            encode_checkpoint(checkpoint).map_err(|error| Error::Checkpoint(chain, error))?;
+
+        // Write a temporary file and rename it, so a crash never leaves a partial
+        // checkpoint behind.
+        async {
+            tokio::fs::create_dir_all(&self.dir).await?;
+            tokio::fs::write(&tmp_path, &data).await?;
+            tokio::fs::File::open(&tmp_path).await?.sync_all().await?;
+            tokio::fs::rename(&tmp_path, &path).await
+        }
+        .await
+        .map_err(|error| Error::Checkpoint(chain, error.into()))
+    }
+}
+
+/// Encode the checkpoint as JSON.
+fn encode_checkpoint(checkpoint: &Checkpoint) -> anyhow::Result<Vec<u8>> {
+    let point = if checkpoint.point.is_origin() {
+        serde_json::Value::Null
+    } else {
+        serde_json::json!({
+            "slot": checkpoint.point.slot_or_default(),
+            "hash": hex::encode(checkpoint.point.hash_or_default()),
+        })
+    };
+    Ok(serde_json::to_vec_pretty(&serde_json::json!({
+        "point": point,
+        "fork": checkpoint.fork,
+    }))?)
+}
+
+/// Decode the checkpoint from JSON.
+fn decode_checkpoint(data: &[u8]) -> anyhow::Result<Checkpoint> {
+    let value: serde_json::Value = serde_json::from_slice(data)?;
+    let fork = value
+        .get("fork")
+        .and_then(serde_json::Value::as_u64)
+        .ok_or_else(|| anyhow::anyhow!("Checkpoint has no valid fork"))?;
+    let point = match value.get("point") {
+        Some(serde_json::Value::Null) => ORIGIN_POINT,
+        Some(point) => {
+            let slot = point
+                .get("slot")
+                .and_then(serde_json::Value::as_u64)
+                .ok_or_else(|| anyhow::anyhow!("Checkpoint point has no valid slot"))?;
+            let hash = point
+                .get("hash")
+                .and_then(serde_json::Value::as_str)
+                .ok_or_else(|| anyhow::anyhow!("Checkpoint point has no valid hash"))?;
+            Point::new(slot, hex::decode(hash)?)
+        },
+        None => anyhow::bail!("Checkpoint has no point"),
+    };
+    Ok(Checkpoint { point, fork })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_checkpoint_store() {
+        let dir =
+            std::env::temp_dir().join(format!("chain-follower-checkpoint-{}", std::process::id()));
+        let store = FileCheckpointStore::new(&dir);
+
+        assert_eq!(store.load(Network::Preprod).await.unwrap(), None);
+
+        let checkpoint = Checkpoint {
+            point: Point::new(42, vec![1, 2, 3]),
+            fork: 7,
+        };
+        store.save(Network::Preprod, &checkpoint).await.unwrap();
+        assert_eq!(
+            store.load(Network::Preprod).await.unwrap(),
+            Some(checkpoint)
+        );
+        // Checkpoints are kept per network.
+        assert_eq!(store.load(Network::Preview).await.unwrap(), None);
+
+        let checkpoint = Checkpoint {
+            point: ORIGIN_POINT,
+            fork: 1,
+        };
+        store.save(Network::Preprod, &checkpoint).await.unwrap();
+        assert_eq!(
+            store.load(Network::Preprod).await.unwrap(),
+            Some(checkpoint)
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_decode_invalid_checkpoint() {
+        assert!(decode_checkpoint(b"{}").is_err());
+        assert!(decode_checkpoint(br#"{"fork": 1}"#).is_err());
+        assert!(decode_checkpoint(br#"{"fork": 1, "point": {"slot": 1, "hash": "zz"}}"#).is_err());
+    }
+}
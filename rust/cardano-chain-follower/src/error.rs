@@ -101,6 +101,18 @@ pub enum Error {
     /// Mithril Auto-update requires an Aggregator and a VKEY and a Path
     #[error("Mithril Auto Update Network {0} failed to start. No Aggregator and/or Genesis VKEY and/or Path are configured.")]
     MithrilUpdateRequiresAggregatorAndVkeyAndPath(Network),
+    /// Chain Sync has no relays configured.
+    #[error("Chain Sync for network {0} has no relays configured")]
+    NoRelays(Network),
+    /// Checkpoint store error
+    #[error("Checkpoint store error for {0}: {1}")]
+    Checkpoint(Network, anyhow::Error),
+    /// Metadata label is decoded by this crate.
+    #[error("Metadata label {0} is decoded by this crate, and can not have a custom decoder")]
+    MetadataLabelReserved(u64),
+    /// Custom metadata decoder is already registered for the label.
+    #[error("Custom metadata decoder is already registered for label {0}")]
+    MetadataDecoderAlreadyRegistered(u64),
     /// Internal Error
     #[error("Internal error")]
     Internal,
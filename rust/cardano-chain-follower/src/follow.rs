@@ -5,15 +5,18 @@ use tokio::sync::broadcast::{self};
 use tracing::{debug, error};
 
 use crate::{
+    block_range::{BlockRangeStream, DEFAULT_READ_AHEAD},
     chain_sync::point_at_tip,
     chain_sync_live_chains::{find_best_fork_block, get_live_block, live_chain_length},
     chain_sync_ready::{block_until_sync_ready, get_chain_update_rx_queue},
     chain_update::{self, ChainUpdate},
+    chain_update_stream::ChainUpdateStream,
+    checkpoint::{Checkpoint, CheckpointStore},
     mithril_snapshot::MithrilSnapshot,
     mithril_snapshot_data::latest_mithril_snapshot_id,
     mithril_snapshot_iterator::MithrilSnapshotIterator,
     network::Network,
-    point::{TIP_POINT, UNKNOWN_POINT},
+    point::{ORIGIN_POINT, TIP_POINT, UNKNOWN_POINT},
     stats::{self, rollback},
     MultiEraBlock, Point, Statistics,
 };
@@ -36,6 +39,8 @@ pub struct ChainFollower {
     mithril_follower: Option<MithrilSnapshotIterator>,
     /// Mithril TIP Reached
     mithril_tip: Option<Point>,
+    /// Checkpoint to resume from, checked on the first update.
+    resume: Option<Point>,
     /// Live Block Updates
     sync_updates: broadcast::Receiver<chain_update::Kind>,
 }
@@ -80,10 +85,147 @@ impl ChainFollower {
             snapshot: MithrilSnapshot::new(chain),
             mithril_follower: None,
             mithril_tip: None,
+            resume: None,
             sync_updates: rx,
         }
     }
 
+    /// Resume following a blockchain from its saved checkpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain` - The blockchain network to follow.
+    /// * `store` - The checkpoint store to load the checkpoint from.
+    /// * `end` - The point or tip to stop following from (inclusive).
+    ///
+    /// # Returns
+    ///
+    /// The Chain Follower that will return blocks following the checkpoint, or starting
+    /// from origin if no checkpoint was saved for the network.
+    ///
+    /// # Notes
+    ///
+    /// The follower intersects the chain at the exact checkpoint point, slot and hash.
+    /// If the checkpoint block was rolled back while the follower was not running, the
+    /// first update is a rollback to the latest block before the checkpoint.
+    ///
+    /// # Errors
+    ///
+    /// If the checkpoint can not be loaded.
+    pub async fn resume_from_checkpoint(
+        chain: Network, store: &dyn CheckpointStore, end: Point,
+    ) -> crate::Result<Self> {
+        let Some(checkpoint) = store.load(chain).await? else {
+            return Ok(Self::new(chain, ORIGIN_POINT, end).await);
+        };
+
+        let mut follower = Self::new(chain, checkpoint.point.clone(), end).await;
+        follower.fork = checkpoint.fork;
+        if !checkpoint.point.is_origin() {
+            follower.resume = Some(checkpoint.point);
+        }
+        Ok(follower)
+    }
+
+    /// The blockchain network being followed.
+    #[must_use]
+    pub fn chain(&self) -> Network {
+        self.chain
+    }
+
+    /// The checkpoint of the most recently returned block.
+    ///
+    /// Returns `None` if the follower has not returned any block yet.
+    #[must_use]
+    pub fn checkpoint(&self) -> Option<Checkpoint> {
+        if self.previous.is_unknown() {
+            return None;
+        }
+        Some(Checkpoint {
+            point: self.current.clone(),
+            fork: self.fork,
+        })
+    }
+
+    /// Save the checkpoint of the most recently returned block to the store.
+    ///
+    /// Does nothing if the follower has not returned any block yet.
+    ///
+    /// # Errors
+    ///
+    /// If the checkpoint can not be saved.
+    pub async fn save_checkpoint(&self, store: &dyn CheckpointStore) -> crate::Result<()> {
+        if let Some(checkpoint) = self.checkpoint() {
+            store.save(self.chain, &checkpoint).await?;
+        }
+        Ok(())
+    }
+
+    /// Intersect the chain at the checkpoint the follower resumes from.
+    ///
+    /// Returns a rollback update if the checkpoint block is no longer on the chain, or
+    /// `None` if the follower can continue with the block following the checkpoint.
+    async fn resume(&mut self, checkpoint: Point) -> Option<ChainUpdate> {
+        let checkpoint_block = if self.snapshot.contains_point(&checkpoint) {
+            self.snapshot.read_block_at(&checkpoint).await
+        } else {
+            get_live_block(self.chain, &checkpoint, 0, true)
+        };
+
+        if let Some(block) = checkpoint_block.filter(|block| block.point().strict_eq(&checkpoint)) {
+            // The checkpoint block was already processed, so continue with the next block.
+            self.previous = checkpoint.clone();
+            self.current = checkpoint;
+            self.fork = block.fork();
+            self.resume_mithril_follower().await;
+            return None;
+        }
+
+        // The checkpoint block was rolled back, so roll back to the block preceding it.
+        let Some(block) = self.resume_rollback_block(&checkpoint).await else {
+            debug!("No block found preceding the checkpoint {checkpoint}.");
+            return None;
+        };
+        debug!("Checkpoint {checkpoint} was rolled back to {block}.");
+
+        rollback(self.chain, stats::RollbackType::Follower, 1);
+        self.previous = checkpoint;
+        self.current = block.point();
+        self.fork = block.fork();
+        self.resume_mithril_follower().await;
+
+        let tip = point_at_tip(self.chain, &self.current).await;
+        Some(ChainUpdate::new(chain_update::Kind::Rollback, tip, block))
+    }
+
+    /// Find the latest block preceding a rolled back checkpoint.
+    async fn resume_rollback_block(&self, checkpoint: &Point) -> Option<MultiEraBlock> {
+        if let Some(block) = get_live_block(self.chain, checkpoint, -1, false) {
+            return Some(block);
+        }
+
+        // The checkpoint is older than the live chain.
+        if !self.snapshot.contains_point(checkpoint) {
+            let latest_mithril_point = latest_mithril_snapshot_id(self.chain).tip();
+            return self.snapshot.read_block_at(&latest_mithril_point).await;
+        }
+
+        // The block in the immutable chain at or after the checkpoint slot links to the
+        // block preceding the checkpoint.
+        let block = self
+            .snapshot
+            .read_block_at(&Point::fuzzy(checkpoint.slot_or_default()))
+            .await?;
+        self.snapshot.read_block_at(&block.previous()).await
+    }
+
+    /// Start reading the mithril snapshot from the block following the current one, if
+    /// it is in the snapshot.
+    async fn resume_mithril_follower(&mut self) {
+        let next = Point::fuzzy(self.current.slot_or_default().saturating_add(1));
+        self.mithril_follower = self.snapshot.try_read_blocks_from_point(&next).await;
+    }
+
     /// If we can, get the next update from the mithril snapshot.
     async fn next_from_mithril(&mut self) -> Option<ChainUpdate> {
         let current_mithril_tip = latest_mithril_snapshot_id(self.chain).tip();
@@ -286,10 +428,27 @@ impl ChainFollower {
         // Can't follow if SYNC is not ready.
         block_until_sync_ready(self.chain).await;
 
+        // Check the checkpoint we resume from is still on the chain.
+        if let Some(checkpoint) = self.resume.take() {
+            if let Some(update) = self.resume(checkpoint).await {
+                return Some(update);
+            }
+        }
+
         // Get next block from the iteration.
         self.unprotected_next().await
     }
 
+    /// Turn the follower into a `futures::Stream` of its chain updates, so it can be
+    /// used with the stream combinators.
+    ///
+    /// The next update is only requested when the stream is polled, so a slow consumer
+    /// applies backpressure to the follower.
+    #[must_use]
+    pub fn into_stream(self) -> ChainUpdateStream {
+        ChainUpdateStream::new(self)
+    }
+
     /// Get a single block from the chain by its point.
     ///
     /// If the Point does not point exactly at a block, it will return the next
@@ -305,6 +464,47 @@ impl ChainFollower {
         follower.next().await
     }
 
+    /// Read a historical range of immutable blocks from the Mithril snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain` - The blockchain network to read.
+    /// * `start` - The point to start reading from (inclusive).
+    /// * `end` - The point to stop reading at (inclusive).
+    ///
+    /// # Returns
+    ///
+    /// The stream of the immutable blocks in the requested range, reading at most
+    /// `DEFAULT_READ_AHEAD` blocks ahead of the consumer.
+    ///
+    /// # Notes
+    ///
+    /// Only blocks in the Mithril snapshot are returned, if `end` is after the immutable
+    /// tip, the stream ends at the immutable tip.
+    ///
+    /// # Errors
+    ///
+    /// If the start point is not within the Mithril snapshot.
+    pub async fn read_range(
+        chain: Network, start: Point, end: Point,
+    ) -> crate::Result<BlockRangeStream> {
+        Self::read_range_with_read_ahead(chain, start, end, DEFAULT_READ_AHEAD).await
+    }
+
+    /// Read a historical range of immutable blocks from the Mithril snapshot, reading at
+    /// most `read_ahead` blocks ahead of the consumer.
+    ///
+    /// See `read_range` for details.
+    ///
+    /// # Errors
+    ///
+    /// If the start point is not within the Mithril snapshot.
+    pub async fn read_range_with_read_ahead(
+        chain: Network, start: Point, end: Point, read_ahead: usize,
+    ) -> crate::Result<BlockRangeStream> {
+        BlockRangeStream::new(chain, start, end, read_ahead).await
+    }
+
     /// Get the current Immutable and live tips.
     ///
     /// Note, this will block until the chain is synced, ready to be followed.
@@ -362,6 +562,7 @@ impl ChainFollower {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{chain_sync_live_chains::live_chain_insert_block, checkpoint::FileCheckpointStore};
 
     fn mock_block() -> MultiEraBlock {
         let raw_block = hex::decode(include_str!("./../test_data/shelley.block"))
@@ -400,6 +601,72 @@ mod tests {
         assert!(follower.mithril_tip.is_none());
     }
 
+    #[tokio::test]
+    async fn test_chain_follower_resume_from_checkpoint() {
+        let chain = Network::Preprod;
+        let end = Point::fuzzy(999u64);
+        let dir =
+            std::env::temp_dir().join(format!("chain-follower-resume-{}", std::process::id()));
+        let store = FileCheckpointStore::new(&dir);
+
+        // No checkpoint, so follow from origin.
+        let follower = ChainFollower::resume_from_checkpoint(chain, &store, end.clone())
+            .await
+            .unwrap();
+        assert_eq!(follower.current, ORIGIN_POINT);
+        assert_eq!(follower.fork, 1);
+        assert!(follower.checkpoint().is_none());
+
+        let mut follower = ChainFollower::new(chain, ORIGIN_POINT, end.clone()).await;
+        let update = ChainUpdate::new(chain_update::Kind::Block, false, mock_block());
+        follower.previous = follower.current.clone();
+        assert!(follower.update_current(Some(&update)));
+        follower.fork = 3;
+        follower.save_checkpoint(&store).await.unwrap();
+
+        // Resume from the checkpoint point, checked on the first update.
+        let follower = ChainFollower::resume_from_checkpoint(chain, &store, end)
+            .await
+            .unwrap();
+        assert_eq!(follower.current, update.block_data().point());
+        assert_eq!(follower.resume, Some(update.block_data().point()));
+        assert_eq!(follower.fork, 3);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    fn live_block(block: &str, fork: u64) -> MultiEraBlock {
+        let raw_block = hex::decode(block).expect("Failed to decode hex block.");
+        MultiEraBlock::new(Network::Preview, raw_block, &UNKNOWN_POINT, fork)
+            .expect("cannot create block")
+    }
+
+    #[tokio::test]
+    async fn test_chain_follower_resume() {
+        let chain = Network::Preview;
+        let earlier = live_block(include_str!("./../test_data/shelley.block"), 2);
+        let checkpoint = live_block(include_str!("./../test_data/mary.block"), 2);
+        live_chain_insert_block(chain, earlier.clone());
+        live_chain_insert_block(chain, checkpoint.clone());
+
+        // The checkpoint block is still on the chain, continue after it.
+        let mut follower = ChainFollower::new(chain, checkpoint.point(), TIP_POINT).await;
+        assert!(follower.resume(checkpoint.point()).await.is_none());
+        assert_eq!(follower.previous, checkpoint.point());
+        assert_eq!(follower.current, checkpoint.point());
+        assert_eq!(follower.fork, 2);
+
+        // The checkpoint block was rolled back, roll back to the block preceding it.
+        let rolled_back = Point::new(checkpoint.point().slot_or_default(), vec![0; 32]);
+        let mut follower = ChainFollower::new(chain, rolled_back.clone(), TIP_POINT).await;
+        let update = follower.resume(rolled_back.clone()).await.unwrap();
+        assert_eq!(update.kind, chain_update::Kind::Rollback);
+        assert!(update.block_data().point().strict_eq(&earlier.point()));
+        assert_eq!(follower.previous, rolled_back);
+        assert!(follower.current.strict_eq(&earlier.point()));
+        assert_eq!(follower.fork, 2);
+    }
+
     #[tokio::test]
     async fn test_chain_follower_update_current_none() {
         let chain = Network::Mainnet;
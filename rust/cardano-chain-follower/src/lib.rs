@@ -1,13 +1,21 @@
 //! Cardano chain follower.
 
+mod block_range;
+mod chain_event;
 mod chain_sync;
 mod chain_sync_config;
 mod chain_sync_live_chains;
+mod chain_sync_live_spillover;
+mod chain_sync_peers;
 mod chain_sync_ready;
 mod chain_update;
+mod chain_update_stream;
+mod checkpoint;
 mod error;
 mod follow;
 pub mod metadata;
+#[cfg(feature = "metrics")]
+mod metrics;
 mod mithril_query;
 mod mithril_snapshot;
 mod mithril_snapshot_config;
@@ -15,6 +23,7 @@ mod mithril_snapshot_data;
 mod mithril_snapshot_iterator;
 mod mithril_snapshot_sync;
 mod mithril_turbo_downloader;
+mod multi_chain_follower;
 mod multi_era_block_data;
 mod network;
 mod point;
@@ -24,12 +33,19 @@ pub mod turbo_downloader;
 mod utils;
 mod witness;
 
+pub use block_range::{BlockRangeStream, DEFAULT_READ_AHEAD};
+pub use chain_event::{ChainEvent, ChainEventStream};
 pub use chain_sync_config::ChainSyncConfig;
 pub use chain_update::{ChainUpdate, Kind};
+pub use chain_update_stream::ChainUpdateStream;
+pub use checkpoint::{Checkpoint, CheckpointStore, FileCheckpointStore};
 pub use error::Result;
 pub use follow::ChainFollower;
 pub use metadata as Metadata;
+#[cfg(feature = "metrics")]
+pub use metrics::StatisticsCollector;
+pub use multi_chain_follower::{MultiChainFollower, NetworkChainUpdate};
 pub use multi_era_block_data::MultiEraBlock;
 pub use network::Network;
 pub use point::{Point, ORIGIN_POINT, TIP_POINT};
-pub use stats::Statistics;
+pub use stats::{CombinedStatistics, MultiChainStatistics, Statistics};
@@ -2,6 +2,9 @@
 
 use std::sync::Arc;
 
+use cardano_blockchain_types::cip19::{
+    network_tag_from_header, Cip19AddressType, MAINNET_NETWORK_TAG, TESTNET_NETWORK_TAG,
+};
 use ed25519_dalek::Verifier;
 use minicbor::Decoder;
 use pallas::ledger::traverse::MultiEraTx;
@@ -12,6 +15,10 @@ use super::{
 };
 use crate::Network;
 
+mod snapshot;
+
+pub use snapshot::{MalformedRegistration, Snapshot, SnapshotBuilder, StakeKeyHash};
+
 /// CIP36 Metadata Label
 pub const LABEL: u64 = 61284;
 /// CIP36 Metadata Signature label
@@ -401,44 +408,31 @@ impl Cip36 {
         };
 
         // See: https://cips.cardano.org/cip/CIP-19 for details on address decoding.
-        let network_tag = header_byte & 0x0F;
-        let header_type = header_byte >> 4;
-        match header_type {
-            0..=3 => {
-                if raw_address.len() != 57 {
-                    validation_report.push(format!("Address Length {} != 57", raw_address.len()));
-                }
-            },
-            4 | 5 => {
-                if raw_address.len() < 29 {
-                    validation_report
-                        .push(format!("Pointer Address Length {} < 29", raw_address.len()));
-                }
-            },
-            6 | 7 | 14 | 15 => {
-                if raw_address.len() != 29 {
-                    validation_report.push(format!(
-                        "Pointer Address Length {} != 29",
-                        raw_address.len()
-                    ));
+        let address_type = Cip19AddressType::from_header(*header_byte);
+        match address_type {
+            Some(address_type) => {
+                if let Err(err) = address_type.validate_length(raw_address.len()) {
+                    validation_report.push(err.to_string());
                 }
             },
-            _ => {
+            None => {
                 validation_report.push(format!(
-                    "Address Type {header_type} is invalid and unsupported"
+                    "Address Type {} is invalid and unsupported",
+                    header_byte >> 4
                 ));
             },
         }
 
         // Check address is for the correct network of the transaction.
-        if header_type == 8 {
+        if address_type == Some(Cip19AddressType::Byron) {
             validation_report.push("Byron Addresses are unsupported".to_string());
         } else {
-            let valid = match chain {
-                Network::Mainnet => network_tag == 1,
-                Network::Preprod | Network::Preview => network_tag == 0,
+            let network_tag = network_tag_from_header(*header_byte);
+            let expected = match chain {
+                Network::Mainnet => MAINNET_NETWORK_TAG,
+                Network::Preprod | Network::Preview => TESTNET_NETWORK_TAG,
             };
-            if !valid {
+            if network_tag != expected {
                 validation_report.push(format!(
                     "Network Tag {network_tag} does not match transactions Network ID"
                 ));
@@ -447,7 +441,7 @@ impl Cip36 {
 
         // Addresses are only payable if they are a normal payment address and not a script
         // address.
-        self.payable = header_type <= 7 && (header_type & 0x1 == 0);
+        self.payable = address_type.is_some_and(Cip19AddressType::is_payable);
         self.payment_addr = raw_address.to_vec();
 
         Some(self.payment_addr.len())
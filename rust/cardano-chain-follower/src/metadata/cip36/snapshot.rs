@@ -0,0 +1,336 @@
+//! Voting power snapshot from CIP36 registrations.
+//!
+//! Correlates the CIP36 registrations of each stake key with the stake distribution, to
+//! produce the voting power of every voting key.
+
+use std::collections::HashMap;
+
+use super::{Cip36, Ed25519PubKey, PROJECT_CATALYST_PURPOSE};
+use crate::{metadata::ValidationReport, utils::blake2b_244};
+
+/// Hash of a stake public key, as used in the stake distribution.
+pub type StakeKeyHash = [u8; 28];
+
+/// A registration which could not be used for the snapshot.
+#[derive(Clone, Debug)]
+pub struct MalformedRegistration {
+    /// Slot of the registration.
+    pub slot: u64,
+    /// Index of the registration transaction in its block.
+    pub txn_idx: usize,
+    /// Why the registration could not be used.
+    pub report: ValidationReport,
+}
+
+/// The latest valid registration of a stake key.
+#[derive(Clone, Debug)]
+struct Registration {
+    /// Slot of the registration.
+    slot: u64,
+    /// Index of the registration transaction in its block.
+    txn_idx: usize,
+    /// The registration itself.
+    cip36: Cip36,
+}
+
+impl Registration {
+    /// The order registrations are applied in, later registrations win.
+    fn order(&self) -> (u64, u64, usize) {
+        (self.cip36.nonce, self.slot, self.txn_idx)
+    }
+}
+
+/// Builder of a voting power `Snapshot`.
+#[derive(Debug, Default)]
+pub struct SnapshotBuilder {
+    /// The latest valid registration of each stake key.
+    registrations: HashMap<StakeKeyHash, Registration>,
+    /// The stake of each stake key, in lovelace.
+    stake: HashMap<StakeKeyHash, u64>,
+    /// Registrations which could not be used.
+    malformed: Vec<MalformedRegistration>,
+}
+
+impl SnapshotBuilder {
+    /// Create a new, empty, snapshot builder.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a decoded registration to the snapshot.
+    ///
+    /// Registrations can be added in any order. For each stake key only the latest valid
+    /// registration (highest nonce, then latest slot and transaction) is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot`: Slot of the registration.
+    /// * `txn_idx`: Index of the registration transaction in its block.
+    /// * `cip36`: The decoded registration.
+    /// * `report`: The validation report of the registration.
+    #[must_use]
+    pub fn registration(
+        mut self, slot: u64, txn_idx: usize, cip36: &Cip36, report: &[String],
+    ) -> Self {
+        let mut report = report.to_vec();
+        let stake_key_hash = Self::check_registration(cip36, &mut report);
+
+        match stake_key_hash {
+            Some(stake_key_hash) if report.is_empty() => {
+                let registration = Registration {
+                    slot,
+                    txn_idx,
+                    cip36: cip36.clone(),
+                };
+                let is_latest = self
+                    .registrations
+                    .get(&stake_key_hash)
+                    .is_none_or(|current| registration.order() > current.order());
+                if is_latest {
+                    self.registrations.insert(stake_key_hash, registration);
+                }
+            },
+            _ => {
+                self.malformed.push(MalformedRegistration {
+                    slot,
+                    txn_idx,
+                    report,
+                });
+            },
+        }
+        self
+    }
+
+    /// Add the stake of a stake key, in lovelace, to the snapshot.
+    ///
+    /// Stake added multiple times for the same stake key is summed.
+    ///
+    /// # Arguments
+    ///
+    /// * `stake_key_hash`: Hash of the stake public key.
+    /// * `lovelace`: Stake controlled by the stake key.
+    #[must_use]
+    pub fn stake(mut self, stake_key_hash: StakeKeyHash, lovelace: u64) -> Self {
+        let stake = self.stake.entry(stake_key_hash).or_default();
+        *stake = stake.saturating_add(lovelace);
+        self
+    }
+
+    /// Check the registration can be used for the snapshot, and get its stake key hash.
+    fn check_registration(cip36: &Cip36, report: &mut ValidationReport) -> Option<StakeKeyHash> {
+        if !cip36.signed {
+            report.push("Registration is not signed".to_string());
+        }
+        if cip36.purpose != PROJECT_CATALYST_PURPOSE {
+            report.push(format!(
+                "Registration purpose {} is not for Project Catalyst",
+                cip36.purpose
+            ));
+        }
+        if !cip36.payable {
+            report.push("Registration payment address is not payable".to_string());
+        }
+        if cip36.voting_keys.iter().all(|key| key.weight == 0) {
+            report.push("Registration has no weighted voting keys".to_string());
+        }
+
+        let Some(stake_pk) = cip36.stake_pk else {
+            report.push("Registration has no stake public key".to_string());
+            return None;
+        };
+        match blake2b_244(stake_pk.as_bytes()) {
+            Ok(stake_key_hash) => Some(stake_key_hash),
+            Err(error) => {
+                report.push(format!("Failed to hash stake public key: {error}"));
+                None
+            },
+        }
+    }
+
+    /// Build the voting power snapshot.
+    ///
+    /// The stake of each registered stake key is split between its voting keys in
+    /// proportion to their weights, with any remainder given to the last voting key.
+    /// Stake without a registration does not give any voting power.
+    #[must_use]
+    pub fn build(self) -> Snapshot {
+        let mut voting_power: HashMap<Ed25519PubKey, u64> = HashMap::new();
+
+        for (stake_key_hash, registration) in &self.registrations {
+            let stake = self.stake.get(stake_key_hash).copied().unwrap_or_default();
+            let keys = &registration.cip36.voting_keys;
+            let total_weight: u64 = keys.iter().map(|key| u64::from(key.weight)).sum();
+            if total_weight == 0 {
+                continue;
+            }
+
+            let mut remaining = stake;
+            let last = keys.iter().rposition(|key| key.weight > 0);
+            for (index, key) in keys.iter().enumerate() {
+                let power = if Some(index) == last {
+                    remaining
+                } else {
+                    // Can not overflow, as weight <= total weight.
+                    let power =
+                        u128::from(stake) * u128::from(key.weight) / u128::from(total_weight);
+                    u64::try_from(power).unwrap_or(u64::MAX)
+                };
+                remaining = remaining.saturating_sub(power);
+
+                if key.weight > 0 {
+                    let key_power = voting_power.entry(key.voting_pk).or_default();
+                    *key_power = key_power.saturating_add(power);
+                }
+            }
+        }
+
+        Snapshot {
+            voting_power,
+            malformed: self.malformed,
+        }
+    }
+}
+
+/// Voting power of each voting key, from the CIP36 registrations and stake distribution.
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    /// Voting power of each voting key.
+    voting_power: HashMap<Ed25519PubKey, u64>,
+    /// Registrations which could not be used.
+    malformed: Vec<MalformedRegistration>,
+}
+
+impl Snapshot {
+    /// Create a new snapshot builder.
+    #[must_use]
+    pub fn builder() -> SnapshotBuilder {
+        SnapshotBuilder::new()
+    }
+
+    /// Get the voting power of a voting key, 0 if it has none.
+    #[must_use]
+    pub fn voting_power(&self, voting_pk: &Ed25519PubKey) -> u64 {
+        self.voting_power
+            .get(voting_pk)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Get the voting power of all voting keys.
+    #[must_use]
+    pub fn voting_powers(&self) -> &HashMap<Ed25519PubKey, u64> {
+        &self.voting_power
+    }
+
+    /// Get the total voting power of the snapshot.
+    #[must_use]
+    pub fn total_voting_power(&self) -> u64 {
+        self.voting_power
+            .values()
+            .fold(0, |total, power| total.saturating_add(*power))
+    }
+
+    /// Get the registrations which could not be used for the snapshot.
+    #[must_use]
+    pub fn malformed(&self) -> &[MalformedRegistration] {
+        &self.malformed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::SigningKey;
+
+    use super::*;
+    use crate::metadata::cip36::VotingPubKey;
+
+    /// A deterministic test public key.
+    fn test_key(seed: u8) -> Ed25519PubKey {
+        SigningKey::from_bytes(&[seed; 32]).verifying_key()
+    }
+
+    /// A valid registration of the stake key to the weighted voting keys.
+    fn registration(stake_seed: u8, nonce: u64, voting_keys: &[(u8, u32)]) -> Cip36 {
+        Cip36 {
+            cip36: Some(true),
+            voting_keys: voting_keys
+                .iter()
+                .map(|(seed, weight)| {
+                    VotingPubKey {
+                        voting_pk: test_key(*seed),
+                        weight: *weight,
+                    }
+                })
+                .collect(),
+            stake_pk: Some(test_key(stake_seed)),
+            payable: true,
+            nonce,
+            raw_nonce: nonce,
+            signed: true,
+            strict_catalyst: true,
+            ..Default::default()
+        }
+    }
+
+    /// Stake key hash of a test key.
+    fn stake_key_hash(seed: u8) -> StakeKeyHash {
+        blake2b_244(test_key(seed).as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_split_voting_power() {
+        let snapshot = Snapshot::builder()
+            .registration(10, 0, &registration(1, 1, &[(10, 1), (11, 2)]), &[])
+            .stake(stake_key_hash(1), 100)
+            .build();
+
+        assert_eq!(snapshot.voting_power(&test_key(10)), 33);
+        assert_eq!(snapshot.voting_power(&test_key(11)), 67);
+        assert_eq!(snapshot.total_voting_power(), 100);
+        assert!(snapshot.malformed().is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_latest_registration_wins() {
+        let snapshot = Snapshot::builder()
+            .registration(30, 0, &registration(1, 3, &[(12, 1)]), &[])
+            .registration(10, 0, &registration(1, 1, &[(10, 1)]), &[])
+            .registration(20, 0, &registration(1, 2, &[(11, 1)]), &[])
+            .registration(40, 0, &registration(2, 1, &[(12, 1)]), &[])
+            .stake(stake_key_hash(1), 100)
+            .stake(stake_key_hash(2), 50)
+            .stake(stake_key_hash(3), 1000)
+            .build();
+
+        assert_eq!(snapshot.voting_power(&test_key(10)), 0);
+        assert_eq!(snapshot.voting_power(&test_key(11)), 0);
+        assert_eq!(snapshot.voting_power(&test_key(12)), 150);
+        assert_eq!(snapshot.total_voting_power(), 150);
+    }
+
+    #[test]
+    fn test_snapshot_malformed_registrations() {
+        let mut unsigned = registration(1, 5, &[(10, 1)]);
+        unsigned.signed = false;
+        let mut no_stake_key = registration(2, 1, &[(10, 1)]);
+        no_stake_key.stake_pk = None;
+
+        let snapshot = Snapshot::builder()
+            .registration(10, 0, &registration(1, 1, &[(11, 1)]), &[])
+            .registration(20, 1, &unsigned, &[])
+            .registration(30, 2, &no_stake_key, &[])
+            .registration(40, 3, &registration(3, 1, &[(10, 1)]), &[
+                "Invalid key found in CIP36 Metadata: 9".to_string(),
+            ])
+            .stake(stake_key_hash(1), 100)
+            .build();
+
+        // The malformed later registration does not replace the valid one.
+        assert_eq!(snapshot.voting_power(&test_key(11)), 100);
+        assert_eq!(snapshot.voting_power(&test_key(10)), 0);
+
+        let malformed: Vec<_> = snapshot.malformed().iter().map(|m| m.slot).collect();
+        assert_eq!(malformed, vec![20, 30, 40]);
+    }
+}
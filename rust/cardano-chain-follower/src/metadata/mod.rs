@@ -1,6 +1,6 @@
 //! Metadata decoding and validating.
 
-use std::{fmt::Debug, sync::Arc};
+use std::{any::Any, fmt::Debug, sync::Arc};
 
 use cip36::Cip36;
 use cip509::Cip509;
@@ -14,6 +14,7 @@ use crate::{utils::usize_from_saturating, Network};
 pub mod cip36;
 pub mod cip509;
 mod raw_aux_data;
+pub mod registry;
 
 /// List of all validation errors (as strings) Metadata is considered Valid if this list
 /// is empty.
@@ -29,6 +30,9 @@ pub enum DecodedMetadataValues {
     Cip36(Arc<Cip36>),
     /// CIP-509 RBAC metadata.
     Cip509(Arc<Cip509>),
+    /// Metadata decoded by a custom decoder, see [`registry::register_decoder`].
+    /// Downcast it to the type produced by the decoder.
+    Custom(Arc<dyn Any + Send + Sync>),
 }
 
 /// An individual decoded metadata item.
@@ -55,6 +59,7 @@ impl DecodedMetadata {
         // Process each known type of metadata here, and record the decoded result.
         Cip36::decode_and_validate(&decoded_metadata, slot, txn, raw_aux_data, true, chain);
         Cip509::decode_and_validate(&decoded_metadata, txn, raw_aux_data);
+        registry::decode_custom(&decoded_metadata, chain, slot, txn, raw_aux_data);
 
         // if !decoded_metadata.0.is_empty() {
         //    debug!("Decoded Metadata final: {decoded_metadata:?}");
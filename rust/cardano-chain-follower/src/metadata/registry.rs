@@ -0,0 +1,164 @@
+//! Registry of custom metadata decoders.
+//!
+//! Applications register a decoder for their own metadatum label, and the decoder runs
+//! for every transaction carrying that label while its block is decoded. The decoded
+//! result is available from [`super::DecodedTransaction::get_metadata`], the same as the
+//! built-in CIP-36 and CIP-509 metadata.
+
+use std::sync::{Arc, LazyLock};
+
+use dashmap::{mapref::entry::Entry, DashMap};
+use pallas::ledger::traverse::MultiEraTx;
+use rbac_registration::cardano::cip509::LABEL as CIP509_LABEL;
+
+use super::{cip36, DecodedMetadata, DecodedMetadataItem, RawAuxData};
+use crate::{error::Error, Network};
+
+/// Label of a transaction metadatum.
+pub type MetadatumLabel = u64;
+
+/// Custom metadata decoder.
+///
+/// Returns the decoded metadata item, or `None` if nothing should be recorded for the
+/// transaction. The decoded value is usually a [`super::DecodedMetadataValues::Custom`].
+pub type MetadataDecoder =
+    Arc<dyn Fn(&MetadataDecoderInput) -> Option<DecodedMetadataItem> + Send + Sync>;
+
+/// Data of a transaction passed to a custom metadata decoder.
+pub struct MetadataDecoderInput<'a> {
+    /// Network of the block.
+    pub chain: Network,
+    /// Slot of the block.
+    pub slot: u64,
+    /// The transaction carrying the metadatum.
+    pub txn: &'a MultiEraTx<'a>,
+    /// Label of the metadatum.
+    pub label: MetadatumLabel,
+    /// Raw CBOR of the metadatum.
+    pub raw: &'a [u8],
+}
+
+/// Labels decoded by this crate, which can not have a custom decoder.
+const RESERVED_LABELS: [MetadatumLabel; 3] = [cip36::LABEL, cip36::SIG_LABEL, CIP509_LABEL];
+
+/// Registered custom metadata decoders.
+static DECODERS: LazyLock<DashMap<MetadatumLabel, MetadataDecoder>> = LazyLock::new(DashMap::new);
+
+/// Register a custom decoder for the metadatum label.
+///
+/// The decoder runs for the blocks decoded after it is registered, so it should be
+/// registered before the chain sync is started.
+///
+/// # Errors
+///
+/// - The label is decoded by this crate.
+/// - A decoder is already registered for the label.
+pub fn register_decoder(
+    label: MetadatumLabel,
+    decoder: impl Fn(&MetadataDecoderInput) -> Option<DecodedMetadataItem> + Send + Sync + 'static,
+) -> crate::Result<()> {
+    if RESERVED_LABELS.contains(&label) {
+        return Err(Error::MetadataLabelReserved(label));
+    }
+    match DECODERS.entry(label) {
+        Entry::Occupied(_) => Err(Error::MetadataDecoderAlreadyRegistered(label)),
+        Entry::Vacant(entry) => {
+            entry.insert(Arc::new(decoder));
+            Ok(())
+        },
+    }
+}
+
+/// Unregister the custom decoder of the metadatum label.
+/// Returns `false` if no decoder was registered for the label.
+pub fn unregister_decoder(label: MetadatumLabel) -> bool {
+    DECODERS.remove(&label).is_some()
+}
+
+/// Run the registered custom decoders of the labels present in the transaction, and
+/// record their results in `decoded_metadata`.
+pub(crate) fn decode_custom(
+    decoded_metadata: &DecodedMetadata, chain: Network, slot: u64, txn: &MultiEraTx,
+    raw_aux_data: &RawAuxData,
+) {
+    // Decoders are cloned out of the map, so they can (un)register decoders themselves.
+    let decoders: Vec<_> = DECODERS
+        .iter()
+        .map(|entry| (*entry.key(), entry.value().clone()))
+        .collect();
+
+    for (label, decoder) in decoders {
+        let Some(raw) = raw_aux_data.get_metadata(label) else {
+            continue;
+        };
+        let input = MetadataDecoderInput {
+            chain,
+            slot,
+            txn,
+            label,
+            raw: raw.as_slice(),
+        };
+        if let Some(item) = decoder(&input) {
+            decoded_metadata.0.insert(label, Arc::new(item));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{metadata::DecodedMetadataValues, point::UNKNOWN_POINT, MultiEraBlock};
+
+    #[test]
+    fn register_and_unregister() {
+        // Labels are global, use one no other test registers.
+        let label = 1_234_567;
+        let decoder = |_: &MetadataDecoderInput| None;
+
+        assert!(matches!(
+            register_decoder(CIP509_LABEL, decoder),
+            Err(Error::MetadataLabelReserved(CIP509_LABEL))
+        ));
+
+        assert!(register_decoder(label, decoder).is_ok());
+        assert!(matches!(
+            register_decoder(label, decoder),
+            Err(Error::MetadataDecoderAlreadyRegistered(l)) if l == label
+        ));
+
+        assert!(unregister_decoder(label));
+        assert!(!unregister_decoder(label));
+        assert!(register_decoder(label, decoder).is_ok());
+        assert!(unregister_decoder(label));
+    }
+
+    #[test]
+    fn custom_decoder_runs_on_block_decode() {
+        // NFT metadata of the transaction 11 of the test block, no other test registers it.
+        let label = 721;
+        let txn_idx = 11;
+        register_decoder(label, |input: &MetadataDecoderInput| {
+            Some(DecodedMetadataItem {
+                value: DecodedMetadataValues::Custom(Arc::new((input.slot, input.raw.to_vec()))),
+                report: Vec::new(),
+            })
+        })
+        .unwrap();
+
+        let raw_block = hex::decode(include_str!("./../../test_data/mary.block")).unwrap();
+        let block = MultiEraBlock::new(Network::Preprod, raw_block, &UNKNOWN_POINT, 0).unwrap();
+        assert!(unregister_decoder(label));
+
+        let item = block.txn_metadata(txn_idx, label).unwrap();
+        let DecodedMetadataValues::Custom(value) = &item.value else {
+            panic!("expected custom metadata, got {:?}", item.value);
+        };
+        let (slot, raw) = value.downcast_ref::<(u64, Vec<u8>)>().unwrap();
+        assert_eq!(*slot, block.point().slot_or_default());
+        assert_eq!(raw, &*block.txn_raw_metadata(txn_idx, label).unwrap());
+        assert!(item.report.is_empty());
+
+        // Transactions without the metadatum are not decoded.
+        assert!(block.txn_metadata(0, label).is_none());
+    }
+}
@@ -0,0 +1,405 @@
+//! Prometheus metrics exporter for the Cardano Chain Follower Statistics
+//!
+//! All values are exported as gauges labelled by network, because the incremental
+//! counters can be reset with `Statistics::reset`.
+
+use chrono::{DateTime, Utc};
+use prometheus::{
+    core::{Collector, Desc},
+    proto::MetricFamily,
+    GaugeVec, Opts, Registry,
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    stats::{Peer, Rollback, Statistics},
+    Network,
+};
+
+/// Namespace of all the exported metrics.
+const NAMESPACE: &str = "cardano_chain_follower";
+
+/// Extracts a metric value from the statistics.
+type Extractor = fn(&Statistics) -> f64;
+
+/// Network labelled gauges, as `(name, help, extractor)`.
+const GAUGES: &[(&str, &str, Extractor)] = &[
+    // Live chain statistics
+    ("live_blocks", "Current number of live blocks", |s| {
+        as_f64(s.live.blocks)
+    }),
+    ("live_head_slot", "Slot of the live chain head", |s| {
+        as_f64(s.live.head_slot)
+    }),
+    (
+        "live_tip_slot",
+        "Live tip slot as reported by the peer",
+        |s| as_f64(s.live.tip),
+    ),
+    (
+        "live_new_blocks",
+        "New blocks read from the blockchain",
+        |s| as_f64(s.live.new_blocks),
+    ),
+    (
+        "live_invalid_blocks",
+        "Blocks that failed to deserialize from the blockchain",
+        |s| as_f64(s.live.invalid_blocks),
+    ),
+    (
+        "live_reconnects",
+        "Number of connections to the node",
+        |s| as_f64(s.live.reconnects),
+    ),
+    (
+        "live_connected",
+        "1 if there is an active connection to the node",
+        |s| f64::from(u8::from(s.live.connected)),
+    ),
+    (
+        "live_synced",
+        "1 if the live chain is synchronized up to tip",
+        |s| f64::from(u8::from(s.live.sync_end.is_some())),
+    ),
+    (
+        "live_backfill_size",
+        "Backfill size to achieve synchronization",
+        |s| as_f64(s.live.backfill_size),
+    ),
+    (
+        "live_backfill_failures",
+        "Number of backfill failures",
+        |s| as_f64(s.live.backfill_failures),
+    ),
+    (
+        "live_bytes",
+        "Size of the live blocks kept in memory, in bytes",
+        |s| as_f64(s.live.bytes),
+    ),
+    (
+        "live_spilled_blocks",
+        "Current number of live blocks spilled over to disk",
+        |s| as_f64(s.live.spilled_blocks),
+    ),
+    (
+        "live_evicted_blocks",
+        "Live blocks evicted from memory to keep the live chain bounded",
+        |s| as_f64(s.live.evicted_blocks),
+    ),
+    (
+        "live_dropped_blocks",
+        "Evicted live blocks which were not spilled over to disk",
+        |s| as_f64(s.live.dropped_blocks),
+    ),
+    ("live_followers", "Number of active followers", |s| {
+        as_f64(u64::try_from(s.live.follower.len()).unwrap_or(u64::MAX))
+    }),
+    // Mithril snapshot statistics
+    (
+        "mithril_updates",
+        "Number of downloaded Mithril snapshots",
+        |s| as_f64(s.mithril.updates),
+    ),
+    ("mithril_tip_slot", "Slot of the immutable tip", |s| {
+        as_f64(s.mithril.tip)
+    }),
+    (
+        "mithril_dl_failures",
+        "Number of failed snapshot downloads",
+        |s| as_f64(s.mithril.dl_failures),
+    ),
+    (
+        "mithril_dl_duration_seconds",
+        "Duration of the last snapshot download",
+        |s| as_f64(s.mithril.last_dl_duration),
+    ),
+    (
+        "mithril_dl_size_bytes",
+        "Size of the snapshot download archive",
+        |s| as_f64(s.mithril.dl_size),
+    ),
+    (
+        "mithril_dl_total_size_bytes",
+        "Expected size of the snapshot download archive",
+        |s| as_f64(s.mithril.dl_total_size),
+    ),
+    (
+        "mithril_dl_resumed_chunks",
+        "Number of resumed snapshot chunk downloads",
+        |s| as_f64(s.mithril.dl_resumed_chunks),
+    ),
+    (
+        "mithril_dl_invalid_chunks",
+        "Number of rejected snapshot chunk responses",
+        |s| as_f64(s.mithril.dl_invalid_chunks),
+    ),
+    (
+        "mithril_extract_duration_seconds",
+        "Duration of the last snapshot extraction",
+        |s| duration(s.mithril.extract_start, s.mithril.extract_end),
+    ),
+    (
+        "mithril_extract_failures",
+        "Number of failed snapshot extractions",
+        |s| as_f64(s.mithril.extract_failures),
+    ),
+    (
+        "mithril_extract_size_bytes",
+        "Size of the last extracted snapshot",
+        |s| as_f64(s.mithril.extract_size),
+    ),
+    (
+        "mithril_deduplicated_size_bytes",
+        "Deduplicated size versus the previous snapshot",
+        |s| as_f64(s.mithril.deduplicated_size),
+    ),
+    (
+        "mithril_deduplicated_files",
+        "Number of identical files deduplicated from the previous snapshot",
+        |s| as_f64(s.mithril.deduplicated),
+    ),
+    (
+        "mithril_changed_files",
+        "Number of changed files from the previous snapshot",
+        |s| as_f64(s.mithril.changed),
+    ),
+    (
+        "mithril_new_files",
+        "Number of new files from the previous snapshot",
+        |s| as_f64(s.mithril.new),
+    ),
+    (
+        "mithril_validate_duration_seconds",
+        "Duration of the last certificate validation",
+        |s| duration(s.mithril.validate_start, s.mithril.validate_end),
+    ),
+    (
+        "mithril_validate_failures",
+        "Number of failed snapshot validations",
+        |s| as_f64(s.mithril.validate_failures),
+    ),
+    (
+        "mithril_invalid_blocks",
+        "Blocks that failed to deserialize from the immutable chain",
+        |s| as_f64(s.mithril.invalid_blocks),
+    ),
+    (
+        "mithril_download_or_validation_failed",
+        "Number of failed snapshot downloads or validations",
+        |s| as_f64(s.mithril.download_or_validation_failed),
+    ),
+    (
+        "mithril_failed_to_get_tip",
+        "Number of failures to get the tip from the snapshot",
+        |s| as_f64(s.mithril.failed_to_get_tip),
+    ),
+    (
+        "mithril_tip_did_not_advance",
+        "Number of times the tip failed to advance",
+        |s| as_f64(s.mithril.tip_did_not_advance),
+    ),
+    (
+        "mithril_tip_failed_to_send_to_updater",
+        "Number of failures to send the new tip to the updater",
+        |s| as_f64(s.mithril.tip_failed_to_send_to_updater),
+    ),
+    (
+        "mithril_failed_to_activate_new_snapshot",
+        "Number of failures to activate a new snapshot",
+        |s| as_f64(s.mithril.failed_to_activate_new_snapshot),
+    ),
+];
+
+/// Extracts a relay peer metric value from the peer statistics.
+type PeerExtractor = fn(&Peer) -> f64;
+
+/// Network and peer labelled gauges, as `(name, help, extractor)`.
+const PEER_GAUGES: &[(&str, &str, PeerExtractor)] = &[
+    ("peer_score", "Health score of the relay peer", |p| {
+        f64::from(p.score)
+    }),
+    (
+        "peer_connected",
+        "1 if there is an active connection to the relay peer",
+        |p| f64::from(u8::from(p.connected)),
+    ),
+    (
+        "peer_connects",
+        "Number of connections to the relay peer",
+        |p| as_f64(p.connects),
+    ),
+    (
+        "peer_failures",
+        "Number of times the relay peer failed to connect or sync",
+        |p| as_f64(p.failures),
+    ),
+    (
+        "peer_latency_seconds",
+        "Duration of the last connection to the relay peer",
+        |p| as_f64(p.latency_ms) / 1000.0,
+    ),
+];
+
+/// Converts a statistics value to a metric value.
+/// Precision loss only happens above 2^53, which is acceptable for metrics.
+#[allow(clippy::cast_precision_loss)]
+fn as_f64(value: u64) -> f64 {
+    value as f64
+}
+
+/// Duration in seconds between the start and the end, 0 if it has not finished yet.
+#[allow(clippy::cast_precision_loss)]
+fn duration(start: DateTime<Utc>, end: DateTime<Utc>) -> f64 {
+    if end < start {
+        return 0.0;
+    }
+    (end - start).num_milliseconds() as f64 / 1000.0
+}
+
+/// Prometheus collector of the chain follower statistics of all networks.
+///
+/// The statistics are read when the metrics are gathered, so it only needs to be
+/// registered once.
+pub struct StatisticsCollector {
+    /// Network labelled gauges with their value extractors.
+    gauges: Vec<(GaugeVec, Extractor)>,
+    /// Network and peer labelled gauges with their value extractors.
+    peer_gauges: Vec<(GaugeVec, PeerExtractor)>,
+    /// Rollback counts, labelled by network, rollback type and depth.
+    rollbacks: GaugeVec,
+}
+
+impl StatisticsCollector {
+    /// Create a new statistics collector.
+    ///
+    /// # Errors
+    ///
+    /// If the metrics can not be created.
+    pub fn new() -> prometheus::Result<Self> {
+        let gauges = GAUGES
+            .iter()
+            .map(|(name, help, extractor)| {
+                let gauge =
+                    GaugeVec::new(Opts::new(*name, *help).namespace(NAMESPACE), &["network"])?;
+                Ok((gauge, *extractor))
+            })
+            .collect::<prometheus::Result<_>>()?;
+        let peer_gauges = PEER_GAUGES
+            .iter()
+            .map(|(name, help, extractor)| {
+                let gauge = GaugeVec::new(Opts::new(*name, *help).namespace(NAMESPACE), &[
+                    "network", "peer",
+                ])?;
+                Ok((gauge, *extractor))
+            })
+            .collect::<prometheus::Result<_>>()?;
+        let rollbacks = GaugeVec::new(
+            Opts::new("rollbacks", "Number of rollbacks of the depth").namespace(NAMESPACE),
+            &["network", "type", "depth"],
+        )?;
+
+        Ok(Self {
+            gauges,
+            peer_gauges,
+            rollbacks,
+        })
+    }
+
+    /// Create a new statistics collector and register it in the registry.
+    ///
+    /// # Errors
+    ///
+    /// If the metrics can not be created or registered.
+    pub fn register(registry: &Registry) -> prometheus::Result<()> {
+        registry.register(Box::new(Self::new()?))
+    }
+
+    /// Update all the metrics from the current statistics.
+    fn update(&self) {
+        self.rollbacks.reset();
+        // Peers can be reconfigured, so only export the current ones.
+        for (gauge, _) in &self.peer_gauges {
+            gauge.reset();
+        }
+        for network in Network::iter() {
+            let stats = Statistics::new(network);
+            let network = network.to_string();
+
+            for (gauge, extractor) in &self.gauges {
+                gauge.with_label_values(&[&network]).set(extractor(&stats));
+            }
+
+            for peer in &stats.live.peers {
+                for (gauge, extractor) in &self.peer_gauges {
+                    gauge
+                        .with_label_values(&[&network, &peer.address])
+                        .set(extractor(peer));
+                }
+            }
+
+            let rollbacks = &stats.live.rollbacks;
+            for (rollback_type, rollbacks) in [
+                ("live", &rollbacks.live),
+                ("peer", &rollbacks.peer),
+                ("follower", &rollbacks.follower),
+            ] {
+                for Rollback { depth, count } in rollbacks {
+                    self.rollbacks
+                        .with_label_values(&[&network, rollback_type, &depth.to_string()])
+                        .set(as_f64(*count));
+                }
+            }
+        }
+    }
+}
+
+impl Collector for StatisticsCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        self.gauges
+            .iter()
+            .flat_map(|(gauge, _)| gauge.desc())
+            .chain(self.peer_gauges.iter().flat_map(|(gauge, _)| gauge.desc()))
+            .chain(self.rollbacks.desc())
+            .collect()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        self.update();
+        self.gauges
+            .iter()
+            .flat_map(|(gauge, _)| gauge.collect())
+            .chain(
+                self.peer_gauges
+                    .iter()
+                    .flat_map(|(gauge, _)| gauge.collect()),
+            )
+            .chain(self.rollbacks.collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statistics_collector() {
+        let registry = Registry::new();
+        StatisticsCollector::register(&registry).unwrap();
+
+        let families = registry.gather();
+        for (name, ..) in GAUGES {
+            let name = format!("{NAMESPACE}_{name}");
+            let family = families.iter().find(|f| f.get_name() == name).unwrap();
+            assert_eq!(family.get_metric().len(), Network::iter().count());
+        }
+    }
+
+    #[test]
+    fn test_duration() {
+        let start = DateTime::<Utc>::from_timestamp(100, 0).unwrap();
+        let end = DateTime::<Utc>::from_timestamp(102, 500_000_000).unwrap();
+        assert!((duration(start, end) - 2.5).abs() < f64::EPSILON);
+        assert!(duration(end, start).abs() < f64::EPSILON);
+    }
+}
@@ -368,7 +368,8 @@ impl SnapshotDownloader for MithrilTurboDownloader {
         debug!("Probe Snapshot location='{location}'.");
 
         let dl_config = self.inner.cfg.dl_config.clone().unwrap_or_default();
-        let dl_processor = ParallelDownloadProcessor::new(location, dl_config).await?;
+        let dl_processor =
+            ParallelDownloadProcessor::new(location, self.inner.cfg.chain, dl_config).await?;
 
         // Decompress and extract and de-dupe each file in the archive.
         stats::mithril_extract_started(self.inner.cfg.chain);
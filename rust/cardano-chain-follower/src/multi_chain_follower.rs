@@ -0,0 +1,179 @@
+//! Follow several blockchain networks concurrently, managed by one handle.
+//!
+//! `MultiChainFollower` merges the chain updates of the followers of each network into a
+//! single stream, tagging every update with its network. The chain sync of all the
+//! networks can be started together, with their Mithril snapshot downloads sharing one
+//! turbo-downloader worker pool, so following several networks does not multiply the
+//! download connections.
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{
+    stream::{BoxStream, SelectAll},
+    Stream, StreamExt,
+};
+use tracing::debug;
+
+use crate::{
+    error::Result, stats::MultiChainStatistics, turbo_downloader::DlWorkerPool, ChainFollower,
+    ChainSyncConfig, ChainUpdate, Network, Point,
+};
+
+/// A chain update, tagged with the network it was received from.
+#[derive(Clone, Debug)]
+pub struct NetworkChainUpdate {
+    /// The network of the update.
+    pub chain: Network,
+    /// The update itself.
+    pub update: ChainUpdate,
+}
+
+/// Follows several blockchain networks concurrently.
+pub struct MultiChainFollower {
+    /// The networks being followed, in the order they were first added.
+    chains: Vec<Network>,
+    /// The merged chain updates of all the followers.
+    updates: SelectAll<BoxStream<'static, NetworkChainUpdate>>,
+}
+
+impl Default for MultiChainFollower {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MultiChainFollower {
+    /// Create a follower of no networks, followers are added with `add` or `follow`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            chains: Vec::new(),
+            updates: SelectAll::new(),
+        }
+    }
+
+    /// Runs Chain Synchronization of several networks.
+    ///
+    /// Must be done BEFORE the chains can be followed.
+    ///
+    /// # Arguments
+    ///
+    /// * `configs`: The chain sync configuration of each network.
+    /// * `worker_pool`: The worker pool shared by the Mithril snapshot downloads of all
+    ///   the networks. If `None`, each download uses its own workers.
+    ///
+    /// # Errors
+    ///
+    /// `Error`: If the chain sync of any network fails to start. The networks started
+    /// before it keep running.
+    pub async fn run_sync(
+        configs: Vec<ChainSyncConfig>, worker_pool: Option<DlWorkerPool>,
+    ) -> Result<()> {
+        for mut cfg in configs {
+            if let Some(worker_pool) = &worker_pool {
+                let dl_config = cfg
+                    .mithril_cfg
+                    .dl_config
+                    .clone()
+                    .unwrap_or_default()
+                    .with_worker_pool(worker_pool.clone());
+                cfg.mithril_cfg = cfg.mithril_cfg.with_dl_config(dl_config);
+            }
+            debug!(chain = cfg.chain.to_string(), "Starting Multi Chain Sync");
+            cfg.run().await?;
+        }
+        Ok(())
+    }
+
+    /// Follow a blockchain, alongside the already followed ones.
+    ///
+    /// See `ChainFollower::new` for the meaning of `start` and `end`.
+    pub async fn follow(&mut self, chain: Network, start: Point, end: Point) {
+        self.add(ChainFollower::new(chain, start, end).await);
+    }
+
+    /// Add an existing follower, e.g. one resumed from its checkpoint.
+    ///
+    /// A network may be followed more than once, for different ranges of blocks.
+    pub fn add(&mut self, follower: ChainFollower) {
+        let chain = follower.chain();
+        if !self.chains.contains(&chain) {
+            self.chains.push(chain);
+        }
+        self.updates.push(
+            follower
+                .into_stream()
+                .map(move |update| NetworkChainUpdate { chain, update })
+                .boxed(),
+        );
+    }
+
+    /// The networks being followed.
+    #[must_use]
+    pub fn chains(&self) -> &[Network] {
+        &self.chains
+    }
+
+    /// Number of the followers which still have updates to return.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.updates.len()
+    }
+
+    /// Returns `true` if no follower has updates left to return.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+
+    /// Get the next update from any of the followed networks.
+    ///
+    /// Returns `None` once every follower has reached its end.
+    pub async fn next(&mut self) -> Option<NetworkChainUpdate> {
+        self.updates.next().await
+    }
+
+    /// Get the statistics of the followed networks, combined and per network.
+    #[must_use]
+    pub fn statistics(&self) -> MultiChainStatistics {
+        MultiChainStatistics::new(&self.chains)
+    }
+}
+
+impl Stream for MultiChainFollower {
+    type Item = NetworkChainUpdate;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.updates.poll_next_unpin(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ORIGIN_POINT, TIP_POINT};
+
+    #[tokio::test]
+    async fn test_multi_chain_follower_add() {
+        let mut follower = MultiChainFollower::new();
+        assert!(follower.is_empty());
+        assert!(follower.chains().is_empty());
+
+        follower
+            .follow(Network::Mainnet, ORIGIN_POINT, TIP_POINT)
+            .await;
+        follower
+            .follow(Network::Preprod, ORIGIN_POINT, TIP_POINT)
+            .await;
+        follower
+            .follow(Network::Mainnet, TIP_POINT, TIP_POINT)
+            .await;
+
+        assert_eq!(follower.len(), 3);
+        assert_eq!(follower.chains(), &[Network::Mainnet, Network::Preprod]);
+        assert_eq!(follower.statistics().combined.networks, 2);
+    }
+}
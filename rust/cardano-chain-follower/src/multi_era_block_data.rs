@@ -174,6 +174,15 @@ impl MultiEraBlock {
         self.fork = fork;
     }
 
+    /// Remake the block with another previous point, without checking it links to the
+    /// block. Blocks already cloned are left unchanged.
+    #[cfg(test)]
+    pub(crate) fn set_previous(&mut self, previous: &Point) {
+        if let Some(inner) = Arc::get_mut(&mut self.inner) {
+            inner.previous = previous.clone();
+        }
+    }
+
     /// Decodes the data into a multi-era block.
     ///
     /// # Returns
@@ -381,24 +390,24 @@ pub(crate) mod tests {
     }
 
     /// Byron Test Block data
-    fn byron_block() -> Vec<u8> {
+    pub(crate) fn byron_block() -> Vec<u8> {
         hex::decode(include_str!("./../test_data/byron.block"))
             .expect("Failed to decode hex block.")
     }
 
     /// Shelley Test Block data
-    fn shelley_block() -> Vec<u8> {
+    pub(crate) fn shelley_block() -> Vec<u8> {
         hex::decode(include_str!("./../test_data/shelley.block"))
             .expect("Failed to decode hex block.")
     }
 
     /// Mary Test Block data
-    fn mary_block() -> Vec<u8> {
+    pub(crate) fn mary_block() -> Vec<u8> {
         hex::decode(include_str!("./../test_data/mary.block")).expect("Failed to decode hex block.")
     }
 
     /// Allegra Test Block data
-    fn allegra_block() -> Vec<u8> {
+    pub(crate) fn allegra_block() -> Vec<u8> {
         hex::decode(include_str!("./../test_data/allegra.block"))
             .expect("Failed to decode hex block.")
     }
@@ -1,6 +1,10 @@
 //! Cardano Chain Follower Statistics
 
-use std::sync::{Arc, LazyLock, RwLock};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, LazyLock, RwLock},
+    time::Duration,
+};
 
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
@@ -8,7 +12,7 @@ use serde::Serialize;
 use strum::{EnumIter, IntoEnumIterator};
 use tracing::error;
 
-use crate::Network;
+use crate::{utils::u64_from_saturating, Network};
 
 // -------- GENERAL STATISTIC TRACKING
 
@@ -32,6 +36,13 @@ pub struct Mithril {
     /// The size of the download archive, in bytes. (If not started and not ended, current
     /// partial download size).
     pub dl_size: u64,
+    /// The expected size of the download archive, in bytes.
+    pub dl_total_size: u64,
+    /// Number of chunk downloads resumed after an interrupted transfer.
+    pub dl_resumed_chunks: u64,
+    /// Number of chunk responses rejected because they did not match the requested
+    /// range.
+    pub dl_invalid_chunks: u64,
     /// Extraction start time. 1/1/1970-00:00:00 UTC = Never extracted.
     pub extract_start: DateTime<Utc>,
     /// Extraction end time. if `extract_end` < `extract_start` its the previous time we
@@ -76,6 +87,8 @@ impl Mithril {
     fn reset(&mut self) {
         self.updates = 0;
         self.dl_failures = 0;
+        self.dl_resumed_chunks = 0;
+        self.dl_invalid_chunks = 0;
         self.extract_failures = 0;
         self.validate_failures = 0;
         self.invalid_blocks = 0;
@@ -122,6 +135,31 @@ pub struct Follower {
     pub sync_end: Option<DateTime<Utc>>,
 }
 
+/// Individual relay peer stats
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct Peer {
+    /// Address of the relay peer.
+    pub address: String,
+    /// Health score of the peer. The healthiest peer is used for chain sync.
+    pub score: u32,
+    /// Is there an active connection to the peer.
+    pub connected: bool,
+    /// Number of times we connected to the peer.
+    pub connects: u64,
+    /// Number of times the peer failed to connect or sync.
+    pub failures: u64,
+    /// How long the last connection to the peer took, in milliseconds.
+    pub latency_ms: u64,
+}
+
+impl Peer {
+    /// Reset incremental counters in the peer statistics.
+    fn reset(&mut self) {
+        self.connects = 0;
+        self.failures = 0;
+    }
+}
+
 /// Statistics related to the live blockchain
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct Live {
@@ -142,6 +180,14 @@ pub struct Live {
     pub backfill_failure_time: Option<DateTime<Utc>>,
     /// Current Number of Live Blocks
     pub blocks: u64,
+    /// Current size of the Live Blocks kept in memory, in bytes.
+    pub bytes: u64,
+    /// Current Number of Live Blocks spilled over to disk.
+    pub spilled_blocks: u64,
+    /// Live Blocks evicted from memory to keep the live chain bounded.
+    pub evicted_blocks: u64,
+    /// Evicted Live Blocks which were dropped, rather than spilled over to disk.
+    pub dropped_blocks: u64,
     /// The current head of the live chain slot#
     pub head_slot: u64,
     /// The current live tip slot# as reported by the peer.
@@ -158,6 +204,8 @@ pub struct Live {
     pub last_disconnected_peer: String,
     /// Is there an active connection to the node
     pub connected: bool,
+    /// Statistics of each configured relay peer.
+    pub peers: Vec<Peer>,
     /// Rollback statistics.
     pub rollbacks: Rollbacks,
     /// New blocks read from blockchain.
@@ -174,6 +222,28 @@ impl Live {
         self.new_blocks = 0;
         self.reconnects = 0;
         self.invalid_blocks = 0;
+        self.evicted_blocks = 0;
+        self.dropped_blocks = 0;
+        self.peers.iter_mut().for_each(Peer::reset);
+    }
+
+    /// Get the stats of a relay peer, adding them if the peer is not yet known.
+    fn peer(&mut self, peer_address: &str) -> Option<&mut Peer> {
+        let index = match self
+            .peers
+            .iter()
+            .position(|peer| peer.address == peer_address)
+        {
+            Some(index) => index,
+            None => {
+                self.peers.push(Peer {
+                    address: peer_address.to_string(),
+                    ..Default::default()
+                });
+                self.peers.len().saturating_sub(1)
+            },
+        };
+        self.peers.get_mut(index)
     }
 }
 
@@ -291,6 +361,103 @@ impl Statistics {
     }
 }
 
+/// Statistics of all the followed networks, combined.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct CombinedStatistics {
+    /// Number of networks.
+    pub networks: u64,
+    /// Number of networks synchronized up-to-tip.
+    pub synced: u64,
+    /// Current Number of Live Blocks.
+    pub live_blocks: u64,
+    /// Current size of the Live Blocks kept in memory, in bytes.
+    pub live_bytes: u64,
+    /// New blocks read from the blockchains.
+    pub new_blocks: u64,
+    /// Blocks that failed to deserialize, live or immutable.
+    pub invalid_blocks: u64,
+    /// Number of times we connected/re-connected to the Nodes.
+    pub reconnects: u64,
+    /// Number of active followers.
+    pub followers: u64,
+    /// Number of Mithril Snapshots that have downloaded successfully.
+    pub mithril_updates: u64,
+    /// Number of times a Mithril Snapshot download failed.
+    pub mithril_dl_failures: u64,
+}
+
+impl CombinedStatistics {
+    /// Add the statistics of a network.
+    fn add(&mut self, stats: &Statistics) {
+        self.networks = self.networks.saturating_add(1);
+        if stats.live.sync_end.is_some() {
+            self.synced = self.synced.saturating_add(1);
+        }
+        self.live_blocks = self.live_blocks.saturating_add(stats.live.blocks);
+        self.live_bytes = self.live_bytes.saturating_add(stats.live.bytes);
+        self.new_blocks = self.new_blocks.saturating_add(stats.live.new_blocks);
+        self.invalid_blocks = self
+            .invalid_blocks
+            .saturating_add(stats.live.invalid_blocks)
+            .saturating_add(stats.mithril.invalid_blocks);
+        self.reconnects = self.reconnects.saturating_add(stats.live.reconnects);
+        self.followers = self
+            .followers
+            .saturating_add(u64_from_saturating(stats.live.follower.len()));
+        self.mithril_updates = self.mithril_updates.saturating_add(stats.mithril.updates);
+        self.mithril_dl_failures = self
+            .mithril_dl_failures
+            .saturating_add(stats.mithril.dl_failures);
+    }
+}
+
+/// Statistics of several follower networks, combined and per network.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct MultiChainStatistics {
+    /// Statistics of all the networks, combined.
+    pub combined: CombinedStatistics,
+    /// Statistics of each network, by network name.
+    pub networks: BTreeMap<String, Statistics>,
+}
+
+impl MultiChainStatistics {
+    /// Get the statistics of the blockchain networks.
+    #[must_use]
+    pub fn new(chains: &[Network]) -> Self {
+        Self::from_statistics(chains.iter().map(|chain| (*chain, Statistics::new(*chain))))
+    }
+
+    /// Combine the statistics of each network.
+    fn from_statistics(stats: impl IntoIterator<Item = (Network, Statistics)>) -> Self {
+        let mut this_stats = Self::default();
+        for (chain, stats) in stats {
+            if this_stats.networks.contains_key(&chain.to_string()) {
+                continue;
+            }
+            this_stats.combined.add(&stats);
+            this_stats.networks.insert(chain.to_string(), stats);
+        }
+        this_stats
+    }
+
+    /// Return the statistics formatted as JSON
+    #[must_use]
+    pub fn as_json(&self, pretty: bool) -> String {
+        let json = if pretty {
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string(self)
+        };
+        match json {
+            Ok(json) => json,
+            Err(error) => {
+                error!("{:?}", error);
+                String::new()
+            },
+        }
+    }
+}
+
 /// Count the invalidly deserialized blocks
 pub(crate) fn stats_invalid_block(chain: Network, immutable: bool) {
     // This will actually always succeed.
@@ -332,6 +499,40 @@ pub(crate) fn new_live_block(
     chain_stats.live.tip = tip_slot;
 }
 
+/// Count the Live Blocks evicted from memory, and those of them which were dropped.
+pub(crate) fn live_blocks_evicted(chain: Network, evicted: u64, dropped: u64) {
+    // This will actually always succeed.
+    let Some(stats) = lookup_stats(chain) else {
+        return;
+    };
+
+    let Ok(mut chain_stats) = stats.write() else {
+        // Worst case if this fails (it never should) is we stop updating stats.
+        error!("Stats RwLock should never be able to error.");
+        return;
+    };
+
+    chain_stats.live.evicted_blocks += evicted;
+    chain_stats.live.dropped_blocks += dropped;
+}
+
+/// Track the size of the Live Chain kept in memory, and the blocks spilled over to disk.
+pub(crate) fn live_chain_size(chain: Network, bytes: u64, spilled_blocks: u64) {
+    // This will actually always succeed.
+    let Some(stats) = lookup_stats(chain) else {
+        return;
+    };
+
+    let Ok(mut chain_stats) = stats.write() else {
+        // Worst case if this fails (it never should) is we stop updating stats.
+        error!("Stats RwLock should never be able to error.");
+        return;
+    };
+
+    chain_stats.live.bytes = bytes;
+    chain_stats.live.spilled_blocks = spilled_blocks;
+}
+
 /// Track the end of the current mithril update
 pub(crate) fn new_mithril_update(
     chain: Network, mithril_tip: u64, total_live_blocks: u64, tip_slot: u64,
@@ -416,6 +617,64 @@ pub(crate) fn peer_connected(chain: Network, active: bool, peer_address: &str) {
     }
 
     chain_stats.live.connected = active;
+
+    if let Some(peer) = chain_stats.live.peer(peer_address) {
+        if active {
+            peer.connects += 1;
+        }
+        peer.connected = active;
+    }
+}
+
+/// Record the relay peers chain sync is configured to use.
+pub(crate) fn peers_configured(chain: Network, peer_addresses: &[String], score: u32) {
+    // This will actually always succeed.
+    let Some(stats) = lookup_stats(chain) else {
+        return;
+    };
+
+    let Ok(mut chain_stats) = stats.write() else {
+        // Worst case if this fails (it never should) is we stop updating stats.
+        error!("Stats RwLock should never be able to error.");
+        return;
+    };
+
+    chain_stats.live.peers = peer_addresses
+        .iter()
+        .map(|address| {
+            Peer {
+                address: address.clone(),
+                score,
+                ..Default::default()
+            }
+        })
+        .collect();
+}
+
+/// Track the health of a relay peer.
+pub(crate) fn peer_health(
+    chain: Network, peer_address: &str, score: u32, failed: bool, latency: Option<Duration>,
+) {
+    // This will actually always succeed.
+    let Some(stats) = lookup_stats(chain) else {
+        return;
+    };
+
+    let Ok(mut chain_stats) = stats.write() else {
+        // Worst case if this fails (it never should) is we stop updating stats.
+        error!("Stats RwLock should never be able to error.");
+        return;
+    };
+
+    if let Some(peer) = chain_stats.live.peer(peer_address) {
+        peer.score = score;
+        if failed {
+            peer.failures += 1;
+        }
+        if let Some(latency) = latency {
+            peer.latency_ms = u64::try_from(latency.as_millis()).unwrap_or(u64::MAX);
+        }
+    }
 }
 
 /// Record when we started syncing
@@ -495,6 +754,55 @@ pub(crate) fn mithril_dl_finished(chain: Network, dl_size: Option<u64>) {
     }
 }
 
+/// Record the progress of the Mithril snapshot download.
+pub(crate) fn mithril_dl_progress(chain: Network, dl_size: u64, dl_total_size: u64) {
+    // This will actually always succeed.
+    let Some(stats) = lookup_stats(chain) else {
+        return;
+    };
+
+    let Ok(mut chain_stats) = stats.write() else {
+        // Worst case if this fails (it never should) is we stop updating stats.
+        error!("Stats RwLock should never be able to error.");
+        return;
+    };
+
+    chain_stats.mithril.dl_size = dl_size;
+    chain_stats.mithril.dl_total_size = dl_total_size;
+}
+
+/// Record an interrupted Mithril snapshot chunk download being resumed.
+pub(crate) fn mithril_dl_chunk_resumed(chain: Network) {
+    // This will actually always succeed.
+    let Some(stats) = lookup_stats(chain) else {
+        return;
+    };
+
+    let Ok(mut chain_stats) = stats.write() else {
+        // Worst case if this fails (it never should) is we stop updating stats.
+        error!("Stats RwLock should never be able to error.");
+        return;
+    };
+
+    chain_stats.mithril.dl_resumed_chunks += 1;
+}
+
+/// Record an invalid Mithril snapshot chunk response.
+pub(crate) fn mithril_dl_chunk_invalid(chain: Network) {
+    // This will actually always succeed.
+    let Some(stats) = lookup_stats(chain) else {
+        return;
+    };
+
+    let Ok(mut chain_stats) = stats.write() else {
+        // Worst case if this fails (it never should) is we stop updating stats.
+        error!("Stats RwLock should never be able to error.");
+        return;
+    };
+
+    chain_stats.mithril.dl_invalid_chunks += 1;
+}
+
 /// Record that extracting the mithril snapshot archive has started.
 pub(crate) fn mithril_extract_started(chain: Network) {
     // This will actually always succeed.
@@ -739,11 +1047,15 @@ mod tests {
             extract_failures: 3,
             validate_failures: 2,
             invalid_blocks: 1,
+            dl_resumed_chunks: 4,
+            dl_invalid_chunks: 2,
             ..Default::default()
         };
         mithril.reset();
         assert_eq!(mithril.updates, 0);
         assert_eq!(mithril.dl_failures, 0);
+        assert_eq!(mithril.dl_resumed_chunks, 0);
+        assert_eq!(mithril.dl_invalid_chunks, 0);
         assert_eq!(mithril.extract_failures, 0);
         assert_eq!(mithril.validate_failures, 0);
         assert_eq!(mithril.invalid_blocks, 0);
@@ -763,6 +1075,20 @@ mod tests {
         assert_eq!(live.invalid_blocks, 0);
     }
 
+    #[test]
+    fn test_live_peer() {
+        let mut live = Live::default();
+        live.peer("relay-a:3001").unwrap().failures = 2;
+        live.peer("relay-b:3001").unwrap().connects = 1;
+        assert_eq!(live.peers.len(), 2);
+        assert_eq!(live.peer("relay-a:3001").unwrap().failures, 2);
+        assert_eq!(live.peers.len(), 2);
+
+        live.reset();
+        assert!(live.peers.iter().all(|peer| peer.failures == 0));
+        assert!(live.peers.iter().all(|peer| peer.connects == 0));
+    }
+
     #[test]
     fn test_statistics_reset_stats() {
         let mut stats = Statistics::default();
@@ -799,6 +1125,19 @@ mod tests {
         assert_eq!(stats.live.tip, 200);
     }
 
+    #[test]
+    fn test_live_blocks_evicted() {
+        let network = Network::Preprod;
+        live_blocks_evicted(network, 3, 1);
+        live_chain_size(network, 4096, 2);
+        let stats = lookup_stats(network).unwrap();
+        let stats = stats.read().unwrap();
+        assert!(stats.live.evicted_blocks >= 3);
+        assert!(stats.live.dropped_blocks >= 1);
+        assert_eq!(stats.live.bytes, 4096);
+        assert_eq!(stats.live.spilled_blocks, 2);
+    }
+
     #[test]
     fn test_mithril_dl_started() {
         let network = Network::Preprod;
@@ -807,4 +1146,50 @@ mod tests {
         let stats = stats.read().unwrap();
         assert!(stats.mithril.dl_start <= Utc::now());
     }
+
+    #[test]
+    fn test_mithril_dl_progress() {
+        let network = Network::Preprod;
+        mithril_dl_progress(network, 1024, 4096);
+        let stats = lookup_stats(network).unwrap();
+        let stats = stats.read().unwrap();
+        assert_eq!(stats.mithril.dl_size, 1024);
+        assert_eq!(stats.mithril.dl_total_size, 4096);
+    }
+
+    #[test]
+    fn test_multi_chain_statistics() {
+        let mut mainnet = Statistics::default();
+        mainnet.live.blocks = 10;
+        mainnet.live.new_blocks = 5;
+        mainnet.live.invalid_blocks = 1;
+        mainnet.live.sync_end = Some(Utc::now());
+        mainnet.live.follower.push(Follower::default());
+        mainnet.mithril.invalid_blocks = 2;
+        let mut preprod = Statistics::default();
+        preprod.live.blocks = 3;
+        preprod.live.new_blocks = 2;
+        preprod.mithril.updates = 1;
+
+        let stats = MultiChainStatistics::from_statistics([
+            (Network::Mainnet, mainnet),
+            (Network::Preprod, preprod),
+            (Network::Mainnet, Statistics::default()),
+        ]);
+        assert_eq!(stats.networks.len(), 2);
+        assert_eq!(stats.combined.networks, 2);
+        assert_eq!(stats.combined.synced, 1);
+        assert_eq!(stats.combined.live_blocks, 13);
+        assert_eq!(stats.combined.new_blocks, 7);
+        assert_eq!(stats.combined.invalid_blocks, 3);
+        assert_eq!(stats.combined.followers, 1);
+        assert_eq!(stats.combined.mithril_updates, 1);
+        assert_eq!(
+            stats
+                .networks
+                .get(&Network::Preprod.to_string())
+                .map(|stats| stats.live.blocks),
+            Some(3)
+        );
+    }
 }
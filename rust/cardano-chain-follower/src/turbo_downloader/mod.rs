@@ -6,27 +6,33 @@
 //!
 //! NOTE: This uses synchronous threading and HTTP Gets because Async proved to be highly
 //! variable in its performance.
+//!
+//! An interrupted chunk transfer is resumed from the last received byte with a new
+//! range request, rather than downloading the whole chunk again. The total bandwidth of
+//! all the workers can be capped with `DlConfig::with_max_bandwidth`, and the number of
+//! chunks downloaded at once by several concurrent downloads can be capped by sharing a
+//! `DlWorkerPool` with `DlConfig::with_worker_pool`.
 
 use std::{
     io::Read,
     net::SocketAddr,
     sync::{
         atomic::{AtomicU64, AtomicUsize, Ordering},
-        Arc, Mutex, OnceLock,
+        Arc, Condvar, Mutex, OnceLock,
     },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use anyhow::{bail, Context, Result};
 use dashmap::DashMap;
 use http::{
-    header::{ACCEPT_RANGES, CONTENT_LENGTH, RANGE},
+    header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, RANGE},
     StatusCode,
 };
 use tracing::{debug, error};
 
-use crate::utils::u64_from_saturating;
+use crate::{network::Network, stats, utils::u64_from_saturating};
 
 /// A Simple DNS Balancing Resolver
 struct BalancingResolver {
@@ -118,6 +124,13 @@ impl BalancingResolver {
 /// Minimum rational size of a chunk in bytes.
 const MIN_CHUNK_SIZE: usize = 1024 * 4; // 4 KB
 
+/// Maximum number of bytes read at once, when the bandwidth is limited.
+const THROTTLED_READ_SIZE: usize = 1024 * 64; // 64 KB
+
+/// Maximum number of consecutive chunk requests which make no progress, before the
+/// chunk download fails.
+const MAX_CHUNK_RETRIES: usize = 3;
+
 /// Parallel Downloader Tuning parameters
 #[derive(Clone, Debug)]
 #[allow(clippy::struct_excessive_bools)]
@@ -132,6 +145,10 @@ pub struct DlConfig {
     pub connection_timeout: Option<Duration>,
     /// Timeout for each data read.
     pub data_read_timeout: Option<Duration>,
+    /// Maximum total download bandwidth of all workers, in bytes per second.
+    pub max_bandwidth: Option<u64>,
+    /// Worker pool shared with other downloads, caps the chunks downloaded at once.
+    pub worker_pool: Option<DlWorkerPool>,
 }
 
 impl DlConfig {
@@ -176,6 +193,20 @@ impl DlConfig {
         self
     }
 
+    /// Change the maximum total download bandwidth, in bytes per second
+    #[must_use]
+    pub fn with_max_bandwidth(mut self, max_bandwidth: u64) -> Self {
+        self.max_bandwidth = Some(max_bandwidth);
+        self
+    }
+
+    /// Share the worker pool with other downloads
+    #[must_use]
+    pub fn with_worker_pool(mut self, worker_pool: DlWorkerPool) -> Self {
+        self.worker_pool = Some(worker_pool);
+        self
+    }
+
     /// Resolve DNS addresses using Hickory Resolver
     fn resolve(url: &str, worker: usize) -> std::io::Result<Vec<std::net::SocketAddr>> {
         let Some(resolver) = RESOLVER.get() else {
@@ -218,7 +249,153 @@ impl Default for DlConfig {
             queue_ahead: 3,
             connection_timeout: None,
             data_read_timeout: None,
+            max_bandwidth: None,
+            worker_pool: None,
+        }
+    }
+}
+
+/// A pool of download workers, shared by concurrent downloads.
+///
+/// Every download still starts its own workers, but a worker only downloads a chunk
+/// while it holds one of the pool slots, so the downloads sharing the pool never make
+/// more requests at once than the pool size. Cheap to clone, clones share the slots.
+#[derive(Clone, Debug)]
+pub struct DlWorkerPool(Arc<DlWorkerPoolInner>);
+
+/// Shared state of the worker pool.
+#[derive(Debug)]
+struct DlWorkerPoolInner {
+    /// Number of the pool slots.
+    size: usize,
+    /// Number of the slots not held by a worker.
+    available: Mutex<usize>,
+    /// Signalled when a slot is released.
+    released: Condvar,
+}
+
+impl DlWorkerPool {
+    /// Create a new worker pool of `size` slots, at least one.
+    #[must_use]
+    pub fn new(size: usize) -> Self {
+        let size = size.max(1);
+        Self(Arc::new(DlWorkerPoolInner {
+            size,
+            available: Mutex::new(size),
+            released: Condvar::new(),
+        }))
+    }
+
+    /// Number of the pool slots.
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.0.size
+    }
+
+    /// Number of the slots currently held by workers.
+    #[must_use]
+    pub fn busy(&self) -> usize {
+        self.0
+            .available
+            .lock()
+            .map_or(0, |available| self.0.size.saturating_sub(*available))
+    }
+
+    /// Wait for a free slot, which is held until the returned guard is dropped.
+    fn acquire(&self) -> DlWorkerSlot<'_> {
+        let Ok(mut available) = self.0.available.lock() else {
+            error!("Worker pool lock should never be poisoned.");
+            return DlWorkerSlot(None);
+        };
+        while *available == 0 {
+            available = match self.0.released.wait(available) {
+                Ok(available) => available,
+                Err(_) => {
+                    error!("Worker pool lock should never be poisoned.");
+                    return DlWorkerSlot(None);
+                },
+            };
+        }
+        *available = available.saturating_sub(1);
+        DlWorkerSlot(Some(self))
+    }
+}
+
+/// A slot of the worker pool, released when dropped.
+struct DlWorkerSlot<'a>(Option<&'a DlWorkerPool>);
+
+impl Drop for DlWorkerSlot<'_> {
+    fn drop(&mut self) {
+        let Some(pool) = self.0 else {
+            return;
+        };
+        if let Ok(mut available) = pool.0.available.lock() {
+            *available = available.saturating_add(1).min(pool.0.size);
         }
+        pool.0.released.notify_one();
+    }
+}
+
+/// Limits the bandwidth shared by all the workers.
+struct BandwidthLimiter {
+    /// Maximum bandwidth, in bytes per second.
+    bytes_per_second: u64,
+    /// The time at which the bandwidth already reserved is used up.
+    next_free: Mutex<Instant>,
+}
+
+impl BandwidthLimiter {
+    /// Create a new bandwidth limiter.
+    fn new(bytes_per_second: u64) -> Self {
+        Self {
+            bytes_per_second: bytes_per_second.max(1),
+            next_free: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Reserve the bandwidth for `bytes`, and wait until it is available.
+    fn throttle(&self, bytes: usize) {
+        let nanos = u128::from(u64_from_saturating(bytes)) * 1_000_000_000
+            / u128::from(self.bytes_per_second);
+        let cost = Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX));
+
+        let wait = {
+            let Ok(mut next_free) = self.next_free.lock() else {
+                error!("Bandwidth limiter lock should never be poisoned.");
+                return;
+            };
+            let now = Instant::now();
+            // Unused bandwidth from idle periods is not accumulated.
+            let start = (*next_free).max(now);
+            *next_free = start + cost;
+            start.saturating_duration_since(now)
+        };
+
+        if !wait.is_zero() {
+            thread::sleep(wait);
+        }
+    }
+}
+
+/// A reader limited by the shared bandwidth limiter.
+struct ThrottledReader<'a, R> {
+    /// The inner reader.
+    inner: R,
+    /// The limiter, if the bandwidth is limited.
+    limiter: Option<&'a BandwidthLimiter>,
+}
+
+impl<R: Read> Read for ThrottledReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let Some(limiter) = self.limiter else {
+            return self.inner.read(buf);
+        };
+        let len = buf.len().min(THROTTLED_READ_SIZE);
+        let Some(buf) = buf.get_mut(..len) else {
+            return Ok(0);
+        };
+        limiter.throttle(len);
+        self.inner.read(buf)
     }
 }
 
@@ -249,8 +426,12 @@ type DlWorkOrder = usize;
 struct ParallelDownloadProcessorInner {
     /// URL to download from.
     url: String,
+    /// Network the download is for, used to record the download statistics.
+    chain: Network,
     /// Configuration
     cfg: DlConfig,
+    /// Shared download bandwidth limiter.
+    bandwidth_limiter: Option<BandwidthLimiter>,
     /// Size of the file we expect to download.
     file_size: usize,
     /// The last chunk we can request
@@ -265,6 +446,8 @@ struct ParallelDownloadProcessorInner {
     new_chunk_queue_rx: crossbeam_channel::Receiver<Option<()>>,
     /// Statistic tracking number of bytes downloaded per worker.
     bytes_downloaded: Vec<AtomicU64>,
+    /// Total number of bytes received, including partial chunks.
+    total_downloaded: AtomicU64,
     /// Left Over Bytes (from the reader)
     left_over_bytes: Mutex<Option<(Arc<Vec<u8>>, usize)>>,
     /// Next Expected Chunk
@@ -308,10 +491,19 @@ impl ParallelDownloadProcessorInner {
         }
     }
 
-    /// Sends a GET request to download a chunk of the file at the specified range
-    fn get_range(&self, agent: &ureq::Agent, chunk: usize) -> anyhow::Result<Arc<Vec<u8>>> {
-        let range_start = self.chunk_start(chunk);
+    /// Sends a GET request to download the rest of a chunk of the file.
+    ///
+    /// The chunk data is appended to `bytes`, which may already hold the start of the
+    /// chunk from an interrupted request. The received data is kept in `bytes` even if
+    /// the request fails, so the download can be resumed from it.
+    fn get_range(
+        &self, agent: &ureq::Agent, chunk: usize, bytes: &mut Vec<u8>,
+    ) -> anyhow::Result<()> {
+        let range_start = self.chunk_start(chunk) + bytes.len();
         let range_end_inclusive = self.chunk_end(chunk);
+        if range_start > range_end_inclusive {
+            return Ok(());
+        }
         let range_header = format!("bytes={range_start}-{range_end_inclusive}");
         let get_range_response = agent
             .get(&self.url)
@@ -328,19 +520,79 @@ impl ParallelDownloadProcessorInner {
             )
         }
 
+        // Make sure the server sent the range we asked for.
+        let expected_range = format!(
+            "bytes {range_start}-{range_end_inclusive}/{}",
+            self.file_size
+        );
+        let content_range = get_range_response.header(CONTENT_RANGE.as_str());
+        if content_range != Some(expected_range.as_str()) {
+            stats::mithril_dl_chunk_invalid(self.chain);
+            bail!("Unexpected Content-Range {content_range:?}, expected {expected_range}");
+        }
+
         let range_size = range_end_inclusive - range_start + 1;
-        let mut bytes: Vec<u8> = Vec::with_capacity(range_size);
+        bytes.reserve_exact(range_size);
 
-        let bytes_read = get_range_response
-            .into_reader()
+        let reader = ThrottledReader {
+            inner: get_range_response.into_reader(),
+            limiter: self.bandwidth_limiter.as_ref(),
+        };
+        let bytes_read = reader
             .take(u64_from_saturating(range_size))
-            .read_to_end(&mut bytes)?;
+            .read_to_end(bytes)?;
 
         if bytes_read != range_size {
             bail!("Expected {range_size} bytes in response, but only read {bytes_read}")
         }
 
-        Ok(Arc::new(bytes))
+        Ok(())
+    }
+
+    /// Download a chunk, resuming it from the received data if a request is interrupted.
+    ///
+    /// Fails if `MAX_CHUNK_RETRIES` consecutive requests make no progress.
+    fn get_chunk(&self, agent: &ureq::Agent, chunk: usize) -> Option<DlBlock> {
+        let mut bytes = Vec::new();
+        let mut retries = 0;
+        loop {
+            let received = bytes.len();
+            if received > 0 {
+                stats::mithril_dl_chunk_resumed(self.chain);
+            }
+
+            let result = self.get_range(agent, chunk, &mut bytes);
+            self.record_progress(bytes.len() - received);
+            match result {
+                Ok(()) => return Some(Arc::new(bytes)),
+                Err(error) => {
+                    error!(
+                        "Error getting chunk: {chunk:?}, received {} bytes, error: {error:?}",
+                        bytes.len()
+                    );
+                },
+            }
+
+            // Quickly retry on error, in case its transient. Only count the retries which
+            // made no progress, a slow but working connection should not fail the chunk.
+            if bytes.len() == received {
+                retries += 1;
+                if retries > MAX_CHUNK_RETRIES {
+                    return None;
+                }
+            } else {
+                retries = 0;
+            }
+        }
+    }
+
+    /// Record the newly downloaded bytes in the download progress.
+    fn record_progress(&self, bytes: usize) {
+        let total = self
+            .total_downloaded
+            .fetch_add(u64_from_saturating(bytes), Ordering::SeqCst)
+            .saturating_add(u64_from_saturating(bytes));
+        stats::mithril_dl_progress(self.chain, total, u64_from_saturating(self.file_size));
     }
 
     /// Queue Chunk to processor.
@@ -365,7 +617,7 @@ impl ParallelDownloadProcessor {
     ///
     /// Can Fail IF there is no HTTP client provided or the URL does not support getting
     /// the content length.
-    pub(crate) async fn new(url: &str, mut cfg: DlConfig) -> anyhow::Result<Self> {
+    pub(crate) async fn new(url: &str, chain: Network, mut cfg: DlConfig) -> anyhow::Result<Self> {
         if cfg.chunk_size < MIN_CHUNK_SIZE {
             bail!(
                 "Download chunk size must be at least {} bytes",
@@ -390,6 +642,8 @@ impl ParallelDownloadProcessor {
 
         let processor = ParallelDownloadProcessor(Arc::new(ParallelDownloadProcessorInner {
             url: String::from(url),
+            chain,
+            bandwidth_limiter: cfg.max_bandwidth.map(BandwidthLimiter::new),
             cfg: cfg.clone(),
             file_size,
             last_chunk,
@@ -398,6 +652,7 @@ impl ParallelDownloadProcessor {
             new_chunk_queue_rx: new_chunk_queue.1,
             new_chunk_queue_tx: new_chunk_queue.0,
             bytes_downloaded,
+            total_downloaded: AtomicU64::new(0),
             left_over_bytes: Mutex::new(None),
             next_expected_chunk: AtomicUsize::new(0),
             next_requested_chunk: AtomicUsize::new(0),
@@ -452,25 +707,11 @@ impl ParallelDownloadProcessor {
                 let delay = Duration::from_millis(next_chunk as u64 * 2);
                 thread::sleep(delay);
             }
-            let mut retries = 0;
-            let mut block;
             // debug!("Worker {worker_id} DL chunk {next_chunk}");
-            loop {
-                block = match params.get_range(&http_agent, next_chunk) {
-                    Ok(block) => Some(block),
-                    Err(error) => {
-                        error!("Error getting chunk: {:?}, error: {:?}", next_chunk, error);
-                        None
-                    },
-                };
-
-                // Quickly retry on error, in case its transient.
-                if block.is_some() || retries > 3 {
-                    break;
-                }
-                retries += 1;
-            }
-            // debug!("Worker {worker_id} DL chunk done {next_chunk}: {retries}");
+            let slot = params.cfg.worker_pool.as_ref().map(DlWorkerPool::acquire);
+            let block = params.get_chunk(&http_agent, next_chunk);
+            drop(slot);
+            // debug!("Worker {worker_id} DL chunk done {next_chunk}");
 
             if let Some(ref block) = block {
                 if let Some(dl_stat) = params.bytes_downloaded.get(worker_id) {
@@ -726,3 +967,182 @@ fn get_content_length(url: &str) -> anyhow::Result<usize> {
 
     Ok(content_length)
 }
+
+#[cfg(test)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// Chunk size of the test downloads.
+    const CHUNK_SIZE: usize = MIN_CHUNK_SIZE;
+
+    /// Serve the responses from a local HTTP server, one per connection, in order.
+    /// Returns the URL to download from, and the thread returning the `Range` header of
+    /// every request.
+    fn serve(responses: Vec<Vec<u8>>) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/file", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|response| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut request = BufReader::new(stream.try_clone().unwrap());
+                    let mut range = String::new();
+                    loop {
+                        let mut line = String::new();
+                        request.read_line(&mut line).unwrap();
+                        if line.trim().is_empty() {
+                            break;
+                        }
+                        if let Some(value) = line.to_lowercase().strip_prefix("range:") {
+                            range = value.trim().to_string();
+                        }
+                    }
+                    stream.write_all(&response).unwrap();
+                    range
+                })
+                .collect()
+        });
+        (url, server)
+    }
+
+    /// Ranged response, which may only carry a part of the announced content.
+    fn partial_content(content_range: &str, content_length: usize, body: &[u8]) -> Vec<u8> {
+        let mut response = format!(
+            "HTTP/1.1 206 Partial Content\r\nContent-Range: {content_range}\r\nContent-Length: {content_length}\r\nConnection: close\r\n\r\n"
+        )
+        .into_bytes();
+        response.extend_from_slice(body);
+        response
+    }
+
+    /// Test file of two chunks.
+    fn test_file() -> Vec<u8> {
+        (0..CHUNK_SIZE * 2)
+            .map(|index| u8::try_from(index % 251).unwrap())
+            .collect()
+    }
+
+    /// Download processor state of the file, without any worker.
+    fn processor(url: String, file_size: usize) -> ParallelDownloadProcessorInner {
+        let cfg = DlConfig::new().with_workers(1).with_chunk_size(CHUNK_SIZE);
+        let new_chunk_queue = crossbeam_channel::unbounded();
+        ParallelDownloadProcessorInner {
+            url,
+            chain: Network::Preprod,
+            bandwidth_limiter: None,
+            cfg,
+            file_size,
+            last_chunk: file_size.div_ceil(CHUNK_SIZE),
+            reorder_queue: DashMap::new(),
+            work_queue: DashMap::new(),
+            new_chunk_queue_tx: new_chunk_queue.0,
+            new_chunk_queue_rx: new_chunk_queue.1,
+            bytes_downloaded: vec![AtomicU64::new(0)],
+            total_downloaded: AtomicU64::new(0),
+            left_over_bytes: Mutex::new(None),
+            next_expected_chunk: AtomicUsize::new(0),
+            next_requested_chunk: AtomicUsize::new(0),
+        }
+    }
+
+    #[test]
+    fn test_bandwidth_limiter() {
+        assert_eq!(BandwidthLimiter::new(0).bytes_per_second, 1);
+
+        // 5 reservations of 100ms each, only the first one is free.
+        let limiter = BandwidthLimiter::new(10_000);
+        let start = Instant::now();
+        for _ in 0..5 {
+            limiter.throttle(1_000);
+        }
+        assert!(start.elapsed() >= Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_throttled_reader() {
+        let data = vec![1u8; THROTTLED_READ_SIZE * 2];
+        let mut buf = vec![0u8; data.len()];
+
+        // Unlimited reads are not split.
+        let mut reader = ThrottledReader {
+            inner: data.as_slice(),
+            limiter: None,
+        };
+        assert_eq!(reader.read(&mut buf).unwrap(), data.len());
+
+        // Limited reads are split, and wait for the bandwidth.
+        let limiter = BandwidthLimiter::new(u64_from_saturating(THROTTLED_READ_SIZE * 4));
+        let mut reader = ThrottledReader {
+            inner: data.as_slice(),
+            limiter: Some(&limiter),
+        };
+        let start = Instant::now();
+        assert_eq!(reader.read(&mut buf).unwrap(), THROTTLED_READ_SIZE);
+        let mut read = Vec::new();
+        reader.read_to_end(&mut read).unwrap();
+        assert_eq!(read.len(), THROTTLED_READ_SIZE);
+        assert!(start.elapsed() >= Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_get_chunk_resumes_partial_read() {
+        let file = test_file();
+        let received = 1_000;
+        let resume_start = CHUNK_SIZE + received;
+        let (url, server) = serve(vec![
+            // The connection is closed after the first part of the chunk.
+            partial_content(
+                &format!("bytes {CHUNK_SIZE}-{}/{}", file.len() - 1, file.len()),
+                CHUNK_SIZE,
+                &file[CHUNK_SIZE..resume_start],
+            ),
+            partial_content(
+                &format!("bytes {resume_start}-{}/{}", file.len() - 1, file.len()),
+                file.len() - resume_start,
+                &file[resume_start..],
+            ),
+        ]);
+        let processor = processor(url, file.len());
+
+        let chunk = processor.get_chunk(&ureq::Agent::new(), 1).unwrap();
+        assert_eq!(chunk.as_slice(), &file[CHUNK_SIZE..]);
+        assert_eq!(
+            processor.total_downloaded.load(Ordering::SeqCst),
+            u64_from_saturating(CHUNK_SIZE)
+        );
+        assert_eq!(server.join().unwrap(), vec![
+            format!("bytes={CHUNK_SIZE}-{}", file.len() - 1),
+            format!("bytes={resume_start}-{}", file.len() - 1),
+        ]);
+    }
+
+    #[test]
+    fn test_get_chunk_rejects_content_range() {
+        let file = test_file();
+        // The first chunk is sent, when the second chunk is requested.
+        let response = partial_content(
+            &format!("bytes 0-{}/{}", CHUNK_SIZE - 1, file.len()),
+            CHUNK_SIZE,
+            &file[..CHUNK_SIZE],
+        );
+        let (url, server) = serve(vec![response; MAX_CHUNK_RETRIES + 2]);
+        let processor = processor(url, file.len());
+        let agent = ureq::Agent::new();
+
+        let mut bytes = Vec::new();
+        let error = processor.get_range(&agent, 1, &mut bytes).unwrap_err();
+        assert!(error.to_string().contains("Unexpected Content-Range"));
+        assert!(bytes.is_empty());
+
+        // Rejected chunks make no progress, so the chunk fails after the retries.
+        assert!(processor.get_chunk(&agent, 1).is_none());
+        assert_eq!(server.join().unwrap().len(), MAX_CHUNK_RETRIES + 2);
+    }
+}
@@ -0,0 +1,92 @@
+//! `catalyst_voting::vote_protocol::voter` bulk vote encryption benchmark
+//!
+//! Compares the throughput of encrypting a vote per proposal with `encrypt_vote`, with
+//! the precomputed election public key table, and with `encrypt_votes_batch`, which
+//! also encrypts the votes in parallel. `encrypt_votes_batch` is expected to have at
+//! least 5x the throughput of `encrypt_vote`, compare the reported `thrpt` values.
+//!
+//! To run these benchmarks use
+//! ```shell
+//! SAMPLE_SIZE=<sample size> VOTES_NUMBER=<votes number> cargo bench -p catalyst-voting vote_encryption
+//! ```
+#![allow(
+    missing_docs,
+    clippy::missing_docs_in_private_items,
+    clippy::unwrap_used
+)]
+
+use catalyst_voting::{
+    crypto::rng::default_rng,
+    vote_protocol::{
+        committee::ElectionSecretKey,
+        voter::{encrypt_vote, encrypt_vote_with_table, encrypt_votes_batch, Vote},
+    },
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const VOTES_NUMBER_ENV: &str = "VOTES_NUMBER";
+const SAMPLE_SIZE_ENV: &str = "SAMPLE_SIZE";
+const DEFAULT_SAMPLE_SIZE: usize = 10;
+const DEFAULT_VOTES_NUMBER: usize = 1000;
+
+const VOTING_OPTIONS: usize = 3;
+
+fn vote_encryption_benches(c: &mut Criterion) {
+    let sample_size = std::env::var(SAMPLE_SIZE_ENV)
+        .map(|s| s.parse().unwrap())
+        .unwrap_or(DEFAULT_SAMPLE_SIZE);
+    let votes_number = std::env::var(VOTES_NUMBER_ENV)
+        .map(|s| s.parse().unwrap())
+        .unwrap_or(DEFAULT_VOTES_NUMBER);
+
+    let mut group = c.benchmark_group("bulk vote encryption");
+    group.sample_size(sample_size);
+    group.throughput(Throughput::Elements(votes_number as u64));
+
+    let election_public_key = ElectionSecretKey::random_with_default_rng().public_key();
+    let votes: Vec<_> = (0..votes_number)
+        .map(|i| Vote::new(i % VOTING_OPTIONS, VOTING_OPTIONS).unwrap())
+        .collect();
+    let mut rng = default_rng();
+
+    group.bench_with_input(
+        BenchmarkId::new("encrypt_vote", votes_number),
+        &votes,
+        |b, votes| {
+            b.iter(|| {
+                votes
+                    .iter()
+                    .map(|vote| encrypt_vote(vote, &election_public_key, &mut rng))
+                    .collect::<Vec<_>>()
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("encrypt_vote_with_table", votes_number),
+        &votes,
+        |b, votes| {
+            b.iter(|| {
+                let table = election_public_key.precompute();
+                votes
+                    .iter()
+                    .map(|vote| encrypt_vote_with_table(vote, &table, &mut rng))
+                    .collect::<Vec<_>>()
+            });
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("encrypt_votes_batch", votes_number),
+        &votes,
+        |b, votes| {
+            b.iter(|| encrypt_votes_batch(votes, &election_public_key, &mut rng));
+        },
+    );
+
+    group.finish();
+}
+
+criterion_group!(benches, vote_encryption_benches);
+
+criterion_main!(benches);
@@ -21,8 +21,11 @@ use catalyst_voting::{
             tally, DecryptionTallySetup,
         },
         voter::{
-            encrypt_vote,
-            proof::{generate_voter_proof, verify_voter_proof, VoterProofCommitment},
+            encrypt_vote, encrypt_votes_batch,
+            proof::{
+                generate_voter_proof, verify_voter_proof, verify_voter_proofs_batch,
+                VoterProofCommitment,
+            },
             Vote,
         },
     },
@@ -121,6 +124,15 @@ fn vote_protocol_benches(c: &mut Criterion) {
         });
     });
 
+    group.bench_function("vote batch encryption", |b| {
+        b.iter(|| {
+            (encrypted_votes, randomness) =
+                encrypt_votes_batch(&votes, &election_public_key, &mut rng)
+                    .into_iter()
+                    .unzip();
+        });
+    });
+
     let mut voter_proofs = Vec::new();
     group.bench_function("voter proof generation", |b| {
         b.iter(|| {
@@ -160,6 +172,20 @@ fn vote_protocol_benches(c: &mut Criterion) {
         });
     });
 
+    group.bench_function("voter proof batch verification", |b| {
+        b.iter(|| {
+            let is_ok = verify_voter_proofs_batch(
+                encrypted_votes
+                    .iter()
+                    .zip(voter_proofs.iter())
+                    .map(|(enc_v, p)| (enc_v, &voter_proof_commitment, p)),
+                &election_public_key,
+                &mut rng,
+            );
+            assert!(is_ok);
+        });
+    });
+
     let mut encrypted_tallies = Vec::new();
     group.bench_function("tally", |b| {
         b.iter(|| {
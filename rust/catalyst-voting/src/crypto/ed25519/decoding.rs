@@ -1,12 +1,19 @@
 //! `Ed25519` objects decoding implementation
+//!
+//! ```cddl
+//! public-key = bytes .size 32
+//! signature = bytes .size 64
+//! ```
 
 use anyhow::anyhow;
 use ed25519_dalek::{
     Signature as Ed25519Signature, SigningKey, VerifyingKey, PUBLIC_KEY_LENGTH, SECRET_KEY_LENGTH,
     SIGNATURE_LENGTH,
 };
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
 
 use super::{PrivateKey, PublicKey, Signature};
+use crate::utils::decode_cbor_array;
 
 impl PrivateKey {
     /// `PrivateKey` bytes size
@@ -63,3 +70,69 @@ impl Signature {
         Self(Ed25519Signature::from_bytes(bytes))
     }
 }
+
+impl Encode<()> for PublicKey {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for PublicKey {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        Self::from_bytes(&decode_cbor_array(d, "public key")?)
+            .map_err(|e| decode::Error::message(format!("Cannot decode public key, error: {e}")))
+    }
+}
+
+impl Encode<()> for Signature {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for Signature {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        Ok(Self::from_bytes(&decode_cbor_array(d, "signature")?))
+    }
+}
+
+#[cfg(feature = "serde")]
+crate::utils::impl_serde_as_cbor!(PublicKey, Signature);
+
+#[cfg(test)]
+mod tests {
+    use super::{super::sign, *};
+
+    #[test]
+    fn ed25519_cbor_golden_test() {
+        // RFC 8032 test vector 1.
+        let private_key = PrivateKey::from_bytes(
+            &hex::decode("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60")
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        );
+
+        let public_key = private_key.public_key();
+        let bytes = minicbor::to_vec(&public_key).unwrap();
+        assert_eq!(
+            hex::encode(&bytes),
+            "5820d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+        );
+        assert_eq!(minicbor::decode::<PublicKey>(&bytes).unwrap(), public_key);
+
+        let signature = sign(&private_key, &[]);
+        let bytes = minicbor::to_vec(&signature).unwrap();
+        assert_eq!(
+            hex::encode(&bytes),
+            "5840e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+        );
+        assert_eq!(minicbor::decode::<Signature>(&bytes).unwrap(), signature);
+    }
+}
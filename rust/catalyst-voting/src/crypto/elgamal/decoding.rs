@@ -1,8 +1,14 @@
 //! Elgamal objects decoding implementation
+//!
+//! ```cddl
+//! ciphertext = bytes .size 64
+//! ```
 
 use anyhow::anyhow;
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
 
 use super::{Ciphertext, GroupElement};
+use crate::utils::decode_cbor_array;
 
 impl Ciphertext {
     /// `Ciphertext` bytes size
@@ -34,12 +40,42 @@ impl Ciphertext {
     }
 }
 
+impl Encode<()> for Ciphertext {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for Ciphertext {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        Self::from_bytes(&decode_cbor_array(d, "ciphertext")?)
+            .map_err(|e| decode::Error::message(format!("Cannot decode ciphertext, error: {e}")))
+    }
+}
+
+#[cfg(feature = "serde")]
+crate::utils::impl_serde_as_cbor!(Ciphertext);
+
 #[cfg(test)]
 mod tests {
     use test_strategy::proptest;
 
     use super::*;
 
+    #[test]
+    fn ciphertext_cbor_golden_test() {
+        let ciphertext = Ciphertext(GroupElement::zero(), GroupElement::GENERATOR);
+        let bytes = minicbor::to_vec(&ciphertext).unwrap();
+        assert_eq!(
+            hex::encode(&bytes),
+            "58400000000000000000000000000000000000000000000000000000000000000000e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76"
+        );
+        assert_eq!(minicbor::decode::<Ciphertext>(&bytes).unwrap(), ciphertext);
+    }
+
     #[proptest]
     fn ciphertext_to_bytes_from_bytes_test(c1: Ciphertext) {
         let bytes = c1.to_bytes();
@@ -5,7 +5,7 @@ mod decoding;
 
 use std::ops::{Add, Mul};
 
-use crate::crypto::group::{GroupElement, Scalar};
+use crate::crypto::group::{GroupElement, GroupElementTable, Scalar};
 
 /// `ElGamal` ciphertext, encrypted message with the public key.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -44,6 +44,16 @@ pub fn encrypt(message: &Scalar, public_key: &GroupElement, randomness: &Scalar)
     Ciphertext(e1, e2)
 }
 
+/// The same as `encrypt`, but with the precomputed multiples table of the `public_key`,
+/// which is faster when encrypting many messages with the same public key.
+pub fn encrypt_with_table(
+    message: &Scalar, public_key: &GroupElementTable, randomness: &Scalar,
+) -> Ciphertext {
+    let e1 = GroupElement::GENERATOR.mul(randomness);
+    let e2 = &GroupElement::GENERATOR.mul(message) + &public_key.mul(randomness);
+    Ciphertext(e1, e2)
+}
+
 /// Decrypt `ElGamal` `Ciphertext`, returns the original message represented as a
 /// `GroupElement`.
 pub fn decrypt(cipher: &Ciphertext, secret_key: &Scalar) -> GroupElement {
@@ -124,4 +134,15 @@ mod tests {
 
         assert_eq!(decrypted, GroupElement::GENERATOR.mul(&message));
     }
+
+    #[proptest]
+    fn elgamal_encryption_with_table_test(secret_key: Scalar, message: Scalar, randomness: Scalar) {
+        let public_key = generate_public_key(&secret_key);
+        let public_key_table = GroupElementTable::new(&public_key);
+
+        assert_eq!(
+            encrypt_with_table(&message, &public_key_table, &randomness),
+            encrypt(&message, &public_key, &randomness)
+        );
+    }
 }
@@ -1,9 +1,16 @@
 //! ristretto255 objects decoding implementation
+//!
+//! ```cddl
+//! scalar = bytes .size 32
+//! group-element = bytes .size 32
+//! ```
 
 use anyhow::anyhow;
 use curve25519_dalek::{ristretto::CompressedRistretto, scalar::Scalar as IScalar};
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
 
 use super::{GroupElement, Scalar};
+use crate::utils::decode_cbor_array;
 
 impl Scalar {
     /// `Scalar` bytes size
@@ -48,12 +55,87 @@ impl GroupElement {
     }
 }
 
+impl Encode<()> for Scalar {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for Scalar {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        Self::from_bytes(decode_cbor_array(d, "scalar")?)
+            .map_err(|e| decode::Error::message(format!("Cannot decode scalar, error: {e}")))
+    }
+}
+
+impl Encode<()> for GroupElement {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for GroupElement {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        Self::from_bytes(&decode_cbor_array(d, "group element")?)
+            .map_err(|e| decode::Error::message(format!("Cannot decode group element, error: {e}")))
+    }
+}
+
+#[cfg(feature = "serde")]
+crate::utils::impl_serde_as_cbor!(Scalar, GroupElement);
+
 #[cfg(test)]
 mod tests {
     use test_strategy::proptest;
 
     use super::*;
 
+    /// Compressed ristretto255 generator.
+    const GENERATOR_HEX: &str = "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76";
+
+    #[test]
+    fn scalar_group_element_cbor_golden_test() {
+        let scalar = Scalar::from(1);
+        let bytes = minicbor::to_vec(&scalar).unwrap();
+        assert_eq!(
+            hex::encode(&bytes),
+            "58200100000000000000000000000000000000000000000000000000000000000000"
+        );
+        assert_eq!(minicbor::decode::<Scalar>(&bytes).unwrap(), scalar);
+
+        let bytes = minicbor::to_vec(&GroupElement::GENERATOR).unwrap();
+        assert_eq!(hex::encode(&bytes), format!("5820{GENERATOR_HEX}"));
+        assert_eq!(
+            minicbor::decode::<GroupElement>(&bytes).unwrap(),
+            GroupElement::GENERATOR
+        );
+
+        // Non canonical scalar.
+        assert!(
+            minicbor::decode::<Scalar>(&[[0x58, 0x20].as_slice(), &[0xFF; 32]].concat()).is_err()
+        );
+        // Invalid length.
+        assert!(minicbor::decode::<GroupElement>(&[0x41, 0x00]).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn scalar_serde_test() {
+        let scalar = Scalar::from(1);
+        let json = serde_json::to_string(&scalar).unwrap();
+        assert_eq!(
+            json,
+            "\"58200100000000000000000000000000000000000000000000000000000000000000\""
+        );
+        assert_eq!(serde_json::from_str::<Scalar>(&json).unwrap(), scalar);
+    }
+
     #[proptest]
     fn scalar_to_bytes_from_bytes_test(e1: Scalar) {
         let bytes = e1.to_bytes();
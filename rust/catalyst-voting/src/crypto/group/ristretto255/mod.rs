@@ -11,8 +11,9 @@ use std::{
 
 use curve25519_dalek::{
     constants::{RISTRETTO_BASEPOINT_POINT, RISTRETTO_BASEPOINT_TABLE},
+    ristretto::RistrettoBasepointTable,
     scalar::Scalar as IScalar,
-    traits::Identity,
+    traits::{Identity, VartimeMultiscalarMul},
     RistrettoPoint,
 };
 
@@ -31,6 +32,12 @@ pub struct Scalar(IScalar);
 #[must_use]
 pub struct GroupElement(RistrettoPoint);
 
+/// Precomputed multiples of the group element, speeds up the repeated scalar
+/// multiplications by the same group element.
+#[derive(Clone)]
+#[must_use]
+pub struct GroupElementTable(RistrettoBasepointTable);
+
 impl From<u64> for Scalar {
     fn from(value: u64) -> Self {
         Scalar(IScalar::from(value))
@@ -95,10 +102,40 @@ impl GroupElement {
     where D: Digest<OutputSize = U64> + Default {
         GroupElement(RistrettoPoint::from_hash(hash))
     }
+
+    /// Multi-scalar multiplication, like `scalars[0] * points[0] + ... + scalars[n] *
+    /// points[n]`.
+    /// Runs in variable time, so must be used only with the public data.
+    pub fn vartime_multiscalar_mul<'a>(
+        scalars: impl IntoIterator<Item = &'a Scalar>,
+        points: impl IntoIterator<Item = &'a GroupElement>,
+    ) -> GroupElement {
+        GroupElement(RistrettoPoint::vartime_multiscalar_mul(
+            scalars.into_iter().map(|s| &s.0),
+            points.into_iter().map(|p| &p.0),
+        ))
+    }
+}
+
+impl GroupElementTable {
+    /// Precompute the multiples table of the group element.
+    /// It is a relatively heavy operation, which pays off only when the table is reused
+    /// for many multiplications.
+    pub fn new(point: &GroupElement) -> Self {
+        GroupElementTable(RistrettoBasepointTable::create(&point.0))
+    }
 }
 
 // `std::ops` traits implementations
 
+impl Mul<&Scalar> for &GroupElementTable {
+    type Output = GroupElement;
+
+    fn mul(self, other: &Scalar) -> GroupElement {
+        GroupElement(&self.0 * &other.0)
+    }
+}
+
 impl Mul<&GroupElement> for &Scalar {
     type Output = GroupElement;
 
@@ -221,5 +258,8 @@ mod tests {
 
         let ge = GroupElement::GENERATOR.mul(&e1).mul(&e1.inverse());
         assert_eq!(ge, GroupElement::GENERATOR);
+
+        let msm = GroupElement::vartime_multiscalar_mul([&e1, &e2], [&ge1, &ge2]);
+        assert_eq!(msm, &ge1.mul(&e1) + &ge2.mul(&e2));
     }
 }
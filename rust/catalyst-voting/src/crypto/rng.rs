@@ -12,3 +12,13 @@ use rand_core::{CryptoRngCore, SeedableRng};
 pub fn default_rng() -> impl CryptoRngCore {
     ChaCha8Rng::from_entropy()
 }
+
+/// Deterministic random number generator `rand_chacha::ChaCha8Rng` seeded with `seed`,
+/// the same seed always produces the same keys, ciphertexts and proofs.
+///
+/// **NOT** suitable for production use, it is meant for the reproducible tests only.
+#[cfg(any(test, feature = "test-utils"))]
+#[must_use]
+pub fn test_rng(seed: u64) -> impl CryptoRngCore + Clone {
+    ChaCha8Rng::seed_from_u64(seed)
+}
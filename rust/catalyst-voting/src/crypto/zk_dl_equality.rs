@@ -12,15 +12,67 @@
 
 // cspell: words NIZK
 
-use crate::crypto::{
-    group::{GroupElement, Scalar},
-    hash::{digest::Digest, Blake2b512Hasher},
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
+
+use crate::{
+    crypto::{
+        group::{GroupElement, Scalar},
+        hash::{digest::Digest, Blake2b512Hasher},
+    },
+    utils::decode_cbor_array,
 };
 
 /// DLEQ proof struct
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[must_use]
 pub struct DleqProof(Scalar, Scalar);
 
+impl DleqProof {
+    /// `DleqProof` bytes size
+    pub const BYTES_SIZE: usize = Scalar::BYTES_SIZE * 2;
+
+    /// Attempt to construct a `DleqProof` from a byte representation.
+    ///
+    /// # Errors
+    ///   - Cannot decode scalar.
+    pub fn from_bytes(bytes: &[u8; Self::BYTES_SIZE]) -> anyhow::Result<Self> {
+        let (challenge, response) = bytes.split_at(Scalar::BYTES_SIZE);
+        Ok(Self(
+            Scalar::from_bytes(challenge.try_into()?)?,
+            Scalar::from_bytes(response.try_into()?)?,
+        ))
+    }
+
+    /// Convert this `DleqProof` to its underlying sequence of bytes.
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; Self::BYTES_SIZE] {
+        let mut res = [0; Self::BYTES_SIZE];
+        let (challenge, response) = res.split_at_mut(Scalar::BYTES_SIZE);
+        challenge.copy_from_slice(&self.0.to_bytes());
+        response.copy_from_slice(&self.1.to_bytes());
+        res
+    }
+}
+
+impl Encode<()> for DleqProof {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for DleqProof {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        Self::from_bytes(&decode_cbor_array(d, "DLEQ proof")?)
+            .map_err(|e| decode::Error::message(format!("Cannot decode DLEQ proof, error: {e}")))
+    }
+}
+
+#[cfg(feature = "serde")]
+crate::utils::impl_serde_as_cbor!(DleqProof);
+
 /// Generates a DLEQ proof.
 pub fn generate_dleq_proof(
     base_1: &GroupElement, base_2: &GroupElement, point_1: &GroupElement, point_2: &GroupElement,
@@ -73,6 +125,17 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn dleq_proof_cbor_golden_test() {
+        let proof = DleqProof(Scalar::from(1), Scalar::from(2));
+        let bytes = minicbor::to_vec(&proof).unwrap();
+        assert_eq!(
+            hex::encode(&bytes),
+            "584001000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000"
+        );
+        assert_eq!(minicbor::decode::<DleqProof>(&bytes).unwrap(), proof);
+    }
+
     #[proptest(cases = 10)]
     fn zk_dleq_test(e1: Scalar, e2: Scalar, dlog1: Scalar, dlog2: Scalar, randomness: Scalar) {
         let base_1 = GroupElement::GENERATOR.mul(&e1);
@@ -1,11 +1,24 @@
 //! ZK Unit Vector objects decoding implementation
+//!
+//! ```cddl
+//! unit-vector-proof = [
+//!     announcements: [* announcement],
+//!     ciphertexts: [* ciphertext],
+//!     responses: [* response-randomness],
+//!     scalar: bytes .size 32,
+//! ]
+//! announcement = bytes .size 96
+//! ciphertext = bytes .size 64
+//! response-randomness = bytes .size 96
+//! ```
 
 use std::io::Read;
 
 use anyhow::anyhow;
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
 
 use super::{Announcement, Ciphertext, GroupElement, ResponseRandomness, Scalar, UnitVectorProof};
-use crate::utils::read_array;
+use crate::utils::{decode_cbor_array, read_array};
 
 impl UnitVectorProof {
     /// Get an underlying vector length.
@@ -151,12 +164,115 @@ impl ResponseRandomness {
     }
 }
 
+impl Encode<()> for UnitVectorProof {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, ctx: &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.array(4)?;
+        e.encode_with(&self.0, ctx)?;
+        e.encode_with(&self.1, ctx)?;
+        e.encode_with(&self.2, ctx)?;
+        e.encode_with(&self.3, ctx)?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for UnitVectorProof {
+    fn decode(d: &mut Decoder<'_>, ctx: &mut ()) -> Result<Self, decode::Error> {
+        if d.array()? != Some(4) {
+            return Err(decode::Error::message(
+                "Invalid unit vector proof array, expected 4 items",
+            ));
+        }
+        let ann: Vec<Announcement> = d.decode_with(ctx)?;
+        let dl: Vec<Ciphertext> = d.decode_with(ctx)?;
+        let rr: Vec<ResponseRandomness> = d.decode_with(ctx)?;
+        if ann.len() != dl.len() || ann.len() != rr.len() {
+            return Err(decode::Error::message(
+                "Invalid unit vector proof, fields lengths mismatch",
+            ));
+        }
+        let scalar = d.decode_with(ctx)?;
+        Ok(Self(ann, dl, rr, scalar))
+    }
+}
+
+impl Encode<()> for Announcement {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for Announcement {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        Self::from_bytes(&decode_cbor_array(d, "announcement")?)
+            .map_err(|e| decode::Error::message(format!("Cannot decode announcement, error: {e}")))
+    }
+}
+
+impl Encode<()> for ResponseRandomness {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for ResponseRandomness {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        Self::from_bytes(&decode_cbor_array(d, "response randomness")?).map_err(|e| {
+            decode::Error::message(format!("Cannot decode response randomness, error: {e}"))
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+crate::utils::impl_serde_as_cbor!(UnitVectorProof, Announcement, ResponseRandomness);
+
 #[cfg(test)]
 mod tests {
     use test_strategy::proptest;
 
     use super::*;
 
+    #[test]
+    fn unit_vector_proof_cbor_golden_test() {
+        let raw = hex::decode(concat!(
+            // Announcement
+            "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d760000000000000000000000000000000000000000000000000000000000000000e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+            // Ciphertext
+            "0000000000000000000000000000000000000000000000000000000000000000e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+            // Response randomness
+            "010000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000300000000000000000000000000000000000000000000000000000000000000",
+            // Scalar
+            "0400000000000000000000000000000000000000000000000000000000000000",
+        ))
+        .unwrap();
+        let proof = UnitVectorProof::from_bytes(&mut raw.as_slice(), 1).unwrap();
+        let bytes = minicbor::to_vec(&proof).unwrap();
+        assert_eq!(
+            hex::encode(&bytes),
+            concat!(
+                "84",
+                "815860e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d760000000000000000000000000000000000000000000000000000000000000000e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+                "8158400000000000000000000000000000000000000000000000000000000000000000e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+                "815860010000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000300000000000000000000000000000000000000000000000000000000000000",
+                "58200400000000000000000000000000000000000000000000000000000000000000",
+            )
+        );
+        assert_eq!(minicbor::decode::<UnitVectorProof>(&bytes).unwrap(), proof);
+
+        // Fields lengths mismatch.
+        let mut invalid = UnitVectorProof::from_bytes(&mut raw.as_slice(), 1).unwrap();
+        invalid.0.clear();
+        let bytes = minicbor::to_vec(&invalid).unwrap();
+        assert!(minicbor::decode::<UnitVectorProof>(&bytes).is_err());
+    }
+
     #[proptest]
     fn proof_to_bytes_from_bytes_test(
         #[strategy(0..5usize)] _size: usize, #[any(#_size)] p1: UnitVectorProof,
@@ -235,6 +235,114 @@ fn check_2(
     &right_1 + &right_2 == left
 }
 
+/// Batch verify unit vector proofs.
+///
+/// Each item consists of a proof, its ciphertexts and its commitment key, all proofs are
+/// verified against the same `public_key`.
+/// Instead of checking the equations of every proof separately, all of them are combined
+/// with random weights into a single multi-scalar multiplication.
+/// Returns `true` only if all proofs are valid, the invalid ones could be found with
+/// [`verify_unit_vector_proof`].
+#[must_use]
+pub fn batch_verify_unit_vector_proofs<'a, R: CryptoRngCore>(
+    items: impl IntoIterator<Item = (&'a UnitVectorProof, &'a [Ciphertext], &'a GroupElement)>,
+    public_key: &GroupElement, rng: &mut R,
+) -> bool {
+    let mut generator_scalar = Scalar::zero();
+    let mut public_key_scalar = Scalar::zero();
+    let mut scalars = Vec::new();
+    let mut points = Vec::new();
+
+    for (proof, ciphertexts, commitment_key) in items {
+        let m = ciphertexts.len();
+        let n = m.next_power_of_two();
+        // calculates log_2(N)
+        let log_n = n.trailing_zeros();
+
+        let mut padded_ciphertexts = ciphertexts.to_vec();
+        padded_ciphertexts.resize(n, Ciphertext::zero());
+
+        let ch_1_hash = calculate_first_challenge_hash(
+            commitment_key,
+            public_key,
+            &padded_ciphertexts,
+            &proof.0,
+        );
+        let ch_1 = Scalar::from_hash(ch_1_hash.clone());
+
+        let ch_2_hash = calculate_second_challenge_hash(ch_1_hash, &proof.1);
+        let ch_2 = Scalar::from_hash(ch_2_hash);
+
+        // `check_1` equations, weighted by `w_1` and `w_2`:
+        // `I * ch_2 + B - G * z - H * w == 0`
+        // `I * (ch_2 - z) + A - H * v == 0`
+        let mut commitment_key_scalar = Scalar::zero();
+        for (an, rand) in proof.0.iter().zip(proof.2.iter()) {
+            let w_1 = Scalar::random(rng);
+            let w_2 = Scalar::random(rng);
+
+            scalars.push(&w_1.mul(&ch_2) + &w_2.mul(&(&ch_2 - &rand.z)));
+            points.push(&an.i);
+            scalars.push(w_1.clone());
+            points.push(&an.b);
+            scalars.push(w_2.clone());
+            points.push(&an.a);
+
+            generator_scalar = &generator_scalar - &w_1.mul(&rand.z);
+            commitment_key_scalar =
+                &commitment_key_scalar - &(&w_1.mul(&rand.w) + &w_2.mul(&rand.v));
+        }
+        scalars.push(commitment_key_scalar);
+        points.push(commitment_key);
+
+        // `check_2` equation, weighted by `w_3` for the first ciphertext elements and by
+        // `w_4` for the second ones:
+        // `sum(C_j * ch_2^(log_2(N)) * ch_1^j) + sum(D_l * ch_2^l)
+        //      - sum(encrypt(P_j(ch_2), 0) * ch_1^j) - encrypt(0, R) == 0`
+        let w_3 = Scalar::random(rng);
+        let w_4 = Scalar::random(rng);
+
+        // exp_ch_2 == `ch_2^(log_2(N))`
+        let exp_ch_2 = (0..log_n).fold(Scalar::one(), |exp, _| exp.mul(&ch_2));
+        // exp_ch_1 = `ch_1^(j)`
+        let mut exp_ch_1 = Scalar::one();
+        for j in 0..n {
+            // Padding ciphertexts are zero, so only the provided ones are added.
+            if let Some(c_j) = ciphertexts.get(j) {
+                let coeff = exp_ch_2.mul(&exp_ch_1);
+                scalars.push(w_3.mul(&coeff));
+                points.push(c_j.first());
+                scalars.push(w_4.mul(&coeff));
+                points.push(c_j.second());
+            }
+            let p_j = calculate_polynomial_val(j, &ch_2, &proof.2);
+            generator_scalar = &generator_scalar - &w_4.mul(&p_j).mul(&exp_ch_1);
+            exp_ch_1 = exp_ch_1.mul(&ch_1);
+        }
+
+        // exp_ch_2 = `ch_2^(l)`
+        let mut exp_ch_2 = Scalar::one();
+        for d_l in &proof.1 {
+            scalars.push(w_3.mul(&exp_ch_2));
+            points.push(d_l.first());
+            scalars.push(w_4.mul(&exp_ch_2));
+            points.push(d_l.second());
+            exp_ch_2 = exp_ch_2.mul(&ch_2);
+        }
+
+        generator_scalar = &generator_scalar - &w_3.mul(&proof.3);
+        public_key_scalar = &public_key_scalar - &w_4.mul(&proof.3);
+    }
+
+    let generator = GroupElement::GENERATOR;
+    scalars.push(generator_scalar);
+    points.push(&generator);
+    scalars.push(public_key_scalar);
+    points.push(public_key);
+
+    GroupElement::vartime_multiscalar_mul(&scalars, points) == GroupElement::zero()
+}
+
 #[cfg(test)]
 mod arbitrary_impl {
     use proptest::{
@@ -369,4 +477,77 @@ mod tests {
             &commitment_key
         ));
     }
+
+    #[proptest(cases = 10)]
+    fn batch_verify_unit_vector_proofs_test(
+        secret_key: Scalar, commitment_key: GroupElement,
+        #[strategy(1..10_usize)] unit_vector_size: usize,
+        #[strategy(proptest::collection::vec(0..#unit_vector_size, 1..5))] unit_vector_indices: Vec<
+            usize,
+        >,
+    ) {
+        let mut rng = OsRng;
+
+        let public_key = generate_public_key(&secret_key);
+
+        let items: Vec<_> = unit_vector_indices
+            .iter()
+            .map(|unit_vector_index| {
+                let unit_vector: Vec<_> = (0..unit_vector_size)
+                    .map(|i| {
+                        if i == *unit_vector_index {
+                            Scalar::one()
+                        } else {
+                            Scalar::zero()
+                        }
+                    })
+                    .collect();
+
+                let encryption_randomness: Vec<_> = unit_vector
+                    .iter()
+                    .map(|_| Scalar::random(&mut rng))
+                    .collect();
+
+                let ciphertexts: Vec<_> = encryption_randomness
+                    .iter()
+                    .zip(unit_vector.iter())
+                    .map(|(r, v)| encrypt(v, &public_key, r))
+                    .collect();
+
+                let proof = generate_unit_vector_proof(
+                    &unit_vector,
+                    ciphertexts.clone(),
+                    encryption_randomness,
+                    &public_key,
+                    &commitment_key,
+                    &mut rng,
+                );
+                (proof, ciphertexts)
+            })
+            .collect();
+
+        assert!(batch_verify_unit_vector_proofs(
+            items
+                .iter()
+                .map(|(proof, ciphertexts)| (proof, ciphertexts.as_slice(), &commitment_key)),
+            &public_key,
+            &mut rng,
+        ));
+
+        // One of the proofs is verified with the wrong commitment key.
+        let wrong_commitment_key = &commitment_key + &GroupElement::GENERATOR;
+        let last = items.len() - 1;
+        assert!(!batch_verify_unit_vector_proofs(
+            items.iter().enumerate().map(|(i, (proof, ciphertexts))| {
+                let commitment_key = if i == last {
+                    &wrong_commitment_key
+                } else {
+                    &commitment_key
+                };
+                (proof, ciphertexts.as_slice(), commitment_key)
+            }),
+            &public_key,
+            &mut rng,
+        ));
+    }
 }
@@ -9,3 +9,88 @@ pub(crate) fn read_array<R: Read, const N: usize>(reader: &mut R) -> anyhow::Res
     reader.read_exact(&mut buf)?;
     Ok(buf)
 }
+
+/// Decode a N-byte array from a CBOR byte string.
+pub(crate) fn decode_cbor_array<const N: usize>(
+    d: &mut minicbor::Decoder<'_>, from: &str,
+) -> Result<[u8; N], minicbor::decode::Error> {
+    d.bytes()?.try_into().map_err(|_| {
+        minicbor::decode::Error::message(format!("Invalid {from} bytes length, expected {N} bytes"))
+    })
+}
+
+/// Serialize the value as its CBOR encoding, a hex string for the human readable
+/// formats, a byte string otherwise.
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_cbor<T: minicbor::Encode<()>, S: serde::Serializer>(
+    value: &T, serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let bytes = minicbor::to_vec(value).map_err(serde::ser::Error::custom)?;
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&hex::encode(bytes))
+    } else {
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+/// Deserialize the value from its CBOR encoding, a hex string for the human readable
+/// formats, a byte string otherwise.
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_cbor<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: for<'b> minicbor::Decode<'b, ()>,
+    D: serde::Deserializer<'de>,
+{
+    let bytes = if deserializer.is_human_readable() {
+        deserializer.deserialize_str(CborBytesVisitor)?
+    } else {
+        deserializer.deserialize_bytes(CborBytesVisitor)?
+    };
+    minicbor::decode(&bytes).map_err(serde::de::Error::custom)
+}
+
+/// `serde` visitor of the CBOR encoded bytes.
+#[cfg(feature = "serde")]
+struct CborBytesVisitor;
+
+#[cfg(feature = "serde")]
+impl serde::de::Visitor<'_> for CborBytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("CBOR encoded bytes, or a hex string of them")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        hex::decode(v).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+}
+
+/// Implement `serde` serialization of the types as their CBOR encoding.
+#[cfg(feature = "serde")]
+macro_rules! impl_serde_as_cbor {
+    ($($type:ty),+ $(,)?) => {$(
+        impl serde::Serialize for $type {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                $crate::utils::serialize_cbor(self, serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $type {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                $crate::utils::deserialize_cbor(deserializer)
+            }
+        }
+    )+};
+}
+
+#[cfg(feature = "serde")]
+pub(crate) use impl_serde_as_cbor;
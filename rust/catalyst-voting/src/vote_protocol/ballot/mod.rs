@@ -0,0 +1,9 @@
+//! Module containing alternative ballot types, besides the single choice `Vote`.
+//!
+//! - [`ranked::RankedVote`] - an ordered list of preferred voting options, tallied
+//!   publicly with the instant-runoff procedure or privately per preference rank.
+//! - [`weighted::WeightedVote`] - a score for every voting option, which can be encrypted
+//!   and tallied homomorphically like a `Vote`.
+
+pub mod ranked;
+pub mod weighted;
@@ -0,0 +1,231 @@
+//! Ranked-choice ballots and the instant-runoff tally.
+
+use anyhow::{anyhow, ensure};
+
+use crate::vote_protocol::voter::Vote;
+
+/// A representation of the voter's ranked preferences.
+/// Holds the voting options ordered from the most to the least preferred,
+/// options which are not ranked are not preferred at all.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RankedVote {
+    /// Voter's ranked voting options, the most preferred first.
+    ranking: Vec<usize>,
+    /// Number of voting options.
+    voting_options: usize,
+}
+
+impl RankedVote {
+    /// Generate a ranked vote.
+    ///
+    /// # Errors
+    ///   - Empty ranking, at least one voting option must be ranked.
+    ///   - Invalid voting choice, every ranked option should be less than the number of
+    ///     `voting_options`.
+    ///   - Duplicate voting choice, every voting option can be ranked only once.
+    pub fn new(ranking: Vec<usize>, voting_options: usize) -> anyhow::Result<Self> {
+        ensure!(
+            !ranking.is_empty(),
+            "Empty ranking, at least one voting option must be ranked."
+        );
+        let mut ranked = vec![false; voting_options];
+        for choice in &ranking {
+            let is_ranked = ranked.get_mut(*choice).ok_or(anyhow!(
+                "Invalid voting choice, the value of choice: {choice}, should be less than the number of voting options: {voting_options}."
+            ))?;
+            ensure!(
+                !*is_ranked,
+                "Duplicate voting choice, the voting option {choice} is ranked more than once."
+            );
+            *is_ranked = true;
+        }
+
+        Ok(Self {
+            ranking,
+            voting_options,
+        })
+    }
+
+    /// Get the ranked voting options, the most preferred first.
+    #[must_use]
+    pub fn ranking(&self) -> &[usize] {
+        &self.ranking
+    }
+
+    /// Get the number of voting options.
+    #[must_use]
+    pub fn voting_options(&self) -> usize {
+        self.voting_options
+    }
+
+    /// Get the most preferred voting option, which is still continuing.
+    fn preferred(&self, continuing: &[bool]) -> Option<usize> {
+        self.ranking
+            .iter()
+            .copied()
+            .find(|choice| continuing.get(*choice).copied().unwrap_or_default())
+    }
+
+    /// Split the ranked vote into a single choice `Vote` per preference rank.
+    ///
+    /// Every `Vote` can be encrypted and proven with the `voter` module, so the
+    /// preferences stay private. Tallying the `Vote`s of the same rank over all voters
+    /// counts how many times each option was preferred at that rank, which gives a
+    /// positional (e.g. Borda) count, but not the instant-runoff result.
+    ///
+    /// # Errors
+    ///   - Invalid voting choice.
+    pub fn to_votes(&self) -> anyhow::Result<Vec<Vote>> {
+        self.ranking
+            .iter()
+            .map(|choice| Vote::new(*choice, self.voting_options))
+            .collect()
+    }
+}
+
+/// A result of the instant-runoff tally.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunoffResult {
+    /// The voting power counted for each voting option in each round, eliminated options
+    /// count `0`.
+    pub rounds: Vec<Vec<u64>>,
+    /// The winning voting option, `None` if there is no majority because of a tie or no
+    /// vote preferring any option.
+    pub winner: Option<usize>,
+}
+
+/// Instant-runoff tally function, over publicly known ranked votes.
+///
+/// Each round counts the voting power of every vote for its most preferred continuing
+/// option. An option with more than half of the counted voting power wins, otherwise
+/// the options with the least voting power are eliminated and another round is counted.
+/// Votes which do not prefer any continuing option are exhausted and not counted.
+/// If every continuing option has the same voting power, the tally ends without a
+/// winner.
+///
+/// # Errors
+///   - Votes and voting power length mismatch.
+///   - Invalid ranked vote at index `i`, a different number of voting options.
+///   - Voting power overflow.
+pub fn instant_runoff_tally(
+    voting_options: usize, votes: &[RankedVote], voting_powers: &[u64],
+) -> anyhow::Result<RunoffResult> {
+    ensure!(
+        votes.len() == voting_powers.len(),
+        "Votes and voting power length mismatch. Votes amount: {0}. \
+        Voting powers amount: {1}.",
+        votes.len(),
+        voting_powers.len(),
+    );
+    for (i, vote) in votes.iter().enumerate() {
+        ensure!(
+            vote.voting_options == voting_options,
+            "Invalid ranked vote at index {i}. \
+            Has {0} voting options, expected {voting_options}.",
+            vote.voting_options,
+        );
+    }
+
+    let mut continuing = vec![true; voting_options];
+    let mut rounds = Vec::new();
+    loop {
+        let mut counts = vec![0_u64; voting_options];
+        for (vote, voting_power) in votes.iter().zip(voting_powers) {
+            if let Some(count) = vote.preferred(&continuing).and_then(|c| counts.get_mut(c)) {
+                *count = count
+                    .checked_add(*voting_power)
+                    .ok_or(anyhow!("Voting power overflow."))?;
+            }
+        }
+        let total = counts
+            .iter()
+            .try_fold(0_u64, |acc, count| acc.checked_add(*count))
+            .ok_or(anyhow!("Voting power overflow."))?;
+
+        let leader = counts
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, count)| **count)
+            .filter(|(_, count)| u128::from(**count) * 2 > u128::from(total))
+            .map(|(choice, _)| choice);
+        let least = continuing
+            .iter()
+            .zip(&counts)
+            .filter(|(is_continuing, _)| **is_continuing)
+            .map(|(_, count)| *count)
+            .min()
+            .unwrap_or_default();
+        let is_tie = continuing
+            .iter()
+            .zip(&counts)
+            .all(|(is_continuing, count)| !is_continuing || *count == least);
+        let is_done = leader.is_some() || total == 0 || is_tie;
+        if !is_done {
+            for (is_continuing, count) in continuing.iter_mut().zip(&counts) {
+                if *count == least {
+                    *is_continuing = false;
+                }
+            }
+        }
+        rounds.push(counts);
+
+        if is_done {
+            return Ok(RunoffResult {
+                rounds,
+                winner: leader,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranked_vote_test() {
+        let vote = RankedVote::new(vec![2, 0], 3).unwrap();
+        assert_eq!(vote.ranking(), &[2, 0]);
+        assert_eq!(vote.to_votes().unwrap(), vec![
+            Vote::new(2, 3).unwrap(),
+            Vote::new(0, 3).unwrap()
+        ]);
+
+        assert!(RankedVote::new(vec![], 3).is_err());
+        assert!(RankedVote::new(vec![3], 3).is_err());
+        assert!(RankedVote::new(vec![1, 1], 3).is_err());
+    }
+
+    #[test]
+    fn instant_runoff_tally_test() {
+        let votes = [
+            RankedVote::new(vec![0, 1], 3).unwrap(),
+            RankedVote::new(vec![1, 0], 3).unwrap(),
+            RankedVote::new(vec![2, 1], 3).unwrap(),
+            RankedVote::new(vec![2], 3).unwrap(),
+        ];
+
+        // Option `2` has a majority in the first round.
+        let result = instant_runoff_tally(3, &votes, &[1, 1, 2, 1]).unwrap();
+        assert_eq!(result.rounds, vec![vec![1, 1, 3]]);
+        assert_eq!(result.winner, Some(2));
+
+        // Option `0` is eliminated, then option `1` wins.
+        let result = instant_runoff_tally(3, &votes, &[2, 3, 3, 1]).unwrap();
+        assert_eq!(result.rounds, vec![vec![2, 3, 4], vec![0, 5, 4]]);
+        assert_eq!(result.winner, Some(1));
+
+        // Options `0` and `1` are tied.
+        let tied = [
+            RankedVote::new(vec![0, 1], 3).unwrap(),
+            RankedVote::new(vec![1, 0], 3).unwrap(),
+        ];
+        let result = instant_runoff_tally(3, &tied, &[1, 1]).unwrap();
+        assert_eq!(result.rounds, vec![vec![1, 1, 0], vec![1, 1, 0]]);
+        assert_eq!(result.winner, None);
+
+        assert!(instant_runoff_tally(3, &votes, &[1]).is_err());
+        assert!(instant_runoff_tally(4, &votes, &[1, 1, 1, 1]).is_err());
+        assert!(instant_runoff_tally(3, &tied, &[u64::MAX, 1]).is_err());
+    }
+}
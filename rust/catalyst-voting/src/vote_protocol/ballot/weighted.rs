@@ -0,0 +1,191 @@
+//! Weighted (score) ballots, their encryption and the score tally.
+
+use anyhow::{anyhow, ensure};
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+use crate::{
+    crypto::{
+        elgamal::encrypt,
+        group::Scalar,
+        rng::{default_rng, rand_core::CryptoRngCore},
+    },
+    vote_protocol::{
+        committee::ElectionPublicKey,
+        voter::{EncryptedVote, EncryptionRandomness},
+    },
+};
+
+/// A representation of the voter's weighted voting choice.
+/// Holds a score for every voting option, from `0` up to the `max_weight`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeightedVote {
+    /// Voter's score for every voting option.
+    weights: Vec<u64>,
+    /// Maximum score of a voting option.
+    max_weight: u64,
+}
+
+impl WeightedVote {
+    /// Generate a weighted vote.
+    ///
+    /// # Errors
+    ///   - Empty weights, at least one voting option must be scored.
+    ///   - Invalid weight, every weight should be not more than the `max_weight`.
+    pub fn new(weights: Vec<u64>, max_weight: u64) -> anyhow::Result<Self> {
+        ensure!(
+            !weights.is_empty(),
+            "Empty weights, at least one voting option must be scored."
+        );
+        for (i, weight) in weights.iter().enumerate() {
+            ensure!(
+                *weight <= max_weight,
+                "Invalid weight, the weight: {weight} of the voting option {i}, should be not more than the max weight: {max_weight}."
+            );
+        }
+
+        Ok(Self {
+            weights,
+            max_weight,
+        })
+    }
+
+    /// Get the voter's score for every voting option.
+    #[must_use]
+    pub fn weights(&self) -> &[u64] {
+        &self.weights
+    }
+
+    /// Get the maximum score of a voting option.
+    #[must_use]
+    pub fn max_weight(&self) -> u64 {
+        self.max_weight
+    }
+
+    /// Get the number of voting options.
+    #[must_use]
+    pub fn voting_options(&self) -> usize {
+        self.weights.len()
+    }
+}
+
+/// Create a new encrypted vote from the given weighted vote and public key.
+///
+/// The encrypted vote holds a ciphertext of the weight per voting option, so it is
+/// tallied with the `tally::tally` function and decrypted with a
+/// `tally::DecryptionTallySetup` of the total voting power multiplied by the
+/// `max_weight`.
+///
+/// **NOTE** the voter proof only proves unit vectors, so there is no proof the encrypted
+/// weights are within the `max_weight`.
+#[must_use]
+pub fn encrypt_weighted_vote<R: CryptoRngCore>(
+    vote: &WeightedVote, public_key: &ElectionPublicKey, rng: &mut R,
+) -> (EncryptedVote, EncryptionRandomness) {
+    let randomness = EncryptionRandomness::random(rng, vote.voting_options());
+
+    let weights: Vec<_> = vote.weights.iter().map(|w| Scalar::from(*w)).collect();
+    let ciphers: Vec<_> = weights
+        .par_iter()
+        .zip(randomness.0.par_iter())
+        .map(|(m, r)| encrypt(m, &public_key.0, r))
+        .collect();
+
+    (EncryptedVote::from(ciphers), randomness)
+}
+
+/// Create a new encrypted vote from the given weighted vote and public key with the
+/// `crypto::default_rng`.
+#[must_use]
+pub fn encrypt_weighted_vote_with_default_rng(
+    vote: &WeightedVote, public_key: &ElectionPublicKey,
+) -> (EncryptedVote, EncryptionRandomness) {
+    encrypt_weighted_vote(vote, public_key, &mut default_rng())
+}
+
+/// Score tally function, over publicly known weighted votes.
+/// Sums the weights of every voting option, multiplied by the voter's voting power.
+///
+/// # Errors
+///   - Votes and voting power length mismatch.
+///   - Invalid weighted vote at index `i`, a different number of voting options.
+///   - Score overflow.
+pub fn score_tally(
+    voting_options: usize, votes: &[WeightedVote], voting_powers: &[u64],
+) -> anyhow::Result<Vec<u64>> {
+    ensure!(
+        votes.len() == voting_powers.len(),
+        "Votes and voting power length mismatch. Votes amount: {0}. \
+        Voting powers amount: {1}.",
+        votes.len(),
+        voting_powers.len(),
+    );
+
+    let mut scores = vec![0_u64; voting_options];
+    for (i, (vote, voting_power)) in votes.iter().zip(voting_powers).enumerate() {
+        ensure!(
+            vote.voting_options() == voting_options,
+            "Invalid weighted vote at index {i}. \
+            Has {0} voting options, expected {voting_options}.",
+            vote.voting_options(),
+        );
+        for (score, weight) in scores.iter_mut().zip(&vote.weights) {
+            *score = weight
+                .checked_mul(*voting_power)
+                .and_then(|s| score.checked_add(s))
+                .ok_or(anyhow!("Score overflow."))?;
+        }
+    }
+    Ok(scores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote_protocol::{
+        committee::ElectionSecretKey,
+        tally::{decrypt_tally, tally, DecryptionTallySetup},
+    };
+
+    #[test]
+    fn weighted_vote_test() {
+        let vote = WeightedVote::new(vec![0, 3, 5], 5).unwrap();
+        assert_eq!(vote.weights(), &[0, 3, 5]);
+        assert_eq!(vote.voting_options(), 3);
+
+        assert!(WeightedVote::new(vec![], 5).is_err());
+        assert!(WeightedVote::new(vec![0, 6], 5).is_err());
+    }
+
+    #[test]
+    fn score_tally_test() {
+        let election_secret_key = ElectionSecretKey::random_with_default_rng();
+        let election_public_key = election_secret_key.public_key();
+
+        let votes = [
+            WeightedVote::new(vec![5, 0, 1], 5).unwrap(),
+            WeightedVote::new(vec![2, 4, 0], 5).unwrap(),
+        ];
+        let voting_powers = [10, 20];
+
+        let scores = score_tally(3, &votes, &voting_powers).unwrap();
+        assert_eq!(scores, vec![90, 80, 10]);
+
+        let encrypted_votes: Vec<_> = votes
+            .iter()
+            .map(|vote| encrypt_weighted_vote_with_default_rng(vote, &election_public_key).0)
+            .collect();
+        let setup = DecryptionTallySetup::new(5 * 30).unwrap();
+        let decrypted_scores: Vec<_> = (0..3)
+            .map(|voting_option| {
+                let encrypted_tally =
+                    tally(voting_option, &encrypted_votes, &voting_powers).unwrap();
+                decrypt_tally(&encrypted_tally, &election_secret_key, &setup).unwrap()
+            })
+            .collect();
+        assert_eq!(decrypted_scores, scores);
+
+        assert!(score_tally(3, &votes, &[1]).is_err());
+        assert!(score_tally(2, &votes, &voting_powers).is_err());
+        assert!(score_tally(3, &votes, &[u64::MAX, 1]).is_err());
+    }
+}
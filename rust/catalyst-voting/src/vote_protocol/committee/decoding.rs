@@ -1,8 +1,14 @@
 //! committee objects decoding implementation
+//!
+//! ```cddl
+//! election-public-key = bytes .size 32
+//! ```
 
 use anyhow::anyhow;
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
 
 use super::{ElectionPublicKey, ElectionSecretKey, GroupElement, Scalar};
+use crate::utils::decode_cbor_array;
 
 impl ElectionSecretKey {
     /// `ElectionSecretKey` bytes size
@@ -46,12 +52,46 @@ impl ElectionPublicKey {
     }
 }
 
+impl Encode<()> for ElectionPublicKey {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for ElectionPublicKey {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        Self::from_bytes(&decode_cbor_array(d, "election public key")?).map_err(|e| {
+            decode::Error::message(format!("Cannot decode election public key, error: {e}"))
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+crate::utils::impl_serde_as_cbor!(ElectionPublicKey);
+
 #[cfg(test)]
 mod tests {
     use test_strategy::proptest;
 
     use super::*;
 
+    #[test]
+    fn election_public_key_cbor_golden_test() {
+        let public_key = ElectionSecretKey(Scalar::from(1)).public_key();
+        let bytes = minicbor::to_vec(&public_key).unwrap();
+        assert_eq!(
+            hex::encode(&bytes),
+            "5820e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76"
+        );
+        assert_eq!(
+            minicbor::decode::<ElectionPublicKey>(&bytes).unwrap(),
+            public_key
+        );
+    }
+
     #[proptest]
     fn election_keys_to_bytes_from_bytes_test(sk1: ElectionSecretKey) {
         let bytes = sk1.to_bytes();
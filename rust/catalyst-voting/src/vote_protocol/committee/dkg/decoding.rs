@@ -0,0 +1,136 @@
+//! DKG messages CBOR encoding and decoding implementation.
+//!
+//! ```cddl
+//! dkg-commitments = [dealer: uint, commitments: [+ bytes .size 32]]
+//! dkg-share = [dealer: uint, recipient: uint, share: bytes .size 32]
+//! dkg-complaint = [dealer: uint, complainer: uint]
+//! ```
+
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
+
+use super::{DkgCommitments, DkgComplaint, DkgShare, GroupElement, Scalar};
+use crate::utils::decode_cbor_array;
+
+/// Decode the array header of a DKG message, which must have `len` items.
+fn decode_array_len(d: &mut Decoder<'_>, len: u64, from: &str) -> Result<(), decode::Error> {
+    match d.array()? {
+        Some(actual) if actual == len => Ok(()),
+        _ => {
+            Err(decode::Error::message(format!(
+                "Invalid {from} array, expected {len} items"
+            )))
+        },
+    }
+}
+
+impl Encode<()> for DkgCommitments {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.array(2)?.u32(self.dealer)?;
+        e.array(self.commitments.len() as u64)?;
+        for commitment in &self.commitments {
+            e.bytes(&commitment.to_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for DkgCommitments {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        decode_array_len(d, 2, "DKG commitments")?;
+        let dealer = d.u32()?;
+        let len = d.array()?.ok_or(decode::Error::message(
+            "Invalid DKG commitments, unexpected indefinite length",
+        ))?;
+        let commitments = (0..len)
+            .map(|_| {
+                let bytes = decode_cbor_array(d, "DKG commitment")?;
+                GroupElement::from_bytes(&bytes).map_err(|e| {
+                    decode::Error::message(format!("Cannot decode DKG commitment, error: {e}"))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            dealer,
+            commitments,
+        })
+    }
+}
+
+impl Encode<()> for DkgShare {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.array(3)?
+            .u32(self.dealer)?
+            .u32(self.recipient)?
+            .bytes(&self.share.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for DkgShare {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        decode_array_len(d, 3, "DKG share")?;
+        let dealer = d.u32()?;
+        let recipient = d.u32()?;
+        let share = Scalar::from_bytes(decode_cbor_array(d, "DKG share")?)
+            .map_err(|e| decode::Error::message(format!("Cannot decode DKG share, error: {e}")))?;
+        Ok(Self {
+            dealer,
+            recipient,
+            share,
+        })
+    }
+}
+
+impl Encode<()> for DkgComplaint {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.array(2)?.u32(self.dealer)?.u32(self.complainer)?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for DkgComplaint {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        decode_array_len(d, 2, "DKG complaint")?;
+        let dealer = d.u32()?;
+        let complainer = d.u32()?;
+        Ok(Self { dealer, complainer })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{DkgDealer, DkgParams},
+        *,
+    };
+
+    #[test]
+    fn dkg_messages_encode_decode_test() {
+        let params = DkgParams::new(2, 3).unwrap();
+        let dealer = DkgDealer::new_with_default_rng(1, &params).unwrap();
+
+        let commitments = dealer.commitments();
+        let bytes = minicbor::to_vec(&commitments).unwrap();
+        let decoded: DkgCommitments = minicbor::decode(&bytes).unwrap();
+        assert_eq!(commitments, decoded);
+
+        let share = dealer.share(2).unwrap();
+        let bytes = minicbor::to_vec(&share).unwrap();
+        let decoded: DkgShare = minicbor::decode(&bytes).unwrap();
+        assert_eq!(share, decoded);
+
+        let complaint = DkgComplaint::new(1, 2);
+        let bytes = minicbor::to_vec(&complaint).unwrap();
+        let decoded: DkgComplaint = minicbor::decode(&bytes).unwrap();
+        assert_eq!(complaint, decoded);
+
+        // Invalid share length.
+        assert!(minicbor::decode::<DkgShare>(&[0x83, 0x01, 0x02, 0x41, 0x00]).is_err());
+    }
+}
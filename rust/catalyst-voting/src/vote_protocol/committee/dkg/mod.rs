@@ -0,0 +1,454 @@
+//! Distributed key generation (DKG) of the election key by the committee members,
+//! following the Pedersen (Joint-Feldman) protocol.
+//!
+//! No committee member ever knows the `ElectionSecretKey`, each member only holds an
+//! `ElectionKeyShare`, and any `threshold` members together can decrypt the tally with
+//! the `tally::threshold` procedures.
+//!
+//! The protocol rounds are:
+//! 1. Every member `i` is a `DkgDealer`, which broadcasts its `DkgCommitments` and
+//!    privately sends a `DkgShare` to every other member `j`.
+//! 2. Every member verifies its received shares with `DkgCommitments::verify_share`, and
+//!    broadcasts a `DkgComplaint` against every dealer which sent an invalid share. A
+//!    dealer answers a complaint by broadcasting the complainer's `DkgShare`.
+//! 3. Every member computes the same `qualified_dealers`, from every broadcast
+//!    `DkgCommitments`, complaint and answer, and combines the shares of the qualified
+//!    dealers into its `ElectionKeyShare` with `ElectionKeyShare::combine`. The
+//!    `ElectionPublicKey` is built from the qualified commitments with
+//!    `election_public_key`.
+
+mod decoding;
+
+use std::ops::Mul;
+
+use anyhow::{anyhow, ensure};
+
+use super::ElectionPublicKey;
+use crate::crypto::{
+    group::{GroupElement, Scalar},
+    rng::{default_rng, rand_core::CryptoRngCore},
+};
+
+/// DKG parameters, shared by all committee members.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DkgParams {
+    /// Number of committee members needed to decrypt.
+    threshold: u32,
+    /// Number of committee members, with indexes from `1` to `members`.
+    members: u32,
+}
+
+impl DkgParams {
+    /// Generate DKG parameters.
+    ///
+    /// # Errors
+    ///   - Invalid threshold, should be more than 0 and not more than the number of
+    ///     `members`.
+    pub fn new(threshold: u32, members: u32) -> anyhow::Result<Self> {
+        ensure!(
+            threshold > 0 && threshold <= members,
+            "Invalid threshold, the value of threshold: {threshold}, should be more than 0 and not more than the number of members: {members}."
+        );
+        Ok(Self { threshold, members })
+    }
+
+    /// Get the number of committee members needed to decrypt.
+    #[must_use]
+    pub fn threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    /// Get the number of committee members.
+    #[must_use]
+    pub fn members(&self) -> u32 {
+        self.members
+    }
+
+    /// Check the committee member index is valid.
+    fn check_index(&self, index: u32) -> anyhow::Result<()> {
+        ensure!(
+            index > 0 && index <= self.members,
+            "Invalid committee member index: {index}, should be from 1 to the number of members: {0}.",
+            self.members
+        );
+        Ok(())
+    }
+}
+
+/// A committee member dealing the shares of its secret polynomial.
+pub struct DkgDealer {
+    /// Committee member index of the dealer.
+    index: u32,
+    /// DKG parameters.
+    params: DkgParams,
+    /// Coefficients of the secret polynomial, the constant term first.
+    coefficients: Vec<Scalar>,
+}
+
+impl DkgDealer {
+    /// Randomly generate the secret polynomial of the `index` committee member.
+    ///
+    /// # Errors
+    ///   - Invalid committee member index.
+    pub fn new<R: CryptoRngCore>(
+        index: u32, params: &DkgParams, rng: &mut R,
+    ) -> anyhow::Result<Self> {
+        params.check_index(index)?;
+        let coefficients = (0..params.threshold).map(|_| Scalar::random(rng)).collect();
+        Ok(Self {
+            index,
+            params: *params,
+            coefficients,
+        })
+    }
+
+    /// Randomly generate the secret polynomial of the `index` committee member with the
+    /// `crypto::default_rng`.
+    ///
+    /// # Errors
+    ///   - Invalid committee member index.
+    pub fn new_with_default_rng(index: u32, params: &DkgParams) -> anyhow::Result<Self> {
+        Self::new(index, params, &mut default_rng())
+    }
+
+    /// Get the commitments to the secret polynomial, to broadcast in the first round.
+    #[must_use]
+    pub fn commitments(&self) -> DkgCommitments {
+        DkgCommitments {
+            dealer: self.index,
+            commitments: self
+                .coefficients
+                .iter()
+                .map(|c| GroupElement::GENERATOR.mul(c))
+                .collect(),
+        }
+    }
+
+    /// Get the share for the `recipient` committee member, to send privately in the
+    /// first round.
+    ///
+    /// # Errors
+    ///   - Invalid committee member index.
+    pub fn share(&self, recipient: u32) -> anyhow::Result<DkgShare> {
+        self.params.check_index(recipient)?;
+        Ok(DkgShare {
+            dealer: self.index,
+            recipient,
+            share: evaluate_polynomial(&self.coefficients, recipient),
+        })
+    }
+
+    /// Get the shares for every committee member.
+    #[must_use]
+    pub fn shares(&self) -> Vec<DkgShare> {
+        (1..=self.params.members)
+            .map(|recipient| {
+                DkgShare {
+                    dealer: self.index,
+                    recipient,
+                    share: evaluate_polynomial(&self.coefficients, recipient),
+                }
+            })
+            .collect()
+    }
+}
+
+/// The dealer's commitments to its secret polynomial coefficients, broadcast in the
+/// first round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DkgCommitments {
+    /// Committee member index of the dealer.
+    dealer: u32,
+    /// Commitments to the polynomial coefficients, the constant term first.
+    commitments: Vec<GroupElement>,
+}
+
+impl DkgCommitments {
+    /// Get the committee member index of the dealer.
+    #[must_use]
+    pub fn dealer(&self) -> u32 {
+        self.dealer
+    }
+
+    /// Get the commitments to the polynomial coefficients, the constant term first.
+    #[must_use]
+    pub fn commitments(&self) -> &[GroupElement] {
+        &self.commitments
+    }
+
+    /// Verify the share was dealt from the committed polynomial.
+    #[must_use]
+    pub fn verify_share(&self, share: &DkgShare) -> bool {
+        share.dealer == self.dealer
+            && GroupElement::GENERATOR.mul(&share.share)
+                == share_verification_key(std::slice::from_ref(self), share.recipient)
+    }
+}
+
+/// A share of the dealer's secret polynomial for a committee member, sent privately in
+/// the first round, or broadcast to answer a complaint in the second round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DkgShare {
+    /// Committee member index of the dealer.
+    dealer: u32,
+    /// Committee member index of the recipient.
+    recipient: u32,
+    /// Value of the dealer's polynomial at the recipient's index.
+    share: Scalar,
+}
+
+impl DkgShare {
+    /// Get the committee member index of the dealer.
+    #[must_use]
+    pub fn dealer(&self) -> u32 {
+        self.dealer
+    }
+
+    /// Get the committee member index of the recipient.
+    #[must_use]
+    pub fn recipient(&self) -> u32 {
+        self.recipient
+    }
+}
+
+/// A complaint against a dealer which sent an invalid share, broadcast in the second
+/// round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DkgComplaint {
+    /// Committee member index of the dealer.
+    dealer: u32,
+    /// Committee member index of the complainer.
+    complainer: u32,
+}
+
+impl DkgComplaint {
+    /// Generate a complaint of the `complainer` against the `dealer`.
+    #[must_use]
+    pub fn new(dealer: u32, complainer: u32) -> Self {
+        Self { dealer, complainer }
+    }
+
+    /// Get the committee member index of the dealer.
+    #[must_use]
+    pub fn dealer(&self) -> u32 {
+        self.dealer
+    }
+
+    /// Get the committee member index of the complainer.
+    #[must_use]
+    pub fn complainer(&self) -> u32 {
+        self.complainer
+    }
+}
+
+/// A committee member's share of the election secret key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElectionKeyShare {
+    /// Committee member index.
+    pub(crate) index: u32,
+    /// Share of the election secret key.
+    pub(crate) share: Scalar,
+}
+
+impl ElectionKeyShare {
+    /// Combine the shares received from the qualified dealers, in the third round.
+    ///
+    /// # Errors
+    ///   - Invalid committee member index.
+    ///   - Missing share of the qualified dealer.
+    ///   - Invalid share of the qualified dealer.
+    pub fn combine(
+        params: &DkgParams, index: u32, qualified: &[DkgCommitments], shares: &[DkgShare],
+    ) -> anyhow::Result<Self> {
+        params.check_index(index)?;
+        ensure!(!qualified.is_empty(), "No qualified dealers.");
+
+        let mut share = Scalar::zero();
+        for commitments in qualified {
+            let dealer_share = shares
+                .iter()
+                .find(|s| s.dealer == commitments.dealer && s.recipient == index)
+                .ok_or(anyhow!(
+                    "Missing share of the qualified dealer {0}.",
+                    commitments.dealer
+                ))?;
+            ensure!(
+                commitments.verify_share(dealer_share),
+                "Invalid share of the qualified dealer {0}.",
+                commitments.dealer
+            );
+            share = &share + &dealer_share.share;
+        }
+        Ok(Self { index, share })
+    }
+
+    /// Get the committee member index.
+    #[must_use]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Generate the corresponding verification key, the same as
+    /// `share_verification_key` of the qualified commitments.
+    pub fn verification_key(&self) -> GroupElement {
+        GroupElement::GENERATOR.mul(&self.share)
+    }
+}
+
+/// Get the indexes of the qualified dealers, the same for every committee member.
+///
+/// A dealer is qualified if its commitments have `threshold` coefficients, and every
+/// complaint against it is answered with a valid share for the complainer.
+///
+/// The `commitments` must contain every received broadcast of the commitments, a dealer
+/// which broadcast different commitments to different members (equivocated) is
+/// disqualified.
+#[must_use]
+pub fn qualified_dealers(
+    params: &DkgParams, commitments: &[DkgCommitments], complaints: &[DkgComplaint],
+    answers: &[DkgShare],
+) -> Vec<u32> {
+    let mut qualified: Vec<_> = commitments
+        .iter()
+        .filter(|c| {
+            params.check_index(c.dealer).is_ok()
+                && commitments
+                    .iter()
+                    .all(|other| other.dealer != c.dealer || other == *c)
+                && u32::try_from(c.commitments.len()).is_ok_and(|len| len == params.threshold)
+                && complaints
+                    .iter()
+                    .filter(|complaint| complaint.dealer == c.dealer)
+                    .all(|complaint| {
+                        answers.iter().any(|answer| {
+                            answer.recipient == complaint.complainer && c.verify_share(answer)
+                        })
+                    })
+        })
+        .map(|c| c.dealer)
+        .collect();
+    qualified.sort_unstable();
+    qualified.dedup();
+    qualified
+}
+
+/// Generate the `ElectionPublicKey` from the commitments of the qualified dealers.
+///
+/// # Errors
+///   - No qualified dealers.
+pub fn election_public_key(qualified: &[DkgCommitments]) -> anyhow::Result<ElectionPublicKey> {
+    ensure!(!qualified.is_empty(), "No qualified dealers.");
+    let key = qualified
+        .iter()
+        .filter_map(|c| c.commitments.first())
+        .fold(GroupElement::zero(), |acc, c| &acc + c);
+    Ok(ElectionPublicKey(key))
+}
+
+/// Generate the verification key of the `index` committee member's `ElectionKeyShare`,
+/// from the commitments of the qualified dealers.
+pub fn share_verification_key(qualified: &[DkgCommitments], index: u32) -> GroupElement {
+    let x = Scalar::from(u64::from(index));
+    let mut key = GroupElement::zero();
+    for c in qualified {
+        let mut power = Scalar::one();
+        for commitment in &c.commitments {
+            key = &key + &commitment.mul(&power);
+            power = &power * &x;
+        }
+    }
+    key
+}
+
+/// Evaluate the polynomial at the committee member `index`.
+fn evaluate_polynomial(coefficients: &[Scalar], index: u32) -> Scalar {
+    let x = Scalar::from(u64::from(index));
+    coefficients
+        .iter()
+        .rev()
+        .fold(Scalar::zero(), |acc, c| &(&acc * &x) + c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dkg_test() {
+        let params = DkgParams::new(2, 3).unwrap();
+        let dealers: Vec<_> = (1..=3)
+            .map(|i| DkgDealer::new_with_default_rng(i, &params).unwrap())
+            .collect();
+        let commitments: Vec<_> = dealers.iter().map(DkgDealer::commitments).collect();
+        let mut shares: Vec<_> = dealers.iter().flat_map(DkgDealer::shares).collect();
+
+        // Dealer `3` sends an invalid share to the member `1`, and does not answer the
+        // complaint.
+        let invalid = shares
+            .iter_mut()
+            .find(|s| s.dealer == 3 && s.recipient == 1)
+            .unwrap();
+        invalid.share = Scalar::one();
+        assert!(!commitments
+            .iter()
+            .any(|c| c.dealer == 3 && c.verify_share(invalid)));
+
+        let complaints = [DkgComplaint::new(3, 1)];
+        let qualified_indexes = qualified_dealers(&params, &commitments, &complaints, &[]);
+        assert_eq!(qualified_indexes, vec![1, 2]);
+
+        // The same commitments received twice do not disqualify the dealer.
+        let duplicated = [commitments.clone(), commitments.clone()].concat();
+        assert_eq!(
+            qualified_dealers(&params, &duplicated, &complaints, &[]),
+            vec![1, 2]
+        );
+
+        let qualified: Vec<_> = commitments
+            .into_iter()
+            .filter(|c| qualified_indexes.contains(&c.dealer))
+            .collect();
+        let key_shares: Vec<_> = (1..=3)
+            .map(|i| ElectionKeyShare::combine(&params, i, &qualified, &shares).unwrap())
+            .collect();
+        for key_share in &key_shares {
+            assert_eq!(
+                key_share.verification_key(),
+                share_verification_key(&qualified, key_share.index)
+            );
+        }
+
+        // The election secret key is the sum of the qualified dealers' constant terms.
+        let secret = dealers
+            .iter()
+            .filter(|d| qualified_indexes.contains(&d.index))
+            .filter_map(|d| d.coefficients.first())
+            .fold(Scalar::zero(), |acc, c| &acc + c);
+        assert_eq!(
+            election_public_key(&qualified).unwrap().0,
+            GroupElement::GENERATOR.mul(&secret)
+        );
+
+        assert!(DkgParams::new(0, 3).is_err());
+        assert!(DkgParams::new(4, 3).is_err());
+        assert!(DkgDealer::new_with_default_rng(4, &params).is_err());
+        assert!(ElectionKeyShare::combine(&params, 1, &qualified, &[]).is_err());
+    }
+
+    #[test]
+    fn equivocating_dealer_test() {
+        let params = DkgParams::new(2, 3).unwrap();
+        let dealers: Vec<_> = (1..=3)
+            .map(|i| DkgDealer::new_with_default_rng(i, &params).unwrap())
+            .collect();
+        let mut commitments: Vec<_> = dealers.iter().map(DkgDealer::commitments).collect();
+
+        // Dealer `2` broadcasts the commitments of another polynomial to some members,
+        // with the shares matching them, so no member complains.
+        let equivocation = DkgDealer::new_with_default_rng(2, &params).unwrap();
+        commitments.push(equivocation.commitments());
+        assert_eq!(qualified_dealers(&params, &commitments, &[], &[]), vec![
+            1, 3
+        ]);
+    }
+}
@@ -1,10 +1,11 @@
 //! Module containing all primitives related to the committee.
 
 mod decoding;
+pub mod dkg;
 
 use crate::crypto::{
     elgamal::generate_public_key,
-    group::{GroupElement, Scalar},
+    group::{GroupElement, GroupElementTable, Scalar},
     rng::{default_rng, rand_core::CryptoRngCore},
 };
 
@@ -36,6 +37,20 @@ impl ElectionSecretKey {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ElectionPublicKey(pub(crate) GroupElement);
 
+impl ElectionPublicKey {
+    /// Precompute the `ElectionPublicKeyTable`, to speed up encryption of many votes.
+    #[must_use]
+    pub fn precompute(&self) -> ElectionPublicKeyTable {
+        ElectionPublicKeyTable(GroupElementTable::new(&self.0))
+    }
+}
+
+/// Election public key with the precomputed multiples table.
+/// It is a heavy object (about 30 KB), so it should be created once and reused for the
+/// whole batch of votes.
+#[derive(Clone)]
+pub struct ElectionPublicKeyTable(pub(crate) GroupElementTable);
+
 #[cfg(test)]
 mod arbitrary_impl {
     use proptest::prelude::{any, Arbitrary, BoxedStrategy, Strategy};
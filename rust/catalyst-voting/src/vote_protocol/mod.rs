@@ -145,6 +145,7 @@
 //! ]);
 //! ```
 
+pub mod ballot;
 pub mod committee;
 pub mod tally;
 pub mod voter;
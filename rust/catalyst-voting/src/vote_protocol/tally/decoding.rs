@@ -0,0 +1,153 @@
+//! Tally objects decoding implementation
+//!
+//! ```cddl
+//! encrypted-tally = bytes .size 64
+//! tally-proof = bytes .size 64
+//! ```
+
+use anyhow::anyhow;
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
+
+use super::{proof::TallyProof, Ciphertext, EncryptedTally};
+use crate::{crypto::zk_dl_equality::DleqProof, utils::decode_cbor_array};
+
+impl EncryptedTally {
+    /// `EncryptedTally` bytes size
+    pub const BYTES_SIZE: usize = Ciphertext::BYTES_SIZE;
+
+    /// Convert this `EncryptedTally` to its underlying sequence of bytes.
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; Self::BYTES_SIZE] {
+        self.0.to_bytes()
+    }
+
+    /// Attempt to construct a `EncryptedTally` from a byte representation.
+    ///
+    /// # Errors
+    ///   - Cannot decode encrypted tally.
+    pub fn from_bytes(bytes: &[u8; Self::BYTES_SIZE]) -> anyhow::Result<Self> {
+        Ok(Self(
+            Ciphertext::from_bytes(bytes).map_err(|_| anyhow!("Cannot decode encrypted tally."))?,
+        ))
+    }
+}
+
+impl TallyProof {
+    /// `TallyProof` bytes size
+    pub const BYTES_SIZE: usize = DleqProof::BYTES_SIZE;
+
+    /// Convert this `TallyProof` to its underlying sequence of bytes.
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; Self::BYTES_SIZE] {
+        self.0.to_bytes()
+    }
+
+    /// Attempt to construct a `TallyProof` from a byte representation.
+    ///
+    /// # Errors
+    ///   - Cannot decode tally proof.
+    pub fn from_bytes(bytes: &[u8; Self::BYTES_SIZE]) -> anyhow::Result<Self> {
+        Ok(Self(
+            DleqProof::from_bytes(bytes).map_err(|_| anyhow!("Cannot decode tally proof."))?,
+        ))
+    }
+}
+
+impl Encode<()> for EncryptedTally {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for EncryptedTally {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        Self::from_bytes(&decode_cbor_array(d, "encrypted tally")?).map_err(|e| {
+            decode::Error::message(format!("Cannot decode encrypted tally, error: {e}"))
+        })
+    }
+}
+
+impl Encode<()> for TallyProof {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for TallyProof {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        Self::from_bytes(&decode_cbor_array(d, "tally proof")?)
+            .map_err(|e| decode::Error::message(format!("Cannot decode tally proof, error: {e}")))
+    }
+}
+
+#[cfg(feature = "serde")]
+crate::utils::impl_serde_as_cbor!(EncryptedTally, TallyProof);
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{
+            decrypt_tally,
+            proof::{generate_tally_proof_with_default_rng, verify_tally_proof},
+            tally, DecryptionTallySetup,
+        },
+        *,
+    };
+    use crate::vote_protocol::{
+        committee::ElectionSecretKey,
+        voter::{encrypt_vote_with_default_rng, Vote},
+    };
+
+    #[test]
+    fn tally_cbor_golden_test() {
+        let raw = hex::decode("0000000000000000000000000000000000000000000000000000000000000000e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76").unwrap();
+        let encrypted_tally = EncryptedTally::from_bytes(&raw.clone().try_into().unwrap()).unwrap();
+        let bytes = minicbor::to_vec(&encrypted_tally).unwrap();
+        assert_eq!(hex::encode(&bytes), format!("5840{}", hex::encode(&raw)));
+        assert_eq!(
+            minicbor::decode::<EncryptedTally>(&bytes).unwrap(),
+            encrypted_tally
+        );
+
+        let raw = hex::decode("01000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000").unwrap();
+        let proof = TallyProof::from_bytes(&raw.clone().try_into().unwrap()).unwrap();
+        let bytes = minicbor::to_vec(&proof).unwrap();
+        assert_eq!(hex::encode(&bytes), format!("5840{}", hex::encode(&raw)));
+        assert_eq!(minicbor::decode::<TallyProof>(&bytes).unwrap(), proof);
+    }
+
+    #[test]
+    fn tally_proof_to_bytes_from_bytes_test() {
+        let election_secret_key = ElectionSecretKey::random_with_default_rng();
+        let election_public_key = election_secret_key.public_key();
+        let vote = Vote::new(1, 2).unwrap();
+        let (encrypted_vote, _) = encrypt_vote_with_default_rng(&vote, &election_public_key);
+        let encrypted_tally = tally(1, &[encrypted_vote], &[10]).unwrap();
+        let proof = generate_tally_proof_with_default_rng(&encrypted_tally, &election_secret_key);
+
+        let encrypted_tally = EncryptedTally::from_bytes(&encrypted_tally.to_bytes()).unwrap();
+        let proof = TallyProof::from_bytes(&proof.to_bytes()).unwrap();
+
+        let setup = DecryptionTallySetup::new(10).unwrap();
+        let result = decrypt_tally(&encrypted_tally, &election_secret_key, &setup).unwrap();
+        assert_eq!(result, 10);
+        assert!(verify_tally_proof(
+            &encrypted_tally,
+            result,
+            &election_public_key,
+            &proof
+        ));
+        assert!(!verify_tally_proof(
+            &encrypted_tally,
+            0,
+            &election_public_key,
+            &proof
+        ));
+    }
+}
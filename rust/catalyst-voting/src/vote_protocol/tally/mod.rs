@@ -1,6 +1,8 @@
 //! Module containing all primitives related to the tally process.
 
+mod decoding;
 pub mod proof;
+pub mod threshold;
 
 use std::ops::{Add, Mul};
 
@@ -25,6 +27,7 @@ pub struct DecryptionTallySetup {
 
 /// A representation of the encrypted tally.
 #[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EncryptedTally(Ciphertext);
 
 impl DecryptionTallySetup {
@@ -15,8 +15,9 @@ use crate::{
 
 /// Tally proof struct.
 #[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[must_use]
-pub struct TallyProof(DleqProof);
+pub struct TallyProof(pub(crate) DleqProof);
 
 /// Generates a tally proof.
 /// More detailed described [here](https://input-output-hk.github.io/catalyst-libs/architecture/08_concepts/catalyst_voting/crypto/#tally-proof)
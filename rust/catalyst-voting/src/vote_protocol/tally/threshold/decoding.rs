@@ -0,0 +1,70 @@
+//! Decryption share CBOR encoding and decoding implementation.
+//!
+//! ```cddl
+//! decryption-share = [index: uint, share: bytes .size 32, proof: bytes .size 64]
+//! ```
+
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
+
+use super::{DecryptionShare, DleqProof, GroupElement};
+use crate::utils::decode_cbor_array;
+
+impl Encode<()> for DecryptionShare {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.array(3)?
+            .u32(self.index)?
+            .bytes(&self.share.to_bytes())?
+            .bytes(&self.proof.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for DecryptionShare {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        if d.array()? != Some(3) {
+            return Err(decode::Error::message(
+                "Invalid decryption share array, expected 3 items",
+            ));
+        }
+        let index = d.u32()?;
+        let share =
+            GroupElement::from_bytes(&decode_cbor_array(d, "decryption share")?).map_err(|e| {
+                decode::Error::message(format!("Cannot decode decryption share, error: {e}"))
+            })?;
+        let proof = DleqProof::from_bytes(&decode_cbor_array(d, "decryption share proof")?)
+            .map_err(|e| {
+                decode::Error::message(format!("Cannot decode decryption share proof, error: {e}"))
+            })?;
+        Ok(Self {
+            index,
+            share,
+            proof,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{generate_decryption_share_with_default_rng, ElectionKeyShare},
+        *,
+    };
+    use crate::{crypto::group::Scalar, vote_protocol::tally::EncryptedTally};
+
+    #[test]
+    fn decryption_share_encode_decode_test() {
+        let key_share = ElectionKeyShare {
+            index: 1,
+            share: Scalar::from(7),
+        };
+        let encrypted_tally = EncryptedTally(crate::crypto::elgamal::Ciphertext::zero());
+        let decryption_share =
+            generate_decryption_share_with_default_rng(&encrypted_tally, &key_share);
+
+        let bytes = minicbor::to_vec(&decryption_share).unwrap();
+        let decoded: DecryptionShare = minicbor::decode(&bytes).unwrap();
+        assert_eq!(decryption_share, decoded);
+    }
+}
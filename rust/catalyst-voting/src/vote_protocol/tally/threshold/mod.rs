@@ -0,0 +1,216 @@
+//! Threshold decryption of the tally, by the committee members holding an
+//! `ElectionKeyShare` from the distributed key generation.
+//!
+//! Every committee member publishes a `DecryptionShare` with a proof of its correctness,
+//! and any `threshold` valid decryption shares decrypt the tally.
+
+mod decoding;
+
+use std::ops::Mul;
+
+use anyhow::{anyhow, ensure};
+
+use super::{DecryptionTallySetup, EncryptedTally};
+use crate::{
+    crypto::{
+        group::{GroupElement, Scalar},
+        rng::{default_rng, rand_core::CryptoRngCore},
+        zk_dl_equality::{generate_dleq_proof, verify_dleq_proof, DleqProof},
+    },
+    vote_protocol::committee::dkg::{DkgParams, ElectionKeyShare},
+};
+
+/// A committee member's share of the tally decryption.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecryptionShare {
+    /// Committee member index.
+    index: u32,
+    /// The first element of the encrypted tally, multiplied by the key share.
+    share: GroupElement,
+    /// Proof the key share of the decryption share is the same as of the verification
+    /// key.
+    proof: DleqProof,
+}
+
+impl DecryptionShare {
+    /// Get the committee member index.
+    #[must_use]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+}
+
+/// Generates a decryption share of the encrypted tally.
+pub fn generate_decryption_share<R: CryptoRngCore>(
+    encrypted_tally: &EncryptedTally, key_share: &ElectionKeyShare, rng: &mut R,
+) -> DecryptionShare {
+    let randomness = Scalar::random(rng);
+    let e1 = encrypted_tally.0.first();
+    let share = e1.mul(&key_share.share);
+
+    let proof = generate_dleq_proof(
+        &GroupElement::GENERATOR,
+        e1,
+        &key_share.verification_key(),
+        &share,
+        &key_share.share,
+        &randomness,
+    );
+
+    DecryptionShare {
+        index: key_share.index,
+        share,
+        proof,
+    }
+}
+
+/// Generates a decryption share of the encrypted tally with `crypto::default_rng`.
+pub fn generate_decryption_share_with_default_rng(
+    encrypted_tally: &EncryptedTally, key_share: &ElectionKeyShare,
+) -> DecryptionShare {
+    generate_decryption_share(encrypted_tally, key_share, &mut default_rng())
+}
+
+/// Verifies a decryption share of the encrypted tally, against the committee member's
+/// `dkg::share_verification_key`.
+#[must_use]
+pub fn verify_decryption_share(
+    encrypted_tally: &EncryptedTally, decryption_share: &DecryptionShare,
+    verification_key: &GroupElement,
+) -> bool {
+    verify_dleq_proof(
+        &decryption_share.proof,
+        &GroupElement::GENERATOR,
+        encrypted_tally.0.first(),
+        verification_key,
+        &decryption_share.share,
+    )
+}
+
+/// Decrypts the encrypted tally result from `threshold` decryption shares.
+/// **NOTE** make sure the provided decryption shares are valid, by executing the
+/// `verify_decryption_share` on each of them.
+///
+/// # Errors
+///   - Not enough decryption shares.
+///   - Cannot decrypt tally result. Provided invalid decryption shares or invalid
+///     encrypted tally result.
+pub fn threshold_decrypt_tally(
+    encrypted_tally: &EncryptedTally, decryption_shares: &[DecryptionShare], params: &DkgParams,
+    setup: &DecryptionTallySetup,
+) -> anyhow::Result<u64> {
+    let threshold = usize::try_from(params.threshold())?;
+    let mut shares: Vec<&DecryptionShare> = Vec::new();
+    for share in decryption_shares {
+        if shares.len() < threshold
+            && share.index > 0
+            && share.index <= params.members()
+            && shares.iter().all(|s| s.index != share.index)
+        {
+            shares.push(share);
+        }
+    }
+    ensure!(
+        shares.len() == threshold,
+        "Not enough decryption shares. Provided {0} distinct decryption shares, {1} are needed.",
+        shares.len(),
+        params.threshold(),
+    );
+
+    let indexes: Vec<_> = shares.iter().map(|s| s.index).collect();
+    let decryption = shares
+        .iter()
+        .map(|s| s.share.mul(&lagrange_coefficient(s.index, &indexes)))
+        .fold(GroupElement::zero(), |acc, d| &acc + &d);
+    let ge = encrypted_tally.0.second() - &decryption;
+
+    let res = setup.discrete_log_setup.discrete_log(ge).map_err(|_| {
+        anyhow!(
+            "Cannot decrypt tally result. \
+            Provided invalid decryption shares or invalid encrypted tally result."
+        )
+    })?;
+    Ok(res)
+}
+
+/// Calculates the Lagrange coefficient of the committee member `index` at `0`, over the
+/// committee members `indexes`.
+fn lagrange_coefficient(index: u32, indexes: &[u32]) -> Scalar {
+    let x = Scalar::from(u64::from(index));
+    let (numerator, denominator) = indexes.iter().filter(|m| **m != index).fold(
+        (Scalar::one(), Scalar::one()),
+        |(numerator, denominator), m| {
+            let m = Scalar::from(u64::from(*m));
+            let difference = &m - &x;
+            (&numerator * &m, &denominator * &difference)
+        },
+    );
+    &numerator * &denominator.inverse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote_protocol::{
+        committee::dkg::{election_public_key, share_verification_key, DkgDealer},
+        tally::tally,
+        voter::{encrypt_vote_with_default_rng, Vote},
+    };
+
+    #[test]
+    fn threshold_decrypt_tally_test() {
+        let params = DkgParams::new(2, 3).unwrap();
+        let dealers: Vec<_> = (1..=3)
+            .map(|i| DkgDealer::new_with_default_rng(i, &params).unwrap())
+            .collect();
+        let commitments: Vec<_> = dealers.iter().map(DkgDealer::commitments).collect();
+        let shares: Vec<_> = dealers.iter().flat_map(DkgDealer::shares).collect();
+        let key_shares: Vec<_> = (1..=3)
+            .map(|i| ElectionKeyShare::combine(&params, i, &commitments, &shares).unwrap())
+            .collect();
+        let election_public_key = election_public_key(&commitments).unwrap();
+
+        let voting_powers = [10, 20, 30];
+        let encrypted_votes: Vec<_> = [0, 1, 0]
+            .into_iter()
+            .map(|choice| {
+                let vote = Vote::new(choice, 2).unwrap();
+                encrypt_vote_with_default_rng(&vote, &election_public_key).0
+            })
+            .collect();
+        let encrypted_tally = tally(0, &encrypted_votes, &voting_powers).unwrap();
+
+        let decryption_shares: Vec<_> = key_shares
+            .iter()
+            .map(|k| generate_decryption_share_with_default_rng(&encrypted_tally, k))
+            .collect();
+        for decryption_share in &decryption_shares {
+            let verification_key = share_verification_key(&commitments, decryption_share.index);
+            assert!(verify_decryption_share(
+                &encrypted_tally,
+                decryption_share,
+                &verification_key
+            ));
+        }
+        let other_key = share_verification_key(&commitments, 3);
+        assert!(!decryption_shares
+            .iter()
+            .filter(|s| s.index != 3)
+            .any(|s| verify_decryption_share(&encrypted_tally, s, &other_key)));
+
+        let setup = DecryptionTallySetup::new(60).unwrap();
+        for pair in decryption_shares.windows(2) {
+            assert_eq!(
+                threshold_decrypt_tally(&encrypted_tally, pair, &params, &setup).unwrap(),
+                40
+            );
+        }
+        assert!(threshold_decrypt_tally(
+            &encrypted_tally,
+            decryption_shares.split_at(1).0,
+            &params,
+            &setup
+        )
+        .is_err());
+    }
+}
@@ -1,13 +1,23 @@
 //! Voter objects decoding implementation.
+//!
+//! ```cddl
+//! encrypted-vote = [* ciphertext]
+//! voter-proof = unit-vector-proof
+//! voter-proof-commitment = bytes .size 32
+//! ```
 
 use std::io::Read;
 
 use anyhow::anyhow;
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
 
-use super::{proof::VoterProof, EncryptedVote};
+use super::{
+    proof::{VoterProof, VoterProofCommitment},
+    EncryptedVote,
+};
 use crate::{
     crypto::{elgamal::Ciphertext, zk_unit_vector::UnitVectorProof},
-    utils::read_array,
+    utils::{decode_cbor_array, read_array},
 };
 
 impl EncryptedVote {
@@ -77,6 +87,55 @@ impl VoterProof {
     }
 }
 
+impl Encode<()> for EncryptedVote {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, ctx: &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.encode_with(&self.0, ctx)?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for EncryptedVote {
+    fn decode(d: &mut Decoder<'_>, ctx: &mut ()) -> Result<Self, decode::Error> {
+        d.decode_with(ctx).map(Self)
+    }
+}
+
+impl Encode<()> for VoterProof {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, ctx: &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        self.0.encode(e, ctx)
+    }
+}
+
+impl Decode<'_, ()> for VoterProof {
+    fn decode(d: &mut Decoder<'_>, ctx: &mut ()) -> Result<Self, decode::Error> {
+        UnitVectorProof::decode(d, ctx).map(Self)
+    }
+}
+
+impl Encode<()> for VoterProofCommitment {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.bytes(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for VoterProofCommitment {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, decode::Error> {
+        Self::from_bytes(&decode_cbor_array(d, "voter proof commitment")?).map_err(|e| {
+            decode::Error::message(format!("Cannot decode voter proof commitment, error: {e}"))
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+crate::utils::impl_serde_as_cbor!(EncryptedVote, VoterProof, VoterProofCommitment);
+
 #[cfg(test)]
 mod tests {
     use std::io::Cursor;
@@ -85,6 +144,57 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn voter_cbor_golden_test() {
+        let ciphertext = "0000000000000000000000000000000000000000000000000000000000000000e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76";
+        let raw = hex::decode(format!("{ciphertext}{ciphertext}")).unwrap();
+        let vote = EncryptedVote::from_bytes(&mut raw.as_slice(), 2).unwrap();
+        let bytes = minicbor::to_vec(&vote).unwrap();
+        assert_eq!(
+            hex::encode(&bytes),
+            format!("825840{ciphertext}5840{ciphertext}")
+        );
+        assert_eq!(minicbor::decode::<EncryptedVote>(&bytes).unwrap(), vote);
+
+        let raw = hex::decode(concat!(
+            "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d760000000000000000000000000000000000000000000000000000000000000000e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+            "0000000000000000000000000000000000000000000000000000000000000000e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+            "010000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000300000000000000000000000000000000000000000000000000000000000000",
+            "0400000000000000000000000000000000000000000000000000000000000000",
+        ))
+        .unwrap();
+        let proof = VoterProof::from_bytes(&mut raw.as_slice(), 1).unwrap();
+        let bytes = minicbor::to_vec(&proof).unwrap();
+        assert_eq!(
+            hex::encode(&bytes),
+            concat!(
+                "84",
+                "815860e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d760000000000000000000000000000000000000000000000000000000000000000e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+                "8158400000000000000000000000000000000000000000000000000000000000000000e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+                "815860010000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000300000000000000000000000000000000000000000000000000000000000000",
+                "58200400000000000000000000000000000000000000000000000000000000000000",
+            )
+        );
+        assert_eq!(minicbor::decode::<VoterProof>(&bytes).unwrap(), proof);
+
+        let commitment = VoterProofCommitment::from_bytes(
+            &hex::decode("e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76")
+                .unwrap()
+                .try_into()
+                .unwrap(),
+        )
+        .unwrap();
+        let bytes = minicbor::to_vec(&commitment).unwrap();
+        assert_eq!(
+            hex::encode(&bytes),
+            "5820e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76"
+        );
+        assert_eq!(
+            minicbor::decode::<VoterProofCommitment>(&bytes).unwrap(),
+            commitment
+        );
+    }
+
     #[proptest]
     fn encrypted_vote_to_bytes_from_bytes_test(
         #[strategy(0..5usize)] _size: usize, #[any(#_size)] vote1: EncryptedVote,
@@ -4,12 +4,14 @@ mod decoding;
 pub mod proof;
 
 use anyhow::{anyhow, bail, ensure};
-use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
 
-use super::committee::{ElectionPublicKey, ElectionSecretKey};
+use super::committee::{ElectionPublicKey, ElectionPublicKeyTable, ElectionSecretKey};
 use crate::crypto::{
     babystep_giantstep::BabyStepGiantStep,
-    elgamal::{decrypt, encrypt, Ciphertext},
+    elgamal::{decrypt, encrypt, encrypt_with_table, Ciphertext},
     group::Scalar,
     rng::{default_rng, rand_core::CryptoRngCore},
 };
@@ -32,16 +34,28 @@ pub struct EncryptedVote(Vec<Ciphertext>);
 
 /// A representation of the encryption randomness, used to encrypt the vote.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct EncryptionRandomness(Vec<Scalar>);
+pub struct EncryptionRandomness(pub(crate) Vec<Scalar>);
 
 impl EncryptionRandomness {
     /// Randomly generate the `EncryptionRandomness`.
-    fn random<R: CryptoRngCore>(rng: &mut R, voting_options: usize) -> Self {
+    pub(crate) fn random<R: CryptoRngCore>(rng: &mut R, voting_options: usize) -> Self {
         Self((0..voting_options).map(|_| Scalar::random(rng)).collect())
     }
 }
 
+impl From<Vec<Ciphertext>> for EncryptedVote {
+    fn from(ciphertexts: Vec<Ciphertext>) -> Self {
+        Self(ciphertexts)
+    }
+}
+
 impl EncryptedVote {
+    /// Get the ciphertexts of the encrypted vote, one per voting option.
+    #[must_use]
+    pub fn ciphertexts(&self) -> &[Ciphertext] {
+        &self.0
+    }
+
     /// Get the ciphertext to the corresponding `voting_option`.
     pub(crate) fn get_ciphertext_for_choice(&self, voting_option: usize) -> Option<&Ciphertext> {
         self.0.get(voting_option)
@@ -114,6 +128,63 @@ pub fn encrypt_vote_with_default_rng(
     encrypt_vote(vote, public_key, &mut default_rng())
 }
 
+/// Create a new encrypted vote from the given vote and the precomputed public key
+/// table.
+#[must_use]
+pub fn encrypt_vote_with_table<R: CryptoRngCore>(
+    vote: &Vote, public_key: &ElectionPublicKeyTable, rng: &mut R,
+) -> (EncryptedVote, EncryptionRandomness) {
+    let randomness = EncryptionRandomness::random(rng, vote.voting_options);
+    let encrypted_vote = encrypt_vote_with_randomness(vote, public_key, &randomness);
+    (encrypted_vote, randomness)
+}
+
+/// Create new encrypted votes from the given votes and public key.
+/// Votes are encrypted in parallel, and the public key multiples table is precomputed
+/// once for the whole batch, so it is much faster than calling `encrypt_vote` for
+/// every vote, e.g. when casting a vote per proposal across many proposals.
+#[must_use]
+pub fn encrypt_votes_batch<R: CryptoRngCore>(
+    votes: &[Vote], public_key: &ElectionPublicKey, rng: &mut R,
+) -> Vec<(EncryptedVote, EncryptionRandomness)> {
+    let public_key = public_key.precompute();
+    let randomness: Vec<_> = votes
+        .iter()
+        .map(|vote| EncryptionRandomness::random(rng, vote.voting_options))
+        .collect();
+
+    votes
+        .par_iter()
+        .zip(randomness.into_par_iter())
+        .map(|(vote, randomness)| {
+            let encrypted_vote = encrypt_vote_with_randomness(vote, &public_key, &randomness);
+            (encrypted_vote, randomness)
+        })
+        .collect()
+}
+
+/// Create new encrypted votes from the given votes and public key with the
+/// `crypto::default_rng`.
+#[must_use]
+pub fn encrypt_votes_batch_with_default_rng(
+    votes: &[Vote], public_key: &ElectionPublicKey,
+) -> Vec<(EncryptedVote, EncryptionRandomness)> {
+    encrypt_votes_batch(votes, public_key, &mut default_rng())
+}
+
+/// Encrypt the vote with the provided randomness and the precomputed public key table.
+fn encrypt_vote_with_randomness(
+    vote: &Vote, public_key: &ElectionPublicKeyTable, randomness: &EncryptionRandomness,
+) -> EncryptedVote {
+    let ciphers = vote
+        .to_unit_vector()
+        .iter()
+        .zip(randomness.0.iter())
+        .map(|(m, r)| encrypt_with_table(m, &public_key.0, r))
+        .collect();
+    EncryptedVote(ciphers)
+}
+
 /// Decrypt the encrypted vote.
 /// **NOTE** make sure tha the provided `vote` is a valid one, by executing the
 /// `verify_voter_proof` on the underlying voter proof.
@@ -197,4 +268,30 @@ mod tests {
         assert!(Vote::new(3, voting_options).is_err());
         assert!(Vote::new(4, voting_options).is_err());
     }
+
+    #[test]
+    fn encrypt_votes_batch_test() {
+        let election_secret_key = ElectionSecretKey::random_with_default_rng();
+        let election_public_key = election_secret_key.public_key();
+        let votes: Vec<_> = [0, 2, 1, 2]
+            .into_iter()
+            .map(|choice| Vote::new(choice, 3).unwrap())
+            .collect();
+
+        let encrypted_votes = encrypt_votes_batch_with_default_rng(&votes, &election_public_key);
+        assert_eq!(encrypted_votes.len(), votes.len());
+        for (vote, (encrypted_vote, randomness)) in votes.iter().zip(&encrypted_votes) {
+            let expected: Vec<_> = vote
+                .to_unit_vector()
+                .iter()
+                .zip(randomness.0.iter())
+                .map(|(m, r)| encrypt(m, &election_public_key.0, r))
+                .collect();
+            assert_eq!(encrypted_vote.ciphertexts(), expected.as_slice());
+            assert_eq!(
+                &decrypt_vote(encrypted_vote, &election_secret_key).unwrap(),
+                vote
+            );
+        }
+    }
 }
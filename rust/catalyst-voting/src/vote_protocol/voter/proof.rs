@@ -11,7 +11,10 @@ use crate::{
         group::{GroupElement, Scalar},
         hash::digest::{consts::U64, Digest},
         rng::{default_rng, rand_core::CryptoRngCore},
-        zk_unit_vector::{generate_unit_vector_proof, verify_unit_vector_proof, UnitVectorProof},
+        zk_unit_vector::{
+            batch_verify_unit_vector_proofs, generate_unit_vector_proof, verify_unit_vector_proof,
+            UnitVectorProof,
+        },
     },
     vote_protocol::committee::ElectionPublicKey,
 };
@@ -23,10 +26,14 @@ use crate::{
 pub struct VoterProof(pub(super) UnitVectorProof);
 
 /// Voter proof commitment struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[must_use]
 pub struct VoterProofCommitment(GroupElement);
 
 impl VoterProofCommitment {
+    /// `VoterProofCommitment` bytes size
+    pub const BYTES_SIZE: usize = GroupElement::BYTES_SIZE;
+
     /// Randomly generate the `VoterProofCommitment`.
     pub fn random<R: CryptoRngCore>(rng: &mut R) -> Self {
         Self(GroupElement::GENERATOR.mul(&Scalar::random(rng)))
@@ -42,6 +49,22 @@ impl VoterProofCommitment {
     where D: Digest<OutputSize = U64> + Default {
         Self(GroupElement::from_hash(hash))
     }
+
+    /// Convert this `VoterProofCommitment` to its underlying sequence of bytes.
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; Self::BYTES_SIZE] {
+        self.0.to_bytes()
+    }
+
+    /// Attempt to construct a `VoterProofCommitment` from a byte representation.
+    ///
+    /// # Errors
+    ///   - Cannot decode voter proof commitment.
+    pub fn from_bytes(bytes: &[u8; Self::BYTES_SIZE]) -> anyhow::Result<Self> {
+        GroupElement::from_bytes(bytes)
+            .map(Self)
+            .map_err(|_| anyhow::anyhow!("Cannot decode voter proof commitment."))
+    }
 }
 
 /// Generates a voter proof.
@@ -107,6 +130,39 @@ pub fn verify_voter_proof(
     verify_unit_vector_proof(&proof.0, encrypted_vote.0, &public_key.0, &commitment.0)
 }
 
+/// Verifies a batch of voter proofs at once, which is significantly faster than verifying
+/// them one by one.
+/// Returns `true` only if all proofs are valid, the invalid ones could be found with
+/// [`verify_voter_proof`].
+#[must_use]
+#[allow(clippy::module_name_repetitions)]
+pub fn verify_voter_proofs_batch<'a, R: CryptoRngCore>(
+    items: impl IntoIterator<Item = (&'a EncryptedVote, &'a VoterProofCommitment, &'a VoterProof)>,
+    public_key: &ElectionPublicKey, rng: &mut R,
+) -> bool {
+    batch_verify_unit_vector_proofs(
+        items
+            .into_iter()
+            .map(|(encrypted_vote, commitment, proof)| {
+                (&proof.0, encrypted_vote.0.as_slice(), &commitment.0)
+            }),
+        &public_key.0,
+        rng,
+    )
+}
+
+/// Verifies a batch of voter proofs at once with `crypto::default_rng`.
+/// Returns `true` only if all proofs are valid, the invalid ones could be found with
+/// [`verify_voter_proof`].
+#[must_use]
+#[allow(clippy::module_name_repetitions)]
+pub fn verify_voter_proofs_batch_with_default_rng<'a>(
+    items: impl IntoIterator<Item = (&'a EncryptedVote, &'a VoterProofCommitment, &'a VoterProof)>,
+    public_key: &ElectionPublicKey,
+) -> bool {
+    verify_voter_proofs_batch(items, public_key, &mut default_rng())
+}
+
 #[cfg(test)]
 mod arbitrary_impl {
     use proptest::prelude::{any_with, Arbitrary, BoxedStrategy, Strategy};
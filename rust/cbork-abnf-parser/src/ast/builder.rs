@@ -0,0 +1,231 @@
+//! Builds the typed ABNF AST from the PEST parse tree.
+
+// cspell: words rulename
+
+use pest::{
+    error::{Error, ErrorVariant},
+    iterators::{Pair, Pairs},
+};
+
+use super::{
+    Abnf, Alternation, Concatenation, Element, NumVal, Repeat, Repetition, Rule, RuleName, Span,
+};
+use crate::abnf::Rule as PestRule;
+
+/// Result of building an AST node, the error is boxed as it is large.
+type BuildResult<T> = Result<T, Box<Error<PestRule>>>;
+
+/// Build the typed AST from the pairs of the `abnf` rule.
+pub(crate) fn build_ast(pairs: Pairs<'_, PestRule>) -> BuildResult<Abnf> {
+    let mut rules = Vec::new();
+    for pair in pairs {
+        match pair.as_rule() {
+            PestRule::abnf => {
+                for child in children(&pair) {
+                    if child.as_rule() == PestRule::rule {
+                        rules.push(rule(&child)?);
+                    }
+                }
+            },
+            PestRule::rule => rules.push(rule(&pair)?),
+            _ => {},
+        }
+    }
+    Ok(Abnf { rules })
+}
+
+/// Get the span of a pair.
+fn span(pair: &Pair<'_, PestRule>) -> Span {
+    let span = pair.as_span();
+    let (line, column) = span.start_pos().line_col();
+    Span {
+        start: span.start(),
+        end: span.end(),
+        line,
+        column,
+    }
+}
+
+/// Get the children of a pair, without comments.
+fn children<'a>(pair: &Pair<'a, PestRule>) -> impl Iterator<Item = Pair<'a, PestRule>> {
+    pair.clone()
+        .into_inner()
+        .filter(|child| child.as_rule() != PestRule::COMMENT)
+}
+
+/// Get the first child of a pair with the given rule, or fail.
+fn required_child<'a>(
+    pair: &Pair<'a, PestRule>, rule: PestRule,
+) -> BuildResult<Pair<'a, PestRule>> {
+    children(pair)
+        .find(|child| child.as_rule() == rule)
+        .ok_or_else(|| error(pair, &format!("Expected a `{rule:?}`")))
+}
+
+/// Create an error located at the pair.
+fn error(pair: &Pair<'_, PestRule>, message: &str) -> Box<Error<PestRule>> {
+    Box::new(Error::new_from_span(
+        ErrorVariant::CustomError {
+            message: message.to_string(),
+        },
+        pair.as_span(),
+    ))
+}
+
+/// Build a `rule`.
+fn rule(pair: &Pair<'_, PestRule>) -> BuildResult<Rule> {
+    let name = rule_name(&required_child(pair, PestRule::rulename)?);
+    let is_incremental = required_child(pair, PestRule::defined_as)?
+        .as_str()
+        .contains("=/");
+    let elements = required_child(pair, PestRule::elements)?;
+    let elements = alternation(&required_child(&elements, PestRule::alternation)?)?;
+    Ok(Rule {
+        name,
+        is_incremental,
+        elements,
+        span: span(pair),
+    })
+}
+
+/// Build a `rulename`.
+fn rule_name(pair: &Pair<'_, PestRule>) -> RuleName {
+    RuleName {
+        name: pair.as_str().to_string(),
+        span: span(pair),
+    }
+}
+
+/// Build an `alternation`.
+fn alternation(pair: &Pair<'_, PestRule>) -> BuildResult<Alternation> {
+    let concatenations = children(pair)
+        .map(|child| concatenation(&child))
+        .collect::<BuildResult<_>>()?;
+    Ok(Alternation {
+        concatenations,
+        span: span(pair),
+    })
+}
+
+/// Build a `concatenation`.
+fn concatenation(pair: &Pair<'_, PestRule>) -> BuildResult<Concatenation> {
+    let repetitions = children(pair)
+        .map(|child| repetition(&child))
+        .collect::<BuildResult<_>>()?;
+    Ok(Concatenation {
+        repetitions,
+        span: span(pair),
+    })
+}
+
+/// Build a `repetition`.
+fn repetition(pair: &Pair<'_, PestRule>) -> BuildResult<Repetition> {
+    let repeat = children(pair)
+        .find(|child| child.as_rule() == PestRule::repeat)
+        .map(|child| repeat(&child))
+        .transpose()?;
+    let element = element(&required_child(pair, PestRule::element)?)?;
+    Ok(Repetition {
+        repeat,
+        element,
+        span: span(pair),
+    })
+}
+
+/// Build a `repeat`, `min*max` or `n`.
+fn repeat(pair: &Pair<'_, PestRule>) -> BuildResult<Repeat> {
+    let number = |digits: &str| -> BuildResult<Option<u64>> {
+        if digits.is_empty() {
+            return Ok(None);
+        }
+        digits
+            .parse()
+            .map(Some)
+            .map_err(|_| error(pair, "Repetition count is too large"))
+    };
+    let repeat = match pair.as_str().split_once('*') {
+        Some((min, max)) => {
+            Repeat {
+                min: number(min)?.unwrap_or_default(),
+                max: number(max)?,
+            }
+        },
+        None => {
+            let count = number(pair.as_str())?;
+            Repeat {
+                min: count.unwrap_or_default(),
+                max: count,
+            }
+        },
+    };
+    if repeat.max.is_some_and(|max| max < repeat.min) {
+        return Err(error(pair, "Repetition maximum is less than its minimum"));
+    }
+    Ok(repeat)
+}
+
+/// Build an `element`.
+fn element(pair: &Pair<'_, PestRule>) -> BuildResult<Element> {
+    let child = children(pair)
+        .next()
+        .ok_or_else(|| error(pair, "Expected an element"))?;
+    match child.as_rule() {
+        PestRule::rulename => Ok(Element::RuleName(rule_name(&child))),
+        PestRule::group => {
+            Ok(Element::Group(alternation(&required_child(
+                &child,
+                PestRule::alternation,
+            )?)?))
+        },
+        PestRule::option => {
+            Ok(Element::Option(alternation(&required_child(
+                &child,
+                PestRule::alternation,
+            )?)?))
+        },
+        PestRule::char_val => Ok(Element::CharVal(unwrap_str(&child).to_string())),
+        PestRule::num_val => num_val(&child).map(Element::NumVal),
+        PestRule::prose_val => Ok(Element::ProseVal(unwrap_str(&child).to_string())),
+        rule => Err(error(&child, &format!("Unexpected `{rule:?}`"))),
+    }
+}
+
+/// Get the string of a pair without its first and last delimiter characters.
+fn unwrap_str<'a>(pair: &Pair<'a, PestRule>) -> &'a str {
+    let s = pair.as_str();
+    s.get(1..s.len().saturating_sub(1)).unwrap_or_default()
+}
+
+/// Build a `num_val`.
+fn num_val(pair: &Pair<'_, PestRule>) -> BuildResult<NumVal> {
+    let child = children(pair)
+        .next()
+        .ok_or_else(|| error(pair, "Expected a numeric value"))?;
+    let radix = match child.as_rule() {
+        PestRule::bin_val => 2,
+        PestRule::dec_val => 10,
+        PestRule::hex_val => 16,
+        rule => return Err(error(&child, &format!("Unexpected `{rule:?}`"))),
+    };
+    // Skip the `b`, `d` or `x` prefix.
+    let digits = child.as_str().get(1..).unwrap_or_default();
+    let value = |digits: &str| {
+        u32::from_str_radix(digits, radix).map_err(|_| error(&child, "Numeric value is too large"))
+    };
+    match digits.split_once('-') {
+        Some((start, end)) => {
+            let (start, end) = (value(start)?, value(end)?);
+            if end < start {
+                return Err(error(&child, "Numeric range end is less than its start"));
+            }
+            Ok(NumVal::Range { start, end })
+        },
+        None => {
+            digits
+                .split('.')
+                .map(value)
+                .collect::<BuildResult<_>>()
+                .map(NumVal::Values)
+        },
+    }
+}
@@ -0,0 +1,139 @@
+//! Typed Abstract Syntax Tree (AST) of a parsed ABNF specification.
+//!
+//! The node names follow the grammar of RFC-5234 Section 4, every node carries the
+//! `Span` of the source it was parsed from.
+
+// cspell: words rulename
+
+pub(crate) mod builder;
+
+/// Location of a node in the parsed ABNF source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// Byte offset of the start of the node.
+    pub start: usize,
+    /// Byte offset of the end of the node (exclusive).
+    pub end: usize,
+    /// Line number of the start of the node, starting at 1.
+    pub line: usize,
+    /// Column number of the start of the node, starting at 1.
+    pub column: usize,
+}
+
+/// A parsed ABNF specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Abnf {
+    /// The rules of the specification, in source order.
+    pub rules: Vec<Rule>,
+}
+
+impl Abnf {
+    /// Get the rules with the given name, in source order.
+    ///
+    /// Rule names are case-insensitive, a name can have multiple rules when it is
+    /// extended with `=/`.
+    pub fn rules_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Rule> {
+        self.rules
+            .iter()
+            .filter(move |rule| rule.name.matches(name))
+    }
+}
+
+/// An ABNF rule, `name = elements` or `name =/ elements`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    /// Name of the rule.
+    pub name: RuleName,
+    /// Whether the rule adds alternatives to a previous rule (`=/`).
+    pub is_incremental: bool,
+    /// The elements of the rule.
+    pub elements: Alternation,
+    /// Source span of the rule.
+    pub span: Span,
+}
+
+/// The name of a rule, as it is written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleName {
+    /// The name.
+    pub name: String,
+    /// Source span of the name.
+    pub span: Span,
+}
+
+impl RuleName {
+    /// Whether the name matches the given name, rule names are case-insensitive.
+    #[must_use]
+    pub fn matches(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+}
+
+/// An alternation, made of one or more concatenations (`a / b`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alternation {
+    /// The alternatives.
+    pub concatenations: Vec<Concatenation>,
+    /// Source span of the alternation.
+    pub span: Span,
+}
+
+/// A concatenation, a sequence of repetitions (`a b`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Concatenation {
+    /// The repetitions, in sequence.
+    pub repetitions: Vec<Repetition>,
+    /// Source span of the concatenation.
+    pub span: Span,
+}
+
+/// An element repeated a number of times.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repetition {
+    /// How many times the element is repeated, exactly once if not given.
+    pub repeat: Option<Repeat>,
+    /// The repeated element.
+    pub element: Element,
+    /// Source span of the repetition.
+    pub span: Span,
+}
+
+/// How many times an element is repeated, `min*max` or `n`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repeat {
+    /// The minimum number of repetitions, zero if not given.
+    pub min: u64,
+    /// The maximum number of repetitions, unlimited if not given.
+    pub max: Option<u64>,
+}
+
+/// An element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Element {
+    /// A reference to a rule.
+    RuleName(RuleName),
+    /// A group, `( alternation )`.
+    Group(Alternation),
+    /// An optional alternation, `[ alternation ]`.
+    Option(Alternation),
+    /// A case-insensitive string, `"..."`, without the quotes.
+    CharVal(String),
+    /// Terminal values, `%b`, `%d` or `%x`.
+    NumVal(NumVal),
+    /// A prose description, `<...>`, without the brackets.
+    ProseVal(String),
+}
+
+/// Terminal values, given as numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NumVal {
+    /// A sequence of values, `%x41.42`.
+    Values(Vec<u32>),
+    /// A single value within a range, `%x30-39`.
+    Range {
+        /// The first value of the range.
+        start: u32,
+        /// The last value of the range (inclusive).
+        end: u32,
+    },
+}
@@ -0,0 +1,284 @@
+//! Conversion of ABNF rules into CDDL rules.
+//!
+//! ABNF describes text, so every converted rule is a CDDL text string type, which can
+//! be used by the CDDL validation pipeline. Only the rules with a CDDL equivalent are
+//! converted:
+//!
+//! - alternations are type choices, `a / b`, and rule references are type names.
+//! - concatenations use the RFC-9165 `.cat` control operator.
+//! - strings and sequences of terminal values are text literals. ABNF strings are
+//!   case-insensitive, so only strings with at most one letter are converted.
+//! - ranges of terminal values use the `.regexp` control operator.
+//! - options, `[a]` or `0*1a`, are a choice with the empty text.
+//!
+//! Other repetitions and prose descriptions have no equivalent, the rules using them
+//! are reported as unsupported and left out of the CDDL. Rule names are
+//! case-insensitive in ABNF, they are converted to lowercase.
+
+// cspell: words tstr regexp
+
+use crate::ast::{Abnf, Alternation, Concatenation, Element, NumVal, Repetition, Rule, Span};
+
+/// The result of converting ABNF rules into CDDL rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CddlConversion {
+    /// The converted CDDL rules, one per line, in source order.
+    pub cddl: String,
+    /// The rules which could not be converted.
+    pub unsupported: Vec<UnsupportedRule>,
+}
+
+/// An ABNF rule without a CDDL equivalent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedRule {
+    /// Name of the rule.
+    pub name: String,
+    /// Why the rule can not be converted.
+    pub reason: String,
+    /// Source span of the rule.
+    pub span: Span,
+}
+
+/// Convert the ABNF rules into CDDL rules, where semantics allow.
+///
+/// Converted rules can reference rules which are not converted, or are not defined in
+/// the ABNF, like the RFC-5234 core rules. Those must be defined before the CDDL is
+/// validated.
+///
+/// # Examples
+///
+/// ```
+/// use cbork_abnf_parser::{cddl::to_cddl, parse_abnf};
+///
+/// let abnf = parse_abnf("sign = \"+\" / \"-\"\n").unwrap();
+/// assert_eq!(to_cddl(&abnf).cddl, "sign = \"+\" / \"-\"\n");
+/// ```
+#[must_use]
+pub fn to_cddl(abnf: &Abnf) -> CddlConversion {
+    let mut conversion = CddlConversion {
+        cddl: String::new(),
+        unsupported: Vec::new(),
+    };
+    for rule in &abnf.rules {
+        match convert_rule(rule) {
+            Ok(cddl) => {
+                conversion.cddl.push_str(&cddl);
+                conversion.cddl.push('\n');
+            },
+            Err(reason) => {
+                conversion.unsupported.push(UnsupportedRule {
+                    name: rule.name.name.clone(),
+                    reason,
+                    span: rule.span,
+                });
+            },
+        }
+    }
+    conversion
+}
+
+/// Result of converting an ABNF node, the reason it can not be converted on failure.
+type ConversionResult<T> = Result<T, String>;
+
+/// A converted CDDL type.
+struct CddlType {
+    /// The CDDL text of the type.
+    text: String,
+    /// Whether the type can be used as an operand without parentheses.
+    is_type2: bool,
+}
+
+impl CddlType {
+    /// Get the text of the type as an operand, in parentheses if needed.
+    fn into_type2(self) -> String {
+        if self.is_type2 {
+            self.text
+        } else {
+            format!("({})", self.text)
+        }
+    }
+}
+
+/// Convert a rule.
+fn convert_rule(rule: &Rule) -> ConversionResult<String> {
+    let name = convert_name(&rule.name.name)?;
+    let assign = if rule.is_incremental { "/=" } else { "=" };
+    let value = convert_alternation(&rule.elements)?;
+    Ok(format!("{name} {assign} {}", value.text))
+}
+
+/// Convert a rule name into a CDDL identifier.
+fn convert_name(name: &str) -> ConversionResult<String> {
+    if name.ends_with('-') {
+        return Err(format!(
+            "rule name `{name}` ends with `-`, which is not a valid CDDL identifier"
+        ));
+    }
+    Ok(name.to_ascii_lowercase())
+}
+
+/// Convert an alternation into a type choice.
+fn convert_alternation(alternation: &Alternation) -> ConversionResult<CddlType> {
+    let choices = alternation
+        .concatenations
+        .iter()
+        .map(convert_concatenation)
+        .collect::<ConversionResult<Vec<_>>>()?;
+    Ok(choice(choices))
+}
+
+/// Convert a concatenation, the operands are nested as `.cat` has only two.
+fn convert_concatenation(concatenation: &Concatenation) -> ConversionResult<CddlType> {
+    let mut operands = concatenation
+        .repetitions
+        .iter()
+        .map(convert_repetition)
+        .collect::<ConversionResult<Vec<_>>>()?;
+    let mut cddl = operands
+        .pop()
+        .ok_or_else(|| "empty concatenation".to_string())?;
+    while let Some(operand) = operands.pop() {
+        cddl = CddlType {
+            text: format!("{} .cat {}", operand.into_type2(), cddl.into_type2()),
+            is_type2: false,
+        };
+    }
+    Ok(cddl)
+}
+
+/// Convert a repetition, only single and optional elements are supported.
+fn convert_repetition(repetition: &Repetition) -> ConversionResult<CddlType> {
+    let element = convert_element(&repetition.element)?;
+    match repetition.repeat {
+        None => Ok(element),
+        Some(repeat) if repeat.min == 1 && repeat.max == Some(1) => Ok(element),
+        Some(repeat) if repeat.min == 0 && repeat.max == Some(1) => Ok(optional(element)),
+        Some(repeat) => {
+            let max = repeat.max.map(|max| max.to_string()).unwrap_or_default();
+            Err(format!(
+                "repetition `{}*{max}` has no CDDL equivalent",
+                repeat.min
+            ))
+        },
+    }
+}
+
+/// Convert an element.
+fn convert_element(element: &Element) -> ConversionResult<CddlType> {
+    match element {
+        Element::RuleName(name) => {
+            Ok(CddlType {
+                text: convert_name(&name.name)?,
+                is_type2: true,
+            })
+        },
+        Element::Group(alternation) => {
+            Ok(CddlType {
+                text: convert_alternation(alternation)?.into_type2(),
+                is_type2: true,
+            })
+        },
+        Element::Option(alternation) => convert_alternation(alternation).map(optional),
+        Element::CharVal(value) => convert_char_val(value),
+        Element::NumVal(NumVal::Values(values)) => {
+            let value = values
+                .iter()
+                .map(|value| convert_char(*value))
+                .collect::<ConversionResult<String>>()?;
+            Ok(text(&value))
+        },
+        Element::NumVal(NumVal::Range { start, end }) => convert_range(*start, *end),
+        Element::ProseVal(prose) => {
+            Err(format!(
+                "prose description `<{prose}>` has no CDDL equivalent"
+            ))
+        },
+    }
+}
+
+/// Convert a case-insensitive string, a choice of its lowercase and uppercase text if it
+/// has one letter.
+fn convert_char_val(value: &str) -> ConversionResult<CddlType> {
+    match value.chars().filter(char::is_ascii_alphabetic).count() {
+        0 => Ok(text(value)),
+        1 => {
+            Ok(choice(vec![
+                text(&value.to_ascii_lowercase()),
+                text(&value.to_ascii_uppercase()),
+            ]))
+        },
+        _ => {
+            Err(format!(
+                "case-insensitive string `\"{value}\"` has no CDDL equivalent"
+            ))
+        },
+    }
+}
+
+/// Convert a range of terminal values into a regular expression of one character.
+fn convert_range(start: u32, end: u32) -> ConversionResult<CddlType> {
+    if start == end {
+        return Ok(text(&convert_char(start)?.to_string()));
+    }
+    let class_char = |value| {
+        convert_char(value).map(|c| {
+            if matches!(c, '\\' | '[' | ']' | '-' | '^') {
+                format!("\\{c}")
+            } else {
+                c.to_string()
+            }
+        })
+    };
+    let class = format!("[{}-{}]", class_char(start)?, class_char(end)?);
+    Ok(CddlType {
+        text: format!("tstr .regexp {}", text(&class).text),
+        is_type2: false,
+    })
+}
+
+/// Convert a terminal value into a character, which can be written in a CDDL text.
+fn convert_char(value: u32) -> ConversionResult<char> {
+    char::from_u32(value)
+        .filter(|c| !c.is_control())
+        .ok_or_else(|| format!("terminal value `{value:#x}` has no CDDL text equivalent"))
+}
+
+/// A CDDL text literal.
+fn text(value: &str) -> CddlType {
+    let mut text = String::from("\"");
+    for c in value.chars() {
+        if matches!(c, '"' | '\\') {
+            text.push('\\');
+        }
+        text.push(c);
+    }
+    text.push('"');
+    CddlType {
+        text,
+        is_type2: true,
+    }
+}
+
+/// A choice of the types.
+fn choice(mut choices: Vec<CddlType>) -> CddlType {
+    if choices.len() == 1 {
+        if let Some(choice) = choices.pop() {
+            return choice;
+        }
+    }
+    // A type choice is made of `type1`, so no choice needs parentheses.
+    let text = choices
+        .into_iter()
+        .map(|choice| choice.text)
+        .collect::<Vec<_>>()
+        .join(" / ");
+    CddlType {
+        text,
+        is_type2: false,
+    }
+}
+
+/// An optional type, a choice with the empty text.
+fn optional(cddl: CddlType) -> CddlType {
+    choice(vec![cddl, text("")])
+}
@@ -5,8 +5,11 @@
 #![allow(missing_docs)] // TODO(apskhem): Temporary, to bo removed in a subsequent PR
 
 use derive_more::From;
+use pest::error::Error;
 pub use pest::Parser;
-use pest::{error::Error, iterators::Pairs};
+
+pub mod ast;
+pub mod cddl;
 
 pub mod abnf {
     pub use pest::Parser;
@@ -25,18 +28,13 @@ pub mod abnf_test {
     pub struct ABNFTestParser;
 }
 
-/// Abstract Syntax Tree (AST) representing parsed ABNF syntax.
-#[derive(Debug)]
-#[allow(dead_code)]
-pub struct AST<'a>(Pairs<'a, abnf::Rule>);
-
 /// Represents an error that may occur during ABNF parsing.
 #[derive(thiserror::Error, Debug, From)]
 #[error("{0}")]
 pub struct ABNFError(Error<abnf::Rule>);
 
 /// Parses the input string containing ABNF (Augmented Backus-Naur Form) syntax and
-/// returns the Abstract Syntax Tree (AST).
+/// returns its typed Abstract Syntax Tree (AST).
 ///
 /// # Arguments
 ///
@@ -44,8 +42,8 @@ pub struct ABNFError(Error<abnf::Rule>);
 ///
 /// # Returns
 ///
-/// Returns a `Result` where the successful variant contains the Abstract Syntax Tree
-/// (AST) representing the parsed ABNF, and the error variant contains a boxed
+/// Returns a `Result` where the successful variant contains the typed Abstract Syntax
+/// Tree (AST) representing the parsed ABNF, and the error variant contains a boxed
 /// `ABNFError`.
 ///
 /// # Errors
@@ -63,12 +61,11 @@ pub struct ABNFError(Error<abnf::Rule>);
 /// let input = fs::read_to_string("path/to/your/file.abnf").unwrap();
 /// let result = parse_abnf(&input);
 /// ```
-pub fn parse_abnf(input: &str) -> Result<AST<'_>, Box<ABNFError>> {
-    let result: Result<AST<'_>, _> = abnf::ABNFParser::parse(abnf::Rule::abnf, input)
-        .map(AST)
-        .map_err(ABNFError);
+pub fn parse_abnf(input: &str) -> Result<ast::Abnf, Box<ABNFError>> {
+    let pairs =
+        abnf::ABNFParser::parse(abnf::Rule::abnf, input).map_err(|e| Box::new(ABNFError(e)))?;
 
-    result.map_err(Box::new)
+    ast::builder::build_ast(pairs).map_err(|e| Box::new(ABNFError(*e)))
 }
 
 #[cfg(test)]
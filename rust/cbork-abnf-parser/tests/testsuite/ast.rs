@@ -0,0 +1,123 @@
+//! Typed AST Tests
+
+use cbork_abnf_parser::{
+    ast::{Element, NumVal, Repeat},
+    parse_abnf,
+};
+
+/// Test the AST of the rules.
+#[test]
+fn check_ast_rules() {
+    let input = [
+        "; comment",
+        "date-fullyear = 4DIGIT",
+        "time-secfrac  = \".\" 1*DIGIT ; comment",
+        "sign = \"+\" / \"-\"",
+        "SIGN =/ [%x20]",
+        "digit = %x30-39",
+        "prose = <any thing>",
+        "",
+    ]
+    .join("\n");
+    let abnf = parse_abnf(&input).unwrap();
+    assert_eq!(abnf.rules.len(), 6);
+
+    let year = abnf.rules_named("DATE-FULLYEAR").next().unwrap();
+    assert_eq!(year.span.line, 2);
+    assert_eq!(year.name.span.column, 1);
+    let repetition = year
+        .elements
+        .concatenations
+        .first()
+        .unwrap()
+        .repetitions
+        .first()
+        .unwrap();
+    assert_eq!(
+        repetition.repeat,
+        Some(Repeat {
+            min: 4,
+            max: Some(4)
+        })
+    );
+    let Element::RuleName(name) = &repetition.element else {
+        panic!("`date-fullyear` is not a rule reference");
+    };
+    assert_eq!(name.name, "DIGIT");
+
+    let secfrac = abnf.rules_named("time-secfrac").next().unwrap();
+    let repetitions = &secfrac.elements.concatenations.first().unwrap().repetitions;
+    assert_eq!(repetitions.len(), 2);
+    assert_eq!(
+        repetitions.first().unwrap().element,
+        Element::CharVal(".".to_string())
+    );
+    assert_eq!(
+        repetitions.get(1).unwrap().repeat,
+        Some(Repeat { min: 1, max: None })
+    );
+
+    let signs: Vec<_> = abnf.rules_named("sign").collect();
+    assert_eq!(signs.len(), 2);
+    assert!(!signs.first().unwrap().is_incremental);
+    assert_eq!(signs.first().unwrap().elements.concatenations.len(), 2);
+    let incremental = signs.get(1).unwrap();
+    assert!(incremental.is_incremental);
+    let Element::Option(option) = &incremental
+        .elements
+        .concatenations
+        .first()
+        .unwrap()
+        .repetitions
+        .first()
+        .unwrap()
+        .element
+    else {
+        panic!("`sign` is not an option");
+    };
+    assert_eq!(
+        option
+            .concatenations
+            .first()
+            .unwrap()
+            .repetitions
+            .first()
+            .unwrap()
+            .element,
+        Element::NumVal(NumVal::Values(vec![0x20]))
+    );
+
+    let first_element = |name: &str| {
+        abnf.rules_named(name)
+            .next()
+            .unwrap()
+            .elements
+            .concatenations
+            .first()
+            .unwrap()
+            .repetitions
+            .first()
+            .unwrap()
+            .element
+            .clone()
+    };
+    assert_eq!(
+        first_element("digit"),
+        Element::NumVal(NumVal::Range {
+            start: 0x30,
+            end: 0x39
+        })
+    );
+    assert_eq!(
+        first_element("prose"),
+        Element::ProseVal("any thing".to_string())
+    );
+}
+
+/// Test the values which can not be represented in the AST.
+#[test]
+fn check_ast_invalid_values() {
+    assert!(parse_abnf("a = %x110000000\n").is_err());
+    assert!(parse_abnf("a = %x39-30\n").is_err());
+    assert!(parse_abnf("a = 3*2b\n").is_err());
+}
@@ -0,0 +1,64 @@
+//! ABNF to CDDL Conversion Tests
+// cspell: words numoffset secfrac tstr regexp
+
+use cbork_abnf_parser::{cddl::to_cddl, parse_abnf};
+use cbork_cddl_parser::{parse_cddl, Extension};
+
+/// Test the conversion of the supported and unsupported rules.
+#[test]
+fn check_to_cddl() {
+    let input = [
+        "time-numoffset = (\"+\" / \"-\") time-hour \":\" time-minute",
+        "time-hour = 2DIGIT",
+        "opt = [ \"a\" ] %x41.42",
+        "Digit = %x30-39",
+        "quote = %x22",
+        "quote =/ %x5C",
+        "time-secfrac = \".\" 1*DIGIT",
+        "prose = <prose>",
+        "name- = \"x\"",
+        "",
+    ]
+    .join("\n");
+    let abnf = parse_abnf(&input).unwrap();
+    let conversion = to_cddl(&abnf);
+
+    assert_eq!(
+        conversion.cddl,
+        [
+            "time-numoffset = (\"+\" / \"-\") .cat (time-hour .cat (\":\" .cat time-minute))",
+            "opt = (\"a\" / \"A\" / \"\") .cat \"AB\"",
+            "digit = tstr .regexp \"[0-9]\"",
+            "quote = \"\\\"\"",
+            "quote /= \"\\\\\"",
+            "",
+        ]
+        .join("\n")
+    );
+
+    let unsupported: Vec<_> = conversion
+        .unsupported
+        .iter()
+        .map(|rule| (rule.name.as_str(), rule.span.line))
+        .collect();
+    assert_eq!(unsupported, [
+        ("time-hour", 2),
+        ("time-secfrac", 7),
+        ("prose", 8),
+        ("name-", 9)
+    ]);
+
+    // The converted rules are valid CDDL.
+    let mut cddl = conversion.cddl;
+    assert!(parse_cddl(&mut cddl, &Extension::CDDL).is_ok());
+}
+
+/// Test the conversion of case-insensitive strings.
+#[test]
+fn check_to_cddl_case_insensitive() {
+    let abnf = parse_abnf("t = \"T\"\nnumber = \"1.0\"\ntrue = \"true\"\n").unwrap();
+    let conversion = to_cddl(&abnf);
+    assert_eq!(conversion.cddl, "t = \"t\" / \"T\"\nnumber = \"1.0\"\n");
+    assert_eq!(conversion.unsupported.len(), 1);
+    assert_eq!(conversion.unsupported.first().unwrap().name, "true");
+}
@@ -1,6 +1,8 @@
 //! ABNF Parser Tests
 mod abnf;
 mod alternations;
+mod ast;
+mod cddl;
 mod character_sets;
 mod comments;
 mod common;
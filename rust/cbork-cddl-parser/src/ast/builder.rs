@@ -0,0 +1,669 @@
+//! Builds the typed CDDL AST from the PEST parse tree.
+//!
+//! All the supported grammars share the RFC-8610 rules, so the builder is generic over
+//! the `Rule` type of each PEST parser, using `CddlRule` to identify the rules.
+
+// cspell: words assignt assigng genericparm genericarg rangeop ctlop grpchoice grpent
+// cspell: words memberkey bareword optcom hexfloat intfloat
+
+use anyhow::{anyhow, bail, ensure, Context};
+use pest::{iterators::Pair, RuleType};
+
+use super::{
+    ByteString, Cddl, Group, GroupChoice, GroupEntry, GroupEntryKind, GroupRule, Identifier,
+    IdentifierKind, MemberKey, Occurrence, Operator, Span, Type, Type1, Type2, TypeRule, Value,
+};
+use crate::parser::Ast;
+
+/// The grammar rules the AST is built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    /// `expr`
+    Expr,
+    /// `typename`
+    Typename,
+    /// `groupname`
+    Groupname,
+    /// `assignt`
+    Assignt,
+    /// `assigng`
+    Assigng,
+    /// `genericparm`
+    Genericparm,
+    /// `genericarg`
+    Genericarg,
+    /// `type`
+    Type,
+    /// `type1`
+    Type1,
+    /// `type2`
+    Type2,
+    /// `rangeop`
+    Rangeop,
+    /// `ctlop`
+    Ctlop,
+    /// `group`
+    Group,
+    /// `grpchoice`
+    Grpchoice,
+    /// `grpent`
+    Grpent,
+    /// `memberkey`
+    Memberkey,
+    /// `bareword`
+    Bareword,
+    /// `optcom`
+    Optcom,
+    /// `occur`
+    Occur,
+    /// `value`
+    Value,
+    /// `number`
+    Number,
+    /// `hexfloat`
+    Hexfloat,
+    /// `intfloat`
+    Intfloat,
+    /// `fraction`
+    Fraction,
+    /// `exponent`
+    Exponent,
+    /// `int`
+    Int,
+    /// `uint`
+    Uint,
+    /// `text`
+    Text,
+    /// `bytes`
+    Bytes,
+    /// `bytes_hex`
+    BytesHex,
+    /// `bytes_b64`
+    BytesB64,
+    /// `bytes_text`
+    BytesText,
+    /// `id`
+    Id,
+    /// `group_socket`
+    GroupSocket,
+    /// `type_socket`
+    TypeSocket,
+    /// `COMMENT`
+    Comment,
+    /// Any other rule.
+    Other,
+}
+
+/// A grammar rule of one of the PEST parsers.
+pub(crate) trait CddlRule: RuleType {
+    /// Get the kind of grammar rule.
+    fn kind(self) -> Kind;
+}
+
+/// Implement `CddlRule` for the `Rule` of each PEST parser module.
+macro_rules! impl_cddl_rule {
+    ($($parser:ident),+) => {
+        $(
+            impl CddlRule for crate::parser::$parser::Rule {
+                fn kind(self) -> Kind {
+                    use crate::parser::$parser::Rule as R;
+                    match self {
+                        R::expr => Kind::Expr,
+                        R::typename => Kind::Typename,
+                        R::groupname => Kind::Groupname,
+                        R::assignt => Kind::Assignt,
+                        R::assigng => Kind::Assigng,
+                        R::genericparm => Kind::Genericparm,
+                        R::genericarg => Kind::Genericarg,
+                        R::r#type => Kind::Type,
+                        R::type1 => Kind::Type1,
+                        R::type2 => Kind::Type2,
+                        R::rangeop => Kind::Rangeop,
+                        R::ctlop => Kind::Ctlop,
+                        R::group => Kind::Group,
+                        R::grpchoice => Kind::Grpchoice,
+                        R::grpent => Kind::Grpent,
+                        R::memberkey => Kind::Memberkey,
+                        R::bareword => Kind::Bareword,
+                        R::optcom => Kind::Optcom,
+                        R::occur => Kind::Occur,
+                        R::value => Kind::Value,
+                        R::number => Kind::Number,
+                        R::hexfloat => Kind::Hexfloat,
+                        R::intfloat => Kind::Intfloat,
+                        R::fraction => Kind::Fraction,
+                        R::exponent => Kind::Exponent,
+                        R::int => Kind::Int,
+                        R::uint => Kind::Uint,
+                        R::text => Kind::Text,
+                        R::bytes => Kind::Bytes,
+                        R::bytes_hex => Kind::BytesHex,
+                        R::bytes_b64 => Kind::BytesB64,
+                        R::bytes_text => Kind::BytesText,
+                        R::id => Kind::Id,
+                        R::group_socket => Kind::GroupSocket,
+                        R::type_socket => Kind::TypeSocket,
+                        R::COMMENT => Kind::Comment,
+                        _ => Kind::Other,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_cddl_rule!(rfc_8610, rfc_9165, cddl);
+
+/// Build the typed AST from the preprocessed PEST AST.
+pub(crate) fn build_ast(ast: Ast) -> anyhow::Result<Cddl> {
+    match ast {
+        Ast::Rfc8610(exprs) => build(&exprs),
+        Ast::Rfc9165(exprs) => build(&exprs),
+        Ast::Cddl(exprs) => build(&exprs),
+    }
+}
+
+/// Build the typed AST from the `expr` rules of a specification.
+fn build<R: CddlRule>(exprs: &[Pair<'_, R>]) -> anyhow::Result<Cddl> {
+    let rules = exprs.iter().map(rule).collect::<anyhow::Result<_>>()?;
+    Ok(Cddl { rules })
+}
+
+/// Get the source span of a pair.
+fn span<R: RuleType>(pair: &Pair<'_, R>) -> Span {
+    let span = pair.as_span();
+    let (line, column) = span.start_pos().line_col();
+    Span {
+        start: span.start(),
+        end: span.end(),
+        line,
+        column,
+    }
+}
+
+/// Get the children of a pair, without comments.
+fn children<'a, R: CddlRule>(pair: &Pair<'a, R>) -> impl Iterator<Item = Pair<'a, R>> {
+    pair.clone()
+        .into_inner()
+        .filter(|child| child.as_rule().kind() != Kind::Comment)
+}
+
+/// Get the first child of a pair with the given kind.
+fn child<'a, R: CddlRule>(pair: &Pair<'a, R>, kind: Kind) -> Option<Pair<'a, R>> {
+    children(pair).find(|child| child.as_rule().kind() == kind)
+}
+
+/// Get the first child of a pair with the given kind, or fail.
+fn required_child<'a, R: CddlRule>(pair: &Pair<'a, R>, kind: Kind) -> anyhow::Result<Pair<'a, R>> {
+    child(pair, kind).ok_or_else(|| unexpected(pair, &format!("a `{kind:?}`")))
+}
+
+/// Error for a pair which does not have the expected structure.
+fn unexpected<R: RuleType>(pair: &Pair<'_, R>, expected: &str) -> anyhow::Error {
+    let span = span(pair);
+    anyhow!(
+        "Expected {expected} at line {}, column {}, found `{}`",
+        span.line,
+        span.column,
+        pair.as_str()
+    )
+}
+
+/// Get the source text of a pair, with all comments removed.
+fn strip_comments<R: CddlRule>(pair: &Pair<'_, R>) -> String {
+    let start = pair.as_span().start();
+    let text = pair.as_str();
+
+    let mut stripped = String::new();
+    let mut pos = 0;
+    for comment in pair
+        .clone()
+        .into_inner()
+        .flatten()
+        .filter(|child| child.as_rule().kind() == Kind::Comment)
+    {
+        let comment = comment.as_span();
+        stripped.push_str(text.get(pos..comment.start() - start).unwrap_or_default());
+        pos = comment.end() - start;
+    }
+    stripped.push_str(text.get(pos..).unwrap_or_default());
+    stripped
+}
+
+/// Build a rule from an `expr`.
+fn rule<R: CddlRule>(pair: &Pair<'_, R>) -> anyhow::Result<super::Rule> {
+    let mut name = None;
+    let mut generic_params = Vec::new();
+    let mut is_choice_extension = false;
+    let mut value = None;
+    let mut entry = None;
+
+    for child in children(pair) {
+        match child.as_rule().kind() {
+            Kind::Typename | Kind::Groupname => name = Some(identifier(&child)?),
+            Kind::Genericparm => {
+                generic_params = children(&child)
+                    .map(|param| identifier(&param))
+                    .collect::<anyhow::Result<_>>()?;
+            },
+            Kind::Assignt => is_choice_extension = child.as_str() == "/=",
+            Kind::Assigng => is_choice_extension = child.as_str() == "//=",
+            Kind::Type => value = Some(ty(&child)?),
+            Kind::Grpent => entry = Some(group_entry(&child)?),
+            _ => return Err(unexpected(&child, "a rule")),
+        }
+    }
+
+    let name = name.ok_or_else(|| unexpected(pair, "a rule name"))?;
+    match (value, entry) {
+        (Some(value), None) => {
+            Ok(super::Rule::Type(TypeRule {
+                name,
+                generic_params,
+                is_choice_extension,
+                value,
+                span: span(pair),
+            }))
+        },
+        (None, Some(entry)) => {
+            Ok(super::Rule::Group(GroupRule {
+                name,
+                generic_params,
+                is_choice_extension,
+                entry,
+                span: span(pair),
+            }))
+        },
+        _ => Err(unexpected(pair, "a type or group rule")),
+    }
+}
+
+/// Build an identifier from an `id`, or a rule wrapping one.
+fn identifier<R: CddlRule>(pair: &Pair<'_, R>) -> anyhow::Result<Identifier> {
+    if pair.as_rule().kind() != Kind::Id {
+        let id = required_child(pair, Kind::Id)?;
+        return identifier(&id);
+    }
+
+    let kind = match children(pair).next().map(|child| child.as_rule().kind()) {
+        Some(Kind::GroupSocket) => IdentifierKind::GroupSocket,
+        Some(Kind::TypeSocket) => IdentifierKind::TypeSocket,
+        _ => IdentifierKind::Name,
+    };
+    Ok(Identifier {
+        name: pair.as_str().to_string(),
+        kind,
+        span: span(pair),
+    })
+}
+
+/// Build the generic arguments from the optional `genericarg` child of a pair.
+fn generic_args<R: CddlRule>(pair: &Pair<'_, R>) -> anyhow::Result<Vec<Type1>> {
+    child(pair, Kind::Genericarg).map_or_else(
+        || Ok(Vec::new()),
+        |args| children(&args).map(|arg| type1(&arg)).collect(),
+    )
+}
+
+/// Build a type from a `type`.
+fn ty<R: CddlRule>(pair: &Pair<'_, R>) -> anyhow::Result<Type> {
+    let choices = children(pair)
+        .map(|choice| type1(&choice))
+        .collect::<anyhow::Result<_>>()?;
+    Ok(Type {
+        choices,
+        span: span(pair),
+    })
+}
+
+/// Build a type choice from a `type1`.
+fn type1<R: CddlRule>(pair: &Pair<'_, R>) -> anyhow::Result<Type1> {
+    let mut parts = children(pair);
+    let lhs = parts.next().ok_or_else(|| unexpected(pair, "a type"))?;
+
+    let operator = match (parts.next(), parts.next()) {
+        (None, None) => None,
+        (Some(operator), Some(rhs)) => {
+            let operator = match operator.as_rule().kind() {
+                Kind::Rangeop => {
+                    Operator::Range {
+                        inclusive: operator.as_str() == "..",
+                    }
+                },
+                Kind::Ctlop => Operator::Control(identifier(&operator)?),
+                _ => return Err(unexpected(&operator, "a range or control operator")),
+            };
+            Some((operator, type2(&rhs)?))
+        },
+        _ => return Err(unexpected(pair, "an operator and its right hand side")),
+    };
+
+    Ok(Type1 {
+        type2: type2(&lhs)?,
+        operator,
+        span: span(pair),
+    })
+}
+
+/// Build a single type from a `type2`.
+fn type2<R: CddlRule>(pair: &Pair<'_, R>) -> anyhow::Result<Type2> {
+    let text = pair.as_str();
+    let type2 = match text.chars().next() {
+        Some('(') => Type2::Parenthesized(Box::new(ty(&required_child(pair, Kind::Type)?)?)),
+        Some('{') => Type2::Map(group(&required_child(pair, Kind::Group)?)?),
+        Some('[') => Type2::Array(group(&required_child(pair, Kind::Group)?)?),
+        Some('~') => {
+            Type2::Unwrap {
+                name: identifier(&required_child(pair, Kind::Typename)?)?,
+                generic_args: generic_args(pair)?,
+            }
+        },
+        Some('&') => {
+            match child(pair, Kind::Group) {
+                Some(inline) => Type2::ChoiceFromInlineGroup(group(&inline)?),
+                None => {
+                    Type2::ChoiceFromGroup {
+                        name: identifier(&required_child(pair, Kind::Groupname)?)?,
+                        generic_args: generic_args(pair)?,
+                    }
+                },
+            }
+        },
+        Some('#') => {
+            let info = child(pair, Kind::Uint)
+                .map(|info| uint(&info))
+                .transpose()?;
+            if let Some(value) = child(pair, Kind::Type) {
+                Type2::TaggedData {
+                    tag: info,
+                    value: Box::new(ty(&value)?),
+                }
+            } else if let Some(major) = text.chars().nth(1).and_then(|c| c.to_digit(10)) {
+                Type2::MajorType {
+                    major: u8::try_from(major)?,
+                    info,
+                }
+            } else {
+                Type2::Any
+            }
+        },
+        _ => {
+            if let Some(value) = child(pair, Kind::Value) {
+                Type2::Value(literal(&value)?)
+            } else {
+                Type2::Typename {
+                    name: identifier(&required_child(pair, Kind::Typename)?)?,
+                    generic_args: generic_args(pair)?,
+                }
+            }
+        },
+    };
+    Ok(type2)
+}
+
+/// Build a group from a `group`.
+fn group<R: CddlRule>(pair: &Pair<'_, R>) -> anyhow::Result<Group> {
+    let choices = children(pair)
+        .map(|choice| {
+            let entries = children(&choice)
+                .filter(|entry| entry.as_rule().kind() == Kind::Grpent)
+                .map(|entry| group_entry(&entry))
+                .collect::<anyhow::Result<_>>()?;
+            Ok(GroupChoice {
+                entries,
+                span: span(&choice),
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+    Ok(Group {
+        choices,
+        span: span(pair),
+    })
+}
+
+/// Build a group entry from a `grpent`.
+fn group_entry<R: CddlRule>(pair: &Pair<'_, R>) -> anyhow::Result<GroupEntry> {
+    let occurrence = child(pair, Kind::Occur)
+        .map(|occur| occurrence(&occur))
+        .transpose()?;
+
+    let kind = if let Some(value) = child(pair, Kind::Type) {
+        GroupEntryKind::Value {
+            key: child(pair, Kind::Memberkey)
+                .map(|key| member_key(&key))
+                .transpose()?,
+            value: ty(&value)?,
+        }
+    } else if let Some(name) = child(pair, Kind::Groupname) {
+        GroupEntryKind::Groupname {
+            name: identifier(&name)?,
+            generic_args: generic_args(pair)?,
+        }
+    } else {
+        GroupEntryKind::InlineGroup(group(&required_child(pair, Kind::Group)?)?)
+    };
+
+    Ok(GroupEntry {
+        occurrence,
+        kind,
+        span: span(pair),
+    })
+}
+
+/// Build a member key from a `memberkey`.
+fn member_key<R: CddlRule>(pair: &Pair<'_, R>) -> anyhow::Result<MemberKey> {
+    let key = children(pair)
+        .next()
+        .ok_or_else(|| unexpected(pair, "a member key"))?;
+    match key.as_rule().kind() {
+        Kind::Type1 => {
+            let stripped = strip_comments(pair);
+            let cut = stripped
+                .trim_end()
+                .trim_end_matches("=>")
+                .trim_end()
+                .ends_with('^');
+            Ok(MemberKey::Type {
+                key: type1(&key)?,
+                cut,
+            })
+        },
+        Kind::Value => Ok(MemberKey::Value(literal(&key)?)),
+        Kind::Bareword => Ok(MemberKey::Bareword(identifier(&key)?)),
+        _ => Err(unexpected(&key, "a member key")),
+    }
+}
+
+/// Build an occurrence from an `occur`.
+fn occurrence<R: CddlRule>(pair: &Pair<'_, R>) -> anyhow::Result<Occurrence> {
+    match pair.as_str() {
+        "?" => Ok(Occurrence::Optional),
+        "+" => Ok(Occurrence::OneOrMore),
+        text => {
+            let star = text
+                .find('*')
+                .ok_or_else(|| unexpected(pair, "an occurrence"))?;
+            let start = pair.as_span().start();
+
+            let mut min = None;
+            let mut max = None;
+            for bound in children(pair) {
+                if bound.as_span().start() - start < star {
+                    min = Some(uint(&bound)?);
+                } else {
+                    max = Some(uint(&bound)?);
+                }
+            }
+            Ok(Occurrence::Range { min, max })
+        },
+    }
+}
+
+/// Build a literal value from a `value`.
+fn literal<R: CddlRule>(pair: &Pair<'_, R>) -> anyhow::Result<Value> {
+    let value = children(pair)
+        .next()
+        .ok_or_else(|| unexpected(pair, "a value"))?;
+    match value.as_rule().kind() {
+        Kind::Number => number(&value),
+        Kind::Text => {
+            let text = value
+                .as_str()
+                .strip_prefix('"')
+                .and_then(|text| text.strip_suffix('"'))
+                .ok_or_else(|| unexpected(&value, "a text string"))?;
+            Ok(Value::Text(unescape(text)))
+        },
+        Kind::Bytes => Ok(Value::Bytes(bytes(&value)?)),
+        _ => Err(unexpected(&value, "a value")),
+    }
+}
+
+/// Build a number from a `number`.
+fn number<R: CddlRule>(pair: &Pair<'_, R>) -> anyhow::Result<Value> {
+    let number = children(pair)
+        .next()
+        .ok_or_else(|| unexpected(pair, "a number"))?;
+    match number.as_rule().kind() {
+        Kind::Hexfloat => hexfloat(number.as_str()).map(Value::Float),
+        Kind::Intfloat => {
+            if child(&number, Kind::Fraction).is_some() || child(&number, Kind::Exponent).is_some()
+            {
+                let float = number
+                    .as_str()
+                    .parse()
+                    .with_context(|| format!("Invalid float `{}`", number.as_str()))?;
+                Ok(Value::Float(float))
+            } else {
+                int(&required_child(&number, Kind::Int)?).map(Value::Int)
+            }
+        },
+        _ => Err(unexpected(&number, "a number")),
+    }
+}
+
+/// Build an integer from an `int`.
+fn int<R: CddlRule>(pair: &Pair<'_, R>) -> anyhow::Result<i128> {
+    let value = i128::from(uint(&required_child(pair, Kind::Uint)?)?);
+    if pair.as_str().starts_with('-') {
+        Ok(-value)
+    } else {
+        Ok(value)
+    }
+}
+
+/// Build an unsigned integer from a `uint`.
+fn uint<R: CddlRule>(pair: &Pair<'_, R>) -> anyhow::Result<u64> {
+    let text = pair.as_str();
+    let value = if let Some(hex) = text.strip_prefix("0x") {
+        u64::from_str_radix(hex, 16)
+    } else if let Some(binary) = text.strip_prefix("0b") {
+        u64::from_str_radix(binary, 2)
+    } else {
+        text.parse()
+    };
+    value.with_context(|| format!("Invalid unsigned integer `{text}`"))
+}
+
+/// Parse a hex float, of the form `-0x123.abc0p+12`.
+fn hexfloat(text: &str) -> anyhow::Result<f64> {
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, text),
+    };
+    let (mantissa, exponent) = unsigned
+        .strip_prefix("0x")
+        .and_then(|hex| hex.split_once('p'))
+        .ok_or_else(|| anyhow!("Invalid hex float `{text}`"))?;
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    let mut value = 0.0_f64;
+    for digit in integer.chars() {
+        let digit = digit
+            .to_digit(16)
+            .ok_or_else(|| anyhow!("Invalid hex float `{text}`"))?;
+        value = value * 16.0 + f64::from(digit);
+    }
+    let mut scale = 1.0_f64 / 16.0;
+    for digit in fraction.chars() {
+        let digit = digit
+            .to_digit(16)
+            .ok_or_else(|| anyhow!("Invalid hex float `{text}`"))?;
+        value += f64::from(digit) * scale;
+        scale /= 16.0;
+    }
+    let exponent: i32 = exponent
+        .parse()
+        .with_context(|| format!("Invalid hex float exponent `{text}`"))?;
+
+    let value = value * 2.0_f64.powi(exponent);
+    Ok(if negative { -value } else { value })
+}
+
+/// Build a byte string from a `bytes`.
+fn bytes<R: CddlRule>(pair: &Pair<'_, R>) -> anyhow::Result<ByteString> {
+    let bytes = children(pair)
+        .next()
+        .ok_or_else(|| unexpected(pair, "a byte string"))?;
+    let stripped = strip_comments(&bytes);
+    let contents = |prefix| {
+        quoted(&stripped, prefix).ok_or_else(|| unexpected(&bytes, "a quoted byte string"))
+    };
+
+    match bytes.as_rule().kind() {
+        Kind::BytesHex => {
+            let digits = contents("h'")?
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| c.to_digit(16))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| unexpected(&bytes, "hex digits"))?;
+            ensure!(
+                digits.len() % 2 == 0,
+                "Odd number of hex digits in `{}`",
+                bytes.as_str()
+            );
+            let decoded = digits
+                .chunks_exact(2)
+                .map(|pair| {
+                    pair.iter()
+                        .try_fold(0_u8, |byte, digit| -> anyhow::Result<u8> {
+                            Ok(byte * 16 + u8::try_from(*digit)?)
+                        })
+                })
+                .collect::<anyhow::Result<_>>()?;
+            Ok(ByteString::Hex(decoded))
+        },
+        Kind::BytesB64 => {
+            let encoded = contents("b64'")?
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect();
+            Ok(ByteString::Base64(encoded))
+        },
+        Kind::BytesText => Ok(ByteString::Text(unescape(contents("'")?))),
+        _ => bail!("Unexpected byte string `{}`", bytes.as_str()),
+    }
+}
+
+/// Get the contents of a string quoted with `prefix` and `'`.
+fn quoted<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    text.strip_prefix(prefix)
+        .and_then(|text| text.strip_suffix('\''))
+}
+
+/// Resolve the `\` escapes of a text or byte string.
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(escaped) = chars.next() {
+                unescaped.push(escaped);
+            }
+        } else {
+            unescaped.push(c);
+        }
+    }
+    unescaped
+}
@@ -0,0 +1,315 @@
+//! Typed Abstract Syntax Tree (AST) of a parsed CDDL specification.
+//!
+//! The node names follow the grammar of RFC-8610 Appendix B, every node carries the
+//! `Span` of the source it was parsed from.
+
+// cspell: words grpchoice grpent memberkey bareword
+
+pub(crate) mod builder;
+pub mod visitor;
+
+pub use visitor::Visitor;
+
+/// Location of a node in the parsed CDDL source.
+///
+/// The source includes the standard postlude, which is appended to the parsed input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// Byte offset of the start of the node.
+    pub start: usize,
+    /// Byte offset of the end of the node (exclusive).
+    pub end: usize,
+    /// Line number of the start of the node, starting at 1.
+    pub line: usize,
+    /// Column number of the start of the node, starting at 1.
+    pub column: usize,
+}
+
+/// A parsed CDDL specification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cddl {
+    /// The rules of the specification, in source order.
+    pub rules: Vec<Rule>,
+}
+
+impl Cddl {
+    /// Get the rules with the given name, in source order.
+    ///
+    /// A name can have multiple rules when it is extended with `/=` or `//=`.
+    pub fn rules_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Rule> {
+        self.rules
+            .iter()
+            .filter(move |rule| rule.name().name == name)
+    }
+}
+
+/// A CDDL rule.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rule {
+    /// A type rule, `name = type` or `name /= type`.
+    Type(TypeRule),
+    /// A group rule, `name = group-entry` or `name //= group-entry`.
+    Group(GroupRule),
+}
+
+impl Rule {
+    /// Get the name of the rule.
+    #[must_use]
+    pub fn name(&self) -> &Identifier {
+        match self {
+            Rule::Type(rule) => &rule.name,
+            Rule::Group(rule) => &rule.name,
+        }
+    }
+
+    /// Get the source span of the rule.
+    #[must_use]
+    pub fn span(&self) -> Span {
+        match self {
+            Rule::Type(rule) => rule.span,
+            Rule::Group(rule) => rule.span,
+        }
+    }
+}
+
+/// A type rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeRule {
+    /// Name of the rule.
+    pub name: Identifier,
+    /// Generic parameters of the rule.
+    pub generic_params: Vec<Identifier>,
+    /// Whether the rule extends an existing type with more choices (`/=`).
+    pub is_choice_extension: bool,
+    /// The type defined by the rule.
+    pub value: Type,
+    /// Source span of the rule.
+    pub span: Span,
+}
+
+/// A group rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupRule {
+    /// Name of the rule.
+    pub name: Identifier,
+    /// Generic parameters of the rule.
+    pub generic_params: Vec<Identifier>,
+    /// Whether the rule extends an existing group with more choices (`//=`).
+    pub is_choice_extension: bool,
+    /// The group entry defined by the rule.
+    pub entry: GroupEntry,
+    /// Source span of the rule.
+    pub span: Span,
+}
+
+/// The kind of an identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentifierKind {
+    /// A plain name.
+    Name,
+    /// A type socket, `$name`.
+    TypeSocket,
+    /// A group socket, `$$name`.
+    GroupSocket,
+}
+
+/// An identifier, naming a rule or generic parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Identifier {
+    /// The identifier, including any socket prefix.
+    pub name: String,
+    /// The kind of identifier.
+    pub kind: IdentifierKind,
+    /// Source span of the identifier.
+    pub span: Span,
+}
+
+/// A type, made of one or more type choices (`a / b`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Type {
+    /// The type choices.
+    pub choices: Vec<Type1>,
+    /// Source span of the type.
+    pub span: Span,
+}
+
+/// A type choice, optionally constrained by a range or control operator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Type1 {
+    /// The type.
+    pub type2: Type2,
+    /// The operator applied to the type, and its right hand side.
+    pub operator: Option<(Operator, Type2)>,
+    /// Source span of the type choice.
+    pub span: Span,
+}
+
+/// A range or control operator.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operator {
+    /// A range, `..` when inclusive and `...` when exclusive of the upper bound.
+    Range {
+        /// Whether the upper bound is included in the range.
+        inclusive: bool,
+    },
+    /// A control operator, such as `.size` or `.bits`.
+    Control(Identifier),
+}
+
+/// A single type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type2 {
+    /// A literal value.
+    Value(Value),
+    /// A reference to a type, `name` or `name<args>`.
+    Typename {
+        /// Name of the type.
+        name: Identifier,
+        /// Generic arguments of the type.
+        generic_args: Vec<Type1>,
+    },
+    /// A parenthesized type, `( type )`.
+    Parenthesized(Box<Type>),
+    /// A map, `{ group }`.
+    Map(Group),
+    /// An array, `[ group ]`.
+    Array(Group),
+    /// An unwrapped type, `~ name`.
+    Unwrap {
+        /// Name of the unwrapped type.
+        name: Identifier,
+        /// Generic arguments of the type.
+        generic_args: Vec<Type1>,
+    },
+    /// A choice made from the entries of a group, `&( group )`.
+    ChoiceFromInlineGroup(Group),
+    /// A choice made from the entries of a named group, `& name`.
+    ChoiceFromGroup {
+        /// Name of the group.
+        name: Identifier,
+        /// Generic arguments of the group.
+        generic_args: Vec<Type1>,
+    },
+    /// A tagged data item, `#6.tag( type )`.
+    TaggedData {
+        /// The tag number, any tag if not given.
+        tag: Option<u64>,
+        /// The type of the tagged data item.
+        value: Box<Type>,
+    },
+    /// A data item of a major type, `#major.info`.
+    MajorType {
+        /// The major type.
+        major: u8,
+        /// The additional information, any if not given.
+        info: Option<u64>,
+    },
+    /// Any data item, `#`.
+    Any,
+}
+
+/// A group, made of one or more group choices (`a // b`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    /// The group choices.
+    pub choices: Vec<GroupChoice>,
+    /// Source span of the group.
+    pub span: Span,
+}
+
+/// A group choice, a sequence of group entries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupChoice {
+    /// The group entries.
+    pub entries: Vec<GroupEntry>,
+    /// Source span of the group choice.
+    pub span: Span,
+}
+
+/// A group entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupEntry {
+    /// How many times the entry can occur, exactly once if not given.
+    pub occurrence: Option<Occurrence>,
+    /// The entry.
+    pub kind: GroupEntryKind,
+    /// Source span of the group entry.
+    pub span: Span,
+}
+
+/// The kind of a group entry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupEntryKind {
+    /// A value entry, optionally with a member key.
+    Value {
+        /// The member key of the entry.
+        key: Option<MemberKey>,
+        /// The type of the entry.
+        value: Type,
+    },
+    /// A reference to a group, `name` or `name<args>`.
+    Groupname {
+        /// Name of the group.
+        name: Identifier,
+        /// Generic arguments of the group.
+        generic_args: Vec<Type1>,
+    },
+    /// An inline group, `( group )`.
+    InlineGroup(Group),
+}
+
+/// The member key of a group entry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemberKey {
+    /// A key of any type, `type =>` or with a cut `type ^ =>`.
+    Type {
+        /// The type of the key.
+        key: Type1,
+        /// Whether the key has a cut (`^`).
+        cut: bool,
+    },
+    /// A bare word key, `name:`.
+    Bareword(Identifier),
+    /// A literal value key, `value:`.
+    Value(Value),
+}
+
+/// How many times a group entry can occur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Occurrence {
+    /// Zero or one times, `?`.
+    Optional,
+    /// One or more times, `+`.
+    OneOrMore,
+    /// Between `min` and `max` times, `min*max`, either bound is unlimited if not given.
+    Range {
+        /// The minimum number of occurrences.
+        min: Option<u64>,
+        /// The maximum number of occurrences.
+        max: Option<u64>,
+    },
+}
+
+/// A literal value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// An integer.
+    Int(i128),
+    /// A floating point number.
+    Float(f64),
+    /// A text string, with escapes resolved.
+    Text(String),
+    /// A byte string.
+    Bytes(ByteString),
+}
+
+/// A literal byte string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ByteString {
+    /// A hex encoded byte string, `h'...'`, decoded.
+    Hex(Vec<u8>),
+    /// A base64url encoded byte string, `b64'...'`, with whitespace removed.
+    Base64(String),
+    /// A text byte string, `'...'`, with escapes resolved.
+    Text(String),
+}
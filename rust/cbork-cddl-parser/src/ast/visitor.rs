@@ -0,0 +1,189 @@
+//! Visitor of the CDDL AST.
+//!
+//! Each `Visitor` method defaults to walking the children of its node with the matching
+//! `walk_*` function, so an implementation only needs to override the nodes it is
+//! interested in, and call the `walk_*` function to keep descending.
+
+// cspell: words grpchoice grpent memberkey
+
+use super::{
+    Cddl, Group, GroupChoice, GroupEntry, GroupEntryKind, Identifier, MemberKey, Occurrence,
+    Operator, Rule, Type, Type1, Type2, Value,
+};
+
+/// A visitor of the nodes of the CDDL AST.
+pub trait Visitor {
+    /// Visit a parsed CDDL specification.
+    fn visit_cddl(&mut self, cddl: &Cddl) {
+        walk_cddl(self, cddl);
+    }
+
+    /// Visit a rule.
+    fn visit_rule(&mut self, rule: &Rule) {
+        walk_rule(self, rule);
+    }
+
+    /// Visit an identifier.
+    fn visit_identifier(&mut self, _identifier: &Identifier) {}
+
+    /// Visit a type.
+    fn visit_type(&mut self, ty: &Type) {
+        walk_type(self, ty);
+    }
+
+    /// Visit a type choice.
+    fn visit_type1(&mut self, type1: &Type1) {
+        walk_type1(self, type1);
+    }
+
+    /// Visit an operator.
+    fn visit_operator(&mut self, operator: &Operator) {
+        walk_operator(self, operator);
+    }
+
+    /// Visit a single type.
+    fn visit_type2(&mut self, type2: &Type2) {
+        walk_type2(self, type2);
+    }
+
+    /// Visit a group.
+    fn visit_group(&mut self, group: &Group) {
+        walk_group(self, group);
+    }
+
+    /// Visit a group choice.
+    fn visit_group_choice(&mut self, choice: &GroupChoice) {
+        walk_group_choice(self, choice);
+    }
+
+    /// Visit a group entry.
+    fn visit_group_entry(&mut self, entry: &GroupEntry) {
+        walk_group_entry(self, entry);
+    }
+
+    /// Visit a member key.
+    fn visit_member_key(&mut self, key: &MemberKey) {
+        walk_member_key(self, key);
+    }
+
+    /// Visit an occurrence.
+    fn visit_occurrence(&mut self, _occurrence: &Occurrence) {}
+
+    /// Visit a literal value.
+    fn visit_value(&mut self, _value: &Value) {}
+}
+
+/// Walk the rules of a CDDL specification.
+pub fn walk_cddl<V: Visitor + ?Sized>(visitor: &mut V, cddl: &Cddl) {
+    for rule in &cddl.rules {
+        visitor.visit_rule(rule);
+    }
+}
+
+/// Walk the name, generic parameters and definition of a rule.
+pub fn walk_rule<V: Visitor + ?Sized>(visitor: &mut V, rule: &Rule) {
+    match rule {
+        Rule::Type(rule) => {
+            visitor.visit_identifier(&rule.name);
+            for param in &rule.generic_params {
+                visitor.visit_identifier(param);
+            }
+            visitor.visit_type(&rule.value);
+        },
+        Rule::Group(rule) => {
+            visitor.visit_identifier(&rule.name);
+            for param in &rule.generic_params {
+                visitor.visit_identifier(param);
+            }
+            visitor.visit_group_entry(&rule.entry);
+        },
+    }
+}
+
+/// Walk the choices of a type.
+pub fn walk_type<V: Visitor + ?Sized>(visitor: &mut V, ty: &Type) {
+    for choice in &ty.choices {
+        visitor.visit_type1(choice);
+    }
+}
+
+/// Walk a type choice, and its operator.
+pub fn walk_type1<V: Visitor + ?Sized>(visitor: &mut V, type1: &Type1) {
+    visitor.visit_type2(&type1.type2);
+    if let Some((operator, rhs)) = &type1.operator {
+        visitor.visit_operator(operator);
+        visitor.visit_type2(rhs);
+    }
+}
+
+/// Walk the name of a control operator.
+pub fn walk_operator<V: Visitor + ?Sized>(visitor: &mut V, operator: &Operator) {
+    if let Operator::Control(name) = operator {
+        visitor.visit_identifier(name);
+    }
+}
+
+/// Walk the children of a single type.
+pub fn walk_type2<V: Visitor + ?Sized>(visitor: &mut V, type2: &Type2) {
+    match type2 {
+        Type2::Value(value) => visitor.visit_value(value),
+        Type2::Typename { name, generic_args }
+        | Type2::Unwrap { name, generic_args }
+        | Type2::ChoiceFromGroup { name, generic_args } => {
+            visitor.visit_identifier(name);
+            for arg in generic_args {
+                visitor.visit_type1(arg);
+            }
+        },
+        Type2::Parenthesized(ty) | Type2::TaggedData { value: ty, .. } => visitor.visit_type(ty),
+        Type2::Map(group) | Type2::Array(group) | Type2::ChoiceFromInlineGroup(group) => {
+            visitor.visit_group(group);
+        },
+        Type2::MajorType { .. } | Type2::Any => {},
+    }
+}
+
+/// Walk the choices of a group.
+pub fn walk_group<V: Visitor + ?Sized>(visitor: &mut V, group: &Group) {
+    for choice in &group.choices {
+        visitor.visit_group_choice(choice);
+    }
+}
+
+/// Walk the entries of a group choice.
+pub fn walk_group_choice<V: Visitor + ?Sized>(visitor: &mut V, choice: &GroupChoice) {
+    for entry in &choice.entries {
+        visitor.visit_group_entry(entry);
+    }
+}
+
+/// Walk the occurrence and children of a group entry.
+pub fn walk_group_entry<V: Visitor + ?Sized>(visitor: &mut V, entry: &GroupEntry) {
+    if let Some(occurrence) = &entry.occurrence {
+        visitor.visit_occurrence(occurrence);
+    }
+    match &entry.kind {
+        GroupEntryKind::Value { key, value } => {
+            if let Some(key) = key {
+                visitor.visit_member_key(key);
+            }
+            visitor.visit_type(value);
+        },
+        GroupEntryKind::Groupname { name, generic_args } => {
+            visitor.visit_identifier(name);
+            for arg in generic_args {
+                visitor.visit_type1(arg);
+            }
+        },
+        GroupEntryKind::InlineGroup(group) => visitor.visit_group(group),
+    }
+}
+
+/// Walk the children of a member key.
+pub fn walk_member_key<V: Visitor + ?Sized>(visitor: &mut V, key: &MemberKey) {
+    match key {
+        MemberKey::Type { key, .. } => visitor.visit_type1(key),
+        MemberKey::Bareword(name) => visitor.visit_identifier(name),
+        MemberKey::Value(value) => visitor.visit_value(value),
+    }
+}
@@ -1,7 +1,9 @@
 //! A parser for CDDL, utilized for parsing in accordance with RFC 8610.
 
+pub mod ast;
 mod parser;
 mod preprocessor;
+pub mod validator;
 
 /// Represents different grammar extensions for handling CDDL specifications.
 pub enum Extension {
@@ -13,6 +15,21 @@ pub enum Extension {
     CDDL,
 }
 
+/// Parses a CDDL input string into its typed AST.
+///
+/// The standard postlude is appended to the input, so its rules are part of the AST.
+///
+/// # Errors
+///
+/// This function may return an error in the following cases:
+///
+/// - If there is an issue with parsing the CDDL input.
+pub fn parse_cddl(input: &mut String, extension: &Extension) -> anyhow::Result<ast::Cddl> {
+    let ast = parser::parse_cddl(input, extension)?;
+    let ast = preprocessor::process_ast(ast)?;
+    ast::builder::build_ast(ast)
+}
+
 /// Verifies semantically a CDDL input string.
 ///
 /// # Errors
@@ -21,7 +38,5 @@ pub enum Extension {
 ///
 /// - If there is an issue with parsing the CDDL input.
 pub fn validate_cddl(input: &mut String, extension: &Extension) -> anyhow::Result<()> {
-    let ast = parser::parse_cddl(input, extension)?;
-    let _ast = preprocessor::process_ast(ast)?;
-    Ok(())
+    parse_cddl(input, extension).map(|_| ())
 }
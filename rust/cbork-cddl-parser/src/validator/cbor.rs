@@ -0,0 +1,181 @@
+//! Generic CBOR data items, decoded for validation.
+
+use anyhow::{bail, ensure};
+use minicbor::{data::Type, Decoder};
+
+/// The maximum nesting of arrays, maps and tags which are decoded.
+const MAX_NESTING: usize = 128;
+
+/// A decoded CBOR data item.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Item {
+    /// An integer, major types 0 and 1.
+    Int(i128),
+    /// A byte string, definite or indefinite length.
+    Bytes(Vec<u8>),
+    /// A text string, definite or indefinite length.
+    Text(String),
+    /// An array.
+    Array(Vec<Item>),
+    /// A map, in encoded order.
+    Map(Vec<(Item, Item)>),
+    /// A tagged data item.
+    Tag(u64, Box<Item>),
+    /// A simple value, including `false` (20), `true` (21), `null` (22) and `undefined`
+    /// (23).
+    Simple(u8),
+    /// A floating point number, with its encoded additional information (25, 26 or 27).
+    Float(f64, u8),
+}
+
+impl Item {
+    /// Get the major type of the item.
+    pub(crate) fn major(&self) -> u8 {
+        match self {
+            Item::Int(value) if *value >= 0 => 0,
+            Item::Int(_) => 1,
+            Item::Bytes(_) => 2,
+            Item::Text(_) => 3,
+            Item::Array(_) => 4,
+            Item::Map(_) => 5,
+            Item::Tag(..) => 6,
+            Item::Simple(_) | Item::Float(..) => 7,
+        }
+    }
+
+    /// Describe the kind of the item.
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            Item::Int(value) if *value >= 0 => "uint".to_string(),
+            Item::Int(_) => "nint".to_string(),
+            Item::Bytes(_) => "bstr".to_string(),
+            Item::Text(_) => "tstr".to_string(),
+            Item::Array(_) => "array".to_string(),
+            Item::Map(_) => "map".to_string(),
+            Item::Tag(tag, _) => format!("tag {tag}"),
+            Item::Simple(20) => "false".to_string(),
+            Item::Simple(21) => "true".to_string(),
+            Item::Simple(22) => "null".to_string(),
+            Item::Simple(23) => "undefined".to_string(),
+            Item::Simple(value) => format!("simple value {value}"),
+            Item::Float(..) => "float".to_string(),
+        }
+    }
+}
+
+/// Decode a single CBOR data item, which must use all the bytes.
+pub(crate) fn decode(bytes: &[u8]) -> anyhow::Result<Item> {
+    let mut decoder = Decoder::new(bytes);
+    let item = decode_item(&mut decoder, 0)?;
+    ensure!(
+        decoder.position() == bytes.len(),
+        "{} trailing bytes after the CBOR data item",
+        bytes.len() - decoder.position()
+    );
+    Ok(item)
+}
+
+/// Decode a CBOR sequence, of zero or more data items.
+pub(crate) fn decode_sequence(bytes: &[u8]) -> anyhow::Result<Vec<Item>> {
+    let mut decoder = Decoder::new(bytes);
+    let mut items = Vec::new();
+    while decoder.position() < bytes.len() {
+        items.push(decode_item(&mut decoder, 0)?);
+    }
+    Ok(items)
+}
+
+/// Decode the next data item.
+fn decode_item(d: &mut Decoder<'_>, nesting: usize) -> anyhow::Result<Item> {
+    ensure!(
+        nesting < MAX_NESTING,
+        "CBOR nesting is deeper than {MAX_NESTING}"
+    );
+
+    let item = match d.datatype()? {
+        Type::Bool => Item::Simple(if d.bool()? { 21 } else { 20 }),
+        Type::Null => {
+            d.null()?;
+            Item::Simple(22)
+        },
+        Type::Undefined => {
+            d.undefined()?;
+            Item::Simple(23)
+        },
+        Type::Simple => Item::Simple(d.simple()?),
+        Type::U8
+        | Type::U16
+        | Type::U32
+        | Type::U64
+        | Type::I8
+        | Type::I16
+        | Type::I32
+        | Type::I64
+        | Type::Int => Item::Int(i128::from(d.int()?)),
+        Type::F16 => Item::Float(f64::from(d.f16()?), 25),
+        Type::F32 => Item::Float(f64::from(d.f32()?), 26),
+        Type::F64 => Item::Float(d.f64()?, 27),
+        Type::Bytes | Type::BytesIndef => {
+            let mut bytes = Vec::new();
+            for chunk in d.bytes_iter()? {
+                bytes.extend_from_slice(chunk?);
+            }
+            Item::Bytes(bytes)
+        },
+        Type::String | Type::StringIndef => {
+            let mut text = String::new();
+            for chunk in d.str_iter()? {
+                text.push_str(chunk?);
+            }
+            Item::Text(text)
+        },
+        Type::Array | Type::ArrayIndef => {
+            let mut items = Vec::new();
+            match d.array()? {
+                Some(len) => {
+                    for _ in 0..len {
+                        items.push(decode_item(d, nesting + 1)?);
+                    }
+                },
+                None => {
+                    while d.datatype()? != Type::Break {
+                        items.push(decode_item(d, nesting + 1)?);
+                    }
+                    d.set_position(d.position() + 1);
+                },
+            }
+            Item::Array(items)
+        },
+        Type::Map | Type::MapIndef => {
+            let mut pairs = Vec::new();
+            match d.map()? {
+                Some(len) => {
+                    for _ in 0..len {
+                        let key = decode_item(d, nesting + 1)?;
+                        pairs.push((key, decode_item(d, nesting + 1)?));
+                    }
+                },
+                None => {
+                    while d.datatype()? != Type::Break {
+                        let key = decode_item(d, nesting + 1)?;
+                        pairs.push((key, decode_item(d, nesting + 1)?));
+                    }
+                    d.set_position(d.position() + 1);
+                },
+            }
+            Item::Map(pairs)
+        },
+        Type::Tag => {
+            let tag = u64::from(d.tag()?);
+            Item::Tag(tag, Box::new(decode_item(d, nesting + 1)?))
+        },
+        Type::Break => bail!("Unexpected break at position {}", d.position()),
+        Type::Unknown(byte) => {
+            bail!(
+                "Unknown CBOR initial byte {byte:#04x} at position {}",
+                d.position()
+            )
+        },
+    };
+    Ok(item)
+}
@@ -0,0 +1,309 @@
+//! Checking of range and control operators.
+
+// cspell: words cborseq
+
+use std::cmp::Ordering;
+
+use super::{
+    cbor::{self, Item},
+    describe_type2, value_matches, Check, Path, Validator,
+};
+use crate::ast::{Operator, Type2, Value};
+
+/// A numeric bound of a range or comparison.
+#[derive(Debug, Clone, Copy)]
+enum Number {
+    /// An integer.
+    Int(i128),
+    /// A floating point number.
+    Float(f64),
+}
+
+impl Number {
+    /// Get the number of a data item, if it is numeric.
+    fn of(item: &Item) -> Option<Self> {
+        match item {
+            Item::Int(value) => Some(Self::Int(*value)),
+            Item::Float(value, _) => Some(Self::Float(*value)),
+            _ => None,
+        }
+    }
+
+    /// Compare two numbers, if they are of the same kind.
+    fn compare(self, other: Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Int(a), Self::Int(b)) => Some(a.cmp(&b)),
+            (Self::Float(a), Self::Float(b)) => a.partial_cmp(&b),
+            _ => None,
+        }
+    }
+}
+
+impl Validator<'_> {
+    /// Check a data item against a type with a range or control operator.
+    pub(super) fn check_operator(
+        &self, lhs: &Type2, operator: &Operator, rhs: &Type2, item: &Item, path: &Path,
+        depth: usize,
+    ) -> Check {
+        let name = match operator {
+            Operator::Range { inclusive } => {
+                return self.check_range(lhs, *inclusive, rhs, item, path);
+            },
+            Operator::Control(name) => name.name.as_str(),
+        };
+
+        match name {
+            "within" | "and" => {
+                self.check_type2(lhs, item, path, depth)?;
+                self.check_type2(rhs, item, path, depth)
+            },
+            "default" => self.check_type2(lhs, item, path, depth),
+            "size" => {
+                self.check_type2(lhs, item, path, depth)?;
+                self.check_size(rhs, item, path)
+            },
+            "lt" | "le" | "gt" | "ge" | "eq" | "ne" => {
+                self.check_type2(lhs, item, path, depth)?;
+                self.check_comparison(name, rhs, item, path)
+            },
+            "bits" => {
+                self.check_type2(lhs, item, path, depth)?;
+                self.check_bits(rhs, item, path, depth)
+            },
+            "cbor" | "cborseq" => {
+                self.check_type2(lhs, item, path, depth)?;
+                let Item::Bytes(bytes) = item else {
+                    return Err(vec![path.mismatch("bstr", item)]);
+                };
+                let embedded = if name == "cbor" {
+                    cbor::decode(bytes)
+                } else {
+                    cbor::decode_sequence(bytes).map(Item::Array)
+                };
+                match embedded {
+                    Ok(embedded) => self.check_type2(rhs, &embedded, &path.inner(), depth),
+                    Err(e) => Err(vec![path.violation(format!("Invalid embedded CBOR: {e}"))]),
+                }
+            },
+            _ => {
+                Err(vec![path.violation(format!(
+                    "control operator `.{name}` is not supported"
+                ))])
+            },
+        }
+    }
+
+    /// Check a number is within a range.
+    fn check_range(
+        &self, min: &Type2, inclusive: bool, max: &Type2, item: &Item, path: &Path,
+    ) -> Check {
+        let (Some(min_bound), Some(max_bound)) = (self.number(min), self.number(max)) else {
+            return Err(vec![path.violation("range bounds must be numbers")]);
+        };
+        let op = if inclusive { ".." } else { "..." };
+        let expected = format!("{}{op}{}", describe_type2(min), describe_type2(max));
+
+        let Some(number) = Number::of(item) else {
+            return Err(vec![path.mismatch(&expected, item)]);
+        };
+        let (Some(lower), Some(upper)) = (number.compare(min_bound), number.compare(max_bound))
+        else {
+            return Err(vec![path.mismatch(&expected, item)]);
+        };
+        let in_range = lower != Ordering::Less
+            && (upper == Ordering::Less || (inclusive && upper == Ordering::Equal));
+        if in_range {
+            Ok(())
+        } else {
+            Err(vec![path.violation(format!(
+                "{} is not in {expected}",
+                describe_item(item)
+            ))])
+        }
+    }
+
+    /// Check the size of a string, in bytes, or of an unsigned integer, in bytes.
+    fn check_size(&self, rhs: &Type2, item: &Item, path: &Path) -> Check {
+        let size = match item {
+            Item::Bytes(bytes) => bytes.len(),
+            Item::Text(text) => text.len(),
+            Item::Int(value) if *value >= 0 => {
+                let bits = 128 - value.leading_zeros();
+                usize::try_from(bits.div_ceil(8)).unwrap_or(usize::MAX)
+            },
+            _ => return Err(vec![path.mismatch("bstr, tstr or uint", item)]),
+        };
+        let size = i128::try_from(size).unwrap_or(i128::MAX);
+
+        let matches = match self.resolve(rhs) {
+            Type2::Value(Value::Int(expected)) => size == *expected,
+            Type2::Value(_) => false,
+            type2 => {
+                let size_item = Item::Int(size);
+                match self.range_of(type2) {
+                    Some((min, inclusive, max)) => {
+                        self.check_range(min, inclusive, max, &size_item, path)
+                            .is_ok()
+                    },
+                    None => {
+                        return Err(vec![path.violation(format!(
+                            "`.size {}` is not a size or range",
+                            describe_type2(rhs)
+                        ))]);
+                    },
+                }
+            },
+        };
+        if matches {
+            Ok(())
+        } else {
+            Err(vec![path.violation(format!(
+                "size {size} is not {}",
+                describe_type2(rhs)
+            ))])
+        }
+    }
+
+    /// Check a comparison control operator.
+    fn check_comparison(&self, name: &str, rhs: &Type2, item: &Item, path: &Path) -> Check {
+        let rhs_value = match self.resolve(rhs) {
+            Type2::Value(value) => value,
+            _ => {
+                return Err(vec![path.violation(format!(
+                    "`.{name} {}` is not a comparison with a value",
+                    describe_type2(rhs)
+                ))]);
+            },
+        };
+        let ordering = self
+            .number(rhs)
+            .zip(Number::of(item))
+            .and_then(|(rhs, number)| number.compare(rhs));
+
+        let matches = match (name, ordering) {
+            ("eq", _) => value_matches(rhs_value, item),
+            ("ne", _) => !value_matches(rhs_value, item),
+            ("lt", Some(ordering)) => ordering == Ordering::Less,
+            ("le", Some(ordering)) => ordering != Ordering::Greater,
+            ("gt", Some(ordering)) => ordering == Ordering::Greater,
+            ("ge", Some(ordering)) => ordering != Ordering::Less,
+            _ => false,
+        };
+        if matches {
+            Ok(())
+        } else {
+            Err(vec![path.violation(format!(
+                "{} is not .{name} {}",
+                describe_item(item),
+                describe_type2(rhs)
+            ))])
+        }
+    }
+
+    /// Check every set bit of an unsigned integer or byte string is an allowed bit.
+    fn check_bits(&self, rhs: &Type2, item: &Item, path: &Path, depth: usize) -> Check {
+        let bits: Vec<u128> = match item {
+            Item::Int(value) if *value >= 0 => {
+                (0..128).filter(|bit| (value >> bit) & 1 == 1).collect()
+            },
+            Item::Bytes(bytes) => {
+                (0_u128..)
+                    .step_by(8)
+                    .zip(bytes)
+                    .flat_map(|(offset, byte)| {
+                        (0..8)
+                            .filter(move |bit| (byte >> bit) & 1 == 1)
+                            .map(move |bit| offset + u128::from(bit))
+                    })
+                    .collect()
+            },
+            _ => return Err(vec![path.mismatch("uint or bstr", item)]),
+        };
+
+        for bit in bits {
+            let bit_item = Item::Int(i128::try_from(bit).unwrap_or(i128::MAX));
+            if self.check_type2(rhs, &bit_item, path, depth).is_err() {
+                return Err(vec![path.violation(format!(
+                    "bit {bit} is not one of {}",
+                    describe_type2(rhs)
+                ))]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve a type through references to single choice type rules.
+    fn resolve<'t>(&'t self, type2: &'t Type2) -> &'t Type2 {
+        let mut resolved = type2;
+        // Bound the number of references, in case the rules are recursive.
+        for _ in 0..super::MAX_RULE_DEPTH {
+            match resolved {
+                Type2::Typename { name, generic_args } if generic_args.is_empty() => {
+                    match self.types.get(name.name.as_str()).map(Vec::as_slice) {
+                        Some([type1]) if type1.operator.is_none() => resolved = &type1.type2,
+                        _ => break,
+                    }
+                },
+                Type2::Parenthesized(ty) => {
+                    match ty.choices.as_slice() {
+                        [type1] if type1.operator.is_none() => resolved = &type1.type2,
+                        _ => break,
+                    }
+                },
+                _ => break,
+            }
+        }
+        resolved
+    }
+
+    /// Get the number of a literal numeric value, or a reference to one.
+    fn number(&self, type2: &Type2) -> Option<Number> {
+        match self.resolve(type2) {
+            Type2::Value(Value::Int(value)) => Some(Number::Int(*value)),
+            Type2::Value(Value::Float(value)) => Some(Number::Float(*value)),
+            _ => None,
+        }
+    }
+
+    /// Get the bounds of a range, or a reference to one.
+    fn range_of<'t>(&'t self, type2: &'t Type2) -> Option<(&'t Type2, bool, &'t Type2)> {
+        let mut resolved = type2;
+        for _ in 0..super::MAX_RULE_DEPTH {
+            let choices = match resolved {
+                Type2::Typename { name, generic_args } if generic_args.is_empty() => {
+                    self.types.get(name.name.as_str())?.as_slice()
+                },
+                Type2::Parenthesized(ty) => {
+                    match ty.choices.as_slice() {
+                        [type1] => {
+                            if let Some((Operator::Range { inclusive }, max)) = &type1.operator {
+                                return Some((&type1.type2, *inclusive, max));
+                            }
+                            resolved = &type1.type2;
+                            continue;
+                        },
+                        _ => return None,
+                    }
+                },
+                _ => return None,
+            };
+            let [type1] = choices else {
+                return None;
+            };
+            if let Some((Operator::Range { inclusive }, max)) = &type1.operator {
+                return Some((&type1.type2, *inclusive, max));
+            }
+            resolved = &type1.type2;
+        }
+        None
+    }
+}
+
+/// Describe the value of a data item, for violations.
+fn describe_item(item: &Item) -> String {
+    match item {
+        Item::Int(value) => value.to_string(),
+        Item::Float(value, _) => value.to_string(),
+        item => item.describe(),
+    }
+}
@@ -0,0 +1,414 @@
+//! Matching of the items of arrays and the entries of maps against groups.
+
+use std::collections::BTreeSet;
+
+use super::{cbor::Item, Check, Path, Validator, Violation, MAX_RULE_DEPTH};
+use crate::ast::{Group, GroupEntry, GroupEntryKind, MemberKey, Occurrence, Type, Type2};
+
+/// The array item which matched the furthest before failing, with its violations.
+#[derive(Default)]
+struct Furthest {
+    /// Index of the array item.
+    index: usize,
+    /// Why the array item did not match.
+    violations: Vec<Violation>,
+}
+
+impl Furthest {
+    /// Record a failed array item, if it is at least as far as the furthest so far.
+    fn record(&mut self, index: usize, violations: Vec<Violation>) {
+        if self.violations.is_empty() || index >= self.index {
+            self.index = index;
+            self.violations = violations;
+        }
+    }
+}
+
+/// Get the minimum and maximum number of occurrences of a group entry.
+fn bounds(occurrence: Option<&Occurrence>) -> (u64, u64) {
+    match occurrence {
+        None => (1, 1),
+        Some(Occurrence::Optional) => (0, 1),
+        Some(Occurrence::OneOrMore) => (1, u64::MAX),
+        Some(Occurrence::Range { min, max }) => (min.unwrap_or(0), max.unwrap_or(u64::MAX)),
+    }
+}
+
+impl Validator<'_> {
+    /// Check the items of an array against a group.
+    pub(super) fn check_array(
+        &self, group: &Group, items: &[Item], path: &Path, depth: usize,
+    ) -> Check {
+        let mut furthest = Furthest::default();
+        let ends = self.group_ends(group, items, 0, path, depth, &mut furthest);
+        if ends.contains(&items.len()) {
+            return Ok(());
+        }
+
+        let matched = ends.last().copied().unwrap_or_default();
+        if !furthest.violations.is_empty() && furthest.index >= matched {
+            Err(furthest.violations)
+        } else if matched < items.len() {
+            Err(vec![path.index(matched).violation("unexpected array item")])
+        } else {
+            Err(vec![path.violation(format!(
+                "array has {} items, more are required",
+                items.len()
+            ))])
+        }
+    }
+
+    /// Get every array index a group can end at, when matched from `start`.
+    fn group_ends(
+        &self, group: &Group, items: &[Item], start: usize, path: &Path, depth: usize,
+        furthest: &mut Furthest,
+    ) -> BTreeSet<usize> {
+        let mut ends = BTreeSet::new();
+        if depth >= MAX_RULE_DEPTH {
+            furthest.record(start, vec![
+                path.violation(format!("group is nested deeper than {MAX_RULE_DEPTH}"))
+            ]);
+            return ends;
+        }
+
+        for choice in &group.choices {
+            let mut positions = BTreeSet::from([start]);
+            for entry in &choice.entries {
+                let mut next = BTreeSet::new();
+                for position in positions {
+                    next.extend(self.entry_ends(entry, items, position, path, depth, furthest));
+                }
+                positions = next;
+            }
+            ends.extend(positions);
+        }
+        ends
+    }
+
+    /// Get every array index a group entry can end at, with its occurrences.
+    fn entry_ends(
+        &self, entry: &GroupEntry, items: &[Item], start: usize, path: &Path, depth: usize,
+        furthest: &mut Furthest,
+    ) -> BTreeSet<usize> {
+        let (min, max) = bounds(entry.occurrence.as_ref());
+
+        let mut ends = BTreeSet::new();
+        if min == 0 {
+            ends.insert(start);
+        }
+        let mut positions = BTreeSet::from([start]);
+        let mut count = 0_u64;
+        // Every occurrence consumes at least one item, or adds no new position.
+        while count < max && !positions.is_empty() && count <= items.len() as u64 {
+            let mut next = BTreeSet::new();
+            for position in &positions {
+                next.extend(self.entry_once_ends(entry, items, *position, path, depth, furthest));
+            }
+            count += 1;
+            if count >= min {
+                ends.extend(next.iter().copied());
+            }
+            positions = next;
+        }
+        ends
+    }
+
+    /// Get every array index a single occurrence of a group entry can end at.
+    fn entry_once_ends(
+        &self, entry: &GroupEntry, items: &[Item], start: usize, path: &Path, depth: usize,
+        furthest: &mut Furthest,
+    ) -> BTreeSet<usize> {
+        match &entry.kind {
+            GroupEntryKind::Value { value, .. } => {
+                if let Some(name) = self.group_reference(value) {
+                    return self.named_group_ends(name, items, start, path, depth, furthest);
+                }
+                if let Some(group) = self.unwrapped_group(value, true) {
+                    return self.group_ends(group, items, start, path, depth + 1, furthest);
+                }
+
+                let Some(item) = items.get(start) else {
+                    return BTreeSet::new();
+                };
+                match self.check_type(value, item, &path.index(start), depth) {
+                    Ok(()) => BTreeSet::from([start + 1]),
+                    Err(violations) => {
+                        furthest.record(start, violations);
+                        BTreeSet::new()
+                    },
+                }
+            },
+            GroupEntryKind::Groupname { name, .. } => {
+                self.named_group_ends(&name.name, items, start, path, depth, furthest)
+            },
+            GroupEntryKind::InlineGroup(group) => {
+                self.group_ends(group, items, start, path, depth + 1, furthest)
+            },
+        }
+    }
+
+    /// Get every array index a named group can end at.
+    fn named_group_ends(
+        &self, name: &str, items: &[Item], start: usize, path: &Path, depth: usize,
+        furthest: &mut Furthest,
+    ) -> BTreeSet<usize> {
+        let Some(entries) = self.groups.get(name) else {
+            furthest.record(start, vec![
+                path.violation(format!("rule `{name}` is not a defined group"))
+            ]);
+            return BTreeSet::new();
+        };
+        if self.generic.contains(name) {
+            furthest.record(start, vec![
+                path.violation(format!("generic rule `{name}` is not supported"))
+            ]);
+            return BTreeSet::new();
+        }
+
+        let mut ends = BTreeSet::new();
+        for entry in entries {
+            ends.extend(self.entry_ends(entry, items, start, path, depth + 1, furthest));
+        }
+        ends
+    }
+
+    /// Get the group of an unwrapped array or map, if a type is only `~name`.
+    fn unwrapped_group(&self, ty: &Type, array: bool) -> Option<&Group> {
+        let [type1] = ty.choices.as_slice() else {
+            return None;
+        };
+        let Type2::Unwrap { name, .. } = &type1.type2 else {
+            return None;
+        };
+        let [unwrapped] = self.types.get(name.name.as_str())?.as_slice() else {
+            return None;
+        };
+        match &unwrapped.type2 {
+            Type2::Array(group) if array => Some(group),
+            Type2::Map(group) if !array => Some(group),
+            _ => None,
+        }
+    }
+
+    /// Check the entries of a map against a group.
+    pub(super) fn check_map(
+        &self, group: &Group, pairs: &[(Item, Item)], path: &Path, depth: usize,
+    ) -> Check {
+        let mut used = vec![false; pairs.len()];
+        let mut violations = Vec::new();
+        self.map_group(group, pairs, &mut used, path, depth, &mut violations);
+
+        for ((key, _), used) in pairs.iter().zip(&used) {
+            if !used {
+                violations.push(path.key(key).violation("unexpected map key"));
+            }
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Match the entries of a map against a group, marking the matched entries as used.
+    ///
+    /// The first group choice without violations is used, otherwise the one with the
+    /// fewest violations.
+    fn map_group(
+        &self, group: &Group, pairs: &[(Item, Item)], used: &mut Vec<bool>, path: &Path,
+        depth: usize, violations: &mut Vec<Violation>,
+    ) {
+        if depth >= MAX_RULE_DEPTH {
+            violations
+                .push(path.violation(format!("group is nested deeper than {MAX_RULE_DEPTH}")));
+            return;
+        }
+
+        let mut best: Option<(Vec<bool>, Vec<Violation>)> = None;
+        for choice in &group.choices {
+            let mut choice_used = used.clone();
+            let mut choice_violations = Vec::new();
+            for entry in &choice.entries {
+                self.map_entry(
+                    entry,
+                    pairs,
+                    &mut choice_used,
+                    path,
+                    depth,
+                    &mut choice_violations,
+                );
+            }
+            if choice_violations.is_empty() {
+                *used = choice_used;
+                return;
+            }
+            if best
+                .as_ref()
+                .is_none_or(|(_, best)| choice_violations.len() < best.len())
+            {
+                best = Some((choice_used, choice_violations));
+            }
+        }
+
+        if let Some((best_used, best_violations)) = best {
+            *used = best_used;
+            violations.extend(best_violations);
+        }
+    }
+
+    /// Match the entries of a map against a group entry.
+    fn map_entry(
+        &self, entry: &GroupEntry, pairs: &[(Item, Item)], used: &mut Vec<bool>, path: &Path,
+        depth: usize, violations: &mut Vec<Violation>,
+    ) {
+        let (min, max) = bounds(entry.occurrence.as_ref());
+
+        let (key, value) = match &entry.kind {
+            GroupEntryKind::Value { key, value } => {
+                if let Some(name) = self.group_reference(value) {
+                    self.map_named_group(name, min, pairs, used, path, depth, violations);
+                    return;
+                }
+                if let Some(group) = self.unwrapped_group(value, false) {
+                    self.map_optional_group(group, min, pairs, used, path, depth, violations);
+                    return;
+                }
+                (key, value)
+            },
+            GroupEntryKind::Groupname { name, .. } => {
+                self.map_named_group(&name.name, min, pairs, used, path, depth, violations);
+                return;
+            },
+            GroupEntryKind::InlineGroup(group) => {
+                self.map_optional_group(group, min, pairs, used, path, depth, violations);
+                return;
+            },
+        };
+        let Some(key) = key else {
+            violations.push(path.violation("map group entry has no member key"));
+            return;
+        };
+
+        // Bare word and value keys always have a cut.
+        let cut = match key {
+            MemberKey::Type { cut, .. } => *cut,
+            MemberKey::Bareword(_) | MemberKey::Value(_) => true,
+        };
+
+        let mut count = 0_u64;
+        for ((item_key, item_value), used) in pairs.iter().zip(used.iter_mut()) {
+            if count >= max {
+                break;
+            }
+            if *used || !self.key_matches(key, item_key, path, depth) {
+                continue;
+            }
+            match self.check_type(value, item_value, &path.key(item_key), depth) {
+                Ok(()) => {
+                    *used = true;
+                    count += 1;
+                },
+                Err(value_violations) if cut => {
+                    *used = true;
+                    count += 1;
+                    violations.extend(value_violations);
+                },
+                Err(_) => {},
+            }
+        }
+
+        if count < min {
+            violations.push(path.violation(format!("missing map entry {}", describe_key(key))));
+        }
+    }
+
+    /// Match the entries of a map against a named group.
+    #[allow(clippy::too_many_arguments)]
+    fn map_named_group(
+        &self, name: &str, min: u64, pairs: &[(Item, Item)], used: &mut Vec<bool>, path: &Path,
+        depth: usize, violations: &mut Vec<Violation>,
+    ) {
+        let Some(entries) = self.groups.get(name) else {
+            violations.push(path.violation(format!("rule `{name}` is not a defined group")));
+            return;
+        };
+        if self.generic.contains(name) {
+            violations.push(path.violation(format!("generic rule `{name}` is not supported")));
+            return;
+        }
+
+        let mut group_used = used.clone();
+        let mut group_violations = Vec::new();
+        let mut matched = false;
+        for entry in entries {
+            let mut entry_used = used.clone();
+            let mut entry_violations = Vec::new();
+            self.map_entry(
+                entry,
+                pairs,
+                &mut entry_used,
+                path,
+                depth + 1,
+                &mut entry_violations,
+            );
+            if entry_violations.is_empty() {
+                group_used = entry_used;
+                matched = true;
+                break;
+            }
+            if group_violations.is_empty() {
+                group_used = entry_used;
+                group_violations = entry_violations;
+            }
+        }
+
+        if matched {
+            *used = group_used;
+        } else if min > 0 {
+            *used = group_used;
+            violations.extend(group_violations);
+        }
+    }
+
+    /// Match the entries of a map against an inline or unwrapped group.
+    ///
+    /// An optional group which does not match is skipped.
+    #[allow(clippy::too_many_arguments)]
+    fn map_optional_group(
+        &self, group: &Group, min: u64, pairs: &[(Item, Item)], used: &mut Vec<bool>, path: &Path,
+        depth: usize, violations: &mut Vec<Violation>,
+    ) {
+        let mut group_used = used.clone();
+        let mut group_violations = Vec::new();
+        self.map_group(
+            group,
+            pairs,
+            &mut group_used,
+            path,
+            depth + 1,
+            &mut group_violations,
+        );
+        if group_violations.is_empty() || min > 0 {
+            *used = group_used;
+            violations.extend(group_violations);
+        }
+    }
+
+    /// Check if a map key matches a member key.
+    fn key_matches(&self, key: &MemberKey, item: &Item, path: &Path, depth: usize) -> bool {
+        match key {
+            MemberKey::Type { key, .. } => self.check_type1(key, item, path, depth).is_ok(),
+            MemberKey::Bareword(name) => matches!(item, Item::Text(text) if *text == name.name),
+            MemberKey::Value(value) => super::value_matches(value, item),
+        }
+    }
+}
+
+/// Describe a member key, for violations.
+fn describe_key(key: &MemberKey) -> String {
+    match key {
+        MemberKey::Type { key, .. } => super::describe_type1(key),
+        MemberKey::Bareword(name) => format!("{:?}", name.name),
+        MemberKey::Value(value) => super::describe_value(value),
+    }
+}
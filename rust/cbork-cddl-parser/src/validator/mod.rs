@@ -0,0 +1,554 @@
+//! Validation of CBOR data items against a parsed CDDL specification.
+//!
+//! Supports type and group choices, literal values, ranges, occurrences, map keys with
+//! cuts, tags, major types, sockets and the `.size`, `.bits`, `.cbor`, `.cborseq`,
+//! `.within`, `.and`, `.default` and comparison control operators. Generic rules and
+//! regular expressions are reported as violations, as they are not supported.
+
+// cspell: words cborseq
+
+mod cbor;
+mod control;
+mod group;
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use cbor::Item;
+
+use crate::ast::{
+    ByteString, Cddl, Group, GroupEntry, GroupEntryKind, Operator, Rule, Type, Type1, Type2, Value,
+};
+
+/// The maximum depth of nested rule references while validating a single data item.
+const MAX_RULE_DEPTH: usize = 64;
+
+/// A part of a CBOR data item which does not conform to the CDDL specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Path to the non-conforming data item, `$` being the validated item.
+    ///
+    /// Array items are addressed as `[index]`, map values as `.key` or `[key]`.
+    pub path: String,
+    /// Why the data item does not conform.
+    pub message: String,
+    /// The nesting depth of the data item.
+    depth: usize,
+    /// Whether the violation is only that the data item is of the wrong type.
+    mismatch: bool,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// The path to a data item.
+#[derive(Debug, Clone)]
+struct Path {
+    /// The path, as displayed in violations.
+    text: String,
+    /// The nesting depth of the data item.
+    depth: usize,
+}
+
+impl Path {
+    /// The path of the validated data item.
+    fn root() -> Self {
+        Self {
+            text: "$".to_string(),
+            depth: 0,
+        }
+    }
+
+    /// The path of an array item.
+    fn index(&self, index: usize) -> Self {
+        Self {
+            text: format!("{}[{index}]", self.text),
+            depth: self.depth + 1,
+        }
+    }
+
+    /// The path of a map value.
+    fn key(&self, key: &Item) -> Self {
+        let text = match key {
+            Item::Text(key)
+                if key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') =>
+            {
+                format!("{}.{key}", self.text)
+            },
+            Item::Text(key) => format!("{}[{key:?}]", self.text),
+            Item::Int(key) => format!("{}[{key}]", self.text),
+            key => format!("{}[<{}>]", self.text, key.describe()),
+        };
+        Self {
+            text,
+            depth: self.depth + 1,
+        }
+    }
+
+    /// The path of a tagged or embedded data item.
+    fn inner(&self) -> Self {
+        Self {
+            text: self.text.clone(),
+            depth: self.depth + 1,
+        }
+    }
+
+    /// A violation of the data item at this path.
+    fn violation(&self, message: impl Into<String>) -> Violation {
+        Violation {
+            path: self.text.clone(),
+            message: message.into(),
+            depth: self.depth,
+            mismatch: false,
+        }
+    }
+
+    /// A violation of the data item at this path being of the wrong type.
+    fn mismatch(&self, expected: &str, item: &Item) -> Violation {
+        Violation {
+            mismatch: true,
+            ..self.violation(format!("expected {expected}, found {}", item.describe()))
+        }
+    }
+}
+
+/// The result of checking a data item.
+type Check = Result<(), Vec<Violation>>;
+
+/// Validates CBOR data items against the rules of a parsed CDDL specification.
+pub struct Validator<'a> {
+    /// The type choices of each type rule.
+    types: HashMap<&'a str, Vec<&'a Type1>>,
+    /// The group entry choices of each group rule.
+    groups: HashMap<&'a str, Vec<&'a GroupEntry>>,
+    /// Names of the generic rules.
+    generic: HashSet<&'a str>,
+}
+
+impl<'a> Validator<'a> {
+    /// Create a validator for the rules of a parsed CDDL specification.
+    #[must_use]
+    pub fn new(cddl: &'a Cddl) -> Self {
+        let mut validator = Self {
+            types: HashMap::new(),
+            groups: HashMap::new(),
+            generic: HashSet::new(),
+        };
+        for rule in &cddl.rules {
+            let name = rule.name().name.as_str();
+            match rule {
+                Rule::Type(rule) => {
+                    if !rule.generic_params.is_empty() {
+                        validator.generic.insert(name);
+                    }
+                    validator
+                        .types
+                        .entry(name)
+                        .or_default()
+                        .extend(&rule.value.choices);
+                },
+                Rule::Group(rule) => {
+                    if !rule.generic_params.is_empty() {
+                        validator.generic.insert(name);
+                    }
+                    validator.groups.entry(name).or_default().push(&rule.entry);
+                },
+            }
+        }
+        validator
+    }
+
+    /// Validate an encoded CBOR data item against a type rule.
+    ///
+    /// # Errors
+    ///
+    /// Returns every violation of the rule, including if the data is not a single valid
+    /// CBOR data item or the rule does not exist.
+    pub fn validate(&self, rule: &str, cbor: &[u8]) -> Result<(), Vec<Violation>> {
+        let root = Path::root();
+        let item =
+            cbor::decode(cbor).map_err(|e| vec![root.violation(format!("Invalid CBOR: {e}"))])?;
+        self.check_named(rule, &[], &item, &root, 0)
+    }
+
+    /// Check a data item against a named type.
+    fn check_named(
+        &self, name: &str, generic_args: &[Type1], item: &Item, path: &Path, depth: usize,
+    ) -> Check {
+        if depth >= MAX_RULE_DEPTH {
+            return Err(vec![path.violation(format!(
+                "rule `{name}` is nested deeper than {MAX_RULE_DEPTH}"
+            ))]);
+        }
+        if !generic_args.is_empty() || self.generic.contains(name) {
+            return Err(vec![
+                path.violation(format!("generic rule `{name}` is not supported"))
+            ]);
+        }
+        match self.types.get(name) {
+            Some(choices) => self.check_choices(name, choices, item, path, depth + 1),
+            None if self.groups.contains_key(name) => {
+                Err(vec![
+                    path.violation(format!("`{name}` is a group, not a type"))
+                ])
+            },
+            None => Err(vec![path.violation(format!("rule `{name}` is not defined"))]),
+        }
+    }
+
+    /// Check a data item against a type.
+    fn check_type(&self, ty: &Type, item: &Item, path: &Path, depth: usize) -> Check {
+        let choices: Vec<_> = ty.choices.iter().collect();
+        self.check_choices(&describe_type(ty), &choices, item, path, depth)
+    }
+
+    /// Check a data item matches any of the type choices.
+    ///
+    /// When no choice matches, the violations of the choice which matched the most of
+    /// the data item are returned. If every choice is of the wrong type, a single
+    /// violation is returned describing the `expected` type.
+    fn check_choices(
+        &self, expected: &str, choices: &[&Type1], item: &Item, path: &Path, depth: usize,
+    ) -> Check {
+        let mut best: Option<((usize, bool), Vec<Violation>)> = None;
+        for choice in choices {
+            let Err(violations) = self.check_type1(choice, item, path, depth) else {
+                return Ok(());
+            };
+            let score = violations
+                .iter()
+                .map(|v| (v.depth, !v.mismatch))
+                .max()
+                .unwrap_or_default();
+            if best.as_ref().is_none_or(|(best, _)| score > *best) {
+                best = Some((score, violations));
+            }
+        }
+
+        match best {
+            Some(((depth, structural), violations)) if structural || depth > path.depth => {
+                Err(violations)
+            },
+            _ => Err(vec![path.mismatch(expected, item)]),
+        }
+    }
+
+    /// Check a data item against a type choice.
+    fn check_type1(&self, type1: &Type1, item: &Item, path: &Path, depth: usize) -> Check {
+        match &type1.operator {
+            None => self.check_type2(&type1.type2, item, path, depth),
+            Some((operator, rhs)) => {
+                self.check_operator(&type1.type2, operator, rhs, item, path, depth)
+            },
+        }
+    }
+
+    /// Check a data item against a single type.
+    fn check_type2(&self, type2: &Type2, item: &Item, path: &Path, depth: usize) -> Check {
+        match type2 {
+            Type2::Value(value) => {
+                if value_matches(value, item) {
+                    Ok(())
+                } else {
+                    Err(vec![path.mismatch(&describe_value(value), item)])
+                }
+            },
+            Type2::Typename { name, generic_args } => {
+                self.check_named(&name.name, generic_args, item, path, depth)
+            },
+            Type2::Parenthesized(ty) => self.check_type(ty, item, path, depth),
+            Type2::Map(group) => {
+                match item {
+                    Item::Map(pairs) => self.check_map(group, pairs, path, depth),
+                    _ => Err(vec![path.mismatch("map", item)]),
+                }
+            },
+            Type2::Array(group) => {
+                match item {
+                    Item::Array(items) => self.check_array(group, items, path, depth),
+                    _ => Err(vec![path.mismatch("array", item)]),
+                }
+            },
+            Type2::Unwrap { name, generic_args } => {
+                self.check_unwrapped(&name.name, generic_args, item, path, depth)
+            },
+            Type2::ChoiceFromInlineGroup(group) => {
+                let choices = self.group_choice_types(group, path, depth)?;
+                self.check_choices("a group choice", &choices, item, path, depth)
+            },
+            Type2::ChoiceFromGroup { name, generic_args } => {
+                if !generic_args.is_empty() || self.generic.contains(name.name.as_str()) {
+                    return Err(vec![path.violation(format!(
+                        "generic rule `{}` is not supported",
+                        name.name
+                    ))]);
+                }
+                let choices = self.named_group_choice_types(&name.name, path, depth)?;
+                self.check_choices(&format!("&{}", name.name), &choices, item, path, depth)
+            },
+            Type2::TaggedData { tag, value } => {
+                match item {
+                    Item::Tag(item_tag, inner) if tag.is_none_or(|tag| tag == *item_tag) => {
+                        self.check_type(value, inner, &path.inner(), depth)
+                    },
+                    _ => Err(vec![path.mismatch(&describe_type2(type2), item)]),
+                }
+            },
+            Type2::MajorType { major, info } => {
+                if major_type_matches(*major, *info, item) {
+                    Ok(())
+                } else {
+                    Err(vec![path.mismatch(&describe_type2(type2), item)])
+                }
+            },
+            Type2::Any => Ok(()),
+        }
+    }
+
+    /// Check a data item against the content of an unwrapped tag type, `~name`.
+    ///
+    /// Unwrapped maps and arrays are groups, which are only valid inside another map or
+    /// array.
+    fn check_unwrapped(
+        &self, name: &str, generic_args: &[Type1], item: &Item, path: &Path, depth: usize,
+    ) -> Check {
+        if !generic_args.is_empty() || self.generic.contains(name) {
+            return Err(vec![
+                path.violation(format!("generic rule `{name}` is not supported"))
+            ]);
+        }
+        let Some(choices) = self.types.get(name) else {
+            return Err(vec![
+                path.violation(format!("rule `{name}` is not a defined type"))
+            ]);
+        };
+
+        let mut violations = Vec::new();
+        for choice in choices {
+            match (&choice.type2, &choice.operator) {
+                (Type2::TaggedData { value, .. }, None) => {
+                    match self.check_type(value, item, path, depth + 1) {
+                        Ok(()) => return Ok(()),
+                        Err(e) => violations.extend(e),
+                    }
+                },
+                _ => {
+                    violations
+                        .push(path.violation(format!("`~{name}` can only unwrap a tag as a type")));
+                },
+            }
+        }
+        Err(violations)
+    }
+
+    /// Get the type choices of a named group, for `&name`.
+    fn named_group_choice_types(
+        &self, name: &str, path: &Path, depth: usize,
+    ) -> Result<Vec<&'a Type1>, Vec<Violation>> {
+        if depth >= MAX_RULE_DEPTH {
+            return Err(vec![path.violation(format!(
+                "rule `{name}` is nested deeper than {MAX_RULE_DEPTH}"
+            ))]);
+        }
+        let Some(entries) = self.groups.get(name) else {
+            return Err(vec![
+                path.violation(format!("rule `{name}` is not a defined group"))
+            ]);
+        };
+        let mut choices = Vec::new();
+        for entry in entries {
+            choices.extend(self.entry_choice_types(entry, path, depth + 1)?);
+        }
+        Ok(choices)
+    }
+
+    /// Get the type choices of the entries of a group, for `&( group )`.
+    fn group_choice_types<'g>(
+        &self, group: &'g Group, path: &Path, depth: usize,
+    ) -> Result<Vec<&'g Type1>, Vec<Violation>>
+    where 'a: 'g {
+        let mut choices = Vec::new();
+        for entry in group.choices.iter().flat_map(|choice| &choice.entries) {
+            choices.extend(self.entry_choice_types(entry, path, depth)?);
+        }
+        Ok(choices)
+    }
+
+    /// Get the type choices of a group entry.
+    fn entry_choice_types<'g>(
+        &self, entry: &'g GroupEntry, path: &Path, depth: usize,
+    ) -> Result<Vec<&'g Type1>, Vec<Violation>>
+    where 'a: 'g {
+        match &entry.kind {
+            GroupEntryKind::Value { value, .. } => {
+                match self.group_reference(value) {
+                    Some(name) => self.named_group_choice_types(name, path, depth),
+                    None => Ok(value.choices.iter().collect()),
+                }
+            },
+            GroupEntryKind::Groupname { name, .. } => {
+                self.named_group_choice_types(&name.name, path, depth)
+            },
+            GroupEntryKind::InlineGroup(group) => self.group_choice_types(group, path, depth),
+        }
+    }
+
+    /// Get the name of the group, if a type is only a reference to a group.
+    ///
+    /// The grammar parses a bare group name as a type, so it is only known to be a group
+    /// once the rules are resolved.
+    fn group_reference<'t>(&self, ty: &'t Type) -> Option<&'t str> {
+        match ty.choices.as_slice() {
+            [Type1 {
+                type2: Type2::Typename { name, .. },
+                operator: None,
+                ..
+            }] if !self.types.contains_key(name.name.as_str())
+                && self.groups.contains_key(name.name.as_str()) =>
+            {
+                Some(name.name.as_str())
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Check if a data item is a literal value.
+fn value_matches(value: &Value, item: &Item) -> bool {
+    match (value, item) {
+        (Value::Int(value), Item::Int(item)) => value == item,
+        #[allow(clippy::float_cmp)]
+        (Value::Float(value), Item::Float(item, _)) => value == item,
+        (Value::Text(value), Item::Text(item)) => value == item,
+        (Value::Bytes(ByteString::Hex(value)), Item::Bytes(item)) => value == item,
+        (Value::Bytes(ByteString::Text(value)), Item::Bytes(item)) => value.as_bytes() == item,
+        (Value::Bytes(ByteString::Base64(value)), Item::Bytes(item)) => {
+            decode_base64url(value).is_some_and(|value| value == *item)
+        },
+        _ => false,
+    }
+}
+
+/// Check if a data item is of a major type, with the additional information if given.
+///
+/// The additional information is the value of an integer, the length of a string, array
+/// or map, the tag number, or the simple value or float size.
+fn major_type_matches(major: u8, info: Option<u64>, item: &Item) -> bool {
+    if item.major() != major {
+        return false;
+    }
+    let Some(info) = info else {
+        return true;
+    };
+    let actual = match item {
+        Item::Int(value) if *value >= 0 => u64::try_from(*value).ok(),
+        Item::Int(value) => u64::try_from(-1 - *value).ok(),
+        Item::Bytes(bytes) => u64::try_from(bytes.len()).ok(),
+        Item::Text(text) => u64::try_from(text.len()).ok(),
+        Item::Array(items) => u64::try_from(items.len()).ok(),
+        Item::Map(pairs) => u64::try_from(pairs.len()).ok(),
+        Item::Tag(tag, _) => Some(*tag),
+        Item::Simple(value) | Item::Float(_, value) => Some(u64::from(*value)),
+    };
+    actual == Some(info)
+}
+
+/// Decode a base64url byte string, with or without padding.
+fn decode_base64url(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut buffer = 0_u32;
+    let mut bits = 0;
+    for c in text.trim_end_matches(['=', '~']).chars() {
+        let value = match c {
+            'A'..='Z' => u32::from(c) - u32::from('A'),
+            'a'..='z' => u32::from(c) - u32::from('a') + 26,
+            '0'..='9' => u32::from(c) - u32::from('0') + 52,
+            '-' | '+' => 62,
+            '_' | '/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push(u8::try_from((buffer >> bits) & 0xFF).ok()?);
+        }
+    }
+    Some(bytes)
+}
+
+/// Describe a type, for violations.
+fn describe_type(ty: &Type) -> String {
+    ty.choices
+        .iter()
+        .map(describe_type1)
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+/// Describe a type choice, for violations.
+fn describe_type1(type1: &Type1) -> String {
+    match &type1.operator {
+        None => describe_type2(&type1.type2),
+        Some((Operator::Range { inclusive }, rhs)) => {
+            let op = if *inclusive { ".." } else { "..." };
+            format!(
+                "{}{op}{}",
+                describe_type2(&type1.type2),
+                describe_type2(rhs)
+            )
+        },
+        Some((Operator::Control(name), rhs)) => {
+            format!(
+                "{} .{} {}",
+                describe_type2(&type1.type2),
+                name.name,
+                describe_type2(rhs)
+            )
+        },
+    }
+}
+
+/// Describe a single type, for violations.
+fn describe_type2(type2: &Type2) -> String {
+    match type2 {
+        Type2::Value(value) => describe_value(value),
+        Type2::Typename { name, .. } => name.name.clone(),
+        Type2::Parenthesized(ty) => format!("({})", describe_type(ty)),
+        Type2::Map(_) => "map".to_string(),
+        Type2::Array(_) => "array".to_string(),
+        Type2::Unwrap { name, .. } => format!("~{}", name.name),
+        Type2::ChoiceFromInlineGroup(_) => "a group choice".to_string(),
+        Type2::ChoiceFromGroup { name, .. } => format!("&{}", name.name),
+        Type2::TaggedData { tag: Some(tag), .. } => format!("tag {tag}"),
+        Type2::TaggedData { tag: None, .. } => "a tag".to_string(),
+        Type2::MajorType {
+            major,
+            info: Some(info),
+        } => format!("#{major}.{info}"),
+        Type2::MajorType { major, info: None } => format!("#{major}"),
+        Type2::Any => "any".to_string(),
+    }
+}
+
+/// Describe a literal value, for violations.
+fn describe_value(value: &Value) -> String {
+    match value {
+        Value::Int(value) => value.to_string(),
+        Value::Float(value) => value.to_string(),
+        Value::Text(value) => format!("{value:?}"),
+        Value::Bytes(ByteString::Hex(value)) => {
+            let hex: String = value.iter().map(|byte| format!("{byte:02x}")).collect();
+            format!("h'{hex}'")
+        },
+        Value::Bytes(ByteString::Base64(value)) => format!("b64'{value}'"),
+        Value::Bytes(ByteString::Text(value)) => format!("'{value}'"),
+    }
+}
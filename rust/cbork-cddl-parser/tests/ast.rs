@@ -0,0 +1,113 @@
+//! Typed AST Tests
+// cspell: words tstr bstr
+
+use cbork_cddl_parser::{
+    ast::{
+        visitor::walk_type2, ByteString, GroupEntryKind, MemberKey, Occurrence, Operator, Rule,
+        Type2, Value, Visitor,
+    },
+    parse_cddl, Extension,
+};
+
+/// Test the AST of type and group rules.
+#[test]
+fn check_ast_rules() {
+    let mut input = [
+        "header = { ? \"alg\" ^ => int, kid: bstr, * tstr => any }",
+        "small = 0..255 / h'01 02'",
+        "$$extension //= (1*3 uri: #6.32(tstr))",
+    ]
+    .join("\n");
+    let cddl = parse_cddl(&mut input, &Extension::CDDL).unwrap();
+
+    let Some(Rule::Type(header)) = cddl.rules_named("header").next() else {
+        panic!("`header` is not a type rule");
+    };
+    assert_eq!(header.span.line, 1);
+    let [choice] = header.value.choices.as_slice() else {
+        panic!("`header` has more than one choice");
+    };
+    let Type2::Map(map) = &choice.type2 else {
+        panic!("`header` is not a map");
+    };
+    let entries = &map.choices.first().unwrap().entries;
+    assert_eq!(entries.len(), 3);
+
+    let first = entries.first().unwrap();
+    assert_eq!(first.occurrence, Some(Occurrence::Optional));
+    let GroupEntryKind::Value {
+        key: Some(MemberKey::Type { key, cut }),
+        ..
+    } = &first.kind
+    else {
+        panic!("first entry has no type key");
+    };
+    assert!(cut);
+    assert_eq!(key.type2, Type2::Value(Value::Text("alg".to_string())));
+
+    let GroupEntryKind::Value {
+        key: Some(MemberKey::Bareword(kid)),
+        ..
+    } = &entries.get(1).unwrap().kind
+    else {
+        panic!("second entry has no bareword key");
+    };
+    assert_eq!(kid.name, "kid");
+    assert_eq!(
+        entries.get(2).unwrap().occurrence,
+        Some(Occurrence::Range {
+            min: None,
+            max: None
+        })
+    );
+
+    let Some(Rule::Type(small)) = cddl.rules_named("small").next() else {
+        panic!("`small` is not a type rule");
+    };
+    let [range, bytes] = small.value.choices.as_slice() else {
+        panic!("`small` does not have two choices");
+    };
+    assert_eq!(range.type2, Type2::Value(Value::Int(0)));
+    assert_eq!(
+        range.operator,
+        Some((
+            Operator::Range { inclusive: true },
+            Type2::Value(Value::Int(255))
+        ))
+    );
+    assert_eq!(
+        bytes.type2,
+        Type2::Value(Value::Bytes(ByteString::Hex(vec![1, 2])))
+    );
+
+    let Some(Rule::Group(extension)) = cddl.rules_named("$$extension").next() else {
+        panic!("`$$extension` is not a group rule");
+    };
+    assert!(extension.is_choice_extension);
+}
+
+/// Collects the names of every referenced type.
+#[derive(Default)]
+struct TypenameCollector(Vec<String>);
+
+impl Visitor for TypenameCollector {
+    fn visit_type2(&mut self, type2: &Type2) {
+        if let Type2::Typename { name, .. } = type2 {
+            self.0.push(name.name.clone());
+        }
+        walk_type2(self, type2);
+    }
+}
+
+/// Test visiting the AST.
+#[test]
+fn check_ast_visitor() {
+    let mut input =
+        "point = [x: coordinate, y: coordinate, ? label: tstr]\ncoordinate = float".to_string();
+    let cddl = parse_cddl(&mut input, &Extension::CDDL).unwrap();
+
+    let point = cddl.rules_named("point").next().unwrap();
+    let mut collector = TypenameCollector::default();
+    collector.visit_rule(point);
+    assert_eq!(collector.0, vec!["coordinate", "coordinate", "tstr"]);
+}
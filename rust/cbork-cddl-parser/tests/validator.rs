@@ -0,0 +1,121 @@
+//! CBOR Validator Tests
+// cspell: words tstr bstr
+
+use cbork_cddl_parser::{ast::Cddl, parse_cddl, validator::Validator, Extension};
+
+/// The CDDL specification the tests validate against.
+fn cddl() -> Cddl {
+    let mut input = [
+        "document = { id: bstr .size 16, ? name: tstr, tags: [* tag], 1 => signatures }",
+        "tag = tstr .size (1..8)",
+        "signatures = [+ #6.32(bstr)]",
+        "flags = uint .bits (0 / 2)",
+        "embedded = bstr .cbor [uint, uint]",
+        "small = 0..10",
+    ]
+    .join("\n");
+    parse_cddl(&mut input, &Extension::CDDL).unwrap()
+}
+
+/// Encode a `document` map, with the given `id` and `tags`.
+fn document(id: &[u8], tags: &[&str]) -> Vec<u8> {
+    let mut cbor = vec![0xA3];
+    cbor.extend_from_slice(b"\x62id");
+    cbor.push(0x40 + u8::try_from(id.len()).unwrap());
+    cbor.extend_from_slice(id);
+    cbor.extend_from_slice(b"\x64tags");
+    cbor.push(0x80 + u8::try_from(tags.len()).unwrap());
+    for tag in tags {
+        cbor.push(0x60 + u8::try_from(tag.len()).unwrap());
+        cbor.extend_from_slice(tag.as_bytes());
+    }
+    // 1 => [32(h'00')]
+    cbor.extend_from_slice(&[0x01, 0x81, 0xD8, 0x20, 0x41, 0x00]);
+    cbor
+}
+
+/// Get the displayed violations of validating CBOR against a rule.
+fn violations(validator: &Validator, rule: &str, cbor: &[u8]) -> Vec<String> {
+    match validator.validate(rule, cbor) {
+        Ok(()) => Vec::new(),
+        Err(violations) => violations.iter().map(ToString::to_string).collect(),
+    }
+}
+
+/// Test validating maps, with their keys and values.
+#[test]
+fn check_validator_map() {
+    let cddl = cddl();
+    let validator = Validator::new(&cddl);
+
+    assert!(validator
+        .validate("document", &document(&[0; 16], &["a", "b"]))
+        .is_ok());
+
+    assert_eq!(
+        violations(&validator, "document", &document(&[0; 15], &[])),
+        vec!["$.id: size 15 is not 16"]
+    );
+    assert_eq!(
+        violations(&validator, "document", &document(&[0; 16], &["", "b"])),
+        vec!["$.tags[0]: size 0 is not (1..8)"]
+    );
+
+    // { "id": 1, "tags": [], 1: [32(h'00')] }
+    let wrong_type = b"\xa3\x62id\x01\x64tags\x80\x01\x81\xd8\x20\x41\x00";
+    assert_eq!(violations(&validator, "document", wrong_type), vec![
+        "$.id: expected bstr .size 16, found uint"
+    ]);
+
+    // { "id": h'00..00', 1: [32(h'00')] }
+    let mut missing = vec![0xA2, 0x62, b'i', b'd', 0x50];
+    missing.extend_from_slice(&[0; 16]);
+    missing.extend_from_slice(&[0x01, 0x81, 0xD8, 0x20, 0x41, 0x00]);
+    assert_eq!(violations(&validator, "document", &missing), vec![
+        "$: missing map entry \"tags\""
+    ]);
+}
+
+/// Test validating arrays, with their occurrences.
+#[test]
+fn check_validator_array() {
+    let cddl = cddl();
+    let validator = Validator::new(&cddl);
+
+    assert!(validator
+        .validate("signatures", b"\x82\xd8\x20\x40\xd8\x20\x41\x00")
+        .is_ok());
+    assert_eq!(violations(&validator, "signatures", b"\x80"), vec![
+        "$: array has 0 items, more are required"
+    ]);
+    assert_eq!(
+        violations(&validator, "signatures", b"\x82\xd8\x20\x40\x40"),
+        vec!["$[1]: expected tag 32, found bstr"]
+    );
+}
+
+/// Test validating control operators and ranges.
+#[test]
+fn check_validator_controls() {
+    let cddl = cddl();
+    let validator = Validator::new(&cddl);
+
+    assert!(validator.validate("flags", b"\x05").is_ok());
+    assert_eq!(violations(&validator, "flags", b"\x02"), vec![
+        "$: bit 1 is not one of (0 / 2)"
+    ]);
+
+    assert!(validator.validate("embedded", b"\x43\x82\x01\x02").is_ok());
+    assert_eq!(
+        violations(&validator, "embedded", b"\x43\x82\x01\x20"),
+        vec!["$[1]: expected uint, found nint"]
+    );
+
+    assert!(validator.validate("small", b"\x0a").is_ok());
+    assert_eq!(violations(&validator, "small", b"\x0b"), vec![
+        "$: 11 is not in 0..10"
+    ]);
+    assert_eq!(violations(&validator, "small", b"\x0a\x00"), vec![
+        "$: Invalid CBOR: 1 trailing bytes after the CBOR data item"
+    ]);
+}
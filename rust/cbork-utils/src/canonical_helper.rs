@@ -0,0 +1,421 @@
+//! CBOR canonical re-encoding, following the core deterministic encoding requirements of
+//! RFC 8949 section 4.2.1.
+//!
+//! Map keys are sorted in the bytewise lexicographic order of their encoding, integers,
+//! lengths and tags use their shortest encoding, floats use the shortest encoding which
+//! preserves their value, and indefinite length items are encoded with definite lengths.
+//!
+//! Big integers and decimal fractions are encoded in their preferred serialization, the
+//! counterpart of the `decode_helper` checks.
+
+use std::fmt::Display;
+
+use minicbor::{
+    data::{IanaTag, Int, Type},
+    decode, encode,
+    encode::Write,
+    Decoder, Encoder,
+};
+use num_bigint::{BigInt, Sign};
+
+use crate::decode_helper::DecimalFraction;
+
+/// The maximum nesting of arrays, maps and tags which are re-encoded.
+const MAX_NESTING: usize = 128;
+
+/// Re-encode a single CBOR data item canonically.
+///
+/// # Errors
+///
+/// Error if the bytes are not a single valid CBOR data item, or a map has duplicate
+/// keys.
+pub fn canonicalize(bytes: &[u8]) -> Result<Vec<u8>, decode::Error> {
+    let mut d = Decoder::new(bytes);
+    let mut e = Encoder::new(Vec::new());
+    canonicalize_item(&mut d, &mut e)?;
+    if d.position() != bytes.len() {
+        return Err(decode::Error::message(format!(
+            "Failed to canonicalize CBOR, {} trailing bytes",
+            bytes.len() - d.position()
+        )));
+    }
+    Ok(e.into_writer())
+}
+
+/// Re-encode the next CBOR data item canonically, streaming it to the encoder.
+///
+/// Only the entries of maps, which must be sorted, and indefinite length strings are
+/// buffered, so large arrays and strings can be written straight to a file or socket.
+///
+/// # Errors
+///
+/// Error if the next data item is not valid CBOR, a map has duplicate keys, or writing
+/// fails.
+pub fn canonicalize_item<W>(d: &mut Decoder, e: &mut Encoder<W>) -> Result<(), decode::Error>
+where
+    W: Write,
+    W::Error: Display,
+{
+    encode_item(d, e, 0)
+}
+
+/// Encode a big integer, as an integer if it is representable as one, otherwise as a
+/// tagged bignum (tags 2 and 3) without leading zero bytes.
+///
+/// # Errors
+///
+/// Error if writing fails.
+pub fn encode_bigint<W: Write>(
+    e: &mut Encoder<W>, value: &BigInt,
+) -> Result<(), encode::Error<W::Error>> {
+    if let Some(int) = i128::try_from(value)
+        .ok()
+        .and_then(|value| Int::try_from(value).ok())
+    {
+        e.int(int)?;
+        return Ok(());
+    }
+
+    let (tag, magnitude) = if value.sign() == Sign::Minus {
+        (IanaTag::NegBignum, BigInt::from(-1) - value)
+    } else {
+        (IanaTag::PosBignum, value.clone())
+    };
+    let (_, magnitude) = magnitude.to_bytes_be();
+    e.tag(tag)?.bytes(&magnitude)?;
+    Ok(())
+}
+
+/// Encode a decimal fraction (tag 4), with the mantissa encoded by `encode_bigint`.
+///
+/// # Errors
+///
+/// Error if writing fails.
+pub fn encode_decimal_fraction<W: Write>(
+    e: &mut Encoder<W>, value: &DecimalFraction,
+) -> Result<(), encode::Error<W::Error>> {
+    e.tag(IanaTag::Decimal)?.array(2)?.i64(value.exponent)?;
+    encode_bigint(e, &value.mantissa)
+}
+
+/// Re-encode the next data item.
+fn encode_item<W>(d: &mut Decoder, e: &mut Encoder<W>, nesting: usize) -> Result<(), decode::Error>
+where
+    W: Write,
+    W::Error: Display,
+{
+    if nesting >= MAX_NESTING {
+        return Err(decode::Error::message(format!(
+            "Failed to canonicalize CBOR, nesting is deeper than {MAX_NESTING}"
+        )));
+    }
+
+    match d.datatype()? {
+        Type::Bool => {
+            e.bool(d.bool()?).map_err(write_error)?;
+        },
+        Type::Null => {
+            d.null()?;
+            e.null().map_err(write_error)?;
+        },
+        Type::Undefined => {
+            d.undefined()?;
+            e.undefined().map_err(write_error)?;
+        },
+        Type::Simple => {
+            e.simple(d.simple()?).map_err(write_error)?;
+        },
+        Type::U8
+        | Type::U16
+        | Type::U32
+        | Type::U64
+        | Type::I8
+        | Type::I16
+        | Type::I32
+        | Type::I64
+        | Type::Int => {
+            e.int(d.int()?).map_err(write_error)?;
+        },
+        Type::F16 => encode_float(e, f64::from(d.f16()?))?,
+        Type::F32 => encode_float(e, f64::from(d.f32()?))?,
+        Type::F64 => encode_float(e, d.f64()?)?,
+        Type::Bytes => {
+            e.bytes(d.bytes()?).map_err(write_error)?;
+        },
+        Type::BytesIndef => {
+            let mut bytes = Vec::new();
+            for chunk in d.bytes_iter()? {
+                bytes.extend_from_slice(chunk?);
+            }
+            e.bytes(&bytes).map_err(write_error)?;
+        },
+        Type::String => {
+            e.str(d.str()?).map_err(write_error)?;
+        },
+        Type::StringIndef => {
+            let mut text = String::new();
+            for chunk in d.str_iter()? {
+                text.push_str(chunk?);
+            }
+            e.str(&text).map_err(write_error)?;
+        },
+        Type::Array | Type::ArrayIndef => {
+            let len = match d.probe().array()? {
+                Some(len) => len,
+                None => indefinite_len(d)?,
+            };
+            let indefinite = d.array()?.is_none();
+            e.array(len).map_err(write_error)?;
+            for _ in 0..len {
+                encode_item(d, e, nesting + 1)?;
+            }
+            if indefinite {
+                skip_break(d)?;
+            }
+        },
+        Type::Map | Type::MapIndef => encode_map(d, e, nesting)?,
+        Type::Tag => {
+            e.tag(d.tag()?).map_err(write_error)?;
+            encode_item(d, e, nesting + 1)?;
+        },
+        Type::Break => {
+            return Err(decode::Error::message(format!(
+                "Failed to canonicalize CBOR, unexpected break at position {}",
+                d.position()
+            )));
+        },
+        Type::Unknown(byte) => {
+            return Err(decode::Error::message(format!(
+                "Failed to canonicalize CBOR, unknown initial byte {byte:#04x} at position {}",
+                d.position()
+            )));
+        },
+    }
+    Ok(())
+}
+
+/// Re-encode the next map, with its entries sorted by their encoded keys.
+fn encode_map<W>(d: &mut Decoder, e: &mut Encoder<W>, nesting: usize) -> Result<(), decode::Error>
+where
+    W: Write,
+    W::Error: Display,
+{
+    let len = d.map()?;
+    let mut entries = Vec::new();
+    loop {
+        match len {
+            Some(len) if entries.len() as u64 >= len => break,
+            None if d.datatype()? == Type::Break => {
+                skip_break(d)?;
+                break;
+            },
+            _ => {},
+        }
+        let mut key = Encoder::new(Vec::new());
+        encode_item(d, &mut key, nesting + 1)?;
+        let mut value = Encoder::new(Vec::new());
+        encode_item(d, &mut value, nesting + 1)?;
+        entries.push((key.into_writer(), value.into_writer()));
+    }
+
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    if entries
+        .windows(2)
+        .any(|pair| matches!(pair, [(a, _), (b, _)] if a == b))
+    {
+        return Err(decode::Error::message(
+            "Failed to canonicalize CBOR, map has duplicate keys",
+        ));
+    }
+
+    e.map(entries.len() as u64).map_err(write_error)?;
+    for (key, value) in entries {
+        e.writer_mut().write_all(&key).map_err(write_error)?;
+        e.writer_mut().write_all(&value).map_err(write_error)?;
+    }
+    Ok(())
+}
+
+/// Encode a float with the shortest encoding which preserves its value.
+///
+/// `NaN` is always encoded as the half float `0x7e00`.
+fn encode_float<W>(e: &mut Encoder<W>, value: f64) -> Result<(), decode::Error>
+where
+    W: Write,
+    W::Error: Display,
+{
+    #[allow(clippy::cast_possible_truncation)]
+    let single = value as f32;
+    if value.is_nan() {
+        e.f16(f32::NAN)
+    } else if f64::from(single).to_bits() != value.to_bits() {
+        e.f64(value)
+    } else if half::f16::from_f32(single).to_f32().to_bits() == single.to_bits() {
+        e.f16(single)
+    } else {
+        e.f32(single)
+    }
+    .map_err(write_error)?;
+    Ok(())
+}
+
+/// Count the items of the next indefinite length array, without consuming it.
+fn indefinite_len(d: &mut Decoder) -> Result<u64, decode::Error> {
+    let mut probe = d.probe();
+    probe.array()?;
+    let mut items = 0_u64;
+    while probe.datatype()? != Type::Break {
+        probe.skip()?;
+        items += 1;
+    }
+    Ok(items)
+}
+
+/// Consume the break ending an indefinite length item.
+fn skip_break(d: &mut Decoder) -> Result<(), decode::Error> {
+    if d.datatype()? != Type::Break {
+        return Err(decode::Error::message(format!(
+            "Failed to canonicalize CBOR, expected break at position {}",
+            d.position()
+        )));
+    }
+    d.set_position(d.position() + 1);
+    Ok(())
+}
+
+/// Convert an error writing the canonical encoding.
+fn write_error<E: Display>(e: E) -> decode::Error {
+    decode::Error::message(format!("Failed to write canonical CBOR: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_canonicalize_sorts_map_keys() {
+        // {"b": 1, 10: 2, "a": 3}
+        let input = [0xA3, 0x61, b'b', 0x01, 0x0A, 0x02, 0x61, b'a', 0x03];
+        let result = canonicalize(&input).expect("Error canonicalizing map");
+        // {10: 2, "a": 3, "b": 1}
+        assert_eq!(result, vec![
+            0xA3, 0x0A, 0x02, 0x61, b'a', 0x03, 0x61, b'b', 0x01
+        ]);
+    }
+
+    #[test]
+    fn test_canonicalize_shortest_integers() {
+        // [0x18 0x01, -1 as 0x39 0x00 0x00, 500 as u64]
+        let input = [
+            0x83, 0x18, 0x01, 0x39, 0x00, 0x00, 0x1B, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+            0xF4,
+        ];
+        let result = canonicalize(&input).expect("Error canonicalizing integers");
+        assert_eq!(result, vec![0x83, 0x01, 0x20, 0x19, 0x01, 0xF4]);
+    }
+
+    #[test]
+    fn test_canonicalize_indefinite_lengths() {
+        // [_ (_ h'01', h'02'), {_ "a": (_ "x", "y")}]
+        let input = [
+            0x9F, 0x5F, 0x41, 0x01, 0x41, 0x02, 0xFF, 0xBF, 0x61, b'a', 0x7F, 0x61, b'x', 0x61,
+            b'y', 0xFF, 0xFF, 0xFF,
+        ];
+        let result = canonicalize(&input).expect("Error canonicalizing indefinite lengths");
+        assert_eq!(result, vec![
+            0x82, 0x42, 0x01, 0x02, 0xA1, 0x61, b'a', 0x62, b'x', b'y'
+        ]);
+    }
+
+    #[test]
+    fn test_canonicalize_floats() {
+        // [1.5 as f64, 100000.0 as f64, 1.1 as f64, NaN as f32]
+        let mut input = vec![0x84];
+        input.push(0xFB);
+        input.extend_from_slice(&1.5_f64.to_be_bytes());
+        input.push(0xFB);
+        input.extend_from_slice(&100_000.0_f64.to_be_bytes());
+        input.push(0xFB);
+        input.extend_from_slice(&1.1_f64.to_be_bytes());
+        input.push(0xFA);
+        input.extend_from_slice(&f32::NAN.to_be_bytes());
+
+        let result = canonicalize(&input).expect("Error canonicalizing floats");
+        let mut expected = vec![0x84, 0xF9, 0x3E, 0x00, 0xFA];
+        expected.extend_from_slice(&100_000.0_f32.to_be_bytes());
+        expected.push(0xFB);
+        expected.extend_from_slice(&1.1_f64.to_be_bytes());
+        expected.extend_from_slice(&[0xF9, 0x7E, 0x00]);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_canonicalize_streaming() {
+        let input = [0xA2, 0x02, 0xD8, 0x20, 0x61, b'x', 0x01, 0xF6];
+        let mut d = Decoder::new(&input);
+        let mut e = Encoder::new(Vec::new());
+        canonicalize_item(&mut d, &mut e).expect("Error canonicalizing item");
+        assert_eq!(e.into_writer(), vec![
+            0xA2, 0x01, 0xF6, 0x02, 0xD8, 0x20, 0x61, b'x'
+        ]);
+    }
+
+    #[test]
+    fn test_canonicalize_invalid() {
+        // Duplicate keys, {1: 1, 0x18 0x01: 2}
+        assert!(canonicalize(&[0xA2, 0x01, 0x01, 0x18, 0x01, 0x02]).is_err());
+        // Trailing bytes
+        assert!(canonicalize(&[0x01, 0x02]).is_err());
+        // Truncated array
+        assert!(canonicalize(&[0x82, 0x01]).is_err());
+    }
+
+    #[test]
+    fn test_encode_bigint() {
+        let values = [
+            BigInt::from(0),
+            BigInt::from(-500),
+            BigInt::from(u64::MAX),
+            BigInt::from(u64::MAX) + 1_u32,
+            BigInt::from(-1) - BigInt::from(u64::MAX),
+            BigInt::from(-2) - BigInt::from(u64::MAX),
+            BigInt::from(u128::MAX) * 1000_u32,
+        ];
+        for value in values {
+            let mut e = Encoder::new(Vec::new());
+            encode_bigint(&mut e, &value).expect("Error encoding bigint");
+            let encoded = e.into_writer();
+            assert_eq!(
+                canonicalize(&encoded).expect("Error canonicalizing bigint"),
+                encoded
+            );
+            let decoded = crate::decode_helper::decode_bigint(&mut Decoder::new(&encoded), "test")
+                .expect("Error decoding bigint");
+            assert_eq!(decoded, value);
+        }
+
+        let mut e = Encoder::new(Vec::new());
+        encode_bigint(&mut e, &(BigInt::from(u64::MAX) + 1_u32)).expect("Error encoding bigint");
+        assert_eq!(e.into_writer(), vec![
+            0xC2, 0x49, 0x01, 0, 0, 0, 0, 0, 0, 0, 0
+        ]);
+    }
+
+    #[test]
+    fn test_encode_decimal_fraction() {
+        let value = DecimalFraction {
+            exponent: -2,
+            mantissa: BigInt::from(27315),
+        };
+        let mut e = Encoder::new(Vec::new());
+        encode_decimal_fraction(&mut e, &value).expect("Error encoding decimal fraction");
+        let encoded = e.into_writer();
+        assert_eq!(encoded, vec![0xC4, 0x82, 0x21, 0x19, 0x6A, 0xB3]);
+        assert_eq!(
+            crate::decode_helper::decode_decimal_fraction(&mut Decoder::new(&encoded), "test")
+                .expect("Error decoding decimal fraction"),
+            value
+        );
+    }
+}
@@ -1,6 +1,26 @@
 //! CBOR decoding helper functions.
 
-use minicbor::{data::Tag, decode, Decoder};
+use minicbor::{
+    data::{IanaTag, Int, Tag, Type},
+    decode, Decoder,
+};
+use num_bigint::{BigInt, Sign};
+
+/// Maximum size of a bignum magnitude which is representable as a CBOR integer.
+const MAX_INT_MAGNITUDE_SIZE: usize = 8;
+
+/// A decimal fraction, `mantissa * 10^exponent`.
+///
+/// ```cddl
+/// decimal-fraction = #6.4([exponent: int, mantissa: int / biguint / bignint])
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecimalFraction {
+    /// Base 10 exponent.
+    pub exponent: i64,
+    /// Mantissa.
+    pub mantissa: BigInt,
+}
 
 /// Generic helper function for decoding different types.
 ///
@@ -74,6 +94,121 @@ pub fn decode_tag(d: &mut Decoder, from: &str) -> Result<Tag, decode::Error> {
         .map_err(|e| decode::Error::message(format!("Failed to decode tag in {from}: {e}")))
 }
 
+/// Helper function for decoding an integer, which MUST be in its shortest encoding.
+///
+/// # Errors
+///
+/// Error if the decoding fails, or the integer is not in its shortest encoding.
+pub fn decode_int(d: &mut Decoder, from: &str) -> Result<Int, decode::Error> {
+    let start = d.position();
+    let int = d
+        .int()
+        .map_err(|e| decode::Error::message(format!("Failed to decode int in {from}: {e}")))?;
+    if d.position().saturating_sub(start) != int_encoded_len(int) {
+        return Err(decode::Error::message(format!(
+            "Failed to decode int in {from}, {int} is not in its shortest encoding"
+        )));
+    }
+    Ok(int)
+}
+
+/// Helper function for decoding a big integer, either an integer or a tagged bignum
+/// (tags 2 and 3).
+///
+/// The preferred serialization is required, a bignum MUST NOT have leading zero bytes,
+/// and MUST NOT be representable as an integer.
+///
+/// # Errors
+///
+/// Error if the decoding fails, or the value is not in its preferred serialization.
+pub fn decode_bigint(d: &mut Decoder, from: &str) -> Result<BigInt, decode::Error> {
+    if d.datatype()? != Type::Tag {
+        return decode_int(d, from).map(|int| BigInt::from(i128::from(int)));
+    }
+
+    let tag = decode_tag(d, from)?;
+    let negative = match IanaTag::try_from(tag) {
+        Ok(IanaTag::PosBignum) => false,
+        Ok(IanaTag::NegBignum) => true,
+        _ => {
+            return Err(decode::Error::message(format!(
+                "Failed to decode bignum in {from}, unexpected tag {tag}"
+            )))
+        },
+    };
+    let magnitude = d
+        .bytes()
+        .map_err(|e| decode::Error::message(format!("Failed to decode bignum in {from}: {e}")))?;
+    if magnitude.first() == Some(&0) {
+        return Err(decode::Error::message(format!(
+            "Failed to decode bignum in {from}, leading zero bytes"
+        )));
+    }
+    if magnitude.len() <= MAX_INT_MAGNITUDE_SIZE {
+        return Err(decode::Error::message(format!(
+            "Failed to decode bignum in {from}, value is representable as an int"
+        )));
+    }
+
+    let magnitude = BigInt::from_bytes_be(Sign::Plus, magnitude);
+    Ok(if negative {
+        BigInt::from(-1) - magnitude
+    } else {
+        magnitude
+    })
+}
+
+/// Helper function for decoding a decimal fraction (tag 4).
+///
+/// The exponent MUST be an integer, the mantissa an integer or a bignum, both in their
+/// preferred serialization.
+///
+/// # Errors
+///
+/// Error if the decoding fails, or the value is not in its preferred serialization.
+pub fn decode_decimal_fraction(
+    d: &mut Decoder, from: &str,
+) -> Result<DecimalFraction, decode::Error> {
+    let tag = decode_tag(d, from)?;
+    if !matches!(IanaTag::try_from(tag), Ok(IanaTag::Decimal)) {
+        return Err(decode::Error::message(format!(
+            "Failed to decode decimal fraction in {from}, unexpected tag {tag}"
+        )));
+    }
+    let len = decode_array_len(d, &format!("{from} decimal fraction"))?;
+    if len != 2 {
+        return Err(decode::Error::message(format!(
+            "Failed to decode decimal fraction in {from}, expected 2 elements, got {len}"
+        )));
+    }
+    let exponent = decode_int(d, &format!("{from} decimal fraction exponent"))?;
+    let exponent = i64::try_from(exponent).map_err(|e| {
+        decode::Error::message(format!(
+            "Failed to decode decimal fraction in {from}, exponent {exponent}: {e}"
+        ))
+    })?;
+    let mantissa = decode_bigint(d, &format!("{from} decimal fraction mantissa"))?;
+    Ok(DecimalFraction { exponent, mantissa })
+}
+
+/// Size of the shortest encoding of the integer.
+fn int_encoded_len(int: Int) -> usize {
+    let value = i128::from(int);
+    // Negative integers are encoded as `-1 - value`.
+    let argument = if value < 0 {
+        value.saturating_add(1).saturating_neg()
+    } else {
+        value
+    };
+    match argument {
+        0..=0x17 => 1,
+        0x18..=0xFF => 2,
+        0x100..=0xFFFF => 3,
+        0x1_0000..=0xFFFF_FFFF => 5,
+        _ => 9,
+    }
+}
+
 /// Decode any in CDDL, only support basic datatype
 ///
 /// # Errors
@@ -235,4 +370,58 @@ mod tests {
         // Should print out the error message with the location of the error
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_decode_int_shortest() {
+        // 1 as 0x18 0x01, and -1 as 0x38 0x00
+        assert!(decode_int(&mut Decoder::new(&[0x18, 0x01]), "test").is_err());
+        assert!(decode_int(&mut Decoder::new(&[0x38, 0x00]), "test").is_err());
+        let int =
+            decode_int(&mut Decoder::new(&[0x39, 0x01, 0xF3]), "test").expect("Error decoding int");
+        assert_eq!(i128::from(int), -500);
+    }
+
+    #[test]
+    fn test_decode_bigint() {
+        // 2^64, tag 2
+        let input = [0xC2, 0x49, 0x01, 0, 0, 0, 0, 0, 0, 0, 0];
+        let result = decode_bigint(&mut Decoder::new(&input), "test").expect("Error decoding");
+        assert_eq!(result, BigInt::from(u64::MAX) + 1_u32);
+
+        // -2^64 - 1, tag 3
+        let input = [0xC3, 0x49, 0x01, 0, 0, 0, 0, 0, 0, 0, 0];
+        let result = decode_bigint(&mut Decoder::new(&input), "test").expect("Error decoding");
+        assert_eq!(result, BigInt::from(i128::from(u64::MAX) * -1 - 2));
+
+        // Plain integer
+        let result = decode_bigint(&mut Decoder::new(&[0x20]), "test").expect("Error decoding");
+        assert_eq!(result, BigInt::from(-1));
+
+        // Leading zero bytes
+        let input = [0xC2, 0x4A, 0x00, 0x01, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert!(decode_bigint(&mut Decoder::new(&input), "test").is_err());
+        // Representable as an integer
+        assert!(decode_bigint(&mut Decoder::new(&[0xC2, 0x41, 0x01]), "test").is_err());
+        // Not a bignum tag
+        assert!(decode_bigint(&mut Decoder::new(&[0xC4, 0x41, 0x01]), "test").is_err());
+    }
+
+    #[test]
+    fn test_decode_decimal_fraction() {
+        // 273.15 as 4([-2, 27315])
+        let input = [0xC4, 0x82, 0x21, 0x19, 0x6A, 0xB3];
+        let result =
+            decode_decimal_fraction(&mut Decoder::new(&input), "test").expect("Error decoding");
+        assert_eq!(result, DecimalFraction {
+            exponent: -2,
+            mantissa: BigInt::from(27315),
+        });
+
+        // Indefinite length array
+        let input = [0xC4, 0x9F, 0x21, 0x19, 0x6A, 0xB3, 0xFF];
+        assert!(decode_decimal_fraction(&mut Decoder::new(&input), "test").is_err());
+        // Exponent is a bignum
+        let input = [0xC4, 0x82, 0xC2, 0x49, 0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0x01];
+        assert!(decode_decimal_fraction(&mut Decoder::new(&input), "test").is_err());
+    }
 }
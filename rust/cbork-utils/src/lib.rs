@@ -1,3 +1,4 @@
 //! CBOR utility modules.
 
+pub mod canonical_helper;
 pub mod decode_helper;
@@ -31,6 +31,20 @@ enum Commands {
         /// IPFS Path
         ipfs_path_str: String,
     },
+    /// Add a local directory to IPFS
+    #[command(name = "add-dir")]
+    AddDirectory {
+        /// Local directory path
+        dir_path: std::path::PathBuf,
+    },
+    /// Get a directory from IPFS and store it locally
+    #[command(name = "get-dir")]
+    GetDirectory {
+        /// IPFS Path
+        ipfs_path_str: String,
+        /// Local destination path
+        dest: std::path::PathBuf,
+    },
     /// Remove the file from being listed (will be garbage collected)
     #[command(name = "rm")]
     UnPinFile {
@@ -51,7 +65,7 @@ async fn main() -> anyhow::Result<()> {
         // TODO(saibatizoku): Re-Enable default transport config when libp2p Cert bug is fixed
         .disable_tls()
         .set_disk_storage(ipfs_data_path);
-    let hermes_node: HermesIpfs = builder.start().await?.into();
+    let hermes_node: HermesIpfs = builder.start_hermes().await?;
     match args.command {
         Commands::ListFiles => {
             println!("Listing files");
@@ -77,6 +91,27 @@ async fn main() -> anyhow::Result<()> {
             println!("* FILE CONTENTS:");
             println!("{get_file}\n");
         },
+        Commands::AddDirectory { dir_path } => {
+            println!("Adding directory {}", dir_path.display());
+            let dir = hermes_node.add_ipfs_directory(dir_path).await?;
+            for entry in &dir.entries {
+                println!("* {} {}", entry.cid(), entry.path());
+            }
+            println!("Added directory: {}", IpfsPath::from(dir.root));
+        },
+        Commands::GetDirectory {
+            ipfs_path_str,
+            dest,
+        } => {
+            println!("Getting directory into {}", dest.display());
+            let ipfs_path: IpfsPath = ipfs_path_str.parse()?;
+            let dir = hermes_node
+                .get_ipfs_directory(ipfs_path.into(), &dest)
+                .await?;
+            for entry in &dir.entries {
+                println!("* {} {}", entry.cid(), entry.path());
+            }
+        },
         Commands::UnPinFile { ipfs_path_str } => {
             println!("Un-pinning file {ipfs_path_str}");
             let ipfs_path: IpfsPath = ipfs_path_str.parse()?;
@@ -1,7 +1,7 @@
 //! Use Hermes IPFS to distribute content using DHT
 #![allow(clippy::println_empty_string)]
 
-use hermes_ipfs::{HermesIpfs, IpfsPath};
+use hermes_ipfs::{pin_mut, HermesIpfs, IpfsPath, StreamExt};
 
 /// Connect Node A, upload file and provide CID by adding to DHT
 async fn connect_node_a_upload_and_provide(
@@ -35,6 +35,7 @@ async fn connect_node_a_upload_and_provide(
     println!("* Providing content to DHT:");
     println!("");
     println!("* Providing {cid} as peer {peer_id_a}");
+    hermes_ipfs.provide(*cid).await?;
     println!("***************************************");
     println!("");
     Ok((hermes_ipfs, ipfs_path))
@@ -82,6 +83,19 @@ async fn main() -> anyhow::Result<()> {
     // connect to Node A's peer ID.
     let hermes_ipfs_b = connect_node_b_to_node_a(&hermes_ipfs_a).await?;
 
+    println!("***************************************");
+    println!("* Find providers of the content:");
+    println!("");
+    let cid = ipfs_path.root().cid().ok_or(anyhow::anyhow!(
+        "ERROR! Could not extract CID from IPFS path."
+    ))?;
+    let providers = hermes_ipfs_b.get_providers(*cid).await?;
+    pin_mut!(providers);
+    if let Some(provider) = providers.next().await {
+        println!("* Found provider {provider} of {cid}");
+    }
+    println!("***************************************");
+    println!("");
     println!("***************************************");
     println!("* Get content from IPFS path {ipfs_path}");
     println!("");
@@ -0,0 +1,456 @@
+//! CAR (Content Addressable aRchive) import and export.
+//!
+//! Whole DAGs are exported as [CARv1](https://ipld.io/specs/transport/car/carv1/)
+//! archives:
+//! ```text
+//! car     = varint(header length) | header | *section
+//! header  = DAG-CBOR { "roots": [+ cid], "version": 1 }
+//! section = varint(cid length + data length) | cid | data
+//! ```
+//! [CARv2](https://ipld.io/specs/transport/car/carv2/) archives are accepted on import,
+//! their CARv1 payload is imported and the index is ignored.
+
+use std::{
+    collections::HashSet,
+    io::{self, Read, Write},
+};
+
+use minicbor::data::Tag;
+
+use crate::{Block, Cid, HermesIpfs};
+
+/// CARv1 header version.
+const CAR_V1: u64 = 1;
+/// CARv2 header version, the CARv2 pragma is a CARv1 header with this version.
+const CAR_V2: u64 = 2;
+/// Size of the CARv2 header, which follows the pragma.
+const CAR_V2_HEADER_SIZE: usize = 40;
+/// Minimal CARv2 data offset, size of the pragma with its varint length prefix and of
+/// the header.
+const CAR_V2_MIN_DATA_OFFSET: u64 = 51;
+/// DAG-CBOR tag of the CID.
+const CID_TAG: u64 = 42;
+/// CIDv0 prefix, a sha2-256 multihash.
+const CID_V0_PREFIX: [u8; 2] = [0x12, 0x20];
+/// CIDv0 size.
+const CID_V0_SIZE: usize = 34;
+/// Maximum size of the header or a section, protects from allocating arbitrary
+/// amounts of memory on malformed archives.
+const MAX_SECTION_SIZE: u64 = 8 * 1024 * 1024;
+
+impl HermesIpfs {
+    /// Export the DAG with the `cid` root as a CARv1 archive.
+    /// Blocks missing locally are fetched from the network.
+    ///
+    /// ## Parameters
+    ///
+    /// * `cid` - `Cid`
+    /// * `writer` - `impl Write`
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<()>`
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if unable to get a block of the DAG, or to write the archive.
+    pub async fn export_car(&self, cid: Cid, writer: impl Write) -> anyhow::Result<()> {
+        self.export_car_blocks(cid, writer, false).await
+    }
+
+    /// Export the DAG with the `cid` root as a CARv1 archive, using only the blocks
+    /// stored locally.
+    ///
+    /// ## Parameters
+    ///
+    /// * `cid` - `Cid`
+    /// * `writer` - `impl Write`
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<()>`
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if a block of the DAG is not stored locally, or unable to write the
+    /// archive.
+    pub async fn export_car_local(&self, cid: Cid, writer: impl Write) -> anyhow::Result<()> {
+        self.export_car_blocks(cid, writer, true).await
+    }
+
+    /// Import all blocks of a CARv1 or CARv2 archive.
+    /// Blocks are not pinned, pin the returned roots to keep them.
+    ///
+    /// ## Parameters
+    ///
+    /// * `reader` - `impl Read`
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<Vec<Cid>>` - roots of the archive.
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if the archive is malformed, a block does not match its CID, or
+    /// unable to store a block.
+    pub async fn import_car(&self, reader: impl Read) -> anyhow::Result<Vec<Cid>> {
+        let mut reader = CarReader::new(reader)?;
+        while let Some(block) = reader.next_block()? {
+            self.node.put_block(&block).await?;
+        }
+        Ok(reader.roots)
+    }
+
+    /// Write the DAG blocks in the depth-first order, every block written once.
+    async fn export_car_blocks(
+        &self, cid: Cid, mut writer: impl Write, local: bool,
+    ) -> anyhow::Result<()> {
+        write_header(&mut writer, &[cid])?;
+        let mut visited = HashSet::new();
+        let mut stack = vec![cid];
+        while let Some(cid) = stack.pop() {
+            if !visited.insert(cid) {
+                continue;
+            }
+            let get_block = self.node.get_block(cid);
+            let block = if local {
+                get_block.local().await?
+            } else {
+                get_block.await?
+            };
+            write_section(&mut writer, &block)?;
+            let mut links = Vec::new();
+            block.references(&mut links)?;
+            stack.extend(links.into_iter().rev());
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Reader of the CAR archive blocks.
+struct CarReader<R> {
+    /// Reader of the CARv1 payload.
+    reader: io::Take<R>,
+    /// Roots of the archive.
+    roots: Vec<Cid>,
+}
+
+impl<R: Read> CarReader<R> {
+    /// Create a new `CarReader`, reading the archive header.
+    fn new(reader: R) -> anyhow::Result<Self> {
+        let mut reader = reader.take(u64::MAX);
+        let (version, roots) = read_header(&mut reader)?;
+        match version {
+            CAR_V1 => Ok(Self { reader, roots }),
+            CAR_V2 => {
+                let mut header = [0u8; CAR_V2_HEADER_SIZE];
+                reader.read_exact(&mut header)?;
+                let (data_offset, data_size) = v2_data_range(&header)?;
+                let padding = data_offset
+                    .checked_sub(CAR_V2_MIN_DATA_OFFSET)
+                    .ok_or(anyhow::anyhow!("Invalid CARv2 data offset {data_offset}"))?;
+                io::copy(&mut (&mut reader).take(padding), &mut io::sink())?;
+
+                let mut reader = reader.into_inner().take(data_size);
+                let (version, roots) = read_header(&mut reader)?;
+                anyhow::ensure!(
+                    version == CAR_V1,
+                    "Invalid CARv2 payload, unsupported CAR version {version}"
+                );
+                Ok(Self { reader, roots })
+            },
+            _ => anyhow::bail!("Unsupported CAR version {version}"),
+        }
+    }
+
+    /// Read the next block, returns `None` at the end of the archive.
+    fn next_block(&mut self) -> anyhow::Result<Option<Block>> {
+        let Some(section) = read_section(&mut self.reader)? else {
+            return Ok(None);
+        };
+        let (cid, data) = section.split_at(cid_size(&section)?);
+        let cid = Cid::try_from(cid)?;
+        Ok(Some(Block::new(cid, data.to_vec())?))
+    }
+}
+
+/// Write the CARv1 header with the provided roots.
+fn write_header(writer: &mut impl Write, roots: &[Cid]) -> anyhow::Result<()> {
+    let mut header = Vec::new();
+    let mut e = minicbor::Encoder::new(&mut header);
+    // DAG-CBOR map keys are sorted by length first.
+    e.map(2)?.str("roots")?.array(roots.len().try_into()?)?;
+    for root in roots {
+        // DAG-CBOR CID bytes are prefixed with the multibase identity prefix.
+        let mut cid = vec![0];
+        cid.extend(root.to_bytes());
+        e.tag(Tag::new(CID_TAG))?.bytes(&cid)?;
+    }
+    e.str("version")?.u64(CAR_V1)?;
+
+    write_varint(writer, header.len().try_into()?)?;
+    writer.write_all(&header)?;
+    Ok(())
+}
+
+/// Write the block as a CAR section.
+fn write_section(writer: &mut impl Write, block: &Block) -> anyhow::Result<()> {
+    let cid = block.cid().to_bytes();
+    write_varint(writer, (cid.len() + block.data().len()).try_into()?)?;
+    writer.write_all(&cid)?;
+    writer.write_all(block.data())?;
+    Ok(())
+}
+
+/// Read the CAR header, returns its version and roots.
+/// The CARv2 pragma is read as a header without roots.
+fn read_header(reader: &mut impl Read) -> anyhow::Result<(u64, Vec<Cid>)> {
+    let header =
+        read_section(reader)?.ok_or(anyhow::anyhow!("Invalid CAR archive, missing header"))?;
+    let mut d = minicbor::Decoder::new(&header);
+    let len = d.map()?.ok_or(anyhow::anyhow!(
+        "Invalid CAR header, expected a definite map"
+    ))?;
+    let mut version = None;
+    let mut roots = Vec::new();
+    for _ in 0..len {
+        match d.str()? {
+            "version" => version = Some(d.u64()?),
+            "roots" => {
+                let len = d.array()?.ok_or(anyhow::anyhow!(
+                    "Invalid CAR roots, expected a definite array"
+                ))?;
+                for _ in 0..len {
+                    anyhow::ensure!(
+                        d.tag()? == Tag::new(CID_TAG),
+                        "Invalid CAR root, expected a CID tag"
+                    );
+                    let bytes = d.bytes()?;
+                    let (prefix, cid) = bytes.split_at(1.min(bytes.len()));
+                    anyhow::ensure!(prefix == [0], "Invalid CAR root, expected a CID prefix");
+                    roots.push(Cid::try_from(cid)?);
+                }
+            },
+            _ => d.skip()?,
+        }
+    }
+    anyhow::ensure!(
+        d.position() == header.len(),
+        "Invalid CAR header, unexpected trailing bytes"
+    );
+    let version = version.ok_or(anyhow::anyhow!("Invalid CAR header, missing version"))?;
+    Ok((version, roots))
+}
+
+/// Get the data offset and size of the CARv1 payload from the CARv2 header.
+fn v2_data_range(header: &[u8; CAR_V2_HEADER_SIZE]) -> anyhow::Result<(u64, u64)> {
+    // 16 bytes of characteristics, then the data offset, data size and index offset.
+    let (_, rest) = header.split_at(16);
+    let (data_offset, rest) = rest.split_at(8);
+    let (data_size, _) = rest.split_at(8);
+    Ok((
+        u64::from_le_bytes(data_offset.try_into()?),
+        u64::from_le_bytes(data_size.try_into()?),
+    ))
+}
+
+/// Read a varint prefixed section, returns `None` at the end of the input.
+fn read_section(reader: &mut impl Read) -> anyhow::Result<Option<Vec<u8>>> {
+    let Some(len) = read_varint(reader)? else {
+        return Ok(None);
+    };
+    anyhow::ensure!(
+        len <= MAX_SECTION_SIZE,
+        "CAR section of {len} bytes exceeds the maximum of {MAX_SECTION_SIZE} bytes"
+    );
+    let mut section = vec![0; len.try_into()?];
+    reader.read_exact(&mut section)?;
+    Ok(Some(section))
+}
+
+/// Get the size of the CID at the beginning of the section.
+fn cid_size(section: &[u8]) -> anyhow::Result<usize> {
+    if section.starts_with(&CID_V0_PREFIX) {
+        return Ok(CID_V0_SIZE);
+    }
+    let mut cursor = section;
+    // Version, codec, multihash code, then the multihash digest size.
+    let mut digest_size = 0;
+    for _ in 0..4 {
+        digest_size = read_varint(&mut cursor)?
+            .ok_or(anyhow::anyhow!("Invalid CAR section, truncated CID"))?;
+    }
+    let size = section.len() - cursor.len() + usize::try_from(digest_size)?;
+    anyhow::ensure!(size <= section.len(), "Invalid CAR section, truncated CID");
+    Ok(size)
+}
+
+/// Write an unsigned LEB128 varint.
+fn write_varint(writer: &mut impl Write, mut value: u64) -> io::Result<()> {
+    loop {
+        let byte = (value & 0x7F).to_le_bytes()[0];
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+/// Read an unsigned LEB128 varint, returns `None` if the input ends before the first
+/// byte.
+fn read_varint(reader: &mut impl Read) -> anyhow::Result<Option<u64>> {
+    let mut value = 0u64;
+    for i in 0..10 {
+        let mut byte = [0u8];
+        if reader.read(&mut byte)? == 0 {
+            anyhow::ensure!(i == 0, "Invalid varint, unexpected end of input");
+            return Ok(None);
+        }
+        let [byte] = byte;
+        value |= u64::from(byte & 0x7F)
+            .checked_shl(7 * i)
+            .filter(|v| v >> (7 * i) == u64::from(byte & 0x7F))
+            .ok_or(anyhow::anyhow!("Invalid varint, overflow"))?;
+        if byte & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+    anyhow::bail!("Invalid varint, too long")
+}
+
+#[cfg(test)]
+mod tests {
+    use ipld_core::cid::multihash::Multihash;
+
+    use super::*;
+
+    /// Raw codec.
+    const RAW: u64 = 0x55;
+    /// Sha2-256 multihash code.
+    const SHA2_256: u64 = 0x12;
+
+    /// Create a raw block from the data and its sha2-256 digest.
+    fn raw_block(data: &[u8], digest: &str) -> Block {
+        let digest: Vec<u8> = (0..digest.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(digest.get(i..i + 2).unwrap(), 16).unwrap())
+            .collect();
+        let cid = Cid::new_v1(RAW, Multihash::wrap(SHA2_256, &digest).unwrap());
+        Block::new(cid, data.to_vec()).unwrap()
+    }
+
+    fn blocks() -> Vec<Block> {
+        vec![
+            raw_block(
+                b"",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            raw_block(
+                b"hello",
+                "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+            ),
+        ]
+    }
+
+    fn car_v1(blocks: &[Block]) -> Vec<u8> {
+        let mut car = Vec::new();
+        let roots: Vec<_> = blocks.iter().map(|b| *b.cid()).collect();
+        write_header(&mut car, &roots).unwrap();
+        for block in blocks {
+            write_section(&mut car, block).unwrap();
+        }
+        car
+    }
+
+    fn read_all(car: &[u8]) -> anyhow::Result<(Vec<Cid>, Vec<Block>)> {
+        let mut reader = CarReader::new(car)?;
+        let mut blocks = Vec::new();
+        while let Some(block) = reader.next_block()? {
+            blocks.push(block);
+        }
+        Ok((reader.roots, blocks))
+    }
+
+    #[test]
+    fn car_v1_roundtrip() {
+        let blocks = blocks();
+        let (roots, read) = read_all(&car_v1(&blocks)).unwrap();
+        assert_eq!(roots, blocks.iter().map(|b| *b.cid()).collect::<Vec<_>>());
+        assert_eq!(read, blocks);
+        for (block, read) in blocks.iter().zip(&read) {
+            assert_eq!(block.data(), read.data());
+        }
+    }
+
+    #[test]
+    fn car_v2_import() {
+        let blocks = blocks();
+        let payload = car_v1(&blocks);
+        let padding = [0; 5];
+
+        let mut car = Vec::new();
+        write_varint(&mut car, 10).unwrap();
+        let mut e = minicbor::Encoder::new(&mut car);
+        e.map(1)
+            .unwrap()
+            .str("version")
+            .unwrap()
+            .u64(CAR_V2)
+            .unwrap();
+        car.extend([0; 16]);
+        car.extend((CAR_V2_MIN_DATA_OFFSET + u64::try_from(padding.len()).unwrap()).to_le_bytes());
+        car.extend(u64::try_from(payload.len()).unwrap().to_le_bytes());
+        car.extend(0u64.to_le_bytes());
+        car.extend(padding);
+        car.extend(&payload);
+        // Trailing index is ignored.
+        car.extend([1, 2, 3]);
+
+        let (roots, read) = read_all(&car).unwrap();
+        assert_eq!(roots.len(), 2);
+        assert_eq!(read, blocks);
+    }
+
+    #[test]
+    fn malformed_car() {
+        let blocks = blocks();
+        let car = car_v1(&blocks);
+
+        // Truncated section.
+        assert!(read_all(car.split_at(car.len() - 1).0).is_err());
+
+        // Block data does not match its CID.
+        let mut corrupted = car.clone();
+        if let Some(last) = corrupted.last_mut() {
+            *last ^= 1;
+        }
+        assert!(read_all(&corrupted).is_err());
+
+        // Unsupported version.
+        let mut car = Vec::new();
+        let mut header = Vec::new();
+        let mut e = minicbor::Encoder::new(&mut header);
+        e.map(2).unwrap().str("roots").unwrap().array(0).unwrap();
+        e.str("version").unwrap().u64(3).unwrap();
+        write_varint(&mut car, header.len().try_into().unwrap()).unwrap();
+        car.extend(header);
+        assert!(read_all(&car).is_err());
+
+        // Empty input.
+        assert!(read_all(&[]).is_err());
+    }
+
+    #[test]
+    fn varint_roundtrip() {
+        for value in [0, 1, 127, 128, 300, u64::from(u32::MAX), u64::MAX] {
+            let mut bytes = Vec::new();
+            write_varint(&mut bytes, value).unwrap();
+            assert_eq!(read_varint(&mut bytes.as_slice()).unwrap(), Some(value));
+        }
+        assert_eq!(read_varint(&mut [].as_slice()).unwrap(), None);
+        assert!(read_varint(&mut [0x80].as_slice()).is_err());
+        assert!(read_varint(&mut [0xFF; 11].as_slice()).is_err());
+    }
+}
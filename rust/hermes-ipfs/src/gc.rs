@@ -0,0 +1,106 @@
+//! Garbage collection of the unpinned blocks.
+
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use rust_ipfs::Ipfs;
+use tokio::task::AbortHandle;
+
+use crate::Cid;
+
+/// Garbage collection policy.
+///
+/// Consulted by [`crate::HermesIpfs::gc`] and the automatic garbage collection for every
+/// unpinned block, before it is removed from the local storage. Allows the embedder to
+/// keep content which is still in use (e.g. referenced by an active Catalyst signed
+/// document), without pinning it explicitly.
+pub trait GcPolicy: Send + Sync {
+    /// Returns `true` if the block with the provided `cid` may be collected.
+    fn may_collect(&self, cid: &Cid) -> bool;
+}
+
+impl<F> GcPolicy for F
+where F: Fn(&Cid) -> bool + Send + Sync
+{
+    fn may_collect(&self, cid: &Cid) -> bool {
+        self(cid)
+    }
+}
+
+/// Options of the automatic garbage collection.
+#[derive(Debug, Clone)]
+pub struct GcOptions {
+    /// Interval between the checks of the local storage size.
+    pub interval: Duration,
+    /// Size of the local storage in bytes, above which the unpinned blocks are collected.
+    pub storage_limit: usize,
+}
+
+impl Default for GcOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60 * 60),
+            storage_limit: 0,
+        }
+    }
+}
+
+/// Garbage collection policy of the node, shared with the automatic garbage collection.
+#[derive(Clone, Default)]
+pub(crate) struct SharedGcPolicy(Arc<RwLock<Option<Arc<dyn GcPolicy>>>>);
+
+impl SharedGcPolicy {
+    /// Replace the policy, `None` allows every unpinned block to be collected.
+    pub(crate) fn set(&self, policy: Option<Arc<dyn GcPolicy>>) {
+        if let Ok(mut current) = self.0.write() {
+            *current = policy;
+        }
+    }
+
+    /// Get the current policy.
+    fn get(&self) -> Option<Arc<dyn GcPolicy>> {
+        self.0.read().ok().and_then(|policy| policy.clone())
+    }
+
+    /// Remove the unpinned blocks allowed by the policy, returns the removed blocks.
+    pub(crate) async fn collect(&self, node: &Ipfs) -> anyhow::Result<Vec<Cid>> {
+        let Some(policy) = self.get() else {
+            return node.gc().await;
+        };
+
+        let mut removed = Vec::new();
+        for cid in node.refs_local().await {
+            if node.is_pinned(&cid).await? || !policy.may_collect(&cid) {
+                continue;
+            }
+            removed.extend(node.remove_block(cid, false).await?);
+        }
+        Ok(removed)
+    }
+
+    /// Spawn the task collecting the unpinned blocks every `GcOptions::interval`, while
+    /// the local storage exceeds `GcOptions::storage_limit`.
+    ///
+    /// The task runs until it is aborted.
+    pub(crate) fn spawn_collect(&self, node: Ipfs, options: GcOptions) -> AbortHandle {
+        let policy = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(options.interval);
+            loop {
+                interval.tick().await;
+                let Ok(size) = node.repo().get_total_size().await else {
+                    continue;
+                };
+                if size > options.storage_limit {
+                    // Failures are transient, collection is retried on the next check.
+                    if let Err(err) = policy.collect(&node).await {
+                        tracing::warn!("Failed to collect the unpinned blocks: {err}");
+                    }
+                }
+            }
+        })
+        .abort_handle()
+    }
+}
@@ -0,0 +1,247 @@
+//! Key management and IPNS names.
+//!
+//! IPNS names are mutable pointers to content, signed by a key of the node keystore, so
+//! updated Catalyst data sets can be referenced by a stable name. A record is published
+//! for the name every time it points to new content, with an increasing sequence number.
+
+// cspell: words ipns
+
+use std::{collections::BTreeMap, str::FromStr, time::Duration};
+
+use ipld_core::cid::{multibase::Base, multihash::Multihash};
+use rust_ipfs::libp2p::identity::{KeyType, Keypair, PublicKey};
+use tokio::sync::RwLock;
+
+use crate::{Cid, DhtOptions, HermesIpfs, IpfsPath, PeerId};
+
+/// Multicodec of the `libp2p-key` CID of the IPNS names.
+const LIBP2P_KEY_CODEC: u64 = 0x72;
+
+/// Options of publishing an IPNS record.
+#[derive(Debug, Clone)]
+pub struct IpnsOptions {
+    /// How long the record is valid for after it is published.
+    pub lifetime: Duration,
+    /// How long resolvers may cache the record, before resolving the name again.
+    pub ttl: Duration,
+    /// Options of storing the record in the DHT, the record is only stored locally if
+    /// `None`.
+    pub dht: Option<DhtOptions>,
+}
+
+impl Default for IpnsOptions {
+    fn default() -> Self {
+        Self {
+            lifetime: Duration::from_secs(48 * 60 * 60),
+            ttl: Duration::from_secs(60 * 60),
+            dht: Some(DhtOptions::default()),
+        }
+    }
+}
+
+/// A named key of the node keystore.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyInfo {
+    /// Name of the key in the keystore.
+    pub name: String,
+    /// Public key.
+    pub public_key: PublicKey,
+}
+
+impl KeyInfo {
+    /// Peer ID of the key, which identifies its IPNS name.
+    #[must_use]
+    pub fn peer_id(&self) -> PeerId {
+        self.public_key.to_peer_id()
+    }
+}
+
+/// Names of the keys added to the node keystore.
+///
+/// The keystore can not list the names of its keys, so they are tracked when the keys are
+/// added.
+#[derive(Debug, Default)]
+pub(crate) struct KeyNames(RwLock<BTreeMap<String, PublicKey>>);
+
+/// Get the IPNS name of the peer ID, `/ipns/<libp2p-key CID>`.
+fn ipns_name(peer_id: &PeerId) -> anyhow::Result<String> {
+    let hash = Multihash::from_bytes(&peer_id.to_bytes())?;
+    let cid = Cid::new_v1(LIBP2P_KEY_CODEC, hash);
+    Ok(format!(
+        "/ipns/{}",
+        cid.to_string_of_base(Base::Base36Lower)?
+    ))
+}
+
+impl HermesIpfs {
+    /// Generate a new ed25519 key, and add it to the node keystore.
+    ///
+    /// ## Parameters
+    ///
+    /// * `name` - Name of the key in the keystore.
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<PeerId>` - Peer ID of the key, which identifies its IPNS name.
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if a key with the name already exists, or the key cannot be stored.
+    pub async fn generate_key(&self, name: &str) -> anyhow::Result<PeerId> {
+        self.add_key(name, &Keypair::generate_ed25519()).await
+    }
+
+    /// Import an ed25519 key to the node keystore.
+    ///
+    /// ## Parameters
+    ///
+    /// * `name` - Name of the key in the keystore.
+    /// * `keypair` - `Keypair`
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<PeerId>` - Peer ID of the key, which identifies its IPNS name.
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if the key is not an ed25519 key, a key with the name already
+    /// exists, or the key cannot be stored.
+    pub async fn import_key(&self, name: &str, keypair: &Keypair) -> anyhow::Result<PeerId> {
+        if keypair.key_type() != KeyType::Ed25519 {
+            anyhow::bail!(
+                "Only ed25519 keys are supported, got {:?}",
+                keypair.key_type()
+            );
+        }
+        self.add_key(name, keypair).await
+    }
+
+    /// List the keys added to the node keystore, ordered by name.
+    ///
+    /// ## Returns
+    ///
+    /// * `Vec<KeyInfo>`
+    pub async fn list_keys(&self) -> Vec<KeyInfo> {
+        self.keys
+            .0
+            .read()
+            .await
+            .iter()
+            .map(|(name, public_key)| {
+                KeyInfo {
+                    name: name.clone(),
+                    public_key: public_key.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Add the key to the node keystore, and track its name.
+    async fn add_key(&self, name: &str, keypair: &Keypair) -> anyhow::Result<PeerId> {
+        let mut keys = self.keys.0.write().await;
+        if keys.contains_key(name) || self.node.keystore().contains(name).await? {
+            anyhow::bail!("Key {name} already exists");
+        }
+        let public_key = self.node.keystore().import_key(keypair, Some(name)).await?;
+        let peer_id = public_key.to_peer_id();
+        keys.insert(name.to_string(), public_key);
+        Ok(peer_id)
+    }
+
+    /// Publish an IPNS record pointing the name of the key to the content.
+    ///
+    /// ## Parameters
+    ///
+    /// * `key` - Name of the key in the keystore, the node identity key if `None`.
+    /// * `cid` - `Cid` of the content.
+    /// * `options` - `IpnsOptions`
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<IpfsPath>` - The IPNS name, `/ipns/<name>`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if the key is not found, the record cannot be created or stored, or
+    /// the DHT put fails.
+    pub async fn ipns_publish(
+        &self, key: Option<&str>, cid: Cid, options: &IpnsOptions,
+    ) -> anyhow::Result<IpfsPath> {
+        let keypair = match key {
+            Some(name) => self.node.keystore().get_keypair(name).await?,
+            None => self.node.keypair().clone(),
+        };
+        let peer_id = keypair.public().to_peer_id();
+        let name = ipns_name(&peer_id)?;
+
+        // The records are stored under the same key as `rust_ipfs`, so the names can be
+        // resolved with `Ipfs::resolve_ipns` too.
+        let datastore = self.node.repo().data_store();
+        let sequence = match datastore.get(name.as_bytes()).await.unwrap_or_default() {
+            Some(record) => {
+                let record = rust_ipns::Record::decode(record)?;
+                record.verify(peer_id)?;
+                record.sequence().saturating_add(1)
+            },
+            None => 0,
+        };
+
+        let value = IpfsPath::from(cid).to_string();
+        // The TTL of an IPNS record is in nanoseconds.
+        let ttl = u64::try_from(options.ttl.as_nanos())?;
+        let record = rust_ipns::Record::new(
+            &keypair,
+            value.as_bytes(),
+            chrono::Duration::from_std(options.lifetime)?,
+            sequence,
+            ttl,
+        )?;
+        let bytes = record.encode()?;
+        datastore.put(name.as_bytes(), &bytes).await?;
+
+        if let Some(dht) = &options.dht {
+            self.dht_put_with_options(&name, bytes, dht).await?;
+        }
+        Ok(IpfsPath::from_str(&name)?)
+    }
+
+    /// Resolve an IPNS name to the content it points to.
+    /// Names pointing to other names are resolved recursively.
+    ///
+    /// ## Parameters
+    ///
+    /// * `name` - `impl Into<IpfsPath>`, the IPNS name `/ipns/<name>`, or the `PeerId` of
+    ///   its key.
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<IpfsPath>` - Path of the content.
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if no valid record is found for the name.
+    pub async fn ipns_resolve(&self, name: impl Into<IpfsPath>) -> anyhow::Result<IpfsPath> {
+        self.node.resolve_ipns(name.into(), true).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ipns_name_of_peer_id() {
+        let peer_id = Keypair::generate_ed25519().public().to_peer_id();
+        let name = ipns_name(&peer_id).unwrap();
+        // Base36 encoded CIDv1 of the `libp2p-key` codec.
+        assert!(name.starts_with("/ipns/k51"));
+        assert!(IpfsPath::from_str(&name).is_ok());
+    }
+
+    #[test]
+    fn default_ipns_options() {
+        let options = IpnsOptions::default();
+        assert!(options.ttl < options.lifetime);
+        assert!(options.dht.is_some());
+    }
+}
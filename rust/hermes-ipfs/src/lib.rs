@@ -2,7 +2,13 @@
 //!
 //! Provides support for storage, and `PubSub` functionality.
 
-use std::str::FromStr;
+use std::{
+    collections::HashSet,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
 use derive_more::{Display, From, Into};
 /// IPFS Content Identifier.
@@ -25,6 +31,8 @@ pub use rust_ipfs::Ipfs;
 pub use rust_ipfs::Multiaddr;
 /// Peer ID type.
 pub use rust_ipfs::PeerId;
+/// Number of peers which must confirm a DHT operation.
+pub use rust_ipfs::Quorum;
 /// Storage type for IPFS node.
 pub use rust_ipfs::StorageType;
 /// Stream for `PubSub` Topic Subscriptions.
@@ -33,35 +41,72 @@ pub use rust_ipfs::SubscriptionStream;
 use rust_ipfs::UninitializedIpfsDefault as UninitializedIpfs;
 use rust_ipfs::{
     dag::ResolveError,
-    libp2p::gossipsub::{Message as PubsubMessage, MessageId as PubsubMessageId},
-    unixfs::AddOpt,
-    PubsubEvent, Quorum,
+    libp2p::{
+        gossipsub::{Message as PubsubMessage, MessageId as PubsubMessageId},
+        relay::client::Transport as RelayClientTransport,
+        swarm::SwarmEvent,
+    },
+    unixfs::{
+        ll::dir::builder::{BufferingTreeBuilder, TreeOptions},
+        AddOpt, Entry as UnixfsEntry,
+    },
+    Block, PubsubEvent,
 };
+use tokio::task::AbortHandle;
+
+mod car;
+mod gc;
+mod ipns;
+mod peer_events;
+mod private_network;
+mod providers;
+mod typed_topic;
+
+use gc::SharedGcPolicy;
+pub use gc::{GcOptions, GcPolicy};
+use ipns::KeyNames;
+pub use ipns::{IpnsOptions, KeyInfo};
+use peer_events::PeerTracker;
+pub use peer_events::{PeerEvent, ReconnectPolicy};
+use private_network::PrivateNetwork;
+pub use private_network::{PreSharedKey, PrivateNetworkError};
+use providers::{ProviderWithdrawals, WITHDRAWAL_TIMEOUT};
+pub use typed_topic::{MalformedMessage, TypedMessage, TypedSubscriptionStream, TypedTopic};
 
 #[derive(Debug, Display, From, Into)]
 /// `PubSub` Message ID.
 pub struct MessageId(pub PubsubMessageId);
 
 /// Builder type for IPFS Node configuration.
-pub struct IpfsBuilder(UninitializedIpfs);
+pub struct IpfsBuilder(
+    UninitializedIpfs,
+    PeerTracker,
+    PrivateNetwork,
+    Option<GcOptions>,
+);
 
 impl IpfsBuilder {
     #[must_use]
     /// Create a new` IpfsBuilder`.
     pub fn new() -> Self {
-        Self(UninitializedIpfs::new())
+        Self(
+            UninitializedIpfs::new(),
+            PeerTracker::new(),
+            PrivateNetwork::default(),
+            None,
+        )
     }
 
     #[must_use]
     /// Set the default configuration for the IPFS node.
     pub fn with_default(self) -> Self {
-        Self(self.0.with_default())
+        Self(self.0.with_default(), self.1, self.2, self.3)
     }
 
     #[must_use]
     /// Set the default listener for the IPFS node.
     pub fn set_default_listener(self) -> Self {
-        Self(self.0.set_default_listener())
+        Self(self.0.set_default_listener(), self.1, self.2, self.3)
     }
 
     #[must_use]
@@ -72,13 +117,21 @@ impl IpfsBuilder {
         Self(
             self.0
                 .set_storage_type(rust_ipfs::StorageType::Disk(storage_path.into())),
+            self.1,
+            self.2,
+            self.3,
         )
     }
 
     #[must_use]
     /// Set the transport configuration for the IPFS node.
     pub fn set_transport_configuration(self, transport: rust_ipfs::p2p::TransportConfig) -> Self {
-        Self(self.0.set_transport_configuration(transport))
+        Self(
+            self.0.set_transport_configuration(transport),
+            self.1,
+            self.2,
+            self.3,
+        )
     }
 
     #[must_use]
@@ -89,15 +142,123 @@ impl IpfsBuilder {
             enable_secure_websocket: false,
             ..Default::default()
         };
-        Self(self.0.set_transport_configuration(transport))
+        Self(
+            self.0.set_transport_configuration(transport),
+            self.1,
+            self.2,
+            self.3,
+        )
+    }
+
+    #[must_use]
+    /// Set the policy of reconnecting to the bootstrap and explicitly added peers.
+    /// Disconnected peers are not reconnected by default.
+    pub fn set_reconnect_policy(mut self, policy: ReconnectPolicy) -> Self {
+        self.1.set_policy(Some(policy));
+        self
+    }
+
+    #[must_use]
+    /// Join the private network of the swarm key, only the nodes sharing the key can
+    /// connect. Replaces the transport configuration with the TCP transport.
+    pub fn set_swarm_key(mut self, swarm_key: PreSharedKey) -> Self {
+        self.2.swarm_key = Some(swarm_key);
+        self
+    }
+
+    #[must_use]
+    /// Restrict the connections to the allowlist of peers, connections with any other
+    /// peer are closed once established.
+    pub fn set_allowed_peers(mut self, peers: impl IntoIterator<Item = PeerId>) -> Self {
+        self.2.allowed_peers = Some(peers.into_iter().collect::<HashSet<_>>());
+        self
+    }
+
+    #[must_use]
+    /// Collect the unpinned blocks automatically, while the local storage exceeds the
+    /// limit. The garbage collection policy of the node is consulted for every block.
+    /// Blocks are only collected by [`HermesIpfs::gc`] by default.
+    pub fn set_gc_options(mut self, options: GcOptions) -> Self {
+        self.3 = Some(options);
+        self
+    }
+
+    #[must_use]
+    /// Add the public IPFS bootstrap peers once the node is started.
+    /// Public bootstrap peers are not used by default.
+    pub fn with_public_bootstrap(mut self) -> Self {
+        self.2.public_bootstrap = true;
+        self
     }
 
     /// Start the IPFS node.
     ///
+    /// The private network configuration is applied, but the reconnection, garbage
+    /// collection, peer events and withdrawal of the provider records are only
+    /// available from the node started by [`IpfsBuilder::start_hermes`].
+    ///
     /// ## Errors
-    /// Returns an error if the IPFS daemon fails to start.
+    /// Returns an error if the IPFS daemon fails to start, or `PrivateNetworkError` if
+    /// the public bootstrap peers are used with the swarm key.
     pub async fn start(self) -> anyhow::Result<Ipfs> {
-        self.0.start().await
+        let node = self.start_hermes().await?;
+        // Background tasks are only stopped by `HermesIpfs::stop`.
+        for task in [node.reconnect_task, node.gc_task].into_iter().flatten() {
+            task.abort();
+        }
+        Ok(node.node)
+    }
+
+    /// Start the IPFS node, with the peer events, reconnection and garbage collection
+    /// attached to the returned `HermesIpfs`.
+    ///
+    /// ## Errors
+    /// Returns an error if the IPFS daemon fails to start, or `PrivateNetworkError` if
+    /// the public bootstrap peers are used with the swarm key.
+    pub async fn start_hermes(self) -> anyhow::Result<HermesIpfs> {
+        let Self(mut node, peers, network, gc_options) = self;
+        network.check()?;
+        if let Some(swarm_key) = network.swarm_key {
+            node = node.with_custom_transport(Box::new(
+                move |keypair: &rust_ipfs::Keypair, _: Option<RelayClientTransport>| {
+                    private_network::transport(keypair, swarm_key)
+                },
+            ));
+        }
+        let events = peers.clone();
+        let allowed = network.clone();
+        let (providers, withdrawals) = providers::channel();
+        let node = node
+            .swarm_events(move |swarm, event| {
+                withdrawals.handle(|cid| swarm.behaviour_mut().stop_providing_block(cid));
+                if let SwarmEvent::ConnectionEstablished { peer_id, .. } = event {
+                    if !allowed.is_allowed(peer_id) {
+                        let _ = swarm.disconnect_peer_id(*peer_id);
+                        return;
+                    }
+                }
+                if let Some(event) = PeerEvent::from_swarm_event(event) {
+                    events.notify(event);
+                }
+            })
+            .start()
+            .await?;
+        if network.public_bootstrap {
+            node.default_bootstrap().await?;
+        }
+        let reconnect_task = peers.spawn_reconnect(node.clone()).await?;
+        let gc_policy = SharedGcPolicy::default();
+        let gc_task = gc_options.map(|options| gc_policy.spawn_collect(node.clone(), options));
+        Ok(HermesIpfs {
+            node,
+            gc_policy,
+            gc_task,
+            peers,
+            reconnect_task,
+            keys: KeyNames::default(),
+            network,
+            providers: Some(providers),
+        })
     }
 }
 
@@ -107,10 +268,46 @@ impl Default for IpfsBuilder {
     }
 }
 
+/// Options of the DHT operations.
+#[derive(Debug, Clone)]
+pub struct DhtOptions {
+    /// Number of peers which must store the record for `dht_put` to succeed.
+    pub quorum: Quorum,
+    /// Maximum time to wait for the operation, waits until it finishes if `None`.
+    pub timeout: Option<Duration>,
+    /// Maximum number of records to collect in `dht_get_all`, unlimited if `None`.
+    pub max_records: Option<usize>,
+}
+
+impl Default for DhtOptions {
+    fn default() -> Self {
+        Self {
+            quorum: Quorum::One,
+            timeout: None,
+            max_records: None,
+        }
+    }
+}
+
 /// Hermes IPFS Node.
 pub struct HermesIpfs {
     /// IPFS node
     node: Ipfs,
+    /// Garbage collection policy
+    gc_policy: SharedGcPolicy,
+    /// Task collecting the unpinned blocks automatically
+    gc_task: Option<AbortHandle>,
+    /// Peer events and the peers to reconnect
+    peers: PeerTracker,
+    /// Task reconnecting to the disconnected peers
+    reconnect_task: Option<AbortHandle>,
+    /// Names of the keys added to the keystore
+    keys: KeyNames,
+    /// Private network configuration
+    network: PrivateNetwork,
+    /// Provider records to withdraw, `None` if the node is not started by
+    /// `IpfsBuilder::start_hermes`
+    providers: Option<ProviderWithdrawals>,
 }
 
 impl HermesIpfs {
@@ -124,14 +321,13 @@ impl HermesIpfs {
     ///
     /// Returns an error if the IPFS daemon fails to start.
     pub async fn start() -> anyhow::Result<Self> {
-        let node: Ipfs = IpfsBuilder::new()
+        IpfsBuilder::new()
             .with_default()
             .set_default_listener()
             // TODO(saibatizoku): Re-Enable default transport config when libp2p Cert bug is fixed
             .disable_tls()
-            .start()
-            .await?;
-        Ok(HermesIpfs { node })
+            .start_hermes()
+            .await
     }
 
     /// Add a file to IPFS.
@@ -171,6 +367,111 @@ impl HermesIpfs {
         Ok(stream_bytes.to_vec())
     }
 
+    /// Add a directory to IPFS, recursively.
+    ///
+    /// Files are added one by one, and are linked into a `UnixFS` directory tree which
+    /// preserves their names relative to `dir_path`. Symbolic links and empty
+    /// sub-directories are skipped.
+    ///
+    /// ## Parameters
+    ///
+    /// * `dir_path` - Path in local disk storage to the directory.
+    ///
+    /// ## Returns
+    ///
+    /// * A result with `IpfsDirectory`, holding the root CID and per-entry CIDs.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the directory cannot be read, or any of its files fails to
+    /// upload.
+    pub async fn add_ipfs_directory(
+        &self, dir_path: impl Into<PathBuf>,
+    ) -> anyhow::Result<IpfsDirectory> {
+        let mut opts = TreeOptions::default();
+        opts.wrap_with_directory();
+        let mut tree = BufferingTreeBuilder::new(opts);
+        let mut entries = Vec::new();
+
+        for (path, file_path) in list_directory_files(dir_path.into()).await? {
+            // The file size is used as an approximation of the total size of the file DAG.
+            let size = tokio::fs::metadata(&file_path).await?.len();
+            let ipfs_path = self.node.add_unixfs(file_path).await?;
+            let cid = *ipfs_path
+                .root()
+                .cid()
+                .ok_or(anyhow::anyhow!("Added file {path} has no CID"))?;
+            tree.put_link(&path, cid, size)?;
+            entries.push(IpfsDirectoryEntry::File { path, cid });
+        }
+
+        // Directory nodes are produced in post-order, so the root directory comes last.
+        let mut directories = Vec::new();
+        for node in tree.build() {
+            let node = node?;
+            self.node
+                .put_block(&Block::new(node.cid, node.block)?)
+                .await?;
+            directories.push(IpfsDirectoryEntry::Directory {
+                path: node.path,
+                cid: node.cid,
+            });
+        }
+        let root = directories
+            .pop()
+            .ok_or(anyhow::anyhow!("No root directory was built"))?
+            .cid();
+        entries.extend(directories);
+
+        Ok(IpfsDirectory { root, entries })
+    }
+
+    /// Get a directory from IPFS, recursively, and store it in local disk storage.
+    ///
+    /// ## Parameters
+    ///
+    /// * `ipfs_path` - `GetIpfsFile(IpfsPath)` Path used to get the directory from IPFS.
+    /// * `dest` - Path in local disk storage where the directory contents are stored.
+    ///
+    /// ## Returns
+    ///
+    /// * A result with `IpfsDirectory`, holding the root CID and per-entry CIDs.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the directory fails to download, contains invalid entry
+    /// paths, or cannot be stored.
+    pub async fn get_ipfs_directory(
+        &self, ipfs_path: GetIpfsFile, dest: impl AsRef<Path>,
+    ) -> anyhow::Result<IpfsDirectory> {
+        let dest = dest.as_ref();
+        let mut root = None;
+        let mut entries = Vec::new();
+
+        for entry in self.node.ls_unixfs(ipfs_path).await? {
+            match entry {
+                UnixfsEntry::RootDirectory { cid, .. } => root = Some(cid),
+                UnixfsEntry::Directory { cid, path } => {
+                    tokio::fs::create_dir_all(dest.join(entry_path(&path)?)).await?;
+                    entries.push(IpfsDirectoryEntry::Directory { path, cid });
+                },
+                UnixfsEntry::File { cid, file, .. } => {
+                    let target = dest.join(entry_path(&file)?);
+                    if let Some(parent) = target.parent() {
+                        tokio::fs::create_dir_all(parent).await?;
+                    }
+                    let bytes = self.node.cat_unixfs(IpfsPath::from(cid)).await?;
+                    tokio::fs::write(target, bytes).await?;
+                    entries.push(IpfsDirectoryEntry::File { path: file, cid });
+                },
+                UnixfsEntry::Error { error } => return Err(error),
+            }
+        }
+
+        let root = root.ok_or(anyhow::anyhow!("IPFS path is not a directory"))?;
+        Ok(IpfsDirectory { root, entries })
+    }
+
     /// Pin content to IPFS.
     ///
     /// ## Parameters
@@ -241,8 +542,46 @@ impl HermesIpfs {
         self.node.remove_pin(cid).recursive().await
     }
 
+    /// Sets the garbage collection policy, consulted during [`HermesIpfs::gc`] and the
+    /// automatic garbage collection.
+    ///
+    /// ## Parameters
+    ///
+    /// * `policy` - `impl GcPolicy`
+    pub fn set_gc_policy(&mut self, policy: impl GcPolicy + 'static) {
+        self.gc_policy.set(Some(Arc::new(policy)));
+    }
+
+    /// Removes the garbage collection policy, so [`HermesIpfs::gc`] and the automatic
+    /// garbage collection collect every unpinned block.
+    pub fn clear_gc_policy(&mut self) {
+        self.gc_policy.set(None);
+    }
+
+    /// Run garbage collection of the unpinned blocks.
+    ///
+    /// If a garbage collection policy is set, every unpinned block is checked with
+    /// [`GcPolicy::may_collect`], and only the allowed ones are removed.
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<Vec<Cid>>` - List of removed blocks.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the garbage collection fails.
+    pub async fn gc(&self) -> anyhow::Result<Vec<Cid>> {
+        self.gc_policy.collect(&self.node).await
+    }
+
     /// Stop and exit the IPFS node daemon.
     pub async fn stop(self) {
+        if let Some(reconnect_task) = self.reconnect_task {
+            reconnect_task.abort();
+        }
+        if let Some(gc_task) = self.gc_task {
+            gc_task.abort();
+        }
         self.node.exit_daemon().await;
     }
 
@@ -261,6 +600,7 @@ impl HermesIpfs {
     }
 
     /// Add peer to address book.
+    /// The peer is reconnected once disconnected, if required by the `ReconnectPolicy`.
     ///
     /// ## Parameters
     ///
@@ -269,9 +609,25 @@ impl HermesIpfs {
     ///
     /// ## Errors
     ///
-    /// Returns error if unable to add peer.
+    /// Returns error if unable to add peer, or `PrivateNetworkError` if the peer is not
+    /// in the allowlist.
     pub async fn add_peer(&self, peer_id: PeerId, addr: Multiaddr) -> anyhow::Result<()> {
-        self.node.add_peer((peer_id, addr)).await
+        self.network.check_peer(&peer_id)?;
+        self.node.add_peer((peer_id, addr)).await?;
+        self.peers.track_added(peer_id).await;
+        Ok(())
+    }
+
+    /// Returns a stream of peer connection lifecycle events: established and closed
+    /// connections, dial failures, bans and failed reconnections.
+    /// Only events which occur after the call are returned.
+    ///
+    /// ## Returns
+    ///
+    /// * `BoxStream<'static, PeerEvent>`
+    #[must_use]
+    pub fn swarm_events(&self) -> BoxStream<'static, PeerEvent> {
+        self.peers.subscribe()
     }
 
     /// List of local listening addresses
@@ -355,7 +711,38 @@ impl HermesIpfs {
     pub async fn dht_put(
         &self, key: impl AsRef<[u8]>, value: impl Into<Vec<u8>>,
     ) -> anyhow::Result<()> {
-        self.node.dht_put(key, value, Quorum::One).await
+        self.dht_put_with_options(key, value, &DhtOptions::default())
+            .await
+    }
+
+    /// Add content to DHT, using the provided options.
+    ///
+    /// ## Parameters
+    ///
+    /// * `key` - `impl AsRef<[u8]>`
+    /// * `value` - `impl Into<Vec<u8>>`
+    /// * `options` - `DhtOptions`
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<()>`
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if unable to add content to DHT, or if the quorum is not reached
+    /// within the timeout.
+    pub async fn dht_put_with_options(
+        &self, key: impl AsRef<[u8]>, value: impl Into<Vec<u8>>, options: &DhtOptions,
+    ) -> anyhow::Result<()> {
+        let put = self.node.dht_put(key, value, options.quorum);
+        match options.timeout {
+            Some(timeout) => {
+                tokio::time::timeout(timeout, put)
+                    .await
+                    .map_err(|_| anyhow::anyhow!("DHT put timed out after {timeout:?}"))?
+            },
+            None => put.await,
+        }
     }
 
     /// Get content from DHT.
@@ -372,16 +759,160 @@ impl HermesIpfs {
     ///
     /// Returns error if unable to get content from DHT
     pub async fn dht_get(&self, key: impl AsRef<[u8]>) -> anyhow::Result<Vec<u8>> {
+        self.dht_get_with_options(key, &DhtOptions::default()).await
+    }
+
+    /// Get the first record found in DHT, using the provided options.
+    /// `DhtOptions::max_records` is ignored.
+    ///
+    /// ## Parameters
+    ///
+    /// * `key` - `impl AsRef<[u8]>`
+    /// * `options` - `DhtOptions`
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<Vec<u8>>`
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if unable to get content from DHT, or if no record is found within
+    /// the timeout.
+    pub async fn dht_get_with_options(
+        &self, key: impl AsRef<[u8]>, options: &DhtOptions,
+    ) -> anyhow::Result<Vec<u8>> {
+        let options = DhtOptions {
+            max_records: Some(1),
+            ..options.clone()
+        };
+        self.dht_get_all(key, &options)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(anyhow::anyhow!("No record found"))
+    }
+
+    /// Get all records found in DHT.
+    /// Records are collected until the lookup finishes, `DhtOptions::max_records`
+    /// records are found or `DhtOptions::timeout` elapses, whichever comes first.
+    /// Records may conflict with each other, resolving them is left to the caller.
+    ///
+    /// ## Parameters
+    ///
+    /// * `key` - `impl AsRef<[u8]>`
+    /// * `options` - `DhtOptions`
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<Vec<Vec<u8>>>`
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if unable to start the DHT lookup.
+    pub async fn dht_get_all(
+        &self, key: impl AsRef<[u8]>, options: &DhtOptions,
+    ) -> anyhow::Result<Vec<Vec<u8>>> {
         let record_stream = self.node.dht_get(key).await?;
         pin_mut!(record_stream);
-        let record = record_stream
-            .next()
+        let max_records = options.max_records.unwrap_or(usize::MAX);
+        let deadline = options
+            .timeout
+            .map(|timeout| tokio::time::Instant::now() + timeout);
+        let mut records = Vec::new();
+        while records.len() < max_records {
+            let record = match deadline {
+                Some(deadline) => {
+                    let Ok(record) = tokio::time::timeout_at(deadline, record_stream.next()).await
+                    else {
+                        break;
+                    };
+                    record
+                },
+                None => record_stream.next().await,
+            };
+            let Some(record) = record else {
+                break;
+            };
+            records.push(record.value);
+        }
+        Ok(records)
+    }
+
+    /// Announce this node as a provider of the content with the given `cid`.
+    /// The provider record is published to the peers closest to the `cid`, and is
+    /// periodically re-published by the node.
+    ///
+    /// ## Parameters
+    ///
+    /// * `cid` - `Cid`
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<()>`
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if the content is not stored locally, or if unable to publish the
+    /// provider record.
+    pub async fn provide(&self, cid: Cid) -> anyhow::Result<()> {
+        self.node.provide(cid).await
+    }
+
+    /// Find the peers which provide the content with the given `cid`.
+    ///
+    /// ## Parameters
+    ///
+    /// * `cid` - `Cid`
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<BoxStream<'static, PeerId>>`
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if unable to start the DHT providers lookup.
+    pub async fn get_providers(&self, cid: Cid) -> anyhow::Result<BoxStream<'static, PeerId>> {
+        self.node.get_providers(cid).await
+    }
+
+    /// Stop announcing this node as a provider of the content with the given `cid`.
+    /// The provider record is no longer re-published nor returned by the node, the
+    /// records already published to the other peers expire.
+    ///
+    /// ## Parameters
+    ///
+    /// * `cid` - `Cid`
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<()>`
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if the node is not started by [`IpfsBuilder::start_hermes`], if
+    /// unable to start the DHT providers lookup, or if the provider record is not
+    /// withdrawn in time.
+    pub async fn stop_providing(&self, cid: Cid) -> anyhow::Result<()> {
+        let Some(providers) = &self.providers else {
+            anyhow::bail!(
+                "Provider records are only withdrawn by the node started by `start_hermes`"
+            );
+        };
+        let withdrawn = providers.withdraw(cid)?;
+        // The record is removed on the next swarm event, the providers lookup emits one
+        // right away.
+        let _providers = self.node.get_providers(cid).await?;
+        tokio::time::timeout(WITHDRAWAL_TIMEOUT, withdrawn)
             .await
-            .ok_or(anyhow::anyhow!("No record found"))?;
-        Ok(record.value)
+            .map_err(|_| {
+                anyhow::anyhow!("Provider record withdrawal timed out after {WITHDRAWAL_TIMEOUT:?}")
+            })??;
+        Ok(())
     }
 
     /// Add address to bootstrap nodes.
+    /// The bootstrap peer is reconnected once disconnected, if required by the
+    /// `ReconnectPolicy` and the address contains the peer id.
     ///
     /// ## Parameters
     ///
@@ -393,9 +924,14 @@ impl HermesIpfs {
     ///
     /// ## Errors
     ///
-    /// Returns error if unable to add address to bootstrap nodes
+    /// Returns error if unable to add address to bootstrap nodes, or
+    /// `PrivateNetworkError` if it is a public bootstrap peer of a private network or its
+    /// peer is not in the allowlist.
     pub async fn add_bootstrap(&self, address: Multiaddr) -> anyhow::Result<Multiaddr> {
-        self.node.add_bootstrap(address).await
+        self.network.check_bootstrap(&address)?;
+        let address = self.node.add_bootstrap(address).await?;
+        self.peers.track_bootstrap(&address).await;
+        Ok(address)
     }
 
     /// Bootstrap the IPFS node.
@@ -490,6 +1026,7 @@ impl HermesIpfs {
     }
 
     /// Ban peer from node.
+    /// The banned peer is no longer reconnected.
     ///
     /// ## Parameters
     ///
@@ -503,13 +1040,47 @@ impl HermesIpfs {
     ///
     /// Returns error if unable to ban peer.
     pub async fn ban_peer(&self, peer: PeerId) -> anyhow::Result<()> {
-        self.node.ban_peer(peer).await
+        self.node.ban_peer(peer).await?;
+        self.peers.untrack(&peer).await;
+        self.peers.notify(PeerEvent::PeerBanned { peer_id: peer });
+        Ok(())
+    }
+
+    /// Unban peer from node.
+    ///
+    /// ## Parameters
+    ///
+    /// * `peer` - `PeerId`
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<()>`
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if unable to unban peer.
+    pub async fn unban_peer(&self, peer: PeerId) -> anyhow::Result<()> {
+        self.node.unban_peer(peer).await?;
+        self.peers.notify(PeerEvent::PeerUnbanned { peer_id: peer });
+        Ok(())
     }
 }
 
+/// Swarm events of the node started without the [`IpfsBuilder`] are not observed, so
+/// [`HermesIpfs::swarm_events`] only returns the ban events, and peers are not
+/// reconnected.
 impl From<Ipfs> for HermesIpfs {
     fn from(node: Ipfs) -> Self {
-        Self { node }
+        Self {
+            node,
+            gc_policy: SharedGcPolicy::default(),
+            gc_task: None,
+            peers: PeerTracker::new(),
+            reconnect_task: None,
+            keys: KeyNames::default(),
+            network: PrivateNetwork::default(),
+            providers: None,
+        }
     }
 }
 
@@ -563,6 +1134,98 @@ impl From<(Option<String>, Vec<u8>)> for AddIpfsFile {
     }
 }
 
+/// Directory added to, or retrieved from IPFS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IpfsDirectory {
+    /// CID of the root directory.
+    pub root: Cid,
+    /// Files and sub-directories, with paths relative to the root directory.
+    pub entries: Vec<IpfsDirectoryEntry>,
+}
+
+/// Entry of an `IpfsDirectory`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpfsDirectoryEntry {
+    /// Sub-directory.
+    Directory {
+        /// Path relative to the root directory.
+        path: String,
+        /// CID of the sub-directory.
+        cid: Cid,
+    },
+    /// File.
+    File {
+        /// Path relative to the root directory.
+        path: String,
+        /// CID of the file.
+        cid: Cid,
+    },
+}
+
+impl IpfsDirectoryEntry {
+    /// Path relative to the root directory.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Directory { path, .. } | Self::File { path, .. } => path,
+        }
+    }
+
+    /// CID of the entry.
+    #[must_use]
+    pub fn cid(&self) -> Cid {
+        match self {
+            Self::Directory { cid, .. } | Self::File { cid, .. } => *cid,
+        }
+    }
+}
+
+/// List all files in the directory recursively, sorted by their paths.
+///
+/// Returns pairs of the `/` separated path relative to `dir_path`, and the full path.
+async fn list_directory_files(dir_path: PathBuf) -> anyhow::Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    let mut pending = vec![(String::new(), dir_path)];
+
+    while let Some((prefix, dir)) = pending.pop() {
+        let mut read_dir = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = read_dir.next_entry().await? {
+            let name = entry.file_name();
+            let name = name
+                .to_str()
+                .ok_or(anyhow::anyhow!("Non UTF-8 file name in {}", dir.display()))?;
+            let path = if prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{prefix}/{name}")
+            };
+            let file_type = entry.file_type().await?;
+            if file_type.is_dir() {
+                pending.push((path, entry.path()));
+            } else if file_type.is_file() {
+                files.push((path, entry.path()));
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Convert a directory entry path received from IPFS into a relative local path,
+/// rejecting any path which could escape the destination directory.
+fn entry_path(path: &str) -> anyhow::Result<PathBuf> {
+    let entry_path = Path::new(path);
+    if path.is_empty()
+        || !entry_path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+    {
+        anyhow::bail!("Invalid directory entry path: {path}");
+    }
+    Ok(entry_path.to_path_buf())
+}
+
 /// Path to get the file from IPFS
 pub struct GetIpfsFile(IpfsPath);
 
@@ -603,3 +1266,129 @@ pub fn subscription_stream_task(
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Is the block stored by the node.
+    async fn contains(node: &HermesIpfs, cid: &Cid) -> bool {
+        node.node.repo().contains(cid).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn automatic_gc() {
+        let mut node = IpfsBuilder::new()
+            .set_gc_options(GcOptions {
+                interval: Duration::from_millis(50),
+                storage_limit: 0,
+            })
+            .start_hermes()
+            .await
+            .unwrap();
+
+        // Blocks are pinned when added, so they are not collected before they are set up.
+        let kept = node
+            .node
+            .put_dag(Ipld::String("kept by the policy".to_string()))
+            .pin(true)
+            .await
+            .unwrap();
+        node.set_gc_policy(move |cid: &Cid| *cid != kept);
+        node.remove_pin(&kept).await.unwrap();
+        let pinned = node
+            .node
+            .put_dag(Ipld::String("pinned".to_string()))
+            .pin(true)
+            .await
+            .unwrap();
+        let unpinned = node
+            .dag_put(Ipld::String("unpinned".to_string()))
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while contains(&node, &unpinned).await {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(contains(&node, &pinned).await);
+        assert!(contains(&node, &kept).await);
+        node.stop().await;
+    }
+
+    #[tokio::test]
+    async fn automatic_gc_below_storage_limit() {
+        let node = IpfsBuilder::new()
+            .set_gc_options(GcOptions {
+                interval: Duration::from_millis(50),
+                storage_limit: 1024 * 1024,
+            })
+            .start_hermes()
+            .await
+            .unwrap();
+
+        let unpinned = node
+            .dag_put(Ipld::String("unpinned".to_string()))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(contains(&node, &unpinned).await);
+        node.stop().await;
+    }
+
+    /// Providers of the content found by the node.
+    async fn providers(node: &HermesIpfs, cid: Cid) -> Vec<PeerId> {
+        let providers = node.get_providers(cid).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(10), providers.collect())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn provide_and_stop_providing() {
+        let node = IpfsBuilder::new()
+            .with_default()
+            .start_hermes()
+            .await
+            .unwrap();
+        let peer_id = node.identity(None).await.unwrap();
+        let cid = node
+            .dag_put(Ipld::String("provided".to_string()))
+            .await
+            .unwrap();
+        assert!(!providers(&node, cid).await.contains(&peer_id));
+
+        node.provide(cid).await.unwrap();
+        assert!(providers(&node, cid).await.contains(&peer_id));
+
+        node.stop_providing(cid).await.unwrap();
+        assert!(!providers(&node, cid).await.contains(&peer_id));
+
+        // Withdrawing the record again, or the record of the content which was never
+        // provided, completes too.
+        node.stop_providing(cid).await.unwrap();
+        let unprovided = node
+            .dag_put(Ipld::String("unprovided".to_string()))
+            .await
+            .unwrap();
+        node.stop_providing(unprovided).await.unwrap();
+        assert!(!providers(&node, unprovided).await.contains(&peer_id));
+        node.stop().await;
+    }
+
+    #[tokio::test]
+    async fn stop_providing_requires_builder() {
+        // The plain `Ipfs` returned by `start` has no provider records withdrawal.
+        let node = HermesIpfs::from(IpfsBuilder::new().with_default().start().await.unwrap());
+        let cid = node
+            .dag_put(Ipld::String("provided".to_string()))
+            .await
+            .unwrap();
+        node.provide(cid).await.unwrap();
+        assert!(node.stop_providing(cid).await.is_err());
+        node.stop().await;
+    }
+}
@@ -0,0 +1,351 @@
+//! Peer connection lifecycle events and the reconnect policy.
+//!
+//! Swarm events of the node are translated into [`PeerEvent`]s and broadcast to every
+//! [`crate::HermesIpfs::swarm_events`] stream. Peers tracked by the [`ReconnectPolicy`]
+//! are dialed again, with an exponential backoff, once their last connection is closed.
+
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use rust_ipfs::libp2p::{futures::stream, swarm::SwarmEvent};
+use tokio::{
+    sync::{broadcast, Mutex},
+    task::{AbortHandle, JoinSet},
+};
+
+use crate::{BoxStream, Ipfs, Multiaddr, PeerId, StreamExt};
+
+/// Number of the peer events buffered for each `swarm_events` stream, the slowest
+/// streams skip the oldest events once it is exceeded.
+const PEER_EVENTS_CAPACITY: usize = 256;
+
+/// Peer connection lifecycle event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PeerEvent {
+    /// A connection to the peer is established.
+    ConnectionEstablished {
+        /// Connected peer.
+        peer_id: PeerId,
+        /// Remote address of the connection.
+        address: Multiaddr,
+        /// `true` if the connection was dialed by this node.
+        outbound: bool,
+        /// Number of the established connections to the peer, including this one.
+        connections: u32,
+    },
+    /// A connection to the peer is closed.
+    ConnectionClosed {
+        /// Disconnected peer.
+        peer_id: PeerId,
+        /// Number of the remaining connections to the peer.
+        connections: u32,
+        /// Reason the connection was closed, `None` if it was closed gracefully.
+        cause: Option<String>,
+    },
+    /// Dialing a peer failed.
+    DialFailure {
+        /// Dialed peer, if known.
+        peer_id: Option<PeerId>,
+        /// Description of the failure.
+        error: String,
+    },
+    /// The peer is banned.
+    PeerBanned {
+        /// Banned peer.
+        peer_id: PeerId,
+    },
+    /// The peer is unbanned.
+    PeerUnbanned {
+        /// Unbanned peer.
+        peer_id: PeerId,
+    },
+    /// Reconnecting to the peer is given up after all attempts allowed by the
+    /// [`ReconnectPolicy`] failed.
+    ReconnectFailed {
+        /// Peer which could not be reconnected.
+        peer_id: PeerId,
+        /// Number of the failed attempts.
+        attempts: u32,
+    },
+}
+
+impl PeerEvent {
+    /// Translate the swarm event, `None` if it is not a peer connection event.
+    pub(crate) fn from_swarm_event<T>(event: &SwarmEvent<T>) -> Option<Self> {
+        match event {
+            SwarmEvent::ConnectionEstablished {
+                peer_id,
+                endpoint,
+                num_established,
+                ..
+            } => {
+                Some(Self::ConnectionEstablished {
+                    peer_id: *peer_id,
+                    address: endpoint.get_remote_address().clone(),
+                    outbound: endpoint.is_dialer(),
+                    connections: num_established.get(),
+                })
+            },
+            SwarmEvent::ConnectionClosed {
+                peer_id,
+                num_established,
+                cause,
+                ..
+            } => {
+                Some(Self::ConnectionClosed {
+                    peer_id: *peer_id,
+                    connections: *num_established,
+                    cause: cause.as_ref().map(ToString::to_string),
+                })
+            },
+            SwarmEvent::OutgoingConnectionError { peer_id, error, .. } => {
+                Some(Self::DialFailure {
+                    peer_id: *peer_id,
+                    error: error.to_string(),
+                })
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Policy of reconnecting to the bootstrap and explicitly added peers, once their last
+/// connection is closed.
+///
+/// The `n`-th attempt is made after `initial_backoff * 2^n`, but no longer than
+/// `max_backoff`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// Reconnect to the bootstrap peers.
+    pub bootstrap_peers: bool,
+    /// Reconnect to the peers added with [`crate::HermesIpfs::add_peer`].
+    pub added_peers: bool,
+    /// Maximum number of the attempts for each disconnection, unlimited if `None`.
+    pub max_attempts: Option<u32>,
+    /// Delay before the first attempt.
+    pub initial_backoff: Duration,
+    /// Maximum delay between the attempts.
+    pub max_backoff: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            bootstrap_peers: true,
+            added_peers: true,
+            max_attempts: Some(10),
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Delay before the `attempt`-th reconnection attempt, counting from zero, `None`
+    /// if no more attempts are allowed.
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Option<Duration> {
+        if self.max_attempts.is_some_and(|max| attempt >= max) {
+            return None;
+        }
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt));
+        Some(backoff.min(self.max_backoff))
+    }
+}
+
+/// Broadcasts the peer events, and tracks the peers to reconnect.
+#[derive(Clone)]
+pub(crate) struct PeerTracker {
+    /// Sender of the peer events.
+    events: broadcast::Sender<PeerEvent>,
+    /// Reconnect policy, reconnecting is disabled if `None`.
+    policy: Option<ReconnectPolicy>,
+    /// Peers to reconnect.
+    peers: Arc<Mutex<HashSet<PeerId>>>,
+}
+
+impl PeerTracker {
+    /// Create a new `PeerTracker`, without the reconnect policy.
+    pub(crate) fn new() -> Self {
+        let (events, _) = broadcast::channel(PEER_EVENTS_CAPACITY);
+        Self {
+            events,
+            policy: None,
+            peers: Arc::default(),
+        }
+    }
+
+    /// Set the reconnect policy.
+    pub(crate) fn set_policy(&mut self, policy: Option<ReconnectPolicy>) {
+        self.policy = policy;
+    }
+
+    /// Broadcast the peer event.
+    pub(crate) fn notify(&self, event: PeerEvent) {
+        // Fails only if there are no subscribers.
+        drop(self.events.send(event));
+    }
+
+    /// Stream of the peer events, starting from the next event.
+    pub(crate) fn subscribe(&self) -> BoxStream<'static, PeerEvent> {
+        stream::unfold(self.events.subscribe(), |mut events| {
+            async move {
+                loop {
+                    match events.recv().await {
+                        Ok(event) => return Some((event, events)),
+                        // Skipped events are lost for this stream only.
+                        Err(broadcast::error::RecvError::Lagged(_)) => {},
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    }
+                }
+            }
+        })
+        .boxed()
+    }
+
+    /// Track the bootstrap peer to reconnect, if required by the policy and the address
+    /// contains the peer id.
+    pub(crate) async fn track_bootstrap(&self, address: &Multiaddr) {
+        let Some(peer_id) = peer_id_of(address) else {
+            return;
+        };
+        if self.policy.as_ref().is_some_and(|p| p.bootstrap_peers) {
+            self.peers.lock().await.insert(peer_id);
+        }
+    }
+
+    /// Track the explicitly added peer to reconnect, if required by the policy.
+    pub(crate) async fn track_added(&self, peer_id: PeerId) {
+        if self.policy.as_ref().is_some_and(|p| p.added_peers) {
+            self.peers.lock().await.insert(peer_id);
+        }
+    }
+
+    /// Stop reconnecting to the peer.
+    pub(crate) async fn untrack(&self, peer_id: &PeerId) {
+        self.peers.lock().await.remove(peer_id);
+    }
+
+    /// Spawn the task reconnecting to the tracked peers of the node, tracking its
+    /// current bootstrap peers. Returns `None` if there is no reconnect policy.
+    ///
+    /// The task runs until it is aborted, aborting it stops all pending reconnections.
+    pub(crate) async fn spawn_reconnect(&self, node: Ipfs) -> anyhow::Result<Option<AbortHandle>> {
+        let Some(policy) = self.policy.clone() else {
+            return Ok(None);
+        };
+        for address in node.get_bootstraps().await? {
+            self.track_bootstrap(&address).await;
+        }
+
+        let mut events = self.events.subscribe();
+        let tracker = self.clone();
+        let task = tokio::spawn(async move {
+            // Reconnections are aborted once the set is dropped.
+            let mut reconnections = JoinSet::new();
+            let mut reconnecting = HashSet::new();
+            loop {
+                let peer_id = match events.recv().await {
+                    Ok(PeerEvent::ConnectionClosed {
+                        peer_id,
+                        connections: 0,
+                        ..
+                    }) => peer_id,
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                while let Some(finished) = reconnections.try_join_next() {
+                    if let Ok(finished) = finished {
+                        reconnecting.remove(&finished);
+                    }
+                }
+                if reconnecting.contains(&peer_id) || !tracker.peers.lock().await.contains(&peer_id)
+                {
+                    continue;
+                }
+                reconnecting.insert(peer_id);
+                reconnections.spawn(reconnect(
+                    node.clone(),
+                    tracker.clone(),
+                    policy.clone(),
+                    peer_id,
+                ));
+            }
+        });
+        Ok(Some(task.abort_handle()))
+    }
+}
+
+/// Reconnect to the peer following the policy, until it is connected, untracked, or
+/// the attempts are exhausted. Returns the peer id.
+async fn reconnect(
+    node: Ipfs, tracker: PeerTracker, policy: ReconnectPolicy, peer_id: PeerId,
+) -> PeerId {
+    let mut attempt = 0;
+    while let Some(backoff) = policy.backoff(attempt) {
+        tokio::time::sleep(backoff).await;
+        if !tracker.peers.lock().await.contains(&peer_id)
+            || node.is_connected(peer_id).await.unwrap_or(false)
+            || node.connect(peer_id).await.is_ok()
+        {
+            return peer_id;
+        }
+        attempt = attempt.saturating_add(1);
+    }
+    tracker.notify(PeerEvent::ReconnectFailed {
+        peer_id,
+        attempts: attempt,
+    });
+    peer_id
+}
+
+/// Peer id of the `/p2p/<peer id>` address component.
+pub(crate) fn peer_id_of(address: &Multiaddr) -> Option<PeerId> {
+    address.iter().find_map(|protocol| {
+        match protocol {
+            rust_ipfs::Protocol::P2p(peer_id) => Some(peer_id),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconnect_backoff() {
+        let policy = ReconnectPolicy {
+            max_attempts: Some(4),
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+            ..ReconnectPolicy::default()
+        };
+        let backoffs: Vec<_> = (0..5).map(|attempt| policy.backoff(attempt)).collect();
+        assert_eq!(backoffs, vec![
+            Some(Duration::from_secs(1)),
+            Some(Duration::from_secs(2)),
+            Some(Duration::from_secs(4)),
+            Some(Duration::from_secs(5)),
+            None,
+        ]);
+
+        let policy = ReconnectPolicy {
+            max_attempts: None,
+            ..policy
+        };
+        assert_eq!(policy.backoff(u32::MAX), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn bootstrap_peer_id() {
+        let peer_id = rust_ipfs::Keypair::generate_ed25519().public().to_peer_id();
+        let address: Multiaddr = format!("/ip4/127.0.0.1/tcp/4001/p2p/{peer_id}")
+            .parse()
+            .unwrap();
+        assert_eq!(peer_id_of(&address), Some(peer_id));
+        let address: Multiaddr = "/ip4/127.0.0.1/tcp/4001".parse().unwrap();
+        assert_eq!(peer_id_of(&address), None);
+    }
+}
@@ -0,0 +1,168 @@
+//! Private network of the nodes sharing a swarm key.
+//!
+//! Every connection of a private network is encrypted with the pre-shared swarm key, the
+//! key of the `swarm.key` file of the IPFS private networks, so only the nodes sharing
+//! it can connect to each other. Private networks use the TCP transport only.
+//!
+//! Connections can be further restricted to an allowlist of peers, with or without the
+//! swarm key. Connections with any other peer are closed as soon as they are
+//! established.
+
+use std::{collections::HashSet, io, time::Duration};
+
+use libp2p_pnet::PnetConfig;
+/// Pre-shared swarm key of a private network, parsed from the `swarm.key` file format.
+pub use libp2p_pnet::PreSharedKey;
+use rust_ipfs::{
+    config::BOOTSTRAP_NODES,
+    libp2p::{
+        core::{
+            muxing::StreamMuxerBox,
+            transport::{upgrade::Version, Boxed},
+        },
+        dns, noise, tcp, yamux, Transport,
+    },
+    Keypair,
+};
+
+use crate::{peer_events::peer_id_of, Multiaddr, PeerId};
+
+/// Timeout of upgrading a private network connection.
+const UPGRADE_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Private network configuration error.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PrivateNetworkError {
+    /// Public bootstrap peers are used with the swarm key of a private network.
+    #[error("Public bootstrap peers cannot be used with a private network swarm key")]
+    PublicBootstrap,
+    /// Peer is not in the allowlist.
+    #[error("Peer {0} is not in the allowlist")]
+    PeerNotAllowed(PeerId),
+}
+
+/// Private network configuration of the node.
+#[derive(Clone, Default)]
+pub(crate) struct PrivateNetwork {
+    /// Swarm key of the private network, the network is public if `None`.
+    pub(crate) swarm_key: Option<PreSharedKey>,
+    /// Peers allowed to connect, any peer is allowed if `None`.
+    pub(crate) allowed_peers: Option<HashSet<PeerId>>,
+    /// Connect to the public IPFS bootstrap peers once the node is started.
+    pub(crate) public_bootstrap: bool,
+}
+
+impl PrivateNetwork {
+    /// Check the configuration does not mix the private and public networks.
+    pub(crate) fn check(&self) -> Result<(), PrivateNetworkError> {
+        if self.swarm_key.is_some() && self.public_bootstrap {
+            return Err(PrivateNetworkError::PublicBootstrap);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if connections with the peer are allowed.
+    pub(crate) fn is_allowed(&self, peer_id: &PeerId) -> bool {
+        self.allowed_peers
+            .as_ref()
+            .is_none_or(|peers| peers.contains(peer_id))
+    }
+
+    /// Check the peer is allowed.
+    pub(crate) fn check_peer(&self, peer_id: &PeerId) -> Result<(), PrivateNetworkError> {
+        if !self.is_allowed(peer_id) {
+            return Err(PrivateNetworkError::PeerNotAllowed(*peer_id));
+        }
+        Ok(())
+    }
+
+    /// Check the bootstrap address can be used, it must not be a public bootstrap peer
+    /// of a private network, and its peer must be allowed if the address contains the
+    /// peer id.
+    pub(crate) fn check_bootstrap(&self, address: &Multiaddr) -> Result<(), PrivateNetworkError> {
+        if self.swarm_key.is_some()
+            && BOOTSTRAP_NODES
+                .iter()
+                .any(|public| public.parse::<Multiaddr>().is_ok_and(|a| &a == address))
+        {
+            return Err(PrivateNetworkError::PublicBootstrap);
+        }
+        match peer_id_of(address) {
+            Some(peer_id) => self.check_peer(&peer_id),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Build the private network TCP transport, every connection is encrypted with the swarm
+/// key before it is authenticated and multiplexed. Relayed connections are not
+/// supported.
+pub(crate) fn transport(
+    keypair: &Keypair, swarm_key: PreSharedKey,
+) -> io::Result<Boxed<(PeerId, StreamMuxerBox)>> {
+    let tcp = tcp::tokio::Transport::new(tcp::Config::default().nodelay(true));
+    let transport = dns::tokio::Transport::system(tcp)?
+        .and_then(move |socket, _| PnetConfig::new(swarm_key).handshake(socket))
+        .upgrade(Version::V1Lazy)
+        .authenticate(noise::Config::new(keypair).map_err(io::Error::other)?)
+        .multiplex(yamux::Config::default())
+        .timeout(UPGRADE_TIMEOUT)
+        .boxed();
+    Ok(transport)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    /// Swarm key in the `swarm.key` file format.
+    const SWARM_KEY: &str = "/key/swarm/psk/1.0.0/\n/base16/\n\
+                             6c8b8a1ec5b8e1ee6b2b3d2d7f1e3a9c0f4d5e6a7b8c9d0e1f2a3b4c5d6e7f80";
+
+    #[test]
+    fn public_bootstrap_with_swarm_key() {
+        let public: Multiaddr = BOOTSTRAP_NODES
+            .first()
+            .and_then(|a| a.parse().ok())
+            .unwrap();
+        let mut network = PrivateNetwork {
+            public_bootstrap: true,
+            ..Default::default()
+        };
+        assert!(network.check().is_ok());
+        assert!(network.check_bootstrap(&public).is_ok());
+
+        network.swarm_key = Some(PreSharedKey::from_str(SWARM_KEY).unwrap());
+        assert_eq!(network.check(), Err(PrivateNetworkError::PublicBootstrap));
+        assert_eq!(
+            network.check_bootstrap(&public),
+            Err(PrivateNetworkError::PublicBootstrap)
+        );
+        let private: Multiaddr = "/ip4/10.0.0.1/tcp/4001".parse().unwrap();
+        assert!(network.check_bootstrap(&private).is_ok());
+    }
+
+    #[test]
+    fn allowed_peers() {
+        let allowed = Keypair::generate_ed25519().public().to_peer_id();
+        let other = Keypair::generate_ed25519().public().to_peer_id();
+        let mut network = PrivateNetwork::default();
+        assert!(network.is_allowed(&other));
+
+        network.allowed_peers = Some(HashSet::from([allowed]));
+        assert!(network.is_allowed(&allowed));
+        assert_eq!(
+            network.check_peer(&other),
+            Err(PrivateNetworkError::PeerNotAllowed(other))
+        );
+        let address: Multiaddr = format!("/ip4/10.0.0.1/tcp/4001/p2p/{other}")
+            .parse()
+            .unwrap();
+        assert_eq!(
+            network.check_bootstrap(&address),
+            Err(PrivateNetworkError::PeerNotAllowed(other))
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! Withdrawal of the provider records.
+//!
+//! `rust_ipfs` has no command to remove a provider record, it is only removed from the
+//! Kademlia behaviour, which is reachable by the swarm events handler of the node.
+//! [`crate::HermesIpfs::stop_providing`] sends the record through a channel to the
+//! handler, and wakes the swarm task with a providers lookup of the record, which emits a
+//! swarm event right away.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use tokio::sync::{mpsc, oneshot};
+
+use crate::Cid;
+
+/// Time to wait for the swarm events handler to remove the provider record.
+pub(crate) const WITHDRAWAL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Provider record to remove, and the sender notified once it is removed.
+type Withdrawal = (Cid, oneshot::Sender<()>);
+
+/// Sender of the provider records to remove.
+#[derive(Clone)]
+pub(crate) struct ProviderWithdrawals {
+    /// Sender of the provider records.
+    requests: mpsc::UnboundedSender<Withdrawal>,
+    /// Number of the provider records not yet received by the handler.
+    pending: Arc<AtomicUsize>,
+}
+
+/// Receiver of the provider records to remove, owned by the swarm events handler.
+pub(crate) struct WithdrawalHandler {
+    /// Receiver of the provider records.
+    requests: Mutex<mpsc::UnboundedReceiver<Withdrawal>>,
+    /// Number of the provider records not yet received by the handler.
+    pending: Arc<AtomicUsize>,
+}
+
+/// Create the channel of the provider records withdrawal.
+pub(crate) fn channel() -> (ProviderWithdrawals, WithdrawalHandler) {
+    let (requests, receiver) = mpsc::unbounded_channel();
+    let pending = Arc::new(AtomicUsize::new(0));
+    let withdrawals = ProviderWithdrawals {
+        requests,
+        pending: pending.clone(),
+    };
+    let handler = WithdrawalHandler {
+        requests: Mutex::new(receiver),
+        pending,
+    };
+    (withdrawals, handler)
+}
+
+impl ProviderWithdrawals {
+    /// Send the provider record of the `cid` to the handler, the returned receiver
+    /// completes once the record is removed.
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if the node is stopped.
+    pub(crate) fn withdraw(&self, cid: Cid) -> anyhow::Result<oneshot::Receiver<()>> {
+        let (removed, rx) = oneshot::channel();
+        // Counted before sending, so the handler never misses a sent record.
+        self.pending.fetch_add(1, Ordering::AcqRel);
+        self.requests
+            .send((cid, removed))
+            .map_err(|_| anyhow::anyhow!("The IPFS node is stopped"))?;
+        Ok(rx)
+    }
+}
+
+impl WithdrawalHandler {
+    /// Remove the received provider records with `stop_providing`.
+    ///
+    /// Only takes the receiver lock if there are pending records.
+    pub(crate) fn handle(&self, mut stop_providing: impl FnMut(&Cid)) {
+        if self.pending.load(Ordering::Acquire) == 0 {
+            return;
+        }
+        let Ok(mut requests) = self.requests.lock() else {
+            return;
+        };
+        while let Ok((cid, removed)) = requests.try_recv() {
+            self.pending.fetch_sub(1, Ordering::AcqRel);
+            stop_providing(&cid);
+            // The caller may have stopped waiting.
+            let _ = removed.send(());
+        }
+    }
+}
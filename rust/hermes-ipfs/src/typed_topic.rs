@@ -0,0 +1,201 @@
+//! Typed `PubSub` topics.
+//!
+//! Messages are published as CBOR envelopes:
+//! ```cddl
+//! envelope = [
+//!     version: 1,
+//!     payload: any,
+//! ]
+//! ```
+//! Received messages which cannot be decoded are not dropped silently, they are
+//! reported as [`MalformedMessage`] so the subscriber can handle or penalize the sender.
+
+use std::marker::PhantomData;
+
+use minicbor::{Decode, Encode};
+
+use crate::{BoxStream, HermesIpfs, MessageId, PeerId, StreamExt};
+
+/// Current version of the message envelope.
+const ENVELOPE_VERSION: u8 = 1;
+
+/// `PubSub` topic carrying messages of the `T` type.
+pub struct TypedTopic<T> {
+    /// Topic name.
+    topic: String,
+    /// Message type marker.
+    _message: PhantomData<fn() -> T>,
+}
+
+/// Message received from a [`TypedTopic`].
+#[derive(Debug, Clone)]
+pub struct TypedMessage<T> {
+    /// Peer which published the message, if known.
+    pub peer: Option<PeerId>,
+    /// Decoded message payload.
+    pub payload: T,
+}
+
+/// Problem report for a message received from a [`TypedTopic`] which cannot be decoded.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Malformed message on topic {topic} from {peer:?}: {error}")]
+pub struct MalformedMessage {
+    /// Topic name.
+    pub topic: String,
+    /// Peer which published the message, if known.
+    pub peer: Option<PeerId>,
+    /// Raw message bytes.
+    pub data: Vec<u8>,
+    /// Description of the decoding failure.
+    pub error: String,
+}
+
+/// Stream of messages received from a [`TypedTopic`].
+pub type TypedSubscriptionStream<T> = BoxStream<'static, Result<TypedMessage<T>, MalformedMessage>>;
+
+impl<T> TypedTopic<T>
+where T: Encode<()> + for<'a> Decode<'a, ()> + Send + 'static
+{
+    /// Create a new `TypedTopic`.
+    ///
+    /// ## Parameters
+    ///
+    /// * `topic` - `impl Into<String>`
+    #[must_use]
+    pub fn new(topic: impl Into<String>) -> Self {
+        Self {
+            topic: topic.into(),
+            _message: PhantomData,
+        }
+    }
+
+    /// Topic name.
+    #[must_use]
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Publishes a message to the topic.
+    ///
+    /// ## Parameters
+    ///
+    /// * `node` - `HermesIpfs`
+    /// * `message` - `T`
+    ///
+    /// ## Returns
+    ///
+    /// * `Result<MessageId>`
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if unable to encode or publish the message.
+    pub async fn publish(&self, node: &HermesIpfs, message: &T) -> anyhow::Result<MessageId> {
+        node.pubsub_publish(self.topic.clone(), encode_envelope(message)?)
+            .await
+    }
+
+    /// Subscribes to the topic.
+    ///
+    /// ## Parameters
+    ///
+    /// * `node` - `HermesIpfs`
+    ///
+    /// ## Returns
+    ///
+    /// * `TypedSubscriptionStream<T>`
+    ///
+    /// ## Errors
+    ///
+    /// Returns error if unable to subscribe to the topic.
+    pub async fn subscribe(&self, node: &HermesIpfs) -> anyhow::Result<TypedSubscriptionStream<T>> {
+        let topic = self.topic.clone();
+        let stream = node.pubsub_subscribe(topic.clone()).await?;
+        Ok(stream
+            .map(move |message| {
+                match decode_envelope(&message.data) {
+                    Ok(payload) => {
+                        Ok(TypedMessage {
+                            peer: message.source,
+                            payload,
+                        })
+                    },
+                    Err(error) => {
+                        Err(MalformedMessage {
+                            topic: topic.clone(),
+                            peer: message.source,
+                            data: message.data,
+                            error: error.to_string(),
+                        })
+                    },
+                }
+            })
+            .boxed())
+    }
+}
+
+/// Encode the message into a CBOR envelope.
+fn encode_envelope<T: Encode<()>>(message: &T) -> anyhow::Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    let mut e = minicbor::Encoder::new(&mut buffer);
+    e.array(2)?.u8(ENVELOPE_VERSION)?.encode(message)?;
+    Ok(buffer)
+}
+
+/// Decode the message from a CBOR envelope, rejecting unknown versions and trailing
+/// bytes.
+fn decode_envelope<T: for<'a> Decode<'a, ()>>(data: &[u8]) -> anyhow::Result<T> {
+    let mut d = minicbor::Decoder::new(data);
+    if d.array()? != Some(2) {
+        anyhow::bail!("Invalid envelope, expected an array of 2 elements");
+    }
+    let version = d.u8()?;
+    if version != ENVELOPE_VERSION {
+        anyhow::bail!("Unsupported envelope version {version}");
+    }
+    let payload = d.decode()?;
+    if d.position() != data.len() {
+        anyhow::bail!("Unexpected trailing bytes after the envelope");
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn envelope_roundtrip() {
+        let message = (1u64, "vote".to_string());
+        let bytes = encode_envelope(&message).unwrap();
+        let decoded: (u64, String) = decode_envelope(&bytes).unwrap();
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn malformed_envelope() {
+        let message = (1u64, "vote".to_string());
+        let mut bytes = encode_envelope(&message).unwrap();
+
+        // Trailing bytes.
+        bytes.push(0);
+        assert!(decode_envelope::<(u64, String)>(&bytes).is_err());
+
+        // Wrong payload type.
+        bytes.pop();
+        assert!(decode_envelope::<u64>(&bytes).is_err());
+
+        // Unsupported version.
+        let mut bytes = Vec::new();
+        minicbor::Encoder::new(&mut bytes)
+            .array(2)
+            .unwrap()
+            .u8(ENVELOPE_VERSION + 1)
+            .unwrap()
+            .encode(&message)
+            .unwrap();
+        assert!(decode_envelope::<(u64, String)>(&bytes).is_err());
+
+        // Not an envelope.
+        assert!(decode_envelope::<u64>(&[0x01]).is_err());
+    }
+}
@@ -6,3 +6,21 @@
 
 /// Block encoding decoding and validation
 pub mod serialize;
+
+/// Chain validation
+pub mod validation;
+
+/// Persistent block store
+pub mod store;
+
+/// Validator set rotation
+pub mod validator_set;
+
+/// Catalyst Signed Documents block payload
+pub mod signed_docs;
+
+/// Sparse Merkle tree
+pub mod smt;
+
+/// Ledger snapshot block payload
+pub mod snapshot;
@@ -2,30 +2,49 @@
 
 //! Block structure
 
+use std::{fmt, str::FromStr};
+
 use anyhow::{bail, Ok};
 use blake2b_simd::{self, Params};
+use sha3::{Digest, Sha3_256};
 use uuid::Uuid;
 
+use crate::{signed_docs::SignedDocuments, validator_set::ValidatorSet};
+
 /// Genesis block MUST have 0 value height.
 const GENESIS_BLOCK: i64 = 0;
 
-/// Block header size
-#[derive(Debug, Clone, PartialEq)]
-pub struct BlockHeaderSize(usize);
-
 /// Signatures
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct Signatures(Vec<Vec<u8>>);
 
+impl Signatures {
+    /// Create new validators signatures
+    #[must_use]
+    pub fn new(signatures: Vec<Vec<u8>>) -> Self {
+        Self(signatures)
+    }
+
+    /// Validators signatures
+    #[must_use]
+    pub fn as_slice(&self) -> &[Vec<u8>] {
+        &self.0
+    }
+}
+
 /// Decoded block
+#[deprecated(note = "Use `Block` and its accessors instead")]
 pub type DecodedBlock = (BlockHeader, BlockData, Signatures);
 
-/// Encoded genesis Block contents as cbor, used for hash validation
-#[derive(Debug, Clone, PartialEq)]
-pub struct EncodedGenesisBlockContents(pub Vec<u8>);
-
 /// Choice of hash function:
 /// must be the same as the hash of the previous block.
+///
+/// The hash function is self-described in the encoded block header, either by its
+/// registered CBOR tag or, for hash functions without one, by a multihash-style prefix
+/// of the hash bytes:
+/// ```text
+/// multihash = unsigned-varint(code) | unsigned-varint(digest length) | digest
+/// ```
 #[derive(Debug, Clone, PartialEq)]
 pub enum HashFunction {
     /// BLAKE3 is based on an optimized instance of the established hash function BLAKE2
@@ -33,16 +52,181 @@ pub enum HashFunction {
     Blake3,
     /// BLAKE2b-512 produces digest side of 512 bits.
     Blake2b,
+    /// SHA3-256 produces digest side of 256 bits, for interoperability with external
+    /// auditors.
+    Sha3_256,
+}
+
+impl HashFunction {
+    /// Hash bytes with the hash function.
+    /// ## Errors
+    ///
+    /// Returns an error if hashing fails.
+    pub fn hash(&self, bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+        Ok(match self {
+            HashFunction::Blake3 => blake3(bytes)?.to_vec(),
+            HashFunction::Blake2b => blake2b_512(bytes)?.to_vec(),
+            HashFunction::Sha3_256 => Sha3_256::digest(bytes).to_vec(),
+        })
+    }
+
+    /// Multihash code of the hash function.
+    #[must_use]
+    pub fn multihash_code(&self) -> u64 {
+        match self {
+            HashFunction::Blake3 => BLAKE3_MULTIHASH_CODE,
+            HashFunction::Blake2b => BLAKE_2B_MULTIHASH_CODE,
+            HashFunction::Sha3_256 => SHA3_256_MULTIHASH_CODE,
+        }
+    }
+
+    /// Hash function of the multihash code.
+    #[must_use]
+    pub fn from_multihash_code(code: u64) -> Option<Self> {
+        match code {
+            BLAKE3_MULTIHASH_CODE => Some(HashFunction::Blake3),
+            BLAKE_2B_MULTIHASH_CODE => Some(HashFunction::Blake2b),
+            SHA3_256_MULTIHASH_CODE => Some(HashFunction::Sha3_256),
+            _ => None,
+        }
+    }
+
+    /// Registered CBOR tag of the hash function, if any.
+    fn cbor_tag(&self) -> Option<u64> {
+        match self {
+            HashFunction::Blake3 => Some(BLAKE3_CBOR_TAG),
+            HashFunction::Blake2b => Some(BLAKE_2B_CBOR_TAG),
+            HashFunction::Sha3_256 => None,
+        }
+    }
+
+    /// CBOR tag of the validators key ids, which are BLAKE2b-128 hashes, tagged with the
+    /// hash function tag for backward compatibility.
+    fn kid_cbor_tag(&self) -> u64 {
+        self.cbor_tag().unwrap_or(BLAKE_2B_CBOR_TAG)
+    }
+
+    /// Encode the hash self-described by the CBOR tag or the multihash prefix.
+    fn encode_hash(
+        &self, encoder: &mut minicbor::Encoder<Vec<u8>>, hash: &[u8],
+    ) -> anyhow::Result<()> {
+        if let Some(tag) = self.cbor_tag() {
+            encoder.tag(minicbor::data::Tag::new(tag))?;
+            encoder.bytes(hash)?;
+        } else {
+            let mut multihash = Vec::new();
+            write_varint(&mut multihash, self.multihash_code());
+            write_varint(&mut multihash, hash.len().try_into()?);
+            multihash.extend_from_slice(hash);
+            encoder.bytes(&multihash)?;
+        }
+        Ok(())
+    }
+
+    /// Decode the hash self-described by the CBOR tag or the multihash prefix.
+    fn decode_hash(cbor_decoder: &mut minicbor::Decoder) -> anyhow::Result<(Self, Vec<u8>)> {
+        if cbor_decoder.datatype()? == minicbor::data::Type::Tag {
+            let hash_function = cbor_decoder.tag()?;
+            let hash_function = match hash_function.as_u64() {
+                BLAKE3_CBOR_TAG => HashFunction::Blake3,
+                BLAKE_2B_CBOR_TAG => HashFunction::Blake2b,
+                _ => bail!(format!("Invalid hash function type {:?}", hash_function)),
+            };
+            let hash = cbor_decoder
+                .bytes()
+                .map_err(|e| anyhow::anyhow!(format!("Invalid cbor for prev block hash : {e}")))?
+                .to_vec();
+            return Ok((hash_function, hash));
+        }
+
+        let mut multihash = cbor_decoder
+            .bytes()
+            .map_err(|e| anyhow::anyhow!(format!("Invalid cbor for prev block hash : {e}")))?;
+        let code = read_varint(&mut multihash)?;
+        let hash_function = HashFunction::from_multihash_code(code)
+            .ok_or(anyhow::anyhow!(format!("Invalid multihash code {code:#x}")))?;
+        let length = read_varint(&mut multihash)?;
+        if u64::try_from(multihash.len())? != length {
+            bail!("Invalid multihash digest length {length}");
+        }
+        Ok((hash_function, multihash.to_vec()))
+    }
+}
+
+/// Write the unsigned varint (LEB128) value.
+#[allow(clippy::cast_possible_truncation)]
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Read the unsigned varint (LEB128) value, advancing the bytes.
+fn read_varint(bytes: &mut &[u8]) -> anyhow::Result<u64> {
+    /// Maximum size of the unsigned varint in bytes.
+    const MAX_VARINT_SIZE: usize = 9;
+
+    let mut value: u64 = 0;
+    for i in 0..MAX_VARINT_SIZE {
+        let Some((byte, rest)) = bytes.split_first() else {
+            bail!("Unexpected end of multihash varint");
+        };
+        *bytes = rest;
+        value |= u64::from(byte & 0x7F) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    bail!("Multihash varint is too long")
 }
 
 /// Kid (The key identifier) size in bytes
-const KID_BYTES: usize = 16;
+pub(crate) const KID_BYTES: usize = 16;
 
 /// Key ID - Blake2b-128 hash of the Role 0 Certificate defining the Session public key.
 /// BLAKE2b-128 produces digest side of 16 bytes.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Kid(pub [u8; KID_BYTES]);
 
+impl fmt::Display for Kid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+/// Parse the hex encoded key identifier, with an optional `0x` prefix.
+impl FromStr for Kid {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s.strip_prefix("0x").unwrap_or(s))?;
+        let kid = bytes.try_into().map_err(|bytes: Vec<u8>| {
+            anyhow::anyhow!(
+                "Invalid Kid length, expected: {KID_BYTES}, provided: {}",
+                bytes.len()
+            )
+        })?;
+        Ok(Self(kid))
+    }
+}
+
+impl serde::Serialize for Kid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: serde::Serializer {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Kid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: serde::Deserializer<'de> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 /// Encoded whole block including block header, cbor encoded block data and signatures.
 pub type EncodedBlock = Vec<u8>;
 
@@ -59,10 +243,47 @@ pub(crate) fn blake2b_512(value: &[u8]) -> anyhow::Result<[u8; 64]> {
         .map_err(|_| anyhow::anyhow!("Invalid length of blake2b_512, expected 64 got {}", b.len()))
 }
 
-/// Block data
+/// Block data, cbor encoded as a CBOR byte string.
 #[derive(Debug, Clone, PartialEq)]
 pub struct BlockData(Vec<u8>);
 
+impl BlockData {
+    /// Create block data from already cbor encoded bytes.
+    #[must_use]
+    pub fn new(encoded: Vec<u8>) -> Self {
+        Self(encoded)
+    }
+
+    /// Cbor encode the plain payload bytes as block data.
+    /// ## Errors
+    ///
+    /// Returns an error if encoding fails.
+    pub fn from_payload(payload: &[u8]) -> anyhow::Result<Self> {
+        let mut encoder = minicbor::Encoder::new(Vec::new());
+        encoder.bytes(payload)?;
+        Ok(Self(encoder.into_writer()))
+    }
+
+    /// Cbor encoded block data bytes.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Plain payload bytes decoded from the cbor encoded block data.
+    /// ## Errors
+    ///
+    /// Returns an error if block data is not a CBOR byte string.
+    pub fn payload(&self) -> anyhow::Result<&[u8]> {
+        minicbor::Decoder::new(&self.0)
+            .bytes()
+            .map_err(|e| anyhow::anyhow!(format!("Invalid cbor for block data : {e}")))
+    }
+}
+
+/// # of elements in block header without the threshold
+const BLOCK_HEADER_SIZE: u64 = 8;
+
 /// CBOR tag for timestamp
 const TIMESTAMP_CBOR_TAG: u64 = 1;
 
@@ -78,14 +299,41 @@ const BLAKE3_CBOR_TAG: u64 = 32781;
 /// CBOR tag for blake2b
 const BLAKE_2B_CBOR_TAG: u64 = 32782;
 
+// Multihash codes
+// `https://github.com/multiformats/multicodec/blob/master/table.csv`
+
+/// Multihash code for BLAKE3
+const BLAKE3_MULTIHASH_CODE: u64 = 0x1E;
+
+/// Multihash code for BLAKE2b-512
+const BLAKE_2B_MULTIHASH_CODE: u64 = 0xB240;
+
+/// Multihash code for SHA3-256
+const SHA3_256_MULTIHASH_CODE: u64 = 0x16;
+
 /// Block
+#[derive(Debug, Clone, PartialEq)]
 pub struct Block {
     /// Block header
-    pub block_header: BlockHeader,
+    block_header: BlockHeader,
     /// cbor encoded block data
-    pub block_data: BlockData,
+    block_data: BlockData,
     /// Validators signatures
-    pub validator_sigs: Signatures,
+    validator_sigs: Signatures,
+}
+
+#[allow(deprecated)]
+impl From<DecodedBlock> for Block {
+    fn from((block_header, block_data, validator_sigs): DecodedBlock) -> Self {
+        Self::new(block_header, block_data, validator_sigs)
+    }
+}
+
+#[allow(deprecated)]
+impl From<Block> for DecodedBlock {
+    fn from(block: Block) -> Self {
+        (block.block_header, block.block_data, block.validator_sigs)
+    }
 }
 
 impl Block {
@@ -101,6 +349,24 @@ impl Block {
         }
     }
 
+    /// Block header
+    #[must_use]
+    pub fn header(&self) -> &BlockHeader {
+        &self.block_header
+    }
+
+    /// Cbor encoded block data
+    #[must_use]
+    pub fn data(&self) -> &BlockData {
+        &self.block_data
+    }
+
+    /// Validators signatures
+    #[must_use]
+    pub fn signatures(&self) -> &Signatures {
+        &self.validator_sigs
+    }
+
     /// Encode block
     /// ## Errors
     ///
@@ -108,7 +374,7 @@ impl Block {
     pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
         // Enforce block data to be cbor encoded in the form of CBOR byte strings
         // which are just (ordered) series of bytes without further interpretation
-        let _ = minicbor::Decoder::new(&self.block_data.0).bytes()?;
+        let _ = self.block_data.payload()?;
 
         // cbor encode block hdr
         let encoded_block_hdr = self.block_header.to_bytes()?;
@@ -136,22 +402,22 @@ impl Block {
     /// ## Errors
     ///
     /// Returns an error if decoding fails.
-    pub fn from_bytes(
-        encoded_block: &[u8],
-    ) -> anyhow::Result<(BlockHeader, BlockData, Signatures)> {
-        // Decoded block hdr
-        let (block_hdr, block_hdr_size, _) = BlockHeader::from_bytes(encoded_block)?;
-
+    pub fn from_bytes(encoded_block: &[u8]) -> anyhow::Result<Block> {
         // Init decoder
         let mut cbor_decoder = minicbor::Decoder::new(encoded_block);
 
-        // Decode remaining block, set position after block hdr data.
-        cbor_decoder.set_position(block_hdr_size.0);
+        // Decoded block hdr
+        let block_hdr = BlockHeader::decode(&mut cbor_decoder)?;
 
-        // Block data
-        let block_data = cbor_decoder
+        // Block data, kept cbor encoded so the block re-encodes to the same bytes.
+        let block_data_start = cbor_decoder.position();
+        cbor_decoder
             .bytes()
             .map_err(|e| anyhow::anyhow!(format!("Invalid cbor for block data : {e}")))?;
+        let block_data = encoded_block
+            .get(block_data_start..cbor_decoder.position())
+            .ok_or(anyhow::anyhow!("Invalid block data position"))?
+            .to_vec();
 
         // Extract signatures
         let number_of_sigs = cbor_decoder
@@ -166,7 +432,43 @@ impl Block {
             sigs.push(sig.to_owned());
         }
 
-        Ok((block_hdr, BlockData(block_data.to_vec()), Signatures(sigs)))
+        Ok(Block::new(
+            block_hdr,
+            BlockData(block_data),
+            Signatures(sigs),
+        ))
+    }
+
+    /// Returns `true` if the block has the genesis block height.
+    #[must_use]
+    pub fn is_genesis(&self) -> bool {
+        self.block_header.is_genesis()
+    }
+
+    /// Validate genesis block, its previous block hash MUST be a hash of the chain
+    /// parameters.
+    /// ## Errors
+    ///
+    /// Returns an error if the block is not a well-formed genesis block.
+    pub fn validate_genesis(&self) -> anyhow::Result<()> {
+        if !self.is_genesis() {
+            bail!(
+                "Module: Immutable ledger,  Message: Genesis block MUST have {GENESIS_BLOCK} height {:?}",
+                self.block_header,
+            );
+        }
+
+        let genesis_to_prev_hash = GenesisPreviousHash::from(&self.block_header)
+            .hash(&self.block_header.previous_block_hash.0)?;
+
+        if self.block_header.previous_block_hash.1 != genesis_to_prev_hash {
+            return Err(anyhow::anyhow!(
+                "Module: Immutable ledger,  Message: Genesis block prev hash is invalid {:?}",
+                self.block_header,
+            ));
+        }
+
+        Ok(())
     }
 
     /// Validate block against previous block or validate itself if genesis block.
@@ -176,20 +478,11 @@ impl Block {
     pub fn validate(&self, previous_block: Option<Block>) -> anyhow::Result<()> {
         if let Some(previous_block) = previous_block {
             // Standard block
-            let hashed_previous_block = match self.block_header.previous_block_hash.0 {
-                HashFunction::Blake3 => {
-                    (
-                        HashFunction::Blake3,
-                        blake3(&previous_block.to_bytes()?)?.to_vec(),
-                    )
-                },
-                HashFunction::Blake2b => {
-                    (
-                        HashFunction::Blake2b,
-                        blake2b_512(&previous_block.to_bytes()?)?.to_vec(),
-                    )
-                },
-            };
+            let hash_function = self.block_header.previous_block_hash.0.clone();
+            let hashed_previous_block = (
+                hash_function.clone(),
+                hash_function.hash(&previous_block.to_bytes()?)?,
+            );
 
             // chain_id MUST be the same as for the previous block (except for genesis).
             if self.block_header.chain_id != previous_block.block_header.chain_id {
@@ -251,61 +544,245 @@ impl Block {
                 ));
             }
 
-            // validator MUST be the same as for the previous block if present (except for genesis)
-            if self.block_header.validator != previous_block.block_header.validator {
+            // validator and threshold MUST be the same as for the previous block (except for
+            // genesis), unless the previous block updated the validator set.
+            let active_validator_set = ValidatorSet::from_block_data(&previous_block.block_data)?
+                .unwrap_or_else(|| ValidatorSet::from(&previous_block.block_header));
+            if ValidatorSet::from(&self.block_header) != active_validator_set {
                 return Err(anyhow::anyhow!(
                     "Module: Immutable ledger,  Message: validator validation failed: {:?} {:?}",
                     self.block_header,
                     previous_block.block_header
                 ));
             }
-        } else if self.block_header.height == GENESIS_BLOCK {
-            // Validate genesis block
-            {
-                let genesis_to_prev_hash = GenesisPreviousHash::new(
-                    self.block_header.chain_id,
-                    self.block_header.block_time_stamp,
-                    self.block_header.ledger_type,
-                    self.block_header.purpose_id,
-                    self.block_header.validator.clone(),
-                )
-                .hash(&self.block_header.previous_block_hash.0)?;
 
-                if self.block_header.previous_block_hash.1 != genesis_to_prev_hash {
-                    return Err(anyhow::anyhow!(
-                    "Module: Immutable ledger,  Message: Genesis block prev hash is invalid {:?}",
-                    self.block_header,
-                ));
-                }
-            }
+            // Catalyst Signed Documents carried by the block data MUST be well-formed.
+            SignedDocuments::from_block_data(&self.block_data).map_err(|e| {
+                anyhow::anyhow!(
+                    "Module: Immutable ledger,  Message: signed documents validation failed: {e}"
+                )
+            })?;
+        } else if self.is_genesis() {
+            self.validate_genesis()?;
         }
 
         Ok(())
     }
 }
 
+/// Block builder
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct BlockBuilder {
+    /// Unique identifier of the chain.
+    chain_id: Option<Uuid>,
+    /// Block height.
+    height: Option<i64>,
+    /// Block epoch-based date/time.
+    block_time_stamp: Option<i64>,
+    /// Previous Block hash.
+    previous_block_hash: Option<(HashFunction, Vec<u8>)>,
+    /// unique identifier of the ledger type.
+    ledger_type: Option<Uuid>,
+    /// unique identifier of the purpose.
+    purpose_id: Option<Uuid>,
+    /// Identifier or identifiers of the entity who was produced and processed a block.
+    validator: Vec<Kid>,
+    /// Minimum number of the validators signatures, all validators by default.
+    threshold: Option<u64>,
+    /// Arbitrary metadata of the block.
+    metadata: Vec<u8>,
+    /// cbor encoded block data
+    block_data: Option<BlockData>,
+    /// Validators signatures
+    validator_sigs: Signatures,
+}
+
+impl BlockBuilder {
+    /// Create new empty block builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set unique identifier of the chain.
+    pub fn chain_id(mut self, chain_id: Uuid) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Set block height.
+    pub fn height(mut self, height: i64) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Set block epoch-based date/time.
+    pub fn block_time_stamp(mut self, block_time_stamp: i64) -> Self {
+        self.block_time_stamp = Some(block_time_stamp);
+        self
+    }
+
+    /// Set previous block hash.
+    pub fn previous_block_hash(mut self, hash_function: HashFunction, hash: Vec<u8>) -> Self {
+        self.previous_block_hash = Some((hash_function, hash));
+        self
+    }
+
+    /// Set unique identifier of the ledger type.
+    pub fn ledger_type(mut self, ledger_type: Uuid) -> Self {
+        self.ledger_type = Some(ledger_type);
+        self
+    }
+
+    /// Set unique identifier of the purpose.
+    pub fn purpose_id(mut self, purpose_id: Uuid) -> Self {
+        self.purpose_id = Some(purpose_id);
+        self
+    }
+
+    /// Set identifiers of the block validators.
+    pub fn validator(mut self, validator: Vec<Kid>) -> Self {
+        self.validator = validator;
+        self
+    }
+
+    /// Set minimum number of the validators signatures (M-of-N), all validators by
+    /// default.
+    pub fn threshold(mut self, threshold: u64) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Set validators and threshold from the validator set.
+    pub fn validator_set(mut self, validator_set: ValidatorSet) -> Self {
+        self.threshold = validator_set.threshold();
+        self.validator = validator_set.validators().to_vec();
+        self
+    }
+
+    /// Set arbitrary block metadata, empty by default.
+    pub fn metadata(mut self, metadata: Vec<u8>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Set cbor encoded block data.
+    pub fn block_data(mut self, block_data: BlockData) -> Self {
+        self.block_data = Some(block_data);
+        self
+    }
+
+    /// Set validators signatures, empty by default.
+    pub fn validator_sigs(mut self, validator_sigs: Signatures) -> Self {
+        self.validator_sigs = validator_sigs;
+        self
+    }
+
+    /// Set genesis block height and previous block hash derived from the chain
+    /// parameters, which MUST be set before.
+    /// ## Errors
+    ///
+    /// Returns an error if any of the chain parameters is not set or hashing fails.
+    pub fn genesis(mut self, hash_function: HashFunction) -> anyhow::Result<Self> {
+        let genesis_to_prev_hash = GenesisPreviousHash::new(
+            self.chain_id
+                .ok_or(anyhow::anyhow!("Block chain id is not set"))?,
+            self.block_time_stamp
+                .ok_or(anyhow::anyhow!("Block timestamp is not set"))?,
+            self.ledger_type
+                .ok_or(anyhow::anyhow!("Block ledger type is not set"))?,
+            self.purpose_id
+                .ok_or(anyhow::anyhow!("Block purpose id is not set"))?,
+            self.validator.clone(),
+        )
+        .with_threshold(self.threshold);
+        let hash = genesis_to_prev_hash.hash(&hash_function)?;
+
+        self.height = Some(GENESIS_BLOCK);
+        self.previous_block_hash = Some((hash_function, hash));
+        Ok(self)
+    }
+
+    /// Build and encode the genesis block, see [`BlockBuilder::genesis`].
+    /// ## Errors
+    ///
+    /// Returns an error if any of the required fields is not set or encoding fails.
+    pub fn encode_genesis_block(self, hash_function: HashFunction) -> anyhow::Result<EncodedBlock> {
+        self.genesis(hash_function)?.build()?.to_bytes()
+    }
+
+    /// Build the block header, ignoring block data and signatures.
+    /// ## Errors
+    ///
+    /// Returns an error if any of the block header fields is not set.
+    pub fn build_header(&self) -> anyhow::Result<BlockHeader> {
+        Ok(BlockHeader {
+            chain_id: self
+                .chain_id
+                .ok_or(anyhow::anyhow!("Block chain id is not set"))?,
+            height: self
+                .height
+                .ok_or(anyhow::anyhow!("Block height is not set"))?,
+            block_time_stamp: self
+                .block_time_stamp
+                .ok_or(anyhow::anyhow!("Block timestamp is not set"))?,
+            previous_block_hash: self
+                .previous_block_hash
+                .clone()
+                .ok_or(anyhow::anyhow!("Block previous hash is not set"))?,
+            ledger_type: self
+                .ledger_type
+                .ok_or(anyhow::anyhow!("Block ledger type is not set"))?,
+            purpose_id: self
+                .purpose_id
+                .ok_or(anyhow::anyhow!("Block purpose id is not set"))?,
+            validator: self.validator.clone(),
+            threshold: self.threshold,
+            metadata: self.metadata.clone(),
+        })
+    }
+
+    /// Build the block.
+    /// ## Errors
+    ///
+    /// Returns an error if any of the required fields is not set or block data is not
+    /// cbor encoded.
+    pub fn build(self) -> anyhow::Result<Block> {
+        let block_header = self.build_header()?;
+        let block_data = self
+            .block_data
+            .ok_or(anyhow::anyhow!("Block data is not set"))?;
+        let _ = block_data.payload()?;
+
+        Ok(Block::new(block_header, block_data, self.validator_sigs))
+    }
+}
+
 /// Block header
 #[derive(Debug, Clone, PartialEq)]
 pub struct BlockHeader {
     /// Unique identifier of the chain.
-    pub chain_id: Uuid,
+    chain_id: Uuid,
     /// Block height.
-    pub height: i64,
+    height: i64,
     /// Block epoch-based date/time.
-    pub block_time_stamp: i64,
+    block_time_stamp: i64,
     /// Previous Block hash.
-    pub previous_block_hash: (HashFunction, Vec<u8>),
+    previous_block_hash: (HashFunction, Vec<u8>),
     /// unique identifier of the ledger type.
     /// In general, this is the way to strictly bound and specify `block_data` of the
     /// ledger for the specific `ledger_type`.
-    pub ledger_type: Uuid,
+    ledger_type: Uuid,
     /// unique identifier of the purpose, each Ledger instance will have a strict time
     /// boundaries, so each of them will run for different purposes.
-    pub purpose_id: Uuid,
+    purpose_id: Uuid,
     /// Identifier or identifiers of the entity who was produced and processed a block.
-    pub validator: Vec<Kid>,
+    validator: Vec<Kid>,
+    /// Minimum number of the validators signatures (M-of-N), `None` if all validators
+    /// MUST sign the block.
+    threshold: Option<u64>,
     /// Add arbitrary metadata to the block.
-    pub metadata: Vec<u8>,
+    metadata: Vec<u8>,
 }
 
 impl BlockHeader {
@@ -325,22 +802,98 @@ impl BlockHeader {
             ledger_type,
             purpose_id,
             validator,
+            threshold: None,
             metadata,
         }
     }
 
+    /// Set minimum number of the validators signatures (M-of-N).
+    #[must_use]
+    pub fn with_threshold(mut self, threshold: u64) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Unique identifier of the chain.
+    #[must_use]
+    pub fn chain_id(&self) -> Uuid {
+        self.chain_id
+    }
+
+    /// Block height.
+    #[must_use]
+    pub fn height(&self) -> i64 {
+        self.height
+    }
+
+    /// Block epoch-based date/time.
+    #[must_use]
+    pub fn block_time_stamp(&self) -> i64 {
+        self.block_time_stamp
+    }
+
+    /// Hash function used for the previous block hash.
+    #[must_use]
+    pub fn hash_function(&self) -> &HashFunction {
+        &self.previous_block_hash.0
+    }
+
+    /// Previous block hash.
+    #[must_use]
+    pub fn previous_block_hash(&self) -> &[u8] {
+        &self.previous_block_hash.1
+    }
+
+    /// Unique identifier of the ledger type.
+    #[must_use]
+    pub fn ledger_type(&self) -> Uuid {
+        self.ledger_type
+    }
+
+    /// Unique identifier of the purpose.
+    #[must_use]
+    pub fn purpose_id(&self) -> Uuid {
+        self.purpose_id
+    }
+
+    /// Identifiers of the block validators.
+    #[must_use]
+    pub fn validator(&self) -> &[Kid] {
+        &self.validator
+    }
+
+    /// Minimum number of the validators signatures, `None` if all validators MUST sign
+    /// the block.
+    #[must_use]
+    pub fn threshold(&self) -> Option<u64> {
+        self.threshold
+    }
+
+    /// Arbitrary block metadata.
+    #[must_use]
+    pub fn metadata(&self) -> &[u8] {
+        &self.metadata
+    }
+
+    /// Returns `true` if the block header has the genesis block height.
+    #[must_use]
+    pub fn is_genesis(&self) -> bool {
+        self.height == GENESIS_BLOCK
+    }
+
     /// Encode block header
     /// ## Errors
     ///
     /// Returns an error encoding fails
     pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
-        /// # of elements in block header
-        const BLOCK_HEADER_SIZE: u64 = 8;
-
         let out: Vec<u8> = Vec::new();
         let mut encoder = minicbor::Encoder::new(out);
 
-        encoder.array(BLOCK_HEADER_SIZE)?;
+        if self.threshold.is_some() {
+            encoder.array(BLOCK_HEADER_SIZE + 1)?;
+        } else {
+            encoder.array(BLOCK_HEADER_SIZE)?;
+        }
 
         // Chain id
         encoder.tag(minicbor::data::Tag::new(UUID_CBOR_TAG))?;
@@ -353,15 +906,11 @@ impl BlockHeader {
         encoder.tag(minicbor::data::Tag::new(TIMESTAMP_CBOR_TAG))?;
         encoder.int(self.block_time_stamp.into())?;
 
-        let hash_function = self.previous_block_hash.0.clone();
-        let cbor_hash_tag = match hash_function {
-            HashFunction::Blake3 => BLAKE3_CBOR_TAG,
-            HashFunction::Blake2b => BLAKE_2B_CBOR_TAG,
-        };
+        let hash_function = &self.previous_block_hash.0;
+        let cbor_hash_tag = hash_function.kid_cbor_tag();
 
         // Prev block hash
-        encoder.tag(minicbor::data::Tag::new(cbor_hash_tag))?;
-        encoder.bytes(&self.previous_block_hash.1)?;
+        hash_function.encode_hash(&mut encoder, &self.previous_block_hash.1)?;
 
         // Ledger type
         encoder.tag(minicbor::data::Tag::new(UUID_CBOR_TAG))?;
@@ -381,6 +930,11 @@ impl BlockHeader {
         // Metadata
         encoder.bytes(&self.metadata)?;
 
+        // Threshold, omitted for the N-of-N signing
+        if let Some(threshold) = self.threshold {
+            encoder.u64(threshold)?;
+        }
+
         Ok(encoder.writer().clone())
     }
 
@@ -388,16 +942,13 @@ impl BlockHeader {
     /// ## Errors
     ///
     /// Returns an error decoding fails
-    pub fn from_bytes(
-        block: &[u8],
-    ) -> anyhow::Result<(
-        BlockHeader,
-        BlockHeaderSize,
-        Option<EncodedGenesisBlockContents>,
-    )> {
-        // Decode cbor to bytes
-        let mut cbor_decoder = minicbor::Decoder::new(block);
-        cbor_decoder.array()?;
+    pub fn from_bytes(block: &[u8]) -> anyhow::Result<BlockHeader> {
+        Self::decode(&mut minicbor::Decoder::new(block))
+    }
+
+    /// Decode block header, leaving the decoder positioned right after it.
+    fn decode(cbor_decoder: &mut minicbor::Decoder) -> anyhow::Result<BlockHeader> {
+        let block_header_size = cbor_decoder.array()?;
 
         // Raw chain_id
         cbor_decoder.tag()?;
@@ -415,18 +966,8 @@ impl BlockHeader {
         cbor_decoder.tag()?;
         let ts: i64 = cbor_decoder.int()?.try_into()?;
 
-        // Raw prev block hash
-        let hash_function = cbor_decoder.tag()?;
-        let prev_block_hash_type = match hash_function.as_u64() {
-            BLAKE3_CBOR_TAG => HashFunction::Blake3,
-            BLAKE_2B_CBOR_TAG => HashFunction::Blake2b,
-            _ => bail!(format!("Invalid hash function type {:?}", hash_function)),
-        };
-
-        let prev_block_hash = cbor_decoder
-            .bytes()
-            .map_err(|e| anyhow::anyhow!(format!("Invalid cbor for prev block hash : {e}")))?
-            .to_vec();
+        // Raw prev block hash, the hash function is detected from its encoding
+        let (prev_block_hash_type, prev_block_hash) = HashFunction::decode_hash(cbor_decoder)?;
 
         // Raw ledger type
         cbor_decoder.tag()?;
@@ -467,7 +1008,17 @@ impl BlockHeader {
             .map_err(|e| anyhow::anyhow!(format!("Invalid cbor for metadata : {e}")))?
             .into();
 
-        let block_header = BlockHeader {
+        let threshold = if block_header_size == Some(BLOCK_HEADER_SIZE + 1) {
+            Some(
+                cbor_decoder
+                    .u64()
+                    .map_err(|e| anyhow::anyhow!(format!("Invalid cbor for threshold : {e}")))?,
+            )
+        } else {
+            None
+        };
+
+        Ok(BlockHeader {
             chain_id,
             height: block_height,
             block_time_stamp: ts,
@@ -475,10 +1026,9 @@ impl BlockHeader {
             ledger_type,
             purpose_id,
             validator: validators,
+            threshold,
             metadata,
-        };
-
-        Ok((block_header, BlockHeaderSize(cbor_decoder.position()), None))
+        })
     }
 }
 
@@ -497,6 +1047,21 @@ pub struct GenesisPreviousHash {
     pub purpose_id: Uuid,
     /// Identifier or identifiers of the entity who was produced and processed a block.
     pub validator: Vec<Kid>,
+    /// Minimum number of the validators signatures, `None` if all validators MUST sign.
+    pub threshold: Option<u64>,
+}
+
+impl From<&BlockHeader> for GenesisPreviousHash {
+    fn from(block_header: &BlockHeader) -> Self {
+        Self::new(
+            block_header.chain_id,
+            block_header.block_time_stamp,
+            block_header.ledger_type,
+            block_header.purpose_id,
+            block_header.validator.clone(),
+        )
+        .with_threshold(block_header.threshold)
+    }
 }
 
 impl GenesisPreviousHash {
@@ -512,9 +1077,17 @@ impl GenesisPreviousHash {
             ledger_type,
             purpose_id,
             validator,
+            threshold: None,
         }
     }
 
+    /// Set minimum number of the validators signatures.
+    #[must_use]
+    pub fn with_threshold(mut self, threshold: Option<u64>) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
     /// Encode genesis previous hash to cbor
     /// ## Errors
     ///
@@ -525,7 +1098,11 @@ impl GenesisPreviousHash {
 
         let out: Vec<u8> = Vec::new();
         let mut encoder = minicbor::Encoder::new(out);
-        encoder.array(GENESIS_TO_PREV_HASH_SIZE)?;
+        if self.threshold.is_some() {
+            encoder.array(GENESIS_TO_PREV_HASH_SIZE + 1)?;
+        } else {
+            encoder.array(GENESIS_TO_PREV_HASH_SIZE)?;
+        }
 
         // Chain id
         encoder.tag(minicbor::data::Tag::new(UUID_CBOR_TAG))?;
@@ -535,10 +1112,7 @@ impl GenesisPreviousHash {
         encoder.tag(minicbor::data::Tag::new(TIMESTAMP_CBOR_TAG))?;
         encoder.int(self.block_time_stamp.into())?;
 
-        let cbor_hash_tag = match hasher {
-            HashFunction::Blake3 => BLAKE3_CBOR_TAG,
-            HashFunction::Blake2b => BLAKE_2B_CBOR_TAG,
-        };
+        let cbor_hash_tag = hasher.kid_cbor_tag();
 
         // Ledger type
         encoder.tag(minicbor::data::Tag::new(UUID_CBOR_TAG))?;
@@ -555,6 +1129,11 @@ impl GenesisPreviousHash {
             encoder.bytes(&val.0)?;
         }
 
+        // Threshold, omitted for the N-of-N signing
+        if let Some(threshold) = self.threshold {
+            encoder.u64(threshold)?;
+        }
+
         Ok(encoder.writer().clone())
     }
 
@@ -566,12 +1145,7 @@ impl GenesisPreviousHash {
         let encoding = self.to_bytes(hasher)?;
 
         // get hash of genesis_to_prev_hash
-        let genesis_prev_hash = match hasher {
-            HashFunction::Blake3 => blake3(&encoding)?.to_vec(),
-            HashFunction::Blake2b => blake2b_512(&encoding)?.to_vec(),
-        };
-
-        Ok(genesis_prev_hash)
+        hasher.hash(&encoding)
     }
 }
 
@@ -586,7 +1160,8 @@ mod tests {
 
     use super::{BlockHeader, Kid};
     use crate::serialize::{
-        blake2b_512, Block, BlockData, GenesisPreviousHash, HashFunction::Blake2b, Signatures,
+        blake2b_512, read_varint, write_varint, Block, BlockBuilder, BlockData,
+        GenesisPreviousHash, HashFunction, HashFunction::Blake2b, Signatures,
     };
 
     #[proptest]
@@ -616,21 +1191,97 @@ mod tests {
 
         let encoded_block_hdr = block_hdr.to_bytes().unwrap();
 
-        let (block_hdr_from_bytes, ..) = BlockHeader::from_bytes(&encoded_block_hdr).unwrap();
-        assert_eq!(block_hdr_from_bytes.chain_id, block_hdr.chain_id);
-        assert_eq!(block_hdr_from_bytes.height, block_hdr.height);
+        let block_hdr_from_bytes = BlockHeader::from_bytes(&encoded_block_hdr).unwrap();
+        assert_eq!(block_hdr_from_bytes.chain_id(), block_hdr.chain_id());
+        assert_eq!(block_hdr_from_bytes.height(), block_hdr.height());
         assert_eq!(
-            block_hdr_from_bytes.block_time_stamp,
-            block_hdr.block_time_stamp
+            block_hdr_from_bytes.block_time_stamp(),
+            block_hdr.block_time_stamp()
         );
         assert_eq!(
-            block_hdr_from_bytes.previous_block_hash,
-            block_hdr.previous_block_hash
+            block_hdr_from_bytes.hash_function(),
+            block_hdr.hash_function()
         );
-        assert_eq!(block_hdr_from_bytes.ledger_type, block_hdr.ledger_type);
-        assert_eq!(block_hdr_from_bytes.purpose_id, block_hdr.purpose_id);
-        assert_eq!(block_hdr_from_bytes.validator, block_hdr.validator);
-        assert_eq!(block_hdr_from_bytes.metadata, block_hdr.metadata);
+        assert_eq!(
+            block_hdr_from_bytes.previous_block_hash(),
+            block_hdr.previous_block_hash()
+        );
+        assert_eq!(block_hdr_from_bytes.ledger_type(), block_hdr.ledger_type());
+        assert_eq!(block_hdr_from_bytes.purpose_id(), block_hdr.purpose_id());
+        assert_eq!(block_hdr_from_bytes.validator(), block_hdr.validator());
+        assert_eq!(block_hdr_from_bytes.metadata(), block_hdr.metadata());
+    }
+
+    #[proptest]
+    fn hash_function_detection(prev_block_hash: Vec<u8>) {
+        for hash_function in [
+            HashFunction::Blake3,
+            HashFunction::Blake2b,
+            HashFunction::Sha3_256,
+        ] {
+            let block_hdr = BlockHeader::new(
+                Uuid::now_v7(),
+                1,
+                1_728_474_515,
+                (hash_function.clone(), prev_block_hash.clone()),
+                Uuid::new_v4(),
+                Uuid::now_v7(),
+                vec![Kid([1; 16])],
+                vec![],
+            );
+
+            let block_hdr_from_bytes =
+                BlockHeader::from_bytes(&block_hdr.to_bytes().unwrap()).unwrap();
+            assert_eq!(block_hdr_from_bytes.hash_function(), &hash_function);
+            assert_eq!(block_hdr_from_bytes, block_hdr);
+        }
+    }
+
+    #[test]
+    fn sha3_256_hash() {
+        let hash = HashFunction::Sha3_256.hash(b"").unwrap();
+        assert_eq!(
+            hex::encode(hash),
+            "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"
+        );
+        assert_eq!(
+            HashFunction::from_multihash_code(HashFunction::Sha3_256.multihash_code()),
+            Some(HashFunction::Sha3_256)
+        );
+        assert_eq!(HashFunction::from_multihash_code(0), None);
+    }
+
+    #[test]
+    fn kid_string_representation() {
+        let kid = Kid([0xAB; 16]);
+        let hex = "abababababababababababababababab";
+        assert_eq!(kid.to_string(), hex);
+        assert_eq!(hex.parse::<Kid>().unwrap(), kid);
+        assert_eq!(format!("0x{hex}").parse::<Kid>().unwrap(), kid);
+        assert!("abab".parse::<Kid>().is_err());
+        assert!("zz".parse::<Kid>().is_err());
+
+        let json = serde_json::to_string(&kid).unwrap();
+        assert_eq!(json, format!("\"{hex}\""));
+        assert_eq!(serde_json::from_str::<Kid>(&json).unwrap(), kid);
+    }
+
+    #[test]
+    fn multihash_varint() {
+        for value in [0, 1, 0x7F, 0x80, 0xB240, u64::MAX >> 1] {
+            let mut bytes = Vec::new();
+            write_varint(&mut bytes, value);
+            let mut slice = bytes.as_slice();
+            assert_eq!(read_varint(&mut slice).unwrap(), value);
+            assert!(slice.is_empty());
+        }
+        let mut bytes = Vec::new();
+        write_varint(&mut bytes, 0xB240);
+        assert_eq!(bytes, vec![0xC0, 0xE4, 0x02]);
+
+        // Truncated and too long varints.
+        assert!(read_varint(&mut [0x80].as_slice()).is_err());
+        assert!(read_varint(&mut [0xFF; 10].as_slice()).is_err());
     }
 
     #[proptest]
@@ -665,11 +1316,7 @@ mod tests {
             metadata,
         );
 
-        let out: Vec<u8> = Vec::new();
-        let mut block_data = minicbor::Encoder::new(out);
-
-        block_data.bytes(&block_data_bytes).unwrap();
-        let encoded_block_data = block_data.writer().clone();
+        let encoded_block_data = BlockData::from_payload(&block_data_bytes).unwrap();
 
         // validator_signature MUST be a signature of the hashed block_header bytes
         // and the block_data bytes
@@ -686,25 +1333,28 @@ mod tests {
 
         let block = Block::new(
             block_hdr.clone(),
-            BlockData(encoded_block_data.clone()),
-            Signatures(vec![signature_a.to_vec(), signature_b.to_vec()]),
+            encoded_block_data.clone(),
+            Signatures::new(vec![signature_a.to_vec(), signature_b.to_vec()]),
         );
 
         let encoded_block = block.to_bytes().unwrap();
 
         // DECODE RAW BYTES BACK INTO BLOCK TYPE
-        let (block_header, block_data, sigs) = Block::from_bytes(&encoded_block).unwrap();
+        let decoded_block = Block::from_bytes(&encoded_block).unwrap();
+
+        assert_eq!(decoded_block.header(), &block_hdr);
+        assert_eq!(decoded_block, block);
 
-        assert_eq!(block_header, block_hdr);
+        // decoded block MUST re-encode to the same bytes
+        assert_eq!(decoded_block.to_bytes().unwrap(), encoded_block);
 
-        // signatures are over encoded block data
-        // block data is returned as plain bytes decoded from cbor
-        assert_eq!(block_data.0, block_data_bytes);
+        // signatures are over plain block data bytes
+        assert_eq!(decoded_block.data().payload().unwrap(), block_data_bytes);
 
         let verifying_key = SigningKey::from_bytes(&validator_secret_key_bytes);
 
-        for sig in sigs.0 {
-            let s: [u8; 64] = sig.try_into().unwrap();
+        for sig in decoded_block.signatures().as_slice() {
+            let s: [u8; 64] = sig.clone().try_into().unwrap();
             let signature = Signature::from_bytes(&s);
             verifying_key
                 .verify_strict(&data_to_sign, &signature)
@@ -714,8 +1364,8 @@ mod tests {
         // ENCODING SHOULD FAIL with block data that is NOT cbor encoded
         let block = Block::new(
             block_hdr.clone(),
-            BlockData(vec![7; 1024]),
-            Signatures(vec![
+            BlockData::new(vec![7; 1024]),
+            Signatures::new(vec![
                 validator_secret_key_bytes.to_vec(),
                 validator_secret_key_bytes.to_vec(),
             ]),
@@ -724,6 +1374,110 @@ mod tests {
         assert!(block.to_bytes().is_err());
     }
 
+    #[proptest]
+    fn block_builder(
+        prev_block_hash: Vec<u8>, metadata: Vec<u8>, block_height: i64, block_timestamp: i64,
+        block_data_bytes: Vec<u8>,
+    ) {
+        let validator = vec![Kid([1; 16]), Kid([2; 16])];
+        let chain_id = Uuid::now_v7();
+        let ledger_type = Uuid::new_v4();
+        let purpose_id = Uuid::now_v7();
+        let block_data = BlockData::from_payload(&block_data_bytes).unwrap();
+        let sigs = Signatures::new(vec![vec![1; 64]]);
+
+        let builder = BlockBuilder::new()
+            .chain_id(chain_id)
+            .height(block_height)
+            .block_time_stamp(block_timestamp)
+            .previous_block_hash(Blake2b, prev_block_hash.clone())
+            .ledger_type(ledger_type)
+            .purpose_id(purpose_id)
+            .validator(validator.clone())
+            .metadata(metadata.clone());
+
+        // block data is required
+        assert!(builder.clone().build().is_err());
+
+        let block = builder
+            .block_data(block_data.clone())
+            .validator_sigs(sigs.clone())
+            .build()
+            .unwrap();
+
+        let expected = Block::new(
+            BlockHeader::new(
+                chain_id,
+                block_height,
+                block_timestamp,
+                (Blake2b, prev_block_hash),
+                ledger_type,
+                purpose_id,
+                validator,
+                metadata,
+            ),
+            block_data,
+            sigs,
+        );
+        assert_eq!(block, expected);
+
+        // missing header fields
+        assert!(BlockBuilder::new()
+            .block_data(BlockData::from_payload(&block_data_bytes).unwrap())
+            .build()
+            .is_err());
+    }
+
+    #[proptest]
+    fn genesis_block_builder(metadata: Vec<u8>, block_data_bytes: Vec<u8>) {
+        let builder = BlockBuilder::new()
+            .chain_id(Uuid::now_v7())
+            .block_time_stamp(1_728_474_515)
+            .ledger_type(Uuid::new_v4())
+            .purpose_id(Uuid::now_v7())
+            .validator(vec![Kid([1; 16]), Kid([2; 16])])
+            .metadata(metadata)
+            .block_data(BlockData::from_payload(&block_data_bytes).unwrap());
+
+        let encoded = builder.clone().encode_genesis_block(Blake2b).unwrap();
+        let genesis = Block::from_bytes(&encoded).unwrap();
+        assert!(genesis.is_genesis());
+        assert!(genesis.validate_genesis().is_ok());
+        assert!(genesis.validate(None).is_ok());
+
+        // Not a genesis block height.
+        let block = builder
+            .clone()
+            .height(1)
+            .previous_block_hash(Blake2b, genesis.header().previous_block_hash().to_vec())
+            .build()
+            .unwrap();
+        assert!(!block.is_genesis());
+        assert!(block.validate_genesis().is_err());
+
+        // Chain parameters are required.
+        assert!(BlockBuilder::new().genesis(Blake2b).is_err());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn decoded_block_conversion() {
+        let block = BlockBuilder::new()
+            .chain_id(Uuid::now_v7())
+            .height(1)
+            .block_time_stamp(1_728_474_515)
+            .previous_block_hash(Blake2b, vec![0; 64])
+            .ledger_type(Uuid::new_v4())
+            .purpose_id(Uuid::now_v7())
+            .block_data(BlockData::from_payload(&[1, 2, 3]).unwrap())
+            .build()
+            .unwrap();
+
+        let decoded: super::DecodedBlock = block.clone().into();
+        assert_eq!(&decoded.0, block.header());
+        assert_eq!(Block::from(decoded), block);
+    }
+
     #[proptest]
     #[allow(clippy::zero_prefixed_literal)]
     fn validate_block_test(prev_block_hash: Vec<u8>, metadata: Vec<u8>, block_data_bytes: Vec<u8>) {
@@ -761,16 +1515,12 @@ mod tests {
             metadata.clone(),
         );
 
-        let out: Vec<u8> = Vec::new();
-        let mut block_data = minicbor::Encoder::new(out);
-
-        block_data.bytes(&block_data_bytes).unwrap();
-        let encoded_block_data = block_data.writer().clone();
+        let encoded_block_data = BlockData::from_payload(&block_data_bytes).unwrap();
 
         let previous_block = Block::new(
             block_hdr.clone(),
-            BlockData(encoded_block_data.clone()),
-            Signatures(vec![
+            encoded_block_data.clone(),
+            Signatures::new(vec![
                 validator_secret_key_bytes.to_vec(),
                 validator_secret_key_bytes.to_vec(),
             ]),
@@ -791,22 +1541,23 @@ mod tests {
             metadata,
         );
 
-        let out: Vec<u8> = Vec::new();
-        let mut block_data = minicbor::Encoder::new(out);
-
-        block_data.bytes(&block_data_bytes).unwrap();
-        let encoded_block_data = block_data.writer().clone();
-
         let current_block = Block::new(
             block_hdr.clone(),
-            BlockData(encoded_block_data.clone()),
-            Signatures(vec![
+            encoded_block_data.clone(),
+            Signatures::new(vec![
                 validator_secret_key_bytes.to_vec(),
                 validator_secret_key_bytes.to_vec(),
             ]),
         );
 
+        // decoded previous block MUST still be usable for validation
+        let decoded_previous_block =
+            Block::from_bytes(&previous_block.to_bytes().unwrap()).unwrap();
+
         assert!(current_block.validate(Some(previous_block),).is_ok());
+        assert!(current_block
+            .validate(Some(decoded_previous_block),)
+            .is_ok());
     }
 
     #[proptest]
@@ -855,16 +1606,12 @@ mod tests {
             metadata.clone(),
         );
 
-        let out: Vec<u8> = Vec::new();
-        let mut block_data = minicbor::Encoder::new(out);
-
-        block_data.bytes(&block_data_bytes).unwrap();
-        let encoded_block_data = block_data.writer().clone();
+        let encoded_block_data = BlockData::from_payload(&block_data_bytes).unwrap();
 
         let block = Block::new(
             block_hdr.clone(),
-            BlockData(encoded_block_data.clone()),
-            Signatures(vec![
+            encoded_block_data.clone(),
+            Signatures::new(vec![
                 validator_secret_key_bytes.to_vec(),
                 validator_secret_key_bytes.to_vec(),
             ]),
@@ -887,8 +1634,8 @@ mod tests {
 
         let block = Block::new(
             block_hdr.clone(),
-            BlockData(encoded_block_data.clone()),
-            Signatures(vec![
+            encoded_block_data.clone(),
+            Signatures::new(vec![
                 validator_secret_key_bytes.to_vec(),
                 validator_secret_key_bytes.to_vec(),
             ]),
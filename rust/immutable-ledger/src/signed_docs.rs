@@ -0,0 +1,277 @@
+//! Catalyst Signed Documents block payload
+//!
+//! Block data could carry Catalyst Signed Documents, either the whole COSE encoded
+//! documents or only their hashes, when the documents are stored elsewhere.
+//!
+//! ```cddl
+//! signed-documents = #6.32801([* signed-document-entry])
+//! signed-document-entry = COSE_Sign_Tagged / COSE_Sign / document-hash
+//! document-hash = bytes .size 32 ; BLAKE2b-256 of the encoded document
+//! ```
+
+use blake2b_simd::Params;
+use coset::{CborSerializable, CoseSign, TaggedCborSerializable};
+
+use crate::serialize::BlockData;
+
+/// CBOR tag of the Catalyst Signed Documents block payload.
+const SIGNED_DOCUMENTS_CBOR_TAG: u64 = 32801;
+
+/// Size of the document hash in bytes.
+pub const DOCUMENT_HASH_BYTES: usize = 32;
+
+/// An entry of the Catalyst Signed Documents block payload.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignedDocumentEntry {
+    /// COSE encoded Catalyst Signed Document.
+    Document(Vec<u8>),
+    /// BLAKE2b-256 hash of the COSE encoded Catalyst Signed Document.
+    Hash([u8; DOCUMENT_HASH_BYTES]),
+}
+
+impl SignedDocumentEntry {
+    /// Create the entry of the COSE encoded Catalyst Signed Document.
+    /// ## Errors
+    ///
+    /// Returns an error if the document is not well-formed.
+    pub fn document(document: Vec<u8>) -> anyhow::Result<Self> {
+        check_document(&document)?;
+        Ok(Self::Document(document))
+    }
+
+    /// Hash of the document, the entry itself if it is only the hash.
+    #[must_use]
+    pub fn hash(&self) -> [u8; DOCUMENT_HASH_BYTES] {
+        match self {
+            Self::Document(document) => document_hash(document),
+            Self::Hash(hash) => *hash,
+        }
+    }
+
+    /// Entry of only the hash of the document.
+    #[must_use]
+    pub fn to_hash(&self) -> Self {
+        Self::Hash(self.hash())
+    }
+}
+
+/// Catalyst Signed Documents block payload.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SignedDocuments(Vec<SignedDocumentEntry>);
+
+impl SignedDocuments {
+    /// Create new Catalyst Signed Documents payload.
+    /// ## Errors
+    ///
+    /// Returns an error if any of the documents is not well-formed.
+    pub fn new(entries: Vec<SignedDocumentEntry>) -> anyhow::Result<Self> {
+        let signed_documents = Self(entries);
+        signed_documents.check()?;
+        Ok(signed_documents)
+    }
+
+    /// Entries of the payload.
+    #[must_use]
+    pub fn entries(&self) -> &[SignedDocumentEntry] {
+        &self.0
+    }
+
+    /// Hashes of all documents of the payload.
+    #[must_use]
+    pub fn document_hashes(&self) -> Vec<[u8; DOCUMENT_HASH_BYTES]> {
+        self.0.iter().map(SignedDocumentEntry::hash).collect()
+    }
+
+    /// Payload with only the hashes of the documents.
+    #[must_use]
+    pub fn to_hashes(&self) -> Self {
+        Self(self.0.iter().map(SignedDocumentEntry::to_hash).collect())
+    }
+
+    /// Check every embedded document is well-formed.
+    /// ## Errors
+    ///
+    /// Returns an error if any of the documents is not well-formed.
+    pub fn check(&self) -> anyhow::Result<()> {
+        for (index, entry) in self.0.iter().enumerate() {
+            if let SignedDocumentEntry::Document(document) = entry {
+                check_document(document)
+                    .map_err(|e| anyhow::anyhow!("Invalid signed document {index}: {e}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encode as the Catalyst Signed Documents block data.
+    /// ## Errors
+    ///
+    /// Returns an error if encoding fails.
+    pub fn to_block_data(&self) -> anyhow::Result<BlockData> {
+        let mut encoder = minicbor::Encoder::new(Vec::new());
+        encoder.tag(minicbor::data::Tag::new(SIGNED_DOCUMENTS_CBOR_TAG))?;
+        encoder.array(self.0.len().try_into()?)?;
+        for entry in &self.0 {
+            match entry {
+                // Documents are already CBOR encoded, so they are embedded as they are.
+                SignedDocumentEntry::Document(document) => {
+                    encoder.writer_mut().extend_from_slice(document);
+                },
+                SignedDocumentEntry::Hash(hash) => {
+                    encoder.bytes(hash)?;
+                },
+            }
+        }
+        BlockData::from_payload(encoder.writer())
+    }
+
+    /// Decode the Catalyst Signed Documents from the block data, `None` if the block data
+    /// does not carry signed documents.
+    /// ## Errors
+    ///
+    /// Returns an error if the block data is malformed signed documents payload, or any
+    /// of the documents is not well-formed.
+    pub fn from_block_data(block_data: &BlockData) -> anyhow::Result<Option<Self>> {
+        let payload = block_data.payload()?;
+        let mut cbor_decoder = minicbor::Decoder::new(payload);
+        let is_signed_documents = cbor_decoder
+            .probe()
+            .tag()
+            .is_ok_and(|tag| tag.as_u64() == SIGNED_DOCUMENTS_CBOR_TAG);
+        if !is_signed_documents {
+            return Ok(None);
+        }
+
+        cbor_decoder.tag()?;
+        let number_of_entries = cbor_decoder.array()?.ok_or(anyhow::anyhow!(
+            "Invalid cbor for signed documents, indefinite length array"
+        ))?;
+        let mut entries = Vec::new();
+        for _entry in 0..number_of_entries {
+            if cbor_decoder.datatype()? == minicbor::data::Type::Bytes {
+                let hash: [u8; DOCUMENT_HASH_BYTES] = cbor_decoder
+                    .bytes()
+                    .map_err(|e| anyhow::anyhow!(format!("Invalid cbor for document hash : {e}")))?
+                    .try_into()?;
+                entries.push(SignedDocumentEntry::Hash(hash));
+            } else {
+                let start = cbor_decoder.position();
+                cbor_decoder
+                    .skip()
+                    .map_err(|e| anyhow::anyhow!(format!("Invalid cbor for document : {e}")))?;
+                let document = payload
+                    .get(start..cbor_decoder.position())
+                    .ok_or(anyhow::anyhow!("Invalid signed document range"))?;
+                entries.push(SignedDocumentEntry::Document(document.to_vec()));
+            }
+        }
+
+        Self::new(entries).map(Some)
+    }
+}
+
+/// BLAKE2b-256 hash of the COSE encoded document.
+fn document_hash(document: &[u8]) -> [u8; DOCUMENT_HASH_BYTES] {
+    let mut hash = [0; DOCUMENT_HASH_BYTES];
+    hash.copy_from_slice(
+        Params::new()
+            .hash_length(DOCUMENT_HASH_BYTES)
+            .hash(document)
+            .as_bytes(),
+    );
+    hash
+}
+
+/// Check the document is a well-formed Catalyst Signed Document, a COSE Sign structure
+/// with a payload and at least one signature.
+fn check_document(document: &[u8]) -> anyhow::Result<()> {
+    let cose = CoseSign::from_tagged_slice(document)
+        .or_else(|_| CoseSign::from_slice(document))
+        .map_err(|e| anyhow::anyhow!("Document is not a COSE Sign structure: {e}"))?;
+    if cose.payload.is_none() {
+        anyhow::bail!("Document MUST have a payload");
+    }
+    if cose.signatures.is_empty() {
+        anyhow::bail!("Document MUST have at least one signature");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use coset::{CborSerializable, CoseSignBuilder, CoseSignatureBuilder, TaggedCborSerializable};
+
+    use super::{SignedDocumentEntry, SignedDocuments};
+    use crate::{serialize::BlockData, validator_set::ValidatorSet};
+
+    fn signed_document(payload: &[u8]) -> CoseSignBuilder {
+        CoseSignBuilder::new()
+            .payload(payload.to_vec())
+            .add_signature(CoseSignatureBuilder::new().signature(vec![1; 64]).build())
+    }
+
+    #[test]
+    fn signed_documents_encoding() {
+        let document = signed_document(b"doc 1").build().to_vec().unwrap();
+        let tagged_document = signed_document(b"doc 2").build().to_tagged_vec().unwrap();
+        let signed_documents = SignedDocuments::new(vec![
+            SignedDocumentEntry::document(document.clone()).unwrap(),
+            SignedDocumentEntry::document(tagged_document).unwrap(),
+            SignedDocumentEntry::document(document.clone())
+                .unwrap()
+                .to_hash(),
+        ])
+        .unwrap();
+
+        let block_data = signed_documents.to_block_data().unwrap();
+        let decoded = SignedDocuments::from_block_data(&block_data)
+            .unwrap()
+            .unwrap();
+        assert_eq!(decoded, signed_documents);
+        assert_eq!(
+            decoded.document_hashes(),
+            decoded.to_hashes().document_hashes()
+        );
+        assert_eq!(
+            decoded.document_hashes().first(),
+            decoded.document_hashes().get(2)
+        );
+
+        let hashes = signed_documents.to_hashes();
+        assert_eq!(
+            SignedDocuments::from_block_data(&hashes.to_block_data().unwrap()).unwrap(),
+            Some(hashes)
+        );
+
+        // Not a signed documents payload.
+        let block_data = BlockData::from_payload(&[1, 2, 3]).unwrap();
+        assert_eq!(SignedDocuments::from_block_data(&block_data).unwrap(), None);
+        let block_data = ValidatorSet::new(vec![crate::serialize::Kid([1; 16])], None)
+            .unwrap()
+            .to_block_data()
+            .unwrap();
+        assert_eq!(SignedDocuments::from_block_data(&block_data).unwrap(), None);
+    }
+
+    #[test]
+    fn malformed_signed_documents() {
+        let unsigned = CoseSignBuilder::new()
+            .payload(b"doc".to_vec())
+            .build()
+            .to_vec()
+            .unwrap();
+        assert!(SignedDocumentEntry::document(unsigned.clone()).is_err());
+        let detached = CoseSignBuilder::new()
+            .add_signature(CoseSignatureBuilder::new().signature(vec![1; 64]).build())
+            .build()
+            .to_vec()
+            .unwrap();
+        assert!(SignedDocumentEntry::document(detached).is_err());
+        assert!(SignedDocumentEntry::document(vec![0x01]).is_err());
+
+        // Malformed documents are rejected when decoding the block data.
+        let block_data = SignedDocuments(vec![SignedDocumentEntry::Document(unsigned)])
+            .to_block_data()
+            .unwrap();
+        assert!(SignedDocuments::from_block_data(&block_data).is_err());
+    }
+}
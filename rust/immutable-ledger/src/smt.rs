@@ -0,0 +1,226 @@
+//! Sparse Merkle tree
+//!
+//! Leaves are keyed by an unsigned 64-bit integer, so the tree has a fixed depth of 64
+//! levels, one per bit of the key starting from the most significant bit:
+//! ```text
+//! leaf  = BLAKE3(0x00 | key: u64 (big endian) | value)
+//! node  = BLAKE3(0x01 | left | right)
+//! empty = 32 zero bytes
+//! ```
+//! A node of two empty subtrees is empty, so only the non-empty subtrees are hashed.
+
+use std::collections::BTreeMap;
+
+/// Size of the tree hashes in bytes.
+pub const SMT_HASH_BYTES: usize = 32;
+
+/// Hash of a tree node.
+pub type SmtHash = [u8; SMT_HASH_BYTES];
+
+/// Depth of the tree, the number of bits of the key.
+const DEPTH: u32 = u64::BITS;
+
+/// Hash of an empty subtree.
+const EMPTY: SmtHash = [0; SMT_HASH_BYTES];
+
+/// Domain separation prefix of the leaf hashes.
+const LEAF_PREFIX: u8 = 0x00;
+
+/// Domain separation prefix of the node hashes.
+const NODE_PREFIX: u8 = 0x01;
+
+/// Sparse Merkle tree of the 64-bit keys.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SparseMerkleTree {
+    /// Leaf hashes ordered by key.
+    leaves: BTreeMap<u64, SmtHash>,
+}
+
+impl SparseMerkleTree {
+    /// Create new empty tree.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of the leaves.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Returns `true` if the tree has no leaves.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Insert the value under the key, replacing the previous value.
+    pub fn insert(&mut self, key: u64, value: &[u8]) {
+        self.leaves.insert(key, leaf_hash(key, value));
+    }
+
+    /// Root hash of the tree, 32 zero bytes if the tree is empty.
+    #[must_use]
+    pub fn root(&self) -> SmtHash {
+        let leaves: Vec<_> = self.leaves.iter().map(|(k, v)| (*k, *v)).collect();
+        subtree_root(&leaves, DEPTH)
+    }
+
+    /// Membership proof of the key, `None` if the key is absent.
+    #[must_use]
+    pub fn proof(&self, key: u64) -> Option<MerkleProof> {
+        if !self.leaves.contains_key(&key) {
+            return None;
+        }
+
+        let leaves: Vec<_> = self.leaves.iter().map(|(k, v)| (*k, *v)).collect();
+        let mut subtree = leaves.as_slice();
+        let mut siblings = Vec::new();
+        for level in (0..DEPTH).rev() {
+            let (left, right) = split(subtree, level);
+            if bit(key, level) {
+                siblings.push(subtree_root(left, level));
+                subtree = right;
+            } else {
+                siblings.push(subtree_root(right, level));
+                subtree = left;
+            }
+        }
+        // Siblings are ordered from the leaf up to the root.
+        siblings.reverse();
+        Some(MerkleProof { key, siblings })
+    }
+}
+
+/// Membership proof of a key of the sparse Merkle tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MerkleProof {
+    /// Key of the leaf.
+    key: u64,
+    /// Sibling hashes ordered from the leaf up to the root.
+    siblings: Vec<SmtHash>,
+}
+
+impl MerkleProof {
+    /// Key of the leaf.
+    #[must_use]
+    pub fn key(&self) -> u64 {
+        self.key
+    }
+
+    /// Sibling hashes ordered from the leaf up to the root.
+    #[must_use]
+    pub fn siblings(&self) -> &[SmtHash] {
+        &self.siblings
+    }
+
+    /// Verify the value is stored under the key of the proof, in the tree of the root.
+    #[must_use]
+    pub fn verify(&self, root: &SmtHash, value: &[u8]) -> bool {
+        if self.siblings.len() != usize::try_from(DEPTH).unwrap_or(usize::MAX) {
+            return false;
+        }
+        let hash = (0..DEPTH).zip(&self.siblings).fold(
+            leaf_hash(self.key, value),
+            |hash, (level, sibling)| {
+                if bit(self.key, level) {
+                    node_hash(sibling, &hash)
+                } else {
+                    node_hash(&hash, sibling)
+                }
+            },
+        );
+        &hash == root
+    }
+}
+
+/// Hash of the leaf.
+fn leaf_hash(key: u64, value: &[u8]) -> SmtHash {
+    *blake3::Hasher::new()
+        .update(&[LEAF_PREFIX])
+        .update(&key.to_be_bytes())
+        .update(value)
+        .finalize()
+        .as_bytes()
+}
+
+/// Hash of the node, empty if both subtrees are empty.
+fn node_hash(left: &SmtHash, right: &SmtHash) -> SmtHash {
+    if left == &EMPTY && right == &EMPTY {
+        return EMPTY;
+    }
+    *blake3::Hasher::new()
+        .update(&[NODE_PREFIX])
+        .update(left)
+        .update(right)
+        .finalize()
+        .as_bytes()
+}
+
+/// Returns `true` if the bit of the key at the level is set.
+fn bit(key: u64, level: u32) -> bool {
+    key.checked_shr(level).is_some_and(|k| k & 1 == 1)
+}
+
+/// Split the ordered leaves of a subtree into its left and right subtrees, by the bit at
+/// the level.
+fn split(leaves: &[(u64, SmtHash)], level: u32) -> (&[(u64, SmtHash)], &[(u64, SmtHash)]) {
+    leaves.split_at(leaves.partition_point(|(key, _)| !bit(*key, level)))
+}
+
+/// Root hash of the subtree of the ordered leaves, with the height of the level.
+fn subtree_root(leaves: &[(u64, SmtHash)], height: u32) -> SmtHash {
+    match (leaves, height.checked_sub(1)) {
+        ([], _) => EMPTY,
+        ([(_, leaf), ..], None) => *leaf,
+        (_, Some(level)) => {
+            let (left, right) = split(leaves, level);
+            node_hash(&subtree_root(left, level), &subtree_root(right, level))
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SparseMerkleTree, EMPTY};
+
+    #[test]
+    fn root_and_proofs() {
+        let mut tree = SparseMerkleTree::new();
+        assert_eq!(tree.root(), EMPTY);
+        assert!(tree.proof(0).is_none());
+
+        for key in 0..10 {
+            tree.insert(key, &key.to_le_bytes());
+        }
+        tree.insert(u64::MAX, b"last");
+        assert_eq!(tree.len(), 11);
+        let root = tree.root();
+        assert_ne!(root, EMPTY);
+
+        for key in 0..10_u64 {
+            let proof = tree.proof(key).unwrap();
+            assert_eq!(proof.key(), key);
+            assert!(proof.verify(&root, &key.to_le_bytes()));
+            assert!(!proof.verify(&root, b"other"));
+        }
+        assert!(tree.proof(u64::MAX).unwrap().verify(&root, b"last"));
+        assert!(tree.proof(10).is_none());
+
+        // Root depends on every leaf.
+        let proof = tree.proof(3).unwrap();
+        tree.insert(3, b"updated");
+        assert_ne!(tree.root(), root);
+        assert!(!proof.verify(&tree.root(), &3_u64.to_le_bytes()));
+        assert!(tree.proof(3).unwrap().verify(&tree.root(), b"updated"));
+
+        // Root does not depend on the insertion order.
+        let mut reversed = SparseMerkleTree::new();
+        reversed.insert(u64::MAX, b"last");
+        for key in (0..10_u64).rev() {
+            reversed.insert(key, &key.to_le_bytes());
+        }
+        assert_eq!(reversed.root(), root);
+    }
+}
@@ -0,0 +1,265 @@
+//! Ledger snapshot block payload
+//!
+//! A snapshot commits to the blocks of a height range with the root of the sparse Merkle
+//! tree of their hashes, keyed by height, each hashed with the hash function of its own
+//! block header. The snapshot block is signed by the validators as any other block, so
+//! once it is appended, the committed blocks can be pruned from the store and the chain
+//! validated from the snapshot block. A pruned block remains verifiable with its Merkle
+//! proof against the snapshot state root.
+//!
+//! The range of the snapshot begins at the genesis block or the previous snapshot block,
+//! and ends right before the snapshot block, so the snapshots chain every pruned block.
+//!
+//! ```cddl
+//! snapshot = #6.32802([
+//!     from-height: uint,
+//!     to-height: uint,
+//!     state-root: bytes .size 32,
+//! ])
+//! ```
+
+use crate::{
+    serialize::{Block, BlockData},
+    smt::{MerkleProof, SmtHash, SparseMerkleTree, SMT_HASH_BYTES},
+    store::BlockStore,
+};
+
+/// CBOR tag of the snapshot block payload.
+const SNAPSHOT_CBOR_TAG: u64 = 32802;
+
+/// Number of the snapshot fields.
+const SNAPSHOT_SIZE: u64 = 3;
+
+/// Ledger snapshot, the commitment to the blocks of the height range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    /// Height of the first committed block.
+    from_height: i64,
+    /// Height of the last committed block.
+    to_height: i64,
+    /// Root of the sparse Merkle tree of the committed block hashes.
+    state_root: SmtHash,
+}
+
+impl Snapshot {
+    /// Create new snapshot.
+    /// ## Errors
+    ///
+    /// Returns an error if the height range is empty or negative.
+    pub fn new(from_height: i64, to_height: i64, state_root: SmtHash) -> anyhow::Result<Self> {
+        let snapshot = Self {
+            from_height,
+            to_height,
+            state_root,
+        };
+        snapshot.check()?;
+        Ok(snapshot)
+    }
+
+    /// Create the snapshot of the stored blocks of the height range.
+    /// ## Errors
+    ///
+    /// Returns an error if any block of the range is not stored, or cannot be read or
+    /// encoded.
+    pub fn from_store(
+        store: &BlockStore, from_height: i64, to_height: i64,
+    ) -> anyhow::Result<Self> {
+        let mut blocks = Vec::new();
+        for height in from_height..=to_height {
+            let block = store
+                .get(height)?
+                .ok_or(anyhow::anyhow!("Block {height} is not stored"))?;
+            blocks.push(block);
+        }
+        Self::new(from_height, to_height, state_tree(&blocks)?.root())
+    }
+
+    /// Height of the first committed block.
+    #[must_use]
+    pub fn from_height(&self) -> i64 {
+        self.from_height
+    }
+
+    /// Height of the last committed block.
+    #[must_use]
+    pub fn to_height(&self) -> i64 {
+        self.to_height
+    }
+
+    /// Root of the sparse Merkle tree of the committed block hashes.
+    #[must_use]
+    pub fn state_root(&self) -> &SmtHash {
+        &self.state_root
+    }
+
+    /// Returns `true` if the block is committed by the snapshot, verified by its Merkle
+    /// proof.
+    #[must_use]
+    pub fn verify_block(&self, block: &Block, proof: &MerkleProof) -> bool {
+        let height = block.header().height();
+        if height < self.from_height || height > self.to_height {
+            return false;
+        }
+        u64::try_from(height).is_ok_and(|key| key == proof.key())
+            && block_hash(block).is_ok_and(|hash| proof.verify(&self.state_root, &hash))
+    }
+
+    /// Check the snapshot is well-formed.
+    /// ## Errors
+    ///
+    /// Returns an error if the height range is empty or negative.
+    pub fn check(&self) -> anyhow::Result<()> {
+        if self.from_height < 0 || self.to_height < self.from_height {
+            anyhow::bail!(
+                "Invalid snapshot height range {}..={}",
+                self.from_height,
+                self.to_height
+            );
+        }
+        Ok(())
+    }
+
+    /// Encode as the snapshot block data.
+    /// ## Errors
+    ///
+    /// Returns an error if encoding fails.
+    pub fn to_block_data(&self) -> anyhow::Result<BlockData> {
+        let mut encoder = minicbor::Encoder::new(Vec::new());
+        encoder.tag(minicbor::data::Tag::new(SNAPSHOT_CBOR_TAG))?;
+        encoder.array(SNAPSHOT_SIZE)?;
+        encoder.i64(self.from_height)?;
+        encoder.i64(self.to_height)?;
+        encoder.bytes(&self.state_root)?;
+        BlockData::from_payload(encoder.writer())
+    }
+
+    /// Decode the snapshot from the block data, `None` if the block data is not a
+    /// snapshot.
+    /// ## Errors
+    ///
+    /// Returns an error if the block data is a malformed or invalid snapshot.
+    pub fn from_block_data(block_data: &BlockData) -> anyhow::Result<Option<Self>> {
+        let mut cbor_decoder = minicbor::Decoder::new(block_data.payload()?);
+        let is_snapshot = cbor_decoder
+            .probe()
+            .tag()
+            .is_ok_and(|tag| tag.as_u64() == SNAPSHOT_CBOR_TAG);
+        if !is_snapshot {
+            return Ok(None);
+        }
+
+        cbor_decoder.tag()?;
+        if cbor_decoder.array()? != Some(SNAPSHOT_SIZE) {
+            anyhow::bail!("Invalid cbor for snapshot, expected {SNAPSHOT_SIZE} elements");
+        }
+        let from_height = cbor_decoder
+            .i64()
+            .map_err(|e| anyhow::anyhow!(format!("Invalid cbor for from height : {e}")))?;
+        let to_height = cbor_decoder
+            .i64()
+            .map_err(|e| anyhow::anyhow!(format!("Invalid cbor for to height : {e}")))?;
+        let state_root: [u8; SMT_HASH_BYTES] = cbor_decoder
+            .bytes()
+            .map_err(|e| anyhow::anyhow!(format!("Invalid cbor for state root : {e}")))?
+            .try_into()?;
+
+        Self::new(from_height, to_height, state_root).map(Some)
+    }
+}
+
+/// Sparse Merkle tree of the block hashes, keyed by height.
+/// ## Errors
+///
+/// Returns an error if any block has a negative height or cannot be encoded.
+pub fn state_tree<'a>(
+    blocks: impl IntoIterator<Item = &'a Block>,
+) -> anyhow::Result<SparseMerkleTree> {
+    let mut tree = SparseMerkleTree::new();
+    for block in blocks {
+        let height = block.header().height();
+        let key =
+            u64::try_from(height).map_err(|_| anyhow::anyhow!("Invalid block height {height}"))?;
+        tree.insert(key, &block_hash(block)?);
+    }
+    Ok(tree)
+}
+
+/// Hash of the block bytes, with the hash function of the block header.
+fn block_hash(block: &Block) -> anyhow::Result<Vec<u8>> {
+    block.header().hash_function().hash(&block.to_bytes()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{state_tree, Snapshot};
+    use crate::serialize::{Block, BlockBuilder, BlockData, HashFunction::Blake2b};
+
+    /// Build a chain of blocks.
+    fn chain(length: i64) -> Vec<Block> {
+        let builder = BlockBuilder::new()
+            .chain_id(Uuid::now_v7())
+            .ledger_type(Uuid::new_v4())
+            .purpose_id(Uuid::now_v7())
+            .block_data(BlockData::from_payload(&[1, 2, 3]).unwrap());
+
+        let mut blocks = vec![builder
+            .clone()
+            .block_time_stamp(1_728_474_515)
+            .genesis(Blake2b)
+            .unwrap()
+            .build()
+            .unwrap()];
+        for height in 1..length {
+            let previous = blocks.last().unwrap().to_bytes().unwrap();
+            blocks.push(
+                builder
+                    .clone()
+                    .height(height)
+                    .block_time_stamp(1_728_474_515 + height)
+                    .previous_block_hash(Blake2b, Blake2b.hash(&previous).unwrap())
+                    .build()
+                    .unwrap(),
+            );
+        }
+        blocks
+    }
+
+    #[test]
+    fn snapshot_encoding() {
+        let snapshot = Snapshot::new(0, 9, [7; 32]).unwrap();
+        let block_data = snapshot.to_block_data().unwrap();
+        assert_eq!(
+            Snapshot::from_block_data(&block_data).unwrap(),
+            Some(snapshot)
+        );
+
+        // Not a snapshot.
+        let block_data = BlockData::from_payload(&[1, 2, 3]).unwrap();
+        assert_eq!(Snapshot::from_block_data(&block_data).unwrap(), None);
+
+        assert!(Snapshot::new(-1, 9, [7; 32]).is_err());
+        assert!(Snapshot::new(5, 4, [7; 32]).is_err());
+    }
+
+    #[test]
+    fn pruned_block_verification() {
+        let blocks = chain(5);
+        let tree = state_tree(&blocks).unwrap();
+        let snapshot = Snapshot::new(0, 4, tree.root()).unwrap();
+
+        for block in &blocks {
+            let height = u64::try_from(block.header().height()).unwrap();
+            assert!(snapshot.verify_block(block, &tree.proof(height).unwrap()));
+        }
+
+        // Proof of another block.
+        let first = blocks.first().unwrap();
+        assert!(!snapshot.verify_block(first, &tree.proof(1).unwrap()));
+
+        // Block outside of the snapshot range.
+        let partial = Snapshot::new(1, 4, tree.root()).unwrap();
+        assert!(!partial.verify_block(first, &tree.proof(0).unwrap()));
+    }
+}
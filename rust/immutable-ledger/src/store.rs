@@ -0,0 +1,638 @@
+//! Persistent block store
+//!
+//! Encoded blocks are appended to segment files inside the store directory:
+//! ```text
+//! record = length: u64 (little endian) | checksum: BLAKE3(block) | block
+//! ```
+//! A new segment is started when the current one exceeds the maximum segment size.
+//! Every append is synced to disk before it is indexed, an incomplete record left at
+//! the end of the last segment by an interrupted append is truncated when the store is
+//! opened. Any other corruption fails the integrity verification on open.
+//!
+//! Segments below a snapshot block could be pruned to bound the disk use, the store then
+//! begins at the first block of the oldest remaining segment.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::bail;
+
+use crate::{
+    serialize::{blake3, Block},
+    snapshot::Snapshot,
+};
+
+/// Default maximum size of a segment file in bytes.
+pub const DEFAULT_SEGMENT_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Segment file name prefix.
+const SEGMENT_PREFIX: &str = "segment-";
+
+/// Segment file name extension.
+const SEGMENT_EXTENSION: &str = "blk";
+
+/// Size of the record length field in bytes.
+const LENGTH_SIZE: usize = std::mem::size_of::<u64>();
+
+/// Size of the record checksum field in bytes.
+const CHECKSUM_SIZE: usize = 32;
+
+/// Size of the record header in bytes.
+const RECORD_HEADER_SIZE: usize = LENGTH_SIZE + CHECKSUM_SIZE;
+
+/// Location of the encoded block within the store.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Location {
+    /// Segment number.
+    segment: u64,
+    /// Offset of the encoded block within the segment.
+    offset: u64,
+    /// Length of the encoded block.
+    length: u64,
+}
+
+/// Append-only persistent store of the encoded blocks, indexed by height and block hash.
+#[derive(Debug)]
+pub struct BlockStore {
+    /// Store directory.
+    dir: PathBuf,
+    /// Maximum size of a segment file in bytes.
+    max_segment_size: u64,
+    /// Height of the first stored block.
+    first_height: Option<i64>,
+    /// Locations of the stored blocks ordered by height.
+    locations: Vec<Location>,
+    /// Block heights by block hash.
+    hashes: HashMap<Vec<u8>, i64>,
+    /// Current segment number.
+    segment: u64,
+    /// Current segment size in bytes.
+    segment_size: u64,
+}
+
+impl BlockStore {
+    /// Open the store in the directory, creating it if needed, with the default maximum
+    /// segment size.
+    /// ## Errors
+    ///
+    /// Returns an error if the store cannot be read or fails the integrity verification.
+    pub fn open(dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::open_with_segment_size(dir, DEFAULT_SEGMENT_SIZE)
+    }
+
+    /// Open the store in the directory, creating it if needed.
+    /// ## Errors
+    ///
+    /// Returns an error if the store cannot be read or fails the integrity verification.
+    pub fn open_with_segment_size(
+        dir: impl AsRef<Path>, max_segment_size: u64,
+    ) -> anyhow::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let mut store = Self {
+            dir,
+            max_segment_size,
+            first_height: None,
+            locations: Vec::new(),
+            hashes: HashMap::new(),
+            segment: 0,
+            segment_size: 0,
+        };
+
+        let segments = store.segments()?;
+        let last_segment = segments.last().copied();
+        for segment in segments {
+            store.load_segment(segment, Some(segment) == last_segment)?;
+        }
+
+        Ok(store)
+    }
+
+    /// Number of the stored blocks.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.locations.len()
+    }
+
+    /// Returns `true` if the store has no blocks.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.locations.is_empty()
+    }
+
+    /// Height of the first stored block.
+    #[must_use]
+    pub fn first_height(&self) -> Option<i64> {
+        self.first_height
+    }
+
+    /// Height of the last stored block.
+    #[must_use]
+    pub fn tip_height(&self) -> Option<i64> {
+        let count = i64::try_from(self.locations.len()).ok()?;
+        self.first_height
+            .and_then(|first| first.checked_add(count)?.checked_sub(1))
+    }
+
+    /// Append the block to the store, the block height MUST follow the last stored block
+    /// height.
+    /// ## Errors
+    ///
+    /// Returns an error if the block height is unexpected or the block cannot be
+    /// encoded or written.
+    pub fn append(&mut self, block: &Block) -> anyhow::Result<()> {
+        let height = block.header().height();
+        if let Some(tip) = self.tip_height() {
+            if Some(height) != tip.checked_add(1) {
+                bail!(
+                    "Unexpected block height {height}, expected {}",
+                    tip.saturating_add(1)
+                );
+            }
+        }
+
+        let encoded = block.to_bytes()?;
+        let hash = block.header().hash_function().hash(&encoded)?;
+        let record = encode_record(&encoded)?;
+        let record_size = u64::try_from(record.len())?;
+
+        if self.segment_size > 0
+            && self.segment_size.saturating_add(record_size) > self.max_segment_size
+        {
+            self.segment = self.segment.saturating_add(1);
+            self.segment_size = 0;
+        }
+
+        let path = self.segment_path(self.segment);
+        let new_segment = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        if let Err(e) = file.write_all(&record).and_then(|()| file.sync_data()) {
+            // Drop a partially written record, so the segment stays consistent.
+            file.set_len(self.segment_size)?;
+            return Err(e.into());
+        }
+        if new_segment {
+            sync_dir(&self.dir)?;
+        }
+
+        self.locations.push(Location {
+            segment: self.segment,
+            offset: self
+                .segment_size
+                .saturating_add(u64::try_from(RECORD_HEADER_SIZE)?),
+            length: u64::try_from(encoded.len())?,
+        });
+        if self.first_height.is_none() {
+            self.first_height = Some(height);
+        }
+        self.hashes.insert(hash, height);
+        self.segment_size = self.segment_size.saturating_add(record_size);
+        Ok(())
+    }
+
+    /// Get the block by height.
+    /// ## Errors
+    ///
+    /// Returns an error if the block cannot be read or decoded.
+    pub fn get(&self, height: i64) -> anyhow::Result<Option<Block>> {
+        let Some(location) = self.location(height) else {
+            return Ok(None);
+        };
+        self.read(location).map(Some)
+    }
+
+    /// Get the block by hash of its encoded bytes, hashed with the hash function of
+    /// the block header.
+    /// ## Errors
+    ///
+    /// Returns an error if the block cannot be read or decoded.
+    pub fn get_by_hash(&self, hash: &[u8]) -> anyhow::Result<Option<Block>> {
+        match self.hashes.get(hash) {
+            Some(height) => self.get(*height),
+            None => Ok(None),
+        }
+    }
+
+    /// Iterate over the stored blocks starting from the height.
+    pub fn iter_from(&self, height: i64) -> impl Iterator<Item = anyhow::Result<Block>> + '_ {
+        let start = self
+            .first_height
+            .and_then(|first| usize::try_from(height.saturating_sub(first)).ok())
+            .unwrap_or(0);
+        self.locations
+            .iter()
+            .skip(start)
+            .map(|location| self.read(*location))
+    }
+
+    /// Prune the segments below the snapshot block at the height, the blocks committed
+    /// by the snapshot remain verifiable by their Merkle proofs against the snapshot
+    /// state root. Only whole segments are removed, so the segment of the snapshot block
+    /// is kept with all its blocks.
+    ///
+    /// Returns the number of the pruned blocks.
+    /// ## Errors
+    ///
+    /// Returns an error if the block at the height is not a stored snapshot block, or the
+    /// segments cannot be removed.
+    pub fn prune_to_snapshot(&mut self, height: i64) -> anyhow::Result<usize> {
+        let (Some(location), Some(first_height)) = (self.location(height), self.first_height)
+        else {
+            bail!("Block {height} is not stored");
+        };
+        if Snapshot::from_block_data(self.read(location)?.data())?.is_none() {
+            bail!("Block {height} is not a snapshot block");
+        }
+
+        let pruned = self
+            .locations
+            .iter()
+            .take_while(|l| l.segment < location.segment)
+            .count();
+        if pruned == 0 {
+            return Ok(0);
+        }
+
+        // Segments are removed in order, so an interrupted pruning leaves a contiguous
+        // store.
+        let segments: BTreeSet<_> = self
+            .locations
+            .iter()
+            .take(pruned)
+            .map(|l| l.segment)
+            .collect();
+        for segment in segments {
+            fs::remove_file(self.segment_path(segment))?;
+        }
+        sync_dir(&self.dir)?;
+
+        let first_height = first_height.saturating_add(i64::try_from(pruned)?);
+        self.locations.drain(..pruned);
+        self.first_height = Some(first_height);
+        self.hashes
+            .retain(|_, block_height| *block_height >= first_height);
+        Ok(pruned)
+    }
+
+    /// Location of the block by height.
+    fn location(&self, height: i64) -> Option<Location> {
+        let index = usize::try_from(height.checked_sub(self.first_height?)?).ok()?;
+        self.locations.get(index).copied()
+    }
+
+    /// Read the block from the location.
+    fn read(&self, location: Location) -> anyhow::Result<Block> {
+        let mut file = File::open(self.segment_path(location.segment))?;
+        file.seek(SeekFrom::Start(location.offset))?;
+        let mut encoded = vec![0; usize::try_from(location.length)?];
+        file.read_exact(&mut encoded)?;
+        Block::from_bytes(&encoded)
+    }
+
+    /// Path of the segment file.
+    fn segment_path(&self, segment: u64) -> PathBuf {
+        self.dir
+            .join(format!("{SEGMENT_PREFIX}{segment:016}.{SEGMENT_EXTENSION}"))
+    }
+
+    /// Sorted numbers of the segment files in the store directory.
+    fn segments(&self) -> anyhow::Result<Vec<u64>> {
+        let mut segments = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(SEGMENT_EXTENSION) {
+                continue;
+            }
+            let segment = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.strip_prefix(SEGMENT_PREFIX))
+                .and_then(|s| s.parse().ok());
+            if let Some(segment) = segment {
+                segments.push(segment);
+            }
+        }
+        segments.sort_unstable();
+        Ok(segments)
+    }
+
+    /// Load and verify the segment records, truncating an incomplete record at the end of
+    /// the last segment.
+    fn load_segment(&mut self, segment: u64, last: bool) -> anyhow::Result<()> {
+        let path = self.segment_path(segment);
+        let data = fs::read(&path)?;
+
+        let mut offset = 0;
+        while offset < data.len() {
+            let (encoded, record_size) = match decode_record(&data, offset) {
+                Record::Complete(encoded, record_size) => (encoded, record_size),
+                Record::Incomplete if last => {
+                    let file = OpenOptions::new().write(true).open(&path)?;
+                    file.set_len(u64::try_from(offset)?)?;
+                    file.sync_all()?;
+                    break;
+                },
+                Record::Incomplete | Record::Corrupted => {
+                    bail!("Corrupted record in segment {segment} at offset {offset}");
+                },
+            };
+
+            let block = Block::from_bytes(encoded).map_err(|e| {
+                anyhow::anyhow!("Invalid block in segment {segment} at offset {offset}: {e}")
+            })?;
+            let height = block.header().height();
+            if let Some(tip) = self.tip_height() {
+                if Some(height) != tip.checked_add(1) {
+                    bail!(
+                        "Unexpected block height {height} in segment {segment} at offset {offset}"
+                    );
+                }
+            }
+
+            self.locations.push(Location {
+                segment,
+                offset: u64::try_from(offset.saturating_add(RECORD_HEADER_SIZE))?,
+                length: u64::try_from(encoded.len())?,
+            });
+            if self.first_height.is_none() {
+                self.first_height = Some(height);
+            }
+            self.hashes
+                .insert(block.header().hash_function().hash(encoded)?, height);
+            offset = offset.saturating_add(record_size);
+        }
+
+        self.segment = segment;
+        self.segment_size = u64::try_from(offset)?;
+        Ok(())
+    }
+}
+
+/// Encode the block bytes as a segment record.
+fn encode_record(encoded: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let length = u64::try_from(encoded.len())?;
+    Ok([
+        length.to_le_bytes().as_slice(),
+        blake3(encoded)?.as_slice(),
+        encoded,
+    ]
+    .concat())
+}
+
+/// Decoded segment record.
+enum Record<'a> {
+    /// Complete record with the block bytes and the record size.
+    Complete(&'a [u8], usize),
+    /// Record left at the end of the segment by an interrupted append.
+    Incomplete,
+    /// Record checksum does not match.
+    Corrupted,
+}
+
+/// Decode the segment record at the offset.
+fn decode_record(data: &[u8], offset: usize) -> Record<'_> {
+    let start = offset.saturating_add(RECORD_HEADER_SIZE);
+    let Some((length, checksum)) = data
+        .get(offset..start)
+        .map(|header| header.split_at(LENGTH_SIZE))
+    else {
+        return Record::Incomplete;
+    };
+    let Some(end) = length
+        .try_into()
+        .ok()
+        .and_then(|length| usize::try_from(u64::from_le_bytes(length)).ok())
+        .and_then(|length| start.checked_add(length))
+    else {
+        return Record::Corrupted;
+    };
+
+    let Some(encoded) = data.get(start..end) else {
+        return Record::Incomplete;
+    };
+    if blake3(encoded).ok().as_ref().map(<[u8; 32]>::as_slice) != Some(checksum) {
+        // The last record may be only partially flushed by an interrupted append.
+        return if end == data.len() {
+            Record::Incomplete
+        } else {
+            Record::Corrupted
+        };
+    }
+    Record::Complete(encoded, end.saturating_sub(offset))
+}
+
+/// Sync the directory entries, so a newly created segment file survives a crash.
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> anyhow::Result<()> {
+    File::open(dir)?.sync_all()?;
+    Ok(())
+}
+
+/// Sync the directory entries, not supported on this platform.
+#[cfg(not(unix))]
+#[allow(clippy::unnecessary_wraps)]
+fn sync_dir(_dir: &Path) -> anyhow::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env::temp_dir, fs::OpenOptions, io::Write, path::PathBuf};
+
+    use uuid::Uuid;
+
+    use super::{BlockStore, RECORD_HEADER_SIZE};
+    use crate::{
+        serialize::{Block, BlockBuilder, BlockData, HashFunction::Blake2b},
+        snapshot::{state_tree, Snapshot},
+    };
+
+    /// Unique temporary store directory.
+    fn store_dir() -> PathBuf {
+        temp_dir().join(format!("immutable-ledger-store-{}", Uuid::new_v4()))
+    }
+
+    /// Build a chain of blocks.
+    fn chain(length: i64) -> Vec<Block> {
+        let builder = BlockBuilder::new()
+            .chain_id(Uuid::now_v7())
+            .ledger_type(Uuid::new_v4())
+            .purpose_id(Uuid::now_v7())
+            .block_data(BlockData::from_payload(&[1, 2, 3]).unwrap());
+
+        let mut blocks = vec![builder
+            .clone()
+            .block_time_stamp(1_728_474_515)
+            .genesis(Blake2b)
+            .unwrap()
+            .build()
+            .unwrap()];
+        for height in 1..length {
+            let previous = blocks.last().unwrap().to_bytes().unwrap();
+            blocks.push(
+                builder
+                    .clone()
+                    .height(height)
+                    .block_time_stamp(1_728_474_515 + height)
+                    .previous_block_hash(Blake2b, Blake2b.hash(&previous).unwrap())
+                    .build()
+                    .unwrap(),
+            );
+        }
+        blocks
+    }
+
+    #[test]
+    fn append_and_reopen() {
+        let dir = store_dir();
+        let blocks = chain(10);
+
+        // Small segments, so blocks are spread over several segment files.
+        let mut store = BlockStore::open_with_segment_size(&dir, 256).unwrap();
+        for block in &blocks {
+            store.append(block).unwrap();
+        }
+        assert!(store.append(blocks.first().unwrap()).is_err());
+        assert!(store.segments().unwrap().len() > 1);
+        drop(store);
+
+        let store = BlockStore::open_with_segment_size(&dir, 256).unwrap();
+        assert_eq!(store.len(), blocks.len());
+        assert_eq!(store.first_height(), Some(0));
+        assert_eq!(store.tip_height(), Some(9));
+
+        for block in &blocks {
+            let height = block.header().height();
+            assert_eq!(store.get(height).unwrap().as_ref(), Some(block));
+
+            let hash = Blake2b.hash(&block.to_bytes().unwrap()).unwrap();
+            assert_eq!(store.get_by_hash(&hash).unwrap().as_ref(), Some(block));
+        }
+        assert!(store.get(10).unwrap().is_none());
+        assert!(store.get_by_hash(&[0; 64]).unwrap().is_none());
+
+        let from_height: Vec<_> = store.iter_from(7).map(Result::unwrap).collect();
+        assert_eq!(from_height, blocks.into_iter().skip(7).collect::<Vec<_>>());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn incomplete_append_is_truncated() {
+        let dir = store_dir();
+        let mut blocks = chain(4);
+        let next = blocks.pop().unwrap();
+
+        let mut store = BlockStore::open(&dir).unwrap();
+        for block in &blocks {
+            store.append(block).unwrap();
+        }
+        let segment = store.segment_path(store.segment);
+        drop(store);
+
+        // Simulate an interrupted append.
+        let mut file = OpenOptions::new().append(true).open(&segment).unwrap();
+        file.write_all(&[0xFF; RECORD_HEADER_SIZE - 1]).unwrap();
+        drop(file);
+
+        let mut store = BlockStore::open(&dir).unwrap();
+        assert_eq!(store.len(), blocks.len());
+        assert_eq!(store.tip_height(), Some(2));
+
+        // Store is still appendable after the recovery.
+        store.append(&next).unwrap();
+        drop(store);
+
+        let store = BlockStore::open(&dir).unwrap();
+        assert_eq!(store.get(3).unwrap(), Some(next));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn corrupted_segment_fails_verification() {
+        let dir = store_dir();
+        let blocks = chain(6);
+
+        let mut store = BlockStore::open_with_segment_size(&dir, 256).unwrap();
+        for block in &blocks {
+            store.append(block).unwrap();
+        }
+        let first_segment = store.segment_path(0);
+        drop(store);
+
+        // Flip a byte of the first record in the first (not the last) segment.
+        let mut data = std::fs::read(&first_segment).unwrap();
+        if let Some(byte) = data.get_mut(RECORD_HEADER_SIZE) {
+            *byte ^= 0xFF;
+        }
+        std::fs::write(&first_segment, data).unwrap();
+
+        assert!(BlockStore::open_with_segment_size(&dir, 256).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn prune_to_snapshot() {
+        let dir = store_dir();
+        let blocks = chain(6);
+
+        let mut store = BlockStore::open_with_segment_size(&dir, 256).unwrap();
+        for block in &blocks {
+            store.append(block).unwrap();
+        }
+        assert!(store.prune_to_snapshot(5).is_err());
+        assert!(store.prune_to_snapshot(6).is_err());
+
+        let snapshot = Snapshot::from_store(&store, 0, 5).unwrap();
+        let tree = state_tree(&blocks).unwrap();
+        assert_eq!(snapshot.state_root(), &tree.root());
+
+        let previous = blocks.last().unwrap();
+        let snapshot_block = BlockBuilder::new()
+            .chain_id(previous.header().chain_id())
+            .ledger_type(previous.header().ledger_type())
+            .purpose_id(previous.header().purpose_id())
+            .height(6)
+            .block_time_stamp(1_728_474_521)
+            .previous_block_hash(
+                Blake2b,
+                Blake2b.hash(&previous.to_bytes().unwrap()).unwrap(),
+            )
+            .block_data(snapshot.to_block_data().unwrap())
+            .build()
+            .unwrap();
+        store.append(&snapshot_block).unwrap();
+
+        let pruned = store.prune_to_snapshot(6).unwrap();
+        assert!(pruned > 0);
+        let first_height = i64::try_from(pruned).unwrap();
+        assert_eq!(store.first_height(), Some(first_height));
+        assert_eq!(store.tip_height(), Some(6));
+        assert!(store.get(0).unwrap().is_none());
+        let genesis_hash = Blake2b
+            .hash(&blocks.first().unwrap().to_bytes().unwrap())
+            .unwrap();
+        assert!(store.get_by_hash(&genesis_hash).unwrap().is_none());
+        assert_eq!(store.get(6).unwrap(), Some(snapshot_block));
+        drop(store);
+
+        let store = BlockStore::open_with_segment_size(&dir, 256).unwrap();
+        assert_eq!(store.first_height(), Some(first_height));
+        assert_eq!(store.len(), 7 - pruned);
+
+        // Pruned blocks are still verifiable against the snapshot.
+        for block in blocks.iter().take(pruned) {
+            let height = u64::try_from(block.header().height()).unwrap();
+            assert!(snapshot.verify_block(block, &tree.proof(height).unwrap()));
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,741 @@
+//! Chain validation
+
+use std::collections::HashMap;
+
+use ed25519_dalek::{Signature, VerifyingKey};
+
+use crate::{
+    serialize::{Block, GenesisPreviousHash, Kid},
+    snapshot::{state_tree, Snapshot},
+    validator_set::ValidatorSet,
+};
+
+/// Kind of the chain validation violation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProblemKind {
+    /// Block cannot be encoded.
+    Encoding(String),
+    /// Chain does not begin with a genesis block.
+    MissingGenesis,
+    /// Chain validated from a snapshot does not begin with a snapshot block.
+    MissingSnapshot,
+    /// Snapshot payload of the block is malformed, or its height range does not end right
+    /// before the block.
+    InvalidSnapshot(String),
+    /// Snapshot state root does not match the committed blocks of the chain.
+    SnapshotStateRoot,
+    /// Genesis block previous hash is not a hash of the chain parameters.
+    GenesisPreviousHash,
+    /// Previous block hash is not a hash of the previous block bytes.
+    PreviousHash,
+    /// Height is not incremented by 1 from the previous block height.
+    Height {
+        /// Expected block height.
+        expected: i64,
+        /// Actual block height.
+        actual: i64,
+    },
+    /// Timestamp is not greater than the timestamp of the previous block.
+    Timestamp {
+        /// Previous block timestamp.
+        previous: i64,
+        /// Actual block timestamp.
+        actual: i64,
+    },
+    /// `chain_id` differs from the previous block.
+    ChainId,
+    /// `ledger_type` differs from the previous block.
+    LedgerType,
+    /// `purpose_id` differs from the previous block.
+    PurposeId,
+    /// Validators or threshold differ from the active validator set, i.e. the previous
+    /// block validator set or the one updated by the previous block.
+    Validator,
+    /// Validator set of the block is malformed, e.g. the threshold is out of range.
+    InvalidValidatorSet(String),
+    /// Validator set update payload of the block is malformed.
+    InvalidValidatorSetUpdate(String),
+    /// Number of signatures does not match the number of validators.
+    SignatureCount {
+        /// Number of validators.
+        expected: usize,
+        /// Number of signatures.
+        actual: usize,
+    },
+    /// Verifying key of the validator is unknown.
+    UnknownValidator(Kid),
+    /// Signature of the validator is invalid.
+    InvalidSignature(Kid),
+    /// Less signatures than required by the threshold are present.
+    NotEnoughSignatures {
+        /// Number of required signatures.
+        required: usize,
+        /// Number of present signatures.
+        actual: usize,
+    },
+}
+
+/// Chain validation violation of the specific block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    /// Index of the block in the validated chain.
+    pub index: usize,
+    /// Height of the block.
+    pub height: i64,
+    /// Kind of the violation.
+    pub kind: ProblemKind,
+}
+
+/// Report of all violations found during chain validation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProblemReport(Vec<Problem>);
+
+impl ProblemReport {
+    /// Create new empty report
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if any violation was reported.
+    #[must_use]
+    pub fn is_problematic(&self) -> bool {
+        !self.0.is_empty()
+    }
+
+    /// Reported violations
+    #[must_use]
+    pub fn problems(&self) -> &[Problem] {
+        &self.0
+    }
+
+    /// Add violation of the block to the report.
+    fn add(&mut self, index: usize, block: &Block, kind: ProblemKind) {
+        self.0.push(Problem {
+            index,
+            height: block.header().height(),
+            kind,
+        });
+    }
+}
+
+/// Validates a sequence of blocks as a chain.
+///
+/// Each validator signature MUST be a signature of the hashed block header bytes and the
+/// plain block data bytes, signatures are ordered the same way as the block validators.
+/// With the M-of-N threshold in the block header, the signatures of the absent signers
+/// are empty byte strings, and at least M signatures MUST be present.
+///
+/// The active validator set is tracked across the chain, starting from the genesis
+/// block, and is replaced by the validator set update payload of a block for all
+/// following blocks.
+#[derive(Debug, Clone, Default)]
+pub struct ChainValidator {
+    /// Verifying keys of the validators.
+    keys: HashMap<[u8; 16], VerifyingKey>,
+}
+
+impl ChainValidator {
+    /// Create new chain validator without any validator keys.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add verifying key of the validator.
+    #[must_use]
+    pub fn with_validator(mut self, kid: Kid, key: VerifyingKey) -> Self {
+        self.keys.insert(kid.0, key);
+        self
+    }
+
+    /// Validate blocks as a chain, collecting all violations.
+    /// Blocks MUST be ordered by height, the chain MUST begin with a well-formed genesis
+    /// block.
+    #[must_use]
+    pub fn validate(&self, blocks: &[Block]) -> ProblemReport {
+        self.validate_chain(blocks, Self::validate_genesis)
+    }
+
+    /// Validate blocks as a chain starting from a snapshot, collecting all violations.
+    /// Blocks MUST be ordered by height, the chain MUST begin with a snapshot block,
+    /// which is trusted by the signatures of its validators instead of the link to the
+    /// pruned previous blocks.
+    #[must_use]
+    pub fn validate_from_snapshot(&self, blocks: &[Block]) -> ProblemReport {
+        self.validate_chain(blocks, Self::validate_snapshot)
+    }
+
+    /// Validate blocks as a chain, the first block is validated by `validate_first`.
+    fn validate_chain(
+        &self, blocks: &[Block], validate_first: fn(usize, &Block, &mut ProblemReport),
+    ) -> ProblemReport {
+        let mut report = ProblemReport::new();
+        let mut previous: Option<&Block> = None;
+        let mut active_validator_set: Option<ValidatorSet> = None;
+        for (index, block) in blocks.iter().enumerate() {
+            if let Some(previous) = previous {
+                Self::validate_link(index, previous, block, &mut report);
+            } else {
+                validate_first(index, block, &mut report);
+            }
+            Self::validate_state_root(index, blocks, block, &mut report);
+
+            let validator_set = ValidatorSet::from(block.header());
+            if active_validator_set
+                .as_ref()
+                .is_some_and(|active| *active != validator_set)
+            {
+                report.add(index, block, ProblemKind::Validator);
+            }
+            self.validate_signatures(index, block, &validator_set, &mut report);
+
+            active_validator_set = match ValidatorSet::from_block_data(block.data()) {
+                Ok(Some(update)) => Some(update),
+                Ok(None) => Some(validator_set),
+                Err(e) => {
+                    report.add(
+                        index,
+                        block,
+                        ProblemKind::InvalidValidatorSetUpdate(e.to_string()),
+                    );
+                    Some(validator_set)
+                },
+            };
+            previous = Some(block);
+        }
+        report
+    }
+
+    /// Validate the first block of the chain as a genesis block.
+    fn validate_genesis(index: usize, block: &Block, report: &mut ProblemReport) {
+        if !block.is_genesis() {
+            report.add(index, block, ProblemKind::MissingGenesis);
+            return;
+        }
+
+        let header = block.header();
+        match GenesisPreviousHash::from(header).hash(header.hash_function()) {
+            Ok(hash) if hash == header.previous_block_hash() => {},
+            Ok(_) => report.add(index, block, ProblemKind::GenesisPreviousHash),
+            Err(e) => report.add(index, block, ProblemKind::Encoding(e.to_string())),
+        }
+    }
+
+    /// Validate the first block of the chain as a snapshot block.
+    fn validate_snapshot(index: usize, block: &Block, report: &mut ProblemReport) {
+        // A malformed snapshot is reported by the state root validation.
+        if matches!(Snapshot::from_block_data(block.data()), Ok(None)) {
+            report.add(index, block, ProblemKind::MissingSnapshot);
+        }
+    }
+
+    /// Validate the snapshot payload of the block, and its state root against the
+    /// committed blocks if they are all part of the chain.
+    fn validate_state_root(
+        index: usize, blocks: &[Block], block: &Block, report: &mut ProblemReport,
+    ) {
+        let snapshot = match Snapshot::from_block_data(block.data()) {
+            Ok(Some(snapshot)) => snapshot,
+            Ok(None) => return,
+            Err(e) => {
+                report.add(index, block, ProblemKind::InvalidSnapshot(e.to_string()));
+                return;
+            },
+        };
+        if Some(snapshot.to_height()) != block.header().height().checked_sub(1) {
+            report.add(
+                index,
+                block,
+                ProblemKind::InvalidSnapshot(format!(
+                    "Snapshot height range {}..={} does not end before the block",
+                    snapshot.from_height(),
+                    snapshot.to_height()
+                )),
+            );
+            return;
+        }
+
+        let committed: Vec<_> = blocks
+            .iter()
+            .take(index)
+            .filter(|b| {
+                (snapshot.from_height()..=snapshot.to_height()).contains(&b.header().height())
+            })
+            .collect();
+        let expected = snapshot
+            .to_height()
+            .saturating_sub(snapshot.from_height())
+            .saturating_add(1);
+        if i64::try_from(committed.len()).ok() != Some(expected) {
+            // Committed blocks are pruned.
+            return;
+        }
+        match state_tree(committed) {
+            Ok(tree) if &tree.root() == snapshot.state_root() => {},
+            Ok(_) => report.add(index, block, ProblemKind::SnapshotStateRoot),
+            Err(e) => report.add(index, block, ProblemKind::Encoding(e.to_string())),
+        }
+    }
+
+    /// Validate block against the previous block.
+    fn validate_link(index: usize, previous: &Block, block: &Block, report: &mut ProblemReport) {
+        let header = block.header();
+        let previous_header = previous.header();
+
+        match previous.to_bytes() {
+            Ok(previous_bytes) => {
+                match header.hash_function().hash(&previous_bytes) {
+                    Ok(hash) if hash == header.previous_block_hash() => {},
+                    Ok(_) => report.add(index, block, ProblemKind::PreviousHash),
+                    Err(e) => report.add(index, block, ProblemKind::Encoding(e.to_string())),
+                }
+            },
+            Err(e) => report.add(index, block, ProblemKind::Encoding(e.to_string())),
+        }
+
+        let expected = previous_header.height().saturating_add(1);
+        if header.height() != expected {
+            report.add(index, block, ProblemKind::Height {
+                expected,
+                actual: header.height(),
+            });
+        }
+
+        if header.block_time_stamp() <= previous_header.block_time_stamp() {
+            report.add(index, block, ProblemKind::Timestamp {
+                previous: previous_header.block_time_stamp(),
+                actual: header.block_time_stamp(),
+            });
+        }
+
+        if header.chain_id() != previous_header.chain_id() {
+            report.add(index, block, ProblemKind::ChainId);
+        }
+
+        if header.ledger_type() != previous_header.ledger_type() {
+            report.add(index, block, ProblemKind::LedgerType);
+        }
+
+        if header.purpose_id() != previous_header.purpose_id() {
+            report.add(index, block, ProblemKind::PurposeId);
+        }
+    }
+
+    /// Validate block signatures against the block validator set.
+    fn validate_signatures(
+        &self, index: usize, block: &Block, validator_set: &ValidatorSet,
+        report: &mut ProblemReport,
+    ) {
+        if let Err(e) = validator_set.check() {
+            report.add(
+                index,
+                block,
+                ProblemKind::InvalidValidatorSet(e.to_string()),
+            );
+            return;
+        }
+
+        let validators = validator_set.validators();
+        let signatures = block.signatures().as_slice();
+        if validators.len() != signatures.len() {
+            report.add(index, block, ProblemKind::SignatureCount {
+                expected: validators.len(),
+                actual: signatures.len(),
+            });
+            return;
+        }
+
+        let signed_data = match signed_data(block) {
+            Ok(signed_data) => signed_data,
+            Err(e) => {
+                report.add(index, block, ProblemKind::Encoding(e.to_string()));
+                return;
+            },
+        };
+
+        let present = signatures.iter().filter(|s| !s.is_empty()).count();
+        if present < validator_set.required_signatures() {
+            report.add(index, block, ProblemKind::NotEnoughSignatures {
+                required: validator_set.required_signatures(),
+                actual: present,
+            });
+        }
+
+        for (kid, signature) in validators.iter().zip(signatures) {
+            // Absent signer
+            if signature.is_empty() {
+                continue;
+            }
+            let Some(key) = self.keys.get(&kid.0) else {
+                report.add(index, block, ProblemKind::UnknownValidator(*kid));
+                continue;
+            };
+            let valid = Signature::from_slice(signature)
+                .is_ok_and(|signature| key.verify_strict(&signed_data, &signature).is_ok());
+            if !valid {
+                report.add(index, block, ProblemKind::InvalidSignature(*kid));
+            }
+        }
+    }
+}
+
+/// Data signed by the block validators, hashed block header bytes and plain block data
+/// bytes.
+fn signed_data(block: &Block) -> anyhow::Result<Vec<u8>> {
+    let header = block.header();
+    let hashed_header = header.hash_function().hash(&header.to_bytes()?)?;
+    Ok([hashed_header.as_slice(), block.data().payload()?].concat())
+}
+
+#[cfg(test)]
+#[allow(clippy::zero_prefixed_literal)]
+mod tests {
+    use ed25519_dalek::{Signer, SigningKey, SECRET_KEY_LENGTH};
+    use uuid::Uuid;
+
+    use super::{signed_data, ChainValidator, ProblemKind};
+    use crate::{
+        serialize::{
+            blake2b_512, Block, BlockBuilder, BlockData, HashFunction::Blake2b, Kid, Signatures,
+        },
+        snapshot::{state_tree, Snapshot},
+        validator_set::ValidatorSet,
+    };
+
+    /// Validator secret key
+    const SECRET_KEY: [u8; SECRET_KEY_LENGTH] = [
+        157, 097, 177, 157, 239, 253, 090, 096, 186, 132, 074, 244, 146, 236, 044, 196, 068, 073,
+        197, 105, 123, 050, 105, 025, 112, 059, 172, 003, 028, 174, 127, 096,
+    ];
+
+    /// Validator key id
+    const KID: Kid = Kid([1; 16]);
+
+    /// Second validator secret key
+    const SECRET_KEY_2: [u8; SECRET_KEY_LENGTH] = [2; SECRET_KEY_LENGTH];
+
+    /// Second validator key id
+    const KID_2: Kid = Kid([2; 16]);
+
+    /// Sign the block by the validators keys, `None` for the absent signers.
+    fn signed_by(unsigned: &Block, keys: &[Option<[u8; SECRET_KEY_LENGTH]>]) -> Block {
+        let signed_data = signed_data(unsigned).unwrap();
+        let signatures = keys
+            .iter()
+            .map(|key| {
+                key.map(|key| {
+                    SigningKey::from_bytes(&key)
+                        .sign(&signed_data)
+                        .to_bytes()
+                        .to_vec()
+                })
+                .unwrap_or_default()
+            })
+            .collect();
+        Block::new(
+            unsigned.header().clone(),
+            unsigned.data().clone(),
+            Signatures::new(signatures),
+        )
+    }
+
+    /// Build a block signed by the validator.
+    fn signed_block(builder: BlockBuilder) -> Block {
+        let unsigned = builder
+            .validator(vec![KID])
+            .block_data(BlockData::from_payload(&[1, 2, 3]).unwrap())
+            .build()
+            .unwrap();
+        let signature = SigningKey::from_bytes(&SECRET_KEY)
+            .sign(&signed_data(&unsigned).unwrap())
+            .to_bytes();
+        Block::new(
+            unsigned.header().clone(),
+            unsigned.data().clone(),
+            Signatures::new(vec![signature.to_vec()]),
+        )
+    }
+
+    /// Build a valid chain of the given length.
+    fn chain(length: i64) -> (BlockBuilder, Vec<Block>) {
+        let builder = BlockBuilder::new()
+            .chain_id(Uuid::now_v7())
+            .ledger_type(Uuid::new_v4())
+            .purpose_id(Uuid::now_v7());
+
+        let mut blocks = vec![signed_block(
+            builder
+                .clone()
+                .validator(vec![KID])
+                .block_time_stamp(1_728_474_515)
+                .genesis(Blake2b)
+                .unwrap(),
+        )];
+        for height in 1..length {
+            let previous_hash = blocks
+                .last()
+                .map(|previous| blake2b_512(&previous.to_bytes().unwrap()).unwrap().to_vec())
+                .unwrap();
+            blocks.push(signed_block(
+                builder
+                    .clone()
+                    .height(height)
+                    .block_time_stamp(1_728_474_515 + height)
+                    .previous_block_hash(Blake2b, previous_hash),
+            ));
+        }
+        (builder, blocks)
+    }
+
+    /// Chain validator knowing the test validator key.
+    fn validator() -> ChainValidator {
+        ChainValidator::new()
+            .with_validator(KID, SigningKey::from_bytes(&SECRET_KEY).verifying_key())
+    }
+
+    #[test]
+    fn valid_chain() {
+        let (_, blocks) = chain(5);
+        assert!(!validator().validate(&blocks).is_problematic());
+    }
+
+    #[test]
+    fn chain_without_genesis() {
+        let (_, blocks) = chain(3);
+
+        let report = validator().validate(blocks.get(1..).unwrap());
+        assert_eq!(report.problems().len(), 1);
+        assert!(report
+            .problems()
+            .iter()
+            .all(|p| p.index == 0 && p.kind == ProblemKind::MissingGenesis));
+
+        // Genesis previous hash is not derived from the chain parameters.
+        let (builder, _) = chain(1);
+        let genesis = signed_block(
+            builder
+                .height(0)
+                .block_time_stamp(1_728_474_515)
+                .previous_block_hash(Blake2b, vec![0; 64]),
+        );
+        let report = validator().validate(&[genesis]);
+        assert!(report
+            .problems()
+            .iter()
+            .all(|p| p.kind == ProblemKind::GenesisPreviousHash));
+        assert!(report.is_problematic());
+    }
+
+    #[test]
+    fn unknown_validator() {
+        let (_, blocks) = chain(2);
+        let report = ChainValidator::new().validate(&blocks);
+        assert_eq!(report.problems().len(), 2);
+        assert!(report
+            .problems()
+            .iter()
+            .all(|p| p.kind == ProblemKind::UnknownValidator(KID)));
+    }
+
+    #[test]
+    fn invalid_chain_reports_all_problems() {
+        let (builder, mut blocks) = chain(2);
+
+        // Wrong height, timestamp, purpose and previous hash, all reported at once.
+        blocks.push(signed_block(
+            builder
+                .purpose_id(Uuid::now_v7())
+                .height(5)
+                .block_time_stamp(0)
+                .previous_block_hash(Blake2b, vec![0; 64]),
+        ));
+
+        // Invalid signature.
+        let last = blocks.last().unwrap().clone();
+        blocks.push(Block::new(
+            last.header().clone(),
+            last.data().clone(),
+            Signatures::new(vec![vec![0; 64]]),
+        ));
+
+        let report = validator().validate(&blocks);
+        let kinds: Vec<_> = report
+            .problems()
+            .iter()
+            .filter(|p| p.index == 2)
+            .map(|p| p.kind.clone())
+            .collect();
+        assert_eq!(kinds, vec![
+            ProblemKind::PreviousHash,
+            ProblemKind::Height {
+                expected: 2,
+                actual: 5
+            },
+            ProblemKind::Timestamp {
+                previous: 1_728_474_516,
+                actual: 0
+            },
+            ProblemKind::PurposeId,
+        ]);
+        assert!(report
+            .problems()
+            .iter()
+            .any(|p| p.index == 3 && p.kind == ProblemKind::InvalidSignature(KID)));
+    }
+
+    #[test]
+    fn validator_set_rotation() {
+        let (builder, mut blocks) = chain(2);
+        let chain_validator = validator()
+            .with_validator(KID_2, SigningKey::from_bytes(&SECRET_KEY_2).verifying_key());
+
+        // 1-of-2 validator set update, signed by the current validator.
+        let update = ValidatorSet::new(vec![KID, KID_2], Some(1)).unwrap();
+        let next = |blocks: &[Block], builder: BlockBuilder| {
+            let previous = blocks.last().unwrap();
+            let height = previous.header().height() + 1;
+            builder
+                .height(height)
+                .block_time_stamp(1_728_474_515 + height)
+                .previous_block_hash(
+                    Blake2b,
+                    blake2b_512(&previous.to_bytes().unwrap()).unwrap().to_vec(),
+                )
+        };
+        let unsigned = next(&blocks, builder.clone())
+            .validator(vec![KID])
+            .block_data(update.to_block_data().unwrap())
+            .build()
+            .unwrap();
+        blocks.push(signed_by(&unsigned, &[Some(SECRET_KEY)]));
+
+        // Signed by the second validator only.
+        let unsigned = next(&blocks, builder.clone())
+            .validator_set(update.clone())
+            .block_data(BlockData::from_payload(&[1, 2, 3]).unwrap())
+            .build()
+            .unwrap();
+        let rotated = signed_by(&unsigned, &[None, Some(SECRET_KEY_2)]);
+        assert_eq!(
+            Block::from_bytes(&rotated.to_bytes().unwrap()).unwrap(),
+            rotated
+        );
+        assert!(rotated.validate(blocks.last().cloned()).is_ok());
+
+        let mut valid = blocks.clone();
+        valid.push(rotated);
+        assert!(!chain_validator.validate(&valid).is_problematic());
+
+        // No signatures present.
+        let mut invalid = blocks.clone();
+        invalid.push(signed_by(&unsigned, &[None, None]));
+        let report = chain_validator.validate(&invalid);
+        assert_eq!(
+            report
+                .problems()
+                .iter()
+                .map(|p| &p.kind)
+                .collect::<Vec<_>>(),
+            vec![&ProblemKind::NotEnoughSignatures {
+                required: 1,
+                actual: 0
+            }]
+        );
+
+        // The previous validator set after the update.
+        let mut invalid = blocks.clone();
+        invalid.push(signed_block(next(&blocks, builder)));
+        let report = chain_validator.validate(&invalid);
+        assert!(report
+            .problems()
+            .iter()
+            .all(|p| p.index == 3 && p.kind == ProblemKind::Validator));
+        assert!(report.is_problematic());
+        assert!(invalid
+            .get(3)
+            .unwrap()
+            .validate(blocks.last().cloned())
+            .is_err());
+    }
+
+    #[test]
+    fn chain_from_snapshot() {
+        let (builder, mut blocks) = chain(4);
+
+        let snapshot_block = |blocks: &[Block], snapshot: &Snapshot| {
+            let previous = blocks.last().unwrap();
+            let height = previous.header().height() + 1;
+            let unsigned = builder
+                .clone()
+                .height(height)
+                .block_time_stamp(1_728_474_515 + height)
+                .previous_block_hash(
+                    Blake2b,
+                    blake2b_512(&previous.to_bytes().unwrap()).unwrap().to_vec(),
+                )
+                .validator(vec![KID])
+                .block_data(snapshot.to_block_data().unwrap())
+                .build()
+                .unwrap();
+            signed_by(&unsigned, &[Some(SECRET_KEY)])
+        };
+
+        let snapshot = Snapshot::new(0, 3, state_tree(&blocks).unwrap().root()).unwrap();
+        let mut invalid = blocks.clone();
+        blocks.push(snapshot_block(&blocks, &snapshot));
+        let previous_hash = blake2b_512(&blocks.last().unwrap().to_bytes().unwrap()).unwrap();
+        blocks.push(signed_block(
+            builder
+                .clone()
+                .height(5)
+                .block_time_stamp(1_728_474_520)
+                .previous_block_hash(Blake2b, previous_hash.to_vec()),
+        ));
+
+        // The whole chain, with the snapshot state root validated.
+        assert!(!validator().validate(&blocks).is_problematic());
+
+        // Chain with the committed blocks pruned.
+        let pruned = blocks.get(4..).unwrap();
+        assert!(!validator().validate_from_snapshot(pruned).is_problematic());
+        assert!(validator().validate(pruned).is_problematic());
+
+        let report = validator().validate_from_snapshot(blocks.get(5..).unwrap());
+        assert_eq!(
+            report
+                .problems()
+                .iter()
+                .map(|p| &p.kind)
+                .collect::<Vec<_>>(),
+            vec![&ProblemKind::MissingSnapshot]
+        );
+
+        // State root does not match the committed blocks.
+        let wrong_root = Snapshot::new(0, 3, [0; 32]).unwrap();
+        invalid.push(snapshot_block(&invalid, &wrong_root));
+        let report = validator().validate(&invalid);
+        assert_eq!(
+            report
+                .problems()
+                .iter()
+                .map(|p| (p.index, &p.kind))
+                .collect::<Vec<_>>(),
+            vec![(4, &ProblemKind::SnapshotStateRoot)]
+        );
+        assert!(!validator()
+            .validate_from_snapshot(invalid.get(4..).unwrap())
+            .is_problematic());
+
+        // Snapshot height range does not end before the block.
+        let mut invalid = invalid.get(..4).unwrap().to_vec();
+        let wrong_range = Snapshot::new(0, 2, [0; 32]).unwrap();
+        invalid.push(snapshot_block(&invalid, &wrong_range));
+        let report = validator().validate_from_snapshot(invalid.get(4..).unwrap());
+        assert!(report
+            .problems()
+            .iter()
+            .all(|p| matches!(p.kind, ProblemKind::InvalidSnapshot(_))));
+        assert!(report.is_problematic());
+    }
+}
@@ -0,0 +1,206 @@
+//! Validator set and validator set rotation
+//!
+//! The validator set of the chain is defined by the genesis block header and could be
+//! changed by a block with the validator set update payload, which MUST be signed by the
+//! current validator set. All blocks after the update MUST carry the new validator set.
+//!
+//! ```cddl
+//! validator-set-update = #6.32800([
+//!     validators: [+ kid],
+//!     ? threshold: uint,
+//! ])
+//! kid = bytes .size 16
+//! ```
+
+use std::collections::HashSet;
+
+use crate::serialize::{BlockData, BlockHeader, Kid, KID_BYTES};
+
+/// CBOR tag of the validator set update block payload.
+const VALIDATOR_SET_UPDATE_CBOR_TAG: u64 = 32800;
+
+/// Validators of the block and the minimum number of their signatures.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatorSet {
+    /// Identifiers of the validators.
+    validators: Vec<Kid>,
+    /// Minimum number of the validators signatures (M-of-N), `None` if all validators
+    /// MUST sign the block.
+    threshold: Option<u64>,
+}
+
+impl From<&BlockHeader> for ValidatorSet {
+    fn from(block_header: &BlockHeader) -> Self {
+        Self {
+            validators: block_header.validator().to_vec(),
+            threshold: block_header.threshold(),
+        }
+    }
+}
+
+impl ValidatorSet {
+    /// Create new validator set.
+    /// ## Errors
+    ///
+    /// Returns an error if validators are empty or duplicated, or the threshold is not
+    /// within the range `1..=validators.len()`.
+    pub fn new(validators: Vec<Kid>, threshold: Option<u64>) -> anyhow::Result<Self> {
+        let validator_set = Self {
+            validators,
+            threshold,
+        };
+        validator_set.check()?;
+        Ok(validator_set)
+    }
+
+    /// Identifiers of the validators.
+    #[must_use]
+    pub fn validators(&self) -> &[Kid] {
+        &self.validators
+    }
+
+    /// Minimum number of the validators signatures, `None` if all validators MUST sign
+    /// the block.
+    #[must_use]
+    pub fn threshold(&self) -> Option<u64> {
+        self.threshold
+    }
+
+    /// Number of the validators signatures required to accept the block.
+    #[must_use]
+    pub fn required_signatures(&self) -> usize {
+        self.threshold.map_or(self.validators.len(), |threshold| {
+            usize::try_from(threshold).unwrap_or(usize::MAX)
+        })
+    }
+
+    /// Check the validator set is well-formed.
+    /// ## Errors
+    ///
+    /// Returns an error if validators are empty or duplicated, or the threshold is not
+    /// within the range `1..=validators.len()`.
+    pub fn check(&self) -> anyhow::Result<()> {
+        if self.validators.is_empty() {
+            anyhow::bail!("Validator set MUST have at least one validator");
+        }
+        let unique: HashSet<_> = self.validators.iter().map(|kid| kid.0).collect();
+        if unique.len() != self.validators.len() {
+            anyhow::bail!("Validator set MUST NOT have duplicated validators");
+        }
+        let required = self.required_signatures();
+        if required == 0 || required > self.validators.len() {
+            anyhow::bail!(
+                "Invalid validator set threshold {required}, MUST be within 1..={}",
+                self.validators.len()
+            );
+        }
+        Ok(())
+    }
+
+    /// Encode as the validator set update block data.
+    /// ## Errors
+    ///
+    /// Returns an error if encoding fails.
+    pub fn to_block_data(&self) -> anyhow::Result<BlockData> {
+        let mut encoder = minicbor::Encoder::new(Vec::new());
+        encoder.tag(minicbor::data::Tag::new(VALIDATOR_SET_UPDATE_CBOR_TAG))?;
+        encoder.array(if self.threshold.is_some() { 2 } else { 1 })?;
+        encoder.array(self.validators.len().try_into()?)?;
+        for kid in &self.validators {
+            encoder.bytes(&kid.0)?;
+        }
+        if let Some(threshold) = self.threshold {
+            encoder.u64(threshold)?;
+        }
+        BlockData::from_payload(encoder.writer())
+    }
+
+    /// Decode the validator set update from the block data, `None` if the block data is
+    /// not a validator set update.
+    /// ## Errors
+    ///
+    /// Returns an error if the block data is a malformed or invalid validator set update.
+    pub fn from_block_data(block_data: &BlockData) -> anyhow::Result<Option<Self>> {
+        let mut cbor_decoder = minicbor::Decoder::new(block_data.payload()?);
+        let is_update = cbor_decoder
+            .probe()
+            .tag()
+            .is_ok_and(|tag| tag.as_u64() == VALIDATOR_SET_UPDATE_CBOR_TAG);
+        if !is_update {
+            return Ok(None);
+        }
+
+        cbor_decoder.tag()?;
+        let size = cbor_decoder.array()?;
+
+        let number_of_validators = cbor_decoder.array()?.ok_or(anyhow::anyhow!(
+            "Invalid cbor for validator set update validators"
+        ))?;
+        let mut validators = Vec::new();
+        for _validator in 0..number_of_validators {
+            let kid: [u8; KID_BYTES] = cbor_decoder
+                .bytes()
+                .map_err(|e| anyhow::anyhow!(format!("Invalid cbor for validators : {e}")))?
+                .try_into()?;
+            validators.push(Kid(kid));
+        }
+
+        let threshold = if size == Some(2) {
+            Some(
+                cbor_decoder
+                    .u64()
+                    .map_err(|e| anyhow::anyhow!(format!("Invalid cbor for threshold : {e}")))?,
+            )
+        } else {
+            None
+        };
+
+        Self::new(validators, threshold).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValidatorSet;
+    use crate::serialize::{BlockData, Kid};
+
+    #[test]
+    fn validator_set_update_encoding() {
+        let validators = vec![Kid([1; 16]), Kid([2; 16]), Kid([3; 16])];
+        for threshold in [None, Some(2)] {
+            let update = ValidatorSet::new(validators.clone(), threshold).unwrap();
+            let block_data = update.to_block_data().unwrap();
+            assert_eq!(
+                ValidatorSet::from_block_data(&block_data).unwrap(),
+                Some(update)
+            );
+        }
+
+        // Not a validator set update.
+        let block_data = BlockData::from_payload(&[1, 2, 3]).unwrap();
+        assert_eq!(ValidatorSet::from_block_data(&block_data).unwrap(), None);
+        let block_data = BlockData::from_payload(&[]).unwrap();
+        assert_eq!(ValidatorSet::from_block_data(&block_data).unwrap(), None);
+    }
+
+    #[test]
+    fn invalid_validator_set() {
+        let validators = vec![Kid([1; 16]), Kid([2; 16])];
+        assert!(ValidatorSet::new(vec![], None).is_err());
+        assert!(ValidatorSet::new(vec![Kid([1; 16]), Kid([1; 16])], None).is_err());
+        assert!(ValidatorSet::new(validators.clone(), Some(0)).is_err());
+        assert!(ValidatorSet::new(validators.clone(), Some(3)).is_err());
+        assert_eq!(
+            ValidatorSet::new(validators.clone(), Some(1))
+                .unwrap()
+                .required_signatures(),
+            1
+        );
+        assert_eq!(
+            ValidatorSet::new(validators, None)
+                .unwrap()
+                .required_signatures(),
+            2
+        );
+    }
+}
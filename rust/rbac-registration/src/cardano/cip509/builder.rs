@@ -0,0 +1,236 @@
+//! Builder of the CIP-509 metadata, for creating new registrations.
+//!
+//! The RBAC metadata is encoded, compressed and split into the 64 bytes chunks, and the
+//! validation signature is filled with a zeroed placeholder. The placeholder is always
+//! the last 64 bytes of the metadata, so the auxiliary data can be hashed and signed
+//! with the role 0 key, and the placeholder replaced with the real signature afterwards.
+
+use minicbor::{Encode, Encoder};
+use pallas::{crypto::hash::Hash, ledger::primitives::conway::TransactionInput};
+use uuid::Uuid;
+
+use super::{
+    rbac::{
+        certs::{C509Cert, X509DerCert},
+        pub_key::SimplePublicKeyType,
+        role_data::RoleData,
+        Cip509RbacMetadata,
+    },
+    types::{cert_key_hash::CertKeyHash, tx_input_hash::TxInputHash},
+    x509_chunks::{CompressionAlgorithm, X509Chunks},
+    Cip509, LABEL,
+};
+
+/// Size of the validation signature placeholder.
+const VALIDATION_SIGNATURE_SIZE: usize = 64;
+
+/// Builder of the CIP-509 metadata.
+#[derive(Debug, Clone)]
+#[allow(clippy::module_name_repetitions)]
+pub struct Cip509Builder {
+    /// Purpose of the registration.
+    purpose: Uuid,
+    /// Hash of the inputs of the transaction the metadata is attached to.
+    txn_inputs_hash: TxInputHash,
+    /// Optional previous transaction ID in the registration chain.
+    prv_tx_id: Option<Hash<32>>,
+    /// RBAC metadata to be chunked.
+    metadata: Cip509RbacMetadata,
+    /// Compression algorithm of the chunks, `None` to select the smallest encoding.
+    compression: Option<CompressionAlgorithm>,
+}
+
+impl Cip509Builder {
+    /// Create a new `Cip509Builder` for a registration attached to a transaction with
+    /// the given inputs.
+    ///
+    /// # Errors
+    ///
+    /// If the transaction inputs cannot be hashed.
+    pub fn new(purpose: Uuid, txn_inputs: &[TransactionInput]) -> anyhow::Result<Self> {
+        Ok(Self {
+            purpose,
+            txn_inputs_hash: TxInputHash::from_txn_inputs(txn_inputs)?,
+            prv_tx_id: None,
+            metadata: Cip509RbacMetadata::new(),
+            compression: None,
+        })
+    }
+
+    /// Set the previous transaction ID, for an update of an existing registration.
+    #[must_use]
+    pub fn previous_tx_id(mut self, prv_tx_id: Hash<32>) -> Self {
+        self.prv_tx_id = Some(prv_tx_id);
+        self
+    }
+
+    /// Set the compression algorithm of the chunks.
+    /// By default the algorithm which gives the smallest encoding is selected.
+    #[must_use]
+    pub fn compression(mut self, compression: CompressionAlgorithm) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Add a x509 certificate.
+    #[must_use]
+    pub fn x509_cert(mut self, cert: X509DerCert) -> Self {
+        self.metadata
+            .x509_certs
+            .get_or_insert_with(Vec::new)
+            .push(cert);
+        self
+    }
+
+    /// Add a c509 certificate.
+    #[must_use]
+    pub fn c509_cert(mut self, cert: C509Cert) -> Self {
+        self.metadata
+            .c509_certs
+            .get_or_insert_with(Vec::new)
+            .push(cert);
+        self
+    }
+
+    /// Add a simple public key.
+    #[must_use]
+    pub fn pub_key(mut self, pub_key: SimplePublicKeyType) -> Self {
+        self.metadata
+            .pub_keys
+            .get_or_insert_with(Vec::new)
+            .push(pub_key);
+        self
+    }
+
+    /// Add a hash of a revoked certificate or public key.
+    #[must_use]
+    pub fn revocation(mut self, cert_key_hash: CertKeyHash) -> Self {
+        self.metadata
+            .revocation_list
+            .get_or_insert_with(Vec::new)
+            .push(cert_key_hash);
+        self
+    }
+
+    /// Add a role data.
+    #[must_use]
+    pub fn role_data(mut self, role_data: RoleData) -> Self {
+        self.metadata
+            .role_set
+            .get_or_insert_with(Vec::new)
+            .push(role_data);
+        self
+    }
+
+    /// Add a purpose key data, the key must be within the range 200 - 299.
+    #[must_use]
+    pub fn purpose_key_data(mut self, key: u16, data: Vec<u8>) -> Self {
+        self.metadata.purpose_key_data.insert(key, data);
+        self
+    }
+
+    /// Build the CIP-509 metadatum, with a zeroed validation signature placeholder.
+    #[must_use]
+    pub fn build(&self) -> Cip509 {
+        Cip509 {
+            purpose: self.purpose,
+            txn_inputs_hash: self.txn_inputs_hash.clone(),
+            prv_tx_id: self.prv_tx_id,
+            x509_chunks: X509Chunks(self.metadata.clone()),
+            validation_signature: vec![0; VALIDATION_SIGNATURE_SIZE],
+        }
+    }
+
+    /// Build the CBOR encoded transaction metadata, the CIP-509 metadatum under the
+    /// CIP-509 label, ready to be attached to the transaction.
+    ///
+    /// # Errors
+    ///
+    /// If the metadata cannot be encoded or compressed.
+    pub fn build_metadata(&self) -> anyhow::Result<Vec<u8>> {
+        let cip509 = self.build();
+        let mut compression = match self.compression {
+            Some(compression) => compression,
+            None => cip509.x509_chunks.smallest_compression()?,
+        };
+
+        let mut buffer = Vec::new();
+        let mut e = Encoder::new(&mut buffer);
+        e.map(1)?.u64(LABEL)?;
+        cip509
+            .encode(&mut e, &mut compression)
+            .map_err(|err| anyhow::anyhow!("Failed to encode CIP509 metadata: {err}"))?;
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::SigningKey;
+    use minicbor::{Decode, Decoder};
+
+    use super::*;
+    use crate::cardano::cip509::rbac::role_data::{KeyLocalRef, LocalRefInt};
+
+    #[test]
+    fn test_cip509_builder() {
+        let txn_inputs = vec![TransactionInput {
+            transaction_id: Hash::new([1; 32]),
+            index: 0,
+        }];
+        let role_data = RoleData {
+            role_number: 0,
+            role_signing_key: Some(KeyLocalRef {
+                local_ref: LocalRefInt::PubKeys,
+                key_offset: 0,
+            }),
+            role_encryption_key: None,
+            payment_key: Some(-1),
+            role_extended_data_keys: [(10, b"Test".to_vec())].into(),
+        };
+        let pub_key = SigningKey::from_bytes(&[2; 32]).verifying_key();
+
+        for compression in [
+            None,
+            Some(CompressionAlgorithm::Raw),
+            Some(CompressionAlgorithm::Brotli),
+            Some(CompressionAlgorithm::Zstd),
+        ] {
+            let mut builder = Cip509Builder::new(Uuid::from_bytes([3; 16]), &txn_inputs)
+                .unwrap()
+                .previous_tx_id(Hash::new([4; 32]))
+                .pub_key(SimplePublicKeyType::Ed25519(pub_key))
+                .pub_key(SimplePublicKeyType::Undefined)
+                .pub_key(SimplePublicKeyType::Deleted)
+                .revocation(CertKeyHash::from([5; 16]))
+                .role_data(role_data.clone())
+                .purpose_key_data(200, vec![6; 100]);
+            if let Some(compression) = compression {
+                builder = builder.compression(compression);
+            }
+            let metadata = builder.build_metadata().unwrap();
+
+            // The validation signature placeholder is the last bytes of the metadata.
+            let (_, signature) = metadata.split_at(metadata.len() - VALIDATION_SIGNATURE_SIZE);
+            assert_eq!(signature, [0; VALIDATION_SIGNATURE_SIZE]);
+
+            let mut d = Decoder::new(&metadata);
+            assert_eq!(d.map().unwrap(), Some(1));
+            assert_eq!(d.u64().unwrap(), LABEL);
+            let cip509 = Cip509::decode(&mut d, &mut ()).unwrap();
+            assert_eq!(cip509, builder.build());
+            assert_eq!(
+                cip509.txn_inputs_hash,
+                TxInputHash::from_txn_inputs(&txn_inputs).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_cip509_builder_invalid_purpose_key() {
+        let builder = Cip509Builder::new(Uuid::from_bytes([3; 16]), &[])
+            .unwrap()
+            .purpose_key_data(100, vec![1]);
+        assert!(builder.build_metadata().is_err());
+    }
+}
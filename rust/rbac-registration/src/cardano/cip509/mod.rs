@@ -4,31 +4,33 @@
 
 // cspell: words pkix
 
+pub mod builder;
 pub mod rbac;
 pub mod types;
 pub mod utils;
 pub(crate) mod validation;
 pub mod x509_chunks;
 
+use c509_certificate::crl::TbsCrl;
 use minicbor::{
     decode::{self},
-    Decode, Decoder,
+    encode, Decode, Decoder, Encode, Encoder,
 };
 use pallas::{crypto::hash::Hash, ledger::traverse::MultiEraTx};
 use strum_macros::FromRepr;
 use types::tx_input_hash::TxInputHash;
 use uuid::Uuid;
 use validation::{
-    validate_aux, validate_payment_key, validate_role_singing_key, validate_stake_public_key,
-    validate_txn_inputs_hash,
+    validate_aux, validate_c509_revocation, validate_payment_key, validate_role_singing_key,
+    validate_stake_public_key, validate_txn_inputs_hash,
 };
-use x509_chunks::X509Chunks;
+use x509_chunks::{CompressionAlgorithm, DecompressionLimits, X509Chunks};
 
 use super::transaction::witness::TxWitness;
 use crate::utils::{
     decode_helper::{decode_bytes, decode_helper, decode_map_len},
     general::{decode_utf8, decremented_index},
-    hashing::{blake2b_128, blake2b_256},
+    hashing::blake2b_256,
 };
 
 /// CIP509 label.
@@ -90,7 +92,13 @@ pub(crate) enum Cip509IntIdentifier {
 }
 
 impl Decode<'_, ()> for Cip509 {
-    fn decode(d: &mut Decoder, ctx: &mut ()) -> Result<Self, decode::Error> {
+    fn decode(d: &mut Decoder, _ctx: &mut ()) -> Result<Self, decode::Error> {
+        Self::decode(d, &mut DecompressionLimits::default())
+    }
+}
+
+impl Decode<'_, DecompressionLimits> for Cip509 {
+    fn decode(d: &mut Decoder, ctx: &mut DecompressionLimits) -> Result<Self, decode::Error> {
         let map_len = decode_map_len(d, "CIP509")?;
         let mut cip509_metadatum = Cip509::default();
         for _ in 0..map_len {
@@ -141,6 +149,28 @@ impl Decode<'_, ()> for Cip509 {
     }
 }
 
+impl Encode<CompressionAlgorithm> for Cip509 {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, algorithm: &mut CompressionAlgorithm,
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.map(4 + u64::from(self.prv_tx_id.is_some()))?;
+        e.u8(Cip509IntIdentifier::Purpose as u8)?
+            .bytes(self.purpose.as_bytes())?;
+        e.u8(Cip509IntIdentifier::TxInputsHash as u8)?
+            .bytes(&<[u8; 16]>::from(self.txn_inputs_hash.clone()))?;
+        if let Some(prv_tx_id) = &self.prv_tx_id {
+            e.u8(Cip509IntIdentifier::PreviousTxId as u8)?
+                .bytes(prv_tx_id.as_ref())?;
+        }
+        self.x509_chunks.encode(e, algorithm)?;
+        // The validation signature is always the last, so it is the last bytes of the
+        // auxiliary data which are zeroed out when the signature is computed.
+        e.u8(Cip509IntIdentifier::ValidationSignature as u8)?
+            .bytes(&self.validation_signature)?;
+        Ok(())
+    }
+}
+
 impl Cip509 {
     /// Basic validation for CIP509
     /// The validation include the following:
@@ -200,4 +230,18 @@ impl Cip509 {
             additional_data: AdditionalData { precomputed_aux },
         }
     }
+
+    /// Validate the C509 certificates of the CIP509 are not revoked by the certificate
+    /// revocation list of their issuer. Revoked certificates are reported in the
+    /// `validation_report`.
+    ///
+    /// # Parameters
+    /// * `crls` - TBS of the certificate revocation lists, their signature verified.
+    /// * `time` - Time of the validation, in seconds since the Unix epoch.
+    /// * `validation_report` - Validation report to store the validation result.
+    pub fn validate_revocation(
+        &self, crls: &[TbsCrl], time: u64, validation_report: &mut Vec<String>,
+    ) -> bool {
+        validate_c509_revocation(self, crls, time, validation_report)
+    }
 }
@@ -1,7 +1,7 @@
 //! Certificates for the RBAC metadata.
 
 use c509_certificate::c509::C509;
-use minicbor::{decode, Decode, Decoder};
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
 use x509_cert::{der::Decode as x509Decode, Certificate};
 
 use super::tag::KeyTag;
@@ -27,7 +27,10 @@ impl Decode<'_, ()> for X509DerCert {
             minicbor::data::Type::Tag => {
                 let tag = decode_tag(d, "X509DerCert")?;
                 match tag {
-                    t if t == KeyTag::Deleted.tag() => Ok(Self::Deleted),
+                    t if t == KeyTag::Deleted.tag() => {
+                        d.undefined()?;
+                        Ok(Self::Deleted)
+                    },
                     _ => Err(decode::Error::message("Unknown tag for X509DerCert")),
                 }
             },
@@ -43,6 +46,19 @@ impl Decode<'_, ()> for X509DerCert {
     }
 }
 
+impl Encode<()> for X509DerCert {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, _ctx: &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        match self {
+            Self::Undefined => e.undefined()?,
+            Self::Deleted => e.tag(KeyTag::Deleted.tag())?.undefined()?,
+            Self::X509Cert(cert) => e.bytes(cert)?,
+        };
+        Ok(())
+    }
+}
+
 // ------------------c509-----------------------
 
 /// Enum of possible X.509 DER certificate.
@@ -65,7 +81,10 @@ impl Decode<'_, ()> for C509Cert {
             minicbor::data::Type::Tag => {
                 let tag = decode_tag(d, "C509Cert")?;
                 match tag {
-                    t if t == KeyTag::Deleted.tag() => Ok(Self::Deleted),
+                    t if t == KeyTag::Deleted.tag() => {
+                        d.undefined()?;
+                        Ok(Self::Deleted)
+                    },
                     _ => Err(decode::Error::message("Unknown tag for C509Cert")),
                 }
             },
@@ -96,6 +115,32 @@ impl Decode<'_, ()> for C509Cert {
     }
 }
 
+impl Encode<()> for C509Cert {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, ctx: &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        match self {
+            Self::Undefined => {
+                e.undefined()?;
+            },
+            Self::Deleted => {
+                e.tag(KeyTag::Deleted.tag())?.undefined()?;
+            },
+            Self::C509CertInMetadatumReference(cert_ref) => {
+                e.array(3)?;
+                cert_ref.encode(e, ctx)?;
+            },
+            Self::C509Certificate(cert) => {
+                let cert = minicbor::to_vec(cert).map_err(|err| {
+                    encode::Error::message(format!("Failed to encode C509 certificate: {err}"))
+                })?;
+                e.bytes(&cert)?;
+            },
+        }
+        Ok(())
+    }
+}
+
 /// C509 certificate in metadatum reference.
 #[derive(Debug, PartialEq, Clone)]
 pub struct C509CertInMetadatumReference {
@@ -135,3 +180,23 @@ impl Decode<'_, ()> for C509CertInMetadatumReference {
         })
     }
 }
+
+impl Encode<()> for C509CertInMetadatumReference {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, _ctx: &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.u8(self.txn_output_field)?.u64(self.txn_output_index)?;
+        match &self.cert_ref {
+            Some(cert_ref) => {
+                e.array(cert_ref.len() as u64)?;
+                for offset in cert_ref {
+                    e.u64(*offset)?;
+                }
+            },
+            None => {
+                e.null()?;
+            },
+        }
+        Ok(())
+    }
+}
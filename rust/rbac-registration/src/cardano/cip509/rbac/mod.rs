@@ -10,7 +10,7 @@ pub(crate) mod tag;
 use std::collections::HashMap;
 
 use certs::{C509Cert, X509DerCert};
-use minicbor::{decode, Decode, Decoder};
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
 use pub_key::SimplePublicKeyType;
 use role_data::RoleData;
 use strum_macros::FromRepr;
@@ -143,6 +143,64 @@ impl Decode<'_, ()> for Cip509RbacMetadata {
     }
 }
 
+impl Encode<()> for Cip509RbacMetadata {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, ctx: &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        let map_len = u64::from(self.x509_certs.is_some())
+            + u64::from(self.c509_certs.is_some())
+            + u64::from(self.pub_keys.is_some())
+            + u64::from(self.revocation_list.is_some())
+            + u64::from(self.role_set.is_some())
+            + self.purpose_key_data.len() as u64;
+        e.map(map_len)?;
+        if let Some(x509_certs) = &self.x509_certs {
+            e.u16(Cip509RbacMetadataInt::X509Certs as u16)?;
+            encode_array_rbac(e, x509_certs, ctx)?;
+        }
+        if let Some(c509_certs) = &self.c509_certs {
+            e.u16(Cip509RbacMetadataInt::C509Certs as u16)?;
+            encode_array_rbac(e, c509_certs, ctx)?;
+        }
+        if let Some(pub_keys) = &self.pub_keys {
+            e.u16(Cip509RbacMetadataInt::PubKeys as u16)?;
+            encode_array_rbac(e, pub_keys, ctx)?;
+        }
+        if let Some(revocation_list) = &self.revocation_list {
+            e.u16(Cip509RbacMetadataInt::RevocationList as u16)?
+                .array(revocation_list.len() as u64)?;
+            for cert_key_hash in revocation_list {
+                e.bytes(&<[u8; 16]>::from(cert_key_hash.clone()))?;
+            }
+        }
+        if let Some(role_set) = &self.role_set {
+            e.u16(Cip509RbacMetadataInt::RoleSet as u16)?;
+            encode_array_rbac(e, role_set, ctx)?;
+        }
+        // Sort the keys, so the same metadata always encodes to the same bytes.
+        let mut purpose_key_data: Vec<_> = self.purpose_key_data.iter().collect();
+        purpose_key_data.sort_by_key(|(key, _)| **key);
+        for (key, data) in purpose_key_data {
+            if !(FIRST_PURPOSE_KEY..=LAST_PURPOSE_KEY).contains(key) {
+                return Err(encode::Error::message(format!("Invalid purpose key {key}, should be with the range {FIRST_PURPOSE_KEY} - {LAST_PURPOSE_KEY}")));
+            }
+            e.u16(*key)?.bytes(data)?;
+        }
+        Ok(())
+    }
+}
+
+/// Encode an array of type T.
+fn encode_array_rbac<W: encode::Write, T: Encode<()>>(
+    e: &mut Encoder<W>, items: &[T], ctx: &mut (),
+) -> Result<(), encode::Error<W::Error>> {
+    e.array(items.len() as u64)?;
+    for item in items {
+        item.encode(e, ctx)?;
+    }
+    Ok(())
+}
+
 /// Decode an array of type T.
 fn decode_array_rbac<'b, T>(d: &mut Decoder<'b>, from: &str) -> Result<Vec<T>, decode::Error>
 where T: Decode<'b, ()> {
@@ -1,7 +1,7 @@
 //! Public key type for RBAC metadata
 
 use ed25519_dalek::VerifyingKey;
-use minicbor::{decode, Decode, Decoder};
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
 
 use super::tag::KeyTag;
 use crate::utils::decode_helper::{decode_bytes, decode_tag};
@@ -24,7 +24,10 @@ impl Decode<'_, ()> for SimplePublicKeyType {
             minicbor::data::Type::Tag => {
                 let tag = decode_tag(d, "SimplePublicKeyType")?;
                 match tag {
-                    t if t == KeyTag::Deleted.tag() => Ok(Self::Deleted),
+                    t if t == KeyTag::Deleted.tag() => {
+                        d.undefined()?;
+                        Ok(Self::Deleted)
+                    },
                     t if t == KeyTag::Ed25519.tag() => {
                         let bytes = decode_bytes(d, "Ed25519 SimplePublicKeyType")?;
                         let mut ed25519 = [0u8; 32];
@@ -53,3 +56,16 @@ impl Decode<'_, ()> for SimplePublicKeyType {
         }
     }
 }
+
+impl Encode<()> for SimplePublicKeyType {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, _ctx: &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        match self {
+            Self::Undefined => e.undefined()?,
+            Self::Deleted => e.tag(KeyTag::Deleted.tag())?.undefined()?,
+            Self::Ed25519(key) => e.tag(KeyTag::Ed25519.tag())?.bytes(key.as_bytes())?,
+        };
+        Ok(())
+    }
+}
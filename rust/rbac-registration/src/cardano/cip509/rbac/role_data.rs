@@ -2,7 +2,7 @@
 
 use std::collections::HashMap;
 
-use minicbor::{decode, Decode, Decoder};
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
 use strum_macros::FromRepr;
 
 use super::{decode_any, decode_map_len, Cip509RbacMetadataInt};
@@ -80,6 +80,42 @@ impl Decode<'_, ()> for RoleData {
         Ok(role_data)
     }
 }
+
+impl Encode<()> for RoleData {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, ctx: &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        let map_len = 1
+            + u64::from(self.role_signing_key.is_some())
+            + u64::from(self.role_encryption_key.is_some())
+            + u64::from(self.payment_key.is_some())
+            + self.role_extended_data_keys.len() as u64;
+        e.map(map_len)?;
+        e.u8(RoleDataInt::RoleNumber as u8)?.u8(self.role_number)?;
+        if let Some(role_signing_key) = &self.role_signing_key {
+            e.u8(RoleDataInt::RoleSigningKey as u8)?.array(2)?;
+            role_signing_key.encode(e, ctx)?;
+        }
+        if let Some(role_encryption_key) = &self.role_encryption_key {
+            e.u8(RoleDataInt::RoleEncryptionKey as u8)?.array(2)?;
+            role_encryption_key.encode(e, ctx)?;
+        }
+        if let Some(payment_key) = self.payment_key {
+            e.u8(RoleDataInt::PaymentKey as u8)?.i16(payment_key)?;
+        }
+        // Sort the keys, so the same role data always encodes to the same bytes.
+        let mut extended_data: Vec<_> = self.role_extended_data_keys.iter().collect();
+        extended_data.sort_by_key(|(key, _)| **key);
+        for (key, data) in extended_data {
+            if !(FIRST_ROLE_EXT_KEY..=LAST_ROLE_EXT_KEY).contains(key) {
+                return Err(encode::Error::message(format!("Invalid role extended data key {key}, should be with the range {FIRST_ROLE_EXT_KEY} - {LAST_ROLE_EXT_KEY}")));
+            }
+            e.u8(*key)?.bytes(data)?;
+        }
+        Ok(())
+    }
+}
+
 /// Local key reference.
 #[derive(Debug, PartialEq, Clone)]
 pub struct KeyLocalRef {
@@ -112,3 +148,12 @@ impl Decode<'_, ()> for KeyLocalRef {
         })
     }
 }
+
+impl Encode<()> for KeyLocalRef {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, _ctx: &mut (),
+    ) -> Result<(), encode::Error<W::Error>> {
+        e.u8(self.local_ref.clone() as u8)?.u64(self.key_offset)?;
+        Ok(())
+    }
+}
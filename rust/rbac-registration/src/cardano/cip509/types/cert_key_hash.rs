@@ -1,9 +1,13 @@
 //! Certificate key hash type
 
+use super::impl_hex_string;
+
 /// Certificate key hash use in revocation list.
 #[derive(Debug, PartialEq, Clone, Default)]
 pub struct CertKeyHash([u8; 16]);
 
+impl_hex_string!(CertKeyHash);
+
 impl From<[u8; 16]> for CertKeyHash {
     fn from(bytes: [u8; 16]) -> Self {
         CertKeyHash(bytes)
@@ -2,3 +2,66 @@
 
 pub mod cert_key_hash;
 pub mod tx_input_hash;
+
+/// Implement hex string representation, `Display`, `FromStr` with an optional `0x`
+/// prefix and `serde` as a hex string, for the fixed size byte array newtype.
+macro_rules! impl_hex_string {
+    ($type:ty) => {
+        impl std::fmt::Display for $type {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(&hex::encode(self.0))
+            }
+        }
+
+        impl std::str::FromStr for $type {
+            type Err = anyhow::Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let bytes = hex::decode(s.strip_prefix("0x").unwrap_or(s))?;
+                bytes
+                    .try_into()
+                    .map_err(|e| anyhow::anyhow!("Invalid {} string: {e}", stringify!($type)))
+            }
+        }
+
+        impl serde::Serialize for $type {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where S: serde::Serializer {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $type {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where D: serde::Deserializer<'de> {
+                let s = String::deserialize(deserializer)?;
+                s.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_hex_string;
+
+#[cfg(test)]
+mod tests {
+    use super::{cert_key_hash::CertKeyHash, tx_input_hash::TxInputHash};
+
+    #[test]
+    fn hex_string_representation() {
+        let hex = "000102030405060708090a0b0c0d0e0f";
+        let hash = TxInputHash::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+        assert_eq!(hash.to_string(), hex);
+        assert_eq!(hex.parse::<TxInputHash>().unwrap(), hash);
+        assert_eq!(format!("0x{hex}").parse::<TxInputHash>().unwrap(), hash);
+        assert!("0001".parse::<TxInputHash>().is_err());
+        assert!("zz".parse::<CertKeyHash>().is_err());
+
+        let json = serde_json::to_string(&CertKeyHash::from([1; 16])).unwrap();
+        assert_eq!(json, "\"01010101010101010101010101010101\"");
+        assert_eq!(
+            serde_json::from_str::<CertKeyHash>(&json).unwrap(),
+            CertKeyHash::from([1; 16])
+        );
+    }
+}
@@ -1,9 +1,40 @@
 //! Transaction input hash type
 
+use pallas::{
+    codec::minicbor::{Encode, Encoder},
+    ledger::primitives::conway::TransactionInput,
+};
+
+use super::impl_hex_string;
+use crate::utils::hashing::blake2b_128;
+
 /// Transaction input hash representing in 16 bytes.
 #[derive(Debug, PartialEq, Clone, Default)]
 pub struct TxInputHash([u8; 16]);
 
+impl_hex_string!(TxInputHash);
+
+impl TxInputHash {
+    /// Compute the hash of the transaction inputs, the blake2b-128 hash of the CBOR
+    /// encoded array of the inputs.
+    ///
+    /// # Errors
+    ///
+    /// If the inputs cannot be encoded or hashed.
+    pub fn from_txn_inputs(inputs: &[TransactionInput]) -> anyhow::Result<Self> {
+        let mut buffer = Vec::new();
+        let mut e = Encoder::new(&mut buffer);
+        e.array(inputs.len() as u64)
+            .map_err(|e| anyhow::anyhow!("Failed to encode array of transaction input: {e}"))?;
+        for input in inputs {
+            input
+                .encode(&mut e, &mut ())
+                .map_err(|e| anyhow::anyhow!("Failed to encode transaction input {e}"))?;
+        }
+        Ok(Self(blake2b_128(&buffer)?))
+    }
+}
+
 impl From<[u8; 16]> for TxInputHash {
     fn from(bytes: [u8; 16]) -> Self {
         TxInputHash(bytes)
@@ -4,12 +4,6 @@ use anyhow::bail;
 
 use crate::cardano::transaction::witness::TxWitness;
 
-/// Extract the first 28 bytes from the given key
-/// Refer to <https://cips.cardano.org/cip/CIP-19> for more information.
-pub(crate) fn extract_key_hash(key: &[u8]) -> Option<Vec<u8>> {
-    key.get(1..29).map(<[u8]>::to_vec)
-}
-
 /// Compare the given public key bytes with the transaction witness set.
 pub(crate) fn compare_key_hash(
     pk_addrs: &[Vec<u8>], witness: &TxWitness, txn_idx: u16,
@@ -14,6 +14,8 @@
 //!        only check whether the index exist within the transaction inputs.
 //! * Role signing key validation for role 0 where the signing keys should only be the
 //!   certificates
+//! * C509 certificates revocation validation, where the c509 certificates should not be
+//!   revoked by the certificate revocation list of their issuer
 //!
 //!  See:
 //! * <https://github.com/input-output-hk/catalyst-CIPs/tree/x509-envelope-metadata/CIP-XXXX>
@@ -21,27 +23,26 @@
 //!
 //! Note: This CIP509 is still under development and is subject to change.
 
-use c509_certificate::{general_names::general_name::GeneralNameValue, C509ExtensionType};
+use c509_certificate::{
+    crl::{check_revocation, RevocationViolation, TbsCrl},
+    general_names::general_name::GeneralNameValue,
+    C509ExtensionType,
+};
+use cardano_blockchain_types::cip19::Cip19Address;
 use der_parser::der::parse_der_sequence;
 use pallas::{
-    codec::{
-        minicbor::{Encode, Encoder},
-        utils::Bytes,
-    },
+    codec::utils::Bytes,
     ledger::{addresses::Address, traverse::MultiEraTx},
 };
 use x509_cert::der::{oid::db::rfc5912::ID_CE_SUBJECT_ALT_NAME, Decode};
 
 use super::{
-    blake2b_128, blake2b_256, decode_utf8, decremented_index,
+    blake2b_256, decode_utf8, decremented_index,
     rbac::{
         certs::{C509Cert, X509DerCert},
         role_data::{LocalRefInt, RoleData},
     },
-    utils::{
-        cip19::{compare_key_hash, extract_key_hash},
-        Cip0134Uri,
-    },
+    utils::{cip19::compare_key_hash, Cip0134Uri},
     Cip509, TxInputHash, TxWitness,
 };
 use crate::utils::general::zero_out_last_n_bytes;
@@ -66,39 +67,17 @@ pub(crate) fn validate_txn_inputs_hash(
     cip509: &Cip509, txn: &MultiEraTx, validation_report: &mut Vec<String>,
 ) -> Option<bool> {
     let function_name = "Validate Transaction Inputs Hash";
-    let mut buffer = Vec::new();
-    let mut e = Encoder::new(&mut buffer);
     // CIP-0509 should only be in conway era
     if let MultiEraTx::Conway(tx) = txn {
-        let inputs = tx.transaction_body.inputs.clone();
-        if let Err(e) = e.array(inputs.len() as u64) {
-            validation_report.push(format!(
-                "{function_name}, Failed to encode array of transaction input: {e}"
-            ));
-            return None;
-        }
-        for input in &inputs {
-            match input.encode(&mut e, &mut ()) {
-                Ok(()) => {},
-                Err(e) => {
-                    validation_report.push(format!(
-                        "{function_name}, Failed to encode transaction input {e}"
-                    ));
-                    return None;
-                },
-            }
-        }
-        // Hash the transaction inputs
-        let inputs_hash = match blake2b_128(&buffer) {
-            Ok(hash) => hash,
+        match TxInputHash::from_txn_inputs(&tx.transaction_body.inputs) {
+            Ok(inputs_hash) => Some(inputs_hash == cip509.txn_inputs_hash),
             Err(e) => {
                 validation_report.push(format!(
                     "{function_name}, Failed to hash transaction inputs {e}"
                 ));
-                return None;
+                None
             },
-        };
-        Some(TxInputHash::from(inputs_hash) == cip509.txn_inputs_hash)
+        }
     } else {
         validation_report.push(format!("{function_name}, Unsupported transaction era for"));
         None
@@ -443,12 +422,15 @@ fn validate_payment_output_key_helper(
     output_address: &[u8], validation_report: &mut Vec<String>, witness: &TxWitness,
 ) -> Option<bool> {
     // Extract the key hash from the output address
-    if let Some(key) = extract_key_hash(output_address) {
+    if let Some(key) = Cip19Address::from_bytes(output_address)
+        .ok()
+        .and_then(|address| address.payment_hash())
+    {
         // Compare the key hash and return the result
         // Set transaction index to 0 because the list of transaction is manually constructed
         // for TxWitness -> &[txn.clone()], so we can assume that the witness contains only
         // the witness within this transaction.
-        return Some(compare_key_hash(&[key], witness, 0).is_ok());
+        return Some(compare_key_hash(&[key.into()], witness, 0).is_ok());
     }
     validation_report.push("Failed to extract payment key hash from address".to_string());
     None
@@ -477,15 +459,59 @@ pub(crate) fn validate_role_singing_key(
     true
 }
 
+// ------------------------ Validate C509 revocation ------------------------
+
+/// C509 certificates revocation validation.
+/// C509 certificates must not be revoked by the CRL of their issuer at the `time`, in
+/// seconds since the Unix epoch. Every violation found is reported, but only revoked
+/// certificates fail the validation.
+pub(crate) fn validate_c509_revocation(
+    cip509: &Cip509, crls: &[TbsCrl], time: u64, validation_report: &mut Vec<String>,
+) -> bool {
+    let function_name = "Validate C509 Revocation";
+    let mut is_valid = true;
+
+    let Some(c509_certs) = &cip509.x509_chunks.0.c509_certs else {
+        return is_valid;
+    };
+    for (index, cert) in c509_certs.iter().enumerate() {
+        let C509Cert::C509Certificate(cert) = cert else {
+            continue;
+        };
+        let tbs_cert = cert.tbs_cert();
+        for crl in crls.iter().filter(|crl| crl.issuer() == tbs_cert.issuer()) {
+            for violation in check_revocation(tbs_cert, crl, time) {
+                if matches!(violation, RevocationViolation::Revoked { .. }) {
+                    is_valid = false;
+                }
+                validation_report.push(format!(
+                    "{function_name}, C509 certificate at index {index}: {violation}"
+                ));
+            }
+        }
+    }
+
+    is_valid
+}
+
 // ------------------------ Tests ------------------------
 
 #[cfg(test)]
 mod tests {
 
+    use c509_certificate::{
+        c509::C509,
+        crl::{RevocationReason, RevokedCertificate},
+        extensions::Extensions,
+        time::Time,
+    };
     use minicbor::{Decode, Decoder};
 
     use super::*;
-    use crate::cardano::transaction::raw_aux_data::RawAuxData;
+    use crate::cardano::{
+        cip509::{rbac::Cip509RbacMetadata, x509_chunks::X509Chunks},
+        transaction::raw_aux_data::RawAuxData,
+    };
 
     fn cip_509_aux_data(tx: &MultiEraTx<'_>) -> Vec<u8> {
         let raw_auxiliary_data = tx
@@ -676,4 +702,74 @@ mod tests {
         let cip509 = Cip509::decode(&mut decoder, &mut ()).expect("Failed to decode Cip509");
         assert!(!validate_stake_public_key(&cip509, tx, &mut validation_report).unwrap());
     }
+
+    #[test]
+    fn test_validate_c509_revocation() {
+        // C509 certificate of the RFC test CA, without the issuer signature
+        let cert = hex::decode(
+            "034301f50d006b52464320746573742043411a63b0cd001a6955b90047010123456789ab014888d0b6b0b37baa4601f6",
+        )
+        .unwrap();
+        let cert = C509::decode(&mut Decoder::new(&cert), &mut ()).unwrap();
+        let tbs_cert = cert.tbs_cert();
+        let cip509 = Cip509 {
+            x509_chunks: X509Chunks(Cip509RbacMetadata {
+                c509_certs: Some(vec![C509Cert::C509Certificate(Box::new(cert.clone()))]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let crl = TbsCrl::new(
+            2,
+            tbs_cert.issuer().clone(),
+            Time::new(1_700_000_000),
+            Time::new(1_800_000_000),
+            vec![RevokedCertificate::new(
+                tbs_cert.certificate_serial_number().clone(),
+                Time::new(1_710_000_000),
+                Some(RevocationReason::KeyCompromise),
+            )],
+            Extensions::new(),
+            tbs_cert.issuer_signature_algorithm().clone(),
+        );
+
+        // Before the revocation
+        let mut validation_report = Vec::new();
+        assert!(validate_c509_revocation(
+            &cip509,
+            &[crl.clone()],
+            1_705_000_000,
+            &mut validation_report
+        ));
+        assert!(validation_report.is_empty());
+
+        // After the revocation
+        assert!(!validate_c509_revocation(
+            &cip509,
+            &[crl.clone()],
+            1_750_000_000,
+            &mut validation_report
+        ));
+        assert_eq!(validation_report.len(), 1);
+
+        // Outdated CRL is reported, the certificate is still revoked
+        let mut validation_report = Vec::new();
+        assert!(!validate_c509_revocation(
+            &cip509,
+            &[crl],
+            1_900_000_000,
+            &mut validation_report
+        ));
+        assert_eq!(validation_report.len(), 2);
+
+        // No CRL of the certificate issuer
+        let mut validation_report = Vec::new();
+        assert!(validate_c509_revocation(
+            &cip509,
+            &[],
+            1_750_000_000,
+            &mut validation_report
+        ));
+        assert!(validation_report.is_empty());
+    }
 }
@@ -1,15 +1,16 @@
 //! X509 chunks handler where compressed chunks are decompressed and decoded.
 
-use std::io::Read;
+use std::io::{Read, Write};
 
-use minicbor::{decode, Decode, Decoder};
+use anyhow::ensure;
+use minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
 use strum_macros::FromRepr;
 
 use super::rbac::Cip509RbacMetadata;
 use crate::utils::decode_helper::{decode_array_len, decode_bytes, decode_helper};
 
 /// Enum of compression algorithms used to compress chunks.
-#[derive(FromRepr, Debug, PartialEq, Clone, Default)]
+#[derive(FromRepr, Debug, PartialEq, Eq, Clone, Copy, Default)]
 #[repr(u8)]
 pub enum CompressionAlgorithm {
     /// Raw data, no compression.
@@ -21,27 +22,85 @@ pub enum CompressionAlgorithm {
     Zstd = 12,
 }
 
+impl CompressionAlgorithm {
+    /// All the compression algorithms, in the order of preference when the compressed
+    /// sizes are equal.
+    const ALL: [Self; 3] = [Self::Raw, Self::Brotli, Self::Zstd];
+
+    /// Select the algorithm which gives the smallest encoding of the data.
+    /// Raw is selected if the compression does not yield any benefits.
+    ///
+    /// # Errors
+    ///
+    /// If the data cannot be compressed.
+    pub fn smallest(data: &[u8]) -> anyhow::Result<Self> {
+        let mut smallest = (Self::Raw, data.len());
+        for algorithm in Self::ALL {
+            let size = compress(data, algorithm)?.len();
+            if size < smallest.1 {
+                smallest = (algorithm, size);
+            }
+        }
+        Ok(smallest.0)
+    }
+}
+
+/// Limits applied when decompressing the chunks, protecting against decompression
+/// bombs.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DecompressionLimits {
+    /// Maximum size of the concatenated compressed chunks.
+    pub max_compressed_size: usize,
+    /// Maximum size of the decompressed data.
+    pub max_decompressed_size: usize,
+}
+
+impl Default for DecompressionLimits {
+    fn default() -> Self {
+        Self {
+            // The maximum size of a Cardano transaction.
+            max_compressed_size: 16 * 1024,
+            max_decompressed_size: 1024 * 1024,
+        }
+    }
+}
+
+/// Maximum size of a single chunk, the metadata bytes size limit.
+const MAX_CHUNK_SIZE: usize = 64;
+
 /// x509 chunks.
 #[derive(Debug, PartialEq, Clone, Default)]
 pub struct X509Chunks(pub Cip509RbacMetadata);
 
-#[allow(dead_code)]
 impl X509Chunks {
-    /// Create new instance of `X509Chunks`.
-    fn new(chunk_data: Cip509RbacMetadata) -> Self {
-        Self(chunk_data)
+    /// Select the compression algorithm which gives the smallest encoding of the
+    /// chunks.
+    ///
+    /// # Errors
+    ///
+    /// If the RBAC metadata cannot be encoded or compressed.
+    pub fn smallest_compression(&self) -> anyhow::Result<CompressionAlgorithm> {
+        let data = minicbor::to_vec(&self.0)
+            .map_err(|e| anyhow::anyhow!("Failed to encode RBAC metadata: {e}"))?;
+        CompressionAlgorithm::smallest(&data)
     }
 }
 
 impl Decode<'_, ()> for X509Chunks {
-    fn decode(d: &mut Decoder, ctx: &mut ()) -> Result<Self, decode::Error> {
+    fn decode(d: &mut Decoder, _ctx: &mut ()) -> Result<Self, decode::Error> {
+        Self::decode(d, &mut DecompressionLimits::default())
+    }
+}
+
+impl Decode<'_, DecompressionLimits> for X509Chunks {
+    fn decode(d: &mut Decoder, limits: &mut DecompressionLimits) -> Result<Self, decode::Error> {
         // Determine the algorithm
-        let algo: u8 = decode_helper(d, "algorithm in X509Chunks", ctx)?;
+        let algo: u8 = decode_helper(d, "algorithm in X509Chunks", limits)?;
         let algorithm = CompressionAlgorithm::from_repr(algo)
             .ok_or(decode::Error::message("Invalid chunk data type"))?;
 
         // Decompress the data
-        let decompressed = decompress(d, &algorithm)
+        let decompressed = decompress(d, algorithm, limits)
             .map_err(|e| decode::Error::message(format!("Failed to decompress {e}")))?;
 
         // Decode the decompressed data.
@@ -53,16 +112,59 @@ impl Decode<'_, ()> for X509Chunks {
     }
 }
 
-/// Decompress the data using the given algorithm.
-fn decompress(d: &mut Decoder, algorithm: &CompressionAlgorithm) -> anyhow::Result<Vec<u8>> {
+impl Encode<CompressionAlgorithm> for X509Chunks {
+    fn encode<W: encode::Write>(
+        &self, e: &mut Encoder<W>, algorithm: &mut CompressionAlgorithm,
+    ) -> Result<(), encode::Error<W::Error>> {
+        let data = minicbor::to_vec(&self.0).map_err(|err| {
+            encode::Error::message(format!("Failed to encode RBAC metadata: {err}"))
+        })?;
+        let compressed = compress(&data, *algorithm)
+            .map_err(|err| encode::Error::message(format!("Failed to compress {err}")))?;
+
+        e.u8(*algorithm as u8)?;
+        e.array(compressed.chunks(MAX_CHUNK_SIZE).len() as u64)?;
+        for chunk in compressed.chunks(MAX_CHUNK_SIZE) {
+            e.bytes(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compress the data using the given algorithm.
+fn compress(data: &[u8], algorithm: CompressionAlgorithm) -> anyhow::Result<Vec<u8>> {
+    match algorithm {
+        CompressionAlgorithm::Raw => Ok(data.to_vec()),
+        CompressionAlgorithm::Zstd => Ok(zstd::stream::encode_all(data, 0)?),
+        CompressionAlgorithm::Brotli => {
+            let mut encoder = brotli::CompressorWriter::new(Vec::new(), 4096, 11, 22);
+            encoder
+                .write_all(data)
+                .map_err(|_| anyhow::anyhow!("Failed to compress using Brotli algorithm"))?;
+            Ok(encoder.into_inner())
+        },
+    }
+}
+
+/// Decompress the data using the given algorithm, within the given limits.
+fn decompress(
+    d: &mut Decoder, algorithm: CompressionAlgorithm, limits: &DecompressionLimits,
+) -> anyhow::Result<Vec<u8>> {
     let chunk_len = decode_array_len(d, "decompression in X509Chunks")?;
     // Vector containing the concatenated chunks
     let mut concat_chunk = vec![];
     for _ in 0..chunk_len {
         let chunk_data = decode_bytes(d, "decompression in X509Chunks")?;
         concat_chunk.extend_from_slice(&chunk_data);
+        ensure!(
+            concat_chunk.len() <= limits.max_compressed_size,
+            "Decompression limit exceeded, compressed size is over {} bytes",
+            limits.max_compressed_size
+        );
     }
 
+    // Read one byte over the limit, to detect the data which exceeds it.
+    let limit = u64::try_from(limits.max_decompressed_size)?.saturating_add(1);
     let mut buffer = vec![];
 
     match algorithm {
@@ -70,15 +172,22 @@ fn decompress(d: &mut Decoder, algorithm: &CompressionAlgorithm) -> anyhow::Resu
             buffer.extend_from_slice(concat_chunk.as_slice());
         },
         CompressionAlgorithm::Zstd => {
-            zstd::stream::copy_decode(concat_chunk.as_slice(), &mut buffer)?;
+            zstd::stream::read::Decoder::new(concat_chunk.as_slice())?
+                .take(limit)
+                .read_to_end(&mut buffer)?;
         },
         CompressionAlgorithm::Brotli => {
-            let mut decoder = brotli::Decompressor::new(concat_chunk.as_slice(), 4096);
-            decoder
+            brotli::Decompressor::new(concat_chunk.as_slice(), 4096)
+                .take(limit)
                 .read_to_end(&mut buffer)
                 .map_err(|_| anyhow::anyhow!("Failed to decompress using Brotli algorithm"))?;
         },
     }
+    ensure!(
+        buffer.len() <= limits.max_decompressed_size,
+        "Decompression limit exceeded, decompressed size is over {} bytes",
+        limits.max_decompressed_size
+    );
     Ok(buffer)
 }
 
@@ -111,6 +220,70 @@ mod tests {
         assert!(x509_chunks.is_ok());
     }
 
+    #[test]
+    fn test_encode_x509_chunks() {
+        let raw_bytes = hex::decode(RAW).unwrap();
+        let x509_chunks = X509Chunks::decode(&mut Decoder::new(&raw_bytes), &mut ()).unwrap();
+
+        for mut algorithm in [
+            CompressionAlgorithm::Raw,
+            CompressionAlgorithm::Brotli,
+            CompressionAlgorithm::Zstd,
+        ] {
+            let mut buffer = Vec::new();
+            x509_chunks
+                .encode(&mut Encoder::new(&mut buffer), &mut algorithm)
+                .unwrap();
+            let decoded = X509Chunks::decode(&mut Decoder::new(&buffer), &mut ()).unwrap();
+            assert_eq!(decoded, x509_chunks);
+        }
+    }
+
+    #[test]
+    fn test_x509_chunks_smallest_compression() {
+        let raw_bytes = hex::decode(RAW).unwrap();
+        let x509_chunks = X509Chunks::decode(&mut Decoder::new(&raw_bytes), &mut ()).unwrap();
+        let data = minicbor::to_vec(&x509_chunks.0).unwrap();
+
+        let smallest = x509_chunks.smallest_compression().unwrap();
+        let smallest_size = compress(&data, smallest).unwrap().len();
+        for algorithm in CompressionAlgorithm::ALL {
+            assert!(smallest_size <= compress(&data, algorithm).unwrap().len());
+        }
+        // Data which cannot be compressed is kept raw.
+        assert_eq!(
+            CompressionAlgorithm::smallest(&[1]).unwrap(),
+            CompressionAlgorithm::Raw
+        );
+    }
+
+    #[test]
+    fn test_decode_x509_chunks_limits() {
+        let mut limits = DecompressionLimits {
+            max_compressed_size: 1024,
+            max_decompressed_size: 1024,
+        };
+        for chunks in [RAW, BROTLI, ZSTD] {
+            let bytes = hex::decode(chunks).unwrap();
+            let x509_chunks = X509Chunks::decode(&mut Decoder::new(&bytes), &mut limits);
+            assert!(x509_chunks.is_ok());
+        }
+
+        // The decompressed data is over the limit.
+        limits.max_decompressed_size = 100;
+        for chunks in [RAW, BROTLI, ZSTD] {
+            let bytes = hex::decode(chunks).unwrap();
+            let err = X509Chunks::decode(&mut Decoder::new(&bytes), &mut limits).unwrap_err();
+            assert!(err.to_string().contains("Decompression limit exceeded"));
+        }
+
+        // The compressed data is over the limit.
+        limits.max_compressed_size = 100;
+        let bytes = hex::decode(ZSTD).unwrap();
+        let err = X509Chunks::decode(&mut Decoder::new(&bytes), &mut limits).unwrap_err();
+        assert!(err.to_string().contains("Decompression limit exceeded"));
+    }
+
     #[test]
     fn test_decode_x509_chunks_zstd() {
         let zstd_bytes = hex::decode(ZSTD).unwrap();
@@ -3,11 +3,13 @@
 pub mod payment_history;
 pub mod point_tx_idx;
 pub mod role_data;
+mod serialize;
 
 use std::{collections::HashMap, sync::Arc};
 
 use anyhow::bail;
-use c509_certificate::c509::C509;
+use c509_certificate::{c509::C509, crl::TbsCrl};
+use cardano_blockchain_types::Network;
 use ed25519_dalek::VerifyingKey;
 use pallas::{
     crypto::hash::Hash,
@@ -29,6 +31,7 @@ use crate::{
         rbac::{
             certs::{C509Cert, X509DerCert},
             pub_key::SimplePublicKeyType,
+            role_data::KeyLocalRef,
         },
         types::cert_key_hash::CertKeyHash,
         Cip509, Cip509Validation,
@@ -36,7 +39,46 @@ use crate::{
     utils::general::decremented_index,
 };
 
+/// Certificate revocation lists, the C509 certificates of the registrations are checked
+/// against at the time of their registration.
+#[derive(Debug, Clone)]
+pub struct RevocationLists {
+    /// Network of the registrations, to get the time of their slot.
+    network: Network,
+    /// TBS of the certificate revocation lists.
+    crls: Vec<TbsCrl>,
+}
+
+impl RevocationLists {
+    /// Create a new instance of revocation lists.
+    ///
+    /// # Arguments
+    /// - `network` - The network of the registrations.
+    /// - `crls` - TBS of the certificate revocation lists, their signature verified.
+    #[must_use]
+    pub fn new(network: Network, crls: Vec<TbsCrl>) -> Self {
+        Self { network, crls }
+    }
+
+    /// Validate the C509 certificates of the CIP509 are not revoked at the point (slot)
+    /// of the registration.
+    fn validate(
+        &self, cip509: &Cip509, point: &Point, validation_report: &mut Vec<String>,
+    ) -> bool {
+        let time = self
+            .network
+            .slot_to_time(point.slot_or_default().into())
+            .timestamp();
+        cip509.validate_revocation(
+            &self.crls,
+            u64::try_from(time).unwrap_or_default(),
+            validation_report,
+        )
+    }
+}
+
 /// Registration chains.
+#[derive(Clone)]
 pub struct RegistrationChain {
     /// Inner part of the registration chain.
     inner: Arc<RegistrationChainInner>,
@@ -60,7 +102,36 @@ impl RegistrationChain {
         point: Point, tracking_payment_keys: &[ShelleyAddress], tx_idx: usize, txn: &MultiEraTx,
         cip509: Cip509,
     ) -> anyhow::Result<Self> {
-        let inner = RegistrationChainInner::new(cip509, tracking_payment_keys, point, tx_idx, txn)?;
+        Self::new_with_revocation_lists(point, tracking_payment_keys, tx_idx, txn, cip509, None)
+    }
+
+    /// Create a new instance of registration chain, its C509 certificates are checked
+    /// against the certificate revocation lists.
+    /// The first new value should be the chain root.
+    ///
+    /// # Arguments
+    /// - `cip509` - The CIP509.
+    /// - `tracking_payment_keys` - The list of payment keys to track.
+    /// - `point` - The point (slot) of the transaction.
+    /// - `tx_idx` - The transaction index.
+    /// - `txn` - The transaction.
+    /// - `revocation_lists` - The certificate revocation lists, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if data is invalid, or a C509 certificate is revoked
+    pub fn new_with_revocation_lists(
+        point: Point, tracking_payment_keys: &[ShelleyAddress], tx_idx: usize, txn: &MultiEraTx,
+        cip509: Cip509, revocation_lists: Option<&RevocationLists>,
+    ) -> anyhow::Result<Self> {
+        let inner = RegistrationChainInner::new(
+            cip509,
+            tracking_payment_keys,
+            point,
+            tx_idx,
+            txn,
+            revocation_lists,
+        )?;
 
         Ok(Self {
             inner: Arc::new(inner),
@@ -81,13 +152,62 @@ impl RegistrationChain {
     pub fn update(
         &self, point: Point, tx_idx: usize, txn: &MultiEraTx, cip509: Cip509,
     ) -> anyhow::Result<Self> {
-        let new_inner = self.inner.update(point, tx_idx, txn, cip509)?;
+        self.update_with_revocation_lists(point, tx_idx, txn, cip509, None)
+    }
+
+    /// Update the registration chain, the C509 certificates of the update are checked
+    /// against the certificate revocation lists.
+    ///
+    /// # Arguments
+    /// - `point` - The point (slot) of the transaction.
+    /// - `tx_idx` - The transaction index.
+    /// - `txn` - The transaction.
+    /// - `cip509` - The CIP509.
+    /// - `revocation_lists` - The certificate revocation lists, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if data is invalid, or a C509 certificate is revoked
+    pub fn update_with_revocation_lists(
+        &self, point: Point, tx_idx: usize, txn: &MultiEraTx, cip509: Cip509,
+        revocation_lists: Option<&RevocationLists>,
+    ) -> anyhow::Result<Self> {
+        let new_inner = self
+            .inner
+            .update(point, tx_idx, txn, cip509, revocation_lists)?;
 
         Ok(Self {
             inner: Arc::new(new_inner),
         })
     }
 
+    /// Serialize the complete registration chain to versioned CBOR, so it can be
+    /// persisted and reloaded with `from_cbor`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the registration data can not be encoded.
+    pub fn to_cbor(&self) -> anyhow::Result<Vec<u8>> {
+        minicbor::to_vec(self.inner.as_ref())
+            .map_err(|e| anyhow::anyhow!("Failed to encode registration chain: {e}"))
+    }
+
+    /// Deserialize a registration chain from the CBOR produced by `to_cbor`, including
+    /// that of any earlier version of this crate.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not a valid serialized registration chain, or was
+    /// serialized by a newer version of this crate.
+    pub fn from_cbor(bytes: &[u8]) -> anyhow::Result<Self> {
+        let inner: RegistrationChainInner = minicbor::decode(bytes)
+            .map_err(|e| anyhow::anyhow!("Failed to decode registration chain: {e}"))?;
+
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
     /// Get the current transaction ID hash.
     #[must_use]
     pub fn current_tx_id_hash(&self) -> Hash<32> {
@@ -130,6 +250,42 @@ impl RegistrationChain {
         &self.inner.role_data
     }
 
+    /// Get the map of role number to all updates of its role data, in chain order.
+    #[must_use]
+    pub fn role_data_history(&self) -> &HashMap<u8, Vec<(PointTxIdx, RoleData)>> {
+        &self.inner.role_data_history
+    }
+
+    /// Get the role data of the role effective at the point (slot), that is the role data
+    /// of its last update at or before the point.
+    /// Returns `None` if the role was not registered yet at the point.
+    #[must_use]
+    pub fn role_data_at(&self, role: u8, point: &Point) -> Option<&RoleData> {
+        let history = self.inner.role_data_history.get(&role)?;
+        let slot = point.slot_or_default();
+        // History is in chain order, so it is sorted by slot.
+        let updates = history
+            .partition_point(|(point_tx_idx, _)| point_tx_idx.point().slot_or_default() <= slot);
+        history
+            .get(updates.checked_sub(1)?)
+            .map(|(_, role_data)| role_data)
+    }
+
+    /// Get the signing key and encryption key references of the role effective at the
+    /// point (slot), both are `None` if the role was not registered yet at the point.
+    #[must_use]
+    pub fn keys_at(&self, role: u8, point: &Point) -> (Option<&KeyLocalRef>, Option<&KeyLocalRef>) {
+        match self.role_data_at(role, point) {
+            Some(role_data) => {
+                (
+                    role_data.signing_key_ref().as_ref(),
+                    role_data.encryption_ref().as_ref(),
+                )
+            },
+            None => (None, None),
+        }
+    }
+
     /// Get the map of tracked payment keys to its history.
     #[must_use]
     pub fn tracking_payment_history(&self) -> &HashMap<ShelleyAddress, Vec<PaymentHistory>> {
@@ -158,6 +314,8 @@ struct RegistrationChainInner {
     // Role
     /// Map of role number to point, transaction index, and role data.
     role_data: HashMap<u8, (PointTxIdx, RoleData)>,
+    /// Map of role number to all updates of its role data, in chain order.
+    role_data_history: HashMap<u8, Vec<(PointTxIdx, RoleData)>>,
     /// Map of tracked payment key to its history.
     tracking_payment_history: HashMap<ShelleyAddress, Vec<PaymentHistory>>,
 }
@@ -172,13 +330,14 @@ impl RegistrationChainInner {
     /// - `point` - The point (slot) of the transaction.
     /// - `tx_idx` - The transaction index.
     /// - `txn` - The transaction.
+    /// - `revocation_lists` - The certificate revocation lists, if any.
     ///
     /// # Errors
     ///
     /// Returns an error if data is invalid
     fn new(
         cip509: Cip509, tracking_payment_keys: &[ShelleyAddress], point: Point, tx_idx: usize,
-        txn: &MultiEraTx,
+        txn: &MultiEraTx, revocation_lists: Option<&RevocationLists>,
     ) -> anyhow::Result<Self> {
         // Should be chain root, return immediately if not
         if cip509.prv_tx_id.is_some() {
@@ -187,9 +346,12 @@ impl RegistrationChainInner {
 
         let mut validation_report = Vec::new();
         let validation_data = cip509.validate(txn, &mut validation_report);
+        let is_valid_revocation = revocation_lists.map_or(true, |lists| {
+            lists.validate(&cip509, &point, &mut validation_report)
+        });
 
         // Do the CIP509 validation, ensuring the basic validation pass.
-        if !is_valid_cip509(&validation_data) {
+        if !is_valid_cip509(&validation_data) || !is_valid_revocation {
             // Log out the error if any
             error!("CIP509 validation failed: {:?}", validation_report);
             bail!("CIP509 validation failed, {:?}", validation_report);
@@ -222,6 +384,7 @@ impl RegistrationChainInner {
             c509_certs: c509_cert_map,
             simple_keys: public_key_map,
             revocations,
+            role_data_history: role_data_history(&role_data_map),
             role_data: role_data_map,
             tracking_payment_history,
         })
@@ -234,20 +397,25 @@ impl RegistrationChainInner {
     /// - `tx_idx` - The transaction index.
     /// - `txn` - The transaction.
     /// - `cip509` - The CIP509.
+    /// - `revocation_lists` - The certificate revocation lists, if any.
     ///
     /// # Errors
     ///
     /// Returns an error if data is invalid
     fn update(
         &self, point: Point, tx_idx: usize, txn: &MultiEraTx, cip509: Cip509,
+        revocation_lists: Option<&RevocationLists>,
     ) -> anyhow::Result<Self> {
         let mut new_inner = self.clone();
 
         let mut validation_report = Vec::new();
         let validation_data = cip509.validate(txn, &mut validation_report);
+        let is_valid_revocation = revocation_lists.map_or(true, |lists| {
+            lists.validate(&cip509, &point, &mut validation_report)
+        });
 
         // Do the CIP509 validation, ensuring the basic validation pass.
-        if !is_valid_cip509(&validation_data) {
+        if !is_valid_cip509(&validation_data) || !is_valid_revocation {
             error!("CIP509 validation failed: {:?}", validation_report);
             bail!("CIP509 validation failed, {:?}", validation_report);
         }
@@ -257,7 +425,7 @@ impl RegistrationChainInner {
             // Previous transaction ID in the CIP509 should equal to the current transaction ID
             // or else it is not a part of the chain
             if prv_tx_id == self.current_tx_id_hash {
-                new_inner.current_tx_id_hash = prv_tx_id;
+                new_inner.current_tx_id_hash = txn.hash();
             } else {
                 bail!("Invalid previous transaction ID, not a part of this registration chain");
             }
@@ -474,6 +642,16 @@ fn chain_root_role_data(
     Ok(role_data_map)
 }
 
+/// Create the role data history from the current role data of each role.
+fn role_data_history(
+    role_data: &HashMap<u8, (PointTxIdx, RoleData)>,
+) -> HashMap<u8, Vec<(PointTxIdx, RoleData)>> {
+    role_data
+        .iter()
+        .map(|(role, entry)| (*role, vec![entry.clone()]))
+        .collect()
+}
+
 /// Update the role data in the registration chain.
 fn update_role_data(
     inner: &mut RegistrationChainInner, role_set: Option<Vec<cip509::rbac::role_data::RoleData>>,
@@ -504,20 +682,25 @@ fn update_role_data(
             };
             let payment_key = get_payment_addr_from_tx(txn, role_data.payment_key)?;
 
+            let new_role_data = RoleData::new(
+                signing_key,
+                encryption_key,
+                payment_key,
+                role_data.role_extended_data_keys.clone(),
+            );
+
+            // Record the update, the history keeps the older role data
+            inner
+                .role_data_history
+                .entry(role_data.role_number)
+                .or_default()
+                .push((point_tx_idx.clone(), new_role_data.clone()));
+
             // Map of role number to point and role data
             // Note that new role data will overwrite the old one
-            inner.role_data.insert(
-                role_data.role_number,
-                (
-                    point_tx_idx.clone(),
-                    RoleData::new(
-                        signing_key,
-                        encryption_key,
-                        payment_key,
-                        role_data.role_extended_data_keys.clone(),
-                    ),
-                ),
-            );
+            inner
+                .role_data
+                .insert(role_data.role_number, (point_tx_idx.clone(), new_role_data));
         }
     }
     Ok(())
@@ -692,9 +875,73 @@ mod test {
         let cip509 = Cip509::decode(&mut decoder, &mut ()).expect("Failed to decode Cip509");
 
         // Update the registration chain
-        assert!(registration_chain
-            .unwrap()
-            .update(point_4.clone(), 1, tx, cip509)
-            .is_ok());
+        let registration_chain = registration_chain.unwrap();
+        let updated_chain = registration_chain.update(point_4.clone(), 1, tx, cip509);
+        assert!(updated_chain.is_ok());
+
+        // Role data before the chain root is not known, and the chain root role data
+        // stays effective until the update.
+        let updated_chain = updated_chain.unwrap();
+        let before_update = Point::new(point_4.slot_or_default() - 1, vec![]);
+        for role in registration_chain.role_data().keys() {
+            assert!(updated_chain.role_data_at(*role, &Point::Origin).is_none());
+            assert_eq!(updated_chain.keys_at(*role, &Point::Origin), (None, None));
+            assert_eq!(
+                updated_chain.keys_at(*role, &before_update),
+                registration_chain.keys_at(*role, &point_1)
+            );
+        }
+        for (role, (_, role_data)) in updated_chain.role_data() {
+            let role_data_at = updated_chain.role_data_at(*role, &point_4).unwrap();
+            assert_eq!(role_data_at.signing_key_ref(), role_data.signing_key_ref());
+            assert_eq!(role_data_at.encryption_ref(), role_data.encryption_ref());
+        }
+    }
+
+    #[test]
+    fn test_registration_chain_cbor_round_trip() {
+        let conway_block_data_1 = conway_1();
+        let point_1 = Point::new(
+            77_429_134,
+            hex::decode("62483f96613b4c48acd28de482eb735522ac180df61766bdb476a7bf83e7bb98")
+                .unwrap(),
+        );
+        let multi_era_block_1 =
+            pallas::ledger::traverse::MultiEraBlock::decode(&conway_block_data_1)
+                .expect("Failed to decode MultiEraBlock");
+        let transactions_1 = multi_era_block_1.txs();
+        let tx_1 = transactions_1
+            .get(3)
+            .expect("Failed to get transaction index");
+        let aux_data_1 = cip_509_aux_data(tx_1);
+        let mut decoder = Decoder::new(aux_data_1.as_slice());
+        let cip509_1 = Cip509::decode(&mut decoder, &mut ()).expect("Failed to decode Cip509");
+
+        let registration_chain = RegistrationChain::new(point_1, &[], 3, tx_1, cip509_1).unwrap();
+
+        let cbor = registration_chain.to_cbor().unwrap();
+        let decoded = RegistrationChain::from_cbor(&cbor).unwrap();
+        assert_eq!(
+            decoded.current_tx_id_hash(),
+            registration_chain.current_tx_id_hash()
+        );
+        assert_eq!(decoded.purpose(), registration_chain.purpose());
+        assert_eq!(
+            decoded.x509_certs().len(),
+            registration_chain.x509_certs().len()
+        );
+        assert_eq!(
+            decoded.role_data().len(),
+            registration_chain.role_data().len()
+        );
+        // Encoding is deterministic, so the decoded chain encodes to the same bytes.
+        assert_eq!(decoded.to_cbor().unwrap(), cbor);
+
+        // Unknown versions are rejected.
+        let mut unknown_version = cbor.clone();
+        if let Some(version) = unknown_version.get_mut(1) {
+            *version = 0x03;
+        }
+        assert!(RegistrationChain::from_cbor(&unknown_version).is_err());
     }
 }
@@ -0,0 +1,438 @@
+//! Versioned CBOR serialization of the registration chain.
+//!
+//! ```cddl
+//! registration-chain = [version: uint, chain: chain-v1 / chain-v2]
+//! chain-v2 = [
+//!     current-tx-id-hash: bytes .size 32,
+//!     purpose: [* bytes .size 16],
+//!     x509-certs: { * uint => [point-tx-idx, bytes] },
+//!     c509-certs: { * uint => [point-tx-idx, bytes .cbor C509] },
+//!     simple-keys: { * uint => [point-tx-idx, bytes .size 32] },
+//!     revocations: [* [point-tx-idx, bytes .size 16]],
+//!     role-data-history: { * uint => [+ [point-tx-idx, role-data]] },
+//!     tracking-payment-history: [* [address: bytes, [* payment-history]]],
+//! ]
+//! ; chain-v1 is chain-v2 with only the current role data of each role,
+//! ; `role-data: { * uint => [point-tx-idx, role-data] }`, in place of its history.
+//! point-tx-idx = [point: null / [slot: uint, hash: bytes], tx-idx: uint]
+//! role-data = [
+//!     signing-key-ref: key-local-ref / null,
+//!     encryption-ref: key-local-ref / null,
+//!     payment-key: bytes / null,
+//!     role-extended-data: { * uint => bytes },
+//! ]
+//! key-local-ref = [local-ref: uint, key-offset: uint]
+//! payment-history = [point-tx-idx, tx-hash: bytes .size 32, output-index: uint, value: bytes]
+//! ```
+//!
+//! Maps are encoded with sorted keys, so the same chain always encodes to the same bytes.
+
+use std::collections::HashMap;
+
+use c509_certificate::c509::C509;
+use ed25519_dalek::VerifyingKey;
+use minicbor::{decode, encode::Write, Decode, Decoder, Encode, Encoder};
+use pallas::{
+    crypto::hash::Hash,
+    ledger::{
+        addresses::{Address, ShelleyAddress},
+        primitives::conway::Value,
+    },
+    network::miniprotocols::Point,
+};
+use uuid::Uuid;
+
+use super::{
+    payment_history::PaymentHistory, point_tx_idx::PointTxIdx, role_data::RoleData,
+    role_data_history, RegistrationChainInner,
+};
+use crate::{
+    cardano::cip509::{
+        rbac::role_data::{KeyLocalRef, LocalRefInt},
+        types::cert_key_hash::CertKeyHash,
+    },
+    utils::decode_helper::{decode_array_len, decode_bytes, decode_helper, decode_map_len},
+};
+
+/// Current version of the serialized registration chain.
+const REGISTRATION_CHAIN_VERSION: u64 = 2;
+
+/// Version of the serialized registration chain without the role data history.
+const REGISTRATION_CHAIN_VERSION_1: u64 = 1;
+
+/// Encode error of the writer `W`.
+type EncodeError<W> = minicbor::encode::Error<<W as Write>::Error>;
+
+impl Encode<()> for RegistrationChainInner {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>, _ctx: &mut ()) -> Result<(), EncodeError<W>> {
+        e.array(2)?.u64(REGISTRATION_CHAIN_VERSION)?;
+
+        e.array(8)?;
+        e.bytes(self.current_tx_id_hash.as_ref())?;
+
+        e.array(self.purpose.len() as u64)?;
+        for purpose in &self.purpose {
+            e.bytes(purpose.as_bytes())?;
+        }
+
+        e.map(self.x509_certs.len() as u64)?;
+        for (idx, (point_tx_idx, cert)) in sorted(&self.x509_certs) {
+            e.u64(idx as u64)?.array(2)?;
+            encode_point_tx_idx(e, point_tx_idx)?;
+            e.bytes(cert)?;
+        }
+
+        e.map(self.c509_certs.len() as u64)?;
+        for (idx, (point_tx_idx, cert)) in sorted(&self.c509_certs) {
+            e.u64(idx as u64)?.array(2)?;
+            encode_point_tx_idx(e, point_tx_idx)?;
+            let cert = minicbor::to_vec(cert).map_err(|err| {
+                minicbor::encode::Error::message(format!(
+                    "Failed to encode C509 certificate: {err}"
+                ))
+            })?;
+            e.bytes(&cert)?;
+        }
+
+        e.map(self.simple_keys.len() as u64)?;
+        for (idx, (point_tx_idx, key)) in sorted(&self.simple_keys) {
+            e.u64(idx as u64)?.array(2)?;
+            encode_point_tx_idx(e, point_tx_idx)?;
+            e.bytes(key.as_bytes())?;
+        }
+
+        e.array(self.revocations.len() as u64)?;
+        for (point_tx_idx, cert_key_hash) in &self.revocations {
+            e.array(2)?;
+            encode_point_tx_idx(e, point_tx_idx)?;
+            e.bytes(&<[u8; 16]>::from(cert_key_hash.clone()))?;
+        }
+
+        e.map(self.role_data_history.len() as u64)?;
+        for (role, history) in sorted(&self.role_data_history) {
+            e.u8(role)?.array(history.len() as u64)?;
+            for (point_tx_idx, role_data) in history {
+                e.array(2)?;
+                encode_point_tx_idx(e, point_tx_idx)?;
+                encode_role_data(e, role_data)?;
+            }
+        }
+
+        let mut tracking_payment_history: Vec<_> = self
+            .tracking_payment_history
+            .iter()
+            .map(|(address, history)| (address.to_vec(), history))
+            .collect();
+        tracking_payment_history.sort_by(|a, b| a.0.cmp(&b.0));
+        e.array(tracking_payment_history.len() as u64)?;
+        for (address, history) in tracking_payment_history {
+            e.array(2)?.bytes(&address)?.array(history.len() as u64)?;
+            for payment in history {
+                encode_payment_history(e, payment)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for RegistrationChainInner {
+    fn decode(d: &mut Decoder<'_>, ctx: &mut ()) -> Result<Self, decode::Error> {
+        decode_array_len(d, "RegistrationChain")?;
+        let version: u64 = decode_helper(d, "version in RegistrationChain", ctx)?;
+        // Older versions must keep being decoded here, so persisted chains survive upgrades.
+        match version {
+            REGISTRATION_CHAIN_VERSION_1 | REGISTRATION_CHAIN_VERSION => {
+                decode_chain(d, ctx, version)
+            },
+            _ => {
+                Err(decode::Error::message(format!(
+                    "Unsupported RegistrationChain version {version}"
+                )))
+            },
+        }
+    }
+}
+
+/// Decode the given version of the registration chain.
+fn decode_chain(
+    d: &mut Decoder, ctx: &mut (), version: u64,
+) -> Result<RegistrationChainInner, decode::Error> {
+    decode_array_len(d, "RegistrationChain")?;
+
+    let current_tx_id_hash = decode_hash(d, "current transaction ID hash")?;
+
+    let mut purpose = Vec::new();
+    for _ in 0..decode_array_len(d, "purpose")? {
+        let bytes = decode_fixed::<16>(d, "purpose")?;
+        purpose.push(Uuid::from_bytes(bytes));
+    }
+
+    let mut x509_certs = HashMap::new();
+    for _ in 0..decode_map_len(d, "x509 certificates")? {
+        let idx: usize = decode_helper(d, "index in x509 certificates", ctx)?;
+        decode_array_len(d, "x509 certificate")?;
+        let point_tx_idx = decode_point_tx_idx(d, ctx)?;
+        let cert = decode_bytes(d, "x509 certificate")?;
+        x509_certs.insert(idx, (point_tx_idx, cert));
+    }
+
+    let mut c509_certs = HashMap::new();
+    for _ in 0..decode_map_len(d, "c509 certificates")? {
+        let idx: usize = decode_helper(d, "index in c509 certificates", ctx)?;
+        decode_array_len(d, "c509 certificate")?;
+        let point_tx_idx = decode_point_tx_idx(d, ctx)?;
+        let cert: C509 = minicbor::decode(&decode_bytes(d, "c509 certificate")?)?;
+        c509_certs.insert(idx, (point_tx_idx, cert));
+    }
+
+    let mut simple_keys = HashMap::new();
+    for _ in 0..decode_map_len(d, "simple public keys")? {
+        let idx: usize = decode_helper(d, "index in simple public keys", ctx)?;
+        decode_array_len(d, "simple public key")?;
+        let point_tx_idx = decode_point_tx_idx(d, ctx)?;
+        let key = VerifyingKey::from_bytes(&decode_fixed::<32>(d, "simple public key")?)
+            .map_err(|e| decode::Error::message(format!("Invalid simple public key: {e}")))?;
+        simple_keys.insert(idx, (point_tx_idx, key));
+    }
+
+    let mut revocations = Vec::new();
+    for _ in 0..decode_array_len(d, "revocations")? {
+        decode_array_len(d, "revocation")?;
+        let point_tx_idx = decode_point_tx_idx(d, ctx)?;
+        let cert_key_hash = CertKeyHash::from(decode_fixed::<16>(d, "revocation")?);
+        revocations.push((point_tx_idx, cert_key_hash));
+    }
+
+    let (role_data, role_data_history) = if version == REGISTRATION_CHAIN_VERSION_1 {
+        let mut role_data = HashMap::new();
+        for _ in 0..decode_map_len(d, "role data")? {
+            let role: u8 = decode_helper(d, "role number in role data", ctx)?;
+            decode_array_len(d, "role data")?;
+            let point_tx_idx = decode_point_tx_idx(d, ctx)?;
+            role_data.insert(role, (point_tx_idx, decode_role_data(d, ctx)?));
+        }
+        // The history starts from the current role data.
+        let role_data_history = role_data_history(&role_data);
+        (role_data, role_data_history)
+    } else {
+        let mut role_data = HashMap::new();
+        let mut role_data_history = HashMap::new();
+        for _ in 0..decode_map_len(d, "role data history")? {
+            let role: u8 = decode_helper(d, "role number in role data history", ctx)?;
+            let mut history = Vec::new();
+            for _ in 0..decode_array_len(d, "role data history")? {
+                decode_array_len(d, "role data")?;
+                let point_tx_idx = decode_point_tx_idx(d, ctx)?;
+                history.push((point_tx_idx, decode_role_data(d, ctx)?));
+            }
+            let current = history
+                .last()
+                .cloned()
+                .ok_or(decode::Error::message(format!(
+                    "Empty role data history of role {role}"
+                )))?;
+            role_data.insert(role, current);
+            role_data_history.insert(role, history);
+        }
+        (role_data, role_data_history)
+    };
+
+    let mut tracking_payment_history = HashMap::new();
+    for _ in 0..decode_array_len(d, "tracking payment history")? {
+        decode_array_len(d, "tracking payment key")?;
+        let address = decode_shelley_address(&decode_bytes(d, "tracking payment key")?)?;
+        let mut history = Vec::new();
+        for _ in 0..decode_array_len(d, "payment history")? {
+            history.push(decode_payment_history(d, ctx)?);
+        }
+        tracking_payment_history.insert(address, history);
+    }
+
+    Ok(RegistrationChainInner {
+        current_tx_id_hash,
+        purpose,
+        x509_certs,
+        c509_certs,
+        simple_keys,
+        revocations,
+        role_data,
+        role_data_history,
+        tracking_payment_history,
+    })
+}
+
+/// The map entries, sorted by key.
+fn sorted<K: Ord + Copy, V>(map: &HashMap<K, V>) -> Vec<(K, &V)> {
+    let mut entries: Vec<_> = map.iter().map(|(k, v)| (*k, v)).collect();
+    entries.sort_by_key(|(k, _)| *k);
+    entries
+}
+
+/// Encode a point and transaction index.
+fn encode_point_tx_idx<W: Write>(
+    e: &mut Encoder<W>, point_tx_idx: &PointTxIdx,
+) -> Result<(), EncodeError<W>> {
+    e.array(2)?;
+    match point_tx_idx.point() {
+        Point::Origin => e.null()?,
+        Point::Specific(slot, hash) => e.array(2)?.u64(*slot)?.bytes(hash)?,
+    };
+    e.u64(point_tx_idx.tx_idx() as u64)?;
+    Ok(())
+}
+
+/// Decode a point and transaction index.
+fn decode_point_tx_idx(d: &mut Decoder, ctx: &mut ()) -> Result<PointTxIdx, decode::Error> {
+    decode_array_len(d, "PointTxIdx")?;
+    let point = if d.datatype()? == minicbor::data::Type::Null {
+        d.null()?;
+        Point::Origin
+    } else {
+        decode_array_len(d, "Point")?;
+        let slot: u64 = decode_helper(d, "slot in Point", ctx)?;
+        Point::Specific(slot, decode_bytes(d, "hash in Point")?)
+    };
+    let tx_idx: usize = decode_helper(d, "transaction index in PointTxIdx", ctx)?;
+    Ok(PointTxIdx::new(point, tx_idx))
+}
+
+/// Encode a key local reference, or null.
+fn encode_key_local_ref<W: Write>(
+    e: &mut Encoder<W>, key_local_ref: Option<&KeyLocalRef>,
+) -> Result<(), EncodeError<W>> {
+    match key_local_ref {
+        Some(key_local_ref) => {
+            e.array(2)?
+                .u8(key_local_ref.local_ref.clone() as u8)?
+                .u64(key_local_ref.key_offset)?;
+        },
+        None => {
+            e.null()?;
+        },
+    }
+    Ok(())
+}
+
+/// Decode a key local reference, or null.
+fn decode_key_local_ref(
+    d: &mut Decoder, ctx: &mut (),
+) -> Result<Option<KeyLocalRef>, decode::Error> {
+    if d.datatype()? == minicbor::data::Type::Null {
+        d.null()?;
+        return Ok(None);
+    }
+    decode_array_len(d, "KeyLocalRef")?;
+    let local_ref = LocalRefInt::from_repr(decode_helper(d, "LocalRef in KeyLocalRef", ctx)?)
+        .ok_or(decode::Error::message("Invalid local reference"))?;
+    let key_offset: u64 = decode_helper(d, "KeyOffset in KeyLocalRef", ctx)?;
+    Ok(Some(KeyLocalRef {
+        local_ref,
+        key_offset,
+    }))
+}
+
+/// Encode the role data.
+fn encode_role_data<W: Write>(
+    e: &mut Encoder<W>, role_data: &RoleData,
+) -> Result<(), EncodeError<W>> {
+    e.array(4)?;
+    encode_key_local_ref(e, role_data.signing_key_ref().as_ref())?;
+    encode_key_local_ref(e, role_data.encryption_ref().as_ref())?;
+    match role_data.payment_key() {
+        Some(address) => e.bytes(&address.to_vec())?,
+        None => e.null()?,
+    };
+    let extended_data = sorted(role_data.role_extended_data());
+    e.map(extended_data.len() as u64)?;
+    for (key, data) in extended_data {
+        e.u8(key)?.bytes(data)?;
+    }
+    Ok(())
+}
+
+/// Decode the role data.
+fn decode_role_data(d: &mut Decoder, ctx: &mut ()) -> Result<RoleData, decode::Error> {
+    decode_array_len(d, "RoleData")?;
+    let signing_key_ref = decode_key_local_ref(d, ctx)?;
+    let encryption_ref = decode_key_local_ref(d, ctx)?;
+    let payment_key = if d.datatype()? == minicbor::data::Type::Null {
+        d.null()?;
+        None
+    } else {
+        Some(decode_shelley_address(&decode_bytes(
+            d,
+            "payment key in RoleData",
+        )?)?)
+    };
+    let mut role_extended_data = HashMap::new();
+    for _ in 0..decode_map_len(d, "role extended data")? {
+        let key: u8 = decode_helper(d, "key in role extended data", ctx)?;
+        role_extended_data.insert(key, decode_bytes(d, "role extended data")?);
+    }
+    Ok(RoleData::new(
+        signing_key_ref,
+        encryption_ref,
+        payment_key,
+        role_extended_data,
+    ))
+}
+
+/// Encode a payment history entry.
+fn encode_payment_history<W: Write>(
+    e: &mut Encoder<W>, payment: &PaymentHistory,
+) -> Result<(), EncodeError<W>> {
+    e.array(4)?;
+    encode_point_tx_idx(e, payment.point_tx_idx())?;
+    e.bytes(payment.tx_hash().as_ref())?
+        .u16(payment.output_index())?;
+    // The value uses the ledger encoding.
+    let value = pallas::codec::minicbor::to_vec(payment.value()).map_err(|err| {
+        minicbor::encode::Error::message(format!("Failed to encode payment value: {err}"))
+    })?;
+    e.bytes(&value)?;
+    Ok(())
+}
+
+/// Decode a payment history entry.
+fn decode_payment_history(d: &mut Decoder, ctx: &mut ()) -> Result<PaymentHistory, decode::Error> {
+    decode_array_len(d, "PaymentHistory")?;
+    let point_tx_idx = decode_point_tx_idx(d, ctx)?;
+    let tx_hash = decode_hash(d, "transaction hash in PaymentHistory")?;
+    let output_index: u16 = decode_helper(d, "output index in PaymentHistory", ctx)?;
+    let value: Value =
+        pallas::codec::minicbor::decode(&decode_bytes(d, "value in PaymentHistory")?).map_err(
+            |e| decode::Error::message(format!("Failed to decode value in PaymentHistory: {e}")),
+        )?;
+    Ok(PaymentHistory::new(
+        point_tx_idx,
+        tx_hash,
+        output_index,
+        value,
+    ))
+}
+
+/// Decode a byte string of a fixed size.
+fn decode_fixed<const N: usize>(d: &mut Decoder, from: &str) -> Result<[u8; N], decode::Error> {
+    let bytes = decode_bytes(d, from)?;
+    bytes.try_into().map_err(|bytes: Vec<u8>| {
+        decode::Error::message(format!(
+            "Invalid length of {from}, expected {N} got {}",
+            bytes.len()
+        ))
+    })
+}
+
+/// Decode a 32 byte hash.
+fn decode_hash(d: &mut Decoder, from: &str) -> Result<Hash<32>, decode::Error> {
+    decode_fixed::<32>(d, from).map(Hash::new)
+}
+
+/// Decode a Shelley address from its bytes.
+fn decode_shelley_address(bytes: &[u8]) -> Result<ShelleyAddress, decode::Error> {
+    match Address::from_bytes(bytes) {
+        Ok(Address::Shelley(address)) => Ok(address),
+        Ok(_) => Err(decode::Error::message("Address is not a Shelley address")),
+        Err(e) => Err(decode::Error::message(format!("Invalid address: {e}"))),
+    }
+}
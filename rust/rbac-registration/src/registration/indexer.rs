@@ -0,0 +1,334 @@
+//! Incremental indexer of RBAC registration chains.
+//!
+//! The indexer is fed the blocks of a chain in order, for example from the
+//! `cardano-chain-follower` chain updates, and keeps an in-memory registry of every
+//! registration chain found in them:
+//!
+//! - `Kind::Block` - call `roll_forward` with the decoded block.
+//! - `Kind::Rollback` - call `roll_back` with the slot of the update block.
+//! - `Kind::ImmutableBlockRollForward` - call `set_immutable` with the slot of the update
+//!   block.
+
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::bail;
+use minicbor::{Decode, Decoder};
+use pallas::{
+    crypto::hash::Hash,
+    ledger::{
+        addresses::ShelleyAddress,
+        traverse::{MultiEraBlock, MultiEraTx},
+    },
+    network::miniprotocols::Point,
+};
+use tracing::warn;
+
+use super::cardano::{RegistrationChain, RevocationLists};
+use crate::cardano::{
+    cip509::{x509_chunks::DecompressionLimits, Cip509, LABEL},
+    transaction::raw_aux_data::RawAuxData,
+};
+
+/// A change made to the registry by a registration, kept so it can be undone.
+struct Change {
+    /// Slot of the registration.
+    slot: u64,
+    /// Transaction ID of the registration.
+    tx_id: Hash<32>,
+    /// Transaction ID of the root of the updated registration chain.
+    root: Hash<32>,
+    /// The registration chain before the registration, `None` if it was its root.
+    previous: Option<RegistrationChain>,
+}
+
+/// In-memory registry of RBAC registration chains, built incrementally from blocks.
+pub struct RbacIndexer {
+    /// The payment keys whose history is tracked in every registration chain.
+    tracking_payment_keys: Vec<ShelleyAddress>,
+    /// Registration chains, keyed by the transaction ID of their root.
+    chains: HashMap<Hash<32>, RegistrationChain>,
+    /// Transaction ID of the root of the chain of every indexed registration.
+    roots: HashMap<Hash<32>, Hash<32>>,
+    /// Changes which can still be rolled back, oldest first.
+    changes: VecDeque<Change>,
+    /// Limits applied when decompressing the registrations x509 chunks.
+    decompression_limits: DecompressionLimits,
+    /// Certificate revocation lists the registrations C509 certificates are checked
+    /// against.
+    revocation_lists: Option<RevocationLists>,
+}
+
+impl RbacIndexer {
+    /// Create a new, empty, indexer.
+    ///
+    /// # Arguments
+    /// - `tracking_payment_keys` - The list of payment keys to track in every chain.
+    #[must_use]
+    pub fn new(tracking_payment_keys: Vec<ShelleyAddress>) -> Self {
+        Self {
+            tracking_payment_keys,
+            chains: HashMap::new(),
+            roots: HashMap::new(),
+            changes: VecDeque::new(),
+            decompression_limits: DecompressionLimits::default(),
+            revocation_lists: None,
+        }
+    }
+
+    /// Set the limits applied when decompressing the registrations x509 chunks.
+    /// Registrations which exceed the limits are skipped and reported.
+    #[must_use]
+    pub fn with_decompression_limits(mut self, decompression_limits: DecompressionLimits) -> Self {
+        self.decompression_limits = decompression_limits;
+        self
+    }
+
+    /// Set the certificate revocation lists the registrations C509 certificates are
+    /// checked against. Registrations with a revoked certificate are skipped and
+    /// reported.
+    #[must_use]
+    pub fn with_revocation_lists(mut self, revocation_lists: RevocationLists) -> Self {
+        self.revocation_lists = Some(revocation_lists);
+        self
+    }
+
+    /// Index the CIP509 registrations of the next block of the chain.
+    ///
+    /// Registrations are applied in transaction order. Registrations which are invalid,
+    /// or do not continue a known chain, are skipped and reported.
+    ///
+    /// # Arguments
+    /// - `block` - The next block of the chain.
+    /// - `report` - Report of the registrations which could not be indexed.
+    pub fn roll_forward(&mut self, block: &MultiEraBlock, report: &mut Vec<String>) {
+        let point = Point::Specific(block.slot(), block.hash().to_vec());
+        let txs = block.txs();
+
+        for (tx_idx, cip509) in cip509_registrations(block, self.decompression_limits, report) {
+            let Some(txn) = txs.get(tx_idx) else {
+                report.push(format!(
+                    "Slot {}: metadata for missing transaction {tx_idx}",
+                    block.slot()
+                ));
+                continue;
+            };
+            let tx_id = txn.hash();
+
+            let result = match cip509.prv_tx_id {
+                None => {
+                    RegistrationChain::new_with_revocation_lists(
+                        point.clone(),
+                        &self.tracking_payment_keys,
+                        tx_idx,
+                        txn,
+                        cip509,
+                        self.revocation_lists.as_ref(),
+                    )
+                    .map(|chain| (tx_id, None, chain))
+                },
+                Some(prv_tx_id) => {
+                    self.update_chain(&prv_tx_id, point.clone(), tx_idx, txn, cip509)
+                },
+            };
+
+            match result {
+                Ok((root, previous, chain)) => {
+                    self.chains.insert(root, chain);
+                    self.roots.insert(tx_id, root);
+                    self.changes.push_back(Change {
+                        slot: block.slot(),
+                        tx_id,
+                        root,
+                        previous,
+                    });
+                },
+                Err(e) => {
+                    warn!("Skipping RBAC registration {tx_id}: {e}");
+                    report.push(format!("Slot {}: registration {tx_id}: {e}", block.slot()));
+                },
+            }
+        }
+    }
+
+    /// Update the registration chain holding the previous transaction of a registration.
+    ///
+    /// # Returns
+    /// The root of the chain, the chain before the update, and the updated chain.
+    fn update_chain(
+        &self, prv_tx_id: &Hash<32>, point: Point, tx_idx: usize, txn: &MultiEraTx, cip509: Cip509,
+    ) -> anyhow::Result<(Hash<32>, Option<RegistrationChain>, RegistrationChain)> {
+        let Some((root, previous)) = self
+            .roots
+            .get(prv_tx_id)
+            .and_then(|root| self.chains.get(root).map(|chain| (*root, chain)))
+        else {
+            bail!("Previous transaction {prv_tx_id} is not a known registration");
+        };
+
+        let chain = previous.update_with_revocation_lists(
+            point,
+            tx_idx,
+            txn,
+            cip509,
+            self.revocation_lists.as_ref(),
+        )?;
+        Ok((root, Some(previous.clone()), chain))
+    }
+
+    /// Roll the registry back to a slot, undoing every registration after it.
+    ///
+    /// # Arguments
+    /// - `slot` - The slot of the block rolled back to, which is kept.
+    pub fn roll_back(&mut self, slot: u64) {
+        while self.changes.back().is_some_and(|change| change.slot > slot) {
+            let Some(change) = self.changes.pop_back() else {
+                break;
+            };
+            self.roots.remove(&change.tx_id);
+            match change.previous {
+                Some(previous) => self.chains.insert(change.root, previous),
+                None => self.chains.remove(&change.root),
+            };
+        }
+    }
+
+    /// Mark every registration up to a slot as immutable, so it is never rolled back.
+    ///
+    /// # Arguments
+    /// - `slot` - The slot of the immutable tip.
+    pub fn set_immutable(&mut self, slot: u64) {
+        while self
+            .changes
+            .front()
+            .is_some_and(|change| change.slot <= slot)
+        {
+            self.changes.pop_front();
+        }
+    }
+
+    /// Get a registration chain by the transaction ID of its root.
+    #[must_use]
+    pub fn chain(&self, root: &Hash<32>) -> Option<&RegistrationChain> {
+        self.chains.get(root)
+    }
+
+    /// Get the registration chain a registration transaction is a part of.
+    #[must_use]
+    pub fn chain_by_tx(&self, tx_id: &Hash<32>) -> Option<&RegistrationChain> {
+        self.roots.get(tx_id).and_then(|root| self.chains.get(root))
+    }
+
+    /// Get all registration chains, with the transaction ID of their root.
+    pub fn chains(&self) -> impl Iterator<Item = (&Hash<32>, &RegistrationChain)> {
+        self.chains.iter()
+    }
+
+    /// Get the number of registration chains.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.chains.len()
+    }
+
+    /// Check if no registration chains have been indexed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.chains.is_empty()
+    }
+}
+
+/// Get the decoded CIP509 registrations of a block, in transaction order.
+fn cip509_registrations(
+    block: &MultiEraBlock, mut decompression_limits: DecompressionLimits, report: &mut Vec<String>,
+) -> Vec<(usize, Cip509)> {
+    let mut aux_data: Vec<(usize, &[u8])> = if let Some(alonzo_block) = block.as_alonzo() {
+        alonzo_block
+            .auxiliary_data_set
+            .iter()
+            .map(|(tx_idx, aux)| (*tx_idx as usize, aux.raw_cbor()))
+            .collect()
+    } else if let Some(babbage_block) = block.as_babbage() {
+        babbage_block
+            .auxiliary_data_set
+            .iter()
+            .map(|(tx_idx, aux)| (*tx_idx as usize, aux.raw_cbor()))
+            .collect()
+    } else if let Some(conway_block) = block.as_conway() {
+        conway_block
+            .auxiliary_data_set
+            .iter()
+            .map(|(tx_idx, aux)| (*tx_idx as usize, aux.raw_cbor()))
+            .collect()
+    } else {
+        // CIP509 is only possible from Alonzo onwards.
+        Vec::new()
+    };
+    aux_data.sort_by_key(|(tx_idx, _)| *tx_idx);
+
+    aux_data
+        .into_iter()
+        .filter_map(|(tx_idx, raw)| {
+            let metadata = RawAuxData::new(raw).get_metadata(LABEL)?;
+            let mut decoder = Decoder::new(metadata.as_slice());
+            match Cip509::decode(&mut decoder, &mut decompression_limits) {
+                Ok(cip509) => Some((tx_idx, cip509)),
+                Err(e) => {
+                    report.push(format!(
+                        "Slot {}: transaction {tx_idx}: failed to decode CIP509 metadata: {e}",
+                        block.slot()
+                    ));
+                    None
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conway_1() -> Vec<u8> {
+        hex::decode(include_str!("../test_data/cardano/conway_1.block"))
+            .expect("Failed to decode hex block.")
+    }
+
+    fn conway_4() -> Vec<u8> {
+        hex::decode(include_str!("../test_data/cardano/conway_4.block"))
+            .expect("Failed to decode hex block.")
+    }
+
+    #[test]
+    fn test_index_and_roll_back() {
+        let block_data_1 = conway_1();
+        let block_1 = MultiEraBlock::decode(&block_data_1).expect("Failed to decode block");
+        let block_data_4 = conway_4();
+        let block_4 = MultiEraBlock::decode(&block_data_4).expect("Failed to decode block");
+
+        // Forth transaction of block 1 is a chain root, updated by the second of block 4.
+        let txs_1 = block_1.txs();
+        let root = txs_1.get(3).expect("Failed to get transaction").hash();
+        let txs_4 = block_4.txs();
+        let update = txs_4.get(1).expect("Failed to get transaction").hash();
+
+        let mut indexer = RbacIndexer::new(vec![]);
+        let mut report = Vec::new();
+        indexer.roll_forward(&block_1, &mut report);
+        indexer.roll_forward(&block_4, &mut report);
+
+        assert_eq!(indexer.len(), 1);
+        let chain = indexer.chain_by_tx(&update).expect("Update is not indexed");
+        assert_eq!(chain.current_tx_id_hash(), update);
+        assert!(indexer.chain(&root).is_some());
+
+        // Rolling back block 4 restores the chain root.
+        indexer.roll_back(block_1.slot());
+        assert!(indexer.chain_by_tx(&update).is_none());
+        let chain = indexer.chain(&root).expect("Root is not indexed");
+        assert_eq!(chain.current_tx_id_hash(), root);
+
+        // Immutable registrations are never rolled back.
+        indexer.set_immutable(block_1.slot());
+        indexer.roll_back(0);
+        assert_eq!(indexer.len(), 1);
+    }
+}
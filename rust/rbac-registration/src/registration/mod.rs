@@ -1,3 +1,4 @@
 //! Registration module
 
 pub mod cardano;
+pub mod indexer;
@@ -3,6 +3,7 @@
 #![allow(missing_docs, clippy::missing_docs_in_private_items)]
 
 use std::{
+    fmt::Display,
     fs::{read_to_string, File},
     io::{Read, Write},
     path::PathBuf,
@@ -28,7 +29,7 @@ enum Cli {
     Build {
         /// Path to the document in the json format
         doc: PathBuf,
-        /// Path to the json schema (Draft 7) to validate document against it
+        /// Path to the json schema (Draft 7 or 2020-12) to validate document against it
         schema: PathBuf,
         /// Path to the output COSE file to store.
         output: PathBuf,
@@ -44,24 +45,119 @@ enum Cli {
         doc: PathBuf,
         /// Signer kid
         kid: String,
+        #[command(flatten)]
+        limits: DecodeLimits,
     },
-    /// Verifies COSE document
+    /// Verifies COSE document, reporting the result of every signature
     Verify {
-        /// Path to the public key in PEM format
+        /// Path to the public key in PEM format, or to the directory of the signers
+        /// public keys, stored as `<kid>.pem`
         pk: PathBuf,
         /// Path to the fully formed (should has at least one signature) COSE document
         doc: PathBuf,
-        /// Path to the json schema (Draft 7) to validate document against it
+        /// Path to the json schema (Draft 7 or 2020-12) to validate document against it
         schema: PathBuf,
+        #[command(flatten)]
+        limits: DecodeLimits,
     },
+    /// Validates COSE document against the Catalyst signed document rules, without
+    /// verifying the signatures
+    Validate {
+        /// Path to the COSE document
+        doc: PathBuf,
+        /// Path to the json schema (Draft 7 or 2020-12) to validate document against it
+        schema: PathBuf,
+        #[command(flatten)]
+        limits: DecodeLimits,
+    },
+    /// Prints COSE document metadata, content, signers and the problem report in the
+    /// JSON format
+    Inspect {
+        /// Path to the COSE document
+        doc: PathBuf,
+        #[command(flatten)]
+        limits: DecodeLimits,
+    },
+}
+
+/// Limits of the resources a decoded COSE document may use, so a hostile document
+/// cannot exhaust the memory.
+#[derive(clap::Args, Debug, Clone)]
+struct DecodeLimits {
+    /// Maximum size of the COSE document, in bytes
+    #[arg(long, default_value_t = 2 * 1024 * 1024)]
+    max_document_size: usize,
+    /// Maximum size of the compressed document content, in bytes
+    #[arg(long, default_value_t = 1024 * 1024)]
+    max_content_size: usize,
+    /// Maximum size of the decompressed document content, in bytes
+    #[arg(long, default_value_t = 16 * 1024 * 1024)]
+    max_decompressed_size: usize,
+    /// Maximum number of the document signatures
+    #[arg(long, default_value_t = 64)]
+    max_signatures: usize,
+    /// Maximum number of the document metadata entries of the protected header
+    #[arg(long, default_value_t = 32)]
+    max_metadata_entries: usize,
 }
 
+/// Decode limit exceeded by the COSE document.
+#[derive(Debug, Clone, PartialEq)]
+enum LimitViolation {
+    /// Document is larger than the limit, the reading is stopped at it.
+    DocumentSize { limit: usize },
+    /// Compressed content is larger than the limit.
+    ContentSize { size: usize, limit: usize },
+    /// Decompressed content is larger than the limit, the decompression is stopped at it.
+    DecompressedSize { limit: usize },
+    /// Too many signatures.
+    Signatures { count: usize, limit: usize },
+    /// Too many metadata entries.
+    MetadataEntries { count: usize, limit: usize },
+}
+
+impl Display for LimitViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DocumentSize { limit } => {
+                write!(f, "COSE document exceeds the limit of {limit} bytes")
+            },
+            Self::ContentSize { size, limit } => {
+                write!(
+                    f,
+                    "COSE content size {size} exceeds the limit of {limit} bytes"
+                )
+            },
+            Self::DecompressedSize { limit } => {
+                write!(
+                    f,
+                    "COSE decompressed content exceeds the limit of {limit} bytes"
+                )
+            },
+            Self::Signatures { count, limit } => {
+                write!(
+                    f,
+                    "COSE has {count} signatures, exceeds the limit of {limit}"
+                )
+            },
+            Self::MetadataEntries { count, limit } => {
+                write!(
+                    f,
+                    "COSE has {count} metadata entries, exceeds the limit of {limit}"
+                )
+            },
+        }
+    }
+}
+
+impl std::error::Error for LimitViolation {}
+
 const CONTENT_ENCODING_KEY: &str = "content encoding";
 const CONTENT_ENCODING_VALUE: &str = "br";
 const UUID_CBOR_TAG: u64 = 37;
 const ULID_CBOR_TAG: u64 = 32780;
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
 struct Metadata {
     r#type: uuid::Uuid,
     id: ulid::Ulid,
@@ -72,7 +168,7 @@ struct Metadata {
     section: Option<String>,
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
 #[serde(untagged)]
 enum DocumentRef {
     /// Reference to the latest document
@@ -162,17 +258,44 @@ impl Cli {
                 let empty_cose_sign = build_empty_cose_doc(compressed_doc, &json_meta);
                 store_cose_file(empty_cose_sign, &output)?;
             },
-            Self::Sign { sk, doc, kid } => {
+            Self::Sign {
+                sk,
+                doc,
+                kid,
+                limits,
+            } => {
                 let sk = load_secret_key_from_file(&sk)?;
-                let mut cose = load_cose_from_file(&doc)?;
+                let mut cose = load_cose_from_file(&doc, &limits)?;
                 add_signature_to_cose(&mut cose, &sk, kid);
                 store_cose_file(cose, &doc)?;
             },
-            Self::Verify { pk, doc, schema } => {
-                let pk = load_public_key_from_file(&pk)?;
+            Self::Verify {
+                pk,
+                doc,
+                schema,
+                limits,
+            } => {
+                let resolver = public_key_resolver(pk)?;
+                let schema = load_schema_from_file(&schema)?;
+                let cose = load_cose_from_file(&doc, &limits)?;
+                validate_cose(&cose, &resolver, &schema, &limits)?;
+            },
+            Self::Validate {
+                doc,
+                schema,
+                limits,
+            } => {
                 let schema = load_schema_from_file(&schema)?;
-                let cose = load_cose_from_file(&doc)?;
-                validate_cose(&cose, &pk, &schema)?;
+                let cose = load_cose_from_file(&doc, &limits)?;
+                validate_cose_structure(&cose, &schema, &limits)?;
+            },
+            Self::Inspect { doc, limits } => {
+                let cose_bytes = read_cose_file(&doc, &limits)?;
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&inspect_cose(&cose_bytes, &limits)?)?
+                );
+                return Ok(());
             },
         }
         println!("Done");
@@ -180,11 +303,33 @@ impl Cli {
     }
 }
 
+/// Catalyst specific string formats, validated in addition to the standard ones.
+const CATALYST_FORMATS: &[(&str, fn(&str) -> bool)] = &[
+    ("ulid", is_ulid),
+    ("uuidv4", |s| is_uuid_version(s, 4)),
+    ("uuidv7", |s| is_uuid_version(s, 7)),
+];
+
+fn is_ulid(s: &str) -> bool {
+    ulid::Ulid::from_string(s).is_ok()
+}
+
+fn is_uuid_version(s: &str, version: usize) -> bool {
+    uuid::Uuid::parse_str(s).is_ok_and(|uuid| uuid.get_version_num() == version)
+}
+
+/// Loads the json schema, its draft is taken from its `$schema` keyword, Draft 7 if it
+/// is missing. Formats are always validated, including the Catalyst specific ones.
 fn load_schema_from_file(schema_path: &PathBuf) -> anyhow::Result<jsonschema::JSONSchema> {
     let schema_file = File::open(schema_path)?;
     let schema_json = serde_json::from_reader(schema_file)?;
-    let schema = jsonschema::JSONSchema::options()
-        .with_draft(jsonschema::Draft::Draft7)
+    let mut options = jsonschema::JSONSchema::options();
+    // Since Draft 2019-09 formats are only annotations, unless asked to be validated.
+    options.should_validate_formats(true);
+    for (name, format) in CATALYST_FORMATS {
+        options.with_format(*name, *format);
+    }
+    let schema = options
         .compile(&schema_json)
         .map_err(|e| anyhow::anyhow!("{e}"))?;
     Ok(schema)
@@ -216,9 +361,25 @@ fn brotli_compress_json(doc: &serde_json::Value) -> anyhow::Result<Vec<u8>> {
     Ok(buf)
 }
 
-fn brotli_decompress_json(mut doc_bytes: &[u8]) -> anyhow::Result<serde_json::Value> {
+/// Decompresses the json document, stopping as soon as it exceeds the
+/// `max_decompressed_size`.
+fn brotli_decompress_json(
+    doc_bytes: &[u8], max_decompressed_size: usize,
+) -> anyhow::Result<serde_json::Value> {
+    /// Size of the decompressor input buffer.
+    const BUFFER_SIZE: usize = 4096;
+
+    let limit = u64::try_from(max_decompressed_size)?.saturating_add(1);
     let mut buf = Vec::new();
-    brotli::BrotliDecompress(&mut doc_bytes, &mut buf)?;
+    brotli::Decompressor::new(doc_bytes, BUFFER_SIZE)
+        .take(limit)
+        .read_to_end(&mut buf)?;
+    if buf.len() > max_decompressed_size {
+        return Err(LimitViolation::DecompressedSize {
+            limit: max_decompressed_size,
+        }
+        .into());
+    }
     let json_doc = serde_json::from_slice(&buf)?;
     Ok(json_doc)
 }
@@ -280,11 +441,34 @@ fn build_empty_cose_doc(doc_bytes: Vec<u8>, meta: &Metadata) -> coset::CoseSign
         .build()
 }
 
-fn load_cose_from_file(cose_path: &PathBuf) -> anyhow::Result<coset::CoseSign> {
-    let mut cose_file = File::open(cose_path)?;
+/// Reads the COSE document bytes, stopping as soon as they exceed the
+/// `max_document_size`.
+fn read_cose_file(cose_path: &PathBuf, limits: &DecodeLimits) -> anyhow::Result<Vec<u8>> {
+    let limit = u64::try_from(limits.max_document_size)?.saturating_add(1);
     let mut cose_file_bytes = Vec::new();
-    cose_file.read_to_end(&mut cose_file_bytes)?;
-    let cose = coset::CoseSign::from_slice(&cose_file_bytes).map_err(|e| anyhow::anyhow!("{e}"))?;
+    File::open(cose_path)?
+        .take(limit)
+        .read_to_end(&mut cose_file_bytes)?;
+    if cose_file_bytes.len() > limits.max_document_size {
+        return Err(LimitViolation::DocumentSize {
+            limit: limits.max_document_size,
+        }
+        .into());
+    }
+    Ok(cose_file_bytes)
+}
+
+/// Loads the COSE document, its decode limits are checked before it is decoded.
+fn load_cose_from_file(
+    cose_path: &PathBuf, limits: &DecodeLimits,
+) -> anyhow::Result<coset::CoseSign> {
+    let cose_file_bytes = read_cose_file(cose_path, limits)?;
+    ensure_decode_limits(&cose_file_bytes, limits)?;
+    decode_cose(&cose_file_bytes)
+}
+
+fn decode_cose(cose_bytes: &[u8]) -> anyhow::Result<coset::CoseSign> {
+    let cose = coset::CoseSign::from_slice(cose_bytes).map_err(|e| anyhow::anyhow!("{e}"))?;
     Ok(cose)
 }
 
@@ -307,6 +491,26 @@ fn load_public_key_from_file(pk_path: &PathBuf) -> anyhow::Result<ed25519_dalek:
     Ok(pk)
 }
 
+/// Resolves the public key of the signer by its `kid`.
+type PublicKeyResolver = Box<dyn Fn(&str) -> anyhow::Result<ed25519_dalek::VerifyingKey>>;
+
+/// Resolves every signer to the same public key, or to its `<kid>.pem` public key file if
+/// `pk_path` is a directory.
+fn public_key_resolver(pk_path: PathBuf) -> anyhow::Result<PublicKeyResolver> {
+    if pk_path.is_dir() {
+        return Ok(Box::new(move |kid| {
+            anyhow::ensure!(
+                !kid.is_empty() && !kid.contains(['/', '\\']),
+                "Invalid signer kid `{kid}` to resolve public key file"
+            );
+            load_public_key_from_file(&pk_path.join(format!("{kid}.pem")))
+                .map_err(|e| anyhow::anyhow!("Cannot resolve public key: {e}"))
+        }));
+    }
+    let pk = load_public_key_from_file(&pk_path)?;
+    Ok(Box::new(move |_| Ok(pk)))
+}
+
 fn add_signature_to_cose(cose: &mut coset::CoseSign, sk: &ed25519_dalek::SigningKey, kid: String) {
     let protected_header = coset::HeaderBuilder::new().key_id(kid.into_bytes());
     let mut signature = coset::CoseSignatureBuilder::new()
@@ -318,35 +522,228 @@ fn add_signature_to_cose(cose: &mut coset::CoseSign, sk: &ed25519_dalek::Signing
 }
 
 fn validate_cose(
-    cose: &coset::CoseSign, pk: &ed25519_dalek::VerifyingKey, schema: &jsonschema::JSONSchema,
+    cose: &coset::CoseSign, resolver: &PublicKeyResolver, schema: &jsonschema::JSONSchema,
+    limits: &DecodeLimits,
+) -> anyhow::Result<()> {
+    validate_cose_structure(cose, schema, limits)?;
+    anyhow::ensure!(
+        !cose.signatures.is_empty(),
+        "COSE document does not have any signatures"
+    );
+
+    let mut verification_error = String::new();
+    for (kid, res) in verify_cose_signatures(cose, resolver) {
+        match res {
+            Ok(()) => println!("Signature of `{kid}` is valid"),
+            Err(e) => verification_error.push_str(&format!("\n - Signature of `{kid}`: {e}")),
+        }
+    }
+    anyhow::ensure!(verification_error.is_empty(), "{verification_error}");
+    Ok(())
+}
+
+/// Verifies every signature with the public key of its signer, resolved by the `kid`.
+fn verify_cose_signatures(
+    cose: &coset::CoseSign, resolver: &PublicKeyResolver,
+) -> Vec<(String, anyhow::Result<()>)> {
+    cose.signatures
+        .iter()
+        .map(|sign| {
+            let kid = String::from_utf8_lossy(&sign.protected.header.key_id).to_string();
+            let res = resolver(&kid).and_then(|pk| verify_cose_signature(cose, sign, &pk));
+            (kid, res)
+        })
+        .collect()
+}
+
+fn verify_cose_signature(
+    cose: &coset::CoseSign, sign: &coset::CoseSignature, pk: &ed25519_dalek::VerifyingKey,
+) -> anyhow::Result<()> {
+    let data_to_sign = cose.tbs_data(&[], sign);
+    let signature_bytes = sign.signature.as_slice().try_into().map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid signature bytes size: expected {}, provided {}.",
+            ed25519_dalek::Signature::BYTE_SIZE,
+            sign.signature.len()
+        )
+    })?;
+    let signature = ed25519_dalek::Signature::from_bytes(signature_bytes);
+    pk.verify_strict(&data_to_sign, &signature)?;
+    Ok(())
+}
+
+/// Checks the decode limits on the encoded `COSE_Sign` document, only reading the CBOR
+/// item heads, so nothing is allocated for the parts exceeding them:
+/// ```cddl
+/// COSE_Sign = [ protected: bstr, unprotected: header_map, payload: bstr / nil,
+///               signatures: [+ COSE_Signature] ]
+/// ```
+fn check_decode_limits(
+    cose_bytes: &[u8], limits: &DecodeLimits,
+) -> anyhow::Result<Vec<LimitViolation>> {
+    let mut violations = Vec::new();
+    let mut d = minicbor::Decoder::new(cose_bytes);
+    if d.datatype()? == minicbor::data::Type::Tag {
+        d.tag()?;
+    }
+    anyhow::ensure!(
+        d.array()? == Some(4),
+        "Invalid COSE document, must be an array of 4 items"
+    );
+
+    let protected = d.bytes()?;
+    if !protected.is_empty() {
+        let metadata_entries = minicbor::Decoder::new(protected).map()?;
+        if let Some(count) = metadata_entries.and_then(|count| usize::try_from(count).ok()) {
+            if count > limits.max_metadata_entries {
+                violations.push(LimitViolation::MetadataEntries {
+                    count,
+                    limit: limits.max_metadata_entries,
+                });
+            }
+        }
+    }
+    d.skip()?;
+
+    if d.datatype()? == minicbor::data::Type::Null {
+        d.skip()?;
+    } else {
+        let content_size = d.bytes()?.len();
+        if content_size > limits.max_content_size {
+            violations.push(LimitViolation::ContentSize {
+                size: content_size,
+                limit: limits.max_content_size,
+            });
+        }
+    }
+
+    if let Some(count) = d.array()?.and_then(|count| usize::try_from(count).ok()) {
+        if count > limits.max_signatures {
+            violations.push(LimitViolation::Signatures {
+                count,
+                limit: limits.max_signatures,
+            });
+        }
+    }
+    Ok(violations)
+}
+
+fn ensure_decode_limits(cose_bytes: &[u8], limits: &DecodeLimits) -> anyhow::Result<()> {
+    let mut limits_error = String::new();
+    for violation in check_decode_limits(cose_bytes, limits)? {
+        limits_error.push_str(&format!("\n - {violation}"));
+    }
+    anyhow::ensure!(limits_error.is_empty(), "{limits_error}");
+    Ok(())
+}
+
+fn validate_cose_structure(
+    cose: &coset::CoseSign, schema: &jsonschema::JSONSchema, limits: &DecodeLimits,
 ) -> anyhow::Result<()> {
     validate_cose_protected_header(cose)?;
+    let json_doc = decode_cose_content(cose, limits)?;
+    validate_json(&json_doc, schema)?;
+    validate_cose_signatures_kid(cose)
+}
 
+fn decode_cose_content(
+    cose: &coset::CoseSign, limits: &DecodeLimits,
+) -> anyhow::Result<serde_json::Value> {
     let Some(payload) = &cose.payload else {
         anyhow::bail!("COSE missing payload field with the JSON content in it");
     };
-    let json_doc = brotli_decompress_json(payload.as_slice())?;
-    validate_json(&json_doc, schema)?;
+    brotli_decompress_json(payload.as_slice(), limits.max_decompressed_size)
+}
 
+fn validate_cose_signatures_kid(cose: &coset::CoseSign) -> anyhow::Result<()> {
     for sign in &cose.signatures {
         anyhow::ensure!(
             !sign.protected.header.key_id.is_empty(),
             "COSE missing signature protected header `kid` field "
         );
+    }
+    Ok(())
+}
 
-        let data_to_sign = cose.tbs_data(&[], sign);
-        let signature_bytes = sign.signature.as_slice().try_into().map_err(|_| {
-            anyhow::anyhow!(
-                "Invalid signature bytes size: expected {}, provided {}.",
-                ed25519_dalek::Signature::BYTE_SIZE,
-                sign.signature.len()
-            )
-        })?;
-        let signature = ed25519_dalek::Signature::from_bytes(signature_bytes);
-        pk.verify_strict(&data_to_sign, &signature)?;
+#[derive(serde::Serialize)]
+struct Inspection {
+    metadata: Option<Metadata>,
+    content: Option<serde_json::Value>,
+    signers: Vec<String>,
+    problems: Vec<String>,
+}
+
+/// Inspects the document, the exceeded decode limits are reported as problems and the
+/// document is not decoded.
+fn inspect_cose(cose_bytes: &[u8], limits: &DecodeLimits) -> anyhow::Result<Inspection> {
+    let violations = check_decode_limits(cose_bytes, limits)?;
+    if !violations.is_empty() {
+        return Ok(Inspection {
+            metadata: None,
+            content: None,
+            signers: Vec::new(),
+            problems: violations.iter().map(ToString::to_string).collect(),
+        });
     }
 
-    Ok(())
+    let cose = decode_cose(cose_bytes)?;
+    let mut problems = Vec::new();
+    let metadata = validate_cose_protected_header(&cose)
+        .and_then(|()| decode_cose_metadata(&cose))
+        .map_err(|e| problems.push(e.to_string()))
+        .ok();
+    let content = decode_cose_content(&cose, limits)
+        .map_err(|e| problems.push(e.to_string()))
+        .ok();
+    if let Err(e) = validate_cose_signatures_kid(&cose) {
+        problems.push(e.to_string());
+    }
+    let signers = cose
+        .signatures
+        .iter()
+        .map(|sign| String::from_utf8_lossy(&sign.protected.header.key_id).to_string())
+        .collect();
+    Ok(Inspection {
+        metadata,
+        content,
+        signers,
+        problems,
+    })
+}
+
+fn find_cose_protected_header_field<'a>(
+    cose: &'a coset::CoseSign, name: &str,
+) -> Option<&'a coset::cbor::Value> {
+    cose.protected
+        .header
+        .rest
+        .iter()
+        .find(|(key, _)| key == &coset::Label::Text(name.to_string()))
+        .map(|(_, value)| value)
+}
+
+fn decode_cose_metadata(cose: &coset::CoseSign) -> anyhow::Result<Metadata> {
+    let required = |name: &str| {
+        find_cose_protected_header_field(cose, name).ok_or(anyhow::anyhow!(
+            "Invalid COSE protected header, missing `{name}` field"
+        ))
+    };
+    let document_ref = |name: &str| {
+        find_cose_protected_header_field(cose, name)
+            .map(decode_cbor_document_ref)
+            .transpose()
+    };
+    Ok(Metadata {
+        r#type: decode_cbor_uuid(required("type")?)?,
+        id: decode_cbor_ulid(required("id")?)?,
+        ver: decode_cbor_ulid(required("ver")?)?,
+        r#ref: document_ref("ref")?,
+        template: document_ref("template")?,
+        reply: document_ref("reply")?,
+        section: find_cose_protected_header_field(cose, "section")
+            .and_then(|value| value.as_text())
+            .map(ToString::to_string),
+    })
 }
 
 fn validate_cose_protected_header(cose: &coset::CoseSign) -> anyhow::Result<()> {
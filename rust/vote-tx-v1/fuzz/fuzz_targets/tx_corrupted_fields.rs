@@ -0,0 +1,48 @@
+//! Corrupts the fields of the valid transactions, and decodes them in both modes.
+
+#![no_main]
+
+use std::sync::LazyLock;
+
+use catalyst_voting::{
+    crypto::{ed25519::PrivateKey, rng::test_rng},
+    vote_protocol::committee::ElectionSecretKey,
+};
+use libfuzzer_sys::fuzz_target;
+use vote_tx_v1::{DecodeMode, Tx};
+
+/// Encoded public and private transactions.
+static TXS: LazyLock<[Vec<u8>; 2]> = LazyLock::new(|| {
+    let mut rng = test_rng(0);
+    let users_private_key = PrivateKey::random(&mut rng);
+    let election_public_key = ElectionSecretKey::random(&mut rng).public_key();
+    let public =
+        Tx::new_public([0u8; 32], 0, 3, 1, &users_private_key).expect("Failed to create public tx");
+    let private = Tx::new_private(
+        [0u8; 32],
+        0,
+        3,
+        1,
+        &election_public_key,
+        &users_private_key,
+        &mut rng,
+    )
+    .expect("Failed to create private tx");
+    [public.to_bytes(), private.to_bytes()]
+});
+
+fuzz_target!(|input: (bool, Vec<(u16, u8)>)| {
+    let (private, corruptions) = input;
+    let mut bytes = TXS[usize::from(private)].clone();
+    for (index, byte) in corruptions {
+        let len = bytes.len();
+        bytes[usize::from(index) % len] = byte;
+    }
+
+    let strict = Tx::from_bytes(&mut bytes.as_slice());
+    let lenient = Tx::from_bytes_with_mode(&mut bytes.as_slice(), DecodeMode::Lenient);
+    if let Ok(strict) = strict {
+        let (tx, _) = lenient.expect("Rejected in the lenient mode");
+        assert_eq!(tx, strict);
+    }
+});
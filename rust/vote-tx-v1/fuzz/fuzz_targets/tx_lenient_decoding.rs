@@ -0,0 +1,24 @@
+//! Decodes arbitrary bytes as a transaction in the lenient mode.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vote_tx_v1::{DecodeMode, Tx};
+
+fuzz_target!(|data: &[u8]| {
+    let lenient = Tx::from_bytes_with_mode(&mut &data[..], DecodeMode::Lenient);
+    if let Ok(strict) = Tx::from_bytes(&mut &data[..]) {
+        // Everything accepted in the strict mode is accepted in the lenient mode too.
+        let (tx, _) = lenient.as_ref().expect("Rejected in the lenient mode");
+        assert_eq!(tx, &strict);
+    }
+    if let Ok((tx, _)) = lenient {
+        // Recovered transactions are encoded without the issues.
+        let bytes = tx.to_bytes();
+        let (decoded, report) =
+            Tx::from_bytes_with_mode(&mut bytes.as_slice(), DecodeMode::Lenient)
+                .expect("Failed to decode the encoded transaction");
+        assert_eq!(decoded, tx);
+        assert!(!report.is_problematic());
+    }
+});
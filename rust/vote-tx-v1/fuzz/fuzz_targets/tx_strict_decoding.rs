@@ -0,0 +1,14 @@
+//! Decodes arbitrary bytes as a transaction in the strict mode.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vote_tx_v1::Tx;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(tx) = Tx::from_bytes(&mut &data[..]) {
+        // A decoded transaction is encoded back to the canonical bytes.
+        let bytes = tx.to_bytes();
+        assert_eq!(Tx::from_bytes(&mut bytes.as_slice()).ok(), Some(tx));
+    }
+});
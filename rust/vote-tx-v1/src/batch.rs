@@ -0,0 +1,158 @@
+//! Batch verification of private vote transactions.
+//!
+//! Tally jobs verify the voter proofs of every private vote transaction, checking them
+//! one by one is costly. [`verify_proofs_batch`] combines the proofs into a single
+//! multi-scalar multiplication, and falls back to the per transaction verification only
+//! to locate the invalid transactions.
+//!
+//! With the `rayon` feature enabled, transactions are split into chunks verified in
+//! parallel.
+
+use catalyst_voting::{
+    crypto::rng::default_rng,
+    vote_protocol::{committee::ElectionPublicKey, voter::proof::verify_voter_proofs_batch},
+};
+#[cfg(feature = "rayon")]
+use rayon::{
+    current_num_threads,
+    iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator},
+    slice::ParallelSlice,
+};
+
+use crate::{proof_commitment, Tx, TxError, VotePayload};
+
+/// Verifies the proofs of the provided transactions.
+/// Public vote transactions are skipped, the same way as [`Tx::verify_proof`] does.
+///
+/// # Errors
+///   - `TxError::InvalidProofs` with the indices of the transactions with invalid proofs.
+pub fn verify_proofs_batch(
+    txs: &[Tx], election_public_key: &ElectionPublicKey,
+) -> Result<(), TxError> {
+    if verify_chunks(txs, election_public_key) {
+        return Ok(());
+    }
+
+    let invalid = find_invalid(txs, election_public_key);
+    if invalid.is_empty() {
+        Ok(())
+    } else {
+        Err(TxError::InvalidProofs(invalid))
+    }
+}
+
+/// Batch verifies the transactions, returns `true` if all proofs are valid.
+#[cfg(not(feature = "rayon"))]
+fn verify_chunks(txs: &[Tx], election_public_key: &ElectionPublicKey) -> bool {
+    verify_chunk(txs, election_public_key)
+}
+
+/// Batch verifies the transactions, one chunk per thread, returns `true` if all proofs
+/// are valid.
+#[cfg(feature = "rayon")]
+fn verify_chunks(txs: &[Tx], election_public_key: &ElectionPublicKey) -> bool {
+    let chunk_size = txs.len().div_ceil(current_num_threads()).max(1);
+    txs.par_chunks(chunk_size)
+        .all(|chunk| verify_chunk(chunk, election_public_key))
+}
+
+/// Batch verifies a single chunk of transactions, returns `true` if all proofs are
+/// valid.
+fn verify_chunk(txs: &[Tx], election_public_key: &ElectionPublicKey) -> bool {
+    let private_votes: Vec<_> = txs
+        .iter()
+        .filter_map(|tx| {
+            if let VotePayload::Private(encrypted_vote, proof) = &tx.vote {
+                Some((encrypted_vote, proof_commitment(&tx.vote_plan_id), proof))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    verify_voter_proofs_batch(
+        private_votes
+            .iter()
+            .map(|(encrypted_vote, commitment, proof)| (*encrypted_vote, commitment, *proof)),
+        election_public_key,
+        &mut default_rng(),
+    )
+}
+
+/// Verifies the transactions one by one, returns indices of the invalid ones.
+#[cfg(not(feature = "rayon"))]
+fn find_invalid(txs: &[Tx], election_public_key: &ElectionPublicKey) -> Vec<usize> {
+    txs.iter()
+        .enumerate()
+        .filter(|(_, tx)| tx.verify_proof(election_public_key).is_err())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Verifies the transactions one by one in parallel, returns indices of the invalid
+/// ones.
+#[cfg(feature = "rayon")]
+fn find_invalid(txs: &[Tx], election_public_key: &ElectionPublicKey) -> Vec<usize> {
+    txs.par_iter()
+        .enumerate()
+        .filter(|(_, tx)| tx.verify_proof(election_public_key).is_err())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use catalyst_voting::{
+        crypto::{ed25519::PrivateKey, rng::test_rng},
+        vote_protocol::committee::ElectionSecretKey,
+    };
+
+    use super::*;
+
+    #[test]
+    fn verify_proofs_batch_test() {
+        let mut rng = test_rng(0);
+        let users_private_key = PrivateKey::random(&mut rng);
+        let election_public_key = ElectionSecretKey::random(&mut rng).public_key();
+        let other_election_public_key = ElectionSecretKey::random(&mut rng).public_key();
+        let voting_options = 3;
+
+        let mut txs: Vec<_> = [0, 1, 2, 1]
+            .iter()
+            .enumerate()
+            .map(|(i, choice)| {
+                Tx::new_private(
+                    [i.try_into().unwrap(); 32],
+                    0,
+                    voting_options,
+                    *choice,
+                    &election_public_key,
+                    &users_private_key,
+                    &mut rng,
+                )
+                .unwrap()
+            })
+            .collect();
+        txs.push(Tx::new_public([0u8; 32], 0, voting_options, 1, &users_private_key).unwrap());
+
+        verify_proofs_batch(&txs, &election_public_key).unwrap();
+        verify_proofs_batch(&[], &election_public_key).unwrap();
+
+        txs.push(
+            Tx::new_private(
+                [0u8; 32],
+                0,
+                voting_options,
+                0,
+                &other_election_public_key,
+                &users_private_key,
+                &mut rng,
+            )
+            .unwrap(),
+        );
+        assert!(matches!(
+            verify_proofs_batch(&txs, &election_public_key),
+            Err(TxError::InvalidProofs(invalid)) if invalid == vec![5]
+        ));
+    }
+}
@@ -0,0 +1,252 @@
+//! Streaming decoding of concatenated v1 transactions.
+//!
+//! Jörmungandr fragment logs and mempool dumps are plain concatenations of the size
+//! prefixed transactions. [`TxBatchDecoder`] reads them one by one, a corrupt
+//! transaction is reported and skipped, so a single bad entry does not abort the whole
+//! dump. Only a broken size prefix, e.g. a truncated dump, stops the decoding.
+
+use std::io::Read;
+
+use anyhow::{anyhow, ensure};
+
+use crate::{decoding::MAX_TX_SIZE, Tx, TxError};
+
+/// Size of the transaction size prefix.
+const TX_SIZE_PREFIX: usize = 4;
+
+/// A single decoded entry.
+/// Holds a byte offset of the entry inside the stream and the decoded transaction, or
+/// an error if the entry is corrupted.
+pub type DecodedEntry = (u64, Result<Tx, TxError>);
+
+/// Summary statistics of the decoded entries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TxBatchStats {
+    /// Number of successfully decoded public vote transactions.
+    pub public: usize,
+    /// Number of successfully decoded private vote transactions.
+    pub private: usize,
+    /// Number of corrupted entries.
+    pub corrupted: usize,
+    /// Number of bytes consumed from the stream.
+    pub bytes: u64,
+}
+
+impl TxBatchStats {
+    /// Returns the total number of entries.
+    #[must_use]
+    pub fn entries(&self) -> usize {
+        self.public + self.private + self.corrupted
+    }
+}
+
+/// Streaming decoder of the concatenated v1 transactions, yields a [`DecodedEntry`] per
+/// transaction.
+pub struct TxBatchDecoder<R> {
+    /// Underlying reader.
+    reader: R,
+    /// Statistics of the already decoded entries.
+    stats: TxBatchStats,
+    /// The stream ended or cannot be decoded any further.
+    finished: bool,
+}
+
+impl<R: Read> TxBatchDecoder<R> {
+    /// Creates a new `TxBatchDecoder` over the provided reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            stats: TxBatchStats::default(),
+            finished: false,
+        }
+    }
+
+    /// Returns the statistics of the entries decoded so far.
+    #[must_use]
+    pub fn stats(&self) -> &TxBatchStats {
+        &self.stats
+    }
+
+    /// Reads the next size prefixed entry, including its size prefix.
+    /// Returns `None` if the stream ends right at the entry boundary.
+    fn read_entry(&mut self) -> anyhow::Result<Option<Vec<u8>>> {
+        let mut size = [0u8; TX_SIZE_PREFIX];
+        let read = read_up_to(&mut self.reader, &mut size)?;
+        if read == 0 {
+            return Ok(None);
+        }
+        self.stats.bytes += u64::try_from(read)?;
+        ensure!(
+            read == size.len(),
+            "Truncated tx size field, read {read} bytes."
+        );
+
+        let size = u32::from_be_bytes(size);
+        ensure!(
+            size <= MAX_TX_SIZE,
+            "Invalid tx size {size}, exceeds the maximum of {MAX_TX_SIZE} bytes."
+        );
+        let mut entry = size.to_be_bytes().to_vec();
+        entry.resize(TX_SIZE_PREFIX + usize::try_from(size)?, 0);
+        let (_, body) = entry.split_at_mut(TX_SIZE_PREFIX);
+        let read = read_up_to(&mut self.reader, body)?;
+        self.stats.bytes += u64::try_from(read)?;
+        ensure!(
+            read == body.len(),
+            "Truncated tx, expected {size} bytes, read {read} bytes."
+        );
+        Ok(Some(entry))
+    }
+}
+
+impl<R: Read> Iterator for TxBatchDecoder<R> {
+    type Item = DecodedEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let offset = self.stats.bytes;
+        let res = match self.read_entry() {
+            Ok(Some(entry)) => decode_entry(&entry),
+            Ok(None) => {
+                self.finished = true;
+                return None;
+            },
+            Err(e) => {
+                self.finished = true;
+                Err(TxError::Decoding(e))
+            },
+        };
+        match &res {
+            Ok(tx) if tx.is_public() => self.stats.public += 1,
+            Ok(_) => self.stats.private += 1,
+            Err(_) => self.stats.corrupted += 1,
+        }
+        Some((offset, res))
+    }
+}
+
+/// Decodes a single entry, which must be fully consumed by the transaction.
+fn decode_entry(entry: &[u8]) -> Result<Tx, TxError> {
+    let mut reader = entry;
+    let tx = Tx::from_bytes(&mut reader)?;
+    if reader.is_empty() {
+        Ok(tx)
+    } else {
+        Err(TxError::Decoding(anyhow!(
+            "Unexpected {} trailing bytes after the tx.",
+            reader.len()
+        )))
+    }
+}
+
+/// Reads into the `buf` until it is full or the reader ends, returns the number of read
+/// bytes.
+fn read_up_to<R: Read>(reader: &mut R, buf: &mut [u8]) -> anyhow::Result<usize> {
+    let mut read = 0;
+    while let Some(rest) = buf.get_mut(read..) {
+        if rest.is_empty() {
+            break;
+        }
+        match reader.read(rest) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {},
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(read)
+}
+
+#[cfg(test)]
+mod tests {
+    use catalyst_voting::{
+        crypto::{ed25519::PrivateKey, rng::test_rng},
+        vote_protocol::committee::ElectionSecretKey,
+    };
+
+    use super::*;
+
+    #[test]
+    fn tx_batch_decoder_test() {
+        let mut rng = test_rng(0);
+        let users_private_key = PrivateKey::random(&mut rng);
+        let election_public_key = ElectionSecretKey::random(&mut rng).public_key();
+
+        let public_tx = Tx::new_public([1u8; 32], 0, 3, 1, &users_private_key).unwrap();
+        let private_tx = Tx::new_private(
+            [2u8; 32],
+            1,
+            3,
+            2,
+            &election_public_key,
+            &users_private_key,
+            &mut rng,
+        )
+        .unwrap();
+
+        // Corrupted entry with a valid size prefix, but an invalid padding tag.
+        let mut corrupted = public_tx.to_bytes();
+        if let Some(padding_tag) = corrupted.get_mut(4) {
+            *padding_tag = 1;
+        }
+
+        let mut stream = Vec::new();
+        let mut offsets = Vec::new();
+        for bytes in [
+            public_tx.to_bytes(),
+            corrupted,
+            private_tx.to_bytes(),
+            public_tx.to_bytes(),
+            // Truncated last entry.
+            private_tx.to_bytes().split_at(100).0.to_vec(),
+        ] {
+            offsets.push(u64::try_from(stream.len()).unwrap());
+            stream.extend(bytes);
+        }
+
+        let mut decoder = TxBatchDecoder::new(stream.as_slice());
+        let entries: Vec<_> = decoder.by_ref().collect();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|(offset, _)| *offset)
+                .collect::<Vec<_>>(),
+            offsets
+        );
+        let txs: Vec<_> = entries.into_iter().map(|(_, tx)| tx.ok()).collect();
+        assert_eq!(txs, vec![
+            Some(public_tx.clone()),
+            None,
+            Some(private_tx),
+            Some(public_tx),
+            None
+        ]);
+
+        assert_eq!(decoder.stats(), &TxBatchStats {
+            public: 2,
+            private: 1,
+            corrupted: 2,
+            bytes: u64::try_from(stream.len()).unwrap(),
+        });
+        assert_eq!(decoder.stats().entries(), 5);
+        assert!(decoder.next().is_none());
+    }
+
+    #[test]
+    fn tx_batch_decoder_empty_test() {
+        let mut decoder = TxBatchDecoder::new([].as_slice());
+        assert!(decoder.next().is_none());
+        assert_eq!(decoder.stats(), &TxBatchStats::default());
+
+        // Size prefix over the maximum stops the decoding.
+        let stream = (MAX_TX_SIZE + 1).to_be_bytes();
+        let mut decoder = TxBatchDecoder::new(stream.as_slice());
+        assert!(matches!(
+            decoder.next(),
+            Some((0, Err(TxError::Decoding(_))))
+        ));
+        assert!(decoder.next().is_none());
+    }
+}
@@ -1,14 +1,15 @@
 //! V1 transaction objects decoding implementation.
 //! <https://input-output-hk.github.io/catalyst-libs/architecture/08_concepts/catalyst_voting/abnf/jorm.abnf>
 
-use std::io::Read;
+use std::io::{self, Read};
 
 use anyhow::{anyhow, bail, ensure};
 use catalyst_voting::crypto::ed25519::{PublicKey, Signature};
 
 use crate::{
+    problem_report::{ProblemKind, ProblemReport},
     utils::{read_array, read_be_u32, read_be_u64, read_be_u8},
-    EncryptedVote, Tx, VotePayload, VoterProof,
+    EncryptedVote, Tx, TxError, VotePayload, VoterProof,
 };
 
 /// Jörmungandr tx fragment tag.
@@ -27,6 +28,37 @@ const PRIVATE_VOTE_TAG: u8 = 2;
 const PUBLIC_VOTE_TAG: u8 = 1;
 /// Jörmungandr tx witness tag.
 const WITNESS_TAG: u8 = 2;
+/// Size of the tx size field.
+const TX_SIZE_FIELD: u64 = 4;
+/// Maximum size of the single transaction, larger tx size fields are corrupted.
+pub(crate) const MAX_TX_SIZE: u32 = 1024 * 1024;
+
+/// Transaction decoding mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecodeMode {
+    /// Reject any unexpected tag value.
+    #[default]
+    Strict,
+    /// Recover from the issues which do not affect the transaction layout, e.g. unknown
+    /// padding or witness tags and extra bytes, reporting them in a [`ProblemReport`].
+    Lenient,
+}
+
+/// Reader counting the bytes read, to locate the decoded fields.
+struct CountingReader<R> {
+    /// Underlying reader.
+    reader: R,
+    /// Number of bytes read so far.
+    position: u64,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        self.position = self.position.saturating_add(read as u64);
+        Ok(read)
+    }
+}
 
 impl Tx {
     /// Write the bytes of the `Tx` body to provided `buf`.
@@ -105,17 +137,58 @@ impl Tx {
     ///   - Invalid voter proof.
     ///   - Invalid vote tag value.
     ///   - Invalid public key.
-    #[allow(clippy::indexing_slicing)]
-    pub fn from_bytes<R: Read>(reader: &mut R) -> anyhow::Result<Self> {
-        // Skip tx size field
-        read_be_u32(reader).map_err(|_| anyhow!("Missing tx size field."))?;
+    pub fn from_bytes<R: Read>(reader: &mut R) -> Result<Self, TxError> {
+        Self::from_bytes_with_mode(reader, DecodeMode::Strict).map(|(tx, _)| tx)
+    }
 
+    /// Attempt to construct a `Tx` from a byte representation, in the provided decoding
+    /// mode.
+    ///
+    /// In the [`DecodeMode::Lenient`] mode the issues which do not affect the transaction
+    /// layout are recovered from, and are returned in the `ProblemReport`. The extra
+    /// bytes covered by the tx size field are consumed, as much as available, unless the
+    /// field exceeds the maximum tx size, so a corrupted size field can not swallow the
+    /// data following the transaction. In the [`DecodeMode::Strict`] mode the report is
+    /// always empty.
+    ///
+    /// # Errors
+    ///   - Invalid padding tag field value, in the strict mode.
+    ///   - Invalid fragment tag field value.
+    ///   - Invalid encrypted vote.
+    ///   - Invalid voter proof.
+    ///   - Invalid vote tag value.
+    ///   - Invalid public key.
+    pub fn from_bytes_with_mode<R: Read>(
+        reader: &mut R, mode: DecodeMode,
+    ) -> Result<(Self, ProblemReport), TxError> {
+        let mut reader = CountingReader {
+            reader,
+            position: 0,
+        };
+        let mut report = ProblemReport::new();
+        let tx = Self::decode(&mut reader, mode, &mut report).map_err(TxError::Decoding)?;
+        Ok((tx, report))
+    }
+
+    /// Decode a `Tx` from a byte representation.
+    #[allow(clippy::indexing_slicing, clippy::too_many_lines)]
+    fn decode<R: Read>(
+        reader: &mut CountingReader<R>, mode: DecodeMode, report: &mut ProblemReport,
+    ) -> anyhow::Result<Self> {
+        let lenient = mode == DecodeMode::Lenient;
+
+        let size = read_be_u32(reader).map_err(|_| anyhow!("Missing tx size field."))?;
+
+        let offset = reader.position;
         let padding_tag = read_be_u8(reader).map_err(|_| anyhow!("Missing padding tag field."))?;
-        ensure!(
-            padding_tag == PADDING_TAG,
-            "Invalid padding tag field value, must be equals to {PADDING_TAG}, \
-            provided: {padding_tag}.",
-        );
+        if padding_tag != PADDING_TAG {
+            ensure!(
+                lenient,
+                "Invalid padding tag field value, must be equals to {PADDING_TAG}, \
+                provided: {padding_tag}.",
+            );
+            report.add(offset, ProblemKind::UnknownPaddingTag(padding_tag));
+        }
 
         let fragment_tag =
             read_be_u8(reader).map_err(|_| anyhow!("Missing fragment tag field."))?;
@@ -158,7 +231,11 @@ impl Tx {
         };
 
         // skip block date (epoch and slot)
-        read_be_u64(reader).map_err(|_| anyhow!("Missing block date field."))?;
+        let offset = reader.position;
+        let block_date = read_be_u64(reader).map_err(|_| anyhow!("Missing block date field."))?;
+        if lenient && block_date != 0 {
+            report.add(offset, ProblemKind::NonZeroBlockDate(block_date));
+        }
 
         let inputs_amount =
             read_be_u8(reader).map_err(|_| anyhow!("Missing inputs amount field."))?;
@@ -184,27 +261,62 @@ impl Tx {
         );
 
         // skip value
-        read_be_u64(reader).map_err(|_| anyhow!("Missing value field."))?;
+        let offset = reader.position;
+        let value = read_be_u64(reader).map_err(|_| anyhow!("Missing value field."))?;
+        if lenient && value != 0 {
+            report.add(offset, ProblemKind::NonZeroValue(value));
+        }
 
         let public_key_bytes =
             read_array(reader).map_err(|_| anyhow!("Missing public_key field."))?;
         let public_key = PublicKey::from_bytes(&public_key_bytes)
             .map_err(|e| anyhow!("Invalid public key, error: {e}."))?;
 
+        let offset = reader.position;
         let witness_tag = read_be_u8(reader).map_err(|_| anyhow!("Missing witness tag field."))?;
-        ensure!(
-            witness_tag == WITNESS_TAG,
-            "Invalid witness tag, expected: {WITNESS_TAG}, \
-            provided: {witness_tag}",
-        );
+        if witness_tag != WITNESS_TAG {
+            ensure!(
+                lenient,
+                "Invalid witness tag, expected: {WITNESS_TAG}, \
+                provided: {witness_tag}",
+            );
+            report.add(offset, ProblemKind::UnknownWitnessTag(witness_tag));
+        }
 
         // Skip nonce field
-        read_be_u32(reader).map_err(|_| anyhow!("Missing nonce field."))?;
+        let offset = reader.position;
+        let nonce = read_be_u32(reader).map_err(|_| anyhow!("Missing nonce field."))?;
+        if lenient && nonce != 0 {
+            report.add(offset, ProblemKind::NonZeroNonce(nonce));
+        }
 
         let signature_bytes =
             read_array(reader).map_err(|_| anyhow!("Missing signature field."))?;
         let signature = Signature::from_bytes(&signature_bytes);
 
+        if lenient {
+            // Consume the extra bytes covered by the tx size field, as much as available.
+            // An oversized tx size field is corrupted, so none are consumed.
+            let offset = reader.position;
+            let expected = u64::from(size);
+            let decoded = offset.saturating_sub(TX_SIZE_FIELD);
+            let extra = if size > MAX_TX_SIZE {
+                0
+            } else {
+                io::copy(
+                    &mut reader.by_ref().take(expected.saturating_sub(decoded)),
+                    &mut io::sink(),
+                )?
+            };
+            if extra > 0 {
+                report.add(offset, ProblemKind::ExtraBytes(extra));
+            }
+            let actual = decoded.saturating_add(extra);
+            if actual != expected {
+                report.add(0, ProblemKind::SizeMismatch { expected, actual });
+            }
+        }
+
         Ok(Self {
             vote_plan_id,
             proposal_index,
@@ -218,19 +330,20 @@ impl Tx {
 #[cfg(test)]
 mod tests {
     use catalyst_voting::{
-        crypto::{ed25519::PrivateKey, rng::rand_core::OsRng},
+        crypto::{ed25519::PrivateKey, rng::test_rng},
         vote_protocol::committee::ElectionSecretKey,
     };
     use test_strategy::proptest;
 
     use super::*;
+    use crate::Problem;
 
     #[proptest]
     fn tx_public_to_bytes_from_bytes_test(
         vote_plan_id: [u8; 32], proposal_index: u8, #[strategy(1u8..5)] voting_options: u8,
-        #[strategy(0..#voting_options)] choice: u8,
+        #[strategy(0..#voting_options)] choice: u8, seed: u64,
     ) {
-        let mut rng = OsRng;
+        let mut rng = test_rng(seed);
         let users_private_key = PrivateKey::random(&mut rng);
 
         let t1 = Tx::new_public(
@@ -301,9 +414,9 @@ mod tests {
     #[proptest]
     fn tx_private_to_bytes_from_bytes_test(
         vote_plan_id: [u8; 32], proposal_index: u8, #[strategy(1u8..5)] voting_options: u8,
-        #[strategy(0..#voting_options)] choice: u8,
+        #[strategy(0..#voting_options)] choice: u8, seed: u64,
     ) {
-        let mut rng = OsRng;
+        let mut rng = test_rng(seed);
         let users_private_key = PrivateKey::random(&mut rng);
         let election_secret_key = ElectionSecretKey::random(&mut rng);
         let election_public_key = election_secret_key.public_key();
@@ -376,4 +489,110 @@ mod tests {
         let t2 = Tx::from_bytes(&mut bytes.as_slice()).unwrap();
         assert_eq!(t1, t2);
     }
+
+    #[test]
+    fn tx_from_invalid_bytes_test() {
+        assert!(matches!(
+            Tx::from_bytes(&mut [0u8; 4].as_slice()),
+            Err(TxError::Decoding(_))
+        ));
+    }
+
+    fn public_tx() -> Tx {
+        let users_private_key = PrivateKey::random(&mut test_rng(0));
+        Tx::new_public([1u8; 32], 0, 3, 1, &users_private_key).unwrap()
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    fn tx_lenient_decoding_test() {
+        let tx = public_tx();
+        let bytes = tx.to_bytes();
+        let (decoded, report) =
+            Tx::from_bytes_with_mode(&mut bytes.as_slice(), DecodeMode::Lenient).unwrap();
+        assert_eq!(decoded, tx);
+        assert!(!report.is_problematic());
+
+        // Unknown padding and witness tags
+        let mut corrupted = bytes.clone();
+        corrupted[4] = 7;
+        let witness_tag_offset = bytes.len() - 69;
+        corrupted[witness_tag_offset] = 3;
+        assert!(Tx::from_bytes(&mut corrupted.as_slice()).is_err());
+        let (decoded, report) =
+            Tx::from_bytes_with_mode(&mut corrupted.as_slice(), DecodeMode::Lenient).unwrap();
+        assert_eq!(decoded, tx);
+        assert_eq!(report.problems(), &[
+            Problem {
+                offset: 4,
+                kind: ProblemKind::UnknownPaddingTag(7),
+            },
+            Problem {
+                offset: witness_tag_offset as u64,
+                kind: ProblemKind::UnknownWitnessTag(3),
+            },
+        ]);
+
+        // Extra bytes covered by the tx size field
+        let mut extended = bytes.clone();
+        extended.extend_from_slice(&[0xAA; 3]);
+        let size = u32::try_from(extended.len() - 4).unwrap();
+        extended[..4].copy_from_slice(&size.to_be_bytes());
+        let mut reader = extended.as_slice();
+        let (decoded, report) = Tx::from_bytes_with_mode(&mut reader, DecodeMode::Lenient).unwrap();
+        assert_eq!(decoded, tx);
+        assert!(reader.is_empty());
+        assert_eq!(report.problems(), &[Problem {
+            offset: bytes.len() as u64,
+            kind: ProblemKind::ExtraBytes(3),
+        }]);
+        // Strict mode does not consume them
+        let mut reader = extended.as_slice();
+        assert_eq!(Tx::from_bytes(&mut reader).unwrap(), tx);
+        assert_eq!(reader.len(), 3);
+
+        // Oversized tx size field, the following bytes are not consumed
+        let mut oversized = extended.clone();
+        oversized[..4].copy_from_slice(&u32::MAX.to_be_bytes());
+        let mut reader = oversized.as_slice();
+        let (decoded, report) = Tx::from_bytes_with_mode(&mut reader, DecodeMode::Lenient).unwrap();
+        assert_eq!(decoded, tx);
+        assert_eq!(reader.len(), 3);
+        assert_eq!(report.problems(), &[Problem {
+            offset: 0,
+            kind: ProblemKind::SizeMismatch {
+                expected: u32::MAX.into(),
+                actual: bytes.len() as u64 - 4,
+            },
+        }]);
+
+        // Truncated tx size field
+        let mut shrunk = bytes.clone();
+        shrunk[..4].copy_from_slice(&1u32.to_be_bytes());
+        let (_, report) =
+            Tx::from_bytes_with_mode(&mut shrunk.as_slice(), DecodeMode::Lenient).unwrap();
+        assert_eq!(report.problems(), &[Problem {
+            offset: 0,
+            kind: ProblemKind::SizeMismatch {
+                expected: 1,
+                actual: bytes.len() as u64 - 4,
+            },
+        }]);
+    }
+
+    #[proptest]
+    fn tx_corrupted_field_test(#[strategy(0usize..161)] index: usize, byte: u8) {
+        let tx = public_tx();
+        let mut bytes = tx.to_bytes();
+        if let Some(b) = bytes.get_mut(index) {
+            *b = byte;
+        }
+
+        // Neither mode panics, and the lenient mode accepts everything the strict one does.
+        let strict = Tx::from_bytes(&mut bytes.as_slice());
+        let lenient = Tx::from_bytes_with_mode(&mut bytes.as_slice(), DecodeMode::Lenient);
+        if let Ok(strict) = strict {
+            assert_eq!(lenient.unwrap().0, strict);
+        }
+    }
 }
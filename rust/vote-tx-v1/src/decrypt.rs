@@ -0,0 +1,135 @@
+//! Bulk decryption of private vote transactions.
+//!
+//! Decrypting a private vote requires solving a discrete logarithm for every voting
+//! option, so tally jobs over large funds spend most of their time here.
+//! [`decrypt_many`] spreads this work over a bounded pool of worker threads and yields
+//! the results as soon as they are ready, each one tagged with the index of the
+//! originating transaction.
+
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        mpsc::{sync_channel, Receiver},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use catalyst_voting::vote_protocol::committee::ElectionSecretKey;
+
+use crate::{Tx, TxError};
+
+/// A single decryption result.
+/// Holds an index of the transaction inside the provided input and the decrypted
+/// choice, or an error if the transaction cannot be decrypted.
+pub type DecryptionResult = (usize, Result<u8, TxError>);
+
+/// A stream of the [`DecryptionResult`] items, produced by the worker pool.
+///
+/// Items are yielded in the order of completion, not in the order of the input
+/// transactions. The stream ends when every transaction has been processed.
+/// Dropping the stream stops the worker pool after the currently processed
+/// transactions are finished.
+#[must_use]
+pub struct DecryptionStream {
+    /// Results receiver.
+    receiver: Receiver<DecryptionResult>,
+}
+
+impl Iterator for DecryptionStream {
+    type Item = DecryptionResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
+/// Decrypts private choices of the provided transactions, using a worker pool sized by
+/// the number of available CPUs.
+///
+/// Public vote transactions are reported with an error, the same way as
+/// [`Tx::private_choice`] does.
+pub fn decrypt_many(txs: Vec<Tx>, secret_key: ElectionSecretKey) -> DecryptionStream {
+    let workers = thread::available_parallelism().unwrap_or(NonZeroUsize::MIN);
+    decrypt_many_with_workers(txs, secret_key, workers)
+}
+
+/// Decrypts private choices of the provided transactions, using a worker pool of the
+/// `workers` size.
+///
+/// The amount of results buffered, but not yet consumed from the [`DecryptionStream`],
+/// is bounded by the `workers` size, so a slow consumer pauses the workers
+/// instead of accumulating results in memory.
+pub fn decrypt_many_with_workers(
+    txs: Vec<Tx>, secret_key: ElectionSecretKey, workers: NonZeroUsize,
+) -> DecryptionStream {
+    let workers = workers.get().min(txs.len().max(1));
+    let (sender, receiver) = sync_channel(workers);
+    let queue = Arc::new(Mutex::new(txs.into_iter().enumerate()));
+    let secret_key = Arc::new(secret_key);
+
+    for _ in 0..workers {
+        let sender = sender.clone();
+        let queue = queue.clone();
+        let secret_key = secret_key.clone();
+        thread::spawn(move || {
+            while let Some((i, tx)) = queue.lock().ok().and_then(|mut queue| queue.next()) {
+                let res = tx.private_choice(&secret_key);
+                // The receiver was dropped, nobody is interested in the results anymore.
+                if sender.send((i, res)).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    DecryptionStream { receiver }
+}
+
+#[cfg(test)]
+mod tests {
+    use catalyst_voting::crypto::{ed25519::PrivateKey, rng::test_rng};
+
+    use super::*;
+
+    #[test]
+    fn decrypt_many_test() {
+        let mut rng = test_rng(0);
+        let users_private_key = PrivateKey::random(&mut rng);
+        let election_secret_key = ElectionSecretKey::random(&mut rng);
+        let election_public_key = election_secret_key.public_key();
+        let voting_options = 3;
+
+        let choices = [0, 1, 2, 1, 0];
+        let mut txs: Vec<_> = choices
+            .iter()
+            .map(|choice| {
+                Tx::new_private(
+                    [0u8; 32],
+                    0,
+                    voting_options,
+                    *choice,
+                    &election_public_key,
+                    &users_private_key,
+                    &mut rng,
+                )
+                .unwrap()
+            })
+            .collect();
+        txs.push(Tx::new_public([0u8; 32], 0, voting_options, 1, &users_private_key).unwrap());
+
+        let mut results: Vec<_> =
+            decrypt_many_with_workers(txs, election_secret_key, NonZeroUsize::new(2).unwrap())
+                .collect();
+        results.sort_by_key(|(i, _)| *i);
+
+        assert_eq!(results.len(), choices.len() + 1);
+        for ((i, res), choice) in results.iter().zip(choices) {
+            assert_eq!(res.as_ref().unwrap(), &choice, "tx index {i}");
+        }
+        assert!(matches!(
+            results.last().unwrap().1,
+            Err(TxError::NotPrivateVote)
+        ));
+    }
+}
@@ -0,0 +1,39 @@
+//! Vote transaction errors.
+
+/// Vote transaction error.
+#[derive(thiserror::Error, Debug)]
+pub enum TxError {
+    /// Voting choice is out of the range of the voting options.
+    #[error("Invalid voting choice: {0}")]
+    InvalidChoice(anyhow::Error),
+    /// Voter proof cannot be generated.
+    #[error("Failed to generate voter proof: {0}")]
+    ProofGeneration(anyhow::Error),
+    /// Operation requires a public vote.
+    #[error("Not a public vote")]
+    NotPublicVote,
+    /// Operation requires a private vote.
+    #[error("Not a private vote")]
+    NotPrivateVote,
+    /// Private vote cannot be decrypted.
+    #[error("Failed to decrypt vote: {0}")]
+    Decryption(anyhow::Error),
+    /// Transaction signature is invalid.
+    #[error("Invalid signature")]
+    InvalidSignature,
+    /// Voter proof of the private vote is invalid.
+    #[error("Invalid proof")]
+    InvalidProof,
+    /// Voter proofs of the private votes are invalid.
+    #[error("Invalid proofs of the transactions at indices {0:?}")]
+    InvalidProofs(Vec<usize>),
+    /// Encrypted tally cannot be calculated.
+    #[error("Failed to tally votes: {0}")]
+    Tally(anyhow::Error),
+    /// Tally proof of the published result is invalid.
+    #[error("Invalid tally proof")]
+    InvalidTallyProof,
+    /// Transaction bytes cannot be decoded.
+    #[error("Invalid transaction bytes: {0}")]
+    Decoding(anyhow::Error),
+}
@@ -45,10 +45,17 @@
 //! assert_eq!(tx_choice, choice);
 //! ```
 
+pub mod batch;
+pub mod batch_decoder;
 mod decoding;
+pub mod decrypt;
+mod error;
+mod problem_report;
+pub mod tally;
 mod utils;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm_binding;
 
-use anyhow::ensure;
 use catalyst_voting::{
     crypto::{
         ed25519::{sign, verify_signature, PrivateKey, PublicKey, Signature},
@@ -64,6 +71,9 @@ use catalyst_voting::{
         },
     },
 };
+pub use decoding::DecodeMode;
+pub use error::TxError;
+pub use problem_report::{Problem, ProblemKind, ProblemReport};
 
 /// A v1 (Jörmungandr) vote transaction struct
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -99,7 +109,7 @@ impl Tx {
     pub fn new_public(
         vote_plan_id: [u8; 32], proposal_index: u8, voting_options: u8, choice: u8,
         users_private_key: &PrivateKey,
-    ) -> anyhow::Result<Self> {
+    ) -> Result<Self, TxError> {
         let vote = VotePayload::new_public(choice, voting_options)?;
         let signature = Self::sign(&vote_plan_id, proposal_index, &vote, users_private_key);
         Ok(Self {
@@ -115,10 +125,11 @@ impl Tx {
     ///
     /// # Errors
     ///   - Invalid voting choice
+    ///   - Voter proof generation failure
     pub fn new_private<R: CryptoRngCore>(
         vote_plan_id: [u8; 32], proposal_index: u8, voting_options: u8, choice: u8,
         election_public_key: &ElectionPublicKey, users_private_key: &PrivateKey, rng: &mut R,
-    ) -> anyhow::Result<Self> {
+    ) -> Result<Self, TxError> {
         let vote = VotePayload::new_private(
             &vote_plan_id,
             choice,
@@ -141,10 +152,11 @@ impl Tx {
     ///
     /// # Errors
     ///   - Invalid voting choice
+    ///   - Voter proof generation failure
     pub fn new_private_with_default_rng(
         vote_plan_id: [u8; 32], proposal_index: u8, voting_options: u8, choice: u8,
         election_public_key: &ElectionPublicKey, users_private_key: &PrivateKey,
-    ) -> anyhow::Result<Self> {
+    ) -> Result<Self, TxError> {
         Self::new_private(
             vote_plan_id,
             proposal_index,
@@ -172,11 +184,11 @@ impl Tx {
     ///
     /// # Errors
     ///   - Not a public vote
-    pub fn public_choice(&self) -> anyhow::Result<u8> {
+    pub fn public_choice(&self) -> Result<u8, TxError> {
         if let VotePayload::Public(choice) = &self.vote {
             Ok(*choice)
         } else {
-            Err(anyhow::anyhow!("Not a public vote"))
+            Err(TxError::NotPublicVote)
         }
     }
 
@@ -184,14 +196,15 @@ impl Tx {
     ///
     /// # Errors
     ///   - Not a private vote
+    ///   - Decryption failure
     #[allow(clippy::cast_possible_truncation)]
-    pub fn private_choice(&self, secret_key: &ElectionSecretKey) -> anyhow::Result<u8> {
+    pub fn private_choice(&self, secret_key: &ElectionSecretKey) -> Result<u8, TxError> {
         if let VotePayload::Private(vote, _) = &self.vote {
-            let vote = decrypt_vote(vote, secret_key)?;
+            let vote = decrypt_vote(vote, secret_key).map_err(TxError::Decryption)?;
             let choice = vote.choice() as u8;
             Ok(choice)
         } else {
-            Err(anyhow::anyhow!("Not a private vote"))
+            Err(TxError::NotPrivateVote)
         }
     }
 
@@ -199,18 +212,18 @@ impl Tx {
     ///
     /// # Errors
     ///   - Invalid signature
-    pub fn verify_signature(&self) -> anyhow::Result<()> {
+    pub fn verify_signature(&self) -> Result<(), TxError> {
         let bytes = Self::bytes_to_sign(
             &self.vote_plan_id,
             self.proposal_index,
             &self.vote,
             &self.public_key,
         );
-        ensure!(
-            verify_signature(&self.public_key, &bytes, &self.signature),
-            "Invalid signature."
-        );
-        Ok(())
+        if verify_signature(&self.public_key, &bytes, &self.signature) {
+            Ok(())
+        } else {
+            Err(TxError::InvalidSignature)
+        }
     }
 
     /// Verify transaction proof of the private vote.
@@ -218,19 +231,17 @@ impl Tx {
     ///
     /// # Errors
     ///   - Invalid proof
-    pub fn verify_proof(&self, election_public_key: &ElectionPublicKey) -> anyhow::Result<()> {
+    pub fn verify_proof(&self, election_public_key: &ElectionPublicKey) -> Result<(), TxError> {
         if let VotePayload::Private(encrypted_vote, proof) = &self.vote {
-            let vote_plan_id_hash = Blake2b512Hasher::new().chain_update(self.vote_plan_id);
-            let commitment = VoterProofCommitment::from_hash(vote_plan_id_hash);
-            ensure!(
-                verify_voter_proof(
-                    encrypted_vote.clone(),
-                    election_public_key,
-                    &commitment,
-                    proof,
-                ),
-                "Invalid proof."
-            );
+            let commitment = proof_commitment(&self.vote_plan_id);
+            if !verify_voter_proof(
+                encrypted_vote.clone(),
+                election_public_key,
+                &commitment,
+                proof,
+            ) {
+                return Err(TxError::InvalidProof);
+            }
         }
         Ok(())
     }
@@ -262,25 +273,31 @@ impl Tx {
     }
 }
 
+/// Voter proof commitment, derived from the vote plan id.
+fn proof_commitment(vote_plan_id: &[u8; 32]) -> VoterProofCommitment {
+    let vote_plan_id_hash = Blake2b512Hasher::new().chain_update(vote_plan_id);
+    VoterProofCommitment::from_hash(vote_plan_id_hash)
+}
+
 #[allow(clippy::missing_docs_in_private_items)]
 impl VotePayload {
-    fn new_public(choice: u8, proposal_voting_options: u8) -> anyhow::Result<Self> {
+    fn new_public(choice: u8, proposal_voting_options: u8) -> Result<Self, TxError> {
         // Try to make a `Vote` just for applying underlying validation, which must be the same
         // even for public vote
-        Vote::new(choice.into(), proposal_voting_options.into())?;
+        Vote::new(choice.into(), proposal_voting_options.into()).map_err(TxError::InvalidChoice)?;
         Ok(Self::Public(choice))
     }
 
     fn new_private<R: CryptoRngCore>(
         vote_plan_id: &[u8; 32], choice: u8, proposal_voting_options: u8,
         election_public_key: &ElectionPublicKey, rng: &mut R,
-    ) -> anyhow::Result<Self> {
-        let vote = Vote::new(choice.into(), proposal_voting_options.into())?;
+    ) -> Result<Self, TxError> {
+        let vote = Vote::new(choice.into(), proposal_voting_options.into())
+            .map_err(TxError::InvalidChoice)?;
 
         let (encrypted_vote, randomness) = encrypt_vote(&vote, election_public_key, rng);
 
-        let vote_plan_id_hash = Blake2b512Hasher::new().chain_update(vote_plan_id);
-        let commitment = VoterProofCommitment::from_hash(vote_plan_id_hash);
+        let commitment = proof_commitment(vote_plan_id);
 
         let voter_proof = generate_voter_proof(
             &vote,
@@ -289,7 +306,8 @@ impl VotePayload {
             election_public_key,
             &commitment,
             rng,
-        )?;
+        )
+        .map_err(TxError::ProofGeneration)?;
 
         Ok(Self::Private(encrypted_vote, voter_proof))
     }
@@ -298,7 +316,8 @@ impl VotePayload {
 #[cfg(test)]
 mod tests {
     use catalyst_voting::{
-        crypto::ed25519::PrivateKey, vote_protocol::committee::ElectionSecretKey,
+        crypto::{ed25519::PrivateKey, rng::test_rng},
+        vote_protocol::committee::ElectionSecretKey,
     };
     use test_strategy::proptest;
 
@@ -307,10 +326,11 @@ mod tests {
     #[proptest]
     fn tx_test(
         vote_plan_id: [u8; 32], proposal_index: u8, #[strategy(1u8..5)] voting_options: u8,
-        #[strategy(0..#voting_options)] choice: u8,
+        #[strategy(0..#voting_options)] choice: u8, seed: u64,
     ) {
-        let users_private_key = PrivateKey::random_with_default_rng();
-        let election_secret_key = ElectionSecretKey::random_with_default_rng();
+        let mut rng = test_rng(seed);
+        let users_private_key = PrivateKey::random(&mut rng);
+        let election_secret_key = ElectionSecretKey::random(&mut rng);
         let election_public_key = election_secret_key.public_key();
 
         let tx = Tx::new_public(
@@ -326,15 +346,19 @@ mod tests {
         tx.verify_signature().unwrap();
         tx.verify_proof(&election_public_key).unwrap();
         assert_eq!(tx.public_choice().unwrap(), choice);
-        assert!(tx.private_choice(&election_secret_key).is_err());
+        assert!(matches!(
+            tx.private_choice(&election_secret_key),
+            Err(TxError::NotPrivateVote)
+        ));
 
-        let tx = Tx::new_private_with_default_rng(
+        let tx = Tx::new_private(
             vote_plan_id,
             proposal_index,
             voting_options,
             choice,
             &election_public_key,
             &users_private_key,
+            &mut rng,
         )
         .unwrap();
         assert!(!tx.is_public());
@@ -342,6 +366,27 @@ mod tests {
         tx.verify_signature().unwrap();
         tx.verify_proof(&election_public_key).unwrap();
         assert_eq!(tx.private_choice(&election_secret_key).unwrap(), choice);
-        assert!(tx.public_choice().is_err());
+        assert!(matches!(tx.public_choice(), Err(TxError::NotPublicVote)));
+    }
+
+    #[test]
+    fn private_tx_deterministic_test() {
+        let new_tx = |seed| {
+            let mut rng = test_rng(seed);
+            let users_private_key = PrivateKey::random(&mut rng);
+            let election_public_key = ElectionSecretKey::random(&mut rng).public_key();
+            Tx::new_private(
+                [0u8; 32],
+                0,
+                3,
+                1,
+                &election_public_key,
+                &users_private_key,
+                &mut rng,
+            )
+            .unwrap()
+        };
+        assert_eq!(new_tx(0), new_tx(0));
+        assert_ne!(new_tx(0), new_tx(1));
     }
 }
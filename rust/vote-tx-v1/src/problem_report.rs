@@ -0,0 +1,64 @@
+//! Report of the issues recovered from while decoding a transaction in the lenient mode.
+
+/// Kind of the recovered from decoding issue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProblemKind {
+    /// Padding tag is not equal to `0`.
+    UnknownPaddingTag(u8),
+    /// Witness tag is not equal to `2`.
+    UnknownWitnessTag(u8),
+    /// Block date is not zeroed.
+    NonZeroBlockDate(u64),
+    /// Input value is not zeroed.
+    NonZeroValue(u64),
+    /// Witness nonce is not zeroed.
+    NonZeroNonce(u32),
+    /// Bytes left over after the signature, which are covered by the tx size field.
+    ExtraBytes(u64),
+    /// Tx size field does not match the size of the decoded transaction.
+    SizeMismatch {
+        /// Size from the tx size field.
+        expected: u64,
+        /// Size of the decoded transaction, including the extra bytes.
+        actual: u64,
+    },
+}
+
+/// Recovered from decoding issue of the specific field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    /// Byte offset of the field inside the transaction bytes, including the tx size
+    /// field.
+    pub offset: u64,
+    /// Kind of the issue.
+    pub kind: ProblemKind,
+}
+
+/// Report of all issues recovered from while decoding a transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProblemReport(Vec<Problem>);
+
+impl ProblemReport {
+    /// Create new empty report
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if any issue was reported.
+    #[must_use]
+    pub fn is_problematic(&self) -> bool {
+        !self.0.is_empty()
+    }
+
+    /// Reported issues
+    #[must_use]
+    pub fn problems(&self) -> &[Problem] {
+        &self.0
+    }
+
+    /// Add issue of the field at the offset to the report.
+    pub(crate) fn add(&mut self, offset: u64, kind: ProblemKind) {
+        self.0.push(Problem { offset, kind });
+    }
+}
@@ -0,0 +1,160 @@
+//! Tally of the private vote transactions and verification of the published results.
+//!
+//! The tallier publishes the decrypted totals of every voting option, each one along
+//! with a [`TallyProof`]. Anyone holding the same transactions and voting powers can
+//! recompute the homomorphic aggregate with [`encrypted_tally`] and check the
+//! published totals with [`verify_tally`], without knowing the election secret key.
+
+use catalyst_voting::{
+    crypto::rng::{default_rng, rand_core::CryptoRngCore},
+    vote_protocol::{
+        committee::{ElectionPublicKey, ElectionSecretKey},
+        tally::{
+            decrypt_tally,
+            proof::{generate_tally_proof, verify_tally_proof, TallyProof},
+            tally, DecryptionTallySetup, EncryptedTally,
+        },
+    },
+};
+
+use crate::{Tx, TxError, VotePayload};
+
+/// Calculates the encrypted tally of the voting option over the private vote
+/// transactions, each one weighted by the corresponding voting power.
+///
+/// # Errors
+///   - `TxError::NotPrivateVote` if any of the transactions is a public vote.
+///   - `TxError::Tally` if the transactions and voting powers length mismatch, or the
+///     voting option is out of the range.
+pub fn encrypted_tally(
+    txs: &[Tx], voting_powers: &[u64], voting_option: usize,
+) -> Result<EncryptedTally, TxError> {
+    let votes = txs
+        .iter()
+        .map(|tx| {
+            match &tx.vote {
+                VotePayload::Private(encrypted_vote, _) => Ok(encrypted_vote.clone()),
+                VotePayload::Public(_) => Err(TxError::NotPrivateVote),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    tally(voting_option, &votes, voting_powers).map_err(TxError::Tally)
+}
+
+/// Decrypts the encrypted tally and generates a proof of the decryption correctness,
+/// to be published along with the result.
+///
+/// # Errors
+///   - `TxError::Decryption` if the encrypted tally cannot be decrypted.
+pub fn decrypt_tally_with_proof<R: CryptoRngCore>(
+    encrypted_tally: &EncryptedTally, secret_key: &ElectionSecretKey, setup: &DecryptionTallySetup,
+    rng: &mut R,
+) -> Result<(u64, TallyProof), TxError> {
+    let result = decrypt_tally(encrypted_tally, secret_key, setup).map_err(TxError::Decryption)?;
+    let proof = generate_tally_proof(encrypted_tally, secret_key, rng);
+    Ok((result, proof))
+}
+
+/// Decrypts the encrypted tally and generates a proof of the decryption correctness
+/// with `crypto::default_rng`.
+///
+/// # Errors
+///   - `TxError::Decryption` if the encrypted tally cannot be decrypted.
+pub fn decrypt_tally_with_proof_with_default_rng(
+    encrypted_tally: &EncryptedTally, secret_key: &ElectionSecretKey, setup: &DecryptionTallySetup,
+) -> Result<(u64, TallyProof), TxError> {
+    decrypt_tally_with_proof(encrypted_tally, secret_key, setup, &mut default_rng())
+}
+
+/// Verifies that the published `result` of the voting option matches the homomorphic
+/// aggregate of the provided transactions.
+///
+/// # Errors
+///   - `TxError::NotPrivateVote` if any of the transactions is a public vote.
+///   - `TxError::Tally` if the encrypted tally cannot be calculated.
+///   - `TxError::InvalidTallyProof` if the tally proof is invalid.
+pub fn verify_tally(
+    txs: &[Tx], voting_powers: &[u64], voting_option: usize, result: u64, proof: &TallyProof,
+    election_public_key: &ElectionPublicKey,
+) -> Result<(), TxError> {
+    let encrypted_tally = encrypted_tally(txs, voting_powers, voting_option)?;
+    if verify_tally_proof(&encrypted_tally, result, election_public_key, proof) {
+        Ok(())
+    } else {
+        Err(TxError::InvalidTallyProof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use catalyst_voting::crypto::{ed25519::PrivateKey, rng::test_rng};
+
+    use super::*;
+
+    #[test]
+    fn verify_tally_test() {
+        let mut rng = test_rng(0);
+        let election_secret_key = ElectionSecretKey::random(&mut rng);
+        let election_public_key = election_secret_key.public_key();
+        let users_private_key = PrivateKey::random(&mut rng);
+        let voting_options = 3;
+
+        let txs: Vec<_> = [0, 2, 0]
+            .into_iter()
+            .map(|choice| {
+                Tx::new_private(
+                    [0u8; 32],
+                    0,
+                    voting_options,
+                    choice,
+                    &election_public_key,
+                    &users_private_key,
+                    &mut rng,
+                )
+                .unwrap()
+            })
+            .collect();
+        let voting_powers = [10, 20, 30];
+
+        let setup = DecryptionTallySetup::new(voting_powers.iter().sum()).unwrap();
+        for (voting_option, expected) in [40, 0, 20].into_iter().enumerate() {
+            let encrypted_tally = encrypted_tally(&txs, &voting_powers, voting_option).unwrap();
+            let (result, proof) =
+                decrypt_tally_with_proof(&encrypted_tally, &election_secret_key, &setup, &mut rng)
+                    .unwrap();
+            assert_eq!(result, expected);
+
+            verify_tally(
+                &txs,
+                &voting_powers,
+                voting_option,
+                result,
+                &proof,
+                &election_public_key,
+            )
+            .unwrap();
+            assert!(matches!(
+                verify_tally(
+                    &txs,
+                    &voting_powers,
+                    voting_option,
+                    result + 1,
+                    &proof,
+                    &election_public_key,
+                ),
+                Err(TxError::InvalidTallyProof)
+            ));
+        }
+
+        let public_tx =
+            Tx::new_public([0u8; 32], 0, voting_options, 0, &users_private_key).unwrap();
+        assert!(matches!(
+            encrypted_tally(&[public_tx], &[10], 0),
+            Err(TxError::NotPrivateVote)
+        ));
+        assert!(matches!(
+            encrypted_tally(&txs, &voting_powers, 3),
+            Err(TxError::Tally(_))
+        ));
+    }
+}
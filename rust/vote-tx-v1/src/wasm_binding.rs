@@ -0,0 +1,100 @@
+//! WASM binding wrapper for the vote transaction v1 crate.
+//!
+//! Transactions are passed to and from JS as their encoded bytes.
+
+use catalyst_voting::{crypto::ed25519::PrivateKey, vote_protocol::committee::ElectionPublicKey};
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+
+use crate::Tx;
+
+/// Wrapper for `Tx::new_public` function, returns the encoded transaction.
+///
+/// # Errors
+/// Returns an error if the provided vote plan id or private key have an invalid length,
+/// or the choice is out of the range of the voting options.
+#[wasm_bindgen]
+pub fn new_public_tx(
+    vote_plan_id: &[u8], proposal_index: u8, voting_options: u8, choice: u8, private_key: &[u8],
+) -> Result<Vec<u8>, JsValue> {
+    let private_key = PrivateKey::from_bytes(&to_array(private_key, "private key")?);
+    let tx = Tx::new_public(
+        to_array(vote_plan_id, "vote plan id")?,
+        proposal_index,
+        voting_options,
+        choice,
+        &private_key,
+    )
+    .map_err(|err| JsValue::from(err.to_string()))?;
+    Ok(tx.to_bytes())
+}
+
+/// Wrapper for `Tx::new_private_with_default_rng` function, returns the encoded
+/// transaction.
+///
+/// # Errors
+/// Returns an error if the provided vote plan id or keys are invalid, or the choice is
+/// out of the range of the voting options.
+#[wasm_bindgen]
+pub fn new_private_tx(
+    vote_plan_id: &[u8], proposal_index: u8, voting_options: u8, choice: u8,
+    election_public_key: &[u8], private_key: &[u8],
+) -> Result<Vec<u8>, JsValue> {
+    let private_key = PrivateKey::from_bytes(&to_array(private_key, "private key")?);
+    let tx = Tx::new_private_with_default_rng(
+        to_array(vote_plan_id, "vote plan id")?,
+        proposal_index,
+        voting_options,
+        choice,
+        &election_public_key_from_bytes(election_public_key)?,
+        &private_key,
+    )
+    .map_err(|err| JsValue::from(err.to_string()))?;
+    Ok(tx.to_bytes())
+}
+
+/// Wrapper for `Tx::verify_signature` function.
+///
+/// # Errors
+/// Returns an error if the transaction cannot be decoded or the signature is invalid.
+#[wasm_bindgen]
+pub fn verify_signature(tx: &[u8]) -> Result<JsValue, JsValue> {
+    match decode_tx(tx)?.verify_signature() {
+        Ok(()) => Ok(JsValue::from("Signature verified")),
+        Err(err) => Err(JsValue::from(err.to_string())),
+    }
+}
+
+/// Wrapper for `Tx::verify_proof` function.
+///
+/// # Errors
+/// Returns an error if the transaction or the election public key cannot be decoded,
+/// or the voter proof is invalid.
+#[wasm_bindgen]
+pub fn verify_proof(tx: &[u8], election_public_key: &[u8]) -> Result<JsValue, JsValue> {
+    let election_public_key = election_public_key_from_bytes(election_public_key)?;
+    match decode_tx(tx)?.verify_proof(&election_public_key) {
+        Ok(()) => Ok(JsValue::from("Proof verified")),
+        Err(err) => Err(JsValue::from(err.to_string())),
+    }
+}
+
+/// Decode the transaction from its bytes.
+fn decode_tx(mut bytes: &[u8]) -> Result<Tx, JsValue> {
+    Tx::from_bytes(&mut bytes).map_err(|err| JsValue::from(err.to_string()))
+}
+
+/// Decode the election public key from its bytes.
+fn election_public_key_from_bytes(bytes: &[u8]) -> Result<ElectionPublicKey, JsValue> {
+    ElectionPublicKey::from_bytes(&to_array(bytes, "election public key")?)
+        .map_err(|err| JsValue::from(err.to_string()))
+}
+
+/// Convert the bytes into a fixed size array.
+fn to_array<const N: usize>(bytes: &[u8], name: &str) -> Result<[u8; N], JsValue> {
+    bytes.try_into().map_err(|_| {
+        JsValue::from(format!(
+            "Invalid {name} length, expected {N} bytes, provided {}.",
+            bytes.len()
+        ))
+    })
+}
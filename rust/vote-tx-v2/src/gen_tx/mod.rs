@@ -3,14 +3,18 @@
 
 mod builder;
 mod event_map;
+mod payload;
 mod tx_body;
+mod verify;
 mod vote;
 
 pub use builder::GeneralizedTxBuilder;
 use coset::CborSerializable;
 pub use event_map::{EventKey, EventMap};
 use minicbor::{Decode, Decoder, Encode, Encoder};
+pub use payload::{PayloadRegistry, PayloadTx, PayloadTxBuilder, RegisteredTx, VotePayload};
 pub use tx_body::{TxBody, VoterData};
+pub use verify::{VerificationContext, VerifyError, VoteProof};
 pub use vote::{Choice, Proof, PropId, Vote};
 
 use crate::Cbor;
@@ -33,6 +37,20 @@ where
 /// `GeneralizedTx` array struct length
 const GENERALIZED_TX_LEN: u64 = 2;
 
+impl<ChoiceT, ProofT, PropIdT, VoterDataT> GeneralizedTx<ChoiceT, ProofT, PropIdT, VoterDataT>
+where
+    ChoiceT: for<'a> Cbor<'a>,
+    ProofT: for<'a> Cbor<'a>,
+    PropIdT: for<'a> Cbor<'a>,
+    VoterDataT: for<'a> Cbor<'a>,
+{
+    /// Returns the `tx-body` field.
+    #[must_use]
+    pub fn tx_body(&self) -> &TxBody<ChoiceT, ProofT, PropIdT, VoterDataT> {
+        &self.tx_body
+    }
+}
+
 impl<ChoiceT, ProofT, PropIdT, VoterDataT> Decode<'_, ()>
     for GeneralizedTx<ChoiceT, ProofT, PropIdT, VoterDataT>
 where
@@ -0,0 +1,243 @@
+//! Vote payloads of the generalized vote transaction.
+//!
+//! A [`VotePayload`] binds together the `choice`, `proof` and `prop-id` types of the
+//! specific vote type, so they are declared once instead of being repeated for every
+//! `GeneralizedTx` usage. Payloads are registered by their `vote-type` in the
+//! [`PayloadRegistry`], which decodes transactions of any registered vote type.
+
+use std::{any::Any, collections::HashMap, marker::PhantomData};
+
+use anyhow::{anyhow, ensure};
+use minicbor::{Decode, Decoder};
+
+use super::{GeneralizedTx, GeneralizedTxBuilder};
+use crate::{uuid::Uuid, Cbor};
+
+/// A vote payload, the `choice`, `proof` and `prop-id` types of the vote type.
+pub trait VotePayload: 'static {
+    /// `choice` type
+    type Choice: for<'a> Cbor<'a> + 'static;
+    /// `proof` type
+    type Proof: for<'a> Cbor<'a> + 'static;
+    /// `prop-id` type
+    type PropId: for<'a> Cbor<'a> + 'static;
+}
+
+/// A generalized tx with the vote payload types.
+pub type PayloadTx<P, VoterDataT> = GeneralizedTx<
+    <P as VotePayload>::Choice,
+    <P as VotePayload>::Proof,
+    <P as VotePayload>::PropId,
+    VoterDataT,
+>;
+
+/// A generalized tx builder with the vote payload types.
+pub type PayloadTxBuilder<P, VoterDataT> = GeneralizedTxBuilder<
+    <P as VotePayload>::Choice,
+    <P as VotePayload>::Proof,
+    <P as VotePayload>::PropId,
+    VoterDataT,
+>;
+
+/// Decoder of the generalized tx with the registered vote payload.
+type PayloadDecoder = fn(&[u8]) -> anyhow::Result<Box<dyn Any>>;
+
+/// A registry of the vote payloads by their `vote-type`.
+#[allow(clippy::module_name_repetitions)]
+pub struct PayloadRegistry<VoterDataT> {
+    /// Registered payload decoders
+    decoders: HashMap<Uuid, PayloadDecoder>,
+    /// `voter-data` type
+    _voter_data: PhantomData<VoterDataT>,
+}
+
+impl<VoterDataT> Default for PayloadRegistry<VoterDataT>
+where VoterDataT: for<'a> Cbor<'a> + 'static
+{
+    fn default() -> Self {
+        Self {
+            decoders: HashMap::new(),
+            _voter_data: PhantomData,
+        }
+    }
+}
+
+impl<VoterDataT> PayloadRegistry<VoterDataT>
+where VoterDataT: for<'a> Cbor<'a> + 'static
+{
+    /// Creates an empty `PayloadRegistry`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the vote payload for the `vote_type`.
+    ///
+    /// # Errors
+    ///   - The `vote_type` is already registered.
+    pub fn register<P: VotePayload>(&mut self, vote_type: Uuid) -> anyhow::Result<()> {
+        ensure!(
+            !self.decoders.contains_key(&vote_type),
+            "Vote type {vote_type:?} is already registered"
+        );
+        self.decoders
+            .insert(vote_type, decode_payload_tx::<P, VoterDataT>);
+        Ok(())
+    }
+
+    /// Returns `true` if the `vote_type` is registered.
+    #[must_use]
+    pub fn is_registered(&self, vote_type: &Uuid) -> bool {
+        self.decoders.contains_key(vote_type)
+    }
+
+    /// Decodes the generalized tx with the vote payload registered for its `vote-type`.
+    ///
+    /// # Errors
+    ///   - Cannot decode `vote-type`.
+    ///   - Unregistered `vote-type`.
+    ///   - Cannot decode the generalized tx with the registered payload.
+    pub fn decode(&self, bytes: &[u8]) -> anyhow::Result<RegisteredTx<VoterDataT>> {
+        let vote_type = decode_vote_type(bytes)?;
+        let decoder = self
+            .decoders
+            .get(&vote_type)
+            .ok_or(anyhow!("Unregistered vote type {vote_type:?}"))?;
+        let tx = decoder(bytes)?;
+        Ok(RegisteredTx {
+            vote_type,
+            tx,
+            _voter_data: PhantomData,
+        })
+    }
+}
+
+/// A generalized tx decoded with the registered vote payload.
+pub struct RegisteredTx<VoterDataT> {
+    /// `vote-type` field
+    vote_type: Uuid,
+    /// Decoded generalized tx
+    tx: Box<dyn Any>,
+    /// `voter-data` type
+    _voter_data: PhantomData<VoterDataT>,
+}
+
+impl<VoterDataT> RegisteredTx<VoterDataT>
+where VoterDataT: for<'a> Cbor<'a> + 'static
+{
+    /// Returns the `vote-type` field.
+    #[must_use]
+    pub fn vote_type(&self) -> &Uuid {
+        &self.vote_type
+    }
+
+    /// Returns `true` if the generalized tx was decoded with the vote payload `P`.
+    #[must_use]
+    pub fn is<P: VotePayload>(&self) -> bool {
+        self.tx.is::<PayloadTx<P, VoterDataT>>()
+    }
+
+    /// Returns the generalized tx with the vote payload `P`.
+    ///
+    /// # Errors
+    ///   - The generalized tx was decoded with another vote payload.
+    pub fn downcast<P: VotePayload>(self) -> anyhow::Result<PayloadTx<P, VoterDataT>> {
+        self.tx
+            .downcast::<PayloadTx<P, VoterDataT>>()
+            .map(|tx| *tx)
+            .map_err(|_| {
+                anyhow!(
+                    "Vote type {:?} is not registered with `{}` payload",
+                    self.vote_type,
+                    std::any::type_name::<P>()
+                )
+            })
+    }
+}
+
+/// Decodes the generalized tx with the vote payload `P`.
+fn decode_payload_tx<P, VoterDataT>(bytes: &[u8]) -> anyhow::Result<Box<dyn Any>>
+where
+    P: VotePayload,
+    VoterDataT: for<'a> Cbor<'a> + 'static,
+{
+    let tx = PayloadTx::<P, VoterDataT>::from_bytes(bytes)?;
+    Ok(Box::new(tx))
+}
+
+/// Decodes the `vote-type` field of the generalized tx, without decoding the rest of it.
+fn decode_vote_type(bytes: &[u8]) -> anyhow::Result<Uuid> {
+    let mut d = Decoder::new(bytes);
+    // `gen-vote-tx` and `tx-body` arrays
+    d.array()?;
+    d.array()?;
+    Uuid::decode(&mut d, &mut ()).map_err(|e| anyhow!("Cannot decode `vote-type`, {e}."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        encoded_cbor::EncodedCbor,
+        private_tx::PrivateBallot,
+        public_tx::{Choice, Proof, PublicBallot},
+    };
+
+    #[test]
+    fn payload_registry_test() {
+        let public_vote_type = Uuid(vec![1]);
+        let private_vote_type = Uuid(vec![2]);
+
+        let mut registry = PayloadRegistry::<Vec<u8>>::new();
+        registry
+            .register::<PublicBallot>(public_vote_type.clone())
+            .unwrap();
+        registry
+            .register::<PrivateBallot>(private_vote_type.clone())
+            .unwrap();
+        assert!(registry
+            .register::<PublicBallot>(private_vote_type.clone())
+            .is_err());
+        assert!(registry.is_registered(&public_vote_type));
+        assert!(!registry.is_registered(&Uuid(vec![3])));
+
+        let tx = PayloadTxBuilder::<PublicBallot, _>::new(
+            public_vote_type.clone(),
+            EncodedCbor(vec![1, 2, 3]),
+        )
+        .with_vote(vec![Choice(1)], Proof, Uuid(vec![4]))
+        .unwrap()
+        .build()
+        .unwrap();
+        let bytes = tx.to_bytes().unwrap();
+
+        let registered = registry.decode(&bytes).unwrap();
+        assert_eq!(registered.vote_type(), &public_vote_type);
+        assert!(registered.is::<PublicBallot>());
+        assert!(!registered.is::<PrivateBallot>());
+        assert_eq!(registered.downcast::<PublicBallot>().unwrap(), tx);
+        assert!(registry
+            .decode(&bytes)
+            .unwrap()
+            .downcast::<PrivateBallot>()
+            .is_err());
+
+        // Public ballot registered as a private one.
+        let tx =
+            PayloadTxBuilder::<PublicBallot, _>::new(private_vote_type, EncodedCbor(vec![1, 2, 3]))
+                .with_vote(vec![Choice(1)], Proof, Uuid(vec![4]))
+                .unwrap()
+                .build()
+                .unwrap();
+        assert!(registry.decode(&tx.to_bytes().unwrap()).is_err());
+
+        // Unregistered vote type.
+        let tx =
+            PayloadTxBuilder::<PublicBallot, _>::new(Uuid(vec![3]), EncodedCbor(vec![1, 2, 3]))
+                .with_vote(vec![Choice(1)], Proof, Uuid(vec![4]))
+                .unwrap()
+                .build()
+                .unwrap();
+        assert!(registry.decode(&tx.to_bytes().unwrap()).is_err());
+    }
+}
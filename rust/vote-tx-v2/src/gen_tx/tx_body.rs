@@ -30,6 +30,38 @@ where
     pub(super) voter_data: VoterData<VoterDataT>,
 }
 
+impl<ChoiceT, ProofT, PropIdT, VoterDataT> TxBody<ChoiceT, ProofT, PropIdT, VoterDataT>
+where
+    ChoiceT: for<'a> Cbor<'a>,
+    ProofT: for<'a> Cbor<'a>,
+    PropIdT: for<'a> Cbor<'a>,
+    VoterDataT: for<'a> Cbor<'a>,
+{
+    /// Returns the `vote-type` field.
+    #[must_use]
+    pub fn vote_type(&self) -> &Uuid {
+        &self.vote_type
+    }
+
+    /// Returns the `event` field.
+    #[must_use]
+    pub fn event(&self) -> &EventMap {
+        &self.event
+    }
+
+    /// Returns the `votes` field.
+    #[must_use]
+    pub fn votes(&self) -> &[Vote<ChoiceT, ProofT, PropIdT>] {
+        &self.votes
+    }
+
+    /// Returns the `voter-data` field.
+    #[must_use]
+    pub fn voter_data(&self) -> &VoterData<VoterDataT> {
+        &self.voter_data
+    }
+}
+
 impl<ChoiceT, ProofT, PropIdT, VoterDataT> Decode<'_, ()>
     for TxBody<ChoiceT, ProofT, PropIdT, VoterDataT>
 where
@@ -0,0 +1,314 @@
+//! Signature and proof verification of the generalized vote transaction.
+//!
+//! Every `COSE_Sign` signature signs the same payload, the CBOR encoded [BLAKE2b-256]
+//! hash of the `tx-body` bytes:
+//! ```cddl
+//! cose-payload = #6.32782(bytes .size 32)
+//! ```
+//! Signatures are made with `Ed25519` keys, the signer is identified by the `kid` of the
+//! signature protected header. Vote proofs are verified by the [`VoteProof`]
+//! implementation of the vote type.
+//!
+//! [BLAKE2b-256]: https://www.blake2.net/blake2.pdf
+
+use std::collections::HashMap;
+
+use catalyst_voting::{
+    crypto::ed25519::{sign, verify_signature, PrivateKey, PublicKey, Signature},
+    vote_protocol::committee::ElectionPublicKey,
+};
+use coset::iana;
+use minicbor::{data::Tag, Encoder};
+
+use super::GeneralizedTx;
+use crate::Cbor;
+
+/// `cose-payload` CBOR tag, BLAKE2b-256 hash bytes.
+const COSE_PAYLOAD_TAG: u64 = 32782;
+/// `cose-payload` hash bytes size.
+const COSE_PAYLOAD_HASH_SIZE: usize = 32;
+
+/// Generalized vote transaction verification error.
+#[derive(thiserror::Error, Debug)]
+pub enum VerifyError {
+    /// `tx-body` cannot be encoded to compute the signature payload.
+    #[error("Cannot encode `tx-body`: {0}")]
+    Encoding(anyhow::Error),
+    /// Transaction has no signatures.
+    #[error("Missing signature")]
+    MissingSignature,
+    /// `COSE_Sign` payload is not the hash of the `tx-body`.
+    #[error("Invalid signature payload")]
+    InvalidPayload,
+    /// Signature algorithm is not `EdDSA`.
+    #[error("Unsupported algorithm of the signature at index {0}")]
+    UnsupportedAlgorithm(usize),
+    /// Signer public key is not known by its `kid`.
+    #[error("Unknown signer of the signature at index {index}, kid: {kid:?}")]
+    UnknownSigner {
+        /// Index of the signature.
+        index: usize,
+        /// `kid` of the signature.
+        kid: Vec<u8>,
+    },
+    /// Signature is invalid.
+    #[error("Invalid signature at index {0}")]
+    InvalidSignature(usize),
+    /// Election public key is required to verify the vote proofs.
+    #[error("Missing election public key")]
+    MissingElectionPublicKey,
+    /// Vote proof is invalid.
+    #[error("Invalid proof of the vote at index {0}")]
+    InvalidProof(usize),
+}
+
+/// Keys to verify the generalized vote transactions with.
+#[derive(Debug, Clone, Default)]
+pub struct VerificationContext {
+    /// Signer public keys by their `kid`.
+    signers: HashMap<Vec<u8>, PublicKey>,
+    /// Election public key, to verify the private vote proofs.
+    election_public_key: Option<ElectionPublicKey>,
+}
+
+impl VerificationContext {
+    /// Creates an empty `VerificationContext`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the signer public key, identified by the `kid`.
+    #[must_use]
+    pub fn with_signer(mut self, kid: Vec<u8>, public_key: PublicKey) -> Self {
+        self.signers.insert(kid, public_key);
+        self
+    }
+
+    /// Sets the election public key.
+    #[must_use]
+    pub fn with_election_public_key(mut self, election_public_key: ElectionPublicKey) -> Self {
+        self.election_public_key = Some(election_public_key);
+        self
+    }
+
+    /// Returns the signer public key by its `kid`.
+    #[must_use]
+    pub fn signer(&self, kid: &[u8]) -> Option<&PublicKey> {
+        self.signers.get(kid)
+    }
+
+    /// Returns the election public key.
+    ///
+    /// # Errors
+    ///   - `VerifyError::MissingElectionPublicKey`
+    pub fn election_public_key(&self) -> Result<&ElectionPublicKey, VerifyError> {
+        self.election_public_key
+            .as_ref()
+            .ok_or(VerifyError::MissingElectionPublicKey)
+    }
+}
+
+/// Vote proof of the vote type, verified along with the choices and the `prop-id` of
+/// the vote.
+pub trait VoteProof<ChoiceT, PropIdT> {
+    /// Returns `true` if the proof of the choices is valid.
+    ///
+    /// # Errors
+    ///   - The `ctx` is missing keys required to verify the proof.
+    fn verify(
+        &self, choices: &[&ChoiceT], prop_id: &PropIdT, ctx: &VerificationContext,
+    ) -> Result<bool, VerifyError>;
+}
+
+impl<ChoiceT, ProofT, PropIdT, VoterDataT> GeneralizedTx<ChoiceT, ProofT, PropIdT, VoterDataT>
+where
+    ChoiceT: for<'a> Cbor<'a>,
+    ProofT: for<'a> Cbor<'a>,
+    PropIdT: for<'a> Cbor<'a>,
+    VoterDataT: for<'a> Cbor<'a>,
+{
+    /// Adds the `Ed25519` signature of the `signature` payload, with the signer `kid`.
+    ///
+    /// # Errors
+    ///   - `VerifyError::Encoding`
+    pub fn sign(&mut self, kid: Vec<u8>, private_key: &PrivateKey) -> Result<(), VerifyError> {
+        self.signature.payload = Some(self.cose_payload()?);
+
+        let protected = coset::HeaderBuilder::new()
+            .algorithm(iana::Algorithm::EdDSA)
+            .key_id(kid)
+            .build();
+        let mut signature = coset::CoseSignatureBuilder::new()
+            .protected(protected)
+            .build();
+        let data_to_sign = self.signature.tbs_data(&[], &signature);
+        signature.signature = sign(private_key, &data_to_sign).to_bytes().to_vec();
+        self.signature.signatures.push(signature);
+        Ok(())
+    }
+
+    /// Verify all transaction signatures with the signer public keys of the `ctx`.
+    ///
+    /// # Errors
+    ///   - `VerifyError::Encoding`
+    ///   - `VerifyError::MissingSignature`
+    ///   - `VerifyError::InvalidPayload`
+    ///   - `VerifyError::UnsupportedAlgorithm`
+    ///   - `VerifyError::UnknownSigner`
+    ///   - `VerifyError::InvalidSignature`
+    pub fn verify_signature(&self, ctx: &VerificationContext) -> Result<(), VerifyError> {
+        if self.signature.signatures.is_empty() {
+            return Err(VerifyError::MissingSignature);
+        }
+        if self.signature.payload.as_ref() != Some(&self.cose_payload()?) {
+            return Err(VerifyError::InvalidPayload);
+        }
+
+        for (index, signature) in self.signature.signatures.iter().enumerate() {
+            let header = &signature.protected.header;
+            if header
+                .alg
+                .as_ref()
+                .is_some_and(|alg| alg != &coset::Algorithm::Assigned(iana::Algorithm::EdDSA))
+            {
+                return Err(VerifyError::UnsupportedAlgorithm(index));
+            }
+            let public_key = ctx.signer(&header.key_id).ok_or_else(|| {
+                VerifyError::UnknownSigner {
+                    index,
+                    kid: header.key_id.clone(),
+                }
+            })?;
+            let signature_bytes = signature
+                .signature
+                .as_slice()
+                .try_into()
+                .map_err(|_| VerifyError::InvalidSignature(index))?;
+            let data_to_sign = self.signature.tbs_data(&[], signature);
+            if !verify_signature(
+                public_key,
+                &data_to_sign,
+                &Signature::from_bytes(signature_bytes),
+            ) {
+                return Err(VerifyError::InvalidSignature(index));
+            }
+        }
+        Ok(())
+    }
+
+    /// Verify the proofs of all votes with the keys of the `ctx`.
+    ///
+    /// # Errors
+    ///   - `VerifyError::MissingElectionPublicKey`
+    ///   - `VerifyError::InvalidProof`
+    pub fn verify_proof(&self, ctx: &VerificationContext) -> Result<(), VerifyError>
+    where ProofT: VoteProof<ChoiceT, PropIdT> {
+        for (index, vote) in self.tx_body.votes.iter().enumerate() {
+            let choices: Vec<_> = vote.choices.iter().map(|choice| &choice.0).collect();
+            if !vote.proof.0.verify(&choices, &vote.prop_id.0, ctx)? {
+                return Err(VerifyError::InvalidProof(index));
+            }
+        }
+        Ok(())
+    }
+
+    /// Verify the transaction signatures and the vote proofs with the keys of the
+    /// `ctx`.
+    ///
+    /// # Errors
+    ///   - Invalid signature, see [`Self::verify_signature`].
+    ///   - Invalid proof, see [`Self::verify_proof`].
+    pub fn verify(&self, ctx: &VerificationContext) -> Result<(), VerifyError>
+    where ProofT: VoteProof<ChoiceT, PropIdT> {
+        self.verify_signature(ctx)?;
+        self.verify_proof(ctx)
+    }
+
+    /// Returns the `cose-payload` bytes, the CBOR encoded hash of the `tx-body`.
+    fn cose_payload(&self) -> Result<Vec<u8>, VerifyError> {
+        let tx_body = self.tx_body.to_bytes().map_err(VerifyError::Encoding)?;
+        let hash = blake2b_simd::Params::new()
+            .hash_length(COSE_PAYLOAD_HASH_SIZE)
+            .hash(&tx_body);
+
+        let mut payload = Vec::new();
+        Encoder::new(&mut payload)
+            .tag(Tag::new(COSE_PAYLOAD_TAG))
+            .and_then(|e| e.bytes(hash.as_bytes()))
+            .map_err(|e| VerifyError::Encoding(anyhow::anyhow!("{e}")))?;
+        Ok(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use catalyst_voting::crypto::rng::test_rng;
+
+    use super::*;
+    use crate::{
+        encoded_cbor::EncodedCbor,
+        gen_tx::GeneralizedTxBuilder,
+        public_tx::{Choice, Proof, PropId},
+        uuid::Uuid,
+    };
+
+    #[test]
+    fn signature_test() {
+        let mut rng = test_rng(0);
+        let alice = PrivateKey::random(&mut rng);
+        let bob = PrivateKey::random(&mut rng);
+
+        let mut tx = GeneralizedTxBuilder::<Choice, Proof, PropId, Vec<u8>>::new(
+            Uuid(vec![1]),
+            EncodedCbor(vec![2]),
+        )
+        .with_vote(vec![Choice(1)], Proof, Uuid(vec![3]))
+        .unwrap()
+        .build()
+        .unwrap();
+        let ctx = VerificationContext::new()
+            .with_signer(b"alice".to_vec(), alice.public_key())
+            .with_signer(b"bob".to_vec(), bob.public_key());
+
+        assert!(matches!(
+            tx.verify(&ctx),
+            Err(VerifyError::MissingSignature)
+        ));
+
+        tx.sign(b"alice".to_vec(), &alice).unwrap();
+        tx.sign(b"bob".to_vec(), &bob).unwrap();
+        tx.verify(&ctx).unwrap();
+        // Signatures survive the encoding.
+        let bytes = tx.to_bytes().unwrap();
+        let decoded = GeneralizedTx::<Choice, Proof, PropId, Vec<u8>>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes().unwrap(), bytes);
+        decoded.verify(&ctx).unwrap();
+
+        // Unknown signer.
+        let ctx = VerificationContext::new().with_signer(b"alice".to_vec(), alice.public_key());
+        assert!(matches!(
+            tx.verify_signature(&ctx),
+            Err(VerifyError::UnknownSigner { index: 1, kid }) if kid == b"bob"
+        ));
+
+        // Signature of another signer key.
+        let ctx = VerificationContext::new()
+            .with_signer(b"alice".to_vec(), alice.public_key())
+            .with_signer(b"bob".to_vec(), alice.public_key());
+        assert!(matches!(
+            tx.verify_signature(&ctx),
+            Err(VerifyError::InvalidSignature(1))
+        ));
+
+        // Signed `tx-body` is modified.
+        let ctx = VerificationContext::new()
+            .with_signer(b"alice".to_vec(), alice.public_key())
+            .with_signer(b"bob".to_vec(), bob.public_key());
+        tx.tx_body.voter_data = EncodedCbor(vec![4]);
+        assert!(matches!(
+            tx.verify_signature(&ctx),
+            Err(VerifyError::InvalidPayload)
+        ));
+    }
+}
@@ -30,6 +30,31 @@ where
     pub(super) prop_id: PropId<PropIdT>,
 }
 
+impl<ChoiceT, ProofT, PropIdT> Vote<ChoiceT, ProofT, PropIdT>
+where
+    ChoiceT: for<'a> Cbor<'a>,
+    ProofT: for<'a> Cbor<'a>,
+    PropIdT: for<'a> Cbor<'a>,
+{
+    /// Returns the `choices` field.
+    #[must_use]
+    pub fn choices(&self) -> &[Choice<ChoiceT>] {
+        &self.choices
+    }
+
+    /// Returns the `proof` field.
+    #[must_use]
+    pub fn proof(&self) -> &Proof<ProofT> {
+        &self.proof
+    }
+
+    /// Returns the `prop-id` field.
+    #[must_use]
+    pub fn prop_id(&self) -> &PropId<PropIdT> {
+        &self.prop_id
+    }
+}
+
 impl<ChoiceT, ProofT, PropIdT> Decode<'_, ()> for Vote<ChoiceT, ProofT, PropIdT>
 where
     ChoiceT: for<'a> Cbor<'a>,
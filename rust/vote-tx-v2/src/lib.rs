@@ -6,8 +6,13 @@ use minicbor::{Decode, Decoder, Encode, Encoder};
 
 pub mod encoded_cbor;
 pub mod gen_tx;
+pub mod private_tx;
 pub mod public_tx;
+pub mod tally;
+pub mod tx_bundle;
 pub mod uuid;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm_binding;
 
 /// Cbor encodable and decodable type trait.
 pub trait Cbor<'a> {
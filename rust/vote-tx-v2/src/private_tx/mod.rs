@@ -0,0 +1,142 @@
+//! A Catalyst private vote transaction v2 objects, structured following this
+//! [spec](https://input-output-hk.github.io/catalyst-libs/architecture/08_concepts/catalyst_voting/v2/#private-vote)
+
+mod vote;
+
+pub use vote::{proof_commitment, Choice, Proof, PropId};
+
+use crate::gen_tx::VotePayload;
+
+/// A private ballot vote payload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrivateBallot;
+
+impl VotePayload for PrivateBallot {
+    type Choice = Choice;
+    type Proof = Proof;
+    type PropId = PropId;
+}
+
+#[cfg(test)]
+mod tests {
+    use catalyst_voting::{
+        crypto::{ed25519::PrivateKey, rng::test_rng},
+        vote_protocol::{
+            committee::ElectionSecretKey,
+            voter::{encrypt_vote, proof::generate_voter_proof, Vote},
+        },
+    };
+
+    use super::*;
+    use crate::{
+        encoded_cbor::EncodedCbor,
+        gen_tx::{PayloadTx, PayloadTxBuilder, VerificationContext, VerifyError},
+        uuid::Uuid,
+        Cbor,
+    };
+
+    #[test]
+    fn private_tx_from_bytes_to_bytes_test() {
+        let mut rng = test_rng(0);
+        let public_key = ElectionSecretKey::random(&mut rng).public_key();
+        let prop_id = Uuid(vec![3]);
+        let commitment = proof_commitment(&prop_id);
+
+        let vote = Vote::new(1, 3).unwrap();
+        let (encrypted_vote, randomness) = encrypt_vote(&vote, &public_key, &mut rng);
+        let choices = encrypted_vote
+            .ciphertexts()
+            .iter()
+            .cloned()
+            .map(Choice)
+            .collect();
+        let proof = generate_voter_proof(
+            &vote,
+            encrypted_vote,
+            randomness,
+            &public_key,
+            &commitment,
+            &mut rng,
+        )
+        .unwrap();
+
+        let tx = PayloadTxBuilder::<PrivateBallot, _>::new(Uuid(vec![1]), EncodedCbor(vec![2]))
+            .with_vote(choices, Proof(proof), prop_id)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let bytes = tx.to_bytes().unwrap();
+        let decoded = PayloadTx::<PrivateBallot, Vec<u8>>::from_bytes(&bytes).unwrap();
+        assert_eq!(tx, decoded);
+
+        // `zk-proof` must have a multiple of the proof group entries.
+        let proof = &decoded.tx_body().votes().first().unwrap().proof().0;
+        let mut bytes = proof.to_bytes().unwrap();
+        assert_eq!(Proof::from_bytes(&bytes).unwrap(), *proof);
+        // `[` followed by the proof groups array header, 3 groups of 8 entries
+        assert_eq!(bytes.get(..3), Some([0x82, 0x98, 24].as_slice()));
+        *bytes.get_mut(2).unwrap() = 23;
+        assert!(Proof::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn private_tx_verify_test() {
+        let mut rng = test_rng(0);
+        let public_key = ElectionSecretKey::random(&mut rng).public_key();
+        let signer = PrivateKey::random(&mut rng);
+        let prop_id = Uuid(vec![3]);
+
+        let vote = Vote::new(2, 3).unwrap();
+        let (encrypted_vote, randomness) = encrypt_vote(&vote, &public_key, &mut rng);
+        let choices: Vec<_> = encrypted_vote
+            .ciphertexts()
+            .iter()
+            .cloned()
+            .map(Choice)
+            .collect();
+        let proof = generate_voter_proof(
+            &vote,
+            encrypted_vote,
+            randomness,
+            &public_key,
+            &proof_commitment(&prop_id),
+            &mut rng,
+        )
+        .unwrap();
+
+        let mut tx = PayloadTxBuilder::<PrivateBallot, _>::new(Uuid(vec![1]), EncodedCbor(vec![2]))
+            .with_vote(choices.clone(), Proof(proof.clone()), prop_id)
+            .unwrap()
+            .build()
+            .unwrap();
+        tx.sign(b"voter".to_vec(), &signer).unwrap();
+
+        let ctx = VerificationContext::new().with_signer(b"voter".to_vec(), signer.public_key());
+        assert!(matches!(
+            tx.verify(&ctx),
+            Err(VerifyError::MissingElectionPublicKey)
+        ));
+        let ctx = ctx.with_election_public_key(public_key);
+        tx.verify(&ctx).unwrap();
+
+        // Another election public key.
+        let other_key = ElectionSecretKey::random(&mut rng).public_key();
+        let other_ctx = ctx.clone().with_election_public_key(other_key);
+        assert!(matches!(
+            tx.verify_proof(&other_ctx),
+            Err(VerifyError::InvalidProof(0))
+        ));
+
+        // Proof of another proposal.
+        let tx = PayloadTxBuilder::<PrivateBallot, _>::new(Uuid(vec![1]), EncodedCbor(vec![2]))
+            .with_vote(choices, Proof(proof), Uuid(vec![4]))
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(matches!(
+            tx.verify_proof(&ctx),
+            Err(VerifyError::InvalidProof(0))
+        ));
+    }
+}
@@ -0,0 +1,154 @@
+//! A private vote tx vote objects.
+
+use catalyst_voting::{
+    crypto::hash::{digest::Digest, Blake2b512Hasher},
+    vote_protocol::voter::{
+        proof::{verify_voter_proof, VoterProof, VoterProofCommitment},
+        EncryptedVote,
+    },
+};
+use minicbor::{Decode, Decoder, Encode, Encoder};
+
+pub use crate::tally::EncryptedChoice as Choice;
+use crate::{
+    gen_tx::{VerificationContext, VerifyError, VoteProof},
+    uuid::Uuid,
+};
+
+/// `zk-proof` array struct length
+const PROOF_LEN: u64 = 2;
+/// `group-element` and `scalar` bytes size
+const ELEMENT_SIZE: usize = 32;
+/// Number of `announcement` elements, group elements
+const ANNOUNCEMENT_LEN: usize = 3;
+/// Number of `ciphertext` elements, group elements
+const CIPHERTEXT_LEN: usize = 2;
+/// Number of `r-response` elements, scalars
+const RESPONSE_LEN: usize = 3;
+/// Number of elements of the single `(announcement, ~ciphertext, r-response)` group
+const PROOF_GROUP_LEN: usize = ANNOUNCEMENT_LEN + CIPHERTEXT_LEN + RESPONSE_LEN;
+
+/// A private voting proof struct, voter's zero knowledge proof of the encrypted choices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proof(pub VoterProof);
+
+/// A private voting proposal id struct.
+pub type PropId = Uuid;
+
+impl Decode<'_, ()> for Proof {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, minicbor::decode::Error> {
+        let Some(PROOF_LEN) = d.array()? else {
+            return Err(minicbor::decode::Error::message(format!(
+                "must be a defined sized array with {PROOF_LEN} entries"
+            )));
+        };
+        let Some(elements) = d.array()? else {
+            return Err(minicbor::decode::Error::message(
+                "proof groups must be a defined sized array",
+            ));
+        };
+        let elements = usize::try_from(elements).map_err(minicbor::decode::Error::message)?;
+        if elements % PROOF_GROUP_LEN != 0 {
+            return Err(minicbor::decode::Error::message(format!(
+                "proof groups must have a multiple of {PROOF_GROUP_LEN} entries, provided: {elements}"
+            )));
+        }
+        let size = elements / PROOF_GROUP_LEN;
+
+        // Regroup into the `VoterProof` bytes layout: announcements, ciphertexts,
+        // responses and the scalar.
+        let mut announcements = Vec::new();
+        let mut ciphertexts = Vec::new();
+        let mut responses = Vec::new();
+        for _ in 0..size {
+            for (buffer, len) in [
+                (&mut announcements, ANNOUNCEMENT_LEN),
+                (&mut ciphertexts, CIPHERTEXT_LEN),
+                (&mut responses, RESPONSE_LEN),
+            ] {
+                for _ in 0..len {
+                    buffer.extend_from_slice(decode_element(d)?);
+                }
+            }
+        }
+        let scalar = decode_element(d)?;
+
+        let bytes = [
+            announcements.as_slice(),
+            ciphertexts.as_slice(),
+            responses.as_slice(),
+            scalar,
+        ]
+        .concat();
+        let proof = VoterProof::from_bytes(&mut bytes.as_slice(), size)
+            .map_err(minicbor::decode::Error::message)?;
+        Ok(Self(proof))
+    }
+}
+
+impl Encode<()> for Proof {
+    fn encode<W: minicbor::encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        let size = self.0.size();
+        let bytes = self.0.to_bytes();
+        let (announcements, rest) = bytes.split_at(size * ANNOUNCEMENT_LEN * ELEMENT_SIZE);
+        let (ciphertexts, rest) = rest.split_at(size * CIPHERTEXT_LEN * ELEMENT_SIZE);
+        let (responses, scalar) = rest.split_at(size * RESPONSE_LEN * ELEMENT_SIZE);
+
+        e.array(PROOF_LEN)?;
+        e.array((size * PROOF_GROUP_LEN) as u64)?;
+        for ((announcement, ciphertext), response) in announcements
+            .chunks(ANNOUNCEMENT_LEN * ELEMENT_SIZE)
+            .zip(ciphertexts.chunks(CIPHERTEXT_LEN * ELEMENT_SIZE))
+            .zip(responses.chunks(RESPONSE_LEN * ELEMENT_SIZE))
+        {
+            for element in announcement
+                .chunks(ELEMENT_SIZE)
+                .chain(ciphertext.chunks(ELEMENT_SIZE))
+                .chain(response.chunks(ELEMENT_SIZE))
+            {
+                e.bytes(element)?;
+            }
+        }
+        e.bytes(scalar)?;
+        Ok(())
+    }
+}
+
+impl VoteProof<Choice, PropId> for Proof {
+    fn verify(
+        &self, choices: &[&Choice], prop_id: &PropId, ctx: &VerificationContext,
+    ) -> Result<bool, VerifyError> {
+        let encrypted_vote: EncryptedVote = choices
+            .iter()
+            .map(|choice| choice.0.clone())
+            .collect::<Vec<_>>()
+            .into();
+        Ok(verify_voter_proof(
+            encrypted_vote,
+            ctx.election_public_key()?,
+            &proof_commitment(prop_id),
+            &self.0,
+        ))
+    }
+}
+
+/// Returns the voter proof commitment key of the proposal, a BLAKE2b-512 hash of the
+/// `proposal` bytes.
+#[must_use]
+pub fn proof_commitment(prop_id: &PropId) -> VoterProofCommitment {
+    VoterProofCommitment::from_hash(Blake2b512Hasher::new().chain_update(&prop_id.0))
+}
+
+/// Decodes a `group-element` or a `scalar`.
+fn decode_element<'b>(d: &mut Decoder<'b>) -> Result<&'b [u8], minicbor::decode::Error> {
+    let bytes = d.bytes()?;
+    if bytes.len() != ELEMENT_SIZE {
+        return Err(minicbor::decode::Error::message(format!(
+            "group element and scalar must be {ELEMENT_SIZE} bytes, provided: {}",
+            bytes.len()
+        )));
+    }
+    Ok(bytes)
+}
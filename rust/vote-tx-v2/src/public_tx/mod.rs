@@ -8,7 +8,20 @@ use std::ops::{Deref, DerefMut};
 use minicbor::{Decode, Encode};
 pub use vote::{Choice, Proof, PropId};
 
-use crate::{gen_tx::GeneralizedTx, Cbor};
+use crate::{
+    gen_tx::{GeneralizedTx, VotePayload},
+    Cbor,
+};
+
+/// A public ballot vote payload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublicBallot;
+
+impl VotePayload for PublicBallot {
+    type Choice = Choice;
+    type Proof = Proof;
+    type PropId = PropId;
+}
 
 /// A public vote tx struct.
 #[derive(Debug, Clone, PartialEq)]
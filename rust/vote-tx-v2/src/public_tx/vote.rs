@@ -2,7 +2,10 @@
 
 use minicbor::{Decode, Encode};
 
-use crate::uuid::Uuid;
+use crate::{
+    gen_tx::{VerificationContext, VerifyError, VoteProof},
+    uuid::Uuid,
+};
 
 /// A public voting choice struct.
 #[derive(Debug, Clone, PartialEq)]
@@ -45,3 +48,12 @@ impl Encode<()> for Proof {
         Ok(())
     }
 }
+
+impl VoteProof<Choice, PropId> for Proof {
+    /// Public votes have no proofs, always valid.
+    fn verify(
+        &self, _choices: &[&Choice], _prop_id: &PropId, _ctx: &VerificationContext,
+    ) -> Result<bool, VerifyError> {
+        Ok(true)
+    }
+}
@@ -0,0 +1,570 @@
+//! Tally of the Catalyst vote transactions v2.
+//!
+//! Votes are grouped by the proposal they are cast for, and weighted by the voting power
+//! of the voter, which is not a part of the transaction and must be provided along with
+//! it.
+//!
+//! - [`PublicTally`] sums the voting power for every public choice.
+//! - [`PrivateTally`] accumulates the encrypted choices, and produces homomorphically
+//!   encrypted totals, which could be decrypted with
+//!   [`catalyst_voting::vote_protocol::tally::decrypt_tally`]. The decrypted totals are
+//!   published along with the tally proofs, which anyone could check against the
+//!   accumulated votes with [`PrivateTally::verify_totals`].
+//!
+//! Voter proofs and signatures are not checked here, transactions must be verified with
+//! [`GeneralizedTx::verify`] before they are tallied.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::{anyhow, ensure};
+use catalyst_voting::{
+    crypto::{
+        elgamal::Ciphertext,
+        rng::{default_rng, rand_core::CryptoRngCore},
+    },
+    vote_protocol::{
+        committee::{ElectionPublicKey, ElectionSecretKey},
+        tally::{
+            decrypt_tally,
+            proof::{generate_tally_proof, verify_tally_proof, TallyProof},
+            tally, DecryptionTallySetup, EncryptedTally,
+        },
+        voter::EncryptedVote,
+    },
+};
+use minicbor::{Decode, Decoder, Encode, Encoder};
+
+use crate::{
+    gen_tx::GeneralizedTx,
+    public_tx::{Choice as PublicChoice, PropId},
+    Cbor,
+};
+
+/// `EncryptedChoice` array struct length
+const ENCRYPTED_CHOICE_LEN: u64 = 2;
+/// `group-element` bytes size
+const GROUP_ELEMENT_SIZE: usize = Ciphertext::BYTES_SIZE / 2;
+
+/// A private voting choice struct, `ElGamal` ciphertext of the single voting option.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncryptedChoice(pub Ciphertext);
+
+/// Voting power of the voter.
+pub type VotingPower = u64;
+
+/// Per proposal totals of the public votes.
+#[derive(Debug, Clone, Default)]
+pub struct PublicTally {
+    /// Total voting power per choice, per proposal.
+    proposals: HashMap<PropId, BTreeMap<u64, VotingPower>>,
+}
+
+/// Per proposal accumulated private votes.
+#[derive(Debug, Clone, Default)]
+pub struct PrivateTally {
+    /// Encrypted votes and their voting powers, per proposal.
+    proposals: HashMap<PropId, (Vec<EncryptedVote>, Vec<VotingPower>)>,
+}
+
+impl PublicTally {
+    /// Creates an empty `PublicTally`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds all votes of the transaction, every choice of the vote is weighted by the
+    /// full `voting_power`.
+    ///
+    /// The tally is left unchanged if the transaction is rejected.
+    ///
+    /// # Errors
+    ///   - Duplicate choice within the vote.
+    ///   - Total voting power overflow.
+    pub fn add<ProofT, VoterDataT>(
+        &mut self, tx: &GeneralizedTx<PublicChoice, ProofT, PropId, VoterDataT>,
+        voting_power: VotingPower,
+    ) -> anyhow::Result<()>
+    where
+        ProofT: for<'a> Cbor<'a>,
+        VoterDataT: for<'a> Cbor<'a>,
+    {
+        // Updated totals, applied only once every vote of the transaction is counted.
+        let mut updates: HashMap<&PropId, BTreeMap<u64, VotingPower>> = HashMap::new();
+        for vote in tx.tx_body().votes() {
+            let prop_id = &vote.prop_id().0;
+            let totals = self.proposals.get(prop_id);
+            let updated = updates.entry(prop_id).or_default();
+            let mut choices = BTreeSet::new();
+            for choice in vote.choices() {
+                let choice = choice.0 .0;
+                ensure!(
+                    choices.insert(choice),
+                    "Duplicate choice {choice} within the vote"
+                );
+                let total = match updated.get(&choice) {
+                    Some(total) => *total,
+                    None => totals.and_then(|t| t.get(&choice)).copied().unwrap_or(0),
+                };
+                let total = total
+                    .checked_add(voting_power)
+                    .ok_or(anyhow!("Total voting power overflow for choice {choice}"))?;
+                updated.insert(choice, total);
+            }
+        }
+        for (prop_id, updated) in updates {
+            self.proposals
+                .entry(prop_id.clone())
+                .or_default()
+                .extend(updated);
+        }
+        Ok(())
+    }
+
+    /// Returns the total voting power per choice for the proposal, only the choices
+    /// which received votes are present.
+    #[must_use]
+    pub fn totals(&self, prop_id: &PropId) -> Option<&BTreeMap<u64, VotingPower>> {
+        self.proposals.get(prop_id)
+    }
+
+    /// Returns an iterator over all tallied proposals.
+    pub fn proposals(&self) -> impl Iterator<Item = &PropId> {
+        self.proposals.keys()
+    }
+}
+
+impl PrivateTally {
+    /// Creates an empty `PrivateTally`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds all votes of the transaction, weighted by the `voting_power`.
+    /// The choices of the vote are the encrypted unit vector, one ciphertext per voting
+    /// option.
+    ///
+    /// The tally is left unchanged if the transaction is rejected.
+    ///
+    /// # Errors
+    ///   - The number of voting options differs from the previous votes for the same
+    ///     proposal.
+    pub fn add<ProofT, VoterDataT>(
+        &mut self, tx: &GeneralizedTx<EncryptedChoice, ProofT, PropId, VoterDataT>,
+        voting_power: VotingPower,
+    ) -> anyhow::Result<()>
+    where
+        ProofT: for<'a> Cbor<'a>,
+        VoterDataT: for<'a> Cbor<'a>,
+    {
+        // Number of voting options per proposal, checked for every vote of the
+        // transaction before any of them is added.
+        let mut voting_options: HashMap<&PropId, usize> = HashMap::new();
+        let mut encrypted_votes = Vec::with_capacity(tx.tx_body().votes().len());
+        for vote in tx.tx_body().votes() {
+            let encrypted_vote: EncryptedVote = vote
+                .choices()
+                .iter()
+                .map(|choice| choice.0 .0.clone())
+                .collect::<Vec<_>>()
+                .into();
+
+            let prop_id = &vote.prop_id().0;
+            let expected = *voting_options.entry(prop_id).or_insert_with(|| {
+                self.proposals
+                    .get(prop_id)
+                    .and_then(|(votes, _)| votes.first())
+                    .map_or(encrypted_vote.size(), EncryptedVote::size)
+            });
+            ensure!(
+                expected == encrypted_vote.size(),
+                "Voting options mismatch, expected: {0}, provided: {1}",
+                expected,
+                encrypted_vote.size()
+            );
+            encrypted_votes.push((prop_id, encrypted_vote));
+        }
+
+        for (prop_id, encrypted_vote) in encrypted_votes {
+            let (votes, voting_powers) = self.proposals.entry(prop_id.clone()).or_default();
+            votes.push(encrypted_vote);
+            voting_powers.push(voting_power);
+        }
+        Ok(())
+    }
+
+    /// Returns the total voting power of all votes for the proposal, needed to decrypt
+    /// its totals.
+    ///
+    /// # Errors
+    ///   - Unknown proposal.
+    ///   - Total voting power overflow.
+    pub fn total_voting_power(&self, prop_id: &PropId) -> anyhow::Result<VotingPower> {
+        let (_, voting_powers) = self
+            .proposals
+            .get(prop_id)
+            .ok_or(anyhow!("Proposal has no votes"))?;
+        voting_powers
+            .iter()
+            .try_fold(0, |total: VotingPower, voting_power| {
+                total.checked_add(*voting_power)
+            })
+            .ok_or(anyhow!("Total voting power overflow"))
+    }
+
+    /// Returns the encrypted total voting power per voting option for the proposal.
+    ///
+    /// # Errors
+    ///   - Unknown proposal.
+    pub fn totals(&self, prop_id: &PropId) -> anyhow::Result<Vec<EncryptedTally>> {
+        let (votes, voting_powers) = self
+            .proposals
+            .get(prop_id)
+            .ok_or(anyhow!("Proposal has no votes"))?;
+        let voting_options = votes.first().map_or(0, EncryptedVote::size);
+        (0..voting_options)
+            .map(|voting_option| tally(voting_option, votes, voting_powers))
+            .collect()
+    }
+
+    /// Decrypts the totals of the proposal, and generates a tally proof for every one of
+    /// them, to be published along with the results.
+    ///
+    /// # Errors
+    ///   - Unknown proposal.
+    ///   - Cannot decrypt tally result.
+    pub fn decrypt_totals<R: CryptoRngCore>(
+        &self, prop_id: &PropId, secret_key: &ElectionSecretKey, setup: &DecryptionTallySetup,
+        rng: &mut R,
+    ) -> anyhow::Result<Vec<(u64, TallyProof)>> {
+        self.totals(prop_id)?
+            .iter()
+            .map(|total| {
+                let result = decrypt_tally(total, secret_key, setup)?;
+                let proof = generate_tally_proof(total, secret_key, rng);
+                Ok((result, proof))
+            })
+            .collect()
+    }
+
+    /// Decrypts the totals of the proposal, and generates a tally proof for every one of
+    /// them with `crypto::default_rng`.
+    ///
+    /// # Errors
+    ///   - Unknown proposal.
+    ///   - Cannot decrypt tally result.
+    pub fn decrypt_totals_with_default_rng(
+        &self, prop_id: &PropId, secret_key: &ElectionSecretKey, setup: &DecryptionTallySetup,
+    ) -> anyhow::Result<Vec<(u64, TallyProof)>> {
+        self.decrypt_totals(prop_id, secret_key, setup, &mut default_rng())
+    }
+
+    /// Verifies the published totals of the proposal, one per voting option, against the
+    /// homomorphic aggregate of the accumulated votes.
+    ///
+    /// # Errors
+    ///   - Unknown proposal.
+    ///   - The number of totals differs from the number of voting options.
+    ///   - Invalid tally proof for the voting option.
+    pub fn verify_totals(
+        &self, prop_id: &PropId, results: &[(u64, TallyProof)], public_key: &ElectionPublicKey,
+    ) -> anyhow::Result<()> {
+        let totals = self.totals(prop_id)?;
+        ensure!(
+            totals.len() == results.len(),
+            "Voting options mismatch, expected: {0}, provided: {1}",
+            totals.len(),
+            results.len()
+        );
+        for (voting_option, (total, (result, proof))) in totals.iter().zip(results).enumerate() {
+            ensure!(
+                verify_tally_proof(total, *result, public_key, proof),
+                "Invalid tally proof for the voting option {voting_option}"
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns an iterator over all tallied proposals.
+    pub fn proposals(&self) -> impl Iterator<Item = &PropId> {
+        self.proposals.keys()
+    }
+}
+
+impl Decode<'_, ()> for EncryptedChoice {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, minicbor::decode::Error> {
+        let Some(ENCRYPTED_CHOICE_LEN) = d.array()? else {
+            return Err(minicbor::decode::Error::message(format!(
+                "must be a defined sized array with {ENCRYPTED_CHOICE_LEN} entries"
+            )));
+        };
+        let mut bytes = [0u8; Ciphertext::BYTES_SIZE];
+        let (first, second) = bytes.split_at_mut(GROUP_ELEMENT_SIZE);
+        for element in [first, second] {
+            let element_bytes = d.bytes()?;
+            if element_bytes.len() != GROUP_ELEMENT_SIZE {
+                return Err(minicbor::decode::Error::message(format!(
+                    "group element must be {GROUP_ELEMENT_SIZE} bytes, provided: {}",
+                    element_bytes.len()
+                )));
+            }
+            element.copy_from_slice(element_bytes);
+        }
+        let ciphertext =
+            Ciphertext::from_bytes(&bytes).map_err(minicbor::decode::Error::message)?;
+        Ok(Self(ciphertext))
+    }
+}
+
+impl Encode<()> for EncryptedChoice {
+    fn encode<W: minicbor::encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        let bytes = self.0.to_bytes();
+        let (first, second) = bytes.split_at(GROUP_ELEMENT_SIZE);
+        e.array(ENCRYPTED_CHOICE_LEN)?;
+        e.bytes(first)?;
+        e.bytes(second)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use catalyst_voting::{
+        crypto::rng::test_rng,
+        vote_protocol::voter::{encrypt_vote, Vote},
+    };
+
+    use super::*;
+    use crate::{
+        encoded_cbor::EncodedCbor, gen_tx::GeneralizedTxBuilder, public_tx::Proof, uuid::Uuid,
+    };
+
+    #[test]
+    fn public_tally_test() {
+        let prop_1 = Uuid(vec![1]);
+        let prop_2 = Uuid(vec![2]);
+        let votes = [
+            (vec![(prop_1.clone(), 0), (prop_2.clone(), 1)], 10),
+            (vec![(prop_1.clone(), 1)], 20),
+            (vec![(prop_1.clone(), 0), (prop_2.clone(), 1)], 30),
+        ];
+
+        let mut public_tally = PublicTally::new();
+        for (votes, voting_power) in votes {
+            let tx = votes
+                .into_iter()
+                .fold(
+                    GeneralizedTxBuilder::<PublicChoice, Proof, PropId, _>::new(
+                        Uuid(vec![]),
+                        EncodedCbor(Vec::<u8>::new()),
+                    ),
+                    |builder, (prop_id, choice)| {
+                        builder
+                            .with_vote(vec![PublicChoice(choice)], Proof, prop_id)
+                            .unwrap()
+                    },
+                )
+                .build()
+                .unwrap();
+            public_tally.add(&tx, voting_power).unwrap();
+        }
+
+        assert_eq!(public_tally.proposals().count(), 2);
+        assert_eq!(
+            public_tally.totals(&prop_1),
+            Some(&BTreeMap::from([(0, 40), (1, 20)]))
+        );
+        assert_eq!(
+            public_tally.totals(&prop_2),
+            Some(&BTreeMap::from([(1, 40)]))
+        );
+        assert_eq!(public_tally.totals(&Uuid(vec![3])), None);
+    }
+
+    /// Public vote transaction with the `choices` for every proposal.
+    fn public_tx(
+        votes: Vec<(PropId, Vec<u64>)>,
+    ) -> GeneralizedTx<PublicChoice, Proof, PropId, Vec<u8>> {
+        votes
+            .into_iter()
+            .fold(
+                GeneralizedTxBuilder::new(Uuid(vec![]), EncodedCbor(Vec::<u8>::new())),
+                |builder, (prop_id, choices)| {
+                    builder
+                        .with_vote(
+                            choices.into_iter().map(PublicChoice).collect(),
+                            Proof,
+                            prop_id,
+                        )
+                        .unwrap()
+                },
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn public_tally_rejected_tx_test() {
+        let prop_1 = Uuid(vec![1]);
+        let prop_2 = Uuid(vec![2]);
+
+        let mut public_tally = PublicTally::new();
+        public_tally
+            .add(
+                &public_tx(vec![(prop_1.clone(), vec![0])]),
+                VotingPower::MAX,
+            )
+            .unwrap();
+        let totals = BTreeMap::from([(0, VotingPower::MAX)]);
+
+        // The last vote overflows the total.
+        let tx = public_tx(vec![
+            (prop_2.clone(), vec![0]),
+            (prop_1.clone(), vec![1, 0]),
+        ]);
+        assert!(public_tally.add(&tx, 1).is_err());
+        // The last vote has a duplicate choice.
+        let tx = public_tx(vec![
+            (prop_2.clone(), vec![0]),
+            (prop_1.clone(), vec![1, 1]),
+        ]);
+        assert!(public_tally.add(&tx, 1).is_err());
+
+        assert_eq!(public_tally.proposals().count(), 1);
+        assert_eq!(public_tally.totals(&prop_1), Some(&totals));
+        assert_eq!(public_tally.totals(&prop_2), None);
+    }
+
+    /// Private vote transaction with a vote for the `choice` out of the `voting_options`
+    /// for every proposal.
+    fn private_tx(
+        votes: &[(usize, usize, &PropId)], public_key: &ElectionPublicKey,
+        rng: &mut impl CryptoRngCore,
+    ) -> GeneralizedTx<EncryptedChoice, Vec<u8>, PropId, Vec<u8>> {
+        let tx = votes
+            .iter()
+            .fold(
+                GeneralizedTxBuilder::<EncryptedChoice, Vec<u8>, PropId, _>::new(
+                    Uuid(vec![]),
+                    EncodedCbor(Vec::<u8>::new()),
+                ),
+                |builder, (choice, voting_options, prop_id)| {
+                    let vote = Vote::new(*choice, *voting_options).unwrap();
+                    let (encrypted_vote, _) = encrypt_vote(&vote, public_key, rng);
+                    let choices = encrypted_vote
+                        .ciphertexts()
+                        .iter()
+                        .cloned()
+                        .map(EncryptedChoice)
+                        .collect();
+                    builder
+                        .with_vote(choices, Vec::new(), (*prop_id).clone())
+                        .unwrap()
+                },
+            )
+            .build()
+            .unwrap();
+
+        GeneralizedTx::from_bytes(&tx.to_bytes().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn private_tally_test() {
+        let mut rng = test_rng(0);
+        let election_secret_key = ElectionSecretKey::random(&mut rng);
+        let election_public_key = election_secret_key.public_key();
+        let voting_options = 3;
+        let prop_id = Uuid(vec![1]);
+
+        let mut private_tally = PrivateTally::new();
+        for (choice, voting_power) in [(0, 10), (2, 20), (0, 30)] {
+            let tx = private_tx(
+                &[(choice, voting_options, &prop_id)],
+                &election_public_key,
+                &mut rng,
+            );
+            private_tally.add(&tx, voting_power).unwrap();
+        }
+
+        let total_voting_power = private_tally.total_voting_power(&prop_id).unwrap();
+        assert_eq!(total_voting_power, 60);
+
+        let setup = DecryptionTallySetup::new(total_voting_power).unwrap();
+        let totals: Vec<_> = private_tally
+            .totals(&prop_id)
+            .unwrap()
+            .iter()
+            .map(|t| decrypt_tally(t, &election_secret_key, &setup).unwrap())
+            .collect();
+        assert_eq!(totals, vec![40, 0, 20]);
+
+        let results = private_tally
+            .decrypt_totals(&prop_id, &election_secret_key, &setup, &mut rng)
+            .unwrap();
+        assert_eq!(results.iter().map(|(r, _)| *r).collect::<Vec<_>>(), totals);
+        private_tally
+            .verify_totals(&prop_id, &results, &election_public_key)
+            .unwrap();
+
+        let mut forged = results.clone();
+        if let Some((result, _)) = forged.first_mut() {
+            *result = 41;
+        }
+        assert!(private_tally
+            .verify_totals(&prop_id, &forged, &election_public_key)
+            .is_err());
+        assert!(private_tally
+            .verify_totals(&prop_id, results.split_at(1).0, &election_public_key)
+            .is_err());
+    }
+
+    #[test]
+    fn private_tally_overflow_test() {
+        let mut rng = test_rng(0);
+        let election_public_key = ElectionSecretKey::random(&mut rng).public_key();
+        let prop_id = Uuid(vec![1]);
+
+        let mut private_tally = PrivateTally::new();
+        assert!(private_tally.total_voting_power(&prop_id).is_err());
+        for voting_power in [VotingPower::MAX, 1] {
+            let tx = private_tx(&[(0, 2, &prop_id)], &election_public_key, &mut rng);
+            private_tally.add(&tx, voting_power).unwrap();
+        }
+        assert!(private_tally.total_voting_power(&prop_id).is_err());
+    }
+
+    #[test]
+    fn private_tally_rejected_tx_test() {
+        let mut rng = test_rng(0);
+        let election_public_key = ElectionSecretKey::random(&mut rng).public_key();
+        let prop_1 = Uuid(vec![1]);
+        let prop_2 = Uuid(vec![2]);
+
+        let mut private_tally = PrivateTally::new();
+        let tx = private_tx(&[(0, 3, &prop_1)], &election_public_key, &mut rng);
+        private_tally.add(&tx, 10).unwrap();
+        let totals = private_tally.totals(&prop_1).unwrap();
+
+        // The last vote has a different number of voting options.
+        let tx = private_tx(
+            &[(0, 2, &prop_2), (0, 3, &prop_1), (0, 2, &prop_1)],
+            &election_public_key,
+            &mut rng,
+        );
+        assert!(private_tally.add(&tx, 20).is_err());
+        // The number of voting options differs within the transaction.
+        let tx = private_tx(
+            &[(0, 2, &prop_2), (0, 3, &prop_2)],
+            &election_public_key,
+            &mut rng,
+        );
+        assert!(private_tally.add(&tx, 20).is_err());
+
+        assert_eq!(private_tally.proposals().count(), 1);
+        assert_eq!(private_tally.total_voting_power(&prop_1).unwrap(), 10);
+        assert_eq!(private_tally.totals(&prop_1).unwrap(), totals);
+    }
+}
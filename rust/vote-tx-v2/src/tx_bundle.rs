@@ -0,0 +1,422 @@
+//! A bundle of the Catalyst vote transactions v2, committed to by the Merkle root.
+//!
+//! `H` is Blake2b-256, and every hash is domain separated by its first byte:
+//! - `leaf = H(0x00 || tx)`, where `tx` is the CBOR encoded transaction.
+//! - `node = H(0x01 || left || right)`, an unpaired node is promoted to the next level as
+//!   is, and the tree root of the empty bundle is `H()`.
+//! - `root = H(0x02 || tx-count || tree-root)`, where `tx-count` is the number of
+//!   transactions as a big-endian `u64`.
+//!
+//! The root commits to the number of transactions, which fixes the shape of the tree,
+//! so an inclusion proof authenticates the index of the transaction along with it.
+//!
+//! ```cddl
+//! tx-bundle = [
+//!     root: bytes .size 32,
+//!     txs: [* #6.24(bytes .cbor gen-vote-tx)],
+//! ]
+//! inclusion-proof = [
+//!     index: uint,
+//!     tx-count: uint,
+//!     path: [* bytes .size 32],
+//! ]
+//! ```
+
+use minicbor::{data::Tag, Decode, Decoder, Encode, Encoder};
+
+use crate::Cbor;
+
+/// `TxBundle` array struct length
+const TX_BUNDLE_LEN: u64 = 2;
+/// `InclusionProof` array struct length
+const INCLUSION_PROOF_LEN: u64 = 3;
+/// encoded-cbor CBOR tag <https://www.iana.org/assignments/cbor-tags/cbor-tags.xhtml/>.
+const ENCODED_CBOR_TAG: u64 = 24;
+/// Merkle tree hash bytes size
+pub const HASH_SIZE: usize = 32;
+/// Domain separation prefix of the leaf hash.
+const LEAF_PREFIX: u8 = 0x00;
+/// Domain separation prefix of the node hash.
+const NODE_PREFIX: u8 = 0x01;
+/// Domain separation prefix of the root hash.
+const ROOT_PREFIX: u8 = 0x02;
+
+/// Merkle tree hash, transaction hash or the root.
+pub type Hash = [u8; HASH_SIZE];
+
+/// A bundle of the encoded vote transactions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TxBundle {
+    /// CBOR encoded transactions
+    txs: Vec<Vec<u8>>,
+    /// Transaction hashes, leaves of the Merkle tree
+    leaves: Vec<Hash>,
+}
+
+/// A proof of the transaction inclusion into the bundle with the known Merkle root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InclusionProof {
+    /// Index of the transaction in the bundle.
+    index: usize,
+    /// Number of transactions in the bundle.
+    tx_count: usize,
+    /// Sibling hashes from the leaf to the root, unpaired nodes have no siblings.
+    path: Vec<Hash>,
+}
+
+impl TxBundle {
+    /// Creates an empty `TxBundle`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the vote transaction to the bundle.
+    ///
+    /// # Errors
+    ///   - Cannot encode the transaction.
+    pub fn push<T: for<'a> Cbor<'a>>(&mut self, tx: &T) -> anyhow::Result<()> {
+        self.push_bytes(tx.to_bytes()?);
+        Ok(())
+    }
+
+    /// Adds the CBOR encoded vote transaction to the bundle.
+    pub fn push_bytes(&mut self, tx: Vec<u8>) {
+        self.leaves.push(leaf_hash(&tx));
+        self.txs.push(tx);
+    }
+
+    /// Returns the CBOR encoded transactions.
+    #[must_use]
+    pub fn txs(&self) -> &[Vec<u8>] {
+        &self.txs
+    }
+
+    /// Returns the number of transactions.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.txs.len()
+    }
+
+    /// Returns `true` if the bundle has no transactions.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.txs.is_empty()
+    }
+
+    /// Returns the hash of the transaction at the `index`, the leaf of the Merkle tree.
+    #[must_use]
+    pub fn tx_hash(&self, index: usize) -> Option<&Hash> {
+        self.leaves.get(index)
+    }
+
+    /// Returns the Merkle root over the transaction hashes, bound to the number of
+    /// transactions.
+    #[must_use]
+    pub fn root(&self) -> Hash {
+        let mut level = self.leaves.clone();
+        while level.len() > 1 {
+            level = next_level(&level);
+        }
+        let tree_root = level.first().copied().unwrap_or_else(|| hash(&[]));
+        root_hash(self.leaves.len(), &tree_root)
+    }
+
+    /// Returns the inclusion proof of the transaction at the `index`, `None` if the index
+    /// is out of bounds.
+    #[must_use]
+    pub fn inclusion_proof(&self, index: usize) -> Option<InclusionProof> {
+        if index >= self.leaves.len() {
+            return None;
+        }
+        let mut path = Vec::new();
+        let mut level = self.leaves.clone();
+        let mut level_index = index;
+        while level.len() > 1 {
+            let sibling = if level_index % 2 == 0 {
+                level.get(level_index.saturating_add(1))
+            } else {
+                level.get(level_index.saturating_sub(1))
+            };
+            path.extend(sibling);
+            level = next_level(&level);
+            level_index /= 2;
+        }
+        Some(InclusionProof {
+            index,
+            tx_count: self.leaves.len(),
+            path,
+        })
+    }
+}
+
+impl InclusionProof {
+    /// Returns the index of the transaction in the bundle.
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the number of transactions in the bundle.
+    #[must_use]
+    pub fn tx_count(&self) -> usize {
+        self.tx_count
+    }
+
+    /// Verifies that the CBOR encoded transaction is included into the bundle with the
+    /// `root`, at the `index` of the bundle with `tx_count` transactions.
+    #[must_use]
+    pub fn verify(&self, tx: &[u8], root: &Hash) -> bool {
+        if self.index >= self.tx_count {
+            return false;
+        }
+        let mut path = self.path.iter();
+        let mut current = leaf_hash(tx);
+        let mut level_index = self.index;
+        let mut level_len = self.tx_count;
+        while level_len > 1 {
+            let is_unpaired = level_index % 2 == 0 && level_index.saturating_add(1) == level_len;
+            if !is_unpaired {
+                let Some(sibling) = path.next() else {
+                    return false;
+                };
+                current = if level_index % 2 == 0 {
+                    node_hash(&current, sibling)
+                } else {
+                    node_hash(sibling, &current)
+                };
+            }
+            level_index /= 2;
+            level_len = level_len.div_ceil(2);
+        }
+        path.next().is_none() && &root_hash(self.tx_count, &current) == root
+    }
+}
+
+impl Decode<'_, ()> for TxBundle {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, minicbor::decode::Error> {
+        let Some(TX_BUNDLE_LEN) = d.array()? else {
+            return Err(minicbor::decode::Error::message(format!(
+                "must be a defined sized array with {TX_BUNDLE_LEN} entries"
+            )));
+        };
+        let root = decode_hash(d)?;
+        let Some(tx_count) = d.array()? else {
+            return Err(minicbor::decode::Error::message(
+                "txs must be a defined sized array",
+            ));
+        };
+        let mut bundle = Self::new();
+        for _ in 0..tx_count {
+            let tag = d.tag()?;
+            if ENCODED_CBOR_TAG != tag.as_u64() {
+                return Err(minicbor::decode::Error::message(format!(
+                    "tag value must be: {ENCODED_CBOR_TAG}, provided: {}",
+                    tag.as_u64(),
+                )));
+            }
+            bundle.push_bytes(d.bytes()?.to_vec());
+        }
+        if bundle.root() != root {
+            return Err(minicbor::decode::Error::message(
+                "root does not match the bundled transactions",
+            ));
+        }
+        Ok(bundle)
+    }
+}
+
+impl Encode<()> for TxBundle {
+    fn encode<W: minicbor::encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.array(TX_BUNDLE_LEN)?;
+        e.bytes(&self.root())?;
+        e.array(self.txs.len() as u64)?;
+        for tx in &self.txs {
+            e.tag(Tag::new(ENCODED_CBOR_TAG))?;
+            e.bytes(tx)?;
+        }
+        Ok(())
+    }
+}
+
+impl Decode<'_, ()> for InclusionProof {
+    fn decode(d: &mut Decoder<'_>, (): &mut ()) -> Result<Self, minicbor::decode::Error> {
+        let Some(INCLUSION_PROOF_LEN) = d.array()? else {
+            return Err(minicbor::decode::Error::message(format!(
+                "must be a defined sized array with {INCLUSION_PROOF_LEN} entries"
+            )));
+        };
+        let index = usize::try_from(d.u64()?).map_err(minicbor::decode::Error::message)?;
+        let tx_count = usize::try_from(d.u64()?).map_err(minicbor::decode::Error::message)?;
+        let Some(path_len) = d.array()? else {
+            return Err(minicbor::decode::Error::message(
+                "path must be a defined sized array",
+            ));
+        };
+        let path = (0..path_len)
+            .map(|_| decode_hash(d))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            index,
+            tx_count,
+            path,
+        })
+    }
+}
+
+impl Encode<()> for InclusionProof {
+    fn encode<W: minicbor::encode::Write>(
+        &self, e: &mut Encoder<W>, (): &mut (),
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.array(INCLUSION_PROOF_LEN)?;
+        e.u64(self.index as u64)?;
+        e.u64(self.tx_count as u64)?;
+        e.array(self.path.len() as u64)?;
+        for hash in &self.path {
+            e.bytes(hash)?;
+        }
+        Ok(())
+    }
+}
+
+/// Decodes a Merkle tree hash.
+fn decode_hash(d: &mut Decoder<'_>) -> Result<Hash, minicbor::decode::Error> {
+    let bytes = d.bytes()?;
+    bytes.try_into().map_err(|_| {
+        minicbor::decode::Error::message(format!(
+            "hash must be {HASH_SIZE} bytes, provided: {}",
+            bytes.len()
+        ))
+    })
+}
+
+/// Computes the next level of the Merkle tree.
+fn next_level(level: &[Hash]) -> Vec<Hash> {
+    let mut next = Vec::with_capacity(level.len().div_ceil(2));
+    for pair in level.chunks(2) {
+        match pair {
+            [left, right] => next.push(node_hash(left, right)),
+            [unpaired] => next.push(*unpaired),
+            _ => {},
+        }
+    }
+    next
+}
+
+/// Computes the leaf hash of the CBOR encoded transaction.
+fn leaf_hash(tx: &[u8]) -> Hash {
+    hash(&[&[LEAF_PREFIX], tx])
+}
+
+/// Computes the node hash of its children.
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    hash(&[&[NODE_PREFIX], left, right])
+}
+
+/// Computes the root hash of the tree root of the bundle with `tx_count` transactions.
+fn root_hash(tx_count: usize, tree_root: &Hash) -> Hash {
+    hash(&[&[ROOT_PREFIX], &(tx_count as u64).to_be_bytes(), tree_root])
+}
+
+/// Computes the Blake2b-256 hash of the concatenated `parts`.
+fn hash(parts: &[&[u8]]) -> Hash {
+    let mut state = blake2b_simd::Params::new()
+        .hash_length(HASH_SIZE)
+        .to_state();
+    for part in parts {
+        state.update(part);
+    }
+    let mut hash = [0; HASH_SIZE];
+    hash.copy_from_slice(state.finalize().as_bytes());
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle(tx_count: u8) -> TxBundle {
+        let mut bundle = TxBundle::new();
+        for tx in 0..tx_count {
+            bundle.push_bytes(vec![tx; 3]);
+        }
+        bundle
+    }
+
+    #[test]
+    fn tx_bundle_from_bytes_to_bytes_test() {
+        for tx_count in 0..8 {
+            let bundle = bundle(tx_count);
+            let bytes = bundle.to_bytes().unwrap();
+            let decoded = TxBundle::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded, bundle);
+            assert_eq!(decoded.root(), bundle.root());
+        }
+
+        // Root does not match the transactions.
+        let mut bytes = bundle(3).to_bytes().unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        assert!(TxBundle::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn tx_bundle_root_test() {
+        assert_eq!(bundle(0).root(), root_hash(0, &hash(&[])));
+
+        let bundle = bundle(3);
+        let leaves: Vec<_> = (0..3).map(|tx| leaf_hash(&[tx; 3])).collect();
+        let [first, second, third] = leaves.as_slice() else {
+            panic!("must be 3 leaves");
+        };
+        assert_eq!(bundle.tx_hash(2), Some(third));
+        assert_eq!(
+            bundle.root(),
+            root_hash(3, &node_hash(&node_hash(first, second), third))
+        );
+    }
+
+    #[test]
+    fn inclusion_proof_test() {
+        for tx_count in 1..8 {
+            let bundle = bundle(tx_count);
+            let root = bundle.root();
+            for (index, tx) in bundle.txs().iter().enumerate() {
+                let proof = bundle.inclusion_proof(index).unwrap();
+                assert!(proof.verify(tx, &root));
+                assert!(!proof.verify(&[0xFF], &root));
+                assert!(!proof.verify(tx, &[0; HASH_SIZE]));
+
+                let bytes = proof.to_bytes().unwrap();
+                assert_eq!(InclusionProof::from_bytes(&bytes).unwrap(), proof);
+            }
+            assert!(bundle.inclusion_proof(bundle.len()).is_none());
+        }
+
+        // Proof of the other transaction.
+        let bundle = bundle(5);
+        let proof = bundle.inclusion_proof(1).unwrap();
+        assert!(!proof.verify(bundle.txs().first().unwrap(), &bundle.root()));
+    }
+
+    #[test]
+    fn inclusion_proof_index_test() {
+        // The last of 3 transactions is hashed with the same path as the second of 2
+        // transactions, only the number of transactions tells them apart.
+        let bundle = bundle(3);
+        let tx = bundle.txs().last().unwrap();
+        let proof = bundle.inclusion_proof(2).unwrap();
+        assert!(proof.verify(tx, &bundle.root()));
+
+        let forged = InclusionProof {
+            index: 1,
+            tx_count: 2,
+            path: proof.path.clone(),
+        };
+        assert!(!forged.verify(tx, &bundle.root()));
+
+        let forged = InclusionProof { index: 1, ..proof };
+        assert!(!forged.verify(tx, &bundle.root()));
+    }
+}
@@ -6,7 +6,7 @@ use minicbor::{data::Tag, Decode, Decoder, Encode};
 const UUID_TAG: u64 = 37;
 
 /// A UUID struct, CBOR tag 37.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Uuid(pub Vec<u8>);
 
 impl Decode<'_, ()> for Uuid {
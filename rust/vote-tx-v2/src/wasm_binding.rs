@@ -0,0 +1,92 @@
+//! WASM binding wrapper for the vote transaction v2 crate.
+//!
+//! Transactions are passed to and from JS as their CBOR encoded bytes.
+
+use catalyst_voting::vote_protocol::{
+    committee::ElectionPublicKey,
+    voter::{encrypt_vote_with_default_rng, proof::generate_voter_proof_with_default_rng, Vote},
+};
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+
+use crate::{
+    encoded_cbor::EncodedCbor,
+    gen_tx::PayloadTxBuilder,
+    private_tx::{self, proof_commitment, PrivateBallot},
+    public_tx::{self, PublicBallot},
+    uuid::Uuid,
+    Cbor,
+};
+
+/// Builds a public vote transaction for a single proposal, returns the encoded
+/// transaction.
+///
+/// # Errors
+/// Returns an error if no choices are provided or the transaction cannot be encoded.
+#[wasm_bindgen]
+// wasm_bindgen does not allowed ref passing unless it implement `RefFromWasmAbi`.
+#[allow(clippy::needless_pass_by_value)]
+pub fn new_public_tx(
+    vote_type: &[u8], voter_data: &[u8], prop_id: &[u8], choices: Vec<u64>,
+) -> Result<Vec<u8>, JsValue> {
+    PayloadTxBuilder::<PublicBallot, Vec<u8>>::new(
+        Uuid(vote_type.to_vec()),
+        EncodedCbor(voter_data.to_vec()),
+    )
+    .with_vote(
+        choices.into_iter().map(public_tx::Choice).collect(),
+        public_tx::Proof,
+        Uuid(prop_id.to_vec()),
+    )
+    .and_then(|builder| builder.build())
+    .and_then(|tx| tx.to_bytes())
+    .map_err(|err| JsValue::from(err.to_string()))
+}
+
+/// Builds a private vote transaction for a single proposal, the choice is encrypted
+/// with the election public key and proven with the voter proof committed to the
+/// `prop_id`. Returns the encoded transaction.
+///
+/// # Errors
+/// Returns an error if the election public key is invalid, the choice is out of the
+/// range of the voting options, or the transaction cannot be encoded.
+#[wasm_bindgen]
+pub fn new_private_tx(
+    vote_type: &[u8], voter_data: &[u8], prop_id: &[u8], voting_options: usize, choice: usize,
+    election_public_key: &[u8],
+) -> Result<Vec<u8>, JsValue> {
+    let election_public_key = election_public_key.try_into().map_err(|_| {
+        JsValue::from(format!(
+            "Invalid election public key length, expected {} bytes, provided {}.",
+            ElectionPublicKey::BYTES_SIZE,
+            election_public_key.len()
+        ))
+    })?;
+    let election_public_key = ElectionPublicKey::from_bytes(election_public_key)
+        .map_err(|err| JsValue::from(err.to_string()))?;
+    let vote = Vote::new(choice, voting_options).map_err(|err| JsValue::from(err.to_string()))?;
+    let (encrypted_vote, randomness) = encrypt_vote_with_default_rng(&vote, &election_public_key);
+    let choices = encrypted_vote
+        .ciphertexts()
+        .iter()
+        .cloned()
+        .map(private_tx::Choice)
+        .collect();
+    let prop_id = Uuid(prop_id.to_vec());
+    let proof = generate_voter_proof_with_default_rng(
+        &vote,
+        encrypted_vote,
+        randomness,
+        &election_public_key,
+        &proof_commitment(&prop_id),
+    )
+    .map_err(|err| JsValue::from(err.to_string()))?;
+
+    PayloadTxBuilder::<PrivateBallot, Vec<u8>>::new(
+        Uuid(vote_type.to_vec()),
+        EncodedCbor(voter_data.to_vec()),
+    )
+    .with_vote(choices, private_tx::Proof(proof), prop_id)
+    .and_then(|builder| builder.build())
+    .and_then(|tx| tx.to_bytes())
+    .map_err(|err| JsValue::from(err.to_string()))
+}